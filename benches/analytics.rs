@@ -0,0 +1,73 @@
+//! `DividendAnalytics::generate` over 100k records
+//!
+//! Run with `cargo bench`. Every all-time analysis works from one grouping of
+//! the records by symbol; a one-year summary skips them.
+
+use chrono::{Duration, NaiveDate};
+use criterion::{criterion_group, criterion_main, Criterion};
+use dividend_tracker::analytics::{AnalyticsScope, DividendAnalytics};
+use dividend_tracker::config::AnalysisSettings;
+use dividend_tracker::models::{Dividend, DividendTracker, DividendType};
+use rust_decimal_macros::dec;
+use std::hint::black_box;
+
+const RECORDS: usize = 100_000;
+const SYMBOLS: usize = 500;
+
+/// Quarterly payments spread evenly over `symbols` symbols
+fn generate_dividends(count: usize, symbols: usize) -> DividendTracker {
+    let mut tracker = DividendTracker::new();
+    let start = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+
+    for i in 0..count {
+        let symbol = format!("SYM{}", i % symbols);
+        let ex_date = start + Duration::days(((i / symbols) * 91) as i64);
+        tracker.add_dividend(
+            Dividend::new(
+                symbol,
+                None,
+                ex_date,
+                ex_date + Duration::days(7),
+                dec!(0.25),
+                dec!(100),
+                DividendType::Regular,
+            )
+            .unwrap(),
+        );
+    }
+
+    tracker
+}
+
+fn every_analysis() -> AnalyticsScope {
+    AnalyticsScope {
+        top_payers: true,
+        frequency: true,
+        consistency: true,
+        yields: true,
+        growth: true,
+        symbol_growth: true,
+    }
+}
+
+fn bench_generate(c: &mut Criterion) {
+    let tracker = generate_dividends(RECORDS, SYMBOLS);
+    let settings = AnalysisSettings::default();
+
+    let mut group = c.benchmark_group("generate_100k");
+    group.sample_size(10);
+    group.bench_function("every_analysis", |b| {
+        b.iter(|| DividendAnalytics::generate(black_box(&tracker), None, None, &settings, every_analysis()).unwrap())
+    });
+    // A one-year summary skips the all-time analyses
+    group.bench_function("one_year_totals", |b| {
+        b.iter(|| {
+            DividendAnalytics::generate(black_box(&tracker), Some(2010), None, &settings, AnalyticsScope::default())
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate);
+criterion_main!(benches);
@@ -1,15 +1,25 @@
 use anyhow::{anyhow, Result};
 use chrono::{Datelike, Local, NaiveDate};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
-use crate::models::{Dividend, DividendTracker, Holding};
+use crate::config::AnalysisSettings;
+use crate::filters::{DividendFilter, Quarter};
+use crate::models::{one_per_payment, Dividend, DividendTracker, Holding, IncomeKind};
+use crate::paths;
+use crate::periods::PaymentPeriods;
 
 /// Analytics summary for dividend data
 #[derive(Debug, Clone)]
 pub struct DividendAnalytics {
     pub total_dividends: Decimal,
+    /// Fees deducted from payments, e.g. ADR custody fees
+    pub total_fees: Decimal,
+    /// Income split into dividends, interest and distributions
+    pub income_by_kind: BTreeMap<IncomeKind, Decimal>,
     pub total_payments: usize,
     pub unique_symbols: usize,
     pub monthly_breakdown: HashMap<u32, MonthlyDividendSummary>,
@@ -19,6 +29,29 @@ pub struct DividendAnalytics {
     pub consistency_analysis: ConsistencyAnalysis,
     pub yield_analysis: Option<YieldAnalysis>,
     pub growth_analysis: Option<GrowthAnalysis>,
+    pub symbol_growth: Vec<SymbolGrowth>,
+}
+
+/// Which all-time analyses [`DividendAnalytics::generate`] computes
+///
+/// Totals and the monthly and quarterly breakdowns are always computed. The
+/// analyses here walk the whole history however narrow the period is, so
+/// each is left empty (or `None`) unless asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnalyticsScope {
+    pub top_payers: bool,
+    pub frequency: bool,
+    pub consistency: bool,
+    pub yields: bool,
+    pub growth: bool,
+    pub symbol_growth: bool,
+}
+
+impl AnalyticsScope {
+    /// Whether any requested analysis needs the dividends grouped by symbol
+    fn needs_symbol_groups(&self) -> bool {
+        self.top_payers || self.frequency || self.consistency || self.yields || self.symbol_growth
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -50,7 +83,7 @@ pub struct StockDividendSummary {
     pub last_payment: NaiveDate,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FrequencyAnalysis {
     pub monthly_payers: Vec<String>,
     pub quarterly_payers: Vec<String>,
@@ -59,7 +92,7 @@ pub struct FrequencyAnalysis {
     pub irregular_payers: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConsistencyAnalysis {
     pub consistent_payers: Vec<ConsistentPayer>,
     pub inconsistent_payers: Vec<String>,
@@ -74,6 +107,32 @@ pub struct ConsistentPayer {
     pub expected_frequency: String,
 }
 
+/// Breakdown of how a symbol's consistency score comes about
+#[derive(Debug, Clone)]
+pub struct ConsistencyExplanation {
+    pub symbol: String,
+    pub intervals: Vec<PaymentInterval>,
+    pub mean_interval: f64,
+    pub std_deviation: f64,
+    pub consistency_score: f64,
+    pub expected_frequency: String,
+}
+
+/// Days between two consecutive ex-dates of a symbol
+#[derive(Debug, Clone)]
+pub struct PaymentInterval {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: i64,
+    /// Difference from the mean interval in days
+    pub deviation: f64,
+    /// Deviates by more than one standard deviation, lowering the score
+    pub drags_score: bool,
+    /// Close to a whole multiple of the typical interval, which usually
+    /// means payments are missing from the records rather than skipped
+    pub likely_missing_payments: u32,
+}
+
 #[derive(Debug, Clone)]
 pub struct YieldAnalysis {
     pub average_yield: Decimal,
@@ -100,6 +159,19 @@ pub struct GrowthAnalysis {
     pub worst_year: Option<YearlyGrowth>,
 }
 
+/// Dividend per share by year for one symbol
+///
+/// Unlike [`GrowthAnalysis`], which sums the income of the whole portfolio,
+/// buying more shares does not show up here as growth.
+#[derive(Debug, Clone)]
+pub struct SymbolGrowth {
+    pub symbol: String,
+    /// Dividend per share paid in each complete year, oldest first
+    pub yearly_per_share: Vec<(i32, Decimal)>,
+    /// Compound annual growth rate (%) from the first to the last year
+    pub cagr: Option<Decimal>,
+}
+
 #[derive(Debug, Clone)]
 pub struct YearlyGrowth {
     pub year: i32,
@@ -108,37 +180,134 @@ pub struct YearlyGrowth {
     pub payment_count: usize,
 }
 
+/// All dividends grouped by symbol and sorted by ex-date
+///
+/// Built in a single pass over the dividend list so each analysis doesn't
+/// have to regroup the full dataset on its own. Alongside every record it
+/// keeps one per payment, for the per-share and frequency work that must not
+/// count a payment twice because it was credited to two accounts.
+struct SymbolGroups<'a> {
+    groups: HashMap<&'a str, Vec<&'a Dividend>>,
+    payments: HashMap<&'a str, Vec<&'a Dividend>>,
+}
+
+/// Per-symbol statistics shared by several analyses
+struct SymbolStats {
+    symbol: String,
+    total_amount: Decimal,
+    payment_count: usize,
+    first_payment: NaiveDate,
+    last_payment: NaiveDate,
+    /// Days between consecutive ex-dates
+    intervals: Vec<i64>,
+}
+
+impl<'a> SymbolGroups<'a> {
+    fn build(dividends: &'a [Dividend]) -> Self {
+        let mut groups: HashMap<&'a str, Vec<&'a Dividend>> = HashMap::new();
+
+        for dividend in dividends {
+            groups
+                .entry(dividend.symbol.as_str())
+                .or_default()
+                .push(dividend);
+        }
+
+        for symbol_dividends in groups.values_mut() {
+            symbol_dividends.sort_by_key(|d| d.ex_date);
+        }
+
+        let payments = groups
+            .iter()
+            .map(|(symbol, dividends)| (*symbol, one_per_payment(dividends.iter().copied())))
+            .collect();
+
+        SymbolGroups { groups, payments }
+    }
+
+    /// One record per payment of a symbol, sorted by ex-date
+    fn get(&self, symbol: &str) -> &[&'a Dividend] {
+        self.payments.get(symbol).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Compute per-symbol statistics
+    fn stats(&self) -> Vec<SymbolStats> {
+        let mut stats: Vec<SymbolStats> = self
+            .groups
+            .iter()
+            .map(|(symbol, dividends)| SymbolStats::compute(symbol, dividends, self.get(symbol)))
+            .collect();
+
+        stats.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        stats
+    }
+}
+
+impl SymbolStats {
+    /// Income adds up every record; counts and spacing go by `payments`, the
+    /// same records with one per payment
+    fn compute(symbol: &str, dividends: &[&Dividend], payments: &[&Dividend]) -> Self {
+        let total_amount: Decimal = dividends.iter().map(|d| d.total_amount).sum();
+        let intervals: Vec<i64> = payments
+            .windows(2)
+            .map(|window| (window[1].ex_date - window[0].ex_date).num_days())
+            .collect();
+
+        SymbolStats {
+            symbol: symbol.to_string(),
+            total_amount,
+            payment_count: payments.len(),
+            first_payment: payments[0].ex_date,
+            last_payment: payments[payments.len() - 1].ex_date,
+            intervals,
+        }
+    }
+
+    /// Average number of days between payments (needs at least two payments)
+    fn mean_interval(&self) -> Option<f64> {
+        if self.intervals.is_empty() {
+            None
+        } else {
+            Some(self.intervals.iter().sum::<i64>() as f64 / self.intervals.len() as f64)
+        }
+    }
+}
+
 impl DividendAnalytics {
     /// Generate comprehensive analytics from dividend tracker data
     pub fn generate(
         tracker: &DividendTracker,
         year_filter: Option<i32>,
         quarter_filter: Option<&str>,
+        settings: &AnalysisSettings,
+        scope: AnalyticsScope,
     ) -> Result<Self> {
         let current_year = Local::now().year();
         let target_year = year_filter.unwrap_or(current_year);
 
-        // Filter dividends based on criteria
-        let mut filtered_dividends = Vec::new();
-        for div in &tracker.dividends {
-            // Check year filter
-            if let Some(year) = year_filter {
-                if div.ex_date.year() != year {
-                    continue;
-                }
-            }
-
-            // Check quarter filter
-            if let Some(quarter) = quarter_filter {
-                if !Self::is_in_quarter(div.ex_date, quarter)? {
-                    continue;
-                }
-            }
-
-            filtered_dividends.push(div);
+        // Payments that drifted across a month boundary count in their usual month
+        let periods = PaymentPeriods::build_by(&tracker.dividends, settings.month_drift_days, settings.date_basis);
+        let mut filter = DividendFilter::new().date_basis(settings.date_basis);
+        if let Some(year) = year_filter {
+            filter = filter.year(year);
+        }
+        if let Some(quarter) = quarter_filter {
+            filter = filter.quarter(Quarter::parse(quarter)?);
         }
 
+        // Filter dividends based on criteria
+        let filtered_dividends: Vec<&Dividend> = tracker
+            .dividends
+            .iter()
+            .filter(|div| filter.matches_in_period(div, periods.period(div)))
+            .collect();
+
         let total_dividends: Decimal = filtered_dividends.iter().map(|d| d.total_amount).sum();
+        let total_fees: Decimal = filtered_dividends.iter().map(|d| d.fee_amount()).sum();
+        let mut income_by_kind: BTreeMap<IncomeKind, Decimal> = BTreeMap::new();
+        for dividend in &filtered_dividends {
+            *income_by_kind.entry(dividend.income_kind).or_default() += dividend.total_amount;
+        }
         let total_payments = filtered_dividends.len();
         let unique_symbols = filtered_dividends
             .iter()
@@ -146,75 +315,69 @@ impl DividendAnalytics {
             .collect::<std::collections::HashSet<_>>()
             .len();
 
-        let monthly_breakdown = Self::calculate_monthly_breakdown(&filtered_dividends, target_year)?;
-        let quarterly_breakdown = Self::calculate_quarterly_breakdown(&filtered_dividends, target_year)?;
-        let top_payers = Self::calculate_top_payers(&tracker.dividends)?;
-        let frequency_analysis = Self::analyze_frequency(&tracker.dividends)?;
-        let consistency_analysis = Self::analyze_consistency(&tracker.dividends)?;
-        let yield_analysis = Self::analyze_yields(tracker)?;
-        let growth_analysis = Self::analyze_growth(&tracker.dividends)?;
+        let monthly_breakdown = Self::calculate_monthly_breakdown(&filtered_dividends, &periods, target_year)?;
+        let quarterly_breakdown = Self::calculate_quarterly_breakdown(&filtered_dividends, &periods, target_year)?;
 
-        Ok(DividendAnalytics {
+        let mut analytics = DividendAnalytics {
             total_dividends,
+            total_fees,
+            income_by_kind,
             total_payments,
             unique_symbols,
             monthly_breakdown,
             quarterly_breakdown,
-            top_payers,
-            frequency_analysis,
-            consistency_analysis,
-            yield_analysis,
-            growth_analysis,
-        })
-    }
-
-    fn is_in_quarter(date: NaiveDate, quarter: &str) -> Result<bool> {
-        // Parse quarter format like "Q1-2024"
-        let parts: Vec<&str> = quarter.split('-').collect();
-        if parts.len() != 2 {
-            return Err(anyhow!("Invalid quarter format. Use Q1-2024, Q2-2024, etc."));
-        }
-
-        let quarter_num = match parts[0] {
-            "Q1" => 1,
-            "Q2" => 2,
-            "Q3" => 3,
-            "Q4" => 4,
-            _ => return Err(anyhow!("Invalid quarter. Use Q1, Q2, Q3, or Q4")),
+            top_payers: Vec::new(),
+            frequency_analysis: FrequencyAnalysis::default(),
+            consistency_analysis: ConsistencyAnalysis::default(),
+            yield_analysis: None,
+            growth_analysis: None,
+            symbol_growth: Vec::new(),
         };
 
-        let year: i32 = parts[1].parse()
-            .map_err(|_| anyhow!("Invalid year in quarter format"))?;
+        if scope.growth {
+            analytics.growth_analysis = Self::analyze_growth(&tracker.dividends, &periods)?;
+        }
+        if !scope.needs_symbol_groups() {
+            return Ok(analytics);
+        }
 
-        let month = date.month();
-        let date_year = date.year();
+        // Group all dividends by symbol once and share the per-symbol statistics
+        // between the top payer, frequency, consistency, and yield analyses
+        let groups = SymbolGroups::build(&tracker.dividends);
+        let symbol_stats = groups.stats();
 
-        if date_year != year {
-            return Ok(false);
+        if scope.top_payers {
+            analytics.top_payers = Self::calculate_top_payers(&symbol_stats)?;
+        }
+        if scope.frequency {
+            analytics.frequency_analysis = Self::analyze_frequency(&symbol_stats)?;
+        }
+        if scope.consistency {
+            analytics.consistency_analysis = Self::analyze_consistency(&symbol_stats)?;
+        }
+        if scope.yields {
+            analytics.yield_analysis = Self::analyze_yields(tracker, &groups, &periods)?;
+        }
+        if scope.symbol_growth {
+            analytics.symbol_growth = Self::analyze_symbol_growth(&groups, &periods, current_year);
         }
 
-        let in_quarter = match quarter_num {
-            1 => month >= 1 && month <= 3,
-            2 => month >= 4 && month <= 6,
-            3 => month >= 7 && month <= 9,
-            4 => month >= 10 && month <= 12,
-            _ => false,
-        };
-
-        Ok(in_quarter)
+        Ok(analytics)
     }
 
     fn calculate_monthly_breakdown(
         dividends: &[&Dividend],
+        periods: &PaymentPeriods,
         year: i32,
     ) -> Result<HashMap<u32, MonthlyDividendSummary>> {
         let mut monthly_data: HashMap<u32, Vec<&Dividend>> = HashMap::new();
 
         for dividend in dividends {
-            if dividend.ex_date.year() == year {
+            let (dividend_year, month) = periods.period(dividend);
+            if dividend_year == year {
                 monthly_data
-                    .entry(dividend.ex_date.month())
-                    .or_insert_with(Vec::new)
+                    .entry(month)
+                    .or_default()
                     .push(dividend);
             }
         }
@@ -259,6 +422,7 @@ impl DividendAnalytics {
 
     fn calculate_quarterly_breakdown(
         dividends: &[&Dividend],
+        periods: &PaymentPeriods,
         year: i32,
     ) -> Result<HashMap<String, QuarterlyDividendSummary>> {
         let mut breakdown = HashMap::new();
@@ -276,7 +440,8 @@ impl DividendAnalytics {
             let quarter_dividends: Vec<&Dividend> = dividends
                 .iter()
                 .filter(|d| {
-                    d.ex_date.year() == year && months.contains(&d.ex_date.month())
+                    let (dividend_year, month) = periods.period(d);
+                    dividend_year == year && months.contains(&month)
                 })
                 .copied()
                 .collect();
@@ -306,76 +471,48 @@ impl DividendAnalytics {
         Ok(breakdown)
     }
 
-    fn calculate_top_payers(dividends: &[Dividend]) -> Result<Vec<StockDividendSummary>> {
-        let mut stock_summaries: HashMap<String, Vec<&Dividend>> = HashMap::new();
-
-        for dividend in dividends {
-            stock_summaries
-                .entry(dividend.symbol.clone())
-                .or_insert_with(Vec::new)
-                .push(dividend);
-        }
-
-        let mut summaries: Vec<StockDividendSummary> = stock_summaries
-            .into_iter()
-            .map(|(symbol, dividends)| {
-                let total_amount: Decimal = dividends.iter().map(|d| d.total_amount).sum();
-                let payment_count = dividends.len();
-                let average_amount = if payment_count > 0 {
-                    total_amount / Decimal::from(payment_count)
+    fn calculate_top_payers(stats: &[SymbolStats]) -> Result<Vec<StockDividendSummary>> {
+        let mut summaries: Vec<StockDividendSummary> = stats
+            .iter()
+            .map(|stat| {
+                let average_amount = if stat.payment_count > 0 {
+                    stat.total_amount / Decimal::from(stat.payment_count)
                 } else {
                     dec!(0)
                 };
 
-                let dates: Vec<NaiveDate> = dividends.iter().map(|d| d.ex_date).collect();
-                let first_payment = *dates.iter().min().unwrap();
-                let last_payment = *dates.iter().max().unwrap();
-
                 StockDividendSummary {
-                    symbol,
-                    total_amount,
-                    payment_count,
+                    symbol: stat.symbol.clone(),
+                    total_amount: stat.total_amount,
+                    payment_count: stat.payment_count,
                     average_amount,
-                    first_payment,
-                    last_payment,
+                    first_payment: stat.first_payment,
+                    last_payment: stat.last_payment,
                 }
             })
             .collect();
 
-        summaries.sort_by(|a, b| b.total_amount.cmp(&a.total_amount));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.total_amount));
         Ok(summaries)
     }
 
-    fn analyze_frequency(dividends: &[Dividend]) -> Result<FrequencyAnalysis> {
-        let mut stock_payments: HashMap<String, Vec<NaiveDate>> = HashMap::new();
-
-        for dividend in dividends {
-            stock_payments
-                .entry(dividend.symbol.clone())
-                .or_insert_with(Vec::new)
-                .push(dividend.ex_date);
-        }
-
+    fn analyze_frequency(stats: &[SymbolStats]) -> Result<FrequencyAnalysis> {
         let mut monthly_payers = Vec::new();
         let mut quarterly_payers = Vec::new();
         let mut semi_annual_payers = Vec::new();
         let mut annual_payers = Vec::new();
         let mut irregular_payers = Vec::new();
 
-        for (symbol, mut dates) in stock_payments {
-            dates.sort();
-
-            if dates.len() < 2 {
-                irregular_payers.push(symbol);
-                continue;
-            }
+        for stat in stats {
+            let symbol = stat.symbol.clone();
 
-            let intervals: Vec<i64> = dates
-                .windows(2)
-                .map(|window| (window[1] - window[0]).num_days())
-                .collect();
-
-            let average_interval = intervals.iter().sum::<i64>() as f64 / intervals.len() as f64;
+            let average_interval = match stat.mean_interval() {
+                Some(mean) => mean,
+                None => {
+                    irregular_payers.push(symbol);
+                    continue;
+                }
+            };
 
             // Classify based on average interval
             match average_interval.round() as i64 {
@@ -396,72 +533,35 @@ impl DividendAnalytics {
         })
     }
 
-    fn analyze_consistency(dividends: &[Dividend]) -> Result<ConsistencyAnalysis> {
-        let mut stock_payments: HashMap<String, Vec<NaiveDate>> = HashMap::new();
-
-        for dividend in dividends {
-            stock_payments
-                .entry(dividend.symbol.clone())
-                .or_insert_with(Vec::new)
-                .push(dividend.ex_date);
-        }
-
+    fn analyze_consistency(stats: &[SymbolStats]) -> Result<ConsistencyAnalysis> {
         let mut consistent_payers = Vec::new();
         let mut inconsistent_payers = Vec::new();
         let mut total_consistency_score = 0.0;
         let mut stock_count = 0;
 
-        for (symbol, mut dates) in stock_payments {
-            dates.sort();
-
-            if dates.len() < 3 {
-                inconsistent_payers.push(symbol);
+        for stat in stats {
+            if stat.payment_count < 3 {
+                inconsistent_payers.push(stat.symbol.clone());
                 continue;
             }
 
-            let intervals: Vec<i64> = dates
-                .windows(2)
-                .map(|window| (window[1] - window[0]).num_days())
-                .collect();
-
-            // Calculate consistency score (lower variance = higher consistency)
-            let mean_interval = intervals.iter().sum::<i64>() as f64 / intervals.len() as f64;
-            let variance = intervals
-                .iter()
-                .map(|interval| {
-                    let diff = *interval as f64 - mean_interval;
-                    diff * diff
-                })
-                .sum::<f64>()
-                / intervals.len() as f64;
-
-            let std_deviation = variance.sqrt();
-            let consistency_score = if mean_interval > 0.0 {
-                100.0 * (1.0 - (std_deviation / mean_interval).min(1.0))
-            } else {
-                0.0
-            };
+            let intervals = &stat.intervals;
+            let (mean_interval, _, consistency_score) = Self::interval_statistics(intervals);
 
             total_consistency_score += consistency_score;
             stock_count += 1;
 
-            let expected_frequency = match mean_interval.round() as i64 {
-                20..=40 => "Monthly".to_string(),
-                80..=100 => "Quarterly".to_string(),
-                170..=200 => "Semi-Annual".to_string(),
-                350..=380 => "Annual".to_string(),
-                _ => "Irregular".to_string(),
-            };
+            let expected_frequency = Self::frequency_label(mean_interval).to_string();
 
             if consistency_score >= 70.0 {
                 consistent_payers.push(ConsistentPayer {
-                    symbol,
+                    symbol: stat.symbol.clone(),
                     consistency_score,
-                    payment_intervals: intervals,
+                    payment_intervals: intervals.clone(),
                     expected_frequency,
                 });
             } else {
-                inconsistent_payers.push(symbol);
+                inconsistent_payers.push(stat.symbol.clone());
             }
         }
 
@@ -478,14 +578,109 @@ impl DividendAnalytics {
         })
     }
 
-    fn analyze_yields(tracker: &DividendTracker) -> Result<Option<YieldAnalysis>> {
-        // Only analyze yields if we have holdings with cost basis
-        let holdings_with_cost: Vec<(&String, &Holding)> = tracker
-            .holdings
+    /// Mean, standard deviation and consistency score of a payment interval series
+    ///
+    /// The score is 100 for perfectly even spacing and falls with the
+    /// coefficient of variation (standard deviation over mean).
+    fn interval_statistics(intervals: &[i64]) -> (f64, f64, f64) {
+        let mean_interval = intervals.iter().sum::<i64>() as f64 / intervals.len() as f64;
+        let variance = intervals
             .iter()
-            .filter(|(_, holding)| holding.avg_cost_basis.is_some())
+            .map(|interval| {
+                let diff = *interval as f64 - mean_interval;
+                diff * diff
+            })
+            .sum::<f64>()
+            / intervals.len() as f64;
+
+        let std_deviation = variance.sqrt();
+        let consistency_score = if mean_interval > 0.0 {
+            100.0 * (1.0 - (std_deviation / mean_interval).min(1.0))
+        } else {
+            0.0
+        };
+
+        (mean_interval, std_deviation, consistency_score)
+    }
+
+    /// Payment frequency implied by a mean interval in days
+    fn frequency_label(mean_interval: f64) -> &'static str {
+        match mean_interval.round() as i64 {
+            20..=40 => "Monthly",
+            80..=100 => "Quarterly",
+            170..=200 => "Semi-Annual",
+            350..=380 => "Annual",
+            _ => "Irregular",
+        }
+    }
+
+    /// Explain the consistency score of one symbol interval by interval
+    pub fn explain_consistency(tracker: &DividendTracker, symbol: &str) -> Result<ConsistencyExplanation> {
+        let symbol = symbol.trim().to_uppercase();
+        let groups = SymbolGroups::build(&tracker.dividends);
+        let dividends = groups.get(&symbol);
+
+        if dividends.len() < 3 {
+            return Err(anyhow!(
+                "{} has {} payment(s); at least 3 are needed to score consistency",
+                symbol,
+                dividends.len()
+            ));
+        }
+
+        let stats = SymbolStats::compute(&symbol, &groups.groups[symbol.as_str()], dividends);
+        let (mean_interval, std_deviation, consistency_score) = Self::interval_statistics(&stats.intervals);
+
+        // The median is the typical spacing even when a few gaps are huge
+        let mut sorted = stats.intervals.clone();
+        sorted.sort();
+        let typical = sorted[sorted.len() / 2] as f64;
+
+        let intervals = dividends
+            .windows(2)
+            .zip(&stats.intervals)
+            .map(|(pair, &days)| {
+                let deviation = days as f64 - mean_interval;
+                let multiple = if typical > 0.0 { days as f64 / typical } else { 0.0 };
+                let likely_missing_payments = if multiple >= 1.8 && (multiple - multiple.round()).abs() <= 0.15 {
+                    multiple.round() as u32 - 1
+                } else {
+                    0
+                };
+
+                PaymentInterval {
+                    from: pair[0].ex_date,
+                    to: pair[1].ex_date,
+                    days,
+                    deviation,
+                    drags_score: std_deviation > 0.0 && deviation.abs() > std_deviation,
+                    likely_missing_payments,
+                }
+            })
             .collect();
 
+        Ok(ConsistencyExplanation {
+            symbol,
+            intervals,
+            mean_interval,
+            std_deviation,
+            consistency_score,
+            expected_frequency: Self::frequency_label(mean_interval).to_string(),
+        })
+    }
+
+    fn analyze_yields(
+        tracker: &DividendTracker,
+        groups: &SymbolGroups,
+        periods: &PaymentPeriods,
+    ) -> Result<Option<YieldAnalysis>> {
+        // Only analyze yields if we have holdings with cost basis; a symbol
+        // held in several accounts counts once, at its combined average cost
+        let mut holdings_with_cost: BTreeMap<&str, Vec<&Holding>> = BTreeMap::new();
+        for holding in tracker.holdings.values().filter(|h| h.avg_cost_basis.is_some()) {
+            holdings_with_cost.entry(holding.symbol.as_str()).or_default().push(holding);
+        }
+
         if holdings_with_cost.is_empty() {
             return Ok(None);
         }
@@ -493,15 +688,17 @@ impl DividendAnalytics {
         let mut stock_yields = Vec::new();
         let current_year = Local::now().year();
 
-        for (symbol, holding) in holdings_with_cost {
-            if let Some(cost_basis) = holding.avg_cost_basis {
+        for (symbol, holdings) in holdings_with_cost {
+            let shares: Decimal = holdings.iter().map(|h| h.shares).sum();
+            let cost: Decimal = holdings.iter().filter_map(|h| h.avg_cost_basis.map(|c| c * h.shares)).sum();
+            // A closed position has no shares to weight its cost by
+            let average_cost = if shares.is_zero() { holdings[0].avg_cost_basis } else { Some(cost / shares) };
+            if let Some(cost_basis) = average_cost {
                 // Calculate annual dividend for this stock
-                let annual_dividend: Decimal = tracker
-                    .dividends
+                let annual_dividend: Decimal = groups
+                    .get(symbol)
                     .iter()
-                    .filter(|d| {
-                        d.symbol == *symbol && d.ex_date.year() == current_year
-                    })
+                    .filter(|d| periods.year(d) == current_year)
                     .map(|d| d.amount_per_share)
                     .sum();
 
@@ -509,10 +706,10 @@ impl DividendAnalytics {
                     let yield_percent = (annual_dividend / cost_basis) * dec!(100);
 
                     stock_yields.push(StockYield {
-                        symbol: symbol.clone(),
+                        symbol: symbol.to_string(),
                         annual_dividend,
                         cost_basis,
-                        shares: holding.shares,
+                        shares,
                         yield_percent,
                     });
                 }
@@ -523,7 +720,7 @@ impl DividendAnalytics {
             return Ok(None);
         }
 
-        stock_yields.sort_by(|a, b| b.yield_percent.cmp(&a.yield_percent));
+        stock_yields.sort_by_key(|s| std::cmp::Reverse(s.yield_percent));
 
         let average_yield = stock_yields.iter().map(|y| y.yield_percent).sum::<Decimal>()
             / Decimal::from(stock_yields.len());
@@ -539,11 +736,11 @@ impl DividendAnalytics {
         }))
     }
 
-    fn analyze_growth(dividends: &[Dividend]) -> Result<Option<GrowthAnalysis>> {
+    fn analyze_growth(dividends: &[Dividend], periods: &PaymentPeriods) -> Result<Option<GrowthAnalysis>> {
         let mut yearly_totals: HashMap<i32, (Decimal, usize)> = HashMap::new();
 
         for dividend in dividends {
-            let year = dividend.ex_date.year();
+            let year = periods.year(dividend);
             let entry = yearly_totals.entry(year).or_insert((dec!(0), 0));
             entry.0 += dividend.total_amount;
             entry.1 += 1;
@@ -618,12 +815,66 @@ impl DividendAnalytics {
         }))
     }
 
+    /// Per-share dividend growth for each symbol over its complete years
+    ///
+    /// The current year is left out so a partly paid year doesn't read as a cut.
+    fn analyze_symbol_growth(
+        groups: &SymbolGroups,
+        periods: &PaymentPeriods,
+        current_year: i32,
+    ) -> Vec<SymbolGrowth> {
+        let mut symbols: Vec<&&str> = groups.groups.keys().collect();
+        symbols.sort();
+
+        symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let mut by_year: HashMap<i32, Decimal> = HashMap::new();
+                for dividend in groups.get(symbol) {
+                    let year = periods.year(dividend);
+                    if year < current_year {
+                        *by_year.entry(year).or_insert(dec!(0)) += dividend.amount_per_share;
+                    }
+                }
+
+                if by_year.is_empty() {
+                    return None;
+                }
+
+                let mut yearly_per_share: Vec<(i32, Decimal)> = by_year.into_iter().collect();
+                yearly_per_share.sort_by_key(|(year, _)| *year);
+
+                let cagr = Self::compound_annual_growth(&yearly_per_share);
+
+                Some(SymbolGrowth {
+                    symbol: symbol.to_string(),
+                    yearly_per_share,
+                    cagr,
+                })
+            })
+            .collect()
+    }
+
+    /// CAGR in percent between the first and last entries of a yearly series
+    pub(crate) fn compound_annual_growth(series: &[(i32, Decimal)]) -> Option<Decimal> {
+        let (first_year, first) = series.first()?;
+        let (last_year, last) = series.last()?;
+        let years = last_year - first_year;
+
+        if years <= 0 || *first <= dec!(0) {
+            return None;
+        }
+
+        let ratio = (*last / *first).to_f64()?;
+        let rate = ratio.powf(1.0 / years as f64) - 1.0;
+        Decimal::from_f64(rate * 100.0).map(|rate| rate.round_dp(2))
+    }
+
     /// Export analytics data to CSV
-    pub fn export_to_csv(&self, file_path: &str) -> Result<()> {
-        use std::fs::File;
+    pub fn export_to_csv(&self, file_path: &Path) -> Result<()> {
         use std::io::Write;
 
-        let mut file = File::create(file_path)?;
+        let mut file = paths::create_file(file_path)?;
 
         // Write headers
         writeln!(
@@ -637,6 +888,18 @@ impl DividendAnalytics {
             "Total Dividends,${:.2},",
             self.total_dividends
         )?;
+        if !self.total_fees.is_zero() {
+            writeln!(
+                file,
+                "Total Fees,${:.2},",
+                self.total_fees
+            )?;
+            writeln!(
+                file,
+                "Net Dividends,${:.2},",
+                self.total_dividends - self.total_fees
+            )?;
+        }
         writeln!(
             file,
             "Total Payments,{},",
@@ -677,4 +940,199 @@ impl DividendAnalytics {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DividendType;
+
+    fn every_analysis() -> AnalyticsScope {
+        AnalyticsScope {
+            top_payers: true,
+            frequency: true,
+            consistency: true,
+            yields: true,
+            growth: true,
+            symbol_growth: true,
+        }
+    }
+
+    fn generate_dividends(count: usize, symbols: usize) -> DividendTracker {
+        let mut tracker = DividendTracker::new();
+        let start = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+
+        for i in 0..count {
+            let symbol = format!("SYM{}", i % symbols);
+            let ex_date = start + chrono::Duration::days(((i / symbols) * 91) as i64);
+            tracker.add_dividend(
+                Dividend::new(
+                    symbol,
+                    None,
+                    ex_date,
+                    ex_date + chrono::Duration::days(7),
+                    dec!(0.25),
+                    dec!(100),
+                    DividendType::Regular,
+                )
+                .unwrap(),
+            );
+        }
+
+        tracker
+    }
+
+    #[test]
+    fn test_symbol_stats_single_pass() {
+        let tracker = generate_dividends(40, 4);
+        let groups = SymbolGroups::build(&tracker.dividends);
+        let stats = groups.stats();
+
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[0].symbol, "SYM0");
+        assert_eq!(stats[0].payment_count, 10);
+        assert_eq!(stats[0].total_amount, dec!(250));
+        assert_eq!(stats[0].intervals, vec![91; 9]);
+        assert_eq!(groups.get("SYM1").len(), 10);
+        assert!(groups.get("MISSING").is_empty());
+    }
+
+    #[test]
+    fn test_payment_credited_to_two_accounts_counts_once() {
+        let single = generate_dividends(8, 1);
+        let mut tracker = single.clone();
+        for dividend in single.dividends.iter().cloned() {
+            let mut copy = dividend;
+            copy.account = Some("IRA".to_string());
+            tracker.add_dividend(copy);
+        }
+
+        let settings = AnalysisSettings::default();
+        let before = DividendAnalytics::generate(&single, None, None, &settings, every_analysis()).unwrap();
+        let after = DividendAnalytics::generate(&tracker, None, None, &settings, every_analysis()).unwrap();
+
+        assert_eq!(after.frequency_analysis.quarterly_payers, vec!["SYM0"]);
+        assert_eq!(
+            after.consistency_analysis.average_consistency_score,
+            before.consistency_analysis.average_consistency_score
+        );
+        assert_eq!(after.consistency_analysis.consistent_payers.len(), 1);
+        assert_eq!(after.symbol_growth[0].yearly_per_share, before.symbol_growth[0].yearly_per_share);
+        // Income still counts what each account was paid
+        assert_eq!(after.top_payers[0].total_amount, before.top_payers[0].total_amount * dec!(2));
+        assert_eq!(after.top_payers[0].payment_count, 8);
+    }
+
+    #[test]
+    fn test_generate_classifies_quarterly_payers() {
+        let tracker = generate_dividends(40, 4);
+        let analytics = DividendAnalytics::generate(&tracker, None, None, &AnalysisSettings::default(), every_analysis()).unwrap();
+
+        assert_eq!(analytics.frequency_analysis.quarterly_payers.len(), 4);
+        assert_eq!(analytics.consistency_analysis.consistent_payers.len(), 4);
+        assert_eq!(analytics.top_payers.len(), 4);
+    }
+
+    #[test]
+    fn test_generate_skips_analyses_outside_scope() {
+        let tracker = generate_dividends(40, 4);
+        let scope = AnalyticsScope {
+            frequency: true,
+            ..Default::default()
+        };
+        let analytics = DividendAnalytics::generate(&tracker, None, None, &AnalysisSettings::default(), scope).unwrap();
+
+        assert_eq!(analytics.total_payments, 40);
+        assert_eq!(analytics.total_dividends, dec!(1000));
+        assert_eq!(analytics.frequency_analysis.quarterly_payers.len(), 4);
+        assert!(analytics.top_payers.is_empty());
+        assert!(analytics.consistency_analysis.consistent_payers.is_empty());
+        assert!(analytics.growth_analysis.is_none());
+        assert!(analytics.symbol_growth.is_empty());
+    }
+
+    #[test]
+    fn test_growth_counts_drifted_payment_in_its_usual_year() {
+        let mut tracker = DividendTracker::new();
+        for (ex_date, shares) in [("2022-01-05", dec!(10)), ("2022-12-29", dec!(11)), ("2024-01-04", dec!(12))] {
+            let date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+            tracker.add_dividend(
+                Dividend::new("JAN".to_string(), None, date, date, dec!(1), shares, DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+
+        let years = |settings: &AnalysisSettings| -> Vec<(i32, usize)> {
+            let analytics = DividendAnalytics::generate(&tracker, None, None, settings, every_analysis()).unwrap();
+            analytics
+                .growth_analysis
+                .unwrap()
+                .year_over_year
+                .iter()
+                .map(|y| (y.year, y.payment_count))
+                .collect()
+        };
+        // Drift is opt-in: by default payments stay in the year of their date
+        assert_eq!(years(&AnalysisSettings::default()), vec![(2022, 2), (2024, 1)]);
+        let drift = AnalysisSettings {
+            month_drift_days: 7,
+            ..AnalysisSettings::default()
+        };
+        assert_eq!(years(&drift), vec![(2022, 1), (2023, 1), (2024, 1)]);
+
+        let filtered = DividendAnalytics::generate(&tracker, Some(2023), None, &drift, every_analysis()).unwrap();
+        assert_eq!(filtered.total_payments, 1);
+        assert!(filtered.monthly_breakdown.contains_key(&1));
+    }
+
+    #[test]
+    fn test_symbol_growth_is_per_share() {
+        let mut tracker = DividendTracker::new();
+        // Share count triples while the per-share dividend grows 10% a year
+        for (ex_date, amount, shares) in [
+            ("2021-06-15", dec!(1.00), dec!(10)),
+            ("2022-06-15", dec!(1.10), dec!(20)),
+            ("2023-06-15", dec!(1.21), dec!(30)),
+        ] {
+            let date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+            tracker.add_dividend(
+                Dividend::new("GRO".to_string(), None, date, date, amount, shares, DividendType::Regular).unwrap(),
+            );
+        }
+
+        let analytics = DividendAnalytics::generate(&tracker, None, None, &AnalysisSettings::default(), every_analysis()).unwrap();
+        let growth = &analytics.symbol_growth[0];
+
+        assert_eq!(growth.symbol, "GRO");
+        assert_eq!(
+            growth.yearly_per_share,
+            vec![(2021, dec!(1.00)), (2022, dec!(1.10)), (2023, dec!(1.21))]
+        );
+        assert_eq!(growth.cagr, Some(dec!(10.00)));
+    }
+
+    #[test]
+    fn test_explain_consistency_flags_missing_payment_gap() {
+        let mut tracker = DividendTracker::new();
+        // Quarterly, with the 2023-08 payment never recorded
+        for ex_date in ["2023-02-15", "2023-05-15", "2023-11-15", "2024-02-15", "2024-05-15"] {
+            let date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+            tracker.add_dividend(
+                Dividend::new("QTR".to_string(), None, date, date, dec!(0.5), dec!(10), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+
+        let explanation = DividendAnalytics::explain_consistency(&tracker, "qtr").unwrap();
+
+        assert_eq!(explanation.intervals.len(), 4);
+        let gap = &explanation.intervals[1];
+        assert_eq!(gap.days, 184);
+        assert!(gap.drags_score);
+        assert_eq!(gap.likely_missing_payments, 1);
+        assert!(explanation.intervals.iter().filter(|i| i.drags_score).count() == 1);
+        assert!(explanation.consistency_score < 70.0);
+
+        assert!(DividendAnalytics::explain_consistency(&tracker, "NONE").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Cache of rendered `summary` and `project` output
+//!
+//! Entries live in the data directory's `analytics_cache` folder, one file per
+//! key. A key hashes the data file's contents together with everything else
+//! the output depends on (command arguments, today's date, settings, output
+//! format), so a stale entry can never match; saving the data also clears the
+//! folder so entries for old data don't pile up.
+
+use anyhow::{Context, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Entries kept at most; the least recently written go first
+const MAX_ENTRIES: usize = 32;
+
+/// The analytics cache of one data directory
+#[derive(Debug, Clone)]
+pub struct AnalyticsCache {
+    dir: PathBuf,
+}
+
+impl AnalyticsCache {
+    pub fn new(data_dir: &Path) -> Self {
+        AnalyticsCache {
+            dir: data_dir.join("analytics_cache"),
+        }
+    }
+
+    /// Key for output built from data with `fingerprint` and the given parameters
+    pub fn key(fingerprint: u64, params: &[&str]) -> String {
+        let mut hasher = DefaultHasher::new();
+        fingerprint.hash(&mut hasher);
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        params.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    fn entry(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.out", key))
+    }
+
+    /// Saved output for `key`, if any
+    pub fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.entry(key)).ok()
+    }
+
+    /// Save output under `key`, dropping the oldest entries beyond the limit
+    pub fn put(&self, key: &str, output: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create analytics cache: {}", self.dir.display()))?;
+        fs::write(self.entry(key), output)?;
+
+        let mut entries: Vec<_> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        if entries.len() > MAX_ENTRIES {
+            entries.sort();
+            for (_, path) in &entries[..entries.len() - MAX_ENTRIES] {
+                let _ = fs::remove_file(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every entry
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to clear analytics cache: {}", self.dir.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_entries_are_keyed_by_data_and_parameters() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = AnalyticsCache::new(temp_dir.path());
+        let key = AnalyticsCache::key(1, &["summary", "--year 2024"]);
+        assert_ne!(key, AnalyticsCache::key(2, &["summary", "--year 2024"]));
+        assert_ne!(key, AnalyticsCache::key(1, &["summary", "--year 2023"]));
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "Total: $10.00\n").unwrap();
+        assert_eq!(cache.get(&key).as_deref(), Some("Total: $10.00\n"));
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(&key), None);
+        cache.clear().unwrap();
+    }
+
+    #[test]
+    fn test_put_keeps_a_bounded_number_of_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = AnalyticsCache::new(temp_dir.path());
+        for n in 0..MAX_ENTRIES + 5 {
+            cache.put(&AnalyticsCache::key(n as u64, &[]), "output").unwrap();
+        }
+        assert_eq!(fs::read_dir(temp_dir.path().join("analytics_cache")).unwrap().count(), MAX_ENTRIES);
+    }
+}
@@ -1,21 +1,192 @@
 use anyhow::{anyhow, Context, Result};
-use chrono::NaiveDate;
-use reqwest::blocking::Client;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use reqwest::blocking::{Client, Response};
+use reqwest::{Proxy, StatusCode};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::thread;
 use std::time::Duration;
 
+use crate::config::ApiSettings;
+use crate::paths;
+
+/// Longest wait between retries, however many there have been
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Called with the position, total and symbol as each symbol of a batch is fetched
+pub type ProgressCallback = Box<dyn Fn(usize, usize, &str)>;
+
 /// Alpha Vantage API client for fetching dividend data
 pub struct AlphaVantageClient {
     client: Client,
     api_key: String,
     cache_dir: PathBuf,
     rate_limit_delay: Duration,
+    timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+/// What kind of failure an [`ApiError`] is, for grouping and exit codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiErrorKind {
+    InvalidKey,
+    RateLimit,
+    UnknownSymbol,
+    Network,
+    Provider,
+}
+
+impl ApiErrorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ApiErrorKind::InvalidKey => "Invalid API key",
+            ApiErrorKind::RateLimit => "Rate limited",
+            ApiErrorKind::UnknownSymbol => "Unknown symbol",
+            ApiErrorKind::Network => "Network",
+            ApiErrorKind::Provider => "Provider error",
+        }
+    }
+
+    /// Process exit code; 1 is left for other errors and 2 for usage errors
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ApiErrorKind::InvalidKey => 3,
+            ApiErrorKind::RateLimit => 4,
+            ApiErrorKind::UnknownSymbol => 5,
+            ApiErrorKind::Network => 6,
+            ApiErrorKind::Provider => 7,
+        }
+    }
+}
+
+/// Why a request to the data provider failed
+///
+/// Timeouts and rate limits are reported differently: one means a slow
+/// network or proxy, the other an exhausted quota.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The provider doesn't accept the API key
+    InvalidKey(String),
+    /// The provider refused the call because of its request quota
+    RateLimited(String),
+    /// The provider has no data for the symbol
+    UnknownSymbol(String),
+    /// No response within the configured timeout
+    Timeout(Duration),
+    /// The connection (or proxy) failed before a response arrived
+    Connection(String),
+    /// The provider answered with a server error
+    Server(StatusCode),
+    /// The provider rejected the request; retrying won't help
+    Rejected(String),
+    /// The response couldn't be read
+    Invalid(String),
+}
+
+impl ApiError {
+    fn from_reqwest(error: reqwest::Error, timeout: Duration) -> Self {
+        // The URL carries the API key
+        let error = error.without_url();
+        if error.is_timeout() {
+            ApiError::Timeout(timeout)
+        } else if error.is_connect() || error.is_request() {
+            ApiError::Connection(error.to_string())
+        } else {
+            ApiError::Invalid(error.to_string())
+        }
+    }
+
+    pub fn kind(&self) -> ApiErrorKind {
+        match self {
+            ApiError::InvalidKey(_) => ApiErrorKind::InvalidKey,
+            ApiError::RateLimited(_) => ApiErrorKind::RateLimit,
+            ApiError::UnknownSymbol(_) => ApiErrorKind::UnknownSymbol,
+            ApiError::Timeout(_) | ApiError::Connection(_) => ApiErrorKind::Network,
+            ApiError::Server(_) | ApiError::Rejected(_) | ApiError::Invalid(_) => ApiErrorKind::Provider,
+        }
+    }
+
+    /// What the user can do about it
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            ApiError::InvalidKey(_) => {
+                "Check the key with `dividend-tracker configure --show`, or get a free one at \
+                 alphavantage.co and save it with `dividend-tracker configure --api-key KEY`"
+            }
+            ApiError::RateLimited(_) => {
+                "Wait a minute (or until tomorrow once the daily quota is used up) and try again; \
+                 raise api.rate_limit_delay_ms in config.toml to space out calls"
+            }
+            ApiError::UnknownSymbol(_) => {
+                "Check the ticker; listings outside the US need an exchange suffix such as SHOP.TRT"
+            }
+            ApiError::Timeout(_) => {
+                "Raise api.timeout_seconds in config.toml if your network or proxy is slow"
+            }
+            ApiError::Connection(_) => {
+                "Check your network connection and proxy settings (api.proxy in config.toml, HTTPS_PROXY)"
+            }
+            ApiError::Server(_) => "Alpha Vantage is having trouble; try again later",
+            ApiError::Rejected(_) | ApiError::Invalid(_) => {
+                "Try again later; if it keeps failing the provider may have changed its API"
+            }
+        }
+    }
+
+    /// Whether the same request may succeed if sent again
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ApiError::Timeout(_) | ApiError::RateLimited(_) | ApiError::Connection(_) | ApiError::Server(_)
+        )
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::InvalidKey(message) => write!(f, "API key rejected: {}", message),
+            ApiError::RateLimited(message) => write!(f, "Rate limit exceeded: {}", message),
+            ApiError::UnknownSymbol(symbol) => write!(f, "Alpha Vantage has no data for {}", symbol),
+            ApiError::Timeout(timeout) => write!(f, "Request timed out after {}s", timeout.as_secs()),
+            ApiError::Connection(message) => write!(f, "Could not connect to Alpha Vantage: {}", message),
+            ApiError::Server(status) => write!(f, "Alpha Vantage returned {}", status),
+            ApiError::Rejected(message) => write!(f, "API error: {}", message),
+            ApiError::Invalid(message) => write!(f, "Failed to parse API response: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Turn an in-body message into an error; Alpha Vantage reports bad keys,
+/// unknown symbols and exhausted quotas as 200 responses
+fn body_error(body: &serde_json::Value, symbol: &str) -> Option<ApiError> {
+    ["Error Message", "Note", "Information"].iter().find_map(|key| {
+        let message = body.get(key)?.as_str()?.to_string();
+        let lower = message.to_lowercase();
+        let about_key = lower.contains("apikey") || lower.contains("api key");
+        Some(if lower.contains("rate limit") || lower.contains("call frequency") {
+            ApiError::RateLimited(message)
+        } else if about_key && ["invalid", "missing", "demo"].iter().any(|w| lower.contains(w)) {
+            ApiError::InvalidKey(message)
+        } else if lower.contains("invalid api call") {
+            ApiError::UnknownSymbol(symbol.to_uppercase())
+        } else {
+            ApiError::Rejected(message)
+        })
+    })
+}
+
+/// Wait before retry number `attempt` (from 0): the base doubled each time
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF)
 }
 
 /// Response structure for dividend data from Alpha Vantage
@@ -82,29 +253,60 @@ struct DailyData {
 }
 
 /// Dividend data extracted from API response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DividendData {
     pub symbol: String,
     pub ex_date: NaiveDate,
     pub amount: Decimal,
 }
 
+/// A declared dividend from the corporate-actions endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DividendAnnouncement {
+    pub symbol: String,
+    pub declaration_date: Option<NaiveDate>,
+    pub ex_date: NaiveDate,
+    pub record_date: Option<NaiveDate>,
+    pub pay_date: Option<NaiveDate>,
+    pub amount: Decimal,
+}
+
+/// A headline from the news endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub title: String,
+    pub source: String,
+    pub url: String,
+    pub published: NaiveDateTime,
+    pub summary: String,
+}
+
 impl AlphaVantageClient {
     /// Create a new Alpha Vantage API client
-    pub fn new(api_key: String) -> Result<Self> {
-        let cache_dir = dirs::cache_dir()
-            .ok_or_else(|| anyhow!("Could not determine cache directory"))?
-            .join("dividend-tracker")
-            .join("api_cache");
+    ///
+    /// Requests go through `settings.proxy` when set, otherwise through
+    /// HTTP_PROXY/HTTPS_PROXY from the environment.
+    pub fn new(api_key: String, settings: &ApiSettings) -> Result<Self> {
+        let cache_dir = paths::cache_dir()?.join("api_cache");
 
         // Create cache directory if it doesn't exist
         fs::create_dir_all(&cache_dir)?;
 
+        let timeout = Duration::from_secs(settings.timeout_seconds.max(1));
+        let mut builder = Client::builder().timeout(timeout);
+        if let Some(proxy) = settings.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            let proxy = Proxy::all(proxy).with_context(|| format!("Invalid api.proxy URL '{}'", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+
         Ok(Self {
-            client: Client::new(),
+            client: builder.build().context("Failed to create HTTP client")?,
             api_key,
             cache_dir,
-            rate_limit_delay: Duration::from_millis(12000), // 5 calls per minute = 12 seconds between calls
+            rate_limit_delay: Duration::from_millis(settings.rate_limit_delay_ms),
+            timeout,
+            max_retries: settings.max_retries,
+            retry_backoff: Duration::from_millis(settings.retry_backoff_ms),
         })
     }
 
@@ -142,32 +344,91 @@ impl AlphaVantageClient {
         // Apply rate limiting
         thread::sleep(self.rate_limit_delay);
 
-        let url = format!(
-            "https://www.alphavantage.co/query?function=TIME_SERIES_MONTHLY_ADJUSTED&symbol={}&apikey={}",
-            symbol, self.api_key
-        );
+        let body = self.query(symbol, &format!("function=TIME_SERIES_MONTHLY_ADJUSTED&symbol={}", symbol))?;
+        Ok(serde_json::from_value(body).map_err(|e| ApiError::Invalid(e.to_string()))?)
+    }
+
+    /// Check that the API answers and accepts the key
+    ///
+    /// Skips the cache, the rate-limit delay and retries. Returns the
+    /// server's clock from the `Date` header when it sends one.
+    pub fn ping(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        let response = self.send(&self.url("function=GLOBAL_QUOTE&symbol=IBM"))?;
+        let server_time = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+        self.read_body(response, "IBM")?;
+        Ok(server_time)
+    }
+
+    /// Declared dividends for a symbol, newest first
+    ///
+    /// Unlike `fetch_dividends` this is never cached: announcements are
+    /// wanted as soon as they appear.
+    pub fn fetch_dividend_announcements(&self, symbol: &str) -> Result<Vec<DividendAnnouncement>> {
+        let body = self.query(symbol, &format!("function=DIVIDENDS&symbol={}", symbol))?;
+        parse_dividend_announcements(symbol, &body)
+    }
+
+    /// Recent headlines mentioning a symbol, newest first
+    pub fn fetch_news(&self, symbol: &str, limit: usize) -> Result<Vec<NewsItem>> {
+        let body = self.query(symbol, &format!(
+            "function=NEWS_SENTIMENT&tickers={}&sort=LATEST&limit={}",
+            symbol, limit
+        ))?;
+        parse_news(&body)
+    }
+
+    fn url(&self, query: &str) -> String {
+        format!("https://www.alphavantage.co/query?{}&apikey={}", query, self.api_key)
+    }
+
+    /// Send a query and return its JSON, turning in-body errors into errors
+    ///
+    /// Timeouts, connection failures, server errors and rate limits are
+    /// retried up to `max_retries` times, waiting longer before each retry.
+    fn query(&self, symbol: &str, query: &str) -> std::result::Result<serde_json::Value, ApiError> {
+        let url = self.url(query);
+        let mut attempt = 0;
+        loop {
+            let error = match self.send(&url).and_then(|response| self.read_body(response, symbol)) {
+                Ok(body) => return Ok(body),
+                Err(error) => error,
+            };
+            if !error.is_transient() || attempt >= self.max_retries {
+                return Err(error);
+            }
+            thread::sleep(backoff_delay(self.retry_backoff, attempt));
+            attempt += 1;
+        }
+    }
 
+    /// Send one request, failing on transport errors and error statuses
+    fn send(&self, url: &str) -> std::result::Result<Response, ApiError> {
         let response = self
             .client
-            .get(&url)
+            .get(url)
             .send()
-            .context("Failed to send API request")?
-            .json::<DividendResponse>()
-            .context("Failed to parse API response")?;
-
-        // Check for error messages
-        if let Some(error) = response.error_message {
-            return Err(anyhow!("API error: {}", error));
+            .map_err(|e| ApiError::from_reqwest(e, self.timeout))?;
+        match response.status() {
+            StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimited("too many requests".to_string())),
+            status if status.is_server_error() => Err(ApiError::Server(status)),
+            status if !status.is_success() => Err(ApiError::Rejected(status.to_string())),
+            _ => Ok(response),
         }
+    }
 
-        // Check for rate limit message
-        if let Some(ref note) = response.note {
-            if note.contains("API call frequency") {
-                return Err(anyhow!("Rate limit exceeded: {}", note));
-            }
+    /// Read a response's JSON, turning in-body messages into errors
+    fn read_body(&self, response: Response, symbol: &str) -> std::result::Result<serde_json::Value, ApiError> {
+        let body = response
+            .json::<serde_json::Value>()
+            .map_err(|e| ApiError::from_reqwest(e, self.timeout))?;
+        match body_error(&body, symbol) {
+            Some(error) => Err(error),
+            None => Ok(body),
         }
-
-        Ok(response)
     }
 
     /// Parse dividend data from API response
@@ -197,7 +458,7 @@ impl AlphaVantageClient {
             }
         }
 
-        dividends.sort_by(|a, b| b.ex_date.cmp(&a.ex_date)); // Sort by date, newest first
+        dividends.sort_by_key(|d| std::cmp::Reverse(d.ex_date)); // Sort by date, newest first
         Ok(dividends)
     }
 
@@ -268,7 +529,7 @@ impl AlphaVantageClient {
         symbols: &[String],
         from_date: Option<NaiveDate>,
         to_date: Option<NaiveDate>,
-        progress_callback: Option<Box<dyn Fn(usize, usize, &str)>>,
+        progress_callback: Option<ProgressCallback>,
     ) -> HashMap<String, Result<Vec<DividendData>>> {
         let mut results = HashMap::new();
         let total = symbols.len();
@@ -286,54 +547,143 @@ impl AlphaVantageClient {
     }
 }
 
-/// Configuration for API settings
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiConfig {
-    pub api_key: String,
-    pub rate_limit_delay_ms: u64,
+/// Parse a `DIVIDENDS` response; dates the provider doesn't know are "None"
+fn parse_dividend_announcements(symbol: &str, body: &serde_json::Value) -> Result<Vec<DividendAnnouncement>> {
+    let date = |record: &serde_json::Value, key: &str| {
+        record
+            .get(key)
+            .and_then(|d| d.as_str())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    };
+
+    let records = body
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| anyhow!("Unexpected response: no dividend data for {}", symbol))?;
+    let mut announcements = Vec::new();
+    for record in records {
+        let Some(ex_date) = date(record, "ex_dividend_date") else {
+            continue;
+        };
+        let amount = record
+            .get("amount")
+            .and_then(|a| a.as_str())
+            .and_then(|a| Decimal::from_str(a).ok())
+            .filter(|a| *a > Decimal::ZERO);
+        let Some(amount) = amount else {
+            continue;
+        };
+        announcements.push(DividendAnnouncement {
+            symbol: symbol.to_uppercase(),
+            declaration_date: date(record, "declaration_date"),
+            ex_date,
+            record_date: date(record, "record_date"),
+            pay_date: date(record, "payment_date"),
+            amount,
+        });
+    }
+
+    announcements.sort_by_key(|a| std::cmp::Reverse(a.ex_date));
+    Ok(announcements)
 }
 
-impl ApiConfig {
-    /// Load API configuration from file
-    pub fn load() -> Result<Self> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| anyhow!("Could not determine config directory"))?
-            .join("dividend-tracker");
+/// Parse a `NEWS_SENTIMENT` response
+fn parse_news(body: &serde_json::Value) -> Result<Vec<NewsItem>> {
+    let text = |item: &serde_json::Value, key: &str| {
+        item.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+    };
+
+    let feed = body
+        .get("feed")
+        .and_then(|f| f.as_array())
+        .ok_or_else(|| anyhow!("Unexpected response: no news feed"))?;
+    let mut items: Vec<NewsItem> = feed
+        .iter()
+        .filter_map(|item| {
+            let published = NaiveDateTime::parse_from_str(&text(item, "time_published"), "%Y%m%dT%H%M%S").ok()?;
+            Some(NewsItem {
+                title: text(item, "title"),
+                source: text(item, "source"),
+                url: text(item, "url"),
+                published,
+                summary: text(item, "summary"),
+            })
+        })
+        .collect();
 
-        let config_file = config_dir.join("config.json");
+    items.sort_by_key(|item| std::cmp::Reverse(item.published));
+    Ok(items)
+}
 
-        if config_file.exists() {
-            let contents = fs::read_to_string(&config_file)?;
-            let config: ApiConfig = serde_json::from_str(&contents)?;
-            Ok(config)
-        } else {
-            // Check environment variable
-            if let Ok(api_key) = std::env::var("ALPHA_VANTAGE_API_KEY") {
-                Ok(ApiConfig {
-                    api_key,
-                    rate_limit_delay_ms: 12000,
-                })
-            } else {
-                Err(anyhow!(
-                    "No API key found. Set ALPHA_VANTAGE_API_KEY environment variable or create config file at {:?}",
-                    config_file
-                ))
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_dividend_announcements_skips_unknown_dates() {
+        let body = json!({ "symbol": "KO", "data": [
+            { "ex_dividend_date": "2024-06-14", "declaration_date": "2024-04-25",
+              "record_date": "2024-06-14", "payment_date": "2024-07-01", "amount": "0.485" },
+            { "ex_dividend_date": "2024-11-29", "declaration_date": "None",
+              "record_date": "None", "payment_date": "None", "amount": "0.485" },
+            { "ex_dividend_date": "None", "amount": "0.46" },
+        ]});
+
+        let announcements = parse_dividend_announcements("ko", &body).unwrap();
+        assert_eq!(announcements.len(), 2);
+        assert_eq!(announcements[0].ex_date, NaiveDate::from_ymd_opt(2024, 11, 29).unwrap());
+        assert_eq!(announcements[0].pay_date, None);
+        assert_eq!(announcements[1].symbol, "KO");
+        assert_eq!(announcements[1].amount, dec!(0.485));
+        assert_eq!(announcements[1].declaration_date, NaiveDate::from_ymd_opt(2024, 4, 25));
+
+        assert!(parse_dividend_announcements("KO", &json!({})).is_err());
     }
 
-    /// Save API configuration to file
-    pub fn save(&self) -> Result<()> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| anyhow!("Could not determine config directory"))?
-            .join("dividend-tracker");
-
-        fs::create_dir_all(&config_dir)?;
+    #[test]
+    fn test_body_error_classifies_provider_messages() {
+        let quota = json!({ "Information": "Our standard API rate limit is 25 requests per day." });
+        let frequency = json!({ "Note": "Thank you for using Alpha Vantage! Our standard API call frequency is 5 calls per minute." });
+        let bad_key = json!({ "Error Message": "the parameter apikey is invalid or missing." });
+        let unknown = json!({ "Error Message": "Invalid API call. Please retry or visit the documentation." });
+        let premium = json!({ "Information": "This is a premium endpoint." });
+
+        assert_eq!(body_error(&quota, "KO").unwrap().kind(), ApiErrorKind::RateLimit);
+        assert_eq!(body_error(&frequency, "KO").unwrap().kind(), ApiErrorKind::RateLimit);
+        assert_eq!(body_error(&bad_key, "KO").unwrap().kind(), ApiErrorKind::InvalidKey);
+        assert_eq!(body_error(&unknown, "xyzq"), Some(ApiError::UnknownSymbol("XYZQ".to_string())));
+        assert_eq!(body_error(&premium, "KO").unwrap().kind(), ApiErrorKind::Provider);
+        assert!(body_error(&json!({ "data": [] }), "KO").is_none());
+
+        assert!(body_error(&quota, "KO").unwrap().is_transient());
+        assert!(!body_error(&bad_key, "KO").unwrap().is_transient());
+        assert_eq!(ApiError::Timeout(Duration::from_secs(30)).kind(), ApiErrorKind::Network);
+        assert_ne!(ApiErrorKind::Network.exit_code(), ApiErrorKind::RateLimit.exit_code());
+    }
 
-        let config_file = config_dir.join("config.json");
-        let json = serde_json::to_string_pretty(&self)?;
-        fs::write(config_file, json)?;
+    #[test]
+    fn test_backoff_doubles_up_to_a_cap() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 40), MAX_BACKOFF);
+    }
 
-        Ok(())
+    #[test]
+    fn test_parse_news_sorts_newest_first() {
+        let body = json!({ "feed": [
+            { "title": "Coca-Cola raises dividend", "source": "Wire", "url": "https://example.com/1",
+              "time_published": "20240215T083000", "summary": "Quarterly dividend up 5%" },
+            { "title": "Later story", "source": "Wire", "url": "https://example.com/2",
+              "time_published": "20240301T120000", "summary": "" },
+            { "title": "No timestamp" },
+        ]});
+
+        let items = parse_news(&body).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Later story");
+        assert_eq!(items[1].summary, "Quarterly dividend up 5%");
     }
 }
@@ -0,0 +1,269 @@
+//! Month-by-month view of ex-dates and pay dates, for `calendar --interactive`
+//!
+//! Recorded dividends fill in the past and the fetched calendar the months
+//! ahead. A calendar entry is left out once a dividend for the same symbol
+//! and ex-date has been recorded, so a payment never shows twice.
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::models::{DividendCalendarEntry, DividendTracker, Holding, DEFAULT_ACCOUNT};
+
+/// Which of a dividend's dates an event is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventKind {
+    ExDate,
+    PayDate,
+}
+
+/// One ex-date or pay date on the calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub kind: EventKind,
+    pub symbol: String,
+    pub amount_per_share: Option<Decimal>,
+    /// Payment for the shares recorded, or held for an upcoming one
+    pub total: Option<Decimal>,
+    /// Whether it comes from the calendar's estimates rather than a recorded dividend
+    pub estimated: bool,
+}
+
+/// A month of events with one day selected
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthCalendar {
+    events: BTreeMap<NaiveDate, Vec<CalendarEvent>>,
+    /// First day of the month shown
+    month: NaiveDate,
+    selected: NaiveDate,
+}
+
+impl MonthCalendar {
+    /// Gather every recorded dividend and calendar entry, opening on `today`'s month
+    pub fn new(tracker: &DividendTracker, calendar: &[DividendCalendarEntry], today: NaiveDate) -> Self {
+        let mut events: BTreeMap<NaiveDate, Vec<CalendarEvent>> = BTreeMap::new();
+        let mut add = |date: NaiveDate, event: CalendarEvent| events.entry(date).or_default().push(event);
+
+        for dividend in &tracker.dividends {
+            let event = |kind| CalendarEvent {
+                kind,
+                symbol: dividend.symbol.clone(),
+                amount_per_share: Some(dividend.amount_per_share),
+                total: Some(dividend.total_amount),
+                estimated: false,
+            };
+            add(dividend.ex_date, event(EventKind::ExDate));
+            add(dividend.pay_date, event(EventKind::PayDate));
+        }
+
+        for entry in calendar {
+            // Accounts that already recorded the payment drop out of the estimate
+            let holdings = tracker.holdings_of(&entry.symbol);
+            let unrecorded: Vec<&Holding> = holdings
+                .iter()
+                .copied()
+                .filter(|h| !tracker.has_duplicate(&entry.symbol, entry.ex_date, h.account_name()))
+                .collect();
+            let recorded = if holdings.is_empty() {
+                tracker.has_duplicate(&entry.symbol, entry.ex_date, DEFAULT_ACCOUNT)
+            } else {
+                unrecorded.is_empty()
+            };
+            if recorded {
+                continue;
+            }
+            let shares = (!unrecorded.is_empty()).then(|| unrecorded.iter().map(|h| h.shares).sum::<Decimal>());
+            let event = |kind| CalendarEvent {
+                kind,
+                symbol: entry.symbol.clone(),
+                amount_per_share: entry.estimated_amount,
+                total: entry.estimated_amount.zip(shares).map(|(amount, shares)| amount * shares),
+                estimated: entry.is_estimated,
+            };
+            add(entry.ex_date, event(EventKind::ExDate));
+            if let Some(pay_date) = entry.pay_date {
+                add(pay_date, event(EventKind::PayDate));
+            }
+        }
+
+        for day in events.values_mut() {
+            day.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.symbol.cmp(&b.symbol)));
+        }
+
+        MonthCalendar {
+            events,
+            month: first_of_month(today),
+            selected: today,
+        }
+    }
+
+    /// First day of the month shown
+    pub fn month(&self) -> NaiveDate {
+        self.month
+    }
+
+    pub fn selected(&self) -> NaiveDate {
+        self.selected
+    }
+
+    /// Events on a day, ex-dates first
+    pub fn events_on(&self, date: NaiveDate) -> &[CalendarEvent] {
+        self.events.get(&date).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Whether a day has an ex-date and whether it has a pay date
+    pub fn kinds_on(&self, date: NaiveDate) -> (bool, bool) {
+        let events = self.events_on(date);
+        (
+            events.iter().any(|e| e.kind == EventKind::ExDate),
+            events.iter().any(|e| e.kind == EventKind::PayDate),
+        )
+    }
+
+    /// Payments due in the month shown
+    pub fn month_income(&self) -> Decimal {
+        self.event_days()
+            .flat_map(|date| self.events_on(date))
+            .filter(|e| e.kind == EventKind::PayDate)
+            .filter_map(|e| e.total)
+            .sum()
+    }
+
+    /// The month as weeks from Monday to Sunday, blank outside the month
+    pub fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let offset = self.month.weekday().num_days_from_monday() as i64;
+        let start = self.month - Duration::days(offset);
+        let mut weeks = Vec::new();
+        let mut day = start;
+        while day.month() == self.month.month() || day < self.month {
+            let mut week = [None; 7];
+            for cell in &mut week {
+                *cell = (day.month() == self.month.month()).then_some(day);
+                day += Duration::days(1);
+            }
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    pub fn next_month(&mut self) {
+        self.show_month(self.month + Months::new(1));
+    }
+
+    pub fn previous_month(&mut self) {
+        self.show_month(self.month - Months::new(1));
+    }
+
+    /// Go to the month of `date` and select it
+    pub fn go_to(&mut self, date: NaiveDate) {
+        self.month = first_of_month(date);
+        self.selected = date;
+    }
+
+    /// Select the next day in the month with an ex-date or pay date
+    pub fn next_event_day(&mut self) {
+        let next = self.event_days().find(|&d| d > self.selected);
+        self.selected = next.unwrap_or(self.selected);
+    }
+
+    /// Select the previous day in the month with an ex-date or pay date
+    pub fn previous_event_day(&mut self) {
+        let previous = self.event_days().rev().find(|&d| d < self.selected);
+        self.selected = previous.unwrap_or(self.selected);
+    }
+
+    /// Show a month, selecting its first day with events (or the 1st)
+    fn show_month(&mut self, month: NaiveDate) {
+        self.month = month;
+        let first_event = self.event_days().next();
+        self.selected = first_event.unwrap_or(month);
+    }
+
+    fn event_days(&self) -> impl DoubleEndedIterator<Item = NaiveDate> + '_ {
+        let end = self.month + Months::new(1);
+        self.events.range(self.month..end).map(|(date, _)| *date)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a first day")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn view() -> MonthCalendar {
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(100), None, None).unwrap());
+        tracker.add_dividend(
+            Dividend::new(
+                "KO".to_string(),
+                None,
+                date("2024-03-14"),
+                date("2024-04-01"),
+                dec!(0.485),
+                dec!(100),
+                DividendType::Regular,
+            )
+            .unwrap(),
+        );
+        let calendar = vec![
+            // Already recorded, so not shown again
+            DividendCalendarEntry::new("KO".to_string(), None, date("2024-03-14"), Some(date("2024-04-01")), Some(dec!(0.485)), true),
+            DividendCalendarEntry::new("KO".to_string(), None, date("2024-06-13"), Some(date("2024-07-01")), Some(dec!(0.485)), true),
+        ];
+        MonthCalendar::new(&tracker, &calendar, date("2024-03-20"))
+    }
+
+    #[test]
+    fn test_month_grid_and_events() {
+        let view = view();
+        assert_eq!(view.month(), date("2024-03-01"));
+        let weeks = view.weeks();
+        // March 2024 starts on a Friday and ends on a Sunday
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(
+            weeks[0],
+            [None, None, None, None, Some(date("2024-03-01")), Some(date("2024-03-02")), Some(date("2024-03-03"))]
+        );
+        assert_eq!(weeks[4][6], Some(date("2024-03-31")));
+
+        assert_eq!(view.kinds_on(date("2024-03-14")), (true, false));
+        assert_eq!(view.events_on(date("2024-04-01")).len(), 1);
+        assert_eq!(view.month_income(), dec!(0));
+    }
+
+    #[test]
+    fn test_navigation_moves_between_months_and_event_days() {
+        let mut view = view();
+        view.previous_event_day();
+        assert_eq!(view.selected(), date("2024-03-14"));
+
+        view.next_month();
+        assert_eq!(view.selected(), date("2024-04-01"));
+        assert_eq!(view.month_income(), dec!(48.5));
+
+        view.next_month();
+        view.next_month();
+        assert_eq!(view.selected(), date("2024-06-13"));
+        view.next_event_day();
+        assert_eq!(view.selected(), date("2024-06-13"));
+
+        view.next_month();
+        let upcoming = view.events_on(view.selected());
+        assert!(upcoming[0].estimated);
+        assert_eq!(upcoming[0].total, Some(dec!(48.500)));
+
+        view.go_to(date("2024-03-20"));
+        assert_eq!(view.month(), date("2024-03-01"));
+        view.previous_month();
+        assert_eq!(view.selected(), date("2024-02-01"));
+    }
+}
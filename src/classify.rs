@@ -0,0 +1,148 @@
+//! Tax classification guessed from the kind of security
+//!
+//! Dividends are recorded as unknown unless told otherwise, which leaves each
+//! one to `tax classify`. Some kinds of security are easy to spot and nearly
+//! always taxed one way: listings on a foreign exchange pay foreign
+//! dividends, municipal bond funds tax-free ones and REITs non-qualified
+//! ones. A dividend added as unknown is classified this way unless
+//! `[classification] auto` is off or `securities.toml` pins the symbol's
+//! classification, and `tax classify` still changes it afterwards.
+
+use crate::config::ClassificationSettings;
+use crate::models::{Dividend, DividendTracker, IncomeKind, TaxClassification};
+use crate::symbols::normalize_symbol;
+
+/// Widely held REITs and REIT funds
+const KNOWN_REITS: &[&str] = &[
+    "ADC", "AGNC", "AMH", "AMT", "ARE", "AVB", "BXMT", "BXP", "CCI", "CPT", "CUBE", "DLR", "EPR", "EQIX", "EQR",
+    "ESS", "EXR", "FRT", "GLPI", "HST", "INVH", "IRM", "IYR", "KIM", "LAMR", "MAA", "MPW", "NLY", "NNN", "O",
+    "OHI", "PLD", "PSA", "REG", "SBAC", "SCHH", "SPG", "STAG", "STWD", "UDR", "USRT", "VICI", "VNQ", "VTR",
+    "WELL", "WPC", "XLRE",
+];
+
+/// Widely held municipal bond funds
+const KNOWN_MUNICIPAL_FUNDS: &[&str] = &[
+    "BFK", "FMB", "HYD", "HYMB", "ITM", "MLN", "MUA", "MUB", "MYI", "NAD", "NEA", "NMZ", "NUV", "NVG", "NZF",
+    "PZA", "SHM", "SMB", "SUB", "TFI", "VTEB",
+];
+
+/// A classification worked out for a dividend, with why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    pub classification: TaxClassification,
+    pub reason: String,
+}
+
+/// Guess the classification of a symbol's dividends
+///
+/// `company_name` lets a name such as "... Municipal Fund" or "... REIT"
+/// identify symbols missing from the lists.
+pub fn guess(dividend: &Dividend, company_name: Option<&str>, settings: &ClassificationSettings) -> Option<Guess> {
+    if dividend.income_kind == IncomeKind::Interest {
+        return None;
+    }
+    let listed = |builtin: &[&str], configured: &[String]| {
+        builtin.contains(&dividend.symbol.as_str())
+            || configured.iter().any(|s| normalize_symbol(s) == dividend.symbol)
+    };
+    let name = company_name.unwrap_or_default().to_lowercase();
+    let guess = |classification, reason: String| Some(Guess { classification, reason });
+
+    if let Some(country) = dividend.source_country().filter(|&c| c != "US") {
+        return guess(TaxClassification::Foreign, format!("{} pays from {}", dividend.symbol, country));
+    }
+    if listed(KNOWN_MUNICIPAL_FUNDS, &settings.municipal_funds)
+        || ["municipal", "tax-free", "tax free", "tax-exempt"].iter().any(|w| name.contains(w))
+    {
+        return guess(TaxClassification::TaxFree, format!("{} is a municipal bond fund", dividend.symbol));
+    }
+    if listed(KNOWN_REITS, &settings.reits) || name.ends_with(" reit") || name.contains("real estate investment trust")
+    {
+        return guess(TaxClassification::NonQualified, format!("{} is a REIT", dividend.symbol));
+    }
+    None
+}
+
+/// Classify a dividend about to be added if it's still unknown, returning the guess made
+pub fn classify_new(tracker: &DividendTracker, dividend: &mut Dividend, settings: &ClassificationSettings) -> Option<Guess> {
+    if !settings.auto
+        || dividend.tax_classification != TaxClassification::Unknown
+        || tracker.securities.tax_classification(&dividend.symbol).is_some()
+    {
+        return None;
+    }
+    let guess = guess(dividend, tracker.company_name(dividend), settings)?;
+    dividend.tax_classification = guess.classification.clone();
+    Some(guess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DividendType;
+    use crate::securities::Securities;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn dividend(symbol: &str) -> Dividend {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        Dividend::new(symbol.to_string(), None, date, date, dec!(0.25), dec!(100), DividendType::Regular).unwrap()
+    }
+
+    fn classified(symbol: &str, name: Option<&str>, settings: &ClassificationSettings) -> Option<TaxClassification> {
+        guess(&dividend(symbol), name, settings).map(|g| g.classification)
+    }
+
+    #[test]
+    fn test_guess_by_listing_lists_and_name() {
+        let settings = ClassificationSettings {
+            reits: vec!["wsr".to_string()],
+            ..ClassificationSettings::default()
+        };
+
+        assert_eq!(classified("O", None, &settings), Some(TaxClassification::NonQualified));
+        assert_eq!(classified("WSR", None, &settings), Some(TaxClassification::NonQualified));
+        assert_eq!(
+            classified("XYZ", Some("Example Realty REIT"), &settings),
+            Some(TaxClassification::NonQualified)
+        );
+        assert_eq!(classified("VTEB", None, &settings), Some(TaxClassification::TaxFree));
+        assert_eq!(
+            classified("ABC", Some("Example Municipal Income Fund"), &settings),
+            Some(TaxClassification::TaxFree)
+        );
+        // A foreign listing is foreign, even when it's a REIT
+        assert_eq!(classified("REI-UN.TO", Some("RioCan REIT"), &settings), Some(TaxClassification::Foreign));
+        assert_eq!(classified("KO", Some("The Coca-Cola Company"), &settings), None);
+
+        let mut interest = dividend("VTEB");
+        interest.income_kind = IncomeKind::Interest;
+        assert_eq!(guess(&interest, None, &settings), None);
+    }
+
+    #[test]
+    fn test_classify_new_leaves_chosen_and_pinned_classifications() {
+        let mut tracker = DividendTracker::new();
+        let settings = ClassificationSettings::default();
+
+        let mut reit = dividend("O");
+        assert_eq!(classify_new(&tracker, &mut reit, &settings).unwrap().reason, "O is a REIT");
+        assert_eq!(reit.tax_classification, TaxClassification::NonQualified);
+
+        let mut chosen = dividend("O");
+        chosen.tax_classification = TaxClassification::Qualified;
+        assert!(classify_new(&tracker, &mut chosen, &settings).is_none());
+        assert_eq!(chosen.tax_classification, TaxClassification::Qualified);
+
+        let off = ClassificationSettings {
+            auto: false,
+            ..ClassificationSettings::default()
+        };
+        assert!(classify_new(&tracker, &mut dividend("O"), &off).is_none());
+
+        tracker.securities = Securities::parse("[O]\ntax_classification = \"qualified\"\n").unwrap();
+        let mut pinned = dividend("O");
+        assert!(classify_new(&tracker, &mut pinned, &settings).is_none());
+        assert_eq!(pinned.tax_classification, TaxClassification::Unknown);
+    }
+}
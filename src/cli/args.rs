@@ -0,0 +1,1947 @@
+//! Command-line definitions
+//!
+//! The clap command tree, the typed values for its options and the routing
+//! table for deprecated command paths. Typed values let clap validate options
+//! at parse time, list the valid choices in `--help` and suggest corrections
+//! for typos.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::config::DateBasis;
+use crate::filters::SymbolMatch;
+use crate::models::{DividendFrequency, IncomeKind, TaxClassification};
+use crate::projections::{GrowthScenario, ProjectionMethod};
+use crate::render::{OutputFormat, TableStyle};
+use crate::securities::FrequencyOverride;
+use crate::symbols::normalize_symbol;
+use crate::tax::{FilingStatus, IncomeBracket};
+use crate::tokens::TokenScope;
+
+#[derive(Parser)]
+#[command(name = "dividend-tracker")]
+#[command(about = "A comprehensive CLI tool for tracking dividend payments, managing stock holdings, and analyzing portfolio performance")]
+#[command(version = env!("CARGO_PKG_VERSION"))]
+#[command(long_about = "
+dividend-tracker helps you track dividend payments, manage stock holdings,
+and analyze your portfolio performance. It supports importing data from CSV files,
+fetching real-time data from APIs, and exporting reports in multiple formats.
+
+Data is stored securely in JSON format with automatic backups.
+
+EXAMPLES:
+    # Add a dividend payment
+    dividend-tracker div add AAPL --ex-date 2024-02-09 --pay-date 2024-02-15 --amount 0.24 --shares 100
+
+    # List all dividends for a specific year
+    dividend-tracker div list --year 2024
+
+    # Add a stock holding
+    dividend-tracker holdings add MSFT --shares 50 --cost-basis 150.00
+
+    # Export data to CSV
+    dividend-tracker data export --format csv --output my_portfolio
+
+    # Show portfolio summary
+    dividend-tracker summary --year 2024
+")]
+pub struct Cli {
+    /// Custom data directory path (default: ~/.dividend-tracker, %APPDATA%\dividend-tracker\data on Windows)
+    #[arg(long, global = true, help = "Specify custom data directory")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Enable verbose output
+    #[arg(short = 'v', long, global = true, help = "Show detailed output")]
+    pub verbose: bool,
+
+    /// Enable quiet mode (minimal output)
+    #[arg(short = 'q', long, global = true, help = "Show minimal output")]
+    pub quiet: bool,
+
+    /// Output format for reports (summary, list, project, tax, holdings list)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table, help = "Render reports as table, json, csv, html or markdown")]
+    pub output_format: OutputFormat,
+
+    /// Border style for terminal tables (overrides display.table_style in config.toml)
+    #[arg(long, global = true, value_enum, help = "Table style: rounded, ascii, markdown or compact")]
+    pub table_style: Option<TableStyle>,
+
+    /// Truncate table cells to this many characters (overrides display.max_column_width)
+    #[arg(long, global = true, value_name = "CHARS", value_parser = clap::value_parser!(u16).range(2..), help = "Cut table cells longer than CHARS with an ellipsis")]
+    pub max_column_width: Option<u16>,
+
+    /// Answer JSON-RPC requests on stdin (add, list, summary, project) until it closes
+    #[arg(long)]
+    pub rpc: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Record and browse dividend payments
+    #[command(visible_alias = "dividends")]
+    Div {
+        #[command(subcommand)]
+        command: DivCommands,
+    },
+    /// Show portfolio summary and statistics
+    Summary(SummaryArgs),
+    /// Project future dividend income based on historical data
+    Project(ProjectArgs),
+    /// Manage stock holdings in your portfolio
+    Holdings {
+        #[command(subcommand)]
+        command: HoldingsCommands,
+    },
+    /// Fetch dividend data from Alpha Vantage API
+    #[command(subcommand_negates_reqs = true)]
+    Fetch(FetchArgs),
+    /// Update existing dividend data with recent dividends
+    Update(UpdateArgs),
+    /// Configure API settings
+    Configure(ConfigureArgs),
+    /// Show dividend alerts for upcoming ex-dates
+    Alerts(AlertsArgs),
+    /// Display dividend calendar
+    Calendar(CalendarArgs),
+    /// Data management commands
+    Data {
+        #[command(subcommand)]
+        command: DataCommands,
+    },
+    /// Tax reporting and analysis commands
+    Tax {
+        #[command(subcommand)]
+        command: TaxCommands,
+    },
+    /// Ticker spellings and aliases
+    Symbol {
+        #[command(subcommand)]
+        command: SymbolCommands,
+    },
+    /// Corporate actions: splits, symbol changes and special dividends
+    Actions {
+        #[command(subcommand)]
+        command: ActionCommands,
+    },
+    /// Dated research notes per symbol
+    Journal {
+        #[command(subcommand)]
+        command: JournalCommands,
+    },
+    /// Recent dividend announcements and headlines for a symbol
+    News(NewsArgs),
+    /// Show a symbol's holding, dividends and journal
+    Show {
+        /// Stock symbol
+        symbol: String,
+    },
+    /// Buys and sells, from which the shares held on each ex-date follow
+    Transactions {
+        #[command(subcommand)]
+        command: TransactionCommands,
+    },
+    /// Dividend cash ledger: credited dividends, withdrawals and reinvestments
+    Cash(CashArgs),
+    /// Option premium income, e.g. from covered calls, kept apart from dividends
+    Premiums {
+        #[command(subcommand)]
+        command: PremiumCommands,
+    },
+    /// Consolidated statements
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+    /// Export dividend income for other tools, e.g. budgeting apps
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+    /// Longer-term analysis of the portfolio's history
+    Analyze {
+        #[command(subcommand)]
+        command: AnalyzeCommands,
+    },
+    /// Living expenses and how much of them dividend income covers
+    Expenses(ExpensesArgs),
+    /// Serve dividends and holdings as a JSON API; write-scoped tokens can also POST /dividends
+    Serve(ServeArgs),
+    /// Run the commands in a script file as one all-or-nothing change
+    Run(RunArgs),
+    /// Set up a data directory, API key and first holdings
+    Init(InitArgs),
+    /// Check the data directory, configuration, API key, backups and clock
+    Doctor(DoctorArgs),
+}
+
+#[derive(Subcommand)]
+pub enum DivCommands {
+    /// Add a new dividend payment record
+    Add(AddArgs),
+    /// List dividend payments
+    List(ListArgs),
+    /// Import dividend data from CSV file
+    Import {
+        /// Path to CSV file
+        file: String,
+    },
+    /// Export dividend data to CSV file
+    Export {
+        /// Output file path
+        #[arg(short, long, default_value = "dividends.csv")]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HoldingsCommands {
+    /// Import holdings from CSV file
+    Import {
+        /// Path to CSV file with holdings data
+        file: String,
+        /// Account for rows without an account column value
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Rebuild holdings from a broker's lot-level export (symbol, purchase date, shares, price)
+    ImportLots {
+        /// Path to CSV file with one row per purchase lot
+        file: String,
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+        /// Import without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Account of the holdings the lots rebuild, when a symbol is held in several
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Add or update a holding in your portfolio
+    Add(HoldingsAddArgs),
+    /// Buy or sell shares of a holding, recomputing its average cost
+    Update(HoldingsUpdateArgs),
+    /// Show the share and cost-basis adjustments made to a holding
+    History {
+        /// Stock symbol
+        symbol: String,
+        /// Account of the holding, when the symbol is held in several
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Remove a holding from your portfolio
+    Remove {
+        /// Stock symbol to remove
+        symbol: String,
+        /// Keep the position as closed (zero shares) for history instead of deleting it
+        #[arg(long)]
+        archive: bool,
+        /// Date the position was closed; defaults to today
+        #[arg(long, requires = "archive")]
+        date: Option<String>,
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Account of the holding, when the symbol is held in several
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// List all holdings
+    List(HoldingsListArgs),
+    /// Export holdings to CSV file
+    Export {
+        /// Output file path
+        #[arg(short, long, default_value = "holdings.csv")]
+        output: PathBuf,
+    },
+    /// Set a forward dividend rate for a holding without (enough) history
+    SetForwardDividend(HoldingsSetForwardArgs),
+    /// Show portfolio holdings summary
+    Summary {
+        /// Include yield calculations
+        #[arg(long)]
+        include_yield: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaxCommands {
+    /// Generate annual tax summary for a specific year
+    Summary(TaxSummaryArgs),
+    /// Generate 1099-DIV style report
+    Report(TaxReportArgs),
+    /// Calculate estimated taxes on dividend income
+    Estimate(TaxEstimateArgs),
+    /// Show tax lot breakdown (if cost basis tracking enabled)
+    Lots(TaxLotsArgs),
+    /// Update tax classification for dividends
+    Classify(TaxClassifyArgs),
+    /// Review and adjust classifications symbol by symbol for a tax year
+    Reclassify(TaxReclassifyArgs),
+    /// Track reclaims of foreign withholding tax above treaty rates
+    Reclaims(TaxReclaimsArgs),
+    /// Foreign dividend income and withholding by country and symbol
+    Foreign(TaxForeignArgs),
+}
+
+#[derive(Subcommand)]
+pub enum ReclaimCommands {
+    /// Record a reclaim filed for a dividend's excess withholding
+    File(ReclaimFileArgs),
+    /// Record the refund received for a filed reclaim
+    Refund(ReclaimRefundArgs),
+}
+
+#[derive(Subcommand)]
+pub enum CashCommands {
+    /// Start crediting paid dividends to the cash ledger
+    Enable {
+        /// First pay date to credit (defaults to today)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Record dividend cash moved out of an account
+    Withdraw(CashWithdrawArgs),
+    /// Record dividend cash used to buy shares
+    Reinvest(CashReinvestArgs),
+}
+
+#[derive(Subcommand)]
+pub enum TransactionCommands {
+    /// Record a purchase; buying a symbol not yet held starts a holding
+    Buy(TradeArgs),
+    /// Record a sale; selling every share closes the holding
+    Sell(TradeArgs),
+    /// List buys and sells with the shares held after each, and check dividends against them
+    List {
+        /// Only list trades in this symbol
+        symbol: Option<String>,
+    },
+    /// Average yield at purchase of the buys, per position and for the portfolio by year
+    EntryYield {
+        /// Only show buys of this symbol
+        symbol: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PremiumCommands {
+    /// Record premium collected from selling an option
+    Add(PremiumAddArgs),
+    /// List recorded premiums, newest first
+    List {
+        /// Only list premiums for this symbol
+        symbol: Option<String>,
+        /// Only list premiums collected in this year
+        #[arg(short, long)]
+        year: Option<i32>,
+    },
+    /// Dividends and option premiums side by side, per symbol
+    Income {
+        /// Year to show (defaults to all years)
+        #[arg(short, long)]
+        year: Option<i32>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExpenseCommands {
+    /// Set living expenses, e.g. 4500/month or 54000/year
+    Set {
+        /// Amount per month, or per year with a /year suffix
+        amount: String,
+    },
+    /// Remove the expense budget
+    Clear,
+    /// Plan a large expense or withdrawal; 'calendar' checks expected dividends cover it
+    Plan {
+        /// Amount needed
+        amount: String,
+        /// Date the money is needed (YYYY-MM-DD)
+        #[arg(short, long)]
+        date: String,
+        /// What the money is for
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+    /// Remove the expenses planned on a date
+    Unplan {
+        /// Date of the planned expense (YYYY-MM-DD)
+        date: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// One-page income statement for a year: gross to net, tax and run-rate
+    Annual(AnnualReportArgs),
+    /// Set aside a percentage of dividends received, e.g. for tithing or zakat
+    Giving(GivingReportArgs),
+}
+
+#[derive(Subcommand)]
+pub enum AnalyzeCommands {
+    /// Yield on cost of each position year by year, from its purchase lots
+    YocHistory(YocHistoryArgs),
+}
+
+#[derive(Args)]
+pub struct YocHistoryArgs {
+    /// Only show this position
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// One income transaction per month for importing into YNAB or Actual
+    Budget(BudgetExportArgs),
+}
+
+#[derive(Subcommand)]
+pub enum SymbolCommands {
+    /// List, add or remove alternative spellings of a symbol
+    Aliases(SymbolAliasesArgs),
+}
+
+#[derive(Args)]
+pub struct SymbolAliasesArgs {
+    #[command(subcommand)]
+    pub command: Option<AliasCommands>,
+}
+
+#[derive(Subcommand)]
+pub enum AliasCommands {
+    /// Map a spelling to a symbol, moving records stored under the spelling
+    Add {
+        /// Alternative spelling, e.g. BP:LN
+        alias: String,
+        /// Symbol to keep the records under, e.g. BP.L
+        symbol: String,
+    },
+    /// Remove an alias (records already moved stay under the symbol)
+    Remove {
+        /// Alias to remove
+        alias: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ActionCommands {
+    /// Apply a feed of corporate actions to holdings and dividend history
+    Apply {
+        /// CSV or JSON feed file, or an http(s) URL serving one
+        feed: String,
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+        /// Apply without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// List the corporate actions applied so far
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum JournalCommands {
+    /// Add a note to a symbol's journal
+    Add {
+        /// Stock symbol
+        symbol: String,
+        /// The note, e.g. "Raised dividend 4%, payout ratio 15%"
+        text: String,
+    },
+    /// List journal entries, newest first
+    List {
+        /// Only list entries for this symbol
+        symbol: Option<String>,
+    },
+    /// Export journal entries
+    Export {
+        /// Export format
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+        /// Output file path
+        #[arg(short, long, default_value = "journal.csv")]
+        output: PathBuf,
+        /// Only export entries for this symbol
+        #[arg(short, long)]
+        symbol: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DataCommands {
+    /// Export data to different formats
+    Export(DataExportArgs),
+    /// Show data statistics and backup information
+    Stats,
+    /// Backup current data
+    Backup,
+    /// Compare the current data with another data directory or a backup
+    Diff {
+        /// Data directory, backup file or backup name to compare against
+        source: String,
+    },
+    /// Load data from backup
+    Load {
+        /// Backup file to load from
+        file: String,
+        /// Replace the current data without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage stored backups
+    Backups {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+    /// Move dividends paid before a year into yearly archive files
+    Archive {
+        /// First year to keep in the active data, e.g. 2020
+        #[arg(long)]
+        before: i32,
+        /// Archive without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Remove old backups, always keeping the most recent ones
+    Prune(BackupPruneArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SummaryArgs {
+    /// Year to summarize (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Quarter to summarize (format: Q1-2024, Q2-2024, etc.)
+    #[arg(long)]
+    pub quarter: Option<String>,
+    /// Only include these symbols (comma-separated, globs like BR* allowed)
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+    /// How --symbol is compared against each dividend's symbol
+    #[arg(long, value_enum, default_value_t = SymbolMatchArg::Exact)]
+    pub symbol_match: SymbolMatchArg,
+    /// Show top dividend paying stocks
+    #[arg(long)]
+    pub top_payers: Option<usize>,
+    /// Show year-over-year growth analysis
+    #[arg(long)]
+    pub growth: bool,
+    /// With --growth, also show dividend-per-share growth for each symbol
+    #[arg(long)]
+    pub by_symbol: bool,
+    /// Show dividend frequency analysis
+    #[arg(long)]
+    pub frequency: bool,
+    /// Show dividend consistency analysis
+    #[arg(long)]
+    pub consistency: bool,
+    /// With --consistency, explain how a symbol's score is made up
+    #[arg(long, value_name = "SYMBOL")]
+    pub explain: Option<String>,
+    /// Show yield analysis (requires holdings with cost basis)
+    #[arg(long)]
+    pub yield_analysis: bool,
+    /// Export summary to CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+    /// Show monthly breakdown for the year
+    #[arg(long)]
+    pub monthly: bool,
+    /// With --monthly, compare each month against this income goal, e.g. 500
+    #[arg(long, value_name = "AMOUNT", requires = "monthly")]
+    pub goal: Option<Decimal>,
+    /// Show uninvested dividend cash per account (requires 'cash enable')
+    #[arg(long)]
+    pub cash: bool,
+    /// Chart the forward income run-rate over the last two years
+    #[arg(long)]
+    pub run_rate_history: bool,
+    /// Show yearly income in constant dollars, adjusted for inflation
+    #[arg(long)]
+    pub real: bool,
+    /// With --real, CSV of year,cpi rows to use instead of the built-in US CPI table
+    #[arg(long, requires = "real")]
+    pub cpi_file: Option<PathBuf>,
+    /// Show all analytics (equivalent to --growth --frequency --consistency --yield-analysis)
+    #[arg(long)]
+    pub all: bool,
+    /// Combine another data directory into a household view (repeatable, label with NAME=DIR)
+    #[arg(long, value_name = "[NAME=]DIR")]
+    pub merge: Vec<String>,
+    /// Only include dividends and holdings in this account
+    #[arg(long)]
+    pub account: Option<String>,
+    /// Also count dividends moved out by `data archive`
+    #[arg(long)]
+    pub include_archived: bool,
+    /// Place payments in years and months by this date (default: date_basis under [analysis])
+    #[arg(long, value_enum)]
+    pub date_basis: Option<DateBasis>,
+    /// Explain how the year's income differs from `tax summary`, listing the payments only one counts
+    #[arg(long, conflicts_with_all = ["quarter", "merge"])]
+    pub reconcile_tax: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ProjectArgs {
+    #[command(subcommand)]
+    pub command: Option<ProjectCommands>,
+    /// Projection method to use
+    #[arg(long, value_enum, default_value_t = ProjectionMethodArg::Last12Months)]
+    pub method: ProjectionMethodArg,
+    /// Growth scenario (conservative, moderate, optimistic, or custom percentage like 7.5%)
+    #[arg(long, default_value = "moderate", value_parser = parse_growth_scenario)]
+    pub growth_rate: GrowthScenario,
+    /// Target year to project (defaults to next year)
+    #[arg(long)]
+    pub year: Option<i32>,
+    /// Export projections to CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+    /// Export projections to JSON file
+    #[arg(long)]
+    pub export_json: Option<PathBuf>,
+    /// Show detailed monthly breakdown
+    #[arg(long)]
+    pub monthly: bool,
+    /// Use actual dividends for elapsed months of the current year and projections for the rest
+    #[arg(long)]
+    pub blend: bool,
+    /// Save this projection as the plan for its year, replacing an earlier one
+    #[arg(long)]
+    pub save_plan: bool,
+    /// Payment frequency to use instead of the detected one, e.g. TSLA=annual (repeatable)
+    #[arg(long, value_name = "SYMBOL=FREQUENCY", value_parser = parse_frequency_override)]
+    pub frequency_override: Vec<FrequencyOverride>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProjectCommands {
+    /// Compare a saved plan with the dividends actually received, month by month
+    Review {
+        /// Year to review (defaults to the current year)
+        #[arg(long)]
+        year: Option<i32>,
+    },
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct FetchArgs {
+    #[command(subcommand)]
+    pub command: Option<FetchCommands>,
+    /// Stock symbols to fetch (comma-separated for multiple)
+    #[arg(required_unless_present = "portfolio")]
+    pub symbols: Option<String>,
+    /// Start date for dividend history (YYYY-MM-DD)
+    #[arg(long, short = 'f')]
+    pub from: Option<String>,
+    /// End date for dividend history (YYYY-MM-DD)
+    #[arg(long, short = 't')]
+    pub to: Option<String>,
+    /// Specific year to fetch
+    #[arg(long)]
+    pub year: Option<i32>,
+    /// Portfolio CSV file to fetch symbols from
+    #[arg(long)]
+    pub portfolio: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum FetchCommands {
+    /// Compare provider dividend history with the recorded dividends of a symbol
+    Verify {
+        /// Stock symbol
+        symbol: String,
+        /// Correct recorded ex-dates and amounts that differ from the provider
+        #[arg(long)]
+        apply: bool,
+        /// Apply without asking for confirmation
+        #[arg(short, long, requires = "apply")]
+        yes: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct UpdateArgs {
+    /// Update all symbols in the database
+    #[arg(long)]
+    pub all: bool,
+    /// Update specific symbol
+    #[arg(long)]
+    pub symbol: Option<String>,
+    /// Fetch dividends since last update
+    #[arg(long)]
+    pub since_last_fetch: bool,
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ConfigureArgs {
+    #[command(subcommand)]
+    pub command: Option<ConfigureCommands>,
+    /// Set Alpha Vantage API key
+    #[arg(long)]
+    pub api_key: Option<String>,
+    /// Show current configuration
+    #[arg(long)]
+    pub show: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigureCommands {
+    /// Open config.toml in $VISUAL or $EDITOR, keeping the changes only if
+    /// the file is valid
+    Edit,
+    /// Check config.toml for unknown settings and values out of range
+    Validate,
+    /// Manage the tokens `serve` accepts
+    Tokens {
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommands {
+    /// Create a token and print it
+    Add {
+        /// What the token may do: read the API, or also add records
+        #[arg(long, value_enum)]
+        scope: TokenScopeArg,
+        /// Name to manage the token by (defaults to its scope and a number, e.g. read-1)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List tokens by name and scope
+    List,
+    /// Revoke a token
+    Remove {
+        /// Name of the token
+        name: String,
+    },
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct AlertsArgs {
+    #[command(subcommand)]
+    pub command: Option<AlertCommands>,
+    /// Generate new alerts
+    #[arg(long)]
+    pub generate: bool,
+    /// Clear existing alerts
+    #[arg(long)]
+    pub clear: bool,
+    /// Clear without asking for confirmation
+    #[arg(short, long, requires = "clear")]
+    pub yes: bool,
+}
+
+#[derive(Subcommand)]
+pub enum AlertCommands {
+    /// Alert when a symbol's trailing-12-month income crosses a target
+    Threshold(AlertThresholdArgs),
+}
+
+#[derive(Args)]
+pub struct AlertThresholdArgs {
+    /// Stock symbol (omit to list every threshold)
+    pub symbol: Option<String>,
+    /// Trailing-12-month income target, e.g. 500
+    #[arg(long, requires = "symbol")]
+    pub annual: Option<String>,
+    /// Alert when income rises above the target instead of falling below it
+    #[arg(long, requires = "annual")]
+    pub above: bool,
+    /// Remove the symbol's threshold
+    #[arg(long, requires = "symbol", conflicts_with = "annual")]
+    pub remove: bool,
+}
+
+#[derive(Args)]
+pub struct CalendarArgs {
+    /// Fetch/update calendar for portfolio holdings
+    #[arg(long)]
+    pub update: bool,
+    /// Number of days to show (default: 90)
+    #[arg(long, short = 'd')]
+    pub days: Option<i64>,
+    /// Export calendar to ICS file
+    #[arg(long)]
+    pub export: Option<PathBuf>,
+    /// Browse month by month: ←/→ change month, ↑/↓ step through days with dividends, Enter lists a day
+    #[arg(short, long, conflicts_with = "export")]
+    pub interactive: bool,
+    /// Payment frequency to estimate with instead of the detected one, e.g. TSLA=annual (repeatable)
+    #[arg(long, value_name = "SYMBOL=FREQUENCY", value_parser = parse_frequency_override, requires = "update")]
+    pub frequency_override: Vec<FrequencyOverride>,
+}
+
+#[derive(Args)]
+pub struct NewsArgs {
+    /// Stock symbol
+    pub symbol: String,
+    /// How many days back to look for announcements and headlines
+    #[arg(short, long, default_value_t = 90)]
+    pub days: i64,
+    /// Add upcoming announced dividends to the calendar without asking
+    #[arg(long)]
+    pub add_to_calendar: bool,
+}
+
+#[derive(Args)]
+pub struct AddArgs {
+    /// Stock symbol (e.g., AAPL, MSFT), or the ISIN/CUSIP of a holding
+    pub symbol: String,
+    /// Ex-dividend date (YYYY-MM-DD, 'tomorrow', 'next friday', etc.)
+    #[arg(long)]
+    pub ex_date: String,
+    /// Payment date (YYYY-MM-DD, 'tomorrow', 'next friday', etc.)
+    #[arg(long)]
+    pub pay_date: String,
+    /// Dividend amount per share
+    #[arg(short, long)]
+    pub amount: String,
+    /// Number of shares owned
+    #[arg(short, long)]
+    pub shares: String,
+    /// Fees deducted from the payment in total, e.g. ADR custody fees
+    #[arg(long)]
+    pub fee: Option<String>,
+    /// Tax withheld at source, in total or as a percentage of the payment (e.g. 35%)
+    #[arg(long)]
+    pub withholding: Option<String>,
+    /// Country that withheld the tax (two-letter code, e.g. CH)
+    #[arg(long)]
+    pub country: Option<String>,
+    /// ISIN of the security (defaults to the holding's)
+    #[arg(long)]
+    pub isin: Option<String>,
+    /// CUSIP of the security (defaults to the holding's)
+    #[arg(long)]
+    pub cusip: Option<String>,
+    /// Account the payment was credited to (for the cash ledger)
+    #[arg(long)]
+    pub account: Option<String>,
+    /// Kind of income: interest (bond funds, money market) is kept off 1099-DIV
+    #[arg(long, value_enum, default_value_t = IncomeKindArg::Dividend)]
+    pub kind: IncomeKindArg,
+    /// Tax classification; without it REITs, municipal funds and foreign listings are classified automatically
+    #[arg(long, value_enum)]
+    pub classification: Option<TaxClassificationArg>,
+    /// Force adding even if duplicate (same symbol + ex-date) exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// Filter by stock symbols (comma-separated, globs like BR* allowed)
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+    /// How --symbol is compared against each dividend's symbol
+    #[arg(long, value_enum, default_value_t = SymbolMatchArg::Exact)]
+    pub symbol_match: SymbolMatchArg,
+    /// Show payments from specific year
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Filter by specific month (1-12)
+    #[arg(short, long)]
+    pub month: Option<u32>,
+    /// Filter by quarter (e.g., Q1-2024)
+    #[arg(long)]
+    pub quarter: Option<String>,
+    /// Show only the trailing twelve months
+    #[arg(long, conflicts_with_all = ["year", "month", "quarter"])]
+    pub ttm: bool,
+    /// Filter by date range (start date YYYY-MM-DD)
+    #[arg(long)]
+    pub date_start: Option<String>,
+    /// Filter by date range (end date YYYY-MM-DD)
+    #[arg(long)]
+    pub date_end: Option<String>,
+    /// Minimum dividend amount per share
+    #[arg(long)]
+    pub amount_min: Option<String>,
+    /// Maximum dividend amount per share
+    #[arg(long)]
+    pub amount_max: Option<String>,
+    /// Minimum total payment
+    #[arg(long)]
+    pub total_min: Option<String>,
+    /// Maximum total payment
+    #[arg(long)]
+    pub total_max: Option<String>,
+    /// Minimum number of shares owned on the ex-date
+    #[arg(long)]
+    pub shares_min: Option<String>,
+    /// Only show payments credited to this account
+    #[arg(long)]
+    pub account: Option<String>,
+    /// Also list dividends moved out by `data archive`
+    #[arg(long)]
+    pub include_archived: bool,
+    /// Show only upcoming pay dates (future)
+    #[arg(long)]
+    pub upcoming: bool,
+    /// Sort keys, comma-separated with optional direction (e.g. symbol,total:desc)
+    #[arg(long, default_value = "ex-date", value_parser = parse_sort_spec::<ListSortField>)]
+    pub sort_by: SortSpec<ListSortField>,
+    /// Reverse the direction of every sort key
+    #[arg(long)]
+    pub reverse: bool,
+    /// Export the listed rows, in the order shown, to a CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct HoldingsAddArgs {
+    /// Stock symbol (e.g., AAPL, MSFT)
+    pub symbol: String,
+    /// Number of shares owned
+    #[arg(short, long)]
+    pub shares: String,
+    /// Average cost basis per share
+    #[arg(short = 'c', long)]
+    pub cost_basis: Option<String>,
+    /// Current dividend yield percentage
+    #[arg(short = 'y', long)]
+    pub yield_pct: Option<String>,
+    /// ISIN of the security, e.g. US0378331005
+    #[arg(long)]
+    pub isin: Option<String>,
+    /// CUSIP of the security, e.g. 037833100
+    #[arg(long)]
+    pub cusip: Option<String>,
+    /// Account the shares are held in, e.g. IRA; without it, the symbol's only holding is updated
+    #[arg(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Args)]
+pub struct HoldingsSetForwardArgs {
+    /// Stock symbol of an existing holding
+    pub symbol: String,
+    /// Expected dividend per share over the next 12 months
+    #[arg(required_unless_present = "clear")]
+    pub amount: Option<String>,
+    /// How often the dividend is paid
+    #[arg(long, value_enum, default_value_t = FrequencyArg::Quarterly)]
+    pub frequency: FrequencyArg,
+    /// Remove the forward dividend and go back to payment history
+    #[arg(long, conflicts_with = "amount")]
+    pub clear: bool,
+    /// Account of the holding, when the symbol is held in several
+    #[arg(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Args)]
+pub struct HoldingsUpdateArgs {
+    /// Stock symbol to update
+    pub symbol: String,
+    /// Shares bought (+25) or sold (-10)
+    #[arg(short, long, allow_hyphen_values = true, required_unless_present = "cost_basis")]
+    pub shares: Option<String>,
+    /// Price per share of the purchase, or the corrected average cost without --shares
+    #[arg(short = 'c', long)]
+    pub cost_basis: Option<String>,
+    /// Date of the trade (YYYY-MM-DD, today, yesterday); defaults to today
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Account of the holding, when the symbol is held in several
+    #[arg(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Args)]
+pub struct HoldingsListArgs {
+    /// Sort keys, comma-separated with optional direction (e.g. yield:desc,symbol)
+    #[arg(long, default_value = "symbol", value_parser = parse_sort_spec::<HoldingsSortField>)]
+    pub sort_by: SortSpec<HoldingsSortField>,
+    /// Reverse the direction of every sort key
+    #[arg(long)]
+    pub desc: bool,
+    /// Also list archived (closed) positions
+    #[arg(long)]
+    pub include_archived: bool,
+}
+
+#[derive(Args)]
+pub struct TaxSummaryArgs {
+    /// Tax year to analyze (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Include estimated tax calculations
+    #[arg(long)]
+    pub estimate: bool,
+    /// Filing status for tax estimates
+    #[arg(long, value_enum, default_value_t = FilingStatusArg::Single)]
+    pub filing_status: FilingStatusArg,
+    /// Income bracket for tax estimates
+    #[arg(long, value_enum, default_value_t = IncomeBracketArg::Medium)]
+    pub income_bracket: IncomeBracketArg,
+    /// Also count dividends moved out by `data archive`
+    #[arg(long)]
+    pub include_archived: bool,
+    /// Export summary to CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct TaxReportArgs {
+    /// Tax year for the report (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Export report to CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+    /// Export report to JSON file
+    #[arg(long)]
+    pub export_json: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct TaxEstimateArgs {
+    /// Tax year to analyze (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Filing status
+    #[arg(short, long, value_enum, default_value_t = FilingStatusArg::Single)]
+    pub filing_status: FilingStatusArg,
+    /// Income bracket
+    #[arg(short, long, value_enum, default_value_t = IncomeBracketArg::Medium)]
+    pub income_bracket: IncomeBracketArg,
+}
+
+#[derive(Args)]
+pub struct AnnualReportArgs {
+    /// Year to report on (defaults to the current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Filing status used for the tax estimate
+    #[arg(short, long, value_enum, default_value_t = FilingStatusArg::Single)]
+    pub filing_status: FilingStatusArg,
+    /// Income bracket used for the tax estimate
+    #[arg(short, long, value_enum, default_value_t = IncomeBracketArg::Medium)]
+    pub income_bracket: IncomeBracketArg,
+}
+
+#[derive(Args)]
+pub struct GivingReportArgs {
+    /// Percentage of income to give (e.g. 10 for a tithe, 2.5 for zakat)
+    #[arg(long)]
+    pub pct: String,
+    /// Year to report on (defaults to the current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Split the year by month or by quarter
+    #[arg(long, value_enum, default_value_t = GivingPeriodArg::Month)]
+    pub by: GivingPeriodArg,
+    /// Take the percentage of gross dividends or of the cash received after withholding and fees
+    #[arg(long, value_enum, default_value_t = GivingBasisArg::Net)]
+    pub basis: GivingBasisArg,
+    /// Export the schedule to a CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct TaxLotsArgs {
+    /// Tax year to analyze (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Filter by stock symbol
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+    /// How --symbol is compared against each lot's symbol
+    #[arg(long, value_enum, default_value_t = SymbolMatchArg::Exact)]
+    pub symbol_match: SymbolMatchArg,
+    /// Export to CSV file
+    #[arg(long)]
+    pub export_csv: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct TaxClassifyArgs {
+    /// Stock symbol to update
+    pub symbol: String,
+    /// Tax classification
+    #[arg(short, long, value_enum, required_unless_present = "country")]
+    pub classification: Option<TaxClassificationArg>,
+    /// Country the dividends are paid from (two-letter code, e.g. CH)
+    #[arg(long)]
+    pub country: Option<String>,
+    /// Year to update (optional, updates all if not specified)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Apply to all future dividends from this symbol
+    #[arg(long)]
+    pub apply_future: bool,
+}
+
+#[derive(Args)]
+pub struct TaxForeignArgs {
+    /// Tax year to report on (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+}
+
+#[derive(Args)]
+pub struct TaxReclassifyArgs {
+    /// Tax year to review (defaults to current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Prompt for each symbol and apply the changes in one save
+    #[arg(long)]
+    pub interactive: bool,
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct TaxReclaimsArgs {
+    #[command(subcommand)]
+    pub command: Option<ReclaimCommands>,
+    /// Only show reclaims filed with this country
+    #[arg(long)]
+    pub country: Option<String>,
+    /// Only show reclaims still waiting for a refund
+    #[arg(long)]
+    pub outstanding: bool,
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct CashArgs {
+    #[command(subcommand)]
+    pub command: Option<CashCommands>,
+    /// Only show entries for this account
+    #[arg(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ExpensesArgs {
+    #[command(subcommand)]
+    pub command: Option<ExpenseCommands>,
+    /// Yearly dividend growth assumed for the crossover (conservative, moderate, optimistic, or a percentage like 7.5%)
+    #[arg(long, default_value = "moderate", value_parser = parse_growth_scenario)]
+    pub growth_rate: GrowthScenario,
+    /// Yearly growth in expenses assumed for the crossover, e.g. 3%
+    #[arg(long, default_value = "3%", value_parser = parse_percentage)]
+    pub inflation: Decimal,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub bind: String,
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    pub port: u16,
+    /// Serve only a redacted income summary behind a share link, hiding holdings and share counts
+    #[arg(long)]
+    pub share: bool,
+    /// Token for the share link, to keep the same link across restarts (random by default)
+    #[arg(long, requires = "share")]
+    pub share_token: Option<String>,
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Alpha Vantage API key to save
+    #[arg(long)]
+    pub api_key: Option<String>,
+    /// Holdings CSV to import (symbol,shares[,cost_basis,current_yield])
+    #[arg(long)]
+    pub import_holdings: Option<PathBuf>,
+    /// Seed a sample portfolio to explore the commands with
+    #[arg(long)]
+    pub demo: bool,
+    /// Ask nothing; only use the options given
+    #[arg(long)]
+    pub no_input: bool,
+}
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Also send one request to Alpha Vantage to test the API key
+    #[arg(long)]
+    pub ping: bool,
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Script with one command per line, e.g. `div add KO --ex-date ...`; `#` starts a comment
+    pub script: PathBuf,
+    /// Run every command but save nothing
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct CashWithdrawArgs {
+    /// Amount withdrawn
+    pub amount: String,
+    /// Account the cash left (defaults to the default account)
+    #[arg(long)]
+    pub account: Option<String>,
+    /// Date of the withdrawal (defaults to today)
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Free-form note, e.g. where the cash went
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TradeArgs {
+    /// Stock symbol
+    pub symbol: String,
+    /// Number of shares
+    #[arg(short, long)]
+    pub shares: String,
+    /// Price per share
+    #[arg(short, long)]
+    pub price: String,
+    /// Date of the trade (defaults to today); may be earlier than trades already recorded
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Account the trade is made in; without it, the symbol's only holding
+    #[arg(long)]
+    pub account: Option<String>,
+}
+
+#[derive(Args)]
+pub struct PremiumAddArgs {
+    /// Underlying stock symbol
+    pub symbol: String,
+    /// Net premium received, after commissions
+    pub amount: String,
+    /// Date the option was sold (defaults to today)
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Number of contracts sold
+    #[arg(long)]
+    pub contracts: Option<u32>,
+    /// Free-form note, e.g. "Jun 190 call"
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CashReinvestArgs {
+    /// Amount reinvested
+    pub amount: String,
+    /// Symbol bought with the cash
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+    /// Account the cash was reinvested in (defaults to the default account)
+    #[arg(long)]
+    pub account: Option<String>,
+    /// Date of the purchase (defaults to today)
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Free-form note
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ReclaimFileArgs {
+    /// Stock symbol of the dividend
+    pub symbol: String,
+    /// Ex-dividend date of the dividend the tax was withheld from
+    #[arg(short, long)]
+    pub ex_date: String,
+    /// Country the reclaim is filed with (e.g. CH, DE)
+    #[arg(long)]
+    pub country: String,
+    /// Tax withheld from the payment (recorded on the dividend)
+    #[arg(long)]
+    pub withheld: Option<String>,
+    /// Treaty withholding rate in percent; the excess above it is reclaimed
+    #[arg(long, required_unless_present = "amount")]
+    pub treaty_rate: Option<String>,
+    /// Amount reclaimed, when it differs from the treaty calculation
+    #[arg(short, long, conflicts_with = "treaty_rate")]
+    pub amount: Option<String>,
+    /// Date the reclaim was filed (defaults to today)
+    #[arg(short, long)]
+    pub date: Option<String>,
+    /// Reference number from the tax authority or custodian
+    #[arg(long)]
+    pub reference: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ReclaimRefundArgs {
+    /// Stock symbol of the dividend
+    pub symbol: String,
+    /// Ex-dividend date of the dividend the reclaim was filed for
+    #[arg(short, long)]
+    pub ex_date: String,
+    /// Amount refunded
+    #[arg(short, long)]
+    pub amount: String,
+    /// Country of the reclaim, needed when several were filed for the dividend
+    #[arg(long)]
+    pub country: Option<String>,
+    /// Date the refund arrived (defaults to today)
+    #[arg(short, long)]
+    pub date: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DataExportArgs {
+    /// Export format
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+    /// Output file path
+    #[arg(short, long, default_value = "dividend_export")]
+    pub output: PathBuf,
+    /// Data to export
+    #[arg(short, long, value_enum, default_value_t = ExportDataType::All)]
+    pub data_type: ExportDataType,
+    /// Only export records for these symbols (comma-separated, globs like BR* allowed)
+    #[arg(short, long)]
+    pub symbol: Option<String>,
+    /// How --symbol is compared against each record's symbol
+    #[arg(long, value_enum, default_value_t = SymbolMatchArg::Exact)]
+    pub symbol_match: SymbolMatchArg,
+    /// Only export dividends with an ex-date in this year
+    #[arg(short, long)]
+    pub year: Option<i32>,
+}
+
+#[derive(Args)]
+pub struct BudgetExportArgs {
+    /// File layout: YNAB's Inflow/Outflow columns, or a signed Amount column as Actual imports
+    #[arg(short, long, value_enum, default_value_t = BudgetFormatArg::Csv)]
+    pub format: BudgetFormatArg,
+    /// Output file path
+    #[arg(short, long, default_value = "dividend_income.csv")]
+    pub output: PathBuf,
+    /// Year to export (defaults to the current year)
+    #[arg(short, long)]
+    pub year: Option<i32>,
+    /// Payee of each transaction
+    #[arg(long, default_value = "Dividend Income")]
+    pub payee: String,
+    /// Budget category of each transaction (csv format only)
+    #[arg(long, default_value = "Income")]
+    pub category: String,
+}
+
+#[derive(Args)]
+pub struct BackupPruneArgs {
+    /// Number of most recent backups to keep per data file
+    #[arg(long, default_value_t = 10)]
+    pub keep: usize,
+    /// Only remove backups older than this age (e.g. 90d, 12w)
+    #[arg(long)]
+    pub older_than: Option<String>,
+}
+
+/// Field used to sort the dividend list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListSortField {
+    Symbol,
+    ExDate,
+    PayDate,
+    Amount,
+    Total,
+}
+
+impl fmt::Display for ListSortField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value_name(self, f)
+    }
+}
+
+/// Field used to sort the holdings list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HoldingsSortField {
+    Symbol,
+    Shares,
+    Yield,
+    Value,
+}
+
+/// Projection method accepted by `project --method`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProjectionMethodArg {
+    /// Use the last 12 months of payments
+    #[value(name = "last-12-months")]
+    Last12Months,
+    /// Average the last 2 years of payments
+    #[value(name = "average-2-years")]
+    Average2Years,
+    /// Average the last 3 years of payments
+    #[value(name = "average-3-years")]
+    Average3Years,
+    /// Use current indicated yields
+    CurrentYield,
+    /// Average 2 years for regular payers, trailing 12 months for irregular ones
+    Adaptive,
+}
+
+impl From<ProjectionMethodArg> for ProjectionMethod {
+    fn from(method: ProjectionMethodArg) -> Self {
+        match method {
+            ProjectionMethodArg::Last12Months => ProjectionMethod::Last12Months,
+            ProjectionMethodArg::Average2Years => ProjectionMethod::AverageYears(2),
+            ProjectionMethodArg::Average3Years => ProjectionMethod::AverageYears(3),
+            ProjectionMethodArg::CurrentYield => ProjectionMethod::CurrentYield,
+            ProjectionMethodArg::Adaptive => ProjectionMethod::Adaptive,
+        }
+    }
+}
+
+/// Parse a growth scenario name or a custom percentage like `7.5%`
+pub fn parse_growth_scenario(value: &str) -> Result<GrowthScenario> {
+    match value.to_lowercase().as_str() {
+        "conservative" => Ok(GrowthScenario::Conservative),
+        "moderate" => Ok(GrowthScenario::Moderate),
+        "optimistic" => Ok(GrowthScenario::Optimistic),
+        custom if custom.ends_with('%') => {
+            let rate: Decimal = custom
+                .trim_end_matches('%')
+                .parse()
+                .map_err(|_| anyhow!("Invalid custom growth rate: {}", value))?;
+            Ok(GrowthScenario::Custom(rate / Decimal::from(100)))
+        }
+        _ => Err(anyhow!(
+            "Invalid growth rate: {}. Use: conservative, moderate, optimistic, or a percentage like '7.5%'",
+            value
+        )),
+    }
+}
+
+/// Parse a rate such as `3%` or `3` into a fraction (0.03)
+pub fn parse_percentage(value: &str) -> Result<Decimal> {
+    let rate: Decimal = value
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| anyhow!("Invalid percentage: {}", value))?;
+    Ok(rate / Decimal::from(100))
+}
+
+/// Parse `SYMBOL=FREQUENCY` for `--frequency-override`
+pub fn parse_frequency_override(value: &str) -> Result<FrequencyOverride> {
+    let (symbol, frequency) = value
+        .split_once('=')
+        .filter(|(symbol, _)| !symbol.trim().is_empty())
+        .ok_or_else(|| anyhow!("Invalid frequency override: {}. Use SYMBOL=FREQUENCY, e.g. TSLA=annual", value))?;
+    let frequency: FrequencyArg = parse_value(frequency, "frequency")?;
+    Ok(FrequencyOverride {
+        symbol: normalize_symbol(symbol),
+        frequency: frequency.into(),
+    })
+}
+
+/// One key of a multi-key sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey<F> {
+    pub field: F,
+    pub descending: bool,
+}
+
+/// Ordered sort keys parsed from `--sort-by symbol:asc,total:desc`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortSpec<F>(pub Vec<SortKey<F>>);
+
+impl<F: Copy> SortSpec<F> {
+    /// Sort ascending by a single field
+    pub fn by(field: F) -> Self {
+        SortSpec(vec![SortKey {
+            field,
+            descending: false,
+        }])
+    }
+
+    /// The same keys with every direction flipped
+    pub fn reversed(&self) -> Self {
+        SortSpec(
+            self.0
+                .iter()
+                .map(|key| SortKey {
+                    field: key.field,
+                    descending: !key.descending,
+                })
+                .collect(),
+        )
+    }
+
+    /// Compare two items key by key, falling through to the next key on ties
+    pub fn compare<T>(&self, a: &T, b: &T, by_field: impl Fn(F, &T, &T) -> Ordering) -> Ordering {
+        self.0.iter().fold(Ordering::Equal, |ordering, key| {
+            ordering.then_with(|| {
+                let ordering = by_field(key.field, a, b);
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+        })
+    }
+}
+
+impl<F: ValueEnum> fmt::Display for SortSpec<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write_value_name(&key.field, f)?;
+            f.write_str(if key.descending { " (descending)" } else { " (ascending)" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse comma-separated sort keys, each optionally suffixed with `:asc` or `:desc`
+pub fn parse_sort_spec<F: ValueEnum>(value: &str) -> Result<SortSpec<F>> {
+    let mut keys = Vec::new();
+
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, direction) = part.split_once(':').unwrap_or((part, "asc"));
+        let field = parse_value::<F>(name, "sort field")?;
+        let descending = match direction.trim().to_lowercase().as_str() {
+            "asc" => false,
+            "desc" => true,
+            other => return Err(anyhow!("Invalid sort direction: {}. Use asc or desc", other)),
+        };
+        keys.push(SortKey { field, descending });
+    }
+
+    if keys.is_empty() {
+        return Err(anyhow!("No sort fields given"));
+    }
+    Ok(SortSpec(keys))
+}
+
+/// Output format for `data export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// File layout written by `export budget`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BudgetFormatArg {
+    Ynab,
+    Csv,
+}
+
+/// Which data `data export` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportDataType {
+    Dividends,
+    Holdings,
+    All,
+}
+
+/// Symbol comparison accepted by `--symbol-match`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SymbolMatchArg {
+    Exact,
+    Prefix,
+    Contains,
+}
+
+impl From<SymbolMatchArg> for SymbolMatch {
+    fn from(mode: SymbolMatchArg) -> Self {
+        match mode {
+            SymbolMatchArg::Exact => SymbolMatch::Exact,
+            SymbolMatchArg::Prefix => SymbolMatch::Prefix,
+            SymbolMatchArg::Contains => SymbolMatch::Contains,
+        }
+    }
+}
+
+impl fmt::Display for SymbolMatchArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value_name(self, f)
+    }
+}
+
+/// Period `report giving` splits the year into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GivingPeriodArg {
+    Month,
+    Quarter,
+}
+
+impl fmt::Display for GivingPeriodArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value_name(self, f)
+    }
+}
+
+/// Income `report giving` takes its percentage of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GivingBasisArg {
+    /// Dividends before withholding and fees
+    Gross,
+    /// Cash received after withholding and fees
+    Net,
+}
+
+impl fmt::Display for GivingBasisArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value_name(self, f)
+    }
+}
+
+/// Kind of income accepted by `div add --kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IncomeKindArg {
+    Dividend,
+    Interest,
+    Distribution,
+}
+
+impl From<IncomeKindArg> for IncomeKind {
+    fn from(kind: IncomeKindArg) -> Self {
+        match kind {
+            IncomeKindArg::Dividend => IncomeKind::Dividend,
+            IncomeKindArg::Interest => IncomeKind::Interest,
+            IncomeKindArg::Distribution => IncomeKind::Distribution,
+        }
+    }
+}
+
+/// Payment frequency accepted by `holdings set-forward-dividend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FrequencyArg {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+}
+
+impl From<FrequencyArg> for DividendFrequency {
+    fn from(frequency: FrequencyArg) -> Self {
+        match frequency {
+            FrequencyArg::Monthly => DividendFrequency::Monthly,
+            FrequencyArg::Quarterly => DividendFrequency::Quarterly,
+            FrequencyArg::SemiAnnual => DividendFrequency::SemiAnnual,
+            FrequencyArg::Annual => DividendFrequency::Annual,
+        }
+    }
+}
+
+/// Scope of an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TokenScopeArg {
+    Read,
+    #[value(alias = "read-write")]
+    Write,
+}
+
+impl From<TokenScopeArg> for TokenScope {
+    fn from(scope: TokenScopeArg) -> Self {
+        match scope {
+            TokenScopeArg::Read => TokenScope::Read,
+            TokenScopeArg::Write => TokenScope::Write,
+        }
+    }
+}
+
+/// Filing status for tax estimates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FilingStatusArg {
+    Single,
+    #[value(aliases = ["mfj", "marriedfilingjointly"])]
+    MarriedJointly,
+    #[value(aliases = ["mfs", "marriedfilingseparately"])]
+    MarriedSeparately,
+    #[value(aliases = ["hoh", "headofhousehold"])]
+    HeadOfHousehold,
+}
+
+impl From<FilingStatusArg> for FilingStatus {
+    fn from(status: FilingStatusArg) -> Self {
+        match status {
+            FilingStatusArg::Single => FilingStatus::Single,
+            FilingStatusArg::MarriedJointly => FilingStatus::MarriedFilingJointly,
+            FilingStatusArg::MarriedSeparately => FilingStatus::MarriedFilingSeparately,
+            FilingStatusArg::HeadOfHousehold => FilingStatus::HeadOfHousehold,
+        }
+    }
+}
+
+/// Income bracket for tax estimates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IncomeBracketArg {
+    Low,
+    #[value(alias = "med")]
+    Medium,
+    High,
+    #[value(aliases = ["vh", "veryhigh"])]
+    VeryHigh,
+}
+
+impl From<IncomeBracketArg> for IncomeBracket {
+    fn from(bracket: IncomeBracketArg) -> Self {
+        match bracket {
+            IncomeBracketArg::Low => IncomeBracket::Low,
+            IncomeBracketArg::Medium => IncomeBracket::Medium,
+            IncomeBracketArg::High => IncomeBracket::High,
+            IncomeBracketArg::VeryHigh => IncomeBracket::VeryHigh,
+        }
+    }
+}
+
+/// Tax classification accepted by `tax classify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TaxClassificationArg {
+    Qualified,
+    #[value(alias = "nonqualified")]
+    NonQualified,
+    #[value(alias = "roc")]
+    ReturnOfCapital,
+    #[value(alias = "taxfree")]
+    TaxFree,
+    Foreign,
+    Unknown,
+}
+
+impl From<TaxClassificationArg> for TaxClassification {
+    fn from(classification: TaxClassificationArg) -> Self {
+        match classification {
+            TaxClassificationArg::Qualified => TaxClassification::Qualified,
+            TaxClassificationArg::NonQualified => TaxClassification::NonQualified,
+            TaxClassificationArg::ReturnOfCapital => TaxClassification::ReturnOfCapital,
+            TaxClassificationArg::TaxFree => TaxClassification::TaxFree,
+            TaxClassificationArg::Foreign => TaxClassification::Foreign,
+            TaxClassificationArg::Unknown => TaxClassification::Unknown,
+        }
+    }
+}
+
+/// A deprecated command path and where it lives now
+#[derive(Debug, PartialEq, Eq)]
+pub struct Route {
+    /// Top-level command accepted for backward compatibility
+    pub legacy: &'static str,
+    /// Current command path it is routed to
+    pub replacement: &'static [&'static str],
+    /// Subcommands of a current command of the same name, left for it to handle
+    pub except: &'static [&'static str],
+}
+
+/// Routing table for legacy top-level commands
+pub const LEGACY_ROUTES: &[Route] = &[
+    Route {
+        legacy: "add",
+        replacement: &["div", "add"],
+        except: &[],
+    },
+    Route {
+        legacy: "list",
+        replacement: &["div", "list"],
+        except: &[],
+    },
+    Route {
+        legacy: "import",
+        replacement: &["div", "import"],
+        except: &[],
+    },
+    Route {
+        legacy: "export",
+        replacement: &["div", "export"],
+        except: &["budget"],
+    },
+];
+
+/// Global options that consume the following argument as their value
+const GLOBAL_OPTIONS_WITH_VALUES: &[&str] = &["--data-dir"];
+
+/// Rewrite a legacy command in `args` to its current path
+///
+/// Returns the (possibly rewritten) arguments and the route that was
+/// applied, so the caller can print a deprecation warning.
+pub fn route_legacy_args(mut args: Vec<String>) -> (Vec<String>, Option<&'static Route>) {
+    let mut index = 1;
+    while index < args.len() {
+        let arg = args[index].as_str();
+        if GLOBAL_OPTIONS_WITH_VALUES.contains(&arg) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            break;
+        }
+    }
+
+    if index >= args.len() {
+        return (args, None);
+    }
+
+    let next = args.get(index + 1).map(String::as_str).unwrap_or_default();
+    let route = LEGACY_ROUTES
+        .iter()
+        .find(|route| route.legacy == args[index] && !route.except.contains(&next));
+
+    if let Some(route) = route {
+        args.splice(
+            index..=index,
+            route.replacement.iter().map(|part| part.to_string()),
+        );
+    }
+
+    (args, route)
+}
+
+/// Parse a value enum from free text, e.g. interactive prompts
+pub fn parse_value<T: ValueEnum>(value: &str, what: &str) -> Result<T> {
+    T::from_str(value.trim(), true).map_err(|_| {
+        let valid: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        anyhow!("Invalid {}. Use: {}", what, valid.join(", "))
+    })
+}
+
+/// Write the CLI name of a value enum
+fn write_value_name<T: ValueEnum>(value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value.to_possible_value() {
+        Some(v) => f.write_str(v.get_name()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_sort_spec() {
+        let spec = parse_sort_spec::<ListSortField>("symbol, total:DESC").unwrap();
+        assert_eq!(
+            spec.0,
+            vec![
+                SortKey { field: ListSortField::Symbol, descending: false },
+                SortKey { field: ListSortField::Total, descending: true },
+            ]
+        );
+        assert_eq!(spec.to_string(), "symbol (ascending), total (descending)");
+        assert!(spec.reversed().0[0].descending);
+
+        assert!(parse_sort_spec::<ListSortField>("price").is_err());
+        assert!(parse_sort_spec::<ListSortField>("symbol:up").is_err());
+        assert!(parse_sort_spec::<ListSortField>(" , ").is_err());
+    }
+
+    #[test]
+    fn test_sort_spec_compares_keys_in_order() {
+        let spec = SortSpec(vec![
+            SortKey { field: 0, descending: false },
+            SortKey { field: 1, descending: true },
+        ]);
+        let mut rows = vec![(1, 5), (0, 2), (1, 9), (0, 7)];
+        rows.sort_by(|a, b| spec.compare(a, b, |field, a, b| if field == 0 { a.0.cmp(&b.0) } else { a.1.cmp(&b.1) }));
+        assert_eq!(rows, vec![(0, 7), (0, 2), (1, 9), (1, 5)]);
+    }
+
+    #[test]
+    fn test_value_names_match_documented_options() {
+        assert_eq!(ListSortField::ExDate.to_string(), "ex-date");
+        assert_eq!(
+            ProjectionMethodArg::from_str("average-2-years", false).unwrap(),
+            ProjectionMethodArg::Average2Years
+        );
+        assert_eq!(
+            FilingStatusArg::from_str("mfj", false).unwrap(),
+            FilingStatusArg::MarriedJointly
+        );
+    }
+
+    fn args(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| part.to_string()).collect()
+    }
+
+    #[test]
+    fn test_route_legacy_args() {
+        let (routed, route) =
+            route_legacy_args(args(&["dt", "--data-dir", "list", "add", "AAPL"]));
+        assert_eq!(routed, args(&["dt", "--data-dir", "list", "div", "add", "AAPL"]));
+        assert_eq!(route.unwrap().legacy, "add");
+
+        let (routed, route) = route_legacy_args(args(&["dt", "-v", "div", "list"]));
+        assert_eq!(routed, args(&["dt", "-v", "div", "list"]));
+        assert!(route.is_none());
+
+        let (_, route) = route_legacy_args(args(&["dt", "--help"]));
+        assert!(route.is_none());
+
+        let (routed, route) = route_legacy_args(args(&["dt", "export", "-o", "out"]));
+        assert_eq!(routed, args(&["dt", "div", "export", "-o", "out"]));
+        assert_eq!(route.unwrap().legacy, "export");
+        let (routed, route) = route_legacy_args(args(&["dt", "export", "budget"]));
+        assert_eq!(routed, args(&["dt", "export", "budget"]));
+        assert!(route.is_none());
+    }
+
+    #[test]
+    fn test_parse_growth_scenario() {
+        assert_eq!(
+            parse_growth_scenario("moderate").unwrap(),
+            GrowthScenario::Moderate
+        );
+        assert_eq!(
+            parse_growth_scenario("7.5%").unwrap(),
+            GrowthScenario::Custom(dec!(0.075))
+        );
+        assert!(parse_growth_scenario("fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_frequency_override() {
+        let parsed = parse_frequency_override("tsla=Annual").unwrap();
+        assert_eq!(parsed.symbol, "TSLA");
+        assert_eq!(parsed.frequency, DividendFrequency::Annual);
+        assert!(parse_frequency_override("TSLA").is_err());
+        assert!(parse_frequency_override("=annual").is_err());
+        assert!(parse_frequency_override("TSLA=weekly").is_err());
+    }
+
+    #[test]
+    fn test_parse_value_lists_valid_choices() {
+        let err = parse_value::<TaxClassificationArg>("bogus", "classification").unwrap_err();
+        assert!(err.to_string().contains("non-qualified"));
+        assert_eq!(
+            parse_value::<TaxClassificationArg>("roc", "classification").unwrap(),
+            TaxClassificationArg::ReturnOfCapital
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Corporate action commands (`actions apply`, `actions list`)
+
+use anyhow::Result;
+use colored::*;
+
+use super::CliConfig;
+use crate::cli::ActionCommands;
+use crate::corporate_actions::{self, CorporateAction, Outcome};
+use crate::diff::DatasetDiff;
+use crate::render::{Report, Section, TableView};
+
+const TITLE: &str = "Corporate Actions";
+
+/// Handle corporate action commands
+pub fn run(config: &CliConfig, command: ActionCommands) -> Result<()> {
+    match command {
+        ActionCommands::Apply { feed, dry_run, yes } => apply(config, &feed, dry_run, yes),
+        ActionCommands::List => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            if tracker.corporate_actions.is_empty() {
+                return config.render(&Report::warning(
+                    TITLE,
+                    "No corporate actions applied. Apply a feed with 'actions apply feed.csv'.",
+                ));
+            }
+            let mut table = TableView::new(&["Date", "Action"]);
+            for action in &tracker.corporate_actions {
+                table.row(vec![action.date().format("%Y-%m-%d").to_string().into(), action.to_string().into()]);
+            }
+            config.render(&Report::new(TITLE).section(Section::untitled().table(table)))
+        }
+    }
+}
+
+fn apply(config: &CliConfig, feed: &str, dry_run: bool, yes: bool) -> Result<()> {
+    let mut actions = corporate_actions::load_feed(feed)?;
+    // Earlier actions first, so a split before a rename applies under the old symbol
+    actions.sort_by_key(CorporateAction::date);
+
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+    let mut preview = tracker.clone();
+    let outcomes = apply_all(&mut preview, &actions)?;
+
+    let applied = outcomes.iter().filter(|o| **o == Outcome::Applied).count();
+    if !config.quiet {
+        for (action, outcome) in actions.iter().zip(&outcomes) {
+            match outcome {
+                Outcome::Applied => println!("  {} {}", "✓".green(), action),
+                Outcome::AlreadyApplied => println!("  {} {} (already applied)", "-".dimmed(), action.to_string().dimmed()),
+                Outcome::NotHeld => println!("  {} {} (not held)", "-".dimmed(), action.to_string().dimmed()),
+            }
+        }
+        println!();
+    }
+    if applied == 0 {
+        config.print("No changes to apply.");
+        return Ok(());
+    }
+
+    println!("{}", "Pending Changes".blue().bold());
+    config.print_changes(&DatasetDiff::compare(&tracker, &preview));
+    if dry_run {
+        println!("{}", format!("Dry run: {} actions not applied.", applied).yellow());
+        return Ok(());
+    }
+    if !config.confirm(&format!("Apply {} corporate actions?", applied), yes)? {
+        return Ok(());
+    }
+
+    // Apply again against fresh data and write it in one save
+    persistence.with_tracker_mut(|tracker| apply_all(tracker, &actions))?;
+    println!("{} Applied {} corporate actions", "✓".green(), applied);
+    Ok(())
+}
+
+fn apply_all(tracker: &mut crate::models::DividendTracker, actions: &[CorporateAction]) -> Result<Vec<Outcome>> {
+    actions
+        .iter()
+        .map(|action| corporate_actions::apply(tracker, action))
+        .collect()
+}
@@ -0,0 +1,305 @@
+//! Dividend alert and calendar commands
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Local, NaiveDate};
+use colored::*;
+use console::{Key, Term};
+use rust_decimal::Decimal;
+use serde_json::json;
+
+use super::{parse_amount, CliConfig};
+use crate::api::AlphaVantageClient;
+use crate::calendar_view::{EventKind as CalendarEventKind, MonthCalendar};
+use crate::cli::{AlertCommands, AlertThresholdArgs, AlertsArgs, CalendarArgs};
+use crate::config::Config;
+use crate::events::{EventKind, EventLog};
+use crate::format::format_money;
+use crate::models::{DividendTracker, IncomeThreshold, ThresholdDirection};
+use crate::notifications::NotificationManager;
+use crate::render::{Report, Section, TableView, Text, Tone};
+
+/// Handle alerts command
+pub fn alerts(config: &CliConfig, args: AlertsArgs) -> Result<()> {
+    let AlertsArgs {
+        command,
+        generate,
+        clear,
+        yes,
+    } = args;
+
+    if let Some(AlertCommands::Threshold(args)) = command {
+        return threshold(config, args);
+    }
+    let mut manager = NotificationManager::load(config.resolve_data_dir()?)?;
+
+    if clear {
+        if !manager.alerts.is_empty() && !config.confirm(&format!("Clear {} alerts?", manager.alerts.len()), yes)? {
+            return Ok(());
+        }
+        manager.alerts.clear();
+        manager.save()?;
+        println!("{}", "Alerts cleared successfully!".green());
+        return Ok(());
+    }
+
+    if generate {
+        manager.generate_alerts(&config.create_persistence_manager()?.load()?)?;
+        EventLog::new(config.resolve_data_dir()?).notify(
+            EventKind::AlertsGenerated,
+            json!({ "count": manager.alerts.len(), "alerts": manager.alerts }),
+        );
+        println!("{}", "Alerts generated successfully!".green());
+    }
+
+    // Show current alerts
+    manager.show_alerts()?;
+
+    Ok(())
+}
+
+fn threshold(config: &CliConfig, args: AlertThresholdArgs) -> Result<()> {
+    let AlertThresholdArgs {
+        symbol,
+        annual,
+        above,
+        remove,
+    } = args;
+    let persistence = config.create_persistence_manager()?;
+    let today = Local::now().naive_local().date();
+
+    if annual.is_none() && !remove {
+        let tracker = persistence.load()?;
+        return config.render(&thresholds_report(&tracker, symbol.as_deref(), today));
+    }
+    // clap only accepts --annual and --remove together with a symbol
+    let symbol = symbol.unwrap_or_default();
+
+    if remove {
+        let symbol = persistence.with_tracker_mut(|tracker| {
+            let symbol = tracker.canonical_symbol(&symbol);
+            tracker
+                .income_thresholds
+                .remove(&symbol)
+                .ok_or_else(|| anyhow!("No income threshold is set for {}", symbol))?;
+            Ok(symbol)
+        })?;
+        println!("{} Removed the income threshold for {}", "✓".green(), symbol.cyan());
+        return Ok(());
+    }
+
+    let threshold = IncomeThreshold {
+        annual: parse_amount(annual.as_deref().unwrap_or_default(), "annual target")?,
+        direction: if above { ThresholdDirection::Above } else { ThresholdDirection::Below },
+    };
+    if threshold.annual <= Decimal::ZERO {
+        bail!("Annual target must be positive");
+    }
+
+    let (symbol, income) = persistence.with_tracker_mut(|tracker| {
+        let symbol = tracker.canonical_symbol(&symbol);
+        tracker.income_thresholds.insert(symbol.clone(), threshold.clone());
+        Ok((symbol.clone(), tracker.trailing_income(&symbol, today)))
+    })?;
+
+    println!(
+        "{} Alerting when {}'s trailing 12-month income is {} {}",
+        "✓".green(),
+        symbol.cyan(),
+        direction_name(threshold.direction),
+        format_money(threshold.annual).green()
+    );
+    let status = if threshold.is_crossed(income) { "already crossed".yellow() } else { "not crossed".normal() };
+    println!("  Last 12 months: {} ({})", format_money(income), status);
+    Ok(())
+}
+
+fn direction_name(direction: ThresholdDirection) -> &'static str {
+    match direction {
+        ThresholdDirection::Below => "below",
+        ThresholdDirection::Above => "above",
+    }
+}
+
+/// Income thresholds with each symbol's trailing 12-month income
+fn thresholds_report(tracker: &DividendTracker, symbol: Option<&str>, today: NaiveDate) -> Report {
+    const TITLE: &str = "Income Thresholds";
+    let symbol = symbol.map(|s| tracker.canonical_symbol(s));
+    let thresholds: Vec<_> = tracker
+        .income_thresholds
+        .iter()
+        .filter(|(s, _)| symbol.as_ref().is_none_or(|symbol| *s == symbol))
+        .collect();
+    if thresholds.is_empty() {
+        return Report::warning(TITLE, "No income thresholds set. Add one with 'alerts threshold SYMBOL --annual AMOUNT'.");
+    }
+
+    let mut table = TableView::new(&["Symbol", "Alert When", "Target", "Last 12 Months", "Status"]);
+    for (symbol, threshold) in thresholds {
+        let income = tracker.trailing_income(symbol, today);
+        let status = if threshold.is_crossed(income) {
+            Text::new("Crossed", Tone::Negative)
+        } else {
+            Text::new("OK", Tone::Positive)
+        };
+        table.row(vec![
+            symbol.clone().into(),
+            direction_name(threshold.direction).into(),
+            format_money(threshold.annual).into(),
+            format_money(income).into(),
+            status,
+        ]);
+    }
+    Report::new(TITLE).section(Section::untitled().table(table))
+}
+
+/// Handle calendar command
+pub fn calendar(config: &CliConfig, args: CalendarArgs) -> Result<()> {
+    let CalendarArgs {
+        update,
+        days,
+        export,
+        interactive,
+        frequency_override,
+    } = args;
+    let mut manager = NotificationManager::load(config.resolve_data_dir()?)?;
+    let tracker = config
+        .create_persistence_manager()?
+        .with_frequency_overrides(&frequency_override)
+        .load()?;
+
+    if update {
+        // Load configuration
+        let settings = Config::load()?;
+        let api_key = settings.get_api_key()?;
+
+        // Create API client
+        let client = AlphaVantageClient::new(api_key, &settings.api)?;
+
+        // Fetch upcoming dividends
+        manager.fetch_upcoming_dividends(&tracker, &client, settings.analysis.default_pay_lag_days)?;
+    }
+
+    // Export to ICS if requested
+    if let Some(output_path) = export {
+        manager.export_to_ics(&output_path)?;
+        return Ok(());
+    }
+
+    if interactive {
+        return browse_calendar(&tracker, &manager);
+    }
+
+    // Show calendar, with planned expenses checked against it
+    manager.show_calendar(&tracker, days)?;
+
+    Ok(())
+}
+
+/// Month-by-month calendar navigated with the arrow keys until q or Esc
+fn browse_calendar(tracker: &DividendTracker, manager: &NotificationManager) -> Result<()> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        bail!("calendar --interactive needs a terminal; leave out --interactive to print the calendar");
+    }
+
+    let today = Local::now().naive_local().date();
+    let mut view = MonthCalendar::new(tracker, &manager.calendar, today);
+    term.hide_cursor()?;
+    let result = (|| -> Result<()> {
+        let mut listing = false;
+        loop {
+            term.clear_screen()?;
+            for line in month_lines(&view, today) {
+                term.write_line(&line)?;
+            }
+            if listing {
+                for line in day_lines(&view) {
+                    term.write_line(&line)?;
+                }
+            }
+            term.write_line("")?;
+            term.write_line(&"←/→ month  ↑/↓ day with dividends  Enter list day  t today  q quit".dimmed().to_string())?;
+
+            listing = false;
+            match term.read_key()? {
+                Key::ArrowLeft | Key::Char('h') => view.previous_month(),
+                Key::ArrowRight | Key::Char('l') => view.next_month(),
+                Key::ArrowUp | Key::Char('k') => view.previous_event_day(),
+                Key::ArrowDown | Key::Char('j') => view.next_event_day(),
+                Key::Char('t') => view.go_to(today),
+                Key::Enter => listing = true,
+                Key::Escape | Key::CtrlC | Key::Char('q') => return Ok(()),
+                _ => {}
+            }
+        }
+    })();
+    term.show_cursor()?;
+    result
+}
+
+/// The month shown as a grid, ex-dates in yellow and pay dates in green
+fn month_lines(view: &MonthCalendar, today: NaiveDate) -> Vec<String> {
+    let mut lines = vec![
+        format!("📅 {}", view.month().format("%B %Y")).green().bold().to_string(),
+        String::new(),
+        " Mo   Tu   We   Th   Fr   Sa   Su".dimmed().to_string(),
+    ];
+    for week in view.weeks() {
+        let cells: Vec<String> = week
+            .iter()
+            .map(|day| {
+                let Some(day) = day else {
+                    return "    ".to_string();
+                };
+                // Markers keep the calendar readable without colors
+                let (marker, cell) = match view.kinds_on(*day) {
+                    (true, true) => ('*', format!("{:>3}", day.format("%-d")).magenta().bold()),
+                    (true, false) => ('x', format!("{:>3}", day.format("%-d")).yellow().bold()),
+                    (false, true) => ('p', format!("{:>3}", day.format("%-d")).green().bold()),
+                    (false, false) => (' ', format!("{:>3}", day.format("%-d")).normal()),
+                };
+                let cell = if *day == today { cell.underline() } else { cell };
+                let cell = if *day == view.selected() { cell.reversed() } else { cell };
+                format!("{}{}", cell, marker)
+            })
+            .collect();
+        lines.push(cells.join(" "));
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "{} ex-date  {} pay date  {} both     Payments this month: {}",
+        "x".yellow(),
+        "p".green(),
+        "*".magenta(),
+        format_money(view.month_income()).green()
+    ));
+    lines
+}
+
+/// The selected day's ex-dates and payments
+fn day_lines(view: &MonthCalendar) -> Vec<String> {
+    let day = view.selected();
+    let mut lines = vec![String::new(), day.format("%A %Y-%m-%d").to_string().blue().bold().to_string()];
+    let events = view.events_on(day);
+    if events.is_empty() {
+        lines.push("  No ex-dates or payments.".to_string());
+    }
+    for event in events {
+        let kind = match event.kind {
+            CalendarEventKind::ExDate => "ex-date",
+            CalendarEventKind::PayDate => "pays",
+        };
+        let mut line = format!("  {} {}", event.symbol.cyan().bold(), kind);
+        if let Some(amount) = event.amount_per_share {
+            line.push_str(&format!(" ${:.4}/share", amount));
+        }
+        if let Some(total) = event.total {
+            line.push_str(&format!(" ({})", format_money(total)));
+        }
+        if event.estimated {
+            line.push_str(&" estimated".dimmed().to_string());
+        }
+        lines.push(line);
+    }
+    lines
+}
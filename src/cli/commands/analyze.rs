@@ -0,0 +1,134 @@
+//! Longer-term portfolio analysis (`analyze yoc-history`)
+
+use anyhow::Result;
+use chrono::Local;
+
+use super::{with_hint, CliConfig};
+use crate::cli::{AnalyzeCommands, YocHistoryArgs};
+use crate::config::{Config, DisplaySettings};
+use crate::format::{format_money, format_percent, format_shares};
+use crate::render::{signed_percent, NoteKind, Report, Section, TableView, Text, Tone};
+use crate::yield_on_cost::{PositionYieldOnCost, YieldOnCostHistory};
+
+const YOC_TITLE: &str = "Yield on Cost History";
+
+/// Handle analyze commands
+pub fn run(config: &CliConfig, command: AnalyzeCommands) -> Result<()> {
+    match command {
+        AnalyzeCommands::YocHistory(args) => yoc_history(config, args),
+    }
+}
+
+fn yoc_history(config: &CliConfig, args: YocHistoryArgs) -> Result<()> {
+    let tracker = config.create_persistence_manager()?.load()?;
+    let today = Local::now().naive_local().date();
+    let symbol = args.symbol.as_deref().map(|s| tracker.canonical_symbol(s));
+    if let Some(symbol) = &symbol {
+        if !tracker.holds(symbol) {
+            return config.render(&Report::warning(
+                YOC_TITLE,
+                with_hint(&format!("No holding found for {}.", symbol), tracker.symbol_hint(symbol)),
+            ));
+        }
+    }
+
+    let settings = Config::load().unwrap_or_default();
+    let history = YieldOnCostHistory::calculate(&tracker, symbol.as_deref(), settings.analysis.month_drift_days, today);
+    config.render(&yoc_history_report(&history, &settings.display))
+}
+
+/// Build the yield-on-cost report: the portfolio by year, then each position
+fn yoc_history_report(history: &YieldOnCostHistory, display: &DisplaySettings) -> Report {
+    let lots_tip = format!(
+        "{} {} no purchase lots; import them with 'holdings import-lots' to include {}.",
+        history.without_lots.join(", "),
+        if history.without_lots.len() == 1 { "has" } else { "have" },
+        if history.without_lots.len() == 1 { "it" } else { "them" },
+    );
+
+    if history.positions.is_empty() {
+        let mut message = "No position has purchase lots from before the last complete year.".to_string();
+        if !history.without_lots.is_empty() {
+            message = format!("{} {}", message, lots_tip);
+        }
+        return Report::warning(YOC_TITLE, message);
+    }
+
+    let mut portfolio = TableView::new(&["Year", "Cost", "Dividends", "Yield on Cost"]);
+    for year in &history.portfolio {
+        portfolio.row(vec![
+            year.year.to_string().into(),
+            format_money(year.cost).into(),
+            format_money(year.income).into(),
+            Text::new(format_percent(year.yield_on_cost, 2), Tone::Highlight),
+        ]);
+    }
+    let mut report = Report::new(YOC_TITLE).section(
+        Section::new("Portfolio")
+            .icon("📈")
+            .table(portfolio)
+            .note(
+                NoteKind::Info,
+                "Each year counts the shares held all year, with sales taken from the oldest lots, against the regular dividends paid that year.",
+            ),
+    );
+
+    for position in &history.positions {
+        report.push(position_section(position, display));
+    }
+
+    if !history.without_lots.is_empty() {
+        report.push(Section::untitled().note(NoteKind::Tip, lots_tip));
+    }
+    report
+}
+
+fn position_section(position: &PositionYieldOnCost, display: &DisplaySettings) -> Section {
+    let mut table = TableView::new(&[
+        "Year",
+        "Div/Share",
+        "Shares",
+        "Cost",
+        "Dividends",
+        "Yield on Cost",
+        "First Lot",
+    ]);
+    for year in &position.years {
+        table.row(vec![
+            year.year.to_string().into(),
+            format!("${:.4}", year.dividends_per_share).into(),
+            format_shares(year.shares, display).into(),
+            format_money(year.cost).into(),
+            format_money(year.income).into(),
+            Text::new(format_percent(year.yield_on_cost, 2), Tone::Highlight),
+            format_percent(year.first_lot_yield, 2).into(),
+        ]);
+    }
+
+    let account = position.account.as_ref().map(|a| format!(" in {}", a)).unwrap_or_default();
+    let mut section = Section::new(format!(
+        "{}{} (first bought {})",
+        position.symbol,
+        account,
+        position.first_purchase.format("%Y-%m-%d")
+    ))
+    .table(table);
+    if let Some(growth) = position.per_share_growth {
+        section = section.field("Dividend Growth (CAGR)", signed_percent(growth));
+    }
+    if let (Some(first), Some(last)) = (position.years.first(), position.years.last()) {
+        if position.years.len() > 1 {
+            section = section.field(
+                "First Lot Yield on Cost",
+                format!(
+                    "{} in {} to {} in {}",
+                    format_percent(first.first_lot_yield, 2),
+                    first.year,
+                    format_percent(last.first_lot_yield, 2),
+                    last.year
+                ),
+            );
+        }
+    }
+    section
+}
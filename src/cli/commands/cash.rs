@@ -0,0 +1,274 @@
+//! Dividend cash ledger commands
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::{parse_amount, parse_date_or_today, CliConfig};
+use crate::cli::{CashArgs, CashCommands, CashReinvestArgs, CashWithdrawArgs};
+use crate::format::format_money;
+use crate::models::{CashBalance, CashMovement, CashMovementKind, DividendTracker};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Dividend Cash Ledger";
+
+/// Handle cash ledger commands
+pub fn run(config: &CliConfig, args: CashArgs) -> Result<()> {
+    let CashArgs { command, account } = args;
+
+    match command {
+        Some(CashCommands::Enable { since }) => enable(config, since),
+        Some(CashCommands::Withdraw(args)) => withdraw(config, args),
+        Some(CashCommands::Reinvest(args)) => reinvest(config, args),
+        None => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let today = Local::now().naive_local().date();
+            config.render(&ledger_report(&tracker, account.as_deref(), today))
+        }
+    }
+}
+
+fn enable(config: &CliConfig, since: Option<String>) -> Result<()> {
+    let since = parse_date_or_today(since.as_deref())?;
+
+    let persistence = config.create_persistence_manager()?;
+    persistence.with_tracker_mut(|tracker| tracker.enable_cash_ledger(since))?;
+
+    println!(
+        "{} Cash ledger enabled; dividends paid from {} are credited to their account",
+        "✓".green(),
+        since.format("%Y-%m-%d").to_string().blue()
+    );
+    println!("  Record the account with 'div add --account', then 'cash withdraw' or 'cash reinvest' as cash is used.");
+
+    Ok(())
+}
+
+fn withdraw(config: &CliConfig, args: CashWithdrawArgs) -> Result<()> {
+    let CashWithdrawArgs {
+        amount,
+        account,
+        date,
+        note,
+    } = args;
+
+    let mut movement = CashMovement::new(
+        parse_date_or_today(date.as_deref())?,
+        account.as_deref(),
+        CashMovementKind::Withdrawal,
+        parse_amount(&amount, "amount")?,
+    )?;
+    movement.note = note;
+
+    record(config, movement)
+}
+
+fn reinvest(config: &CliConfig, args: CashReinvestArgs) -> Result<()> {
+    let CashReinvestArgs {
+        amount,
+        symbol,
+        account,
+        date,
+        note,
+    } = args;
+
+    let mut movement = CashMovement::new(
+        parse_date_or_today(date.as_deref())?,
+        account.as_deref(),
+        CashMovementKind::Reinvestment,
+        parse_amount(&amount, "amount")?,
+    )?;
+    movement.note = note;
+
+    let persistence = config.create_persistence_manager()?;
+    let (movement, balance) = persistence.with_tracker_mut(|tracker| {
+        movement.symbol = symbol.map(|s| tracker.canonical_symbol(&s));
+        let balance = tracker.record_cash_movement(movement.clone())?;
+        Ok((movement, balance))
+    })?;
+
+    print_recorded(&movement, &balance);
+    Ok(())
+}
+
+fn record(config: &CliConfig, movement: CashMovement) -> Result<()> {
+    let persistence = config.create_persistence_manager()?;
+    let balance = persistence.with_tracker_mut(|tracker| tracker.record_cash_movement(movement.clone()))?;
+
+    print_recorded(&movement, &balance);
+    Ok(())
+}
+
+fn print_recorded(movement: &CashMovement, balance: &CashBalance) {
+    let what = match (movement.kind, &movement.symbol) {
+        (CashMovementKind::Withdrawal, _) => "withdrawal".to_string(),
+        (CashMovementKind::Reinvestment, Some(symbol)) => format!("reinvestment in {}", symbol.cyan()),
+        (CashMovementKind::Reinvestment, None) => "reinvestment".to_string(),
+    };
+    println!(
+        "{} Recorded {} {} from {}",
+        "✓".green(),
+        format_money(movement.amount).green(),
+        what,
+        movement.account
+    );
+    println!("  Cash remaining in {}: {}", movement.account, format_money(balance.balance()));
+}
+
+/// Section showing uninvested dividend cash per account, as used by `summary --cash`
+pub fn cash_balance_section(tracker: &DividendTracker, today: NaiveDate) -> Section {
+    let section = Section::new("Dividend Cash").icon("💵");
+    let Some(ledger) = &tracker.cash else {
+        return section.note(NoteKind::Tip, "The cash ledger is off. Start it with 'cash enable'.");
+    };
+
+    let balances = ledger.balances(&tracker.dividends, today);
+    if balances.is_empty() {
+        return section.note(
+            NoteKind::Info,
+            format!("No dividends paid since {} yet.", ledger.since.format("%Y-%m-%d")),
+        );
+    }
+
+    let mut table = TableView::new(&["Account", "Credited", "Withdrawn", "Reinvested", "Balance"]);
+    for balance in &balances {
+        table.row(vec![
+            Text::new(balance.account.clone(), Tone::Highlight),
+            format_money(balance.credited).into(),
+            format_money(balance.withdrawn).into(),
+            format_money(balance.reinvested).into(),
+            Text::new(format_money(balance.balance()), Tone::Positive),
+        ]);
+    }
+
+    let total: Decimal = balances.iter().map(|b| b.balance()).sum();
+    section
+        .table(table)
+        .field("Uninvested Cash", Text::new(format_money(total), Tone::Positive))
+        .note(
+            NoteKind::Info,
+            format!(
+                "Dividends paid since {} are credited net of withholding and fees.",
+                ledger.since.format("%Y-%m-%d")
+            ),
+        )
+}
+
+/// Build the ledger: credited dividends and movements with a running balance per account
+pub fn ledger_report(tracker: &DividendTracker, account: Option<&str>, today: NaiveDate) -> Report {
+    let Some(ledger) = &tracker.cash else {
+        return Report::warning(TITLE, "The cash ledger is off. Start it with 'cash enable'.");
+    };
+
+    // Credits sort before same-day movements so a payment can be reinvested on arrival
+    let mut entries: Vec<(NaiveDate, u8, &str, String, Decimal)> = Vec::new();
+    for dividend in ledger.credits(&tracker.dividends, today) {
+        entries.push((
+            dividend.pay_date,
+            0,
+            dividend.account_name(),
+            format!("{} dividend", dividend.symbol),
+            dividend.cash_credited(),
+        ));
+    }
+    for movement in ledger.movements.iter().filter(|m| m.date <= today) {
+        let description = match (movement.kind, &movement.symbol) {
+            (CashMovementKind::Withdrawal, _) => "Withdrawal".to_string(),
+            (CashMovementKind::Reinvestment, Some(symbol)) => format!("Reinvested in {}", symbol),
+            (CashMovementKind::Reinvestment, None) => "Reinvested".to_string(),
+        };
+        let description = match &movement.note {
+            Some(note) => format!("{} ({})", description, note),
+            None => description,
+        };
+        entries.push((movement.date, 1, &movement.account, description, -movement.amount));
+    }
+    entries.retain(|(_, _, acct, _, _)| account.is_none_or(|a| a.trim() == *acct));
+    entries.sort_by_key(|e| (e.0, e.1));
+
+    if entries.is_empty() {
+        let message = match account {
+            Some(account) => format!("No cash ledger entries for account {}.", account.trim()),
+            None => format!("No dividends paid since {} yet.", ledger.since.format("%Y-%m-%d")),
+        };
+        return Report::warning(TITLE, message);
+    }
+
+    let mut running: HashMap<&str, Decimal> = HashMap::new();
+    let mut table = TableView::new(&["Date", "Account", "Entry", "Amount", "Balance"]);
+    for (date, _, acct, description, amount) in &entries {
+        let balance = running.entry(acct).or_default();
+        *balance += *amount;
+        let tone = if amount.is_sign_negative() { Tone::Negative } else { Tone::Positive };
+        table.row(vec![
+            date.format("%Y-%m-%d").to_string().into(),
+            (*acct).into(),
+            description.clone().into(),
+            Text::new(format_money(*amount), tone),
+            format_money(*balance).into(),
+        ]);
+    }
+
+    Report::new(TITLE)
+        .section(Section::untitled().table(table))
+        .section(cash_balance_section(tracker, today))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dividend(symbol: &str, pay_date: &str, amount: Decimal, account: Option<&str>) -> Dividend {
+        let mut dividend = Dividend::new(
+            symbol.to_string(),
+            None,
+            date(pay_date),
+            date(pay_date),
+            amount,
+            dec!(100),
+            DividendType::Regular,
+        )
+        .unwrap();
+        dividend.account = account.map(str::to_string);
+        dividend
+    }
+
+    #[test]
+    fn test_cash_ledger_balances_per_account() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(dividend("KO", "2023-12-15", dec!(0.46), None));
+        tracker.add_dividend(dividend("KO", "2024-04-01", dec!(0.485), None));
+        let mut foreign = dividend("NESN", "2024-04-26", dec!(3), Some("IRA"));
+        foreign.withholding_tax = Some(dec!(105));
+        tracker.add_dividend(foreign);
+
+        let withdrawal =
+            CashMovement::new(date("2024-03-01"), None, CashMovementKind::Withdrawal, dec!(10)).unwrap();
+        assert!(tracker.record_cash_movement(withdrawal.clone()).is_err(), "ledger not enabled");
+
+        tracker.enable_cash_ledger(date("2024-01-01")).unwrap();
+        assert!(tracker.enable_cash_ledger(date("2024-01-01")).is_err());
+        // The December payment predates the ledger, so nothing is available yet
+        assert!(tracker.record_cash_movement(withdrawal).is_err());
+
+        let reinvest =
+            CashMovement::new(date("2024-04-02"), None, CashMovementKind::Reinvestment, dec!(40)).unwrap();
+        let balance = tracker.record_cash_movement(reinvest).unwrap();
+        assert_eq!(balance.balance(), dec!(8.5));
+
+        let balances = tracker.cash.as_ref().unwrap().balances(&tracker.dividends, date("2024-12-31"));
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[0].account, "Default");
+        assert_eq!(balances[0].reinvested, dec!(40));
+        assert_eq!(balances[1].account, "IRA");
+        assert_eq!(balances[1].balance(), dec!(195));
+    }
+}
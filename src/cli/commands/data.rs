@@ -0,0 +1,299 @@
+//! Data management commands (`data export`, `data stats`, backups)
+
+use anyhow::Result;
+use chrono::Datelike;
+use colored::*;
+
+use super::{parse_age, CliConfig};
+use crate::cli::{
+    BackupCommands, BackupPruneArgs, DataCommands, DataExportArgs, ExportDataType, ExportFormat,
+};
+use crate::config::{BackupSettings, Config};
+use crate::diff::{diff_report, DatasetDiff};
+use crate::filters::DividendFilter;
+use crate::paths;
+
+/// Handle data management commands
+pub fn run(config: &CliConfig, command: DataCommands) -> Result<()> {
+    match command {
+        DataCommands::Export(DataExportArgs {
+            format,
+            output,
+            data_type,
+            symbol,
+            symbol_match,
+            year,
+        }) => {
+            config.print_verbose("Creating persistence manager for data export");
+            let persistence = config.create_persistence_manager()?;
+            let mut tracker = persistence.load()?;
+
+            let mut filter = DividendFilter::new();
+            if let Some(symbol) = &symbol {
+                filter = filter.symbol(symbol, symbol_match.into());
+            }
+            if let Some(year) = year {
+                filter = filter.year(year);
+            }
+            tracker.dividends.retain(|d| filter.matches(d));
+            tracker.holdings.retain(|key, _| filter.matches_symbol(&key.symbol));
+            let mut notifications = persistence.load_notifications()?;
+            notifications.calendar.retain(|e| filter.matches_symbol(&e.symbol));
+            notifications.alerts.retain(|a| filter.matches_symbol(&a.symbol));
+
+            match data_type {
+                ExportDataType::Dividends => {
+                    let extension = if format == ExportFormat::Csv { ".csv" } else { ".json" };
+                    let output_path = paths::with_suffix(&output, extension);
+
+                    if format == ExportFormat::Csv {
+                        persistence.export_to_csv(&tracker, &output_path)?;
+                        println!(
+                            "{} Dividends exported to {}",
+                            "✓".green(),
+                            output_path.display().to_string().cyan()
+                        );
+                    } else {
+                        persistence.export_to_json(&tracker, &notifications, &output_path)?;
+                        println!(
+                            "{} All data exported to {}",
+                            "✓".green(),
+                            output_path.display().to_string().cyan()
+                        );
+                    }
+                }
+                ExportDataType::Holdings => {
+                    let output_path = paths::with_suffix(&output, "_holdings.csv");
+                    persistence.export_holdings_to_csv(&tracker, &output_path)?;
+                    println!(
+                        "{} Holdings exported to {}",
+                        "✓".green(),
+                        output_path.display().to_string().cyan()
+                    );
+                }
+                ExportDataType::All => {
+                    if format == ExportFormat::Csv {
+                        // Export both dividends and holdings as separate CSV files
+                        let dividends_path = paths::with_suffix(&output, "_dividends.csv");
+                        let holdings_path = paths::with_suffix(&output, "_holdings.csv");
+
+                        persistence.export_to_csv(&tracker, &dividends_path)?;
+                        persistence.export_holdings_to_csv(&tracker, &holdings_path)?;
+
+                        println!("{} Data exported to:", "✓".green());
+                        println!(
+                            "  Dividends: {}",
+                            dividends_path.display().to_string().cyan()
+                        );
+                        println!("  Holdings: {}", holdings_path.display().to_string().cyan());
+                    } else {
+                        let output_path = paths::with_suffix(&output, ".json");
+                        persistence.export_to_json(&tracker, &notifications, &output_path)?;
+                        println!(
+                            "{} All data exported to {}",
+                            "✓".green(),
+                            output_path.display().to_string().cyan()
+                        );
+                    }
+                }
+            }
+        }
+        DataCommands::Stats => {
+            config.print_verbose("Loading data statistics");
+            let persistence = config.create_persistence_manager()?;
+            let tracker = persistence.load()?;
+            let stats = persistence.get_stats(&tracker)?;
+
+            config.print(&format!("{}", "Data Statistics".green().bold()));
+            if !config.quiet {
+                println!();
+                println!(
+                    "📂 {} {}",
+                    "Data Directory:".bright_blue(),
+                    stats.data_directory.display().to_string().cyan()
+                );
+                println!(
+                    "💰 {} {}",
+                    "Dividend Records:".bright_blue(),
+                    stats.dividend_count.to_string().cyan()
+                );
+                if let (Some(first), Some(last)) = (stats.archived_years.first(), stats.archived_years.last()) {
+                    let years = if first == last {
+                        first.to_string()
+                    } else {
+                        format!("{}-{}", first, last)
+                    };
+                    println!(
+                        "🗄  {} {} ({})",
+                        "Archived Dividends:".bright_blue(),
+                        stats.archived_dividend_count.to_string().cyan(),
+                        years
+                    );
+                }
+                println!(
+                    "📊 {} {}",
+                    "Holdings:".bright_blue(),
+                    stats.holding_count.to_string().cyan()
+                );
+                println!(
+                    "💾 {} {} bytes",
+                    "Total Data Size:".bright_blue(),
+                    stats.total_size_bytes.to_string().cyan()
+                );
+                println!(
+                    "🔄 {} {}",
+                    "Backup Files:".bright_blue(),
+                    stats.backup_count.to_string().cyan()
+                );
+                let saved_pct = if stats.backup_uncompressed_bytes > 0 {
+                    100.0
+                        * (1.0
+                            - stats.backup_size_bytes as f64
+                                / stats.backup_uncompressed_bytes as f64)
+                } else {
+                    0.0
+                };
+                println!(
+                    "🗜  {} {} bytes ({:?} compression, {:.1}% saved)",
+                    "Backup Storage:".bright_blue(),
+                    stats.backup_size_bytes.to_string().cyan(),
+                    stats.backup_settings.compression,
+                    saved_pct
+                );
+                println!(
+                    "🧹 {} {}",
+                    "Retention Policy:".bright_blue(),
+                    retention_summary(&stats.backup_settings).cyan()
+                );
+                if let Some(max_mb) = stats.backup_settings.max_total_size_mb {
+                    let limit = max_mb * 1024 * 1024;
+                    let used_pct = if limit > 0 {
+                        100.0 * stats.backup_size_bytes as f64 / limit as f64
+                    } else {
+                        100.0
+                    };
+                    println!(
+                        "📏 {} {:.1}% of {} MB",
+                        "Backup Quota Used:".bright_blue(),
+                        used_pct,
+                        max_mb
+                    );
+                }
+            }
+        }
+        DataCommands::Backup => {
+            config.print("Creating manual backup...");
+            config.print_verbose("Initializing persistence manager for backup");
+            let persistence = config.create_persistence_manager()?;
+
+            // Load and save to force a backup, calendar and alerts included
+            config.print_verbose("Loading current data");
+            let tracker = persistence.load()?;
+            config.print_verbose("Saving data to create backup");
+            persistence.create_backup(&tracker)?;
+
+            config.print_success("Manual backup created successfully!");
+        }
+        DataCommands::Backups {
+            command: BackupCommands::Prune(BackupPruneArgs { keep, older_than }),
+        } => {
+            let persistence = config.create_persistence_manager()?;
+            let min_age = older_than.as_deref().map(parse_age).transpose()?;
+
+            let removed = persistence.prune_backups(keep, min_age)?;
+            config.print_success(&format!(
+                "Removed {} backup{} (kept newest {} per file)",
+                removed,
+                if removed == 1 { "" } else { "s" },
+                keep
+            ));
+        }
+        DataCommands::Diff { source } => {
+            let persistence = config.create_persistence_manager()?;
+            let base = persistence.load_snapshot(&source)?;
+            let current = persistence.load()?;
+
+            let diff = DatasetDiff::compare(&base, &current);
+            let display = Config::load().unwrap_or_default().display;
+            config.render(&diff_report(&diff, &source, &display))?;
+        }
+        DataCommands::Load { file, yes } => {
+            config.print_verbose(&format!("Restoring data from backup {}", file));
+            let persistence = config.create_persistence_manager()?;
+            let current = persistence.load()?;
+            let question = format!(
+                "Replace the current {} dividends and {} holdings with backup {}?",
+                current.dividends.len(),
+                current.holdings.len(),
+                file
+            );
+            if !config.confirm(&question, yes)? {
+                return Ok(());
+            }
+            let (tracker, notifications) = persistence.restore_backup(&file)?;
+
+            config.print_success(&format!(
+                "Restored {} dividends and {} holdings from {}",
+                tracker.dividends.len(),
+                tracker.holdings.len(),
+                file
+            ));
+            match notifications {
+                Some(notifications) => config.print(&format!(
+                    "  Calendar: {} entries, alerts: {}",
+                    notifications.calendar.len(),
+                    notifications.alerts.len()
+                )),
+                None => config.print("  The backup predates calendar and alerts in backups; kept the current ones"),
+            }
+        }
+        DataCommands::Archive { before, yes } => {
+            let persistence = config.create_persistence_manager()?;
+            let current = persistence.load()?;
+            let due = current.dividends.iter().filter(|d| d.pay_date.year() < before).count();
+            if due == 0 {
+                config.print(&format!("No dividends paid before {} to archive", before));
+                return Ok(());
+            }
+
+            let question = format!(
+                "Move {} of {} dividends paid before {} into yearly archives?",
+                due,
+                current.dividends.len(),
+                before
+            );
+            if !config.confirm(&question, yes)? {
+                return Ok(());
+            }
+
+            let counts = persistence.archive_dividends(before)?;
+            let archived: usize = counts.values().sum();
+            config.print_success(&format!(
+                "Archived {} dividend{} paid before {} into {} yearly file{}",
+                archived,
+                if archived == 1 { "" } else { "s" },
+                before,
+                counts.len(),
+                if counts.len() == 1 { "" } else { "s" }
+            ));
+            for (year, count) in &counts {
+                config.print(&format!("  {}: {} dividends", year, count));
+            }
+            config.print("  Use --include-archived with div list, summary or tax summary to see them");
+        }
+    }
+
+    Ok(())
+}
+
+/// One-line description of the backup retention policy
+fn retention_summary(settings: &BackupSettings) -> String {
+    let mut parts = vec![format!("keep {} per file", settings.max_count)];
+    if let Some(days) = settings.max_age_days {
+        parts.push(format!("max age {} days", days));
+    }
+    if let Some(max_mb) = settings.max_total_size_mb {
+        parts.push(format!("max total {} MB", max_mb));
+    }
+    parts.join(", ")
+}
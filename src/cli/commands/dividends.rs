@@ -0,0 +1,955 @@
+//! Dividend record commands (`div add`, `div list`, ...)
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use colored::*;
+use csv::Reader;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{load_tracker, parse_dividend_date, with_hint, CliConfig};
+use crate::classify::Guess;
+use crate::cli::{parse_percentage, AddArgs, DivCommands, IncomeKindArg, ListArgs, ListSortField, SortSpec, SymbolMatchArg};
+use crate::config::{Config, DisplaySettings};
+use crate::filters::{self, DividendFilter, Quarter};
+use crate::format::{format_money, format_shares};
+use crate::models::{Dividend, DividendTracker, DividendType, DuplicateDividend, Holding};
+use crate::progress;
+use crate::render::{Report, Section, TableView, Text, Tone};
+
+const LIST_TITLE: &str = "Dividend Payments";
+
+/// Handle dividend record commands
+pub fn run(config: &CliConfig, command: DivCommands) -> Result<()> {
+    match command {
+        DivCommands::Add(args) => add(config, args),
+        DivCommands::List(args) => list(config, args),
+        DivCommands::Import { file } => import(config, &file),
+        DivCommands::Export { output } => {
+            config.print_verbose("Creating persistence manager for dividend export");
+            let persistence = config.create_persistence_manager()?;
+            let tracker = persistence.load()?;
+            persistence.export_to_csv(&tracker, &output)?;
+            config.print_success(&format!("Dividends exported to {}", output.display()));
+            Ok(())
+        }
+    }
+}
+
+/// Handle listing dividend payments with filtering and sorting
+pub fn list(config: &CliConfig, args: ListArgs) -> Result<()> {
+    // Load persistence manager and existing data
+    let persistence = config.create_persistence_manager()?;
+    let tracker = load_tracker(&persistence, args.include_archived)?;
+
+    if tracker.dividends.is_empty() {
+        return config.render(&Report::warning(
+            LIST_TITLE,
+            "No dividend records found. Use 'add' command to add some!",
+        ));
+    }
+
+    let listing = build_listing(&tracker, &args)?;
+
+    if listing.dividends.is_empty() {
+        let hint = args.symbol.as_deref().and_then(|s| tracker.symbol_hint(s));
+        return config.render(&Report::warning(
+            LIST_TITLE,
+            with_hint("No dividends match the specified filters.", hint),
+        ));
+    }
+
+    let today = Local::now().naive_local().date();
+    let display = Config::load().unwrap_or_default().display;
+    config.render(&listing_report(&listing, &args, today, &display))?;
+
+    // Export exactly the rows shown, in the same order
+    if let Some(csv_path) = &args.export_csv {
+        persistence.export_dividends_to_csv(listing.dividends.iter().copied(), csv_path)?;
+        config.print_success(&format!(
+            "Exported {} dividends to {}",
+            listing.dividends.len(),
+            csv_path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Build the list report view: the dividend table, totals and applied filters
+pub fn listing_report(
+    listing: &DividendListing,
+    args: &ListArgs,
+    today: NaiveDate,
+    display: &DisplaySettings,
+) -> Report {
+    // Fee, withholding and net columns only appear when something was deducted
+    let show_fees = !listing.total_fees.is_zero();
+    let show_withholding = !listing.total_withholding.is_zero();
+    let show_account = listing.dividends.iter().any(|d| d.account.is_some());
+    let mut headers = vec![
+        "Symbol",
+        "Company",
+        "Ex-Date",
+        "Pay-Date",
+        "$/Share",
+        "Shares",
+        "Total",
+    ];
+    if show_fees {
+        headers.push("Fees");
+    }
+    if show_withholding {
+        headers.push("Withheld");
+    }
+    if show_fees || show_withholding {
+        headers.push("Net");
+    }
+    if show_account {
+        headers.push("Account");
+    }
+    let mut table = TableView::new(&headers);
+
+    for dividend in &listing.dividends {
+        // Highlight upcoming dividends
+        let tone = if dividend.pay_date > today {
+            Tone::Positive
+        } else {
+            Tone::Plain
+        };
+
+        let mut row = vec![
+            Text::new(dividend.symbol.clone(), tone),
+            Text::new(listing.tracker.company_name(dividend).unwrap_or("-"), tone),
+            Text::new(dividend.ex_date.format("%Y-%m-%d").to_string(), tone),
+            Text::new(dividend.pay_date.format("%Y-%m-%d").to_string(), tone),
+            Text::new(format!("${:.4}", dividend.amount_per_share), tone),
+            Text::new(format_shares(dividend.shares_owned, display), tone),
+            Text::new(format_money(dividend.total_amount), tone),
+        ];
+        if show_fees {
+            row.push(match dividend.fees {
+                Some(fees) => Text::new(format_money(fees), tone),
+                None => Text::new("-", tone),
+            });
+        }
+        if show_withholding {
+            row.push(match dividend.withholding_tax {
+                Some(withholding) => Text::new(format_money(withholding), tone),
+                None => Text::new("-", tone),
+            });
+        }
+        if show_fees || show_withholding {
+            row.push(Text::new(format_money(dividend.cash_credited()), tone));
+        }
+        if show_account {
+            row.push(Text::new(dividend.account_name(), tone));
+        }
+        table.row(row);
+    }
+
+    let mut totals = Section::untitled().table(table);
+
+    // Subtotal each year when the listing spans more than one
+    if listing.yearly_totals.len() > 1 {
+        for year in &listing.yearly_totals {
+            totals = totals.field(
+                format!("{} Subtotal", year.year),
+                format!(
+                    "{} ({} {})",
+                    format_money(year.total),
+                    year.payments,
+                    if year.payments == 1 { "payment" } else { "payments" }
+                ),
+            );
+        }
+    }
+
+    totals = totals.field(
+        "Total Dividends",
+        Text::new(format_money(listing.total_income), Tone::Positive),
+    );
+    if show_fees {
+        totals = totals.field(
+            "Total Fees",
+            Text::new(format_money(listing.total_fees), Tone::Warning),
+        );
+    }
+    if show_withholding {
+        totals = totals.field(
+            "Total Withheld",
+            Text::new(format_money(listing.total_withholding), Tone::Warning),
+        );
+    }
+    if show_fees || show_withholding {
+        totals = totals.field(
+            "Net Dividends",
+            Text::new(
+                format_money(listing.total_income - listing.total_fees - listing.total_withholding),
+                Tone::Positive,
+            ),
+        );
+    }
+    let mut report = Report::new(LIST_TITLE).section(totals.field(
+        "Number of Payments",
+        Text::new(listing.dividends.len().to_string(), Tone::Highlight),
+    ));
+
+    let has_filters = args.symbol.is_some() || args.year.is_some() || args.month.is_some() ||
+                     args.quarter.is_some() || args.ttm ||
+                     args.date_start.is_some() || args.date_end.is_some() ||
+                     args.amount_min.is_some() || args.amount_max.is_some() ||
+                     args.total_min.is_some() || args.total_max.is_some() ||
+                     args.shares_min.is_some() || args.account.is_some() || args.upcoming;
+
+    if has_filters || args.sort_by != SortSpec::by(ListSortField::ExDate) || args.reverse {
+        let mut filters = Section::new("Applied Filters");
+
+        if let Some(sym) = &args.symbol {
+            let symbol = if args.symbol_match == SymbolMatchArg::Exact {
+                sym.clone()
+            } else {
+                format!("{} ({})", sym, args.symbol_match)
+            };
+            filters = filters.field("Symbol", Text::new(symbol, Tone::Highlight));
+        }
+        if let Some(y) = args.year {
+            filters = filters.field("Year", Text::new(y.to_string(), Tone::Highlight));
+        }
+        if let Some(m) = args.month {
+            filters = filters.field("Month", Text::new(m.to_string(), Tone::Highlight));
+        }
+        if let Some(q) = &args.quarter {
+            filters = filters.field("Quarter", Text::new(q.to_uppercase(), Tone::Highlight));
+        }
+        if args.ttm {
+            filters = filters.field("Trailing 12 Months", Text::new("Yes", Tone::Positive));
+        }
+        if let Some(ds) = &args.date_start {
+            filters = filters.field("Date Start", Text::new(ds.clone(), Tone::Highlight));
+        }
+        if let Some(de) = &args.date_end {
+            filters = filters.field("Date End", Text::new(de.clone(), Tone::Highlight));
+        }
+        if let Some(am) = &args.amount_min {
+            filters = filters.field("Min Amount", Text::new(format!("${}", am), Tone::Highlight));
+        }
+        if let Some(am) = &args.amount_max {
+            filters = filters.field("Max Amount", Text::new(format!("${}", am), Tone::Highlight));
+        }
+        if let Some(tm) = &args.total_min {
+            filters = filters.field("Min Total", Text::new(format!("${}", tm), Tone::Highlight));
+        }
+        if let Some(tm) = &args.total_max {
+            filters = filters.field("Max Total", Text::new(format!("${}", tm), Tone::Highlight));
+        }
+        if let Some(sm) = &args.shares_min {
+            filters = filters.field("Min Shares", Text::new(sm.clone(), Tone::Highlight));
+        }
+        if let Some(account) = &args.account {
+            filters = filters.field("Account", Text::new(account.clone(), Tone::Highlight));
+        }
+        if args.upcoming {
+            filters = filters.field("Upcoming Only", Text::new("Yes", Tone::Positive));
+        }
+
+        filters = filters.field("Sorted by", list_sort(args).to_string());
+
+        report.push(filters);
+    }
+
+    report
+}
+
+/// Filtered and sorted dividend records ready for display
+#[derive(Debug)]
+pub struct DividendListing<'a> {
+    /// The tracker the payments were listed from
+    pub tracker: &'a DividendTracker,
+    pub dividends: Vec<&'a Dividend>,
+    pub total_income: Decimal,
+    /// Fees deducted across the listed payments
+    pub total_fees: Decimal,
+    /// Tax withheld at source across the listed payments
+    pub total_withholding: Decimal,
+    /// Subtotals by ex-date year, oldest first
+    pub yearly_totals: Vec<YearTotal>,
+}
+
+/// Income and payment count for one year of a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearTotal {
+    pub year: i32,
+    pub total: Decimal,
+    pub payments: usize,
+}
+
+/// Translate the list arguments into a [`DividendFilter`]
+fn list_filter(args: &ListArgs, today: NaiveDate) -> Result<DividendFilter> {
+    let mut filter = DividendFilter::new();
+
+    if let Some(symbol) = &args.symbol {
+        filter = filter.symbol(symbol, args.symbol_match.into());
+    }
+    if let Some(year) = args.year {
+        filter = filter.year(year);
+    }
+    if let Some(month) = args.month {
+        filter = filter.month(month);
+    }
+    if let Some(quarter) = &args.quarter {
+        filter = filter.quarter(Quarter::parse(quarter)?);
+    }
+    if args.ttm {
+        filter = filter.since(filters::ttm_start(today)).until(today);
+    }
+    if let Some(ds) = &args.date_start {
+        filter = filter.since(parse_dividend_date(ds)?);
+    }
+    if let Some(de) = &args.date_end {
+        filter = filter.until(parse_dividend_date(de)?);
+    }
+    let amount_min = parse_limit(args.amount_min.as_deref(), "minimum amount")?;
+    let amount_max = parse_limit(args.amount_max.as_deref(), "maximum amount")?;
+    let total_min = parse_limit(args.total_min.as_deref(), "minimum total")?;
+    let total_max = parse_limit(args.total_max.as_deref(), "maximum total")?;
+    check_range(amount_min, amount_max, "--amount-min", "--amount-max")?;
+    check_range(total_min, total_max, "--total-min", "--total-max")?;
+
+    if let Some(amount) = amount_min {
+        filter = filter.amount_min(amount);
+    }
+    if let Some(amount) = amount_max {
+        filter = filter.amount_max(amount);
+    }
+    if let Some(total) = total_min {
+        filter = filter.total_min(total);
+    }
+    if let Some(total) = total_max {
+        filter = filter.total_max(total);
+    }
+    if let Some(shares) = parse_limit(args.shares_min.as_deref(), "minimum shares")? {
+        filter = filter.shares_min(shares);
+    }
+    if let Some(account) = &args.account {
+        filter = filter.account(account);
+    }
+    // Upcoming: future pay dates only
+    if args.upcoming {
+        filter = filter.paid_after(today);
+    }
+
+    Ok(filter)
+}
+
+/// Parse an optional decimal filter value, naming the filter in the error
+fn parse_limit(value: Option<&str>, name: &str) -> Result<Option<Decimal>> {
+    value
+        .map(|v| {
+            Decimal::from_str(v)
+                .map_err(|_| anyhow!("Invalid {} format: {}. Use decimal format like 0.50", name, v))
+        })
+        .transpose()
+}
+
+/// Reject a range whose minimum is above its maximum
+fn check_range(min: Option<Decimal>, max: Option<Decimal>, min_flag: &str, max_flag: &str) -> Result<()> {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(anyhow!(
+            "{} ({}) cannot be greater than {} ({})",
+            min_flag,
+            min,
+            max_flag,
+            max
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Sort keys for the listing, with `--reverse` applied
+fn list_sort(args: &ListArgs) -> SortSpec<ListSortField> {
+    if args.reverse {
+        args.sort_by.reversed()
+    } else {
+        args.sort_by.clone()
+    }
+}
+
+/// Apply the list filters and sort order to the tracker's dividends
+pub fn build_listing<'a>(tracker: &'a DividendTracker, args: &ListArgs) -> Result<DividendListing<'a>> {
+    let today = Local::now().naive_local().date();
+    let mut dividends = list_filter(args, today)?.apply(&tracker.dividends);
+
+    // Sort dividends
+    let sort = list_sort(args);
+    dividends.sort_by(|a, b| {
+        sort.compare(a, b, |field, a, b| match field {
+            ListSortField::Symbol => a.symbol.cmp(&b.symbol),
+            ListSortField::ExDate => a.ex_date.cmp(&b.ex_date),
+            ListSortField::PayDate => a.pay_date.cmp(&b.pay_date),
+            ListSortField::Amount => a.amount_per_share.cmp(&b.amount_per_share),
+            ListSortField::Total => a.total_amount.cmp(&b.total_amount),
+        })
+    });
+
+    let total_income = dividends.iter().map(|d| d.total_amount).sum();
+    let total_fees = dividends.iter().map(|d| d.fee_amount()).sum();
+    let total_withholding = dividends.iter().map(|d| d.withholding_amount()).sum();
+
+    let mut by_year: BTreeMap<i32, YearTotal> = BTreeMap::new();
+    for dividend in &dividends {
+        let year = dividend.ex_date.year();
+        let entry = by_year.entry(year).or_insert(YearTotal {
+            year,
+            total: Decimal::ZERO,
+            payments: 0,
+        });
+        entry.total += dividend.total_amount;
+        entry.payments += 1;
+    }
+
+    Ok(DividendListing {
+        tracker,
+        dividends,
+        total_income,
+        total_fees,
+        total_withholding,
+        yearly_totals: by_year.into_values().collect(),
+    })
+}
+
+/// Handle adding a new dividend record
+pub fn add(config: &CliConfig, args: AddArgs) -> Result<()> {
+    println!("{}", "Adding dividend record...".green().bold());
+
+    // Check, build and store the dividend in a single locked transaction
+    let persistence = config.create_persistence_manager()?;
+    persistence.with_tracker_mut(|tracker| {
+        // Duplicates are refused unless the force flag is used
+        let (dividend, guess) = match add_to_tracker(tracker, &args) {
+            Ok(added) => added,
+            Err(e) => {
+                if let Some(DuplicateDividend { existing }) = e.downcast_ref() {
+                    println!("{} Duplicate dividend found!", "⚠".yellow());
+                    println!("  Symbol: {}", existing.symbol.cyan());
+                    println!(
+                        "  Ex-date: {}",
+                        existing.ex_date.format("%Y-%m-%d").to_string().blue()
+                    );
+                    println!("  Amount: ${:.4} per share", existing.amount_per_share);
+                    println!("  Total: ${:.2}", existing.total_amount);
+                    println!();
+                    println!(
+                        "Use {} to override duplicate protection.",
+                        "--force".yellow()
+                    );
+                }
+                return Err(e);
+            }
+        };
+        let settings = Config::load().unwrap_or_default();
+        let symbol = dividend.symbol.clone();
+        if matched_identifier(tracker, &args.symbol).is_some() {
+            println!(
+                "{} Matched {} to holding {}",
+                "ℹ".blue(),
+                args.symbol.trim().to_uppercase(),
+                symbol.cyan()
+            );
+        }
+
+        // Validate against holdings if available
+        if let Some(holding) = tracker.holding_for(&dividend) {
+            println!("📊 Validating against holdings for {}...", symbol.cyan());
+            println!("  Holdings: {} shares", holding.shares);
+
+            match holding.shares_entitled_on(dividend.ex_date) {
+                Some(held) if held != dividend.shares_owned => println!(
+                    "{} Warning: Dividend shares ({}) differ from the {} shares held before the ex-date, per 'transactions list'",
+                    "⚠".yellow(),
+                    dividend.shares_owned,
+                    held
+                ),
+                Some(_) => {}
+                None if dividend.shares_owned > holding.shares => {
+                    println!(
+                        "{} Warning: Dividend shares ({}) exceed current holdings ({})",
+                        "⚠".yellow(),
+                        dividend.shares_owned,
+                        holding.shares
+                    );
+                    println!("  This may indicate a stock split or updated holdings needed.");
+                }
+                None => {}
+            }
+        } else {
+            println!(
+                "{} No holdings found for {}. Consider adding holdings first with 'holdings add'",
+                "ℹ".blue(),
+                symbol.cyan()
+            );
+        }
+
+        // Display dividend details for confirmation
+        println!();
+        println!("{}", "💰 Dividend Details".green().bold());
+        println!("  Symbol: {}", dividend.symbol.cyan());
+        if let Some(isin) = &dividend.isin {
+            println!("  ISIN: {}", isin);
+        }
+        if let Some(cusip) = &dividend.cusip {
+            println!("  CUSIP: {}", cusip);
+        }
+        if let Some(account) = &dividend.account {
+            println!("  Account: {}", account);
+        }
+        if !dividend.income_kind.is_dividend() {
+            println!("  Income kind: {}", dividend.income_kind.name());
+        }
+        println!(
+            "  Ex-date: {}",
+            dividend.ex_date.format("%Y-%m-%d").to_string().blue()
+        );
+        println!(
+            "  Pay-date: {}",
+            dividend.pay_date.format("%Y-%m-%d").to_string().blue()
+        );
+        println!("  Amount per share: ${:.4}", dividend.amount_per_share);
+        println!(
+            "  Shares owned: {}",
+            format_shares(dividend.shares_owned, &settings.display)
+        );
+        println!(
+            "  Total dividend: {}",
+            format_money(dividend.total_amount).green()
+        );
+        if let Some(fees) = dividend.fees {
+            println!(
+                "  Fees: {} (net {})",
+                format_money(fees),
+                format_money(dividend.net_amount()).green()
+            );
+        }
+        if let Some(withholding) = dividend.withholding_tax {
+            println!(
+                "  Withholding tax: {}{} (net {})",
+                format_money(withholding),
+                dividend
+                    .country
+                    .as_ref()
+                    .map(|c| format!(" to {}", c))
+                    .unwrap_or_default(),
+                format_money(dividend.cash_credited()).green()
+            );
+        }
+        if let Some(guess) = guess {
+            println!(
+                "  Tax classification: {:?} ({}; change it with 'tax classify')",
+                guess.classification, guess.reason
+            );
+        }
+
+        Ok(())
+    })?;
+
+    println!();
+    println!("{} Dividend record added successfully!", "✓".green());
+
+    Ok(())
+}
+
+/// CSV record for dividend import
+///
+/// Takes the columns `div export` writes as well as snake_case names, so an
+/// export can be imported again. Other columns (`Total Amount`) are ignored.
+#[derive(Debug, Deserialize)]
+struct DividendRecord {
+    #[serde(alias = "Symbol")]
+    symbol: String,
+    #[serde(default, alias = "Company Name")]
+    company_name: Option<String>,
+    #[serde(alias = "Ex Date")]
+    ex_date: String,
+    #[serde(alias = "Pay Date")]
+    pay_date: String,
+    #[serde(alias = "Amount Per Share", alias = "amount")]
+    amount_per_share: String,
+    #[serde(alias = "Shares Owned", alias = "shares")]
+    shares_owned: String,
+    #[serde(default, alias = "Dividend Type")]
+    dividend_type: Option<DividendType>,
+    #[serde(default, alias = "ISIN")]
+    isin: Option<String>,
+    #[serde(default, alias = "CUSIP")]
+    cusip: Option<String>,
+    #[serde(default, alias = "Account")]
+    account: Option<String>,
+}
+
+impl DividendRecord {
+    /// The same dividend as `div add` would be given it
+    fn add_args(&self) -> AddArgs {
+        let given = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        AddArgs {
+            symbol: self.symbol.clone(),
+            ex_date: self.ex_date.trim().to_string(),
+            pay_date: self.pay_date.trim().to_string(),
+            amount: self.amount_per_share.trim().to_string(),
+            shares: self.shares_owned.trim().to_string(),
+            fee: None,
+            withholding: None,
+            country: None,
+            isin: given(&self.isin),
+            cusip: given(&self.cusip),
+            account: given(&self.account),
+            kind: IncomeKindArg::Dividend,
+            classification: None,
+            force: false,
+        }
+    }
+}
+
+/// Import dividends from a CSV file in one transaction
+///
+/// Rows are built and added as `div add` adds them; a row matching a recorded
+/// dividend (same symbol and ex-date) is skipped, and a bad row aborts the
+/// import.
+fn import(config: &CliConfig, file: &str) -> Result<()> {
+    println!("{}", "Importing dividends from CSV...".green().bold());
+
+    if !Path::new(file).exists() {
+        bail!("File not found: {}", file);
+    }
+
+    let rows = Reader::from_path(file)?.records().count();
+    let mut reader = Reader::from_path(file)?;
+    let pb = progress::bar(rows, "Importing dividends");
+
+    let settings = Config::load().unwrap_or_default();
+    let persistence = config.create_persistence_manager()?;
+    let result = persistence.with_tracker_mut(|tracker| {
+        let (mut imported, mut skipped) = (0, 0);
+        for (index, record) in pb.wrap_iter(reader.deserialize::<DividendRecord>()).enumerate() {
+            // Row 1 is the header
+            let row = index + 2;
+            let record = record.with_context(|| format!("Row {}", row))?;
+            let mut dividend = new_dividend(tracker, &record.add_args()).with_context(|| format!("Row {}", row))?;
+            dividend.company_name = record.company_name.filter(|name| !name.trim().is_empty());
+            if let Some(dividend_type) = record.dividend_type {
+                dividend.dividend_type = dividend_type;
+            }
+            match tracker.add_new_dividend(dividend, false, &settings.classification) {
+                Ok((dividend, _)) => {
+                    imported += 1;
+                    config.print_verbose(&format!("Imported {} {}", dividend.symbol, dividend.ex_date));
+                }
+                Err(e) if e.downcast_ref::<DuplicateDividend>().is_some() => {
+                    skipped += 1;
+                    pb.suspend(|| println!("  {} {}", "Skipped".yellow(), e));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((imported, skipped))
+    });
+    pb.finish_and_clear();
+    let (imported, skipped) = result?;
+
+    println!();
+    println!("{}", "Import completed successfully!".green().bold());
+    println!("  {} dividends imported", imported.to_string().green());
+    println!("  {} duplicates skipped", skipped.to_string().yellow());
+
+    Ok(())
+}
+
+/// Holding whose ISIN or CUSIP was given in place of its symbol
+fn matched_identifier<'a>(tracker: &'a DividendTracker, symbol: &str) -> Option<&'a Holding> {
+    // Brokers outside the US often report the ISIN instead of the ticker
+    tracker
+        .find_holding_by_identifier(symbol)
+        .filter(|_| !tracker.holds(&symbol.trim().to_uppercase()))
+}
+
+/// Build the dividend described by `args` and add it to the tracker
+///
+/// `div add`, `--rpc` and `POST /dividends` all add through here, so they
+/// check duplicates and classify alike; see
+/// [`DividendTracker::add_new_dividend`].
+pub fn add_to_tracker(tracker: &mut DividendTracker, args: &AddArgs) -> Result<(Dividend, Option<Guess>)> {
+    let dividend = new_dividend(tracker, args)?;
+    let settings = Config::load().unwrap_or_default();
+    tracker.add_new_dividend(dividend, args.force, &settings.classification)
+}
+
+/// Build the dividend described by `args` without storing it
+///
+/// The symbol is resolved against the tracker's holdings and aliases, and
+/// identifiers default to the holding's. Duplicates are not checked here.
+pub fn new_dividend(tracker: &DividendTracker, args: &AddArgs) -> Result<Dividend> {
+    // Parse and validate inputs
+    let ex_date = parse_dividend_date(&args.ex_date)?;
+    let pay_date = parse_dividend_date(&args.pay_date)?;
+
+    let amount = Decimal::from_str(&args.amount).map_err(|_| {
+        anyhow!(
+            "Invalid amount format: {}. Use decimal format like 0.94",
+            args.amount
+        )
+    })?;
+
+    let shares = Decimal::from_str(&args.shares).map_err(|_| {
+        anyhow!(
+            "Invalid shares format: {}. Use decimal format like 100",
+            args.shares
+        )
+    })?;
+
+    let fee = args
+        .fee
+        .as_ref()
+        .map(|fee| {
+            Decimal::from_str(fee).map_err(|_| {
+                anyhow!("Invalid fee format: {}. Use decimal format like 0.75", fee)
+            })
+        })
+        .transpose()?;
+
+    let symbol = match matched_identifier(tracker, &args.symbol) {
+        Some(holding) => holding.symbol.clone(),
+        None => tracker.canonical_symbol(&args.symbol),
+    };
+
+    let mut dividend = Dividend::new(
+        symbol,
+        None, // company_name
+        ex_date,
+        pay_date,
+        amount,
+        shares,
+        DividendType::Regular,
+    )?;
+    if let Some(fee) = fee {
+        dividend.set_fees(fee)?;
+    }
+    if let Some(withholding) = &args.withholding {
+        let amount = if withholding.trim().ends_with('%') {
+            (dividend.total_amount * parse_percentage(withholding)?).round_dp(2)
+        } else {
+            Decimal::from_str(withholding.trim()).map_err(|_| {
+                anyhow!(
+                    "Invalid withholding format: {}. Use an amount like 12.50 or a rate like 15%",
+                    withholding
+                )
+            })?
+        };
+        dividend.set_withholding(amount)?;
+    }
+    if let Some(country) = &args.country {
+        dividend.set_country(country)?;
+    }
+    let account = args.account.as_ref().map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+    let position = tracker
+        .position_key(&dividend.symbol, account.as_deref())?
+        .and_then(|key| tracker.holdings.get(&key));
+    if let Some(holding) = position.or_else(|| tracker.holdings_of(&dividend.symbol).first().copied()) {
+        dividend.isin = holding.isin.clone();
+        dividend.cusip = holding.cusip.clone();
+    }
+    dividend.set_identifiers(args.isin.as_deref(), args.cusip.as_deref())?;
+    // Paid into the holding's account unless told otherwise
+    dividend.account = account.or_else(|| position.and_then(|h| h.account.clone()));
+    dividend.income_kind = args.kind.into();
+    if let Some(classification) = args.classification {
+        dividend.tax_classification = classification.into();
+    }
+
+    Ok(dividend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Block;
+    use rust_decimal_macros::dec;
+
+    fn list_args() -> ListArgs {
+        ListArgs {
+            symbol: None,
+            symbol_match: SymbolMatchArg::Exact,
+            year: None,
+            month: None,
+            quarter: None,
+            ttm: false,
+            date_start: None,
+            date_end: None,
+            amount_min: None,
+            amount_max: None,
+            total_min: None,
+            total_max: None,
+            shares_min: None,
+            account: None,
+            include_archived: false,
+            upcoming: false,
+            sort_by: SortSpec::by(ListSortField::ExDate),
+            reverse: false,
+            export_csv: None,
+        }
+    }
+
+    fn tracker() -> DividendTracker {
+        let mut tracker = DividendTracker::new();
+        for (symbol, month, amount) in [("MSFT", 3, dec!(0.75)), ("AAPL", 2, dec!(0.24)), ("AAPL", 5, dec!(0.25))] {
+            let ex_date = NaiveDate::from_ymd_opt(2024, month, 10).unwrap();
+            let pay_date = NaiveDate::from_ymd_opt(2024, month, 15).unwrap();
+            tracker.add_dividend(
+                Dividend::new(
+                    symbol.to_string(),
+                    None,
+                    ex_date,
+                    pay_date,
+                    amount,
+                    dec!(100),
+                    DividendType::Regular,
+                )
+                .unwrap(),
+            );
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_build_listing_filters_and_totals() {
+        let tracker = tracker();
+        let args = ListArgs {
+            symbol: Some("aapl".to_string()),
+            ..list_args()
+        };
+
+        let listing = build_listing(&tracker, &args).unwrap();
+        assert_eq!(listing.dividends.len(), 2);
+        assert_eq!(listing.total_income, dec!(49));
+    }
+
+    #[test]
+    fn test_build_listing_sorts() {
+        let tracker = tracker();
+        let args = ListArgs {
+            sort_by: SortSpec::by(ListSortField::Amount),
+            reverse: true,
+            ..list_args()
+        };
+
+        let listing = build_listing(&tracker, &args).unwrap();
+        let symbols: Vec<_> = listing.dividends.iter().map(|d| d.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["MSFT", "AAPL", "AAPL"]);
+        assert_eq!(listing.dividends[1].amount_per_share, dec!(0.25));
+    }
+
+    #[test]
+    fn test_build_listing_filters_by_quarter() {
+        let tracker = tracker();
+        let args = ListArgs {
+            quarter: Some("q1-2024".to_string()),
+            ..list_args()
+        };
+
+        let listing = build_listing(&tracker, &args).unwrap();
+        let symbols: Vec<_> = listing.dividends.iter().map(|d| d.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["AAPL", "MSFT"]);
+
+        let args = ListArgs {
+            quarter: Some("Q5-2024".to_string()),
+            ..list_args()
+        };
+        assert!(build_listing(&tracker, &args).is_err());
+    }
+
+    #[test]
+    fn test_build_listing_total_and_share_ranges() {
+        let tracker = tracker();
+        let args = ListArgs {
+            total_min: Some("25".to_string()),
+            total_max: Some("75".to_string()),
+            shares_min: Some("100".to_string()),
+            ..list_args()
+        };
+
+        let listing = build_listing(&tracker, &args).unwrap();
+        let totals: Vec<_> = listing.dividends.iter().map(|d| d.total_amount).collect();
+        assert_eq!(totals, vec![dec!(75), dec!(25)]);
+
+        let args = ListArgs {
+            amount_min: Some("0.50".to_string()),
+            amount_max: Some("0.25".to_string()),
+            ..list_args()
+        };
+        let err = build_listing(&tracker, &args).unwrap_err();
+        assert!(err.to_string().contains("--amount-min (0.50) cannot be greater than --amount-max (0.25)"));
+    }
+
+    #[test]
+    fn test_listing_subtotals_each_year() {
+        let mut tracker = tracker();
+        let ex_date = NaiveDate::from_ymd_opt(2023, 11, 10).unwrap();
+        tracker.add_dividend(
+            Dividend::new("MSFT".to_string(), None, ex_date, ex_date, dec!(0.68), dec!(100), DividendType::Regular)
+                .unwrap(),
+        );
+
+        let args = list_args();
+        let listing = build_listing(&tracker, &args).unwrap();
+        assert_eq!(
+            listing.yearly_totals,
+            vec![
+                YearTotal { year: 2023, total: dec!(68), payments: 1 },
+                YearTotal { year: 2024, total: dec!(124), payments: 3 },
+            ]
+        );
+
+        let today = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let report = listing_report(&listing, &args, today, &DisplaySettings::default());
+        let Block::Fields(fields) = &report.sections[0].blocks[1] else {
+            panic!("expected the totals after the table");
+        };
+        let labels: Vec<_> = fields.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels, vec!["2023 Subtotal", "2024 Subtotal", "Total Dividends", "Number of Payments"]);
+    }
+
+    #[test]
+    fn test_build_listing_rejects_bad_amount() {
+        let tracker = tracker();
+        let args = ListArgs {
+            amount_min: Some("abc".to_string()),
+            ..list_args()
+        };
+
+        assert!(build_listing(&tracker, &args).is_err());
+    }
+
+    #[test]
+    fn test_listing_report_marks_upcoming_and_filters() {
+        let tracker = tracker();
+        let args = ListArgs {
+            symbol: Some("AAPL".to_string()),
+            ..list_args()
+        };
+        let listing = build_listing(&tracker, &args).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+
+        let report = listing_report(&listing, &args, today, &DisplaySettings::default());
+
+        let Block::Table(table) = &report.sections[0].blocks[0] else {
+            panic!("expected the dividend table first");
+        };
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][0].tone, Tone::Plain);
+        assert_eq!(table.rows[1][0].tone, Tone::Positive);
+        assert_eq!(table.rows[1][6].text, "$25.00");
+
+        assert_eq!(report.sections[1].title, "Applied Filters");
+    }
+}
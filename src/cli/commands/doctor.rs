@@ -0,0 +1,375 @@
+//! Health check command
+//!
+//! `doctor` looks at everything outside the data itself that can make the
+//! tracker misbehave: the data directory, the configuration, the API key,
+//! the data file's schema, the backups and the system clock. Every problem
+//! comes with a fix, and the report is what bug reports should include.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local};
+
+use super::CliConfig;
+use crate::api::{AlphaVantageClient, ApiError};
+use crate::cli::DoctorArgs;
+use crate::config::Config;
+use crate::persistence::{PersistenceManager, SCHEMA_VERSION};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+/// Clock differences below this are not worth mentioning
+const CLOCK_TOLERANCE_MINUTES: i64 = 5;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warning,
+    Failed,
+}
+
+/// One line of the report
+#[derive(Debug, Clone)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    details: String,
+    /// What to do about a warning or failure
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, details: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Ok,
+            details: details.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, details: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Warning,
+            details: details.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn failed(name: &'static str, details: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Failed,
+            details: details.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run every check and print the report; fails if any check failed
+pub fn run(config: &CliConfig, args: DoctorArgs) -> Result<()> {
+    let data_dir = config.resolve_data_dir()?;
+    let persistence = config.create_persistence_manager()?;
+    let now = Local::now();
+
+    let mut checks = vec![check_data_dir(&persistence, &data_dir)];
+    let (config_check, settings) = check_config();
+    checks.push(config_check);
+    let (api_check, server_time) = check_api(settings.as_ref(), args.ping);
+    checks.push(api_check);
+    let data_file = persistence.inspect_data_file();
+    let last_saved = data_file
+        .as_ref()
+        .ok()
+        .and_then(|info| info.as_ref())
+        .and_then(|info| DateTime::parse_from_rfc3339(&info.last_saved).ok());
+    checks.push(match data_file {
+        Ok(info) => check_schema(info.map(|info| (info.schema_version, info.app_version))),
+        Err(e) => Check::failed(
+            "Data file",
+            format!("{:#}", e),
+            "Run any command to recover from the newest good backup, or restore one with `data load <backup>`",
+        ),
+    });
+    checks.push(check_backups(&persistence));
+    checks.push(check_clock(now.fixed_offset(), last_saved, server_time));
+
+    let failed = checks.iter().filter(|c| c.status == Status::Failed).count();
+    config.render(&doctor_report(&checks, &data_dir.display().to_string()))?;
+    if failed > 0 {
+        bail!("{} of {} checks failed", failed, checks.len());
+    }
+    Ok(())
+}
+
+fn check_data_dir(persistence: &PersistenceManager, data_dir: &std::path::Path) -> Check {
+    const NAME: &str = "Data directory";
+    if !data_dir.exists() {
+        return Check::warning(
+            NAME,
+            format!("{} does not exist yet", data_dir.display()),
+            "Run `dividend-tracker init` to create it",
+        );
+    }
+    if !data_dir.is_dir() {
+        return Check::failed(
+            NAME,
+            format!("{} is not a directory", data_dir.display()),
+            "Move the file aside or pass a different --data-dir",
+        );
+    }
+    if let Err(e) = persistence.check_writable() {
+        return Check::failed(
+            NAME,
+            format!("{:#}", e),
+            format!("Make {} and its backups folder writable by your user", data_dir.display()),
+        );
+    }
+    if let Some(age) = persistence.lock_age() {
+        return Check::warning(
+            NAME,
+            format!("Locked for {}s by another process", age.as_secs()),
+            format!(
+                "If no other dividend-tracker is running, delete {}",
+                data_dir.join(".lock").display()
+            ),
+        );
+    }
+    Check::ok(NAME, format!("{} is writable", data_dir.display()))
+}
+
+/// Parse `config.toml` and sanity-check its values
+fn check_config() -> (Check, Option<Config>) {
+    const NAME: &str = "Configuration";
+    let file = match Config::config_file() {
+        Ok(file) => file,
+        Err(e) => return (Check::failed(NAME, format!("{:#}", e), "Set HOME (or APPDATA on Windows)"), None),
+    };
+    let settings = match Config::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            return (
+                Check::failed(
+                    NAME,
+                    format!("{} is invalid: {}", file.display(), e),
+                    "Fix the file, or move it aside and run `dividend-tracker init`",
+                ),
+                None,
+            )
+        }
+    };
+
+    let problems = settings.problems();
+    let check = if let Some(dir) = settings.storage.data_dir.as_ref().filter(|dir| !dir.exists()) {
+        Check::warning(
+            NAME,
+            format!("storage.data_dir {} does not exist", dir.display()),
+            "Create the directory or run `dividend-tracker init` to choose another",
+        )
+    } else if let Some(problem) = problems.first() {
+        let details = match problems.len() {
+            1 => problem.clone(),
+            n => format!("{} (and {} more)", problem, n - 1),
+        };
+        Check::warning(
+            NAME,
+            details,
+            "See all problems with `dividend-tracker configure validate` and fix them with `configure edit`",
+        )
+    } else if file.exists() {
+        Check::ok(NAME, format!("{} is valid", file.display()))
+    } else {
+        Check::ok(NAME, "No config.toml, using defaults")
+    };
+    (check, Some(settings))
+}
+
+/// Look for an API key and, with `ping`, try it
+fn check_api(settings: Option<&Config>, ping: bool) -> (Check, Option<DateTime<FixedOffset>>) {
+    const NAME: &str = "API key";
+    let Some((settings, key)) = settings.and_then(|settings| Some((settings, settings.get_api_key().ok()?))) else {
+        return (
+            Check::warning(
+                NAME,
+                "No Alpha Vantage key; `fetch` won't work",
+                "Get a free key at alphavantage.co and run `dividend-tracker configure --api-key KEY`",
+            ),
+            None,
+        );
+    };
+    if !ping {
+        return (Check::ok(NAME, "Set (not checked; add --ping to try it)"), None);
+    }
+
+    let result = AlphaVantageClient::new(key, &settings.api).and_then(|client| client.ping());
+    match result {
+        Ok(server_time) => (Check::ok(NAME, "Alpha Vantage accepted the key"), server_time),
+        Err(e) => {
+            let hint = e.downcast_ref::<ApiError>().map_or(
+                "Check your network connection and the key with `dividend-tracker configure --show`",
+                ApiError::remediation,
+            );
+            (Check::failed(NAME, format!("{:#}", e), hint), None)
+        }
+    }
+}
+
+/// Compare the data file's schema version with this build's
+fn check_schema(info: Option<(u32, String)>) -> Check {
+    const NAME: &str = "Data file";
+    match info {
+        None => Check::ok(NAME, "No data saved yet"),
+        Some((version, app_version)) if version > SCHEMA_VERSION => Check::failed(
+            NAME,
+            format!(
+                "Schema version {} was written by dividend-tracker {}, newer than this one",
+                version, app_version
+            ),
+            format!("Upgrade to dividend-tracker {} or later", app_version),
+        ),
+        Some((version, _)) if version < SCHEMA_VERSION => Check::warning(
+            NAME,
+            format!("Schema version {} is older than {}", version, SCHEMA_VERSION),
+            "Run any command that saves, e.g. `data backup`, to migrate it",
+        ),
+        Some((version, app_version)) => Check::ok(
+            NAME,
+            format!("Schema version {} (saved by {})", version, app_version),
+        ),
+    }
+}
+
+/// Make sure every backup could actually be restored
+fn check_backups(persistence: &PersistenceManager) -> Check {
+    const NAME: &str = "Backups";
+    let backups = match persistence.verify_backups() {
+        Ok(backups) => backups,
+        Err(e) => {
+            return Check::failed(NAME, format!("{:#}", e), "Make the backups folder readable by your user");
+        }
+    };
+    let broken: Vec<String> = backups
+        .iter()
+        .filter(|(_, problem)| problem.is_some())
+        .filter_map(|(path, _)| path.file_name().map(|name| name.to_string_lossy().to_string()))
+        .collect();
+
+    if backups.is_empty() {
+        Check::ok(NAME, "None yet")
+    } else if broken.is_empty() {
+        Check::ok(NAME, format!("{} backups, all readable", backups.len()))
+    } else if broken.len() == backups.len() {
+        Check::failed(
+            NAME,
+            format!("None of the {} backups can be restored", backups.len()),
+            "Run `dividend-tracker data backup` to make a good one",
+        )
+    } else {
+        Check::warning(
+            NAME,
+            format!("{} of {} can't be restored: {}", broken.len(), backups.len(), broken.join(", ")),
+            "Delete the broken files from the backups folder",
+        )
+    }
+}
+
+/// Look for a clock that is far off or has moved back
+fn check_clock(
+    now: DateTime<FixedOffset>,
+    last_saved: Option<DateTime<FixedOffset>>,
+    server_time: Option<DateTime<FixedOffset>>,
+) -> Check {
+    const NAME: &str = "Clock";
+    const FIX: &str = "Set the system date and time, or turn on automatic time sync";
+    let tolerance = Duration::minutes(CLOCK_TOLERANCE_MINUTES);
+
+    if now.year() < 2020 {
+        return Check::failed(NAME, format!("System clock reads {}", now.format("%Y-%m-%d %H:%M")), FIX);
+    }
+    if let Some(server_time) = server_time {
+        let skew = now.signed_duration_since(server_time);
+        if skew.abs() > tolerance {
+            return Check::warning(
+                NAME,
+                format!("{} minutes off the API server's clock", skew.num_minutes()),
+                FIX,
+            );
+        }
+    }
+    if let Some(saved) = last_saved.filter(|saved| *saved > now + tolerance) {
+        return Check::warning(
+            NAME,
+            format!("Data was last saved at {}, after the current time", saved.format("%Y-%m-%d %H:%M")),
+            FIX,
+        );
+    }
+    Check::ok(NAME, now.format("%Y-%m-%d %H:%M %:z").to_string())
+}
+
+fn doctor_report(checks: &[Check], data_dir: &str) -> Report {
+    let mut table = TableView::new(&["Check", "Status", "Details"]);
+    for check in checks {
+        let status = match check.status {
+            Status::Ok => Text::new("ok", Tone::Positive),
+            Status::Warning => Text::new("warning", Tone::Warning),
+            Status::Failed => Text::new("failed", Tone::Negative),
+        };
+        table.row(vec![check.name.into(), status, check.details.clone().into()]);
+    }
+
+    let environment = Section::new("Environment")
+        .icon("🔧")
+        .field("Version", env!("CARGO_PKG_VERSION"))
+        .field("Platform", format!("{} {}", std::env::consts::OS, std::env::consts::ARCH))
+        .field("Data Directory", data_dir);
+
+    let mut fixes = Section::new("Fixes").icon("💡");
+    for check in checks {
+        if let Some(fix) = &check.fix {
+            fixes = fixes.note(NoteKind::Tip, format!("{}: {}", check.name, fix));
+        }
+    }
+
+    let mut report = Report::new("Dividend Tracker Health Check");
+    report.sections.push(environment);
+    report.sections.push(Section::new("Checks").icon("🩺").table(table));
+    if !fixes.blocks.is_empty() {
+        report.sections.push(fixes);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(text).unwrap()
+    }
+
+    #[test]
+    fn test_check_clock_flags_skew_and_saves_from_the_future() {
+        let now = at("2024-08-15T12:00:00+02:00");
+
+        assert_eq!(check_clock(now, Some(at("2024-08-15T09:00:00Z")), None).status, Status::Ok);
+        assert_eq!(check_clock(now, None, Some(at("2024-08-15T10:03:00Z"))).status, Status::Ok);
+
+        let skewed = check_clock(now, None, Some(at("2024-08-15T11:00:00Z")));
+        assert_eq!(skewed.status, Status::Warning);
+        assert_eq!(skewed.details, "-60 minutes off the API server's clock");
+
+        let moved_back = check_clock(now, Some(at("2024-09-01T08:00:00+02:00")), None);
+        assert_eq!(moved_back.status, Status::Warning);
+        assert!(moved_back.fix.is_some());
+
+        assert_eq!(check_clock(at("1970-01-01T00:00:00Z"), None, None).status, Status::Failed);
+    }
+
+    #[test]
+    fn test_check_schema_compares_with_this_build() {
+        assert_eq!(check_schema(None).status, Status::Ok);
+        assert_eq!(check_schema(Some((SCHEMA_VERSION, "0.1.0".into()))).status, Status::Ok);
+        let newer = check_schema(Some((SCHEMA_VERSION + 1, "9.0.0".into())));
+        assert_eq!(newer.status, Status::Failed);
+        assert_eq!(newer.fix.as_deref(), Some("Upgrade to dividend-tracker 9.0.0 or later"));
+    }
+}
@@ -0,0 +1,380 @@
+//! Living expenses and dividend coverage commands
+
+use anyhow::Result;
+use chrono::{Datelike, Local, Months, NaiveDate};
+use colored::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::{parse_amount, parse_dividend_date, CliConfig};
+use crate::cli::{ExpenseCommands, ExpensesArgs};
+use crate::format::{format_money, format_percent};
+use crate::models::{DividendTracker, ExpenseBudget, PlannedExpense};
+use crate::projections::GrowthScenario;
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+use crate::run_rate::ForwardIncome;
+
+const TITLE: &str = "Expense Coverage";
+
+/// Months of received and projected income in the coverage table
+const COVERAGE_MONTHS: u32 = 12;
+
+/// Crossovers further out than this are reported as never
+const MAX_CROSSOVER_YEARS: f64 = 100.0;
+
+/// Handle expenses commands
+pub fn run(config: &CliConfig, args: ExpensesArgs) -> Result<()> {
+    let ExpensesArgs {
+        command,
+        growth_rate,
+        inflation,
+    } = args;
+
+    match command {
+        Some(ExpenseCommands::Set { amount }) => set(config, &amount),
+        Some(ExpenseCommands::Clear) => clear(config),
+        Some(ExpenseCommands::Plan { amount, date, note }) => plan(config, &amount, &date, note),
+        Some(ExpenseCommands::Unplan { date }) => unplan(config, &date),
+        None => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let today = Local::now().naive_local().date();
+            let Some(budget) = &tracker.expenses else {
+                return config.render(&Report::warning(
+                    TITLE,
+                    "No expenses set. Set them with 'expenses set 4500/month'.",
+                ));
+            };
+            let coverage = ExpenseCoverage::calculate(&tracker, budget, today);
+            config.render(&coverage_report(&coverage, &growth_rate, inflation))
+        }
+    }
+}
+
+fn set(config: &CliConfig, amount: &str) -> Result<()> {
+    let budget = ExpenseBudget::parse(amount, Local::now().naive_local().date())?;
+
+    let persistence = config.create_persistence_manager()?;
+    persistence.with_tracker_mut(|tracker| {
+        tracker.expenses = Some(budget.clone());
+        Ok(())
+    })?;
+
+    println!(
+        "{} Expenses set to {}/month ({}/year)",
+        "✓".green(),
+        format_money(budget.monthly).green(),
+        format_money(budget.annual())
+    );
+    Ok(())
+}
+
+fn clear(config: &CliConfig) -> Result<()> {
+    let persistence = config.create_persistence_manager()?;
+    let previous = persistence.with_tracker_mut(|tracker| Ok(tracker.expenses.take()))?;
+
+    match previous {
+        Some(budget) => println!(
+            "{} Removed expenses of {}/month",
+            "✓".green(),
+            format_money(budget.monthly)
+        ),
+        None => println!("No expenses were set"),
+    }
+    Ok(())
+}
+
+fn plan(config: &CliConfig, amount: &str, date: &str, note: Option<String>) -> Result<()> {
+    let expense = PlannedExpense {
+        date: parse_dividend_date(date)?,
+        amount: parse_amount(amount, "amount")?,
+        note,
+    };
+
+    let persistence = config.create_persistence_manager()?;
+    persistence.with_tracker_mut(|tracker| tracker.plan_expense(expense.clone()))?;
+
+    println!(
+        "{} Planned {}{} on {}",
+        "✓".green(),
+        format_money(expense.amount).green(),
+        expense.note.as_ref().map(|n| format!(" for {}", n)).unwrap_or_default(),
+        expense.date.format("%Y-%m-%d").to_string().blue()
+    );
+    println!("  'calendar' shows whether dividends expected by then cover it.");
+    Ok(())
+}
+
+fn unplan(config: &CliConfig, date: &str) -> Result<()> {
+    let date = parse_dividend_date(date)?;
+
+    let persistence = config.create_persistence_manager()?;
+    let removed = persistence.with_tracker_mut(|tracker| Ok(tracker.remove_planned_expenses(date)))?;
+
+    if removed.is_empty() {
+        println!("No expenses were planned on {}", date.format("%Y-%m-%d"));
+    } else {
+        let total: Decimal = removed.iter().map(|e| e.amount).sum();
+        println!(
+            "{} Removed {} planned on {}",
+            "✓".green(),
+            format_money(total),
+            date.format("%Y-%m-%d")
+        );
+    }
+    Ok(())
+}
+
+/// One month of dividend income measured against expenses
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageMonth {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub income: Decimal,
+    /// Whether the income is projected rather than received
+    pub projected: bool,
+}
+
+/// When dividend income is expected to cover expenses
+#[derive(Debug, Clone, PartialEq)]
+pub enum Crossover {
+    /// Forward income already covers expenses
+    Covered,
+    /// Projected month in which forward income overtakes expenses
+    On(NaiveDate),
+    /// Income never catches up at the assumed rates
+    Never,
+}
+
+/// Dividend income measured against living expenses
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpenseCoverage {
+    pub as_of: NaiveDate,
+    pub monthly_expenses: Decimal,
+    /// Forward annual income run-rate on `as_of`
+    pub forward_annual: Decimal,
+    /// The last twelve complete months received, then twelve projected
+    pub months: Vec<CoverageMonth>,
+}
+
+impl ExpenseCoverage {
+    /// Measure income against `budget` as of `as_of`
+    ///
+    /// Projected months repeat the pattern received a year earlier, scaled so
+    /// the year adds up to the forward run-rate; without a pattern the
+    /// run-rate is spread evenly.
+    pub fn calculate(tracker: &DividendTracker, budget: &ExpenseBudget, as_of: NaiveDate) -> Self {
+        let forward_annual = ForwardIncome::calculate(tracker, as_of).total();
+        let this_month = as_of.with_day(1).unwrap_or(as_of);
+        let received_in = |month: NaiveDate| -> Decimal {
+            tracker
+                .dividends
+                .iter()
+                .filter(|d| d.pay_date.year() == month.year() && d.pay_date.month() == month.month())
+                .map(|d| d.total_amount)
+                .sum()
+        };
+
+        let mut months = Vec::new();
+        for back in (1..=COVERAGE_MONTHS).rev() {
+            let month = this_month - Months::new(back);
+            months.push(CoverageMonth {
+                month,
+                income: received_in(month),
+                projected: false,
+            });
+        }
+
+        let trailing: Decimal = months.iter().map(|m| m.income).sum();
+        for ahead in 0..COVERAGE_MONTHS {
+            let month = this_month + Months::new(ahead);
+            let income = if trailing.is_zero() {
+                forward_annual / Decimal::from(12)
+            } else {
+                received_in(month - Months::new(12)) * forward_annual / trailing
+            };
+            months.push(CoverageMonth {
+                month,
+                income,
+                projected: true,
+            });
+        }
+
+        ExpenseCoverage {
+            as_of,
+            monthly_expenses: budget.monthly,
+            forward_annual,
+            months,
+        }
+    }
+
+    /// Share of expenses covered by the forward run-rate, in percent
+    pub fn forward_coverage(&self) -> Decimal {
+        percent_of(self.forward_annual / Decimal::from(12), self.monthly_expenses)
+    }
+
+    /// Income received over the last twelve complete months
+    pub fn trailing_income(&self) -> Decimal {
+        self.months.iter().filter(|m| !m.projected).map(|m| m.income).sum()
+    }
+
+    /// When forward income overtakes expenses if income grows at `growth` and
+    /// expenses at `inflation` a year, both as fractions
+    pub fn crossover(&self, growth: Decimal, inflation: Decimal) -> Crossover {
+        let expenses = self.monthly_expenses * Decimal::from(12);
+        if self.forward_annual >= expenses {
+            return Crossover::Covered;
+        }
+
+        let relative_growth = ((Decimal::ONE + growth) / (Decimal::ONE + inflation)).to_f64().unwrap_or(1.0);
+        if self.forward_annual.is_zero() || relative_growth <= 1.0 {
+            return Crossover::Never;
+        }
+
+        let gap = (expenses / self.forward_annual).to_f64().unwrap_or(f64::INFINITY);
+        let years = gap.ln() / relative_growth.ln();
+        if years > MAX_CROSSOVER_YEARS {
+            return Crossover::Never;
+        }
+        let months = (years * 12.0).ceil() as u32;
+        match self.as_of.checked_add_months(Months::new(months)) {
+            Some(date) => Crossover::On(date),
+            None => Crossover::Never,
+        }
+    }
+}
+
+fn percent_of(income: Decimal, expenses: Decimal) -> Decimal {
+    if expenses.is_zero() {
+        Decimal::ZERO
+    } else {
+        income / expenses * Decimal::from(100)
+    }
+}
+
+fn coverage_text(percent: Decimal) -> Text {
+    let tone = if percent >= Decimal::from(100) {
+        Tone::Positive
+    } else if percent >= Decimal::from(50) {
+        Tone::Warning
+    } else {
+        Tone::Plain
+    };
+    Text::new(format_percent(percent, 1), tone)
+}
+
+/// Build the coverage view: coverage now, per month, and the projected crossover
+pub fn coverage_report(coverage: &ExpenseCoverage, growth: &GrowthScenario, inflation: Decimal) -> Report {
+    let expenses = coverage.monthly_expenses;
+    let trailing = coverage.trailing_income();
+
+    let now = Section::new("Coverage Now")
+        .icon("🎯")
+        .field("Monthly Expenses", Text::new(format_money(expenses), Tone::Highlight))
+        .field(
+            "Forward Income",
+            format!("{}/month", format_money(coverage.forward_annual / Decimal::from(12))),
+        )
+        .field("Covered by Forward Income", coverage_text(coverage.forward_coverage()))
+        .field(
+            "Last 12 Months",
+            format!("{}/month received", format_money(trailing / Decimal::from(12))),
+        )
+        .field(
+            "Covered by Last 12 Months",
+            coverage_text(percent_of(trailing / Decimal::from(12), expenses)),
+        );
+
+    let mut table = TableView::new(&["Month", "Income", "Expenses", "Coverage", "Basis"]);
+    for month in &coverage.months {
+        table.row(vec![
+            month.month.format("%Y-%m").to_string().into(),
+            format_money(month.income).into(),
+            format_money(expenses).into(),
+            coverage_text(percent_of(month.income, expenses)),
+            if month.projected { "projected" } else { "received" }.into(),
+        ]);
+    }
+    let monthly = Section::new("Monthly Coverage")
+        .icon("📅")
+        .table(table)
+        .note(
+            NoteKind::Info,
+            "Projected months follow last year's payment pattern, scaled to the forward run-rate.",
+        );
+
+    let mut crossover = Section::new("Crossover").icon("🏁");
+    crossover = match coverage.crossover(growth.get_growth_rate(), inflation) {
+        Crossover::Covered => crossover.field(
+            "Projected Crossover",
+            Text::new("Reached - dividends cover expenses", Tone::Positive),
+        ),
+        Crossover::On(date) => {
+            let months = (date.year() - coverage.as_of.year()) * 12 + date.month() as i32
+                - coverage.as_of.month() as i32;
+            crossover
+                .field("Projected Crossover", Text::new(date.format("%Y-%m").to_string(), Tone::Highlight))
+                .field("Time to Go", format!("{} years {} months", months / 12, months % 12))
+        }
+        Crossover::Never if coverage.forward_annual.is_zero() => crossover.field(
+            "Projected Crossover",
+            Text::new("Needs dividend income first", Tone::Warning),
+        ),
+        Crossover::Never => crossover.field(
+            "Projected Crossover",
+            Text::new("Not reached at these rates", Tone::Warning),
+        ),
+    };
+    crossover = crossover.note(
+        NoteKind::Info,
+        format!(
+            "Assumes dividends grow {} a year and expenses {}; change with --growth-rate and --inflation.",
+            format_percent(growth.get_growth_rate() * Decimal::from(100), 1),
+            format_percent(inflation * Decimal::from(100), 1)
+        ),
+    );
+
+    Report::new(TITLE).section(now).section(monthly).section(crossover)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_expense_coverage_and_crossover() {
+        let mut tracker = DividendTracker::new();
+        for pay_date in ["2023-10-01", "2024-01-01", "2024-04-01", "2024-07-01"] {
+            let pay_date = date(pay_date);
+            tracker.add_dividend(
+                Dividend::new("KO".to_string(), None, pay_date, pay_date, dec!(1.5), dec!(1000), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+        let budget = ExpenseBudget::parse("$12,000/year", date("2024-08-01")).unwrap();
+        assert_eq!(budget.monthly, dec!(1000));
+        assert!(ExpenseBudget::parse("100/week", date("2024-08-01")).is_err());
+
+        let coverage = ExpenseCoverage::calculate(&tracker, &budget, date("2024-08-15"));
+        // 1.5 x 4 x 1000 = 6000 a year against 12000 of expenses
+        assert_eq!(coverage.forward_annual, dec!(6000));
+        assert_eq!(coverage.forward_coverage(), dec!(50));
+        assert_eq!(coverage.months.len(), 24);
+        assert_eq!(coverage.months[0].month, date("2023-08-01"));
+        assert_eq!(coverage.trailing_income(), dec!(6000));
+        let october = coverage.months.iter().find(|m| m.projected && m.month == date("2024-10-01")).unwrap();
+        assert_eq!(october.income, dec!(1500));
+
+        // Doubling at 7.2% real growth takes just under ten years
+        match coverage.crossover(dec!(0.1), dec!(0.0261)) {
+            Crossover::On(date) => assert_eq!(date.year(), 2034),
+            other => panic!("expected a crossover date, got {:?}", other),
+        }
+        assert_eq!(coverage.crossover(dec!(0.02), dec!(0.03)), Crossover::Never);
+    }
+}
@@ -0,0 +1,193 @@
+//! Exports for other tools (`export budget`)
+
+use anyhow::Result;
+use chrono::{Datelike, Local, Months, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::CliConfig;
+use crate::cli::{BudgetExportArgs, BudgetFormatArg, ExportCommands};
+use crate::config::DateBasis;
+use crate::filters::DividendFilter;
+use crate::format::{format_money, round_money};
+use crate::models::DividendTracker;
+use crate::paths;
+
+pub fn run(config: &CliConfig, command: ExportCommands) -> Result<()> {
+    match command {
+        ExportCommands::Budget(args) => budget(config, args),
+    }
+}
+
+/// Dividend cash credited during one calendar month
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetMonth {
+    /// Last day of the month, the date the transaction is booked on
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub payments: usize,
+    pub symbols: BTreeSet<String>,
+}
+
+impl BudgetMonth {
+    fn memo(&self) -> String {
+        let symbols: Vec<&str> = self.symbols.iter().map(String::as_str).collect();
+        format!(
+            "{} dividend {} from {}",
+            self.payments,
+            if self.payments == 1 { "payment" } else { "payments" },
+            symbols.join(", ")
+        )
+    }
+}
+
+/// Group the cash credited (by pay date) in `year` into months
+///
+/// Only months that are over by `today` are included, so re-running the
+/// export during the year never hands the budget a month that is still
+/// growing.
+pub fn budget_months(tracker: &DividendTracker, year: i32, today: NaiveDate) -> Vec<BudgetMonth> {
+    let dividends = DividendFilter::new()
+        .date_basis(DateBasis::PayDate)
+        .year(year)
+        .apply(&tracker.dividends);
+
+    let mut months: BTreeMap<u32, BudgetMonth> = BTreeMap::new();
+    for dividend in dividends {
+        let date = month_end(dividend.pay_date);
+        if date >= today {
+            continue;
+        }
+        let month = months.entry(dividend.pay_date.month()).or_insert(BudgetMonth {
+            date,
+            amount: Decimal::ZERO,
+            payments: 0,
+            symbols: BTreeSet::new(),
+        });
+        month.amount += dividend.cash_credited();
+        month.payments += 1;
+        month.symbols.insert(dividend.symbol.clone());
+    }
+    months.into_values().collect()
+}
+
+fn month_end(date: NaiveDate) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    first + Months::new(1) - chrono::Duration::days(1)
+}
+
+/// Write the months as an import file for a budgeting app
+pub fn export_budget(
+    months: &[BudgetMonth],
+    format: BudgetFormatArg,
+    payee: &str,
+    category: &str,
+    output_path: &Path,
+) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(paths::create_file(output_path)?);
+    match format {
+        // YNAB's file import takes separate outflow and inflow columns
+        BudgetFormatArg::Ynab => {
+            wtr.write_record(["Date", "Payee", "Memo", "Outflow", "Inflow"])?;
+            for month in months {
+                wtr.write_record([
+                    month.date.format("%Y-%m-%d").to_string(),
+                    payee.to_string(),
+                    month.memo(),
+                    String::new(),
+                    format!("{:.2}", round_money(month.amount)),
+                ])?;
+            }
+        }
+        BudgetFormatArg::Csv => {
+            wtr.write_record(["Date", "Payee", "Category", "Notes", "Amount"])?;
+            for month in months {
+                wtr.write_record([
+                    month.date.format("%Y-%m-%d").to_string(),
+                    payee.to_string(),
+                    category.to_string(),
+                    month.memo(),
+                    format!("{:.2}", round_money(month.amount)),
+                ])?;
+            }
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+fn budget(config: &CliConfig, args: BudgetExportArgs) -> Result<()> {
+    let BudgetExportArgs {
+        format,
+        output,
+        year,
+        payee,
+        category,
+    } = args;
+
+    let tracker = config.create_persistence_manager()?.load()?;
+    let today = Local::now().naive_local().date();
+    let year = year.unwrap_or_else(|| today.year());
+    let months = budget_months(&tracker, year, today);
+    if months.is_empty() {
+        println!("{}", format!("No dividend income from a completed month of {} to export.", year).yellow());
+        return Ok(());
+    }
+
+    export_budget(&months, format, &payee, &category, &output)?;
+    let total: Decimal = months.iter().map(|m| m.amount).sum();
+    println!(
+        "{} Exported {} months of dividend income ({}) to {}",
+        "✓".green(),
+        months.len().to_string().yellow(),
+        format_money(total),
+        output.display().to_string().cyan()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dividend(symbol: &str, pay_date: &str, amount: Decimal) -> Dividend {
+        Dividend::new(
+            symbol.to_string(),
+            None,
+            date(pay_date),
+            date(pay_date),
+            amount,
+            dec!(10),
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_budget_months_group_cash_credited_by_completed_month() {
+        let mut tracker = DividendTracker::new();
+        let mut withheld = dividend("O", "2024-01-15", dec!(0.50));
+        withheld.withholding_tax = Some(dec!(0.75));
+        tracker.add_dividend(dividend("KO", "2024-01-10", dec!(1.00)));
+        tracker.add_dividend(withheld);
+        tracker.add_dividend(dividend("KO", "2024-04-10", dec!(1.00)));
+        tracker.add_dividend(dividend("KO", "2024-07-10", dec!(1.00)));
+
+        // July is still under way on the 20th
+        let months = budget_months(&tracker, 2024, date("2024-07-20"));
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].date, date("2024-01-31"));
+        assert_eq!(months[0].amount, dec!(14.25));
+        assert_eq!(months[0].memo(), "2 dividend payments from KO, O");
+        assert_eq!(months[1].date, date("2024-04-30"));
+        assert_eq!(months[1].memo(), "1 dividend payment from KO");
+    }
+}
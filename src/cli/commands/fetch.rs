@@ -0,0 +1,650 @@
+//! Market data commands (`fetch`, `update`, `configure`)
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, Local, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use colored::*;
+use rust_decimal::Decimal;
+use serde_json::json;
+
+use super::CliConfig;
+use crate::api::{AlphaVantageClient, ApiError, ApiErrorKind, DividendData};
+use crate::cli::{ConfigureArgs, ConfigureCommands, FetchArgs, FetchCommands, TokenCommands, UpdateArgs};
+use crate::config::Config;
+use crate::diff::DatasetDiff;
+use crate::events::{EventKind, EventLog};
+use crate::format::format_money;
+use crate::models::{DividendTracker, Holding};
+use crate::progress;
+use crate::reconcile::{self, Reconciliation};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+use crate::securities::Securities;
+use crate::tokens::ApiTokens;
+
+/// A fetched dividend priced against the shares held at its ex-date
+struct PricedPayment<'a> {
+    dividend: &'a DividendData,
+    shares: Decimal,
+    /// False when the holding's history doesn't reach back to the ex-date
+    /// and the earliest known share count was assumed
+    ownership_known: bool,
+}
+
+impl PricedPayment<'_> {
+    fn total(&self) -> Decimal {
+        self.dividend.amount * self.shares
+    }
+}
+
+/// Price each payment with the shares held the day before its ex-date,
+/// across every account holding the symbol
+fn price_payments<'a>(holdings: &[&Holding], dividends: &'a [DividendData]) -> Vec<PricedPayment<'a>> {
+    dividends
+        .iter()
+        .map(|dividend| {
+            let entitled: Vec<Option<Decimal>> =
+                holdings.iter().map(|holding| holding.shares_entitled_on(dividend.ex_date)).collect();
+            if entitled.iter().all(Option::is_some) {
+                PricedPayment {
+                    dividend,
+                    shares: entitled.into_iter().flatten().sum(),
+                    ownership_known: true,
+                }
+            } else {
+                PricedPayment {
+                    dividend,
+                    shares: holdings.iter().map(|holding| holding.shares_on(dividend.ex_date)).sum(),
+                    ownership_known: false,
+                }
+            }
+        })
+        .collect()
+}
+
+fn find_holdings<'a>(tracker: &'a DividendTracker, symbol: &str) -> Vec<&'a Holding> {
+    tracker.holdings_of(&tracker.canonical_symbol(symbol))
+}
+
+/// Handle the fetch command
+pub fn fetch(config: &CliConfig, args: FetchArgs) -> Result<()> {
+    let FetchArgs {
+        command,
+        symbols,
+        from,
+        to,
+        year,
+        portfolio,
+    } = args;
+
+    if let Some(FetchCommands::Verify { symbol, apply, yes }) = command {
+        return verify(config, &symbol, apply, yes);
+    }
+
+    println!("{}", "Fetching dividend data...".green().bold());
+
+    // Load configuration
+    let settings = Config::load()?;
+    let api_key = settings.get_api_key()?;
+
+    // Create API client
+    let client = AlphaVantageClient::new(api_key, &settings.api)?;
+
+    // Parse dates
+    let from_date = parse_date_input(from, year, true)?;
+    let to_date = parse_date_input(to, year, false)?;
+
+    // Get symbols to fetch
+    let symbol_list = if let Some(portfolio_file) = portfolio {
+        load_symbols_from_portfolio(&portfolio_file)?
+    } else {
+        symbols
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_uppercase())
+            .collect::<Vec<_>>()
+    };
+
+    // Holdings price each payment with the shares held at its ex-date
+    let tracker = config.create_persistence_manager()?.load()?;
+
+    let mut success_count = 0;
+    let mut total_dividends = 0;
+    // Returned once the fetch has been logged, so the exit code reflects it
+    let mut failure = None;
+
+    if symbol_list.len() == 1 {
+        // Single symbol fetch
+        let symbol = &symbol_list[0];
+        println!("Fetching dividends for {}...", symbol.cyan());
+
+        match client.fetch_dividends(symbol, from_date, to_date) {
+            Ok(dividends) => {
+                success_count += 1;
+                total_dividends += dividends.len();
+                if dividends.is_empty() {
+                    println!(
+                        "{}: No dividends found for the specified period",
+                        symbol.yellow()
+                    );
+                } else {
+                    println!(
+                        "{}: Found {} dividend payments",
+                        symbol.green(),
+                        dividends.len()
+                    );
+                    // The provider gives ex-dates only; pay dates follow the symbol's usual lag
+                    let pay_lag = tracker.pay_lag_days(symbol, settings.analysis.default_pay_lag_days);
+                    match find_holdings(&tracker, symbol) {
+                        holdings if !holdings.is_empty() => print_priced(&price_payments(&holdings, &dividends), pay_lag),
+                        _ => {
+                            for dividend in &dividends {
+                                println!(
+                                    "  {} (pays ~{}) - ${} per share",
+                                    dividend.ex_date.format("%Y-%m-%d"),
+                                    estimated_pay_date(dividend, pay_lag),
+                                    dividend.amount
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => failure = Some(e),
+        }
+    } else {
+        // Batch fetch with progress bar
+        let pb = progress::bar(symbol_list.len(), "Fetching");
+
+        let pb_clone = pb.clone();
+        let results = client.batch_fetch_dividends(
+            &symbol_list,
+            from_date,
+            to_date,
+            Some(Box::new(move |current, _total, symbol| {
+                pb_clone.set_position(current as u64);
+                pb_clone.set_message(format!("Fetching {}", symbol));
+            })),
+        );
+
+        pb.finish_with_message("Done");
+
+        // Display results
+        for (symbol, result) in &results {
+            match result {
+                Ok(dividends) => {
+                    success_count += 1;
+                    total_dividends += dividends.len();
+                    match find_holdings(&tracker, symbol) {
+                        holdings if !holdings.is_empty() => {
+                            let payments = price_payments(&holdings, dividends);
+                            let total: Decimal = payments.iter().map(PricedPayment::total).sum();
+                            let unknown = payments.iter().filter(|p| !p.ownership_known).count();
+                            let mut line = format!("{}: {} dividends, {}", symbol.green(), dividends.len(), format_money(total));
+                            if unknown > 0 {
+                                line.push_str(&format!(" ({} with unknown ownership)", unknown).yellow().to_string());
+                            }
+                            println!("{}", line);
+                        }
+                        _ => println!("{}: {} dividends", symbol.green(), dividends.len()),
+                    }
+                }
+                Err(e) => {
+                    println!("{}: {}", symbol.red(), e);
+                }
+            }
+        }
+
+        println!();
+        println!(
+            "Fetched {} symbols successfully, {} total dividend payments",
+            success_count.to_string().green(),
+            total_dividends.to_string().cyan()
+        );
+        failure = summarize_failures(&results);
+    }
+
+    EventLog::new(config.resolve_data_dir()?).notify(
+        EventKind::FetchCompleted,
+        json!({
+            "symbols": symbol_list,
+            "succeeded": success_count,
+            "dividends_found": total_dividends,
+        }),
+    );
+
+    failure.map_or(Ok(()), Err)
+}
+
+/// Print failed symbols grouped by kind of failure, each group with what to
+/// do about it
+///
+/// Returns an error carrying the most common kind, so the exit code says why
+/// most symbols failed.
+fn summarize_failures(results: &HashMap<String, Result<Vec<DividendData>>>) -> Option<anyhow::Error> {
+    let mut groups: BTreeMap<Option<ApiErrorKind>, Vec<(&str, &anyhow::Error)>> = BTreeMap::new();
+    for (symbol, result) in results {
+        if let Err(e) = result {
+            let kind = e.downcast_ref::<ApiError>().map(ApiError::kind);
+            groups.entry(kind).or_default().push((symbol, e));
+        }
+    }
+    if groups.is_empty() {
+        return None;
+    }
+
+    let failed: usize = groups.values().map(Vec::len).sum();
+    println!("{}", format!("{} of {} symbols failed:", failed, results.len()).red());
+    for (kind, failures) in groups.iter_mut() {
+        failures.sort_by_key(|(symbol, _)| *symbol);
+        let symbols: Vec<&str> = failures.iter().map(|(symbol, _)| *symbol).collect();
+        println!("  {} ({}): {}", kind.map_or("Other", ApiErrorKind::label), failures.len(), symbols.join(", "));
+        if let Some(api_error) = failures[0].1.downcast_ref::<ApiError>() {
+            println!("    {}", api_error.remediation().yellow());
+        }
+    }
+
+    // Ties go to the kind listed first
+    let (_, failures) = groups
+        .iter()
+        .rev()
+        .max_by_key(|(_, failures)| failures.len())
+        .expect("at least one failure");
+    let error = failures[0].1;
+    let summary = format!("{} of {} symbols failed to fetch", failed, results.len());
+    Some(match error.downcast_ref::<ApiError>() {
+        Some(api_error) => anyhow::Error::new(api_error.clone()).context(summary),
+        None => anyhow!(summary),
+    })
+}
+
+/// Estimated pay date of a fetched dividend, `pay_lag` days after its ex-date
+fn estimated_pay_date(dividend: &DividendData, pay_lag: i64) -> String {
+    (dividend.ex_date + Duration::days(pay_lag)).format("%Y-%m-%d").to_string()
+}
+
+/// Print each payment with the shares it was paid on, flagging those where
+/// the share count had to be assumed
+fn print_priced(payments: &[PricedPayment], pay_lag: i64) {
+    for payment in payments {
+        let line = format!(
+            "  {} (pays ~{}) - ${} per share x {} shares = {}",
+            payment.dividend.ex_date.format("%Y-%m-%d"),
+            estimated_pay_date(payment.dividend, pay_lag),
+            payment.dividend.amount,
+            payment.shares.normalize(),
+            format_money(payment.total())
+        );
+        if payment.ownership_known {
+            println!("{}", line);
+        } else {
+            println!("{} {}", line, "(ownership unknown, shares assumed)".yellow());
+        }
+    }
+
+    let total: Decimal = payments.iter().map(PricedPayment::total).sum();
+    println!("  Total: {}", format_money(total).green());
+    let unknown = payments.iter().filter(|p| !p.ownership_known).count();
+    if unknown > 0 {
+        println!(
+            "  {}",
+            format!(
+                "{} {} the holding's recorded trades; record earlier trades with 'holdings update' to price them exactly",
+                unknown,
+                if unknown == 1 { "payment predates" } else { "payments predate" }
+            )
+            .yellow()
+        );
+    }
+}
+
+/// Compare the provider's dividend history for a symbol with the recorded
+/// dividends, correcting differences only with `--apply`
+fn verify(config: &CliConfig, symbol: &str, apply: bool, yes: bool) -> Result<()> {
+    let settings = Config::load()?;
+    let client = AlphaVantageClient::new(settings.get_api_key()?, &settings.api)?;
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+    let symbol = tracker.canonical_symbol(symbol);
+
+    config.print_verbose(&format!("Fetching dividend history for {}", symbol));
+    let provider = client.fetch_dividends(&symbol, None, None)?;
+    let reconciliation = reconcile::reconcile(&tracker, &symbol, &provider);
+    config.render(&verify_report(&tracker, &symbol, &provider, &reconciliation))?;
+
+    let differences = reconciliation.mismatched.len();
+    if differences == 0 || !apply {
+        return Ok(());
+    }
+
+    let mut preview = tracker.clone();
+    reconciliation.apply(&mut preview);
+    println!("{}", "Pending Changes".blue().bold());
+    config.print_changes(&DatasetDiff::compare(&tracker, &preview));
+    if !config.confirm(&format!("Correct {} {} records?", differences, symbol), yes)? {
+        return Ok(());
+    }
+
+    // Reconcile again against fresh data and write it in one save
+    let corrected = persistence.with_tracker_mut(|tracker| {
+        let reconciliation = reconcile::reconcile(tracker, &symbol, &provider);
+        Ok(reconciliation.apply(tracker))
+    })?;
+    println!("{} Corrected {} {} records to match the provider", "✓".green(), corrected, symbol.cyan());
+    Ok(())
+}
+
+fn verify_report(tracker: &DividendTracker, symbol: &str, provider: &[DividendData], reconciliation: &Reconciliation) -> Report {
+    let date = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+    let mut report = Report::new(format!("Verify {} Against Provider History", symbol));
+    let mut summary = Section::untitled()
+        .field("Provider Payments", provider.len().to_string())
+        .field("Matching Records", Text::new(reconciliation.matched.to_string(), Tone::Positive));
+
+    if reconciliation.mismatched.is_empty() && reconciliation.missing.is_empty() && reconciliation.unconfirmed.is_empty() {
+        summary = summary.note(NoteKind::Tip, "Every recorded dividend matches the provider.");
+        report.push(summary);
+        return report;
+    }
+    report.push(summary);
+
+    if !reconciliation.mismatched.is_empty() {
+        let mut table = TableView::new(&["Recorded Ex-Date", "Field", "Recorded", "Provider"]);
+        for mismatch in &reconciliation.mismatched {
+            for change in &mismatch.changes {
+                table.row(vec![
+                    date(tracker.dividends[mismatch.index].ex_date).into(),
+                    change.field.into(),
+                    Text::new(change.before.clone(), Tone::Negative),
+                    Text::new(change.after.clone(), Tone::Positive),
+                ]);
+            }
+        }
+        let note = if reconciliation.mismatched.len() == 1 {
+            "Run with --apply to correct this record.".to_string()
+        } else {
+            format!("Run with --apply to correct these {} records.", reconciliation.mismatched.len())
+        };
+        report.push(Section::new("Differences").icon("⚠️").table(table).note(NoteKind::Info, note));
+    }
+
+    if !reconciliation.missing.is_empty() {
+        let mut table = TableView::new(&["Ex-Date", "Amount"]);
+        for payment in &reconciliation.missing {
+            table.row(vec![date(payment.ex_date).into(), format!("${}", payment.amount).into()]);
+        }
+        report.push(
+            Section::new("Not Recorded")
+                .table(table)
+                .note(NoteKind::Info, "These payments aren't recorded; add any you received with 'div add'."),
+        );
+    }
+
+    if !reconciliation.unconfirmed.is_empty() {
+        let mut table = TableView::new(&["Ex-Date", "Amount"]);
+        for &index in &reconciliation.unconfirmed {
+            let dividend = &tracker.dividends[index];
+            table.row(vec![date(dividend.ex_date).into(), format!("${}", dividend.amount_per_share).into()]);
+        }
+        report.push(
+            Section::new("Not Found at Provider")
+                .table(table)
+                .note(NoteKind::Warning, "The provider has no payment near these ex-dates; check them against your statements."),
+        );
+    }
+
+    report
+}
+
+/// Handle the update command
+pub fn update(_config: &CliConfig, args: UpdateArgs) -> Result<()> {
+    let UpdateArgs {
+        all,
+        symbol,
+        since_last_fetch,
+    } = args;
+
+    println!("{}", "Update functionality not yet implemented.".yellow());
+    println!("This will update existing dividend data with recent dividends.");
+
+    if all {
+        println!("Would update all symbols in the database");
+    } else if let Some(symbol) = symbol {
+        println!("Would update dividends for {}", symbol.cyan());
+    }
+
+    if since_last_fetch {
+        println!("Would fetch only dividends since last update");
+    }
+
+    Ok(())
+}
+
+/// Handle the configure command
+pub fn configure(config: &CliConfig, args: ConfigureArgs) -> Result<()> {
+    let ConfigureArgs { command, api_key, show } = args;
+    match command {
+        Some(ConfigureCommands::Edit) => return edit_config(config),
+        Some(ConfigureCommands::Validate) => return validate_config(),
+        Some(ConfigureCommands::Tokens { command }) => return tokens(config, command),
+        None => {}
+    }
+    let mut settings = Config::load()?;
+
+    if show {
+        println!("{}", "Current Configuration:".green().bold());
+        println!(
+            "API Key: {}",
+            if settings.api.alpha_vantage_key.is_some() {
+                "******* (configured)".green()
+            } else {
+                "Not configured".yellow()
+            }
+        );
+        println!("Rate Limit Delay: {}ms", settings.api.rate_limit_delay_ms);
+        println!("Max Retries: {}", settings.api.max_retries);
+        println!("Retry Backoff: {}ms", settings.api.retry_backoff_ms);
+        println!("Request Timeout: {}s", settings.api.timeout_seconds);
+        println!(
+            "Proxy: {}",
+            settings.api.proxy.as_deref().unwrap_or("from HTTP_PROXY/HTTPS_PROXY, if set")
+        );
+        println!("Cache Enabled: {}", settings.cache.enabled);
+        println!("Cache TTL: {} hours", settings.cache.ttl_hours);
+        return Ok(());
+    }
+
+    if let Some(key) = api_key {
+        settings.api.alpha_vantage_key = Some(key);
+        settings.save()?;
+        println!("{}", "API key saved successfully!".green());
+        println!("Configuration file: {:?}", Config::config_file()?);
+    } else {
+        println!("{}", "Configuration Options:".green().bold());
+        println!("Use --api-key to set your Alpha Vantage API key");
+        println!("Use --show to display current configuration");
+        println!("Use 'configure edit' to change any setting in your editor");
+        println!();
+        println!("To get a free API key, visit: https://www.alphavantage.co/support/#api-key");
+    }
+
+    Ok(())
+}
+
+/// Handle `configure tokens`
+fn tokens(config: &CliConfig, command: TokenCommands) -> Result<()> {
+    let mut tokens = ApiTokens::load()?;
+    match command {
+        TokenCommands::Add { scope, name } => {
+            let today = Local::now().naive_local().date();
+            let token = tokens.add(name, scope.into(), today)?.clone();
+            tokens.save()?;
+            println!("{} Added {} token '{}':", "✓".green(), token.scope.name(), token.name.cyan());
+            println!("  {}", token.token.yellow());
+            println!("  It is shown only now; send it as 'Authorization: Bearer <token>'.");
+        }
+        TokenCommands::List => {
+            if tokens.is_empty() {
+                println!("No API tokens. Add one with 'configure tokens add --scope read'.");
+                return Ok(());
+            }
+            let mut table = TableView::new(&["Name", "Scope", "Created"]);
+            for token in &tokens.tokens {
+                table.row(vec![
+                    token.name.clone().into(),
+                    token.scope.name().into(),
+                    token.created.format("%Y-%m-%d").to_string().into(),
+                ]);
+            }
+            config.render(&Report::new("API Tokens").section(Section::untitled().table(table)))?;
+        }
+        TokenCommands::Remove { name } => {
+            if !tokens.remove(&name) {
+                bail!("No token named '{}'", name);
+            }
+            tokens.save()?;
+            println!("{} Removed token '{}'", "✓".green(), name.cyan());
+        }
+    }
+    Ok(())
+}
+
+/// Open config.toml in the user's editor, keeping the edit only once it parses
+///
+/// The edit is made on a copy next to the file, so an invalid config never
+/// replaces a working one.
+fn edit_config(config: &CliConfig) -> Result<()> {
+    let path = Config::config_file()?;
+    if !path.exists() {
+        Config::default().save()?;
+    }
+    let original = fs::read_to_string(&path)?;
+    let dir = path.parent().ok_or_else(|| anyhow!("{} has no parent directory", path.display()))?;
+    let mut draft = tempfile::Builder::new().prefix(".config").suffix(".toml").tempfile_in(dir)?;
+    draft.write_all(original.as_bytes())?;
+    draft.flush()?;
+
+    loop {
+        run_editor(draft.path())?;
+        let edited = fs::read_to_string(draft.path())?;
+        match Config::parse(&edited) {
+            Ok(_) if edited == original => {
+                println!("No changes to {}", path.display());
+                return Ok(());
+            }
+            Ok(settings) => {
+                draft.persist(&path)?;
+                println!("{} Saved {}", "✓".green(), path.display().to_string().cyan());
+                for problem in settings.problems() {
+                    println!("{} {}", "⚠".yellow(), problem);
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{} {:#}", "Invalid configuration:".red(), e);
+                if !config.confirm("Edit again?", false)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Report every problem in config.toml, failing if there are any
+fn validate_config() -> Result<()> {
+    // securities.toml rejects anything it doesn't understand, so loading it is the check
+    Securities::load()?;
+    let securities = Securities::file()?;
+    if securities.exists() {
+        println!("{} {} is valid", "✓".green(), securities.display().to_string().cyan());
+    }
+
+    let path = Config::config_file()?;
+    if !path.exists() {
+        println!("No config file at {}; the defaults are in use", path.display());
+        return Ok(());
+    }
+
+    let settings = Config::parse(&fs::read_to_string(&path)?)
+        .with_context(|| format!("{} is invalid", path.display()))?;
+    let problems = settings.problems();
+    if problems.is_empty() {
+        println!("{} {} is valid", "✓".green(), path.display().to_string().cyan());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{} {}", "⚠".yellow(), problem);
+    }
+    bail!(
+        "{} {} in {}; fix with `dividend-tracker configure edit`",
+        problems.len(),
+        if problems.len() == 1 { "problem" } else { "problems" },
+        path.display()
+    )
+}
+
+/// Run $VISUAL or $EDITOR (which may include arguments) on a file and wait for it
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'; set EDITOR to the one you use", editor))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}; config.toml was not changed", editor, status);
+    }
+    Ok(())
+}
+
+/// Parse date input from string or year
+fn parse_date_input(
+    date_str: Option<String>,
+    year: Option<i32>,
+    is_from: bool,
+) -> Result<Option<NaiveDate>> {
+    if let Some(date) = date_str {
+        Ok(Some(NaiveDate::parse_from_str(&date, "%Y-%m-%d")?))
+    } else if let Some(y) = year {
+        if is_from {
+            Ok(Some(
+                NaiveDate::from_ymd_opt(y, 1, 1).ok_or_else(|| anyhow!("Invalid year"))?,
+            ))
+        } else {
+            Ok(Some(
+                NaiveDate::from_ymd_opt(y, 12, 31).ok_or_else(|| anyhow!("Invalid year"))?,
+            ))
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Load symbols from a portfolio CSV file
+fn load_symbols_from_portfolio(file_path: &str) -> Result<Vec<String>> {
+    let mut symbols = Vec::new();
+    let mut rdr = csv::Reader::from_path(file_path)?;
+
+    for result in rdr.records() {
+        let record = result?;
+        if let Some(symbol) = record.get(0) {
+            symbols.push(symbol.trim().to_uppercase());
+        }
+    }
+
+    if symbols.is_empty() {
+        return Err(anyhow!("No symbols found in portfolio file"));
+    }
+
+    Ok(symbols)
+}
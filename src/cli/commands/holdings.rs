@@ -0,0 +1,216 @@
+//! Portfolio holdings commands
+
+use anyhow::{anyhow, Result};
+use colored::*;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::{parse_dividend_date, CliConfig};
+use crate::cli::{
+    HoldingsAddArgs, HoldingsCommands, HoldingsListArgs, HoldingsSetForwardArgs, HoldingsUpdateArgs,
+};
+use crate::diff::DatasetDiff;
+use crate::lots;
+use crate::models::{ForwardDividend, Holding};
+use crate::persistence::PersistenceManager;
+
+/// Handle holdings-related commands
+pub fn run(config: &CliConfig, command: HoldingsCommands) -> Result<()> {
+    let persistence = config.create_persistence_manager()?;
+
+    match command {
+        HoldingsCommands::Import { file, account } => {
+            crate::holdings::import_holdings(&persistence, &file, account.as_deref())?;
+        }
+        HoldingsCommands::ImportLots {
+            file,
+            dry_run,
+            yes,
+            account,
+        } => {
+            import_lots(config, &persistence, &file, account.as_deref(), dry_run, yes)?;
+        }
+        HoldingsCommands::Add(HoldingsAddArgs {
+            symbol,
+            shares,
+            cost_basis,
+            yield_pct,
+            isin,
+            cusip,
+            account,
+        }) => {
+            let shares_decimal = Decimal::from_str(&shares)
+                .map_err(|_| anyhow!("Invalid shares amount: {}", shares))?;
+
+            let cost_basis_decimal = if let Some(cb) = cost_basis {
+                Some(Decimal::from_str(&cb).map_err(|_| anyhow!("Invalid cost basis: {}", cb))?)
+            } else {
+                None
+            };
+
+            let yield_decimal = if let Some(y) = yield_pct {
+                Some(
+                    Decimal::from_str(&y)
+                        .map_err(|_| anyhow!("Invalid yield percentage: {}", y))?,
+                )
+            } else {
+                None
+            };
+
+            let mut holding = Holding::new(symbol, shares_decimal, cost_basis_decimal, yield_decimal)?;
+            holding.set_identifiers(isin.as_deref(), cusip.as_deref())?;
+            if let Some(account) = &account {
+                holding.set_account(account);
+            }
+            crate::holdings::add_holding(&persistence, holding)?;
+        }
+        HoldingsCommands::Update(HoldingsUpdateArgs {
+            symbol,
+            shares,
+            cost_basis,
+            date,
+            account,
+        }) => {
+            let shares_delta = match shares {
+                Some(shares) => Decimal::from_str(shares.trim_start_matches('+'))
+                    .map_err(|_| anyhow!("Invalid share change: {}. Use e.g. +25 or -10", shares))?,
+                None => Decimal::ZERO,
+            };
+            let price = cost_basis
+                .map(|cb| Decimal::from_str(&cb).map_err(|_| anyhow!("Invalid cost basis: {}", cb)))
+                .transpose()?;
+            let date = match date {
+                Some(date) => parse_dividend_date(&date)?,
+                None => chrono::Local::now().naive_local().date(),
+            };
+
+            crate::holdings::update_holding(&persistence, &symbol, account.as_deref(), shares_delta, price, date)?;
+        }
+        HoldingsCommands::History { symbol, account } => {
+            let tracker = persistence.load()?;
+            config.render(&crate::holdings::history_report(&tracker, &symbol, account.as_deref())?)?;
+        }
+        HoldingsCommands::Remove {
+            symbol,
+            archive,
+            date,
+            yes,
+            account,
+        } => {
+            if archive {
+                let date = match date {
+                    Some(date) => parse_dividend_date(&date)?,
+                    None => chrono::Local::now().naive_local().date(),
+                };
+                crate::holdings::archive_holding(&persistence, &symbol, account.as_deref(), date)?;
+            } else {
+                let tracker = persistence.load()?;
+                let key = tracker.position_key(&tracker.canonical_symbol(&symbol), account.as_deref())?;
+                if let Some(holding) = key.map(|key| &tracker.holdings[&key]) {
+                    let question = format!(
+                        "Delete holding {} ({} shares)? Use --archive to keep it in history instead.",
+                        holding.label(), holding.shares
+                    );
+                    if !config.confirm(&question, yes)? {
+                        return Ok(());
+                    }
+                }
+                crate::holdings::remove_holding(&persistence, &symbol, account.as_deref())?;
+            }
+        }
+        HoldingsCommands::List(HoldingsListArgs {
+            sort_by,
+            desc,
+            include_archived,
+        }) => {
+            let tracker = persistence.load()?;
+            config.render(&crate::holdings::holdings_report(&tracker, &sort_by, desc, include_archived))?;
+        }
+        HoldingsCommands::Export { output } => {
+            crate::holdings::export_holdings(&persistence, &output)?;
+        }
+        HoldingsCommands::SetForwardDividend(HoldingsSetForwardArgs {
+            symbol,
+            amount,
+            frequency,
+            clear: _,
+            account,
+        }) => {
+            // clap guarantees an amount unless --clear was given
+            let forward = match amount {
+                Some(amount) => {
+                    let amount_decimal = Decimal::from_str(&amount)
+                        .map_err(|_| anyhow!("Invalid forward dividend: {}", amount))?;
+                    Some(ForwardDividend::new(amount_decimal, frequency.into())?)
+                }
+                None => None,
+            };
+
+            crate::holdings::set_forward_dividend(&persistence, &symbol, account.as_deref(), forward)?;
+        }
+        HoldingsCommands::Summary { include_yield } => {
+            crate::holdings::show_summary(&persistence, include_yield)?;
+        }
+    }
+    Ok(())
+}
+
+/// Preview a lot import, confirm, then rebuild the holdings from the lots
+fn import_lots(
+    config: &CliConfig,
+    persistence: &PersistenceManager,
+    file: &str,
+    account: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let lots = lots::load_lots(file)?;
+    if lots.is_empty() {
+        config.print("No lots found in the file.");
+        return Ok(());
+    }
+
+    let tracker = persistence.load()?;
+    let mut preview = tracker.clone();
+    let imports = lots::apply_lots(&mut preview, lots.clone(), account)?;
+    if !config.quiet {
+        for import in &imports {
+            let cost = import.avg_cost_basis.map(|c| format!("${}", c)).unwrap_or_else(|| "N/A".to_string());
+            let verb = if import.replaced { "Rebuilt".yellow() } else { "Imported".green() };
+            println!(
+                "  {} {} from {} lots: {} shares at {} average",
+                verb,
+                import.symbol.cyan(),
+                import.lots,
+                import.shares,
+                cost
+            );
+        }
+        println!();
+    }
+
+    println!("{}", "Pending Changes".blue().bold());
+    config.print_changes(&DatasetDiff::compare(&tracker, &preview));
+    if dry_run {
+        println!("{}", format!("Dry run: {} lots not imported.", lots.len()).yellow());
+        return Ok(());
+    }
+    let replaced = imports.iter().filter(|import| import.replaced).count();
+    let question = if replaced > 0 {
+        format!(
+            "Import {} lots? This replaces the shares, cost basis and history of {} existing holdings.",
+            lots.len(),
+            replaced
+        )
+    } else {
+        format!("Import {} lots?", lots.len())
+    };
+    if !config.confirm(&question, yes)? {
+        return Ok(());
+    }
+
+    // Apply again against fresh data and write it in one save
+    persistence.with_tracker_mut(|tracker| lots::apply_lots(tracker, lots, account))?;
+    println!("{} Imported lots for {} holdings", "✓".green(), imports.len());
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! First-run setup command
+
+use anyhow::{bail, Result};
+use chrono::{Local, Months, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use super::CliConfig;
+use crate::cli::InitArgs;
+use crate::config::Config;
+use crate::models::{Dividend, DividendTracker, DividendType, Holding};
+use crate::paths;
+
+/// Sample positions for `--demo`: symbol, shares, cost basis, dividend per
+/// payment and payments a year
+const DEMO_PORTFOLIO: [(&str, Decimal, Decimal, Decimal, u32); 3] = [
+    ("KO", dec!(100), dec!(52.40), dec!(0.485), 4),
+    ("JNJ", dec!(40), dec!(155.10), dec!(1.24), 4),
+    ("O", dec!(150), dec!(56.75), dec!(0.2625), 12),
+];
+
+/// Walk through setting up a data directory, API key and first holdings
+pub fn run(config: &CliConfig, args: InitArgs) -> Result<()> {
+    let InitArgs {
+        api_key,
+        import_holdings,
+        demo,
+        no_input,
+    } = args;
+    let interactive = !no_input && !config.quiet && io::stdin().is_terminal();
+    let mut settings = Config::load().unwrap_or_default();
+
+    println!("{}", "Welcome to Dividend Tracker!".green().bold());
+    println!();
+
+    // 1. Where the data lives
+    let mut config = config.clone();
+    let data_dir = config.resolve_data_dir()?;
+    let chosen_by_user = config.data_dir.is_some()
+        || std::env::var_os(paths::DATA_DIR_ENV).is_some_and(|dir| !dir.is_empty());
+    let data_dir = match ask_if(interactive && !chosen_by_user, &format!("Data directory [{}]", data_dir.display()))? {
+        Some(answer) => {
+            let dir = PathBuf::from(answer);
+            settings.storage.data_dir = Some(dir.clone());
+            settings.save()?;
+            config.data_dir = Some(dir.clone());
+            dir
+        }
+        None => data_dir,
+    };
+    let persistence = config.create_persistence_manager()?;
+    persistence.ensure_directories()?;
+    let tracker = persistence.load()?;
+    println!("{} Data directory: {}", "✓".green(), data_dir.display().to_string().cyan());
+    if !tracker.dividends.is_empty() || !tracker.holdings.is_empty() {
+        println!(
+            "  Already holds {} dividends and {} holdings.",
+            tracker.dividends.len(),
+            tracker.holdings.len()
+        );
+    }
+
+    // 2. API key for fetching dividend history
+    let api_key = match api_key {
+        Some(key) => Some(key),
+        None if settings.api.alpha_vantage_key.is_some() => {
+            println!("{} Alpha Vantage API key already configured", "✓".green());
+            None
+        }
+        None => ask_if(interactive, "Alpha Vantage API key for 'fetch' (enter to skip)")?,
+    };
+    if let Some(key) = api_key {
+        settings.api.alpha_vantage_key = Some(key.trim().to_string());
+        settings.save()?;
+        println!("{} API key saved to {}", "✓".green(), Config::config_file()?.display());
+    }
+    let has_api_key = settings.api.alpha_vantage_key.is_some();
+
+    // 3. Holdings from a broker export
+    let import = match import_holdings {
+        Some(path) => Some(path),
+        None => ask_if(interactive, "Holdings CSV to import (symbol,shares[,cost_basis]; enter to skip)")?
+            .map(PathBuf::from),
+    };
+    if let Some(path) = import {
+        println!();
+        crate::holdings::import_holdings(&persistence, &path.to_string_lossy(), None)?;
+    }
+
+    // 4. Sample data to explore with
+    let empty = {
+        let tracker = persistence.load()?;
+        tracker.dividends.is_empty() && tracker.holdings.is_empty()
+    };
+    let demo = demo || (empty && ask_if(interactive, "Add a sample portfolio to try the commands? [y/N]")?
+        .is_some_and(|answer| matches!(answer.to_lowercase().as_str(), "y" | "yes")));
+    if demo {
+        if !empty {
+            bail!("--demo only seeds an empty data directory; {} already has data", data_dir.display());
+        }
+        let today = Local::now().naive_local().date();
+        let (holdings, dividends) = persistence.with_tracker_mut(|tracker| {
+            seed_demo(tracker, today)?;
+            Ok((tracker.holdings.len(), tracker.dividends.len()))
+        })?;
+        println!(
+            "{} Added a sample portfolio of {} holdings and {} dividends",
+            "✓".green(),
+            holdings,
+            dividends
+        );
+        println!("  Start over later by deleting {}", data_dir.join("dividends.json").display());
+    }
+
+    println!();
+    println!("{}", "Next steps".blue().bold());
+    println!("  dividend-tracker holdings add KO --shares 100    # add a position");
+    println!("  dividend-tracker div add KO --ex-date 2024-06-14 --pay-date 2024-07-01 --amount 0.485 --shares 100");
+    if has_api_key {
+        println!("  dividend-tracker fetch KO                         # download dividend history");
+    } else {
+        println!("  dividend-tracker configure --api-key KEY          # enable 'fetch'");
+    }
+    println!("  dividend-tracker summary                          # income so far");
+    println!("  dividend-tracker project                          # expected income");
+    Ok(())
+}
+
+/// Ask a question on stdout when `interactive`; `None` for no answer
+fn ask_if(interactive: bool, question: &str) -> Result<Option<String>> {
+    if !interactive {
+        return Ok(None);
+    }
+    print!("{}: ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+/// Fill an empty tracker with the demo portfolio and a year of its dividends
+fn seed_demo(tracker: &mut DividendTracker, today: NaiveDate) -> Result<()> {
+    for (symbol, shares, cost_basis, amount, per_year) in DEMO_PORTFOLIO {
+        tracker.add_holding(Holding::new(symbol.to_string(), shares, Some(cost_basis), None)?);
+
+        let interval = 12 / per_year;
+        for n in 1..=per_year {
+            let pay_date = today - Months::new(interval * n);
+            let ex_date = pay_date - chrono::Duration::days(14);
+            tracker.add_dividend(Dividend::new(
+                symbol.to_string(),
+                None,
+                ex_date,
+                pay_date,
+                amount,
+                shares,
+                DividendType::Regular,
+            )?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_demo_adds_a_year_of_income() {
+        let mut tracker = DividendTracker::new();
+        let today = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+        seed_demo(&mut tracker, today).unwrap();
+
+        assert_eq!(tracker.holdings.len(), 3);
+        assert_eq!(tracker.dividends.len(), 4 + 4 + 12);
+        assert!(tracker.dividends.iter().all(|d| d.pay_date < today));
+        let total: Decimal = tracker.dividends.iter().map(|d| d.total_amount).sum();
+        // 100 x 0.485 x 4 + 40 x 1.24 x 4 + 150 x 0.2625 x 12
+        assert_eq!(total, dec!(864.90));
+    }
+}
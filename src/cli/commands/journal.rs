@@ -0,0 +1,169 @@
+//! Symbol journal commands (`journal add`, `journal list`, `journal export`)
+
+use anyhow::{bail, Result};
+use chrono::Local;
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+use super::CliConfig;
+use crate::cli::{ExportFormat, JournalCommands};
+use crate::models::{DividendTracker, JournalEntry};
+use crate::paths;
+use crate::render::{Report, Section, TableView};
+
+const TITLE: &str = "Journal";
+
+/// Handle journal commands
+pub fn run(config: &CliConfig, command: JournalCommands) -> Result<()> {
+    match command {
+        JournalCommands::Add { symbol, text } => add(config, &symbol, &text),
+        JournalCommands::List { symbol } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            config.render(&journal_report(&tracker, symbol.as_deref()))
+        }
+        JournalCommands::Export { format, output, symbol } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let entries = entries(&tracker, symbol.as_deref());
+            export(&entries, format, &output)?;
+            println!(
+                "{} Exported {} journal entries to {}",
+                "✓".green(),
+                entries.len(),
+                output.display().to_string().cyan()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn add(config: &CliConfig, symbol: &str, text: &str) -> Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        bail!("Journal note cannot be empty");
+    }
+
+    let persistence = config.create_persistence_manager()?;
+    let (entry, hint, count) = persistence.with_tracker_mut(|tracker| {
+        let entry = JournalEntry {
+            symbol: tracker.canonical_symbol(symbol),
+            at: Local::now(),
+            text: text.to_string(),
+        };
+        if entry.symbol.is_empty() {
+            bail!("Symbol cannot be empty");
+        }
+        let hint = tracker.symbol_hint(&entry.symbol);
+        tracker.journal.push(entry.clone());
+        Ok((entry, hint, tracker.journal_for(symbol).len()))
+    })?;
+
+    println!(
+        "{} Added a note to {} ({} in its journal)",
+        "✓".green(),
+        entry.symbol.cyan(),
+        count
+    );
+    if let Some(hint) = hint {
+        println!("{}", format!("{} has no holding or dividends yet. {}", entry.symbol, hint).yellow());
+    }
+    Ok(())
+}
+
+/// Journal entries, all or for one symbol, newest first
+fn entries<'a>(tracker: &'a DividendTracker, symbol: Option<&str>) -> Vec<&'a JournalEntry> {
+    let mut entries = match symbol {
+        Some(symbol) => tracker.journal_for(symbol),
+        None => tracker.journal.iter().collect(),
+    };
+    entries.reverse();
+    entries
+}
+
+/// Table of journal entries, newest first
+pub fn journal_table(entries: &[&JournalEntry], with_symbol: bool) -> TableView {
+    let mut table = if with_symbol {
+        TableView::new(&["Date", "Symbol", "Note"])
+    } else {
+        TableView::new(&["Date", "Note"])
+    };
+    for entry in entries {
+        let mut row = vec![entry.at.format("%Y-%m-%d %H:%M").to_string().into()];
+        if with_symbol {
+            row.push(entry.symbol.clone().into());
+        }
+        row.push(entry.text.clone().into());
+        table.row(row);
+    }
+    table
+}
+
+fn journal_report(tracker: &DividendTracker, symbol: Option<&str>) -> Report {
+    let entries = entries(tracker, symbol);
+    if entries.is_empty() {
+        let message = match symbol {
+            Some(symbol) => format!(
+                "No journal entries for {}. Add one with 'journal add {} \"...\"'.",
+                tracker.canonical_symbol(symbol),
+                tracker.canonical_symbol(symbol)
+            ),
+            None => "No journal entries. Add one with 'journal add SYMBOL \"...\"'.".to_string(),
+        };
+        return Report::warning(TITLE, message);
+    }
+
+    let title = match symbol {
+        Some(symbol) => format!("{}: {}", TITLE, tracker.canonical_symbol(symbol)),
+        None => TITLE.to_string(),
+    };
+    Report::new(title).section(Section::untitled().table(journal_table(&entries, symbol.is_none())))
+}
+
+fn export(entries: &[&JournalEntry], format: ExportFormat, output: &Path) -> Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(paths::create_file(output)?);
+            wtr.write_record(["Symbol", "Timestamp", "Note"])?;
+            for entry in entries {
+                wtr.write_record([entry.symbol.as_str(), &entry.at.to_rfc3339(), entry.text.as_str()])?;
+            }
+            wtr.flush()?;
+        }
+        ExportFormat::Json => {
+            paths::ensure_parent_dir(output)?;
+            fs::write(output, serde_json::to_string_pretty(entries)?)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(symbol: &str, day: u32, text: &str) -> JournalEntry {
+        JournalEntry {
+            symbol: symbol.to_string(),
+            at: Local.with_ymd_and_hms(2024, 5, day, 9, 30, 0).unwrap(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_journal_entries_are_newest_first_and_follow_aliases() {
+        let mut tracker = DividendTracker::new();
+        tracker.journal.push(entry("KO", 1, "Raised dividend 4%"));
+        tracker.journal.push(entry("PEP", 2, "Payout ratio 70%"));
+        tracker.journal.push(entry("KO", 3, "Ex-date moved"));
+        tracker.add_symbol_alias("COKE", "KO").unwrap();
+
+        let notes: Vec<&str> = entries(&tracker, Some("coke")).iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(notes, ["Ex-date moved", "Raised dividend 4%"]);
+        assert_eq!(entries(&tracker, None).len(), 3);
+
+        let table = journal_table(&entries(&tracker, None), true);
+        assert_eq!(table.rows[0][0].text, "2024-05-03 09:30");
+        assert_eq!(table.rows[1][1].text, "PEP");
+    }
+}
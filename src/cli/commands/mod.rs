@@ -0,0 +1,405 @@
+//! Command handlers, one module per command group
+//!
+//! Each handler takes the global [`CliConfig`] and its parsed arguments.
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use colored::*;
+use rust_decimal::Decimal;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::analytics_cache::AnalyticsCache;
+use crate::cli::Commands;
+use crate::config::Config;
+use crate::diff::{DatasetDiff, FieldChange};
+use crate::models::DividendTracker;
+use crate::paths;
+use crate::persistence::PersistenceManager;
+use crate::render::{self, OutputFormat, Report, TableOptions, TableStyle};
+use crate::securities::Securities;
+
+pub mod actions;
+pub mod alerts;
+pub mod analyze;
+pub mod cash;
+pub mod data;
+pub mod dividends;
+pub mod doctor;
+pub mod expenses;
+pub mod export;
+pub mod fetch;
+pub mod holdings;
+pub mod init;
+pub mod journal;
+pub mod news;
+pub mod premiums;
+pub mod project;
+pub mod report;
+pub mod rpc;
+pub mod run;
+pub mod serve;
+pub mod show;
+pub mod summary;
+pub mod symbols;
+pub mod tax;
+pub mod transactions;
+
+/// Changed records listed by [`CliConfig::print_changes`] before it summarizes the rest
+pub const MAX_PRINTED_CHANGES: usize = 20;
+
+/// Global CLI configuration passed to all command handlers
+#[derive(Clone)]
+pub struct CliConfig {
+    pub data_dir: Option<PathBuf>,
+    pub verbose: bool,
+    pub quiet: bool,
+    pub output_format: OutputFormat,
+    /// `--table-style`, overriding the configured table style
+    pub table_style: Option<TableStyle>,
+    /// `--max-column-width`, overriding the configured width
+    pub max_column_width: Option<usize>,
+    /// Tracker that a `run` script stages its changes in, instead of the data file
+    pub staged: Option<Arc<Mutex<DividendTracker>>>,
+}
+
+impl CliConfig {
+    /// Resolve the data directory (`--data-dir` > `DIVIDEND_TRACKER_DATA_DIR` > default)
+    pub fn resolve_data_dir(&self) -> Result<PathBuf> {
+        paths::resolve_data_dir(self.data_dir.as_deref())
+    }
+
+    /// Create a PersistenceManager with the configured data directory
+    pub fn create_persistence_manager(&self) -> Result<PersistenceManager> {
+        let persistence = PersistenceManager::with_custom_path(self.resolve_data_dir()?)
+            .with_securities(Securities::load().unwrap_or_default());
+        Ok(match &self.staged {
+            Some(staged) => persistence.staged(Arc::clone(staged)),
+            None => persistence,
+        })
+    }
+
+    /// Table layout from config.toml with command-line overrides applied
+    pub fn table_options(&self) -> TableOptions {
+        Config::load()
+            .unwrap_or_default()
+            .display
+            .table_options(self.table_style, self.max_column_width)
+    }
+
+    /// Render a report to stdout in the configured output format
+    pub fn render(&self, report: &Report) -> Result<()> {
+        print!("{}", render::render(report, self.output_format, &self.table_options())?);
+        Ok(())
+    }
+
+    /// Render the report `build` produces, or the output saved by an earlier
+    /// run over the same data and `params`
+    ///
+    /// `params` must cover every argument the report depends on. Without a
+    /// saved data file, or with `cache.enabled = false`, the report is always
+    /// built.
+    pub fn render_cached(
+        &self,
+        persistence: &PersistenceManager,
+        params: &str,
+        build: impl FnOnce() -> Result<Report>,
+    ) -> Result<()> {
+        let config = Config::load().unwrap_or_default();
+        let tables = config.display.table_options(self.table_style, self.max_column_width);
+        let key = match persistence.data_fingerprint()? {
+            Some(fingerprint) if config.cache.enabled => {
+                let settings = format!(
+                    "{:?} {:?} {:?} {:?}",
+                    config.display,
+                    config.analysis,
+                    tables,
+                    persistence.securities()
+                );
+                let format = format!("{:?} {}", self.output_format, colored::control::SHOULD_COLORIZE.should_colorize());
+                Some(AnalyticsCache::key(fingerprint, &[params, &settings, &format]))
+            }
+            _ => None,
+        };
+        let cache = persistence.analytics_cache();
+        if let Some(output) = key.as_deref().and_then(|key| cache.get(key)) {
+            self.print_verbose("Using cached report");
+            print!("{}", output);
+            return Ok(());
+        }
+
+        let output = render::render(&build()?, self.output_format, &tables)?;
+        if let Some(key) = key {
+            if let Err(e) = cache.put(&key, &output) {
+                self.print_verbose(&format!("Could not cache report: {}", e));
+            }
+        }
+        print!("{}", output);
+        Ok(())
+    }
+
+    /// Print message respecting verbose/quiet flags
+    pub fn print(&self, message: &str) {
+        if !self.quiet {
+            println!("{}", message);
+        }
+    }
+
+    /// Print verbose message only in verbose mode
+    pub fn print_verbose(&self, message: &str) {
+        if self.verbose && !self.quiet {
+            println!("🔧 {}", message);
+        }
+    }
+
+    /// Print error message (always shown unless quiet)
+    pub fn print_error(&self, message: &str) {
+        if !self.quiet {
+            eprintln!("❌ {}", message);
+        }
+    }
+
+    /// Print success message (always shown unless quiet)
+    pub fn print_success(&self, message: &str) {
+        if !self.quiet {
+            println!("✅ {}", message);
+        }
+    }
+
+    /// Ask whether to go ahead with a destructive change
+    ///
+    /// `yes` (from `--yes`) answers for the user. Otherwise the question is
+    /// asked on stdout and only `y`/`yes` confirms; end of input declines. In
+    /// quiet mode nothing is asked, so `--yes` is required.
+    pub fn confirm(&self, question: &str, yes: bool) -> Result<bool> {
+        if yes {
+            return Ok(true);
+        }
+        if self.quiet {
+            return Err(anyhow!("Confirmation needed; pass --yes to proceed in quiet mode"));
+        }
+
+        print!("{} [y/N]: ", question);
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        let confirmed = matches!(answer.trim().to_lowercase().as_str(), "y" | "yes");
+        if !confirmed {
+            println!("{}", "Cancelled, nothing was changed.".yellow());
+        }
+        Ok(confirmed)
+    }
+
+    /// Print the records a change touched, old values in red and new in green
+    ///
+    /// Long diffs stop after [`MAX_PRINTED_CHANGES`] records. Nothing is
+    /// printed in quiet mode.
+    pub fn print_changes(&self, diff: &DatasetDiff) {
+        if self.quiet {
+            return;
+        }
+
+        let mut lines: Vec<(String, &[FieldChange])> = Vec::new();
+        for changed in &diff.changed_dividends {
+            let key = format!("{} {}", changed.after.symbol, changed.after.ex_date.format("%Y-%m-%d"));
+            lines.push((key, &changed.changes));
+        }
+        for changed in &diff.changed_holdings {
+            lines.push((changed.after.label(), &changed.changes));
+        }
+
+        for (key, changes) in lines.iter().take(MAX_PRINTED_CHANGES) {
+            println!("  {}", key.cyan());
+            for change in changes.iter() {
+                println!("    {}", format!("- {}: {}", change.field, change.before).red());
+                println!("    {}", format!("+ {}: {}", change.field, change.after).green());
+            }
+        }
+        for dividend in &diff.added_dividends {
+            println!("  {}", format!("+ {} {}", dividend.symbol, dividend.ex_date.format("%Y-%m-%d")).green());
+        }
+        for dividend in &diff.removed_dividends {
+            println!("  {}", format!("- {} {}", dividend.symbol, dividend.ex_date.format("%Y-%m-%d")).red());
+        }
+        for holding in &diff.added_holdings {
+            println!("  {}", format!("+ {}", holding.label()).green());
+        }
+        for holding in &diff.removed_holdings {
+            println!("  {}", format!("- {}", holding.label()).red());
+        }
+        if lines.len() > MAX_PRINTED_CHANGES {
+            println!("  {}", format!("... and {} more records", lines.len() - MAX_PRINTED_CHANGES).dimmed());
+        }
+    }
+}
+
+/// Dispatch a parsed command to its handler
+pub fn run(command: Option<Commands>, config: &CliConfig) -> Result<()> {
+    match command {
+        Some(Commands::Div { command }) => dividends::run(config, command),
+        Some(Commands::Summary(args)) => summary::run(config, args),
+        Some(Commands::Project(args)) => project::run(config, args),
+        Some(Commands::Holdings { command }) => holdings::run(config, command),
+        Some(Commands::Fetch(args)) => fetch::fetch(config, args),
+        Some(Commands::Update(args)) => fetch::update(config, args),
+        Some(Commands::Configure(args)) => fetch::configure(config, args),
+        Some(Commands::Alerts(args)) => alerts::alerts(config, args),
+        Some(Commands::Calendar(args)) => alerts::calendar(config, args),
+        Some(Commands::Data { command }) => data::run(config, command),
+        Some(Commands::Tax { command }) => tax::run(config, command),
+        Some(Commands::Symbol { command }) => symbols::run(config, command),
+        Some(Commands::Actions { command }) => actions::run(config, command),
+        Some(Commands::Journal { command }) => journal::run(config, command),
+        Some(Commands::News(args)) => news::run(config, args),
+        Some(Commands::Show { symbol }) => show::run(config, &symbol),
+        Some(Commands::Transactions { command }) => transactions::run(config, command),
+        Some(Commands::Cash(args)) => cash::run(config, args),
+        Some(Commands::Premiums { command }) => premiums::run(config, command),
+        Some(Commands::Report { command }) => report::run(config, command),
+        Some(Commands::Export { command }) => export::run(config, command),
+        Some(Commands::Analyze { command }) => analyze::run(config, command),
+        Some(Commands::Expenses(args)) => expenses::run(config, args),
+        Some(Commands::Serve(args)) => serve::run(config, args),
+        Some(Commands::Run(args)) => run::run(config, args),
+        Some(Commands::Init(args)) => init::run(config, args),
+        Some(Commands::Doctor(args)) => doctor::run(config, args),
+        None => {
+            println!("{}", "Dividend Tracker CLI".green().bold());
+            if !config.create_persistence_manager()?.data_exists() {
+                println!("No data yet. Run {} to get set up.", "dividend-tracker init".cyan());
+            }
+            println!("Use --help to see available commands");
+            Ok(())
+        }
+    }
+}
+
+/// Load the tracker, with the dividends moved out by `data archive` when asked for
+pub fn load_tracker(persistence: &PersistenceManager, include_archived: bool) -> Result<DividendTracker> {
+    if include_archived {
+        persistence.load_with_archive()
+    } else {
+        persistence.load()
+    }
+}
+
+/// Append a "Did you mean ...?" hint to a message, if there is one
+pub fn with_hint(message: &str, hint: Option<String>) -> String {
+    match hint {
+        Some(hint) => format!("{} {}", message, hint),
+        None => message.to_string(),
+    }
+}
+
+/// Parse natural language date strings like "tomorrow", "next friday", or standard YYYY-MM-DD format
+pub fn parse_dividend_date(date_str: &str) -> Result<NaiveDate> {
+    let date_str = date_str.trim().to_lowercase();
+    let today = Local::now().naive_local().date();
+
+    match date_str.as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + Duration::days(1)),
+        "yesterday" => Ok(today - Duration::days(1)),
+        "next monday" => Ok(next_weekday(today, Weekday::Mon)),
+        "next tuesday" => Ok(next_weekday(today, Weekday::Tue)),
+        "next wednesday" => Ok(next_weekday(today, Weekday::Wed)),
+        "next thursday" => Ok(next_weekday(today, Weekday::Thu)),
+        "next friday" => Ok(next_weekday(today, Weekday::Fri)),
+        "next saturday" => Ok(next_weekday(today, Weekday::Sat)),
+        "next sunday" => Ok(next_weekday(today, Weekday::Sun)),
+        _ => {
+            // Try to parse as standard date format (YYYY-MM-DD)
+            NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+                .map_err(|_| anyhow!("Invalid date format. Use YYYY-MM-DD or natural language like 'tomorrow', 'next friday'"))
+        }
+    }
+}
+
+/// Parse a decimal argument, naming it in the error
+pub fn parse_amount(value: &str, what: &str) -> Result<Decimal> {
+    Decimal::from_str(value)
+        .map_err(|_| anyhow!("Invalid {} format: {}. Use decimal format like 12.50", what, value))
+}
+
+/// Parse an optional date argument, defaulting to today
+pub fn parse_date_or_today(date: Option<&str>) -> Result<NaiveDate> {
+    match date {
+        Some(date) => parse_dividend_date(date),
+        None => Ok(Local::now().naive_local().date()),
+    }
+}
+
+/// Parse an age like "90d", "12w", or "48h" into a duration
+pub fn parse_age(age: &str) -> Result<std::time::Duration> {
+    let age = age.trim().to_lowercase();
+    let (number, unit_secs) = if let Some(n) = age.strip_suffix('d') {
+        (n, 86_400)
+    } else if let Some(n) = age.strip_suffix('w') {
+        (n, 7 * 86_400)
+    } else if let Some(n) = age.strip_suffix('h') {
+        (n, 3_600)
+    } else {
+        (age.as_str(), 86_400)
+    };
+
+    let count: u64 = number
+        .parse()
+        .map_err(|_| anyhow!("Invalid age: {}. Use a number of days like 90d, 12w, or 48h", age))?;
+
+    Ok(std::time::Duration::from_secs(count * unit_secs))
+}
+
+/// Get the next occurrence of a specific weekday
+fn next_weekday(from_date: NaiveDate, target_weekday: Weekday) -> NaiveDate {
+    let current_weekday = from_date.weekday();
+    let days_until_target = (target_weekday.num_days_from_monday() as i64 + 7
+        - current_weekday.num_days_from_monday() as i64)
+        % 7;
+    let days_to_add = if days_until_target == 0 {
+        7
+    } else {
+        days_until_target
+    };
+    from_date + Duration::days(days_to_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dividend_date() {
+        let today = Local::now().naive_local().date();
+        assert_eq!(parse_dividend_date("today").unwrap(), today);
+        assert_eq!(parse_dividend_date("Tomorrow").unwrap(), today + Duration::days(1));
+        assert_eq!(
+            parse_dividend_date("2024-02-15").unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 15).unwrap()
+        );
+        assert!(parse_dividend_date("15/02/2024").is_err());
+    }
+
+    #[test]
+    fn test_next_weekday_skips_today() {
+        // 2024-01-05 is a Friday
+        let friday = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        assert_eq!(
+            next_weekday(friday, Weekday::Fri),
+            NaiveDate::from_ymd_opt(2024, 1, 12).unwrap()
+        );
+        assert_eq!(
+            next_weekday(friday, Weekday::Mon),
+            NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d").unwrap().as_secs(), 90 * 86_400);
+        assert_eq!(parse_age("2w").unwrap().as_secs(), 14 * 86_400);
+        assert_eq!(parse_age("48h").unwrap().as_secs(), 48 * 3_600);
+        assert!(parse_age("soon").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Dividend announcements and headlines for a symbol (`news`)
+
+use anyhow::Result;
+use chrono::{Duration, Local, NaiveDate};
+use colored::*;
+use std::io::{self, IsTerminal};
+
+use super::CliConfig;
+use crate::api::{AlphaVantageClient, DividendAnnouncement, NewsItem};
+use crate::cli::NewsArgs;
+use crate::config::Config;
+use crate::format::format_money;
+use crate::models::DividendCalendarEntry;
+use crate::notifications::NotificationManager;
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+/// Headlines requested from the provider before filtering for dividends
+const NEWS_LIMIT: usize = 50;
+
+/// Show recent dividend announcements and offer to put upcoming ones on the calendar
+pub fn run(config: &CliConfig, args: NewsArgs) -> Result<()> {
+    let NewsArgs {
+        symbol,
+        days,
+        add_to_calendar,
+    } = args;
+    let symbol = symbol.trim().to_uppercase();
+    let today = Local::now().naive_local().date();
+    let since = today - Duration::days(days);
+
+    let settings = Config::load()?;
+    let client = AlphaVantageClient::new(settings.get_api_key()?, &settings.api)?;
+    config.print_verbose(&format!("Fetching dividend announcements for {}", symbol));
+    let announcements = recent_announcements(client.fetch_dividend_announcements(&symbol)?, since, today);
+    // Headlines are extra context; an exhausted quota shouldn't hide the announcements
+    let news = match client.fetch_news(&symbol, NEWS_LIMIT) {
+        Ok(news) => dividend_news(news, since),
+        Err(e) => {
+            config.print(&format!("{} Headlines unavailable: {}", "⚠".yellow(), e));
+            Vec::new()
+        }
+    };
+    config.render(&news_report(&symbol, &announcements, &news, days))?;
+
+    let mut manager = NotificationManager::load(config.resolve_data_dir()?)?;
+    let tracker = config.create_persistence_manager()?.load()?;
+    let pay_lag = tracker.pay_lag_days(&symbol, settings.analysis.default_pay_lag_days);
+    let upcoming: Vec<DividendCalendarEntry> = announcements
+        .iter()
+        .filter(|a| a.ex_date >= today)
+        .map(|a| calendar_entry(a, pay_lag))
+        .filter(|entry| {
+            !manager.calendar.iter().any(|e| {
+                !e.is_estimated
+                    && (!e.pay_date_estimated || entry.pay_date_estimated)
+                    && e.symbol == entry.symbol
+                    && e.ex_date == entry.ex_date
+            })
+        })
+        .collect();
+    if upcoming.is_empty() {
+        return Ok(());
+    }
+
+    // Only ask someone who can answer
+    let question = format!("Add {} upcoming announced dividends to the calendar?", upcoming.len());
+    let interactive = !config.quiet && io::stdin().is_terminal();
+    if !add_to_calendar && !interactive {
+        config.print(&format!("Run with --add-to-calendar to add {} upcoming dividends to the calendar.", upcoming.len()));
+        return Ok(());
+    }
+    if !config.confirm(&question, add_to_calendar)? {
+        return Ok(());
+    }
+
+    let added = upcoming.into_iter().filter(|entry| manager.add_announced(entry.clone())).count();
+    manager.save()?;
+    println!("{} Added {} announced dividends to the calendar", "✓".green(), added);
+    Ok(())
+}
+
+/// Announcements declared since `since`, plus any still to go ex-dividend
+fn recent_announcements(
+    announcements: Vec<DividendAnnouncement>,
+    since: NaiveDate,
+    today: NaiveDate,
+) -> Vec<DividendAnnouncement> {
+    announcements
+        .into_iter()
+        .filter(|a| a.ex_date >= today || a.declaration_date.unwrap_or(a.ex_date) >= since)
+        .collect()
+}
+
+/// Calendar entry for an announced dividend; a missing pay date is
+/// estimated as `pay_lag` days after the ex-date and marked as such
+fn calendar_entry(announcement: &DividendAnnouncement, pay_lag: i64) -> DividendCalendarEntry {
+    let pay_date = announcement.pay_date.unwrap_or(announcement.ex_date + Duration::days(pay_lag));
+    let mut entry = DividendCalendarEntry::new(
+        announcement.symbol.clone(),
+        None,
+        announcement.ex_date,
+        Some(pay_date),
+        Some(announcement.amount),
+        false,
+    );
+    entry.pay_date_estimated = announcement.pay_date.is_none();
+    entry
+}
+
+/// Headlines since `since` that mention dividends
+fn dividend_news(news: Vec<NewsItem>, since: NaiveDate) -> Vec<NewsItem> {
+    news.into_iter()
+        .filter(|item| item.published.date() >= since)
+        .filter(|item| {
+            let text = format!("{} {}", item.title, item.summary).to_lowercase();
+            text.contains("dividend") || text.contains("distribution")
+        })
+        .collect()
+}
+
+fn news_report(symbol: &str, announcements: &[DividendAnnouncement], news: &[NewsItem], days: i64) -> Report {
+    let date = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string());
+
+    let mut declared = Section::new("Announced Dividends").icon("📣");
+    if announcements.is_empty() {
+        declared = declared.note(NoteKind::Info, format!("No dividends declared in the last {} days.", days));
+    } else {
+        let mut table = TableView::new(&["Declared", "Ex-Date", "Record Date", "Pay Date", "Amount"]);
+        for a in announcements {
+            table.row(vec![
+                date(a.declaration_date).into(),
+                a.ex_date.format("%Y-%m-%d").to_string().into(),
+                date(a.record_date).into(),
+                date(a.pay_date).into(),
+                Text::new(format_money(a.amount), Tone::Positive),
+            ]);
+        }
+        declared = declared.table(table);
+    }
+
+    let mut headlines = Section::new("Headlines").icon("📰");
+    if news.is_empty() {
+        headlines = headlines.note(NoteKind::Info, format!("No dividend headlines in the last {} days.", days));
+    } else {
+        let mut table = TableView::new(&["Date", "Source", "Headline", "Link"]);
+        for item in news {
+            table.row(vec![
+                item.published.format("%Y-%m-%d").to_string().into(),
+                item.source.clone().into(),
+                item.title.clone().into(),
+                item.url.clone().into(),
+            ]);
+        }
+        headlines = headlines.table(table);
+    }
+
+    Report::new(format!("Dividend News: {}", symbol))
+        .section(declared)
+        .section(headlines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendTracker, DividendType};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn announcement(declared: Option<&str>, ex_date: &str) -> DividendAnnouncement {
+        DividendAnnouncement {
+            symbol: "KO".to_string(),
+            declaration_date: declared.map(date),
+            ex_date: date(ex_date),
+            record_date: None,
+            pay_date: None,
+            amount: dec!(0.485),
+        }
+    }
+
+    #[test]
+    fn test_recent_announcements_keeps_new_and_upcoming() {
+        let today = date("2024-08-15");
+        let since = date("2024-05-17");
+        let kept = recent_announcements(
+            vec![
+                announcement(None, "2024-11-29"),
+                announcement(Some("2024-07-18"), "2024-09-13"),
+                announcement(Some("2024-04-25"), "2024-06-14"),
+                announcement(None, "2024-03-14"),
+            ],
+            since,
+            today,
+        );
+        let ex_dates: Vec<NaiveDate> = kept.iter().map(|a| a.ex_date).collect();
+        assert_eq!(ex_dates, [date("2024-11-29"), date("2024-09-13")]);
+    }
+
+    #[test]
+    fn test_calendar_entry_estimates_missing_pay_date_from_history() {
+        let mut tracker = DividendTracker::new();
+        assert_eq!(tracker.pay_lag_days("KO", 14), 14);
+        for (ex, pay) in [("2023-11-30", "2023-12-15"), ("2024-02-28", "2024-03-15"), ("2024-05-30", "2024-06-14")] {
+            tracker.add_dividend(
+                Dividend::new("KO".to_string(), None, date(ex), date(pay), dec!(0.485), dec!(100), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+        assert_eq!(tracker.pay_lag_days("KO", 14), 15);
+
+        let estimated = calendar_entry(&announcement(None, "2024-09-13"), tracker.pay_lag_days("KO", 14));
+        assert_eq!(estimated.pay_date, Some(date("2024-09-28")));
+        assert!(estimated.pay_date_estimated);
+        assert!(!estimated.is_estimated);
+
+        let mut declared = announcement(None, "2024-09-13");
+        declared.pay_date = Some(date("2024-10-01"));
+        let confirmed = calendar_entry(&declared, 15);
+        assert!(!confirmed.pay_date_estimated);
+
+        // The announced pay date replaces the estimated one
+        let mut manager = NotificationManager::new("unused");
+        assert!(manager.add_announced(estimated));
+        assert!(manager.add_announced(confirmed.clone()));
+        assert_eq!(manager.calendar, vec![confirmed]);
+    }
+
+    #[test]
+    fn test_dividend_news_filters_topic_and_age() {
+        let item = |title: &str, published: &str| NewsItem {
+            title: title.to_string(),
+            source: "Wire".to_string(),
+            url: String::new(),
+            published: date(published).and_hms_opt(9, 0, 0).unwrap(),
+            summary: String::new(),
+        };
+        let news = dividend_news(
+            vec![
+                item("Coca-Cola raises quarterly Dividend", "2024-08-01"),
+                item("Coca-Cola launches new flavor", "2024-08-02"),
+                item("Coca-Cola declares dividend", "2024-01-01"),
+            ],
+            date("2024-05-17"),
+        );
+        assert_eq!(news.len(), 1);
+        assert_eq!(news[0].title, "Coca-Cola raises quarterly Dividend");
+    }
+}
@@ -0,0 +1,202 @@
+//! Option premium commands (`premiums add`, `premiums list`, `premiums income`)
+
+use anyhow::Result;
+use chrono::Datelike;
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use super::{parse_amount, parse_date_or_today, CliConfig};
+use crate::cli::{PremiumAddArgs, PremiumCommands};
+use crate::format::{format_money, format_percent, percent_of};
+use crate::models::{DividendTracker, OptionPremium};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Option Premiums";
+
+/// Handle option premium commands
+pub fn run(config: &CliConfig, command: PremiumCommands) -> Result<()> {
+    match command {
+        PremiumCommands::Add(args) => add(config, args),
+        PremiumCommands::List { symbol, year } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            config.render(&premiums_report(&tracker, symbol.as_deref(), year))
+        }
+        PremiumCommands::Income { year } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            config.render(&income_report(&tracker, year))
+        }
+    }
+}
+
+fn add(config: &CliConfig, args: PremiumAddArgs) -> Result<()> {
+    let PremiumAddArgs {
+        symbol,
+        amount,
+        date,
+        contracts,
+        note,
+    } = args;
+
+    let mut premium = OptionPremium::new(
+        &symbol,
+        parse_date_or_today(date.as_deref())?,
+        parse_amount(&amount, "premium")?,
+    )?;
+    premium.contracts = contracts;
+    premium.note = note;
+
+    let persistence = config.create_persistence_manager()?;
+    let (premium, hint) = persistence.with_tracker_mut(|tracker| {
+        premium.symbol = tracker.canonical_symbol(&premium.symbol);
+        let hint = tracker.symbol_hint(&premium.symbol);
+        tracker.premiums.push(premium.clone());
+        Ok((premium, hint))
+    })?;
+
+    println!(
+        "{} Recorded {} premium on {} ({})",
+        "✓".green(),
+        format_money(premium.amount).green(),
+        premium.symbol.cyan(),
+        premium.date.format("%Y-%m-%d").to_string().blue()
+    );
+    if let Some(hint) = hint {
+        println!("{}", format!("{} has no holding or dividends yet. {}", premium.symbol, hint).yellow());
+    }
+    Ok(())
+}
+
+/// Premiums for a symbol and year, newest first
+fn premiums<'a>(tracker: &'a DividendTracker, symbol: Option<&str>, year: Option<i32>) -> Vec<&'a OptionPremium> {
+    let symbol = symbol.map(|s| tracker.canonical_symbol(s));
+    let mut premiums: Vec<&OptionPremium> = tracker
+        .premiums
+        .iter()
+        .filter(|p| symbol.as_ref().is_none_or(|s| p.symbol == *s))
+        .filter(|p| year.is_none_or(|y| p.date.year() == y))
+        .collect();
+    premiums.sort_by_key(|p| std::cmp::Reverse(p.date));
+    premiums
+}
+
+fn premiums_report(tracker: &DividendTracker, symbol: Option<&str>, year: Option<i32>) -> Report {
+    let premiums = premiums(tracker, symbol, year);
+    if premiums.is_empty() {
+        return Report::warning(TITLE, "No option premiums recorded. Add one with 'premiums add SYMBOL AMOUNT'.");
+    }
+
+    let mut table = TableView::new(&["Date", "Symbol", "Contracts", "Premium", "Note"]);
+    for premium in &premiums {
+        table.row(vec![
+            premium.date.format("%Y-%m-%d").to_string().into(),
+            premium.symbol.clone().into(),
+            premium.contracts.map(|c| c.to_string()).unwrap_or_default().into(),
+            Text::new(format_money(premium.amount), Tone::Positive),
+            premium.note.clone().unwrap_or_default().into(),
+        ]);
+    }
+
+    let total: Decimal = premiums.iter().map(|p| p.amount).sum();
+    let title = match year {
+        Some(year) => format!("{} for {}", TITLE, year),
+        None => TITLE.to_string(),
+    };
+    Report::new(title).section(
+        Section::untitled()
+            .table(table)
+            .field("Premiums Recorded", premiums.len().to_string())
+            .field("Total Premiums", Text::new(format_money(total), Tone::Emphasis)),
+    )
+}
+
+/// Net dividend income and option premiums per symbol, optionally for one year
+fn income_by_symbol(tracker: &DividendTracker, year: Option<i32>) -> BTreeMap<String, (Decimal, Decimal)> {
+    let mut by_symbol: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
+    for dividend in tracker.dividends.iter().filter(|d| year.is_none_or(|y| d.pay_date.year() == y)) {
+        by_symbol.entry(dividend.symbol.clone()).or_default().0 += dividend.net_amount();
+    }
+    for premium in tracker.premiums.iter().filter(|p| year.is_none_or(|y| p.date.year() == y)) {
+        by_symbol.entry(premium.symbol.clone()).or_default().1 += premium.amount;
+    }
+    by_symbol
+}
+
+fn income_report(tracker: &DividendTracker, year: Option<i32>) -> Report {
+    let title = match year {
+        Some(year) => format!("Combined Income for {}", year),
+        None => "Combined Income".to_string(),
+    };
+    let by_symbol = income_by_symbol(tracker, year);
+    if by_symbol.is_empty() {
+        return Report::warning(title, "No dividends or option premiums recorded for this period.");
+    }
+
+    let mut table = TableView::new(&["Symbol", "Dividends", "Premiums", "Total", "From Premiums"]);
+    for (symbol, (dividends, premiums)) in &by_symbol {
+        let total = *dividends + *premiums;
+        table.row(vec![
+            symbol.clone().into(),
+            format_money(*dividends).into(),
+            format_money(*premiums).into(),
+            Text::new(format_money(total), Tone::Emphasis),
+            percent_of(*premiums, total).map(|p| format_percent(p, 1)).unwrap_or_default().into(),
+        ]);
+    }
+
+    let dividends: Decimal = by_symbol.values().map(|(d, _)| *d).sum();
+    let premiums: Decimal = by_symbol.values().map(|(_, p)| *p).sum();
+    Report::new(title).section(
+        Section::untitled()
+            .table(table)
+            .field("Dividend Income", format_money(dividends))
+            .field("Option Premiums", format_money(premiums))
+            .field("Total Income", Text::new(format_money(dividends + premiums), Tone::Emphasis))
+            .note(
+                NoteKind::Info,
+                "Option premiums are left out of dividend summaries, projections, yields and tax reports.",
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType};
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_income_by_symbol_combines_dividends_and_premiums() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(
+            Dividend::new(
+                "KO".to_string(),
+                None,
+                date("2024-03-14"),
+                date("2024-04-01"),
+                dec!(0.485),
+                dec!(100),
+                DividendType::Regular,
+            )
+            .unwrap(),
+        );
+        tracker.premiums.push(OptionPremium::new("ko", date("2024-05-10"), dec!(62.50)).unwrap());
+        tracker.premiums.push(OptionPremium::new("PEP", date("2023-11-03"), dec!(80)).unwrap());
+
+        let all = income_by_symbol(&tracker, None);
+        assert_eq!(all["KO"], (dec!(48.50), dec!(62.50)));
+        assert_eq!(all["PEP"], (Decimal::ZERO, dec!(80)));
+
+        let this_year = income_by_symbol(&tracker, Some(2024));
+        assert_eq!(this_year.len(), 1);
+
+        let listed = premiums(&tracker, None, None);
+        assert_eq!(listed[0].symbol, "KO");
+        assert!(OptionPremium::new("KO", date("2024-05-10"), dec!(-1)).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+//! Dividend income projection command
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+
+use super::CliConfig;
+use crate::cli::{ProjectArgs, ProjectCommands};
+use crate::config::Config;
+use crate::format::{format_money, format_percent, format_signed_percent, percent_of};
+use crate::models::DividendTracker;
+use crate::projections::{BlendedEstimate, DividendProjection, PlanReview, ProjectionEngine, ProjectionMethod};
+use crate::render::{signed_money, NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Dividend Income Projections";
+const REVIEW_TITLE: &str = "Projection Review";
+
+/// Handle dividend projection command
+pub fn run(config: &CliConfig, args: ProjectArgs) -> Result<()> {
+    if let Some(ProjectCommands::Review { year }) = args.command {
+        return review(config, year);
+    }
+    let export_csv = args.export_csv.clone();
+    let export_json = args.export_json.clone();
+    let save_plan = args.save_plan;
+
+    // Load persistence manager and existing data
+    let persistence = config
+        .create_persistence_manager()?
+        .with_frequency_overrides(&args.frequency_override);
+    let today = Local::now().naive_local().date();
+
+    // Exports and saved plans need the projection itself
+    if export_csv.is_none() && export_json.is_none() && !save_plan {
+        let params = format!("project {:?} {}", args, today);
+        return config.render_cached(&persistence, &params, || Ok(build_projection(&persistence.load()?, args, today)?.0));
+    }
+
+    let tracker = persistence.load()?;
+    let (report, projection) = build_projection(&tracker, args, today)?;
+    config.render(&report)?;
+    let Some(projection) = projection else {
+        return Ok(());
+    };
+
+    // Export to CSV if requested
+    if let Some(csv_path) = export_csv {
+        ProjectionEngine::export_to_csv(&projection, &csv_path)?;
+        config.print_success(&format!("Projections exported to {}", csv_path.display()));
+    }
+
+    // Export to JSON if requested
+    if let Some(json_path) = export_json {
+        ProjectionEngine::export_to_json(&projection, &json_path)?;
+        config.print_success(&format!("Projections exported to {}", json_path.display()));
+    }
+
+    if save_plan {
+        let year = projection.year;
+        let plan = projection.to_plan(today);
+        let total = plan.total();
+        let replaced = persistence.with_tracker_mut(|tracker| Ok(tracker.projection_plans.insert(year.to_string(), plan)))?;
+
+        println!(
+            "{} Saved the {} projection of {} as the plan for {}",
+            "✓".green(),
+            projection.growth_scenario.name(),
+            format_money(total).cyan(),
+            year
+        );
+        if let Some(replaced) = replaced {
+            println!("  Replaced the plan saved on {}", replaced.saved_on.format("%Y-%m-%d"));
+        }
+        println!("  Track it with 'project review --year {}'", year);
+    }
+
+    Ok(())
+}
+
+/// Handle `project review`
+fn review(config: &CliConfig, year: Option<i32>) -> Result<()> {
+    let tracker = config.create_persistence_manager()?.load()?;
+    let today = Local::now().naive_local().date();
+    let year = year.unwrap_or_else(|| today.year());
+    let settings = Config::load().unwrap_or_default().analysis;
+
+    let report = match ProjectionEngine::review_plan(&tracker, year, &settings, today) {
+        Some(review) => review_report(&review),
+        None => Report::warning(
+            REVIEW_TITLE,
+            format!("No plan saved for {}. Save one with 'project --year {} --save-plan'.", year, year),
+        ),
+    };
+    config.render(&report)
+}
+
+/// Build the projection report for a loaded tracker, with the projection behind it
+///
+/// Without holdings or dividend history only a warning report comes back.
+pub fn build_projection(
+    tracker: &DividendTracker,
+    args: ProjectArgs,
+    today: NaiveDate,
+) -> Result<(Report, Option<DividendProjection>)> {
+    let ProjectArgs {
+        command: _,
+        method,
+        growth_rate: growth_scenario,
+        year,
+        export_csv: _,
+        export_json: _,
+        monthly,
+        blend,
+        save_plan: _,
+        frequency_override: _,
+    } = args;
+
+    if tracker.holdings.is_empty() {
+        return Ok((
+            Report::new(TITLE).section(
+                Section::untitled()
+                    .note(NoteKind::Warning, "No holdings found. Add holdings first to generate projections.")
+                    .note(NoteKind::Tip, "Use the 'holdings add' command to add your stock positions."),
+            ),
+            None,
+        ));
+    }
+
+    if tracker.dividends.is_empty() {
+        return Ok((
+            Report::new(TITLE).section(
+                Section::untitled()
+                    .note(NoteKind::Warning, "No dividend history found. Add dividend records first.")
+                    .note(NoteKind::Tip, "Use the 'add' command to add historical dividend payments."),
+            ),
+            None,
+        ));
+    }
+
+    let projection_method = ProjectionMethod::from(method);
+
+    // Blending only makes sense for the year in progress
+    let year = match (blend, year) {
+        (true, Some(year)) if year != today.year() => {
+            return Err(anyhow!(
+                "--blend only applies to the current year ({}), not {}",
+                today.year(),
+                year
+            ));
+        }
+        (true, None) => Some(today.year()),
+        (_, year) => year,
+    };
+
+    // Generate projections
+    let settings = Config::load().unwrap_or_default().analysis;
+    let mut projection = ProjectionEngine::generate_projection(
+        tracker,
+        projection_method,
+        growth_scenario,
+        year,
+        &settings,
+    )?;
+
+    if blend {
+        projection.blend = Some(ProjectionEngine::blend_with_actuals(&projection, tracker, &settings, today)?);
+    }
+
+    Ok((projection_report(&projection, monthly), Some(projection)))
+}
+
+/// Build the projection report view, optionally with the monthly cash flow
+pub fn projection_report(projection: &DividendProjection, monthly: bool) -> Report {
+    let mut report = Report::new(TITLE).section(projection_summary(projection));
+
+    if let Some(blend) = &projection.blend {
+        report.push(blended_estimate(projection, blend, monthly));
+    }
+
+    if monthly {
+        report.push(monthly_projections(projection));
+    }
+
+    if !projection.stock_projections.is_empty() {
+        report.push(stock_projections(projection));
+    }
+
+    report.section(projection_metadata(projection))
+}
+
+/// Build the plan review view: totals to date and each month against the plan
+pub fn review_report(review: &PlanReview) -> Report {
+    let plan = &review.plan;
+    let planned_to_date = review.planned_to_date();
+    let difference = review.actual_to_date() - planned_to_date;
+
+    let mut summary = Section::new(format!("Plan vs Actual for {}", review.year))
+        .icon("🎯")
+        .field(
+            "Plan Saved",
+            Text::new(
+                format!("{} ({}, {})", plan.saved_on.format("%Y-%m-%d"), plan.method, plan.growth_scenario),
+                Tone::Highlight,
+            ),
+        )
+        .field("Planned Annual Income", Text::new(format_money(plan.total()), Tone::Highlight))
+        .field("As Of", review.as_of.format("%Y-%m-%d").to_string())
+        .field("Months Complete", format!("{} of 12", review.elapsed_months));
+
+    if review.elapsed_months == 0 {
+        summary = summary.note(
+            NoteKind::Info,
+            format!("No month of {} is over yet; actuals appear once January ends.", review.year),
+        );
+    } else {
+        let status = signed_money(difference);
+        let label = if difference >= Decimal::ZERO { "Ahead of Plan" } else { "Behind Plan" };
+        let value = match percent_of(difference, planned_to_date) {
+            Some(percent) => format!("{} ({})", status.text, format_signed_percent(percent, 1)),
+            None => status.text,
+        };
+        summary = summary
+            .field("Planned to Date", format_money(planned_to_date))
+            .field("Actual to Date", Text::new(format_money(review.actual_to_date()), Tone::Positive))
+            .field(label, Text::new(value, status.tone));
+    }
+
+    let mut table = TableView::new(&["Month", "Planned", "Actual", "Difference", "Cumulative"]);
+    let mut cumulative = Decimal::ZERO;
+    for month in &review.months {
+        let name = NaiveDate::from_ymd_opt(review.year, month.month, 1)
+            .map(|date| date.format("%B").to_string())
+            .unwrap_or_default();
+        let row = match month.actual {
+            Some(actual) => {
+                cumulative += actual - month.planned;
+                vec![
+                    name.into(),
+                    format_money(month.planned).into(),
+                    format_money(actual).into(),
+                    signed_money(actual - month.planned),
+                    signed_money(cumulative),
+                ]
+            }
+            None => vec![name.into(), format_money(month.planned).into(), "-".into(), "-".into(), "-".into()],
+        };
+        table.row(row);
+    }
+
+    Report::new(REVIEW_TITLE).section(summary).section(
+        Section::new("Monthly Comparison")
+            .icon("📅")
+            .table(table),
+    )
+}
+
+fn projection_summary(projection: &DividendProjection) -> Section {
+    let monthly_average = projection.total_projected_income / Decimal::from(12);
+    let metadata = &projection.metadata;
+
+    let section = Section::new("Projection Summary")
+        .icon("📊")
+        .field("Target Year", Text::new(projection.year.to_string(), Tone::Highlight))
+        .field(
+            "Projection Method",
+            Text::new(format!("{:?}", projection.method), Tone::Highlight),
+        )
+        .field(
+            "Growth Scenario",
+            Text::new(projection.growth_scenario.name(), Tone::Highlight),
+        )
+        .field(
+            "Projected Annual Income",
+            Text::new(format_money(projection.total_projected_income), Tone::Positive),
+        )
+        .field(
+            "Average Monthly Income",
+            Text::new(format_money(monthly_average), Tone::Warning),
+        );
+
+    if !metadata.exclusion_is_significant() {
+        return section;
+    }
+
+    section.note(
+        NoteKind::Warning,
+        format!(
+            "Income is under-reported: {} of your holdings ({}) have no dividend history and are left out. \
+             Add their dividend history or set one with 'holdings set-forward-dividend' to include them.",
+            format_percent(metadata.excluded_weight * Decimal::from(100), 1),
+            metadata.stocks_excluded.join(", ")
+        ),
+    )
+}
+
+fn blended_estimate(projection: &DividendProjection, blend: &BlendedEstimate, monthly: bool) -> Section {
+    let mut section = Section::new("Year-to-Date Blend")
+        .icon("🧮")
+        .field("As Of", Text::new(blend.as_of.format("%Y-%m-%d").to_string(), Tone::Highlight))
+        .field(
+            format!("Actual Received ({} months)", blend.elapsed_months),
+            Text::new(format_money(blend.actual_to_date), Tone::Positive),
+        )
+        .field(
+            format!("Projected Remaining ({} months)", 12 - blend.elapsed_months),
+            Text::new(format_money(blend.projected_remaining), Tone::Warning),
+        )
+        .field(
+            "Full-Year Estimate",
+            Text::new(format_money(blend.full_year_estimate), Tone::Positive),
+        )
+        .field(
+            "YTD Progress",
+            Text::new(format_percent(blend.ytd_progress, 1), Tone::Highlight),
+        );
+
+    if monthly {
+        let mut table = TableView::new(&["Month", "Amount", "Basis"]);
+        for month in &blend.monthly {
+            let month_name = projection
+                .monthly_projections
+                .get(&month.month)
+                .map(|m| m.month_name.clone())
+                .unwrap_or_default();
+            let basis = if month.actual {
+                Text::new("Actual", Tone::Positive)
+            } else {
+                Text::new("Projected", Tone::Warning)
+            };
+            table.row(vec![month_name.into(), format_money(month.amount).into(), basis]);
+        }
+        section = section.table(table);
+    }
+
+    section
+}
+
+fn monthly_projections(projection: &DividendProjection) -> Section {
+    let mut table = TableView::new(&["Month", "Projected Income", "Payments", "Top Contributors"]);
+
+    for month in 1..=12 {
+        if let Some(monthly) = projection.monthly_projections.get(&month) {
+            let top_contributors = if monthly.top_payers.len() > 3 {
+                format!("{}, +{} more",
+                        monthly.top_payers[..3].join(", "),
+                        monthly.top_payers.len() - 3)
+            } else {
+                monthly.top_payers.join(", ")
+            };
+
+            table.row(vec![
+                monthly.month_name.clone().into(),
+                format_money(monthly.projected_amount).into(),
+                monthly.payment_count.to_string().into(),
+                top_contributors.into(),
+            ]);
+        }
+    }
+
+    Section::new("Monthly Projected Cash Flow").icon("📅").table(table)
+}
+
+fn stock_projections(projection: &DividendProjection) -> Section {
+    let mut table = TableView::new(&[
+        "Symbol",
+        "Shares",
+        "Current $/Share",
+        "Projected $/Share",
+        "Annual Projection",
+        "Frequency",
+    ]);
+
+    // Sort by projected annual dividend (highest first)
+    let mut sorted_stocks: Vec<_> = projection.stock_projections.iter().collect();
+    sorted_stocks.sort_by_key(|s| std::cmp::Reverse(s.projected_annual_dividend));
+
+    for stock in sorted_stocks {
+        table.row(vec![
+            stock.label().into(),
+            stock.current_shares.to_string().into(),
+            format!("${:.3}", stock.historical_dividend_per_share).into(),
+            format!("${:.3}", stock.projected_dividend_per_share).into(),
+            format_money(stock.projected_annual_dividend).into(),
+            stock.payment_frequency.name().into(),
+        ]);
+    }
+
+    Section::new("Individual Stock Projections").icon("📈").table(table)
+}
+
+fn projection_metadata(projection: &DividendProjection) -> Section {
+    let metadata = &projection.metadata;
+
+    let mut section = Section::new("Projection Details")
+        .icon("ℹ️")
+        .field(
+            "Confidence Score",
+            Text::new(format!("{}%", metadata.confidence_score), Tone::Highlight),
+        )
+        .field(
+            "Historical Data Points",
+            Text::new(metadata.data_points_used.to_string(), Tone::Highlight),
+        )
+        .field(
+            "Stocks Included",
+            Text::new(metadata.stocks_included.to_string(), Tone::Highlight),
+        );
+
+    if !metadata.stocks_excluded.is_empty() {
+        section = section.field(
+            "Stocks Excluded",
+            Text::new(
+                format!(
+                    "{} ({}) - no historical dividend data",
+                    metadata.stocks_excluded.len(),
+                    metadata.stocks_excluded.join(", ")
+                ),
+                Tone::Warning,
+            ),
+        );
+        section = section.field(
+            "Excluded Share of Holdings",
+            Text::new(
+                format_percent(metadata.excluded_weight * Decimal::from(100), 1),
+                Tone::Warning,
+            ),
+        );
+    }
+
+    if let (Some(start), Some(end)) = metadata.historical_range {
+        section = section.field(
+            "Historical Range",
+            Text::new(
+                format!("{} to {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d")),
+                Tone::Highlight,
+            ),
+        );
+    }
+
+    // Confidence interpretation
+    let (kind, interpretation) = match metadata.confidence_score {
+        90..=100 => (NoteKind::Info, "High confidence based on comprehensive historical data"),
+        70..=89 => (NoteKind::Tip, "Moderate confidence - consider updating historical data"),
+        50..=69 => (NoteKind::Warning, "Low confidence - projections are estimates only"),
+        _ => (NoteKind::Warning, "Very low confidence - add more historical data"),
+    };
+
+    section.note(kind, interpretation)
+}
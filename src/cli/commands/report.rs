@@ -0,0 +1,449 @@
+//! Consolidated statements (`report annual`, `report giving`)
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use rust_decimal::Decimal;
+use std::path::Path;
+
+use super::{parse_amount, CliConfig};
+use crate::cli::{AnnualReportArgs, GivingBasisArg, GivingPeriodArg, GivingReportArgs, ReportCommands};
+use crate::config::{Config, DateBasis};
+use crate::filters::DividendFilter;
+use crate::format::{format_money, round_money};
+use crate::models::{CashMovementKind, DividendTracker};
+use crate::paths;
+use crate::render::{signed_percent, NoteKind, Report, Section, TableView, Text, Tone};
+use crate::run_rate::ForwardIncome;
+use crate::tax::{EstimatedTax, TaxAnalyzer, TaxAssumptions};
+
+const TITLE: &str = "Annual Income Statement";
+const GIVING_TITLE: &str = "Giving Report";
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October",
+    "November", "December",
+];
+
+/// One year of dividend income, from gross payments down to what was kept
+#[derive(Debug, Clone)]
+pub struct AnnualStatement {
+    pub year: i32,
+    pub payments: usize,
+    pub gross: Decimal,
+    pub withholding: Decimal,
+    pub fees: Decimal,
+    /// Withholding reclaims refunded during the year
+    pub refunds: Decimal,
+    /// Reinvested from the cash ledger, `None` when the ledger is off
+    pub reinvested: Option<Decimal>,
+    pub estimated_tax: Option<EstimatedTax>,
+    /// Annual income the portfolio was paying at the end of the year
+    pub ending_run_rate: Decimal,
+    /// The same run-rate a year earlier, for comparison
+    pub starting_run_rate: Decimal,
+}
+
+impl AnnualStatement {
+    /// Build the statement from payments made (by pay date) during `year`
+    ///
+    /// `as_of` caps the year end, so the current year reports to date.
+    pub fn build(
+        tracker: &DividendTracker,
+        year: i32,
+        assumptions: Option<TaxAssumptions>,
+        as_of: NaiveDate,
+    ) -> Result<Self> {
+        let dividends = DividendFilter::new()
+            .date_basis(DateBasis::PayDate)
+            .year(year)
+            .apply(&tracker.dividends);
+
+        let year_end = NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(as_of).min(as_of);
+        let refunds = tracker
+            .reclaims
+            .iter()
+            .filter(|r| r.refund_date.is_some_and(|d| d.year() == year))
+            .filter_map(|r| r.refunded)
+            .sum();
+        let reinvested = tracker.cash.as_ref().map(|ledger| {
+            ledger
+                .movements
+                .iter()
+                .filter(|m| m.kind == CashMovementKind::Reinvestment && m.date.year() == year)
+                .map(|m| m.amount)
+                .sum()
+        });
+        let accounts = Config::load().unwrap_or_default().accounts;
+        let estimated_tax =
+            TaxAnalyzer::generate_tax_summary_for_accounts(tracker, year, assumptions, &accounts)?.estimated_tax;
+
+        Ok(AnnualStatement {
+            year,
+            payments: dividends.len(),
+            gross: dividends.iter().map(|d| d.total_amount).sum(),
+            withholding: dividends.iter().filter_map(|d| d.withholding_tax).sum(),
+            fees: dividends.iter().map(|d| d.fee_amount()).sum(),
+            refunds,
+            reinvested,
+            estimated_tax,
+            ending_run_rate: ForwardIncome::calculate(tracker, year_end).total(),
+            starting_run_rate: NaiveDate::from_ymd_opt(year - 1, 12, 31)
+                .map(|date| ForwardIncome::calculate(tracker, date).total())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Cash received: gross less withholding and fees
+    pub fn net_received(&self) -> Decimal {
+        self.gross - self.withholding - self.fees
+    }
+
+    /// What is left once the estimated tax is paid, refunds included
+    pub fn net_after_tax(&self) -> Decimal {
+        let tax = self
+            .estimated_tax
+            .as_ref()
+            .map(|t| t.total_estimated_tax)
+            .unwrap_or(Decimal::ZERO);
+        self.net_received() + self.refunds - tax
+    }
+}
+
+/// Handle report commands
+pub fn run(config: &CliConfig, command: ReportCommands) -> Result<()> {
+    match command {
+        ReportCommands::Annual(args) => annual(config, args),
+        ReportCommands::Giving(args) => giving(config, args),
+    }
+}
+
+fn annual(config: &CliConfig, args: AnnualReportArgs) -> Result<()> {
+    let AnnualReportArgs {
+        year,
+        filing_status,
+        income_bracket,
+    } = args;
+
+    let tracker = config.create_persistence_manager()?.load()?;
+    let today = Local::now().naive_local().date();
+    let year = year.unwrap_or_else(|| today.year());
+
+    let assumptions = TaxAssumptions {
+        filing_status: filing_status.into(),
+        income_bracket: income_bracket.into(),
+        tax_year: year,
+    };
+    let statement = AnnualStatement::build(&tracker, year, Some(assumptions), today)?;
+
+    config.render(&annual_report(&statement, today))
+}
+
+/// Render the statement as a single report
+pub fn annual_report(statement: &AnnualStatement, today: NaiveDate) -> Report {
+    if statement.payments == 0 {
+        return Report::warning(TITLE, format!("No dividends were paid in {}.", statement.year));
+    }
+
+    let less = |amount: Decimal| Text::new(format!("-{}", format_money(amount)), Tone::Negative);
+    let mut income = Section::new(format!("Income Statement for {}", statement.year))
+        .icon("📄")
+        .field("Payments", statement.payments.to_string())
+        .field("Gross Dividends", Text::new(format_money(statement.gross), Tone::Emphasis))
+        .field("Foreign Withholding", less(statement.withholding))
+        .field("Fees", less(statement.fees))
+        .field("Net Received", Text::new(format_money(statement.net_received()), Tone::Positive));
+    if statement.refunds > Decimal::ZERO {
+        income = income.field(
+            "Withholding Refunded",
+            Text::new(format!("+{}", format_money(statement.refunds)), Tone::Positive),
+        );
+    }
+    income = match statement.reinvested {
+        Some(reinvested) => income.field("Reinvested", format_money(reinvested)),
+        None => income.field("Reinvested", Text::new("not tracked", Tone::Plain)),
+    };
+    if let Some(tax) = &statement.estimated_tax {
+        income = income
+            .field("Estimated Tax", less(tax.total_estimated_tax))
+            .field("Net After Tax", Text::new(format_money(statement.net_after_tax()), Tone::Positive));
+    }
+
+    let mut run_rate = Section::new("Forward Income")
+        .icon("📈")
+        .field(
+            "Run-Rate at Year End",
+            Text::new(format_money(statement.ending_run_rate), Tone::Highlight),
+        )
+        .field("Monthly Run-Rate", format_money(statement.ending_run_rate / Decimal::from(12)));
+    if statement.starting_run_rate > Decimal::ZERO {
+        let change = (statement.ending_run_rate - statement.starting_run_rate) / statement.starting_run_rate
+            * Decimal::from(100);
+        run_rate = run_rate
+            .field("Run-Rate a Year Earlier", format_money(statement.starting_run_rate))
+            .field("Change", signed_percent(change));
+    }
+    run_rate = run_rate.note(
+        NoteKind::Info,
+        "Run-rate: each holding's latest regular dividend per share x payments per year x shares held.",
+    );
+
+    let mut report = Report::new(TITLE).section(income).section(run_rate);
+    if statement.year == today.year() {
+        report.push(Section::untitled().note(
+            NoteKind::Warning,
+            format!("{} is not over yet; figures are year to date.", statement.year),
+        ));
+    }
+    if statement.reinvested.is_none() {
+        report.push(Section::untitled().note(
+            NoteKind::Tip,
+            "Track reinvested dividends with 'cash enable' and 'cash reinvest'.",
+        ));
+    }
+    report
+}
+
+/// Giving set aside for one month or quarter
+#[derive(Debug, Clone, PartialEq)]
+pub struct GivingPeriod {
+    pub label: String,
+    pub income: Decimal,
+    pub giving: Decimal,
+}
+
+/// A year of dividend income split into periods with the share to give from each
+#[derive(Debug, Clone, PartialEq)]
+pub struct GivingSchedule {
+    pub year: i32,
+    pub percent: Decimal,
+    pub basis: GivingBasisArg,
+    pub periods: Vec<GivingPeriod>,
+}
+
+impl GivingSchedule {
+    /// Split the dividends received (by pay date) in `year` into periods
+    pub fn build(
+        tracker: &DividendTracker,
+        year: i32,
+        percent: Decimal,
+        by: GivingPeriodArg,
+        basis: GivingBasisArg,
+    ) -> Result<Self> {
+        if percent <= Decimal::ZERO || percent > Decimal::from(100) {
+            bail!("Giving percentage must be between 0 and 100, got {}", percent);
+        }
+
+        let labels: Vec<String> = match by {
+            GivingPeriodArg::Month => MONTH_NAMES.iter().map(|m| m.to_string()).collect(),
+            GivingPeriodArg::Quarter => (1..=4).map(|q| format!("Q{}-{}", q, year)).collect(),
+        };
+        let mut periods: Vec<GivingPeriod> = labels
+            .into_iter()
+            .map(|label| GivingPeriod {
+                label,
+                income: Decimal::ZERO,
+                giving: Decimal::ZERO,
+            })
+            .collect();
+
+        let dividends = DividendFilter::new()
+            .date_basis(DateBasis::PayDate)
+            .year(year)
+            .apply(&tracker.dividends);
+        for dividend in dividends {
+            let index = match by {
+                GivingPeriodArg::Month => dividend.pay_date.month0() as usize,
+                GivingPeriodArg::Quarter => dividend.pay_date.month0() as usize / 3,
+            };
+            periods[index].income += match basis {
+                GivingBasisArg::Gross => dividend.total_amount,
+                GivingBasisArg::Net => dividend.cash_credited(),
+            };
+        }
+        for period in &mut periods {
+            period.giving = period.income * percent / Decimal::from(100);
+        }
+
+        Ok(GivingSchedule {
+            year,
+            percent,
+            basis,
+            periods,
+        })
+    }
+
+    pub fn total_income(&self) -> Decimal {
+        self.periods.iter().map(|p| p.income).sum()
+    }
+
+    pub fn total_giving(&self) -> Decimal {
+        self.periods.iter().map(|p| p.giving).sum()
+    }
+
+    /// Write the schedule as CSV, one row per period plus a total
+    pub fn export_to_csv(&self, output_path: &Path) -> Result<()> {
+        let mut wtr = csv::Writer::from_writer(paths::create_file(output_path)?);
+        wtr.write_record(["Period", "Income", "Percent", "Giving"])?;
+
+        let percent = self.percent.normalize().to_string();
+        for period in &self.periods {
+            wtr.write_record([
+                period.label.as_str(),
+                &round_money(period.income).to_string(),
+                &percent,
+                &round_money(period.giving).to_string(),
+            ])?;
+        }
+        wtr.write_record([
+            "Total",
+            &round_money(self.total_income()).to_string(),
+            &percent,
+            &round_money(self.total_giving()).to_string(),
+        ])?;
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+fn giving(config: &CliConfig, args: GivingReportArgs) -> Result<()> {
+    let GivingReportArgs {
+        pct,
+        year,
+        by,
+        basis,
+        export_csv,
+    } = args;
+
+    let percent = parse_amount(pct.trim_end_matches('%'), "percentage")?;
+    let tracker = config.create_persistence_manager()?.load()?;
+    let year = year.unwrap_or_else(|| Local::now().year());
+    let schedule = GivingSchedule::build(&tracker, year, percent, by, basis)?;
+
+    config.render(&giving_report(&schedule))?;
+
+    if let Some(csv_path) = export_csv {
+        schedule.export_to_csv(&csv_path)?;
+        config.print_success(&format!("Giving schedule exported to {}", csv_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Render the giving schedule with running totals
+pub fn giving_report(schedule: &GivingSchedule) -> Report {
+    if schedule.total_income().is_zero() {
+        return Report::warning(GIVING_TITLE, format!("No dividends were received in {}.", schedule.year));
+    }
+
+    let basis = match schedule.basis {
+        GivingBasisArg::Gross => "gross dividends",
+        GivingBasisArg::Net => "dividends received after withholding and fees",
+    };
+
+    let mut table = TableView::new(&["Period", "Income", "Giving", "Cumulative"]);
+    let mut cumulative = Decimal::ZERO;
+    for period in &schedule.periods {
+        cumulative += period.giving;
+        table.row(vec![
+            period.label.clone().into(),
+            format_money(period.income).into(),
+            Text::new(format_money(period.giving), Tone::Positive),
+            format_money(cumulative).into(),
+        ]);
+    }
+
+    Report::new(GIVING_TITLE).section(
+        Section::new(format!("{}% of {} income", schedule.percent.normalize(), schedule.year))
+            .icon("🤲")
+            .table(table)
+            .field("Total Income", format_money(schedule.total_income()))
+            .field("Total Giving", Text::new(format_money(schedule.total_giving()), Tone::Emphasis))
+            .note(NoteKind::Info, format!("Giving is calculated on {}.", basis)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CashMovement, Dividend, DividendType, Holding};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dividend(symbol: &str, pay_date: &str, amount: Decimal, shares: Decimal) -> Dividend {
+        Dividend::new(
+            symbol.to_string(),
+            None,
+            date(pay_date),
+            date(pay_date),
+            amount,
+            shares,
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_annual_statement_gross_to_net() {
+        let mut tracker = DividendTracker::new();
+        for pay_date in ["2023-04-01", "2023-07-01", "2023-10-01", "2023-12-15"] {
+            tracker.add_dividend(dividend("KO", pay_date, dec!(0.46), dec!(100)));
+        }
+        for pay_date in ["2024-04-01", "2024-07-01", "2024-10-01", "2024-12-15"] {
+            tracker.add_dividend(dividend("KO", pay_date, dec!(0.485), dec!(100)));
+        }
+        let mut nestle = dividend("NESN", "2024-04-26", dec!(3), dec!(40));
+        nestle.withholding_tax = Some(dec!(42));
+        nestle.set_fees(dec!(2)).unwrap();
+        tracker.add_dividend(nestle);
+        tracker.add_dividend(dividend("T", "2024-02-01", dec!(0.2775), dec!(100)));
+        let mut closed = Holding::new("T".to_string(), dec!(100), None, None).unwrap();
+        closed.closed_date = Some(date("2024-06-30"));
+        tracker.add_holding(closed);
+
+        tracker.enable_cash_ledger(date("2024-01-01")).unwrap();
+        let reinvest =
+            CashMovement::new(date("2024-10-02"), None, CashMovementKind::Reinvestment, dec!(50)).unwrap();
+        tracker.record_cash_movement(reinvest).unwrap();
+
+        let statement = AnnualStatement::build(&tracker, 2024, None, date("2025-06-01")).unwrap();
+        assert_eq!(statement.payments, 6);
+        assert_eq!(statement.gross, dec!(341.75));
+        assert_eq!(statement.withholding, dec!(42));
+        assert_eq!(statement.fees, dec!(2));
+        assert_eq!(statement.net_received(), dec!(297.75));
+        assert_eq!(statement.reinvested, Some(dec!(50)));
+        // KO at 0.485 x 4 x 100 plus NESN at 3 x 1 x 40; T was sold mid-year
+        assert_eq!(statement.ending_run_rate, dec!(314));
+        assert_eq!(statement.starting_run_rate, dec!(184));
+    }
+
+    #[test]
+    fn test_giving_schedule_by_quarter() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(dividend("KO", "2024-04-01", dec!(0.485), dec!(100)));
+        tracker.add_dividend(dividend("KO", "2024-07-01", dec!(0.485), dec!(100)));
+        let mut nestle = dividend("NESN", "2024-04-26", dec!(3), dec!(40));
+        nestle.withholding_tax = Some(dec!(20));
+        tracker.add_dividend(nestle);
+
+        let net = GivingSchedule::build(&tracker, 2024, dec!(10), GivingPeriodArg::Quarter, GivingBasisArg::Net)
+            .unwrap();
+        assert_eq!(net.periods.len(), 4);
+        assert_eq!(net.periods[1].label, "Q2-2024");
+        assert_eq!(net.periods[1].income, dec!(148.5));
+        assert_eq!(net.periods[1].giving, dec!(14.85));
+        assert_eq!(net.total_giving(), dec!(19.7));
+
+        let gross =
+            GivingSchedule::build(&tracker, 2024, dec!(2.5), GivingPeriodArg::Month, GivingBasisArg::Gross).unwrap();
+        assert_eq!(gross.periods.len(), 12);
+        assert_eq!(gross.total_income(), dec!(217));
+        assert_eq!(gross.periods[3].giving, dec!(4.2125));
+
+        assert!(GivingSchedule::build(&tracker, 2024, dec!(0), GivingPeriodArg::Month, GivingBasisArg::Net).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+//! JSON-RPC mode for editors and automation
+//!
+//! `--rpc` reads one JSON-RPC 2.0 request per line from stdin and writes one
+//! response per line to stdout, so a tool can keep the tracker running as a
+//! child process instead of starting it for every command. Methods are
+//! `add`, `list`, `summary` and `project`; their params are the command's
+//! options by name (`{"symbol": "KO", "ex_date": "2024-06-14", ...}`), with
+//! `true` for flags. The tracker is loaded once and only reloaded when
+//! `dividends.json` changes on disk.
+
+use chrono::Local;
+use clap::Parser;
+use serde_json::{json, Map, Value};
+use std::io::{self, BufRead, Write};
+use std::time::SystemTime;
+
+use super::{dividends, project, summary, CliConfig};
+use crate::cli::{Cli, Commands, DivCommands};
+use crate::models::DividendTracker;
+use crate::persistence::PersistenceManager;
+use crate::server::DividendView;
+
+/// Invalid JSON was received
+const PARSE_ERROR: i64 = -32700;
+/// The JSON is not a valid request object
+const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The params don't fit the method's options
+const INVALID_PARAMS: i64 = -32602;
+/// The command itself failed, e.g. a duplicate dividend
+const COMMAND_FAILED: i64 = -32000;
+
+/// Serve JSON-RPC requests from stdin until it closes
+pub fn run(config: &CliConfig) -> anyhow::Result<()> {
+    let mut session = RpcSession::new(config.create_persistence_manager()?);
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle_line(&line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// An error object of a JSON-RPC response
+#[derive(Debug, Clone, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A long-running RPC session over one data directory
+pub struct RpcSession {
+    persistence: PersistenceManager,
+    /// The loaded tracker and the file state it was loaded from
+    cached: Option<(Option<(SystemTime, u64)>, DividendTracker)>,
+}
+
+impl RpcSession {
+    pub fn new(persistence: PersistenceManager) -> Self {
+        RpcSession {
+            persistence,
+            cached: None,
+        }
+    }
+
+    /// Answer one line of input; notifications get no response
+    pub fn handle_line(&mut self, line: &str) -> Option<Value> {
+        match serde_json::from_str::<Value>(line) {
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let responses: Vec<Value> = requests.into_iter().filter_map(|r| self.handle_request(r)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => self.handle_request(request),
+            Err(e) => Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+        }
+    }
+
+    fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let Value::Object(mut request) = request else {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "Request must be an object"),
+            ));
+        };
+        let id = request.remove("id");
+        let reply_id = id.clone().unwrap_or(Value::Null);
+
+        if request.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Some(error_response(
+                reply_id,
+                RpcError::new(INVALID_REQUEST, "Expected \"jsonrpc\": \"2.0\""),
+            ));
+        }
+        let Some(method) = request.get("method").and_then(Value::as_str).map(str::to_string) else {
+            return Some(error_response(reply_id, RpcError::new(INVALID_REQUEST, "Missing method")));
+        };
+        let params = match request.remove("params") {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(params)) => params,
+            Some(_) => {
+                return Some(error_response(
+                    reply_id,
+                    RpcError::new(INVALID_PARAMS, "Params must be an object of named options"),
+                ));
+            }
+        };
+
+        let result = self.call(&method, &params);
+        // Requests without an id are notifications
+        id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": reply_id, "result": result }),
+            Err(error) => error_response(reply_id, error),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: &Map<String, Value>) -> Result<Value, RpcError> {
+        let command: &[&str] = match method {
+            "add" => &["div", "add"],
+            "list" => &["div", "list"],
+            "summary" => &["summary"],
+            "project" => &["project"],
+            _ => return Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+        let args = command_args(method, params)?;
+        let cli = Cli::try_parse_from(["dividend-tracker"].iter().chain(command).map(|s| s.to_string()).chain(args))
+            .map_err(|e| {
+                // Only clap's first line is about the params; the rest is usage for a terminal
+                let message = e.to_string();
+                let first = message.lines().next().unwrap_or_default();
+                RpcError::new(INVALID_PARAMS, first.trim_start_matches("error: "))
+            })?;
+        let today = Local::now().naive_local().date();
+        let failed = |e: anyhow::Error| RpcError::new(COMMAND_FAILED, e.to_string());
+
+        match cli.command {
+            Some(Commands::Div {
+                command: DivCommands::Add(args),
+            }) => {
+                let view = self
+                    .persistence
+                    .with_tracker_mut(|tracker| {
+                        let (added, _) = dividends::add_to_tracker(tracker, &args)?;
+                        Ok(DividendView::from(&added))
+                    })
+                    .map_err(failed)?;
+                self.cached = None;
+                Ok(json!(view))
+            }
+            Some(Commands::Div {
+                command: DivCommands::List(args),
+            }) => {
+                let archived;
+                let tracker = if args.include_archived {
+                    archived = self.persistence.load_with_archive().map_err(failed)?;
+                    &archived
+                } else {
+                    self.tracker().map_err(failed)?
+                };
+                let listing = dividends::build_listing(tracker, &args).map_err(failed)?;
+                let items: Vec<DividendView> = listing.dividends.iter().map(|d| DividendView::from(*d)).collect();
+                Ok(json!({
+                    "count": items.len(),
+                    "total_income": listing.total_income,
+                    "total_fees": listing.total_fees,
+                    "items": items,
+                }))
+            }
+            Some(Commands::Summary(args)) => {
+                let tracker = if args.include_archived {
+                    self.persistence.load_with_archive().map_err(failed)?
+                } else {
+                    self.tracker().map_err(failed)?.clone()
+                };
+                let (report, _) = summary::build_summary(tracker, args, today).map_err(failed)?;
+                Ok(json!(report))
+            }
+            Some(Commands::Project(args)) => {
+                let (report, _) = project::build_projection(self.tracker().map_err(failed)?, args, today).map_err(failed)?;
+                Ok(json!(report))
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        }
+    }
+
+    /// The tracker, reloaded if the data file changed since it was read
+    fn tracker(&mut self) -> anyhow::Result<&DividendTracker> {
+        let modified = self.persistence.last_modified();
+        if !matches!(&self.cached, Some((loaded, _)) if loaded.is_some() && *loaded == modified) {
+            self.cached = Some((modified, self.persistence.load()?));
+        }
+        Ok(&self.cached.as_ref().expect("tracker was just loaded").1)
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Translate named params into command-line options
+///
+/// `ex_date` becomes `--ex-date`, `true` a bare flag, arrays a repeated
+/// option; `false` and `null` are left out. The dividend symbol of `add` is
+/// positional.
+fn command_args(method: &str, params: &Map<String, Value>) -> Result<Vec<String>, RpcError> {
+    let mut args = Vec::new();
+    for (key, value) in params {
+        if key.starts_with("export") {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("'{}' is not available in --rpc mode", key),
+            ));
+        }
+        let positional = method == "add" && key == "symbol";
+        let flag = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                Value::Null | Value::Bool(false) => continue,
+                Value::Bool(true) if !positional => {
+                    args.push(flag.clone());
+                    continue;
+                }
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                _ => {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        format!("Unsupported value for '{}'", key),
+                    ));
+                }
+            };
+            if !positional {
+                args.push(flag.clone());
+            }
+            args.push(value);
+        }
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn call(session: &mut RpcSession, request: Value) -> Value {
+        session.handle_line(&request.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_rpc_session_adds_lists_and_reports_errors() {
+        let dir = TempDir::new().unwrap();
+        let mut session = RpcSession::new(PersistenceManager::with_custom_path(dir.path()));
+
+        let added = call(
+            &mut session,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "add", "params": {
+                "symbol": "ko", "ex_date": "2024-06-14", "pay_date": "2024-07-01",
+                "amount": "0.485", "shares": 100
+            }}),
+        );
+        assert_eq!(added["id"], 1);
+        assert_eq!(added["result"]["symbol"], "KO");
+        assert_eq!(added["result"]["total_amount"], "48.500");
+
+        let listed = call(&mut session, json!({ "jsonrpc": "2.0", "id": 2, "method": "list" }));
+        assert_eq!(listed["result"]["count"], 1);
+        assert_eq!(listed["result"]["items"][0]["ex_date"], "2024-06-14");
+
+        let duplicate = call(
+            &mut session,
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "add", "params": {
+                "symbol": "KO", "ex_date": "2024-06-14", "pay_date": "2024-07-01",
+                "amount": "0.485", "shares": "100"
+            }}),
+        );
+        assert_eq!(duplicate["error"]["code"], COMMAND_FAILED);
+
+        let bad_option = call(
+            &mut session,
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "list", "params": { "colour": "red" } }),
+        );
+        assert_eq!(bad_option["error"]["code"], INVALID_PARAMS);
+
+        let unknown = call(&mut session, json!({ "jsonrpc": "2.0", "id": 5, "method": "delete" }));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        assert_eq!(session.handle_line("{not json").unwrap()["error"]["code"], PARSE_ERROR);
+        assert!(session
+            .handle_line(&json!({ "jsonrpc": "2.0", "method": "list" }).to_string())
+            .is_none());
+    }
+
+    #[test]
+    fn test_command_args_translates_params() {
+        let params = json!({ "symbol": "KO", "by_symbol": true, "growth": false, "year": 2024 });
+        let args = command_args("summary", params.as_object().unwrap()).unwrap();
+        assert_eq!(args, ["--by-symbol", "--symbol", "KO", "--year", "2024"]);
+
+        let params = json!({ "symbol": "KO", "force": true });
+        let args = command_args("add", params.as_object().unwrap()).unwrap();
+        assert_eq!(args, ["--force", "KO"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Batch script command
+//!
+//! `run script.dtx` executes one command per line inside a single process.
+//! The whole script works on one in-memory copy of the data: it is loaded
+//! once, every command reads and writes that copy, and it is saved once at
+//! the end, only if every command succeeded.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use colored::*;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use super::CliConfig;
+use crate::cli::{self, Cli, Commands, DataCommands, RunArgs};
+use crate::models::DividendTracker;
+use crate::render::OutputFormat;
+
+/// One command of a script
+struct ScriptLine {
+    number: usize,
+    text: String,
+    cli: Cli,
+}
+
+/// Run every command in the script, then save once
+pub fn run(config: &CliConfig, args: RunArgs) -> Result<()> {
+    let RunArgs { script, dry_run } = args;
+
+    let contents =
+        fs::read_to_string(&script).with_context(|| format!("Failed to read script {}", script.display()))?;
+    // Check the whole script before running any of it
+    let lines = parse_script(&contents).with_context(|| format!("Invalid script {}", script.display()))?;
+    if lines.is_empty() {
+        bail!("No commands found in {}", script.display());
+    }
+
+    let count = lines.len();
+    let persistence = config.create_persistence_manager()?;
+    let run_all = move |tracker: &mut DividendTracker| -> Result<()> {
+        let staged = Arc::new(Mutex::new(std::mem::take(tracker)));
+        for line in lines {
+            let line_config = CliConfig {
+                data_dir: config.data_dir.clone(),
+                verbose: config.verbose || line.cli.verbose,
+                quiet: config.quiet || line.cli.quiet,
+                output_format: if line.cli.output_format == OutputFormat::Table {
+                    config.output_format
+                } else {
+                    line.cli.output_format
+                },
+                table_style: line.cli.table_style.or(config.table_style),
+                max_column_width: line.cli.max_column_width.map(usize::from).or(config.max_column_width),
+                staged: Some(Arc::clone(&staged)),
+            };
+            if !line_config.quiet {
+                println!("{} {}", format!("[{}]", line.number).dimmed(), line.text.bold());
+            }
+            super::run(line.cli.command, &line_config)
+                .with_context(|| format!("Line {} failed: {}", line.number, line.text))?;
+        }
+        *tracker = staged.lock().map_err(|_| anyhow!("Script state was lost"))?.clone();
+        Ok(())
+    };
+
+    if dry_run {
+        run_all(&mut persistence.load()?)?;
+    } else {
+        persistence
+            .with_tracker_mut(run_all)
+            .context("Script stopped; no changes were saved")?;
+    }
+
+    if !config.quiet {
+        println!();
+        if dry_run {
+            println!("{} Ran {} commands (dry run, nothing saved)", "✓".green(), count);
+        } else {
+            println!("{} Ran {} commands and saved the changes", "✓".green(), count);
+        }
+    }
+    Ok(())
+}
+
+/// Parse every command line of a script
+///
+/// Blank lines and `#` comments are skipped, and a leading `dividend-tracker`
+/// is optional so lines can be pasted from a shell script.
+fn parse_script(contents: &str) -> Result<Vec<ScriptLine>> {
+    let mut lines = Vec::new();
+    for (index, text) in contents.lines().enumerate() {
+        let number = index + 1;
+        let mut words = split_words(text).with_context(|| format!("Line {}", number))?;
+        if words.first().map(String::as_str) == Some("dividend-tracker") {
+            words.remove(0);
+        }
+        if words.is_empty() {
+            continue;
+        }
+
+        let (args, _) = cli::route_legacy_args(std::iter::once("dividend-tracker".to_string()).chain(words).collect());
+        let cli = Cli::try_parse_from(args)
+            .map_err(|e| anyhow!("Line {}: {}", number, e.to_string().lines().next().unwrap_or_default()))?;
+        if cli.rpc || cli.data_dir.is_some() {
+            bail!("Line {}: --rpc and --data-dir can't be used inside a script", number);
+        }
+        if let Some(reason) = cli.command.as_ref().and_then(unsupported) {
+            bail!("Line {}: {}", number, reason);
+        }
+        lines.push(ScriptLine {
+            number,
+            text: text.trim().to_string(),
+            cli,
+        });
+    }
+    Ok(lines)
+}
+
+/// Why a command can't run inside a script, if it can't
+///
+/// These commands keep state outside the tracker, which a failed script
+/// could not roll back, or never finish.
+fn unsupported(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Run(_) => Some("scripts can't run other scripts"),
+        Commands::Serve(_) => Some("serve runs until interrupted and can't be scripted"),
+        Commands::Init(_)
+        | Commands::Configure(_)
+        | Commands::Alerts(_)
+        | Commands::Calendar(_)
+        | Commands::News(_) => {
+            Some("this command keeps its own files and can't be part of a script")
+        }
+        Commands::Data {
+            command: DataCommands::Backup | DataCommands::Load { .. } | DataCommands::Backups { .. },
+        } => Some("backups work on the data files directly and can't be part of a script"),
+        Commands::Data {
+            command: DataCommands::Archive { .. },
+        } => Some("archiving writes its own files and can't be part of a script"),
+        _ => None,
+    }
+}
+
+/// Split a line into words the way a shell would
+///
+/// Single and double quotes group words, a backslash escapes the next
+/// character, and `#` at the start of a word comments out the rest.
+fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                let escaped = chars.next().ok_or_else(|| anyhow!("trailing backslash"))?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, '#') if word.is_none() => break,
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(q) = quote {
+        bail!("unterminated {} quote", q);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words_handles_quotes_and_comments() {
+        assert_eq!(
+            split_words(r#"div add KO --ex-date "next friday" --account 'Roth IRA' # comment"#).unwrap(),
+            ["div", "add", "KO", "--ex-date", "next friday", "--account", "Roth IRA"]
+        );
+        assert_eq!(split_words(r#"a\ b "" c#d"#).unwrap(), ["a b", "", "c#d"]);
+        assert!(split_words("   # only a comment").unwrap().is_empty());
+        assert!(split_words("'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_parse_script_checks_every_line() {
+        let lines = parse_script("# seed\n\ndividend-tracker holdings add KO --shares 100\nsummary\n").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].number, 3);
+        assert_eq!(lines[1].text, "summary");
+
+        let Err(error) = parse_script("summary\nsummary --bogus\n") else {
+            panic!("expected the second line to be rejected");
+        };
+        assert!(error.to_string().starts_with("Line 2:"));
+        assert!(parse_script("serve").is_err());
+        assert!(parse_script("run other.dtx").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! JSON API command
+//!
+//! Serves dividends and holdings for reading, and adds dividends sent to
+//! `POST /dividends` by requests carrying a write-scoped API token.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use std::net::TcpListener;
+use uuid::Uuid;
+
+use super::CliConfig;
+use crate::cli::ServeArgs;
+use crate::server::{self, Access};
+use crate::tokens::ApiTokens;
+
+/// Shortest share token accepted, so a chosen one isn't easily guessed
+const MIN_SHARE_TOKEN_LEN: usize = 16;
+
+/// Listen for API requests until interrupted
+pub fn run(config: &CliConfig, args: ServeArgs) -> Result<()> {
+    let ServeArgs {
+        bind,
+        port,
+        share,
+        share_token,
+    } = args;
+
+    let access = match (share, share_token) {
+        (false, _) => Access::Api(ApiTokens::load()?),
+        (true, Some(token)) => {
+            if token.len() < MIN_SHARE_TOKEN_LEN
+                || !token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                bail!(
+                    "Share token must be at least {} letters, digits, '-' or '_'",
+                    MIN_SHARE_TOKEN_LEN
+                );
+            }
+            Access::Shared(token)
+        }
+        (true, None) => Access::Shared(Uuid::new_v4().simple().to_string()),
+    };
+
+    let persistence = config.create_persistence_manager()?;
+    let listener =
+        TcpListener::bind((bind.as_str(), port)).with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+    let address = listener.local_addr()?;
+
+    match &access {
+        Access::Api(tokens) => {
+            println!("{} Serving dividend data on {}", "✓".green(), format!("http://{}", address).blue());
+            println!("  GET /dividends and /holdings (?limit=, ?offset=, ?fields=) or /events; press Ctrl+C to stop.");
+            if cfg!(feature = "graphql") {
+                println!("  GraphQL queries at /graphql, by GET ?query= or POST.");
+            }
+            if tokens.is_empty() {
+                println!("  No API tokens: anyone can read and nobody can write. See 'configure tokens add'.");
+            } else {
+                println!(
+                    "  {} API tokens configured; send one as 'Authorization: Bearer <token>'. POST /dividends needs write scope.",
+                    tokens.tokens.len()
+                );
+            }
+        }
+        Access::Shared(token) => {
+            println!(
+                "{} Sharing an income summary at {}",
+                "✓".green(),
+                format!("http://{}/share/{}", address, token).blue()
+            );
+            println!("  Totals and charts only: no holdings, share counts or other endpoints; press Ctrl+C to stop.");
+        }
+    }
+
+    server::run(listener, &persistence, &access)
+}
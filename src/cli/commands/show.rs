@@ -0,0 +1,97 @@
+//! Symbol detail view (`show <symbol>`)
+
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+
+use super::journal::journal_table;
+use super::{with_hint, CliConfig};
+use crate::config::Config;
+use crate::format::{format_money, format_shares};
+use crate::models::DividendTracker;
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+/// Most recent payments listed in the view
+const RECENT_DIVIDENDS: usize = 5;
+
+/// Show everything recorded about one symbol
+pub fn run(config: &CliConfig, symbol: &str) -> Result<()> {
+    let tracker = config.create_persistence_manager()?.load()?;
+    config.render(&symbol_report(&tracker, symbol)?)
+}
+
+fn symbol_report(tracker: &DividendTracker, symbol: &str) -> Result<Report> {
+    let symbol = tracker.canonical_symbol(symbol);
+    let holdings = tracker.holdings_of(&symbol);
+    let mut dividends = tracker.get_dividends_for_symbol(&symbol);
+    let mut journal = tracker.journal_for(&symbol);
+    if holdings.is_empty() && dividends.is_empty() && journal.is_empty() {
+        let message = format!("Nothing recorded for {}.", symbol);
+        bail!(with_hint(&message, tracker.symbol_hint(&symbol)));
+    }
+    let display = Config::load().unwrap_or_default().display;
+
+    let mut positions = Vec::new();
+    for holding in &holdings {
+        let mut position = Section::new("Holding").icon("💼");
+        if let Some(account) = &holding.account {
+            position = position.field("Account", account.as_str());
+        }
+        position = position.field("Shares", format_shares(holding.shares, &display));
+        if let Some(cost) = holding.avg_cost_basis {
+            position = position
+                .field("Avg Cost Basis", format_money(cost))
+                .field("Total Cost", format_money(cost * holding.shares));
+        }
+        if let Some(forward) = &holding.forward_dividend {
+            position = position.field(
+                "Forward Dividend",
+                format!("{}/share ({:?})", format_money(forward.annual_amount), forward.frequency),
+            );
+        }
+        if let Some(closed) = holding.closed_date {
+            position = position.field("Closed", Text::new(closed.format("%Y-%m-%d").to_string(), Tone::Warning));
+        }
+        positions.push(position);
+    }
+    if positions.is_empty() {
+        positions.push(Section::new("Holding").icon("💼").note(NoteKind::Info, "Not currently held."));
+    }
+
+    dividends.sort_by_key(|d| std::cmp::Reverse(d.pay_date));
+    let mut income = Section::new("Dividends").icon("💰");
+    if dividends.is_empty() {
+        income = income.note(NoteKind::Info, "No dividends recorded.");
+    } else {
+        let total: Decimal = dividends.iter().map(|d| d.total_amount).sum();
+        let mut recent = TableView::new(&["Ex-Date", "Pay Date", "Per Share", "Total"]);
+        for dividend in dividends.iter().take(RECENT_DIVIDENDS) {
+            recent.row(vec![
+                dividend.ex_date.format("%Y-%m-%d").to_string().into(),
+                dividend.pay_date.format("%Y-%m-%d").to_string().into(),
+                format!("${}", dividend.amount_per_share).into(),
+                Text::new(format_money(dividend.total_amount), Tone::Positive),
+            ]);
+        }
+        income = income
+            .field("Payments", dividends.len().to_string())
+            .field("Total Received", Text::new(format_money(total), Tone::Positive))
+            .table(recent);
+    }
+
+    journal.reverse();
+    let mut notes = Section::new("Journal").icon("📝");
+    if journal.is_empty() {
+        notes = notes.note(
+            NoteKind::Tip,
+            format!("No notes yet. Add one with 'journal add {} \"...\"'.", symbol),
+        );
+    } else {
+        notes = notes.table(journal_table(&journal, false));
+    }
+
+    let mut report = Report::new(symbol);
+    for position in positions {
+        report = report.section(position);
+    }
+    Ok(report.section(income).section(notes))
+}
@@ -0,0 +1,1261 @@
+//! Portfolio summary and analytics command
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::path::PathBuf;
+
+use super::cash::cash_balance_section;
+use super::{load_tracker, with_hint, CliConfig};
+use crate::analytics::{AnalyticsScope, ConsistencyExplanation, DividendAnalytics};
+use crate::cli::SummaryArgs;
+use crate::config::{AnalysisSettings, Config, DateBasis};
+use crate::filters::DividendFilter;
+use crate::format::{format_money, format_percent, format_signed_percent, percent_of};
+use crate::inflation::CpiTable;
+use crate::models::{Dividend, DividendTracker, IncomeKind};
+use crate::periods::PaymentPeriods;
+use crate::persistence::PersistenceManager;
+use crate::run_rate;
+use crate::tax::TaxAnalyzer;
+use crate::render::{signed_money, signed_percent, NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Portfolio Summary & Analytics";
+
+/// Which optional sections a summary report includes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SummarySections {
+    pub monthly: bool,
+    /// Monthly income goal the monthly breakdown is measured against
+    pub monthly_goal: Option<Decimal>,
+    pub top_payers: Option<usize>,
+    pub growth: bool,
+    pub growth_by_symbol: bool,
+    pub frequency: bool,
+    pub consistency: bool,
+    pub yield_analysis: bool,
+}
+
+/// Handle summary command with comprehensive analytics
+pub fn run(config: &CliConfig, args: SummaryArgs) -> Result<()> {
+    let export_csv = args.export_csv.clone();
+
+    // Load persistence manager and existing data
+    let persistence = config.create_persistence_manager()?;
+    let today = Local::now().naive_local().date();
+
+    // Exports need the analytics themselves, and merged directories and CPI
+    // files can change without this data changing. The analysis settings
+    // decide which payments land in which year, so they are part of the key.
+    if export_csv.is_none() && args.merge.is_empty() && args.cpi_file.is_none() {
+        let settings = Config::load().unwrap_or_default().analysis;
+        let params = format!("summary {:?} {:?} {}", args, settings, today);
+        return config.render_cached(&persistence, &params, || {
+            let tracker = load_tracker(&persistence, args.include_archived)?;
+            Ok(build_summary(tracker, args, today)?.0)
+        });
+    }
+
+    let tracker = load_tracker(&persistence, args.include_archived)?;
+    let (report, analytics) = build_summary(tracker, args, today)?;
+    config.render(&report)?;
+
+    // Export to CSV if requested
+    if let (Some(csv_path), Some(analytics)) = (export_csv, analytics) {
+        analytics.export_to_csv(&csv_path)?;
+        config.print_success(&format!("Analytics exported to {}", csv_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Build the summary report for a loaded tracker, with the analytics behind it
+///
+/// When there is nothing to summarize only a warning report comes back.
+pub fn build_summary(
+    mut tracker: DividendTracker,
+    args: SummaryArgs,
+    today: NaiveDate,
+) -> Result<(Report, Option<DividendAnalytics>)> {
+    let SummaryArgs {
+        year,
+        quarter,
+        symbol,
+        symbol_match,
+        top_payers,
+        growth,
+        by_symbol,
+        frequency,
+        consistency,
+        explain,
+        yield_analysis,
+        export_csv,
+        monthly,
+        goal,
+        all,
+        merge,
+        cash,
+        run_rate_history,
+        real,
+        cpi_file,
+        account,
+        include_archived: _,
+        date_basis,
+        reconcile_tax,
+    } = args;
+
+    let mut settings = Config::load().unwrap_or_default().analysis;
+    if let Some(date_basis) = date_basis {
+        settings.date_basis = date_basis;
+    }
+
+    let cpi = match (real, &cpi_file) {
+        (false, _) => None,
+        (true, Some(path)) => Some(CpiTable::load(path)?),
+        (true, None) => Some(CpiTable::builtin()),
+    };
+
+    // Cash and run-rate history belong to this directory, whatever else is merged or filtered
+    let cash_section = cash.then(|| cash_balance_section(&tracker, today));
+    let run_rate_section = run_rate_history.then(|| run_rate_history_section(&tracker, today));
+
+    // Other household members are combined in memory only; no files change
+    let mut members = Vec::new();
+    if !merge.is_empty() {
+        members.push(HouseholdMember {
+            owner: "Primary".to_string(),
+            tracker: tracker.clone(),
+        });
+        for spec in &merge {
+            members.push(HouseholdMember::load(spec)?);
+        }
+        tracker = combine_household(&members);
+    }
+
+    if tracker.dividends.is_empty() {
+        return Ok((
+            Report::warning(TITLE, "No dividend records found. Use 'add' command to add some dividends first!"),
+            None,
+        ));
+    }
+
+    // Narrow every section to the requested symbols
+    if let Some(symbol) = &symbol {
+        let hint = tracker.symbol_hint(symbol);
+        let filter = DividendFilter::new().symbol(symbol, symbol_match.into());
+        tracker.dividends.retain(|d| filter.matches_symbol(&d.symbol));
+        tracker.holdings.retain(|key, _| filter.matches_symbol(&key.symbol));
+        for member in &mut members {
+            member.tracker.dividends.retain(|d| filter.matches_symbol(&d.symbol));
+        }
+
+        if tracker.dividends.is_empty() {
+            let message = format!("No dividend records found for {}.", symbol);
+            return Ok((Report::warning(TITLE, with_hint(&message, hint)), None));
+        }
+    }
+
+    // Narrow every section to one account's dividends and holdings
+    if let Some(account) = &account {
+        let filter = DividendFilter::new().account(account);
+        tracker.dividends.retain(|d| filter.matches(d));
+        tracker.holdings.retain(|key, _| key.is_in(account));
+        for member in &mut members {
+            member.tracker.dividends.retain(|d| filter.matches(d));
+        }
+
+        if tracker.dividends.is_empty() {
+            return Ok((
+                Report::warning(TITLE, format!("No dividend records found in account {}.", account)),
+                None,
+            ));
+        }
+    }
+
+    if reconcile_tax {
+        let year = year.unwrap_or_else(|| today.year());
+        let mut filter = DividendFilter::new();
+        if let Some(symbol) = &symbol {
+            filter = filter.symbol(symbol, symbol_match.into());
+        }
+        if let Some(account) = &account {
+            filter = filter.account(account);
+        }
+        return Ok((tax_reconciliation(&tracker, &filter, year, &settings)?, None));
+    }
+
+    // Set flags based on 'all' option
+    let sections = SummarySections {
+        monthly,
+        monthly_goal: goal,
+        top_payers,
+        growth: all || growth,
+        growth_by_symbol: (all || growth) && by_symbol,
+        frequency: all || frequency,
+        consistency: all || consistency,
+        yield_analysis: all || yield_analysis,
+    };
+
+    // Generate analytics, leaving out the all-time analyses nothing will show
+    let scope = AnalyticsScope {
+        top_payers: sections.top_payers.is_some() || export_csv.is_some(),
+        frequency: sections.frequency,
+        consistency: sections.consistency,
+        yields: sections.yield_analysis,
+        growth: sections.growth || cpi.is_some(),
+        symbol_growth: sections.growth_by_symbol,
+    };
+    let analytics = DividendAnalytics::generate(
+        &tracker,
+        year,
+        quarter.as_deref(),
+        &settings,
+        scope,
+    )?;
+
+    let mut report = summary_report(&analytics, year, quarter.as_deref(), sections)?;
+
+    if let Some(cpi) = &cpi {
+        report.push(real_income(&analytics, cpi));
+    }
+
+    if !members.is_empty() {
+        report.push(household_breakdown(&members, &analytics, year, quarter.as_deref(), &settings)?);
+    }
+
+    if let Some(section) = cash_section {
+        report.push(section);
+    }
+    if let Some(section) = run_rate_section {
+        report.push(section);
+    }
+
+    if let Some(symbol) = explain.filter(|_| sections.consistency) {
+        let explanation = DividendAnalytics::explain_consistency(&tracker, &symbol)?;
+        report.push(consistency_explanation(&explanation));
+    }
+
+    Ok((report, Some(analytics)))
+}
+
+/// Why the summary's income for `year` differs from `tax summary`'s
+///
+/// The summary places a payment by its date basis, moved into the symbol's
+/// usual month when it drifted across a month boundary; the tax commands take
+/// the calendar year of the pay date. Every payment counted by only one of
+/// them is listed, and together they make up the whole difference. Both
+/// sides only count the payments `filter` keeps, so a summary narrowed to a
+/// symbol or account is compared with the tax income of the same payments.
+fn tax_reconciliation(
+    tracker: &DividendTracker,
+    filter: &DividendFilter,
+    year: i32,
+    settings: &AnalysisSettings,
+) -> Result<Report> {
+    let mut scoped = tracker.clone();
+    scoped.dividends.retain(|d| filter.matches(d));
+    let periods = PaymentPeriods::build_by(&scoped.dividends, settings.month_drift_days, settings.date_basis);
+    let basis = settings.date_basis;
+
+    let summary_total: Decimal = scoped
+        .dividends
+        .iter()
+        .filter(|d| periods.year(d) == year)
+        .map(|d| d.total_amount)
+        .sum();
+    let tax = TaxAnalyzer::generate_tax_summary(&scoped, year, None)?;
+    let tax_total = tax.total_dividend_income + tax.interest_income;
+
+    let mut crossing: Vec<&Dividend> = scoped
+        .dividends
+        .iter()
+        .filter(|d| (periods.year(d) == year) != (d.pay_date.year() == year))
+        .collect();
+    crossing.sort_by(|a, b| a.ex_date.cmp(&b.ex_date).then_with(|| a.symbol.cmp(&b.symbol)));
+
+    let tax_label = if tax.interest_income.is_zero() {
+        format_money(tax_total)
+    } else {
+        format!(
+            "{} (dividends {}, interest {})",
+            format_money(tax_total),
+            format_money(tax.total_dividend_income),
+            format_money(tax.interest_income)
+        )
+    };
+    let mut totals = Section::new(format!("Summary vs. Tax Income for {}", year))
+        .icon("⚖️")
+        .field(format!("Summary (by {})", basis.label()), format_money(summary_total))
+        .field("Tax Summary (by pay date)", tax_label)
+        .field("Difference", signed_money(summary_total - tax_total));
+
+    if crossing.is_empty() {
+        totals = totals.note(NoteKind::Info, "Both count the same payments for the year.");
+        return Ok(Report::new(TITLE).section(totals));
+    }
+
+    let mut table = TableView::new(&["Symbol", "Ex-Date", "Pay Date", "Amount", "Counted By", "Why"]);
+    for dividend in &crossing {
+        let placed = periods.year(dividend);
+        let (counted_by, tone) = if placed == year {
+            ("Summary only", Tone::Highlight)
+        } else {
+            ("Tax only", Tone::Warning)
+        };
+        let why = if placed != basis.date_of(dividend).year() {
+            format!("Counted in {} with its usual month", placed)
+        } else {
+            format!("Ex-date in {}, paid in {}", dividend.ex_date.year(), dividend.pay_date.year())
+        };
+        table.row(vec![
+            dividend.symbol.clone().into(),
+            dividend.ex_date.format("%Y-%m-%d").to_string().into(),
+            dividend.pay_date.format("%Y-%m-%d").to_string().into(),
+            format_money(dividend.total_amount).into(),
+            Text::new(counted_by, tone),
+            why.into(),
+        ]);
+    }
+
+    let mut payments = Section::new("Payments Counted by Only One").icon("🔀").table(table).note(
+        NoteKind::Info,
+        format!(
+            "{} payment{} across the year boundary make{} up the difference.",
+            crossing.len(),
+            if crossing.len() == 1 { "" } else { "s" },
+            if crossing.len() == 1 { "s" } else { "" }
+        ),
+    );
+    if basis == DateBasis::ExDate {
+        payments = payments.note(
+            NoteKind::Tip,
+            "Set date_basis = \"pay-date\" under [analysis] in config.toml, or pass --date-basis pay-date, to count summary years by pay date like the tax commands.",
+        );
+    }
+
+    Ok(Report::new(TITLE).section(totals).section(payments))
+}
+
+/// Months shown by `--run-rate-history`
+const RUN_RATE_MONTHS: usize = 24;
+
+/// Width of the longest bar in the run-rate chart
+const CHART_WIDTH: usize = 30;
+
+/// Month-by-month forward income run-rate with a bar per month
+pub fn run_rate_history_section(tracker: &DividendTracker, today: NaiveDate) -> Section {
+    let section = Section::new("Forward Income Run-Rate").icon("📈");
+    let points = run_rate::history(tracker, RUN_RATE_MONTHS, today);
+    if points.is_empty() {
+        return section.note(NoteKind::Info, "No forward income yet.");
+    }
+
+    let peak = points.iter().map(|p| p.annual_income).max().unwrap_or_default();
+    let mut table = TableView::new(&["Month", "Run-Rate", "Change", "Chart"]);
+    let mut previous: Option<Decimal> = None;
+    for point in &points {
+        let change = match previous {
+            Some(previous) if !previous.is_zero() => {
+                signed_percent((point.annual_income - previous) / previous * Decimal::from(100))
+            }
+            _ => "-".into(),
+        };
+        let width = if peak.is_zero() {
+            0
+        } else {
+            (point.annual_income / peak * Decimal::from(CHART_WIDTH))
+                .round()
+                .to_usize()
+                .unwrap_or(0)
+        };
+        let month = if point.saved {
+            point.date.format("%Y-%m").to_string()
+        } else {
+            format!("{}*", point.date.format("%Y-%m"))
+        };
+        table.row(vec![
+            month.into(),
+            format_money(point.annual_income).into(),
+            change,
+            Text::new("█".repeat(width.max(1)), Tone::Positive),
+        ]);
+        previous = Some(point.annual_income);
+    }
+
+    let first = points[0].annual_income;
+    let last = points[points.len() - 1].annual_income;
+    let mut section = section
+        .table(table)
+        .field("Current Run-Rate", Text::new(format_money(last), Tone::Highlight));
+    if !first.is_zero() && points.len() > 1 {
+        section = section.field(
+            format!("Growth since {}", points[0].date.format("%Y-%m")),
+            signed_percent((last - first) / first * Decimal::from(100)),
+        );
+    }
+    if points.iter().any(|p| !p.saved) {
+        section = section.note(
+            NoteKind::Info,
+            "* Reconstructed from current records; a snapshot is saved in each month the data changes.",
+        );
+    }
+    section
+}
+
+/// Yearly income next to the same income in constant dollars of the latest year
+pub fn real_income(analytics: &DividendAnalytics, cpi: &CpiTable) -> Section {
+    let section = Section::new("Inflation-Adjusted Income").icon("🧮");
+    let Some(growth) = &analytics.growth_analysis else {
+        return section.note(NoteKind::Warning, "Insufficient data (need 2+ years)");
+    };
+    let years = &growth.year_over_year;
+    let base_year = years[years.len() - 1].year;
+
+    let real_header = format!("Real ({} $)", base_year);
+    let mut table = TableView::new(&["Year", "Nominal", "CPI", &real_header, "Nominal Growth", "Real Growth"]);
+    let mut previous_real: Option<Decimal> = None;
+    let mut first_real: Option<(i32, Decimal, Decimal)> = None;
+    let mut missing = Vec::new();
+    let mut carried = Vec::new();
+    for yearly in years {
+        let real = cpi.to_constant_dollars(yearly.total_dividends, yearly.year, base_year);
+        match real {
+            Some(_) if !cpi.index.contains_key(&yearly.year) => carried.push(yearly.year.to_string()),
+            None => missing.push(yearly.year.to_string()),
+            Some(_) => {}
+        }
+
+        let real_growth = match (real, previous_real) {
+            (Some(real), Some(previous)) if !previous.is_zero() => {
+                signed_percent((real - previous) / previous * Decimal::from(100))
+            }
+            _ => "-".into(),
+        };
+        table.row(vec![
+            yearly.year.to_string().into(),
+            format_money(yearly.total_dividends).into(),
+            cpi.index_for(yearly.year)
+                .map(|index| index.round_dp(1).to_string())
+                .unwrap_or_else(|| "-".to_string())
+                .into(),
+            real.map(|real| Text::new(format_money(real), Tone::Positive))
+                .unwrap_or_else(|| "-".into()),
+            yearly.growth_rate.map(signed_percent).unwrap_or_else(|| "-".into()),
+            real_growth,
+        ]);
+
+        if let Some(real) = real {
+            first_real.get_or_insert((yearly.year, yearly.total_dividends, real));
+        }
+        previous_real = real;
+    }
+
+    let mut section = section.table(table);
+    if let Some((first_year, nominal, real)) = first_real.filter(|(year, _, _)| *year != base_year) {
+        let last = years[years.len() - 1].total_dividends;
+        if !nominal.is_zero() {
+            section = section
+                .field(
+                    format!("Nominal Growth since {}", first_year),
+                    signed_percent((last - nominal) / nominal * Decimal::from(100)),
+                )
+                .field(
+                    format!("Real Growth since {}", first_year),
+                    signed_percent((last - real) / real * Decimal::from(100)),
+                )
+                .field(
+                    format!("Inflation since {}", first_year),
+                    signed_percent((real - nominal) / nominal * Decimal::from(100)),
+                );
+        }
+    }
+
+    section = section.note(NoteKind::Info, format!("Using {}.", cpi.source));
+    if !carried.is_empty() {
+        section = section.note(
+            NoteKind::Info,
+            format!(
+                "Figures for {} use the {} index, so no inflation is counted after it; pass --cpi-file for newer figures.",
+                carried.join(", "),
+                cpi.latest_year().unwrap_or(base_year)
+            ),
+        );
+    }
+    if !missing.is_empty() {
+        section = section.note(
+            NoteKind::Warning,
+            format!("No CPI for {}; those years are left out of real figures.", missing.join(", ")),
+        );
+    }
+    section
+}
+
+/// One data directory in a merged household summary
+#[derive(Debug, Clone)]
+pub struct HouseholdMember {
+    pub owner: String,
+    pub tracker: DividendTracker,
+}
+
+impl HouseholdMember {
+    /// Load a member from a `--merge` value, either `DIR` or `NAME=DIR`
+    ///
+    /// Without a name the directory's own name labels the owner.
+    fn load(spec: &str) -> Result<Self> {
+        let (owner, dir) = match spec.split_once('=') {
+            Some((name, dir)) if !name.is_empty() && !name.contains(['/', '\\']) => {
+                (name.to_string(), PathBuf::from(dir))
+            }
+            _ => {
+                let dir = PathBuf::from(spec);
+                let owner = dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| spec.to_string());
+                (owner, dir)
+            }
+        };
+
+        if !dir.is_dir() {
+            bail!("Data directory not found: {}", dir.display());
+        }
+
+        let tracker = PersistenceManager::with_custom_path(&dir).load()?;
+        Ok(HouseholdMember { owner, tracker })
+    }
+}
+
+/// Combine every member's records into one tracker
+///
+/// Dividends are kept side by side. Open holdings of the same symbol in the
+/// same account add up their shares and blend the cost basis; a closed holding never overrides an
+/// open one.
+pub fn combine_household(members: &[HouseholdMember]) -> DividendTracker {
+    let mut combined = DividendTracker::new();
+
+    for member in members {
+        combined.dividends.extend(member.tracker.dividends.iter().cloned());
+        combined.reclaims.extend(member.tracker.reclaims.iter().cloned());
+
+        for holding in member.tracker.holdings.values() {
+            match combined.holdings.get_mut(&holding.key()) {
+                None => {
+                    combined.holdings.insert(holding.key(), holding.clone());
+                }
+                Some(existing) if existing.is_closed() => {
+                    *existing = holding.clone();
+                }
+                Some(_) if holding.is_closed() => {}
+                Some(existing) => {
+                    let shares = existing.shares + holding.shares;
+                    existing.avg_cost_basis = match (existing.avg_cost_basis, holding.avg_cost_basis) {
+                        (Some(a), Some(b)) if !shares.is_zero() => {
+                            Some(((a * existing.shares + b * holding.shares) / shares).round_dp(4))
+                        }
+                        _ => None,
+                    };
+                    existing.shares = shares;
+                    existing.adjustments.clear();
+                }
+            }
+        }
+    }
+
+    combined
+}
+
+/// Per-owner totals next to the combined household figures
+fn household_breakdown(
+    members: &[HouseholdMember],
+    combined: &DividendAnalytics,
+    year: Option<i32>,
+    quarter: Option<&str>,
+    settings: &AnalysisSettings,
+) -> Result<Section> {
+    let mut table = TableView::new(&["Owner", "Income", "Payments", "Stocks", "Share"]);
+
+    for member in members {
+        let analytics = DividendAnalytics::generate(&member.tracker, year, quarter, settings, AnalyticsScope::default())?;
+        let share = percent_of(analytics.total_dividends, combined.total_dividends)
+            .filter(|_| combined.total_dividends > Decimal::ZERO)
+            .map(|share| format_percent(share, 1))
+            .unwrap_or_else(|| "-".to_string());
+        table.row(vec![
+            Text::new(member.owner.clone(), Tone::Highlight),
+            format_money(analytics.total_dividends).into(),
+            analytics.total_payments.to_string().into(),
+            analytics.unique_symbols.to_string().into(),
+            share.into(),
+        ]);
+    }
+
+    table.row(vec![
+        Text::new("Combined", Tone::Emphasis),
+        Text::new(format_money(combined.total_dividends), Tone::Positive),
+        combined.total_payments.to_string().into(),
+        combined.unique_symbols.to_string().into(),
+        "100.0%".into(),
+    ]);
+
+    Ok(Section::new("Household Breakdown")
+        .icon("🏠")
+        .table(table)
+        .note(NoteKind::Info, "Merged in memory only; each data directory is left unchanged."))
+}
+
+/// Build the summary report view from computed analytics
+pub fn summary_report(
+    analytics: &DividendAnalytics,
+    year: Option<i32>,
+    quarter: Option<&str>,
+    sections: SummarySections,
+) -> Result<Report> {
+    let mut report = Report::new(TITLE).section(basic_summary(analytics, year, quarter));
+
+    // Monthly breakdown if requested
+    if sections.monthly && !analytics.monthly_breakdown.is_empty() {
+        report.push(monthly_breakdown(analytics, year, sections.monthly_goal)?);
+    }
+
+    // Quarterly breakdown if quarter filter is used
+    if quarter.is_some() && !analytics.quarterly_breakdown.is_empty() {
+        report.push(quarterly_breakdown(analytics));
+    }
+
+    if let Some(limit) = sections.top_payers {
+        if !analytics.top_payers.is_empty() {
+            report.push(top_payers(analytics, limit));
+        }
+    }
+
+    if sections.growth {
+        report.push(growth_analysis(analytics));
+    }
+
+    if sections.growth_by_symbol {
+        report.push(symbol_growth(analytics));
+    }
+
+    if sections.frequency {
+        report.push(frequency_analysis(analytics));
+    }
+
+    if sections.consistency {
+        report.push(consistency_analysis(analytics));
+    }
+
+    if sections.yield_analysis {
+        report.push(yield_analysis(analytics));
+    }
+
+    Ok(report)
+}
+
+fn basic_summary(
+    analytics: &DividendAnalytics,
+    year: Option<i32>,
+    quarter: Option<&str>,
+) -> Section {
+    let mut section = Section::new("Basic Summary").icon("📊");
+
+    if let Some(year) = year {
+        section = section.field("Year", Text::new(year.to_string(), Tone::Highlight));
+    }
+    if let Some(quarter) = quarter {
+        section = section.field("Quarter", Text::new(quarter, Tone::Highlight));
+    }
+
+    section = section
+        .field(
+            "Total Dividend Income",
+            Text::new(format_money(analytics.total_dividends), Tone::Positive),
+        );
+
+    // Interest and distributions are included above; split them out when present
+    if analytics.income_by_kind.keys().any(|kind| !kind.is_dividend()) {
+        for (kind, amount) in &analytics.income_by_kind {
+            section = section.field(format!("  {}", kind_label(*kind)), format_money(*amount));
+        }
+    }
+
+    if !analytics.total_fees.is_zero() {
+        section = section
+            .field("Fees Deducted", Text::new(format_money(analytics.total_fees), Tone::Warning))
+            .field(
+                "Net Income",
+                Text::new(
+                    format_money(analytics.total_dividends - analytics.total_fees),
+                    Tone::Positive,
+                ),
+            );
+    }
+
+    section = section
+        .field(
+            "Total Payments",
+            Text::new(analytics.total_payments.to_string(), Tone::Highlight),
+        )
+        .field(
+            "Unique Stocks",
+            Text::new(analytics.unique_symbols.to_string(), Tone::Highlight),
+        );
+
+    if analytics.total_payments > 0 {
+        let avg_payment = analytics.total_dividends / Decimal::from(analytics.total_payments);
+        section = section.field("Average Payment", Text::new(format_money(avg_payment), Tone::Warning));
+    }
+
+    section
+}
+
+fn kind_label(kind: IncomeKind) -> &'static str {
+    match kind {
+        IncomeKind::Dividend => "Dividends",
+        IncomeKind::Interest => "Interest",
+        IncomeKind::Distribution => "Distributions",
+    }
+}
+
+fn monthly_breakdown(analytics: &DividendAnalytics, year: Option<i32>, goal: Option<Decimal>) -> Result<Section> {
+    let today = Local::now().date_naive();
+    let display_year = year.unwrap_or_else(|| today.year());
+
+    let mut headers = vec!["Month", "Total", "Payments", "Stocks", "Top Stock", "Top Amount"];
+    if goal.is_some() {
+        headers.extend(["Vs Goal", "Cumulative"]);
+    }
+    let mut table = TableView::new(&headers);
+
+    let mut months: Vec<u32> = analytics.monthly_breakdown.keys().copied().collect();
+    if goal.is_some() {
+        // A month without income still counts against the goal once it has started
+        let elapsed = match display_year.cmp(&today.year()) {
+            std::cmp::Ordering::Less => 12,
+            std::cmp::Ordering::Equal => today.month(),
+            std::cmp::Ordering::Greater => 0,
+        };
+        months.extend(1..=elapsed);
+    }
+    months.sort_unstable();
+    months.dedup();
+
+    let mut cumulative = Decimal::ZERO;
+    let mut months_at_goal = 0;
+    for month in &months {
+        let summary = analytics.monthly_breakdown.get(month);
+        let total = summary.map_or(Decimal::ZERO, |summary| summary.total_amount);
+        let month_name = match *month {
+            1 => "January",
+            2 => "February",
+            3 => "March",
+            4 => "April",
+            5 => "May",
+            6 => "June",
+            7 => "July",
+            8 => "August",
+            9 => "September",
+            10 => "October",
+            11 => "November",
+            12 => "December",
+            _ => return Err(anyhow!("Invalid month: {}", month)),
+        };
+
+        let total_tone = goal.map_or(Tone::Plain, |goal| goal_tone(total, goal));
+        let mut row = vec![
+            month_name.into(),
+            Text::new(format_money(total), total_tone),
+            summary.map_or(0, |summary| summary.payment_count).to_string().into(),
+            summary.map_or(0, |summary| summary.unique_symbols).to_string().into(),
+            summary.and_then(|summary| summary.top_symbol.as_deref()).unwrap_or("-").into(),
+            match summary {
+                Some(summary) if summary.top_amount > Decimal::ZERO => format_money(summary.top_amount).into(),
+                _ => "-".into(),
+            },
+        ];
+        if let Some(goal) = goal {
+            let difference = total - goal;
+            cumulative += difference;
+            if difference >= Decimal::ZERO {
+                months_at_goal += 1;
+            }
+            row.push(signed_money(difference));
+            row.push(signed_money(cumulative));
+        }
+        table.row(row);
+    }
+
+    let mut section = Section::new("Monthly Breakdown")
+        .icon("📅")
+        .field("Year", Text::new(display_year.to_string(), Tone::Highlight));
+    if let Some(goal) = goal {
+        let label = if cumulative >= Decimal::ZERO { "Cumulative Surplus" } else { "Cumulative Deficit" };
+        section = section
+            .field("Monthly Goal", format_money(goal))
+            .field("Months at Goal", format!("{} of {}", months_at_goal, months.len()))
+            .field(label, signed_money(cumulative));
+    }
+    Ok(section.table(table))
+}
+
+/// Green once a month reaches the goal, yellow within a quarter of it, red below that
+fn goal_tone(total: Decimal, goal: Decimal) -> Tone {
+    if total >= goal {
+        Tone::Positive
+    } else if total >= goal * dec!(0.75) {
+        Tone::Warning
+    } else {
+        Tone::Negative
+    }
+}
+
+fn quarterly_breakdown(analytics: &DividendAnalytics) -> Section {
+    let mut table = TableView::new(&["Quarter", "Total", "Payments", "Stocks"]);
+
+    let mut quarters: Vec<_> = analytics.quarterly_breakdown.keys().collect();
+    quarters.sort();
+
+    for quarter in quarters {
+        let summary = &analytics.quarterly_breakdown[quarter];
+        table.row(vec![
+            quarter.clone().into(),
+            format_money(summary.total_amount).into(),
+            summary.payment_count.to_string().into(),
+            summary.unique_symbols.to_string().into(),
+        ]);
+    }
+
+    Section::new("Quarterly Breakdown").icon("📈").table(table)
+}
+
+fn top_payers(analytics: &DividendAnalytics, limit: usize) -> Section {
+    let mut table = TableView::new(&[
+        "Rank",
+        "Symbol",
+        "Total",
+        "Payments",
+        "Avg/Payment",
+        "First Payment",
+        "Latest Payment",
+    ]);
+
+    for (i, payer) in analytics.top_payers.iter().take(limit).enumerate() {
+        table.row(vec![
+            (i + 1).to_string().into(),
+            payer.symbol.clone().into(),
+            format_money(payer.total_amount).into(),
+            payer.payment_count.to_string().into(),
+            format_money(payer.average_amount).into(),
+            payer.first_payment.format("%Y-%m-%d").to_string().into(),
+            payer.last_payment.format("%Y-%m-%d").to_string().into(),
+        ]);
+    }
+
+    Section::new(format!("Top {} Dividend Payers", limit))
+        .icon("🏆")
+        .table(table)
+}
+
+fn growth_analysis(analytics: &DividendAnalytics) -> Section {
+    let Some(growth) = &analytics.growth_analysis else {
+        return Section::new("Growth Analysis")
+            .icon("📈")
+            .note(NoteKind::Warning, "Insufficient data (need 2+ years)");
+    };
+
+    let mut table = TableView::new(&["Year", "Total", "Payments", "Growth Rate"]);
+    for yearly in &growth.year_over_year {
+        table.row(vec![
+            yearly.year.to_string().into(),
+            format_money(yearly.total_dividends).into(),
+            yearly.payment_count.to_string().into(),
+            yearly.growth_rate.map(signed_percent).unwrap_or_else(|| "-".into()),
+        ]);
+    }
+
+    let mut section = Section::new("Year-over-Year Growth Analysis")
+        .icon("📈")
+        .table(table)
+        .field("Total Growth Rate", signed_percent(growth.total_growth_rate))
+        .field("Average Annual Growth", signed_percent(growth.average_annual_growth));
+
+    if let Some(best) = &growth.best_year {
+        section = section.field(
+            "Best Year",
+            format!("{} with {} growth", best.year, format_signed_percent(best.growth_rate.unwrap_or_default(), 1)),
+        );
+    }
+
+    if let Some(worst) = &growth.worst_year {
+        section = section.field(
+            "Worst Year",
+            format!("{} with {} growth", worst.year, format_signed_percent(worst.growth_rate.unwrap_or_default(), 1)),
+        );
+    }
+
+    section
+}
+
+/// Complete years shown per symbol in the per-share growth table
+const SYMBOL_GROWTH_YEARS: usize = 5;
+
+fn symbol_growth(analytics: &DividendAnalytics) -> Section {
+    let section = Section::new("Dividend per Share Growth by Symbol").icon("📊");
+
+    let mut years: Vec<i32> = analytics
+        .symbol_growth
+        .iter()
+        .flat_map(|growth| growth.yearly_per_share.iter().map(|(year, _)| *year))
+        .collect();
+    years.sort();
+    years.dedup();
+    let years = &years[years.len().saturating_sub(SYMBOL_GROWTH_YEARS)..];
+
+    if years.is_empty() {
+        return section.note(NoteKind::Warning, "No complete years of dividend history yet");
+    }
+
+    let year_headers: Vec<String> = years.iter().map(|year| year.to_string()).collect();
+    let mut headers = vec!["Symbol"];
+    headers.extend(year_headers.iter().map(String::as_str));
+    headers.push("CAGR");
+    let mut table = TableView::new(&headers);
+
+    for growth in &analytics.symbol_growth {
+        let mut row: Vec<Text> = vec![growth.symbol.clone().into()];
+        for year in years {
+            row.push(
+                growth
+                    .yearly_per_share
+                    .iter()
+                    .find(|(y, _)| y == year)
+                    .map(|(_, per_share)| format!("${:.4}", per_share.round_dp(4)).into())
+                    .unwrap_or_else(|| "-".into()),
+            );
+        }
+        row.push(growth.cagr.map(signed_percent).unwrap_or_else(|| "-".into()));
+        table.row(row);
+    }
+
+    section
+        .table(table)
+        .note(NoteKind::Info, "Per-share amounts exclude the current year; buying shares does not count as growth")
+}
+
+fn frequency_analysis(analytics: &DividendAnalytics) -> Section {
+    let freq = &analytics.frequency_analysis;
+    let mut section = Section::new("Dividend Frequency Analysis").icon("⏰");
+
+    for (label, payers) in [
+        ("Monthly Payers", &freq.monthly_payers),
+        ("Quarterly Payers", &freq.quarterly_payers),
+        ("Semi-Annual Payers", &freq.semi_annual_payers),
+        ("Annual Payers", &freq.annual_payers),
+        ("Irregular Payers", &freq.irregular_payers),
+    ] {
+        if !payers.is_empty() {
+            section = section.field(format!("{} ({})", label, payers.len()), payers.join(", "));
+        }
+    }
+
+    section
+}
+
+fn consistency_analysis(analytics: &DividendAnalytics) -> Section {
+    let consistency = &analytics.consistency_analysis;
+    let mut section = Section::new("Dividend Consistency Analysis").icon("🎯").field(
+        "Portfolio Consistency Score",
+        Text::new(
+            format!("{:.1}%", consistency.average_consistency_score),
+            Tone::Highlight,
+        ),
+    );
+
+    if !consistency.consistent_payers.is_empty() {
+        let mut table = TableView::new(&["Symbol", "Score", "Frequency"]);
+
+        for payer in &consistency.consistent_payers {
+            let tone = if payer.consistency_score >= 90.0 {
+                Tone::Positive
+            } else if payer.consistency_score >= 80.0 {
+                Tone::Warning
+            } else {
+                Tone::Plain
+            };
+
+            table.row(vec![
+                payer.symbol.clone().into(),
+                Text::new(format!("{:.1}%", payer.consistency_score), tone),
+                payer.expected_frequency.clone().into(),
+            ]);
+        }
+
+        section = section
+            .field(
+                "Consistent Payers",
+                consistency.consistent_payers.len().to_string(),
+            )
+            .table(table);
+    }
+
+    if !consistency.inconsistent_payers.is_empty() {
+        section = section.field(
+            format!("Inconsistent Payers ({})", consistency.inconsistent_payers.len()),
+            Text::new(consistency.inconsistent_payers.join(", "), Tone::Negative),
+        );
+    }
+
+    section
+}
+
+fn consistency_explanation(explanation: &ConsistencyExplanation) -> Section {
+    let mut table = TableView::new(&["From", "To", "Days", "vs Mean", "Note"]);
+    for interval in &explanation.intervals {
+        let note = if interval.likely_missing_payments > 0 {
+            Text::new(
+                format!("gap - {} payment(s) likely missing from records", interval.likely_missing_payments),
+                Tone::Warning,
+            )
+        } else if interval.drags_score {
+            Text::new("lowers score", Tone::Negative)
+        } else {
+            Text::default()
+        };
+
+        table.row(vec![
+            interval.from.format("%Y-%m-%d").to_string().into(),
+            interval.to.format("%Y-%m-%d").to_string().into(),
+            interval.days.to_string().into(),
+            format!("{:+.1}", interval.deviation).into(),
+            note,
+        ]);
+    }
+
+    let mut section = Section::new(format!("Consistency Explanation: {}", explanation.symbol))
+        .icon("🔍")
+        .field(
+            "Consistency Score",
+            Text::new(format!("{:.1}%", explanation.consistency_score), Tone::Highlight),
+        )
+        .field("Expected Frequency", explanation.expected_frequency.clone())
+        .field("Mean Interval", format!("{:.1} days", explanation.mean_interval))
+        .field("Standard Deviation", format!("{:.1} days", explanation.std_deviation))
+        .table(table)
+        .note(
+            NoteKind::Info,
+            "Score = 100 x (1 - standard deviation / mean interval); intervals more than one standard deviation from the mean lower it",
+        );
+
+    if explanation.intervals.iter().any(|i| i.likely_missing_payments > 0) {
+        section = section.note(
+            NoteKind::Tip,
+            "Gaps that are a whole multiple of the usual interval are usually unrecorded payments; add them to raise the score",
+        );
+    }
+
+    section
+}
+
+fn yield_analysis(analytics: &DividendAnalytics) -> Section {
+    let Some(yields) = &analytics.yield_analysis else {
+        return Section::new("Yield Analysis")
+            .icon("💰")
+            .note(NoteKind::Warning, "No holdings with cost basis found")
+            .note(NoteKind::Tip, "Add holdings with cost basis using 'holdings add' command");
+    };
+
+    let mut section = Section::new("Dividend Yield Analysis")
+        .icon("💰")
+        .field("Portfolio Average Yield", format_percent(yields.average_yield, 2));
+
+    if !yields.stock_yields.is_empty() {
+        let mut table = TableView::new(&[
+            "Symbol",
+            "Annual Dividend",
+            "Cost Basis",
+            "Shares",
+            "Yield %",
+        ]);
+
+        for stock_yield in &yields.stock_yields {
+            let tone = if stock_yield.yield_percent >= Decimal::from(5) {
+                Tone::Positive
+            } else if stock_yield.yield_percent >= Decimal::from(3) {
+                Tone::Warning
+            } else {
+                Tone::Plain
+            };
+
+            table.row(vec![
+                stock_yield.symbol.clone().into(),
+                format_money(stock_yield.annual_dividend).into(),
+                format_money(stock_yield.cost_basis).into(),
+                stock_yield.shares.to_string().into(),
+                Text::new(format_percent(stock_yield.yield_percent, 2), tone),
+            ]);
+        }
+
+        section = section.table(table);
+
+        if let Some(highest) = &yields.highest_yielding {
+            section = section.field(
+                "Highest Yielding",
+                format!("{} at {}", highest.symbol, format_percent(highest.yield_percent, 2)),
+            );
+        }
+
+        if let Some(lowest) = &yields.lowest_yielding {
+            section = section.field(
+                "Lowest Yielding",
+                format!("{} at {}", lowest.symbol, format_percent(lowest.yield_percent, 2)),
+            );
+        }
+    }
+
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType, Holding, DEFAULT_ACCOUNT};
+    use crate::render::Block;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn tracker() -> DividendTracker {
+        let mut tracker = DividendTracker::new();
+        for (symbol, month) in [("AAPL", 2), ("AAPL", 5), ("MSFT", 3)] {
+            let date = NaiveDate::from_ymd_opt(2024, month, 15).unwrap();
+            tracker.add_dividend(
+                Dividend::new(
+                    symbol.to_string(),
+                    None,
+                    date,
+                    date,
+                    dec!(0.5),
+                    dec!(10),
+                    DividendType::Regular,
+                )
+                .unwrap(),
+            );
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_summary_report_sections_follow_flags() {
+        let analytics = DividendAnalytics::generate(
+            &tracker(),
+            Some(2024),
+            None,
+            &AnalysisSettings::default(),
+            AnalyticsScope {
+                top_payers: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let sections = SummarySections {
+            monthly: true,
+            top_payers: Some(2),
+            ..Default::default()
+        };
+
+        let report = summary_report(&analytics, Some(2024), None, sections).unwrap();
+        let titles: Vec<_> = report.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Basic Summary", "Monthly Breakdown", "Top 2 Dividend Payers"]
+        );
+
+        match &report.sections[0].blocks[0] {
+            Block::Fields(fields) => {
+                assert_eq!(fields[0].label, "Year");
+                assert_eq!(fields[1].label, "Total Dividend Income");
+                assert_eq!(fields[1].value.text, "$15.00");
+            }
+            other => panic!("expected fields, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_monthly_breakdown_against_goal() {
+        let analytics = DividendAnalytics::generate(
+            &tracker(),
+            Some(2024),
+            None,
+            &AnalysisSettings::default(),
+            AnalyticsScope::default(),
+        )
+        .unwrap();
+        let section = monthly_breakdown(&analytics, Some(2024), Some(dec!(6))).unwrap();
+
+        match &section.blocks[0] {
+            Block::Fields(fields) => {
+                assert_eq!(fields[1].value.text, "$6.00");
+                assert_eq!(fields[2].value.text, "0 of 12");
+                assert_eq!(fields[3].label, "Cumulative Deficit");
+                assert_eq!(fields[3].value, Text::new("-$57.00", Tone::Negative));
+            }
+            other => panic!("expected fields, got {:?}", other),
+        }
+        match &section.blocks[1] {
+            Block::Table(table) => {
+                assert_eq!(table.rows.len(), 12);
+                assert_eq!(table.rows[0][1], Text::new("$0.00", Tone::Negative));
+                assert_eq!(table.rows[1][1], Text::new("$5.00", Tone::Warning));
+                assert_eq!(table.rows[1][6].text, "-$1.00");
+                assert_eq!(table.rows[2][7].text, "-$8.00");
+            }
+            other => panic!("expected table, got {:?}", other),
+        }
+
+        let section = monthly_breakdown(&analytics, Some(2024), Some(dec!(5))).unwrap();
+        match &section.blocks[1] {
+            Block::Table(table) => assert_eq!(table.rows[4][1], Text::new("$5.00", Tone::Positive)),
+            other => panic!("expected table, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_growth_section_without_history_warns() {
+        let analytics = DividendAnalytics::generate(
+            &tracker(),
+            None,
+            None,
+            &AnalysisSettings::default(),
+            AnalyticsScope {
+                growth: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let section = growth_analysis(&analytics);
+
+        assert_eq!(section.title, "Growth Analysis");
+        assert!(matches!(section.blocks[0], Block::Note(_)));
+    }
+
+    #[test]
+    fn test_combine_household_adds_holdings() {
+        let mut mine = tracker();
+        mine.add_holding(Holding::new("AAPL".to_string(), dec!(100), Some(dec!(150)), None).unwrap());
+        let mut theirs = tracker();
+        theirs.add_holding(Holding::new("AAPL".to_string(), dec!(50), Some(dec!(180)), None).unwrap());
+        theirs.add_holding(Holding::new("MSFT".to_string(), dec!(10), None, None).unwrap());
+
+        let members = vec![
+            HouseholdMember { owner: "Primary".to_string(), tracker: mine },
+            HouseholdMember { owner: "Partner".to_string(), tracker: theirs },
+        ];
+        let combined = combine_household(&members);
+
+        assert_eq!(combined.dividends.len(), 6);
+        let aapl = combined.holding("AAPL", DEFAULT_ACCOUNT).unwrap();
+        assert_eq!(aapl.shares, dec!(150));
+        assert_eq!(aapl.avg_cost_basis, Some(dec!(160)));
+        assert_eq!(combined.holding("MSFT", DEFAULT_ACCOUNT).unwrap().shares, dec!(10));
+    }
+}
@@ -0,0 +1,113 @@
+//! Symbol alias commands (`symbol aliases`)
+
+use anyhow::Result;
+use colored::*;
+use std::collections::BTreeMap;
+
+use super::CliConfig;
+use crate::cli::{AliasCommands, SymbolAliasesArgs, SymbolCommands};
+use crate::models::DividendTracker;
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+use crate::symbols::normalize_symbol;
+
+const TITLE: &str = "Symbol Aliases";
+
+/// Handle symbol commands
+pub fn run(config: &CliConfig, command: SymbolCommands) -> Result<()> {
+    match command {
+        SymbolCommands::Aliases(SymbolAliasesArgs { command }) => aliases(config, command),
+    }
+}
+
+fn aliases(config: &CliConfig, command: Option<AliasCommands>) -> Result<()> {
+    let persistence = config.create_persistence_manager()?;
+
+    match command {
+        None => {
+            let tracker = persistence.load()?;
+            config.render(&aliases_report(&tracker))?;
+        }
+        Some(AliasCommands::Add { alias, symbol }) => {
+            let (alias, target, (dividends, holding)) = persistence.with_tracker_mut(|tracker| {
+                let moved = tracker.add_symbol_alias(&alias, &symbol)?;
+                let alias = normalize_symbol(&alias);
+                let target = tracker.symbol_aliases[&alias].clone();
+                Ok((alias, target, moved))
+            })?;
+
+            println!("{} {} now maps to {}", "✓".green(), alias.cyan(), target.cyan());
+            if dividends > 0 || holding {
+                println!(
+                    "  Moved {} dividend{}{} from {}",
+                    dividends,
+                    if dividends == 1 { "" } else { "s" },
+                    if holding { " and the holding" } else { "" },
+                    alias
+                );
+            }
+        }
+        Some(AliasCommands::Remove { alias }) => {
+            let alias = normalize_symbol(&alias);
+            let target = persistence.with_tracker_mut(|tracker| tracker.remove_symbol_alias(&alias))?;
+            println!("{} Removed alias {} (was {})", "✓".green(), alias.cyan(), target);
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the alias list, flagging stored symbols written in another convention
+pub fn aliases_report(tracker: &DividendTracker) -> Report {
+    // Records saved before normalization may still use another spelling
+    let mut unnormalized: BTreeMap<&str, String> = BTreeMap::new();
+    let stored = tracker
+        .dividends
+        .iter()
+        .map(|d| d.symbol.as_str())
+        .chain(tracker.holdings.keys().map(|key| key.symbol.as_str()));
+    for symbol in stored {
+        let canonical = tracker.canonical_symbol(symbol);
+        if canonical != symbol {
+            unnormalized.insert(symbol, canonical);
+        }
+    }
+
+    if tracker.symbol_aliases.is_empty() && unnormalized.is_empty() {
+        return Report::warning(
+            TITLE,
+            "No aliases defined. Map a spelling with 'symbol aliases add BP:LN BP.L'.",
+        );
+    }
+
+    let mut report = Report::new(TITLE);
+
+    if !tracker.symbol_aliases.is_empty() {
+        let mut table = TableView::new(&["Alias", "Symbol"]);
+        for (alias, symbol) in &tracker.symbol_aliases {
+            table.row(vec![alias.clone().into(), Text::new(symbol.clone(), Tone::Highlight)]);
+        }
+        report.push(
+            Section::untitled()
+                .table(table)
+                .note(NoteKind::Info, "Exchange spellings like VOD:LN and VOD LN are normalized to VOD.L automatically."),
+        );
+    }
+
+    if !unnormalized.is_empty() {
+        let mut table = TableView::new(&["Stored As", "Normalizes To"]);
+        for (stored, canonical) in &unnormalized {
+            table.row(vec![
+                Text::new(*stored, Tone::Warning),
+                Text::new(canonical.clone(), Tone::Highlight),
+            ]);
+        }
+        report.push(
+            Section::new("Records Under Other Spellings")
+                .icon("⚠")
+                .table(table)
+                .note(NoteKind::Tip, "Move them with 'symbol aliases add <stored> <symbol>'."),
+        );
+    }
+
+    report
+}
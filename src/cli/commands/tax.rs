@@ -0,0 +1,1181 @@
+//! Tax reporting and classification commands
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use colored::*;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::{load_tracker, parse_amount, parse_date_or_today, parse_dividend_date, with_hint, CliConfig};
+use crate::cli::{
+    parse_value, ReclaimCommands, ReclaimFileArgs, ReclaimRefundArgs, TaxClassificationArg,
+    TaxClassifyArgs, TaxCommands, TaxEstimateArgs, TaxForeignArgs, TaxLotsArgs, TaxReclaimsArgs,
+    TaxReclassifyArgs, TaxReportArgs, TaxSummaryArgs,
+};
+use crate::config::{Config, DateBasis};
+use crate::diff::DatasetDiff;
+use crate::filters::{DividendFilter, SymbolMatch};
+use crate::format::{format_money, format_percent};
+use crate::identifiers::parse_country;
+use crate::models::{DividendTracker, TaxClassification, WithholdingReclaim};
+use crate::paths;
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+use crate::tax::{
+    AccountTaxSummary, EstimatedTax, ForeignDividendSummary, Form1099DIV, SymbolTaxSummary, TaxAnalyzer, TaxAssumptions,
+    TaxLotSummary, TaxSummary, UNKNOWN_COUNTRY,
+};
+
+const LOTS_TITLE: &str = "Tax Lot Analysis";
+const RECLAIMS_TITLE: &str = "Withholding Tax Reclaims";
+const FOREIGN_TITLE: &str = "Foreign Dividend Income";
+
+/// Handle tax-related commands
+pub fn run(config: &CliConfig, command: TaxCommands) -> Result<()> {
+    match command {
+        TaxCommands::Summary(args) => summary(config, args),
+        TaxCommands::Report(args) => report(config, args),
+        TaxCommands::Estimate(args) => estimate(config, args),
+        TaxCommands::Lots(args) => lots(config, args),
+        TaxCommands::Classify(args) => classify(config, args),
+        TaxCommands::Reclassify(args) => reclassify(config, args),
+        TaxCommands::Reclaims(args) => reclaims(config, args),
+        TaxCommands::Foreign(args) => foreign(config, args),
+    }
+}
+
+/// Handle tax summary command
+pub fn summary(config: &CliConfig, args: TaxSummaryArgs) -> Result<()> {
+    let TaxSummaryArgs {
+        year,
+        estimate,
+        filing_status,
+        income_bracket,
+        include_archived,
+        export_csv,
+    } = args;
+
+    let persistence = config.create_persistence_manager()?;
+    let tracker = load_tracker(&persistence, include_archived)?;
+
+    if tracker.dividends.is_empty() {
+        return config.render(&Report::warning("Tax Summary Report", "No dividend records found."));
+    }
+
+    let tax_year = year.unwrap_or_else(|| Local::now().year());
+
+    // Parse tax assumptions if estimate is requested
+    let tax_assumptions = if estimate {
+        Some(TaxAssumptions {
+            filing_status: filing_status.into(),
+            income_bracket: income_bracket.into(),
+            tax_year,
+        })
+    } else {
+        None
+    };
+
+    // Generate tax summary, leaving retirement accounts out of the estimate
+    let accounts = Config::load().unwrap_or_default().accounts;
+    let summary = TaxAnalyzer::generate_tax_summary_for_accounts(&tracker, tax_year, tax_assumptions, &accounts)?;
+
+    config.render(&tax_summary_report(&summary))?;
+
+    // Export if requested
+    if let Some(csv_path) = export_csv {
+        TaxAnalyzer::export_tax_summary_csv(&summary, &csv_path)?;
+        config.print_success(&format!("Tax summary exported to {}", csv_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handle tax report (1099-DIV style) command
+pub fn report(config: &CliConfig, args: TaxReportArgs) -> Result<()> {
+    let TaxReportArgs {
+        year,
+        export_csv,
+        export_json,
+    } = args;
+
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+
+    if tracker.dividends.is_empty() {
+        return config.render(&Report::warning("1099-DIV Style Tax Report", "No dividend records found."));
+    }
+
+    let tax_year = year.unwrap_or_else(|| Local::now().year());
+
+    // Generate 1099-DIV report
+    let report = TaxAnalyzer::generate_1099_div_report(&tracker, tax_year)?;
+
+    config.render(&form_1099_div_report(&report))?;
+
+    // Export if requested
+    if let Some(csv_path) = export_csv {
+        TaxAnalyzer::export_1099_div_csv(&report, &csv_path)?;
+        config.print_success(&format!("1099-DIV report exported to {}", csv_path.display()));
+    }
+
+    if let Some(json_path) = export_json {
+        let json_str = serde_json::to_string_pretty(&report)?;
+        paths::ensure_parent_dir(&json_path)?;
+        std::fs::write(&json_path, json_str)?;
+        config.print_success(&format!("1099-DIV report exported to {}", json_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handle tax estimate command
+pub fn estimate(config: &CliConfig, args: TaxEstimateArgs) -> Result<()> {
+    let TaxEstimateArgs {
+        year,
+        filing_status,
+        income_bracket,
+    } = args;
+
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+
+    if tracker.dividends.is_empty() {
+        return config.render(&Report::warning("Tax Estimate Calculator", "No dividend records found."));
+    }
+
+    let tax_year = year.unwrap_or_else(|| Local::now().year());
+
+    let tax_assumptions = TaxAssumptions {
+        filing_status: filing_status.into(),
+        income_bracket: income_bracket.into(),
+        tax_year,
+    };
+
+    // Generate tax summary with estimates, leaving retirement accounts out
+    let accounts = Config::load().unwrap_or_default().accounts;
+    let summary =
+        TaxAnalyzer::generate_tax_summary_for_accounts(&tracker, tax_year, Some(tax_assumptions), &accounts)?;
+
+    config.render(&tax_estimate_report(&summary))
+}
+
+/// Handle tax lots command
+pub fn lots(config: &CliConfig, args: TaxLotsArgs) -> Result<()> {
+    let TaxLotsArgs {
+        year,
+        symbol,
+        symbol_match,
+        export_csv,
+    } = args;
+
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+
+    if tracker.dividends.is_empty() {
+        return config.render(&Report::warning(LOTS_TITLE, "No dividend records found."));
+    }
+
+    let tax_year = year.unwrap_or_else(|| Local::now().year());
+
+    // Generate tax summary to get tax lots
+    let summary = TaxAnalyzer::generate_tax_summary(&tracker, tax_year, None)?;
+
+    if summary.tax_lots.is_empty() {
+        return config.render(&Report::warning(
+            LOTS_TITLE,
+            "No tax lot information found. Add tax lot IDs to dividends or import broker lots with 'holdings import-lots'.",
+        ));
+    }
+
+    // Filter by symbol if requested
+    let filter = match &symbol {
+        Some(sym) => DividendFilter::new().symbol(sym, symbol_match.into()),
+        None => DividendFilter::new(),
+    };
+    let filtered_lots: Vec<_> = summary.tax_lots.iter().filter(|lot| filter.matches_symbol(&lot.symbol)).collect();
+
+    config.render(&tax_lots_report(&filtered_lots, symbol.as_deref()))?;
+
+    // Export if requested
+    if let Some(csv_path) = export_csv {
+        export_tax_lots_csv(&filtered_lots, &csv_path)?;
+        config.print_success(&format!("Tax lots exported to {}", csv_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handle tax classification command
+pub fn classify(config: &CliConfig, args: TaxClassifyArgs) -> Result<()> {
+    let TaxClassifyArgs {
+        symbol,
+        classification,
+        country,
+        year,
+        apply_future,
+    } = args;
+
+    println!("{}", "Update Tax Classification".green().bold());
+    println!();
+
+    let tax_class = classification.map(TaxClassification::from);
+    let country = country.as_deref().map(parse_country).transpose()?;
+
+    let persistence = config.create_persistence_manager()?;
+    let symbol_upper = symbol.to_uppercase();
+
+    // Tax years follow the pay date
+    let mut filter = DividendFilter::new()
+        .symbol(&symbol_upper, SymbolMatch::Exact)
+        .date_basis(DateBasis::PayDate);
+    if let Some(target_year) = year {
+        filter = filter.year(target_year);
+    }
+
+    // Update dividends
+    let (updated_count, before, after) = persistence.with_tracker_mut(|tracker| {
+        let before = tracker.clone();
+        let mut updated_count = 0;
+        for dividend in &mut tracker.dividends {
+            if filter.matches(dividend) {
+                if let Some(tax_class) = &tax_class {
+                    dividend.tax_classification = tax_class.clone();
+                }
+                if let Some(country) = &country {
+                    dividend.country = Some(country.clone());
+                }
+                updated_count += 1;
+            }
+        }
+        Ok((updated_count, before, tracker.clone()))
+    })?;
+
+    if updated_count == 0 {
+        let message = format!("No dividend records found for {} in the specified period.", symbol_upper);
+        println!("{}", with_hint(&message, after.symbol_hint(&symbol_upper)).yellow());
+        return Ok(());
+    }
+
+    let changes: Vec<String> = tax_class
+        .iter()
+        .map(|class| format!("{:?}", class))
+        .chain(country.iter().map(|country| format!("country {}", country)))
+        .collect();
+    println!("{} Updated {} dividend records for {} to {}",
+             "✓".green(),
+             updated_count,
+             symbol_upper.cyan(),
+             changes.join(", "));
+    config.print_changes(&DatasetDiff::compare(&before, &after));
+
+    if apply_future {
+        println!("{}", "Note: --apply-future flag noted. Future dividends will need to be manually classified.".yellow());
+        println!("Consider updating your data import process to automatically classify {} dividends.", symbol_upper);
+    }
+
+    Ok(())
+}
+
+/// Handle the year-end reclassification wizard
+pub fn reclassify(config: &CliConfig, args: TaxReclassifyArgs) -> Result<()> {
+    let TaxReclassifyArgs { year, interactive } = args;
+
+    use std::io::{self, BufRead, Write};
+
+    println!("{}", "Year-End Tax Reclassification".green().bold());
+    println!();
+
+    // Read-only snapshot for the prompts; changes are applied in a transaction
+    let persistence = config.create_persistence_manager()?;
+    let tracker = persistence.load()?;
+
+    let tax_year = year.unwrap_or_else(|| Local::now().year());
+
+    // Group the tax year's dividends by symbol (by pay date, matching tax summary)
+    let mut by_symbol: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, dividend) in tracker.dividends.iter().enumerate() {
+        if dividend.pay_date.year() == tax_year {
+            by_symbol
+                .entry(dividend.symbol.clone())
+                .or_default()
+                .push(index);
+        }
+    }
+
+    if by_symbol.is_empty() {
+        println!("{}", format!("No dividend records found for {}.", tax_year).yellow());
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut pending: BTreeMap<String, TaxClassification> = BTreeMap::new();
+
+    'symbols: for (symbol, indices) in &by_symbol {
+        let mut breakdown: BTreeMap<String, (usize, Decimal)> = BTreeMap::new();
+        for &index in indices {
+            let dividend = &tracker.dividends[index];
+            let entry = breakdown
+                .entry(format!("{:?}", dividend.tax_classification))
+                .or_insert((0, Decimal::ZERO));
+            entry.0 += 1;
+            entry.1 += dividend.total_amount;
+        }
+
+        println!("{} ({} payments)", symbol.cyan().bold(), indices.len());
+        for (class, (count, total)) in &breakdown {
+            println!("  {:<16} {:>3} × ${:.2}", class, count, total);
+        }
+
+        if !interactive {
+            println!();
+            continue;
+        }
+
+        // Ask again after a typo rather than losing the answers so far
+        loop {
+            print!("  New classification [enter = keep, q = stop]: ");
+            io::stdout().flush()?;
+
+            let answer = match lines.next() {
+                Some(line) => line?.trim().to_string(),
+                None => break 'symbols,
+            };
+            println!();
+
+            if answer.eq_ignore_ascii_case("q") {
+                break 'symbols;
+            }
+            if answer.is_empty() {
+                break;
+            }
+
+            match parse_value::<TaxClassificationArg>(&answer, "classification") {
+                Ok(new_class) => {
+                    pending.insert(symbol.clone(), new_class.into());
+                    break;
+                }
+                Err(e) => println!("  {}", e.to_string().red()),
+            }
+        }
+    }
+
+    if !interactive {
+        println!("Run with {} to adjust classifications.", "--interactive".yellow());
+        return Ok(());
+    }
+
+    // Show the diff before writing anything
+    let mut preview = tracker.clone();
+    apply_reclassification(&mut preview, tax_year, &pending);
+    let diff = DatasetDiff::compare(&tracker, &preview);
+    let changed_records = diff.changed_dividends.len();
+    println!("{}", "Pending Changes".blue().bold());
+    config.print_changes(&diff);
+
+    if changed_records == 0 {
+        println!("  {}", "No changes to apply.".dimmed());
+        return Ok(());
+    }
+
+    print!("Apply {} changes? [y/N]: ", changed_records);
+    io::stdout().flush()?;
+    let confirmed = match lines.next() {
+        Some(line) => line?.trim().eq_ignore_ascii_case("y"),
+        None => false,
+    };
+
+    if !confirmed {
+        println!("{}", "Reclassification cancelled, no changes written.".yellow());
+        return Ok(());
+    }
+
+    // Apply all changes against fresh data and write them in a single save
+    persistence.with_tracker_mut(|tracker| {
+        apply_reclassification(tracker, tax_year, &pending);
+        Ok(())
+    })?;
+
+    println!("{} Reclassified {} dividend records for {}",
+             "✓".green(),
+             changed_records,
+             tax_year);
+
+    Ok(())
+}
+
+/// Set the classification of each symbol's dividends paid in `tax_year`
+fn apply_reclassification(
+    tracker: &mut DividendTracker,
+    tax_year: i32,
+    pending: &BTreeMap<String, TaxClassification>,
+) {
+    for dividend in &mut tracker.dividends {
+        if dividend.pay_date.year() != tax_year {
+            continue;
+        }
+        if let Some(new_class) = pending.get(&dividend.symbol) {
+            dividend.tax_classification = new_class.clone();
+        }
+    }
+}
+
+/// Handle withholding tax reclaim commands
+pub fn reclaims(config: &CliConfig, args: TaxReclaimsArgs) -> Result<()> {
+    let TaxReclaimsArgs {
+        command,
+        country,
+        outstanding,
+    } = args;
+
+    match command {
+        Some(ReclaimCommands::File(args)) => file_reclaim(config, args),
+        Some(ReclaimCommands::Refund(args)) => refund_reclaim(config, args),
+        None => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let today = Local::now().naive_local().date();
+            config.render(&reclaims_report(&tracker, country.as_deref(), outstanding, today))
+        }
+    }
+}
+
+/// Record a new reclaim, working out the excess over the treaty rate
+fn file_reclaim(config: &CliConfig, args: ReclaimFileArgs) -> Result<()> {
+    let ReclaimFileArgs {
+        symbol,
+        ex_date,
+        country,
+        withheld,
+        treaty_rate,
+        amount,
+        date,
+        reference,
+    } = args;
+
+    let ex_date = parse_dividend_date(&ex_date)?;
+    let filed_date = parse_date_or_today(date.as_deref())?;
+    let withheld = withheld.map(|w| parse_amount(&w, "withheld")).transpose()?;
+    let treaty_rate = treaty_rate.map(|r| parse_amount(&r, "treaty rate")).transpose()?;
+    let amount = amount.map(|a| parse_amount(&a, "amount")).transpose()?;
+
+    if let Some(rate) = treaty_rate {
+        if rate < Decimal::ZERO || rate > Decimal::from(100) {
+            return Err(anyhow!("Treaty rate must be between 0 and 100 percent"));
+        }
+    }
+
+    let persistence = config.create_persistence_manager()?;
+    let reclaim = persistence.with_tracker_mut(|tracker| {
+        let symbol_upper = symbol.trim().to_uppercase();
+        let dividend = tracker
+            .dividends
+            .iter_mut()
+            .find(|d| d.symbol == symbol_upper && d.ex_date == ex_date)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No {} dividend with ex-date {} found. Record it with 'div add' first.",
+                    symbol_upper,
+                    ex_date
+                )
+            })?;
+
+        if let Some(withheld) = withheld {
+            if withheld < Decimal::ZERO || withheld > dividend.total_amount {
+                return Err(anyhow!(
+                    "Withheld tax (${}) must be between $0 and the dividend total (${})",
+                    withheld,
+                    dividend.total_amount
+                ));
+            }
+            dividend.withholding_tax = Some(withheld);
+        }
+
+        let claimed = match (amount, treaty_rate) {
+            (Some(amount), _) => amount,
+            (None, Some(rate)) => {
+                let withheld = dividend.withholding_tax.ok_or_else(|| {
+                    anyhow!("Withholding for {} is unknown. Pass --withheld with the tax taken at source.", symbol_upper)
+                })?;
+                let treaty_tax = dividend.total_amount * rate / Decimal::from(100);
+                let excess = (withheld - treaty_tax).round_dp(2);
+                if excess <= Decimal::ZERO {
+                    return Err(anyhow!(
+                        "Withholding of {} is already at or below the {}% treaty rate; nothing to reclaim",
+                        format_money(withheld),
+                        rate.normalize()
+                    ));
+                }
+                excess
+            }
+            (None, None) => unreachable!("clap requires --amount or --treaty-rate"),
+        };
+
+        let mut reclaim = WithholdingReclaim::new(&symbol_upper, ex_date, &country, claimed, filed_date)?;
+        reclaim.reference = reference.clone();
+        tracker.file_reclaim(reclaim.clone())?;
+        Ok(reclaim)
+    })?;
+
+    println!(
+        "{} Filed reclaim of {} for {} ({}) with ex-date {}",
+        "✓".green(),
+        format_money(reclaim.claimed).green(),
+        reclaim.symbol.cyan(),
+        reclaim.country,
+        reclaim.ex_date
+    );
+
+    Ok(())
+}
+
+/// Close a reclaim with the refund that arrived
+fn refund_reclaim(config: &CliConfig, args: ReclaimRefundArgs) -> Result<()> {
+    let ReclaimRefundArgs {
+        symbol,
+        ex_date,
+        amount,
+        country,
+        date,
+    } = args;
+
+    let ex_date = parse_dividend_date(&ex_date)?;
+    let amount = parse_amount(&amount, "amount")?;
+    let refund_date = parse_date_or_today(date.as_deref())?;
+
+    let persistence = config.create_persistence_manager()?;
+    let reclaim = persistence.with_tracker_mut(|tracker| {
+        let reclaim = tracker.find_reclaim_mut(&symbol, ex_date, country.as_deref())?;
+        reclaim.record_refund(amount, refund_date)?;
+        Ok(reclaim.clone())
+    })?;
+
+    println!(
+        "{} Recorded refund of {} for {} ({}), claimed {}",
+        "✓".green(),
+        format_money(amount).green(),
+        reclaim.symbol.cyan(),
+        reclaim.country,
+        format_money(reclaim.claimed)
+    );
+
+    Ok(())
+}
+
+/// Build the reclaim tracker report
+pub fn reclaims_report(
+    tracker: &DividendTracker,
+    country: Option<&str>,
+    outstanding_only: bool,
+    today: NaiveDate,
+) -> Report {
+    let country = country.map(|c| c.trim().to_uppercase());
+    let reclaims: Vec<&WithholdingReclaim> = tracker
+        .reclaims
+        .iter()
+        .filter(|r| country.as_ref().is_none_or(|c| &r.country == c))
+        .filter(|r| !outstanding_only || r.is_pending())
+        .collect();
+
+    if reclaims.is_empty() {
+        let message = if tracker.reclaims.is_empty() {
+            "No withholding reclaims recorded. File one with 'tax reclaims file'."
+        } else {
+            "No reclaims match the specified filters."
+        };
+        return Report::warning(RECLAIMS_TITLE, message);
+    }
+
+    let mut table = TableView::new(&[
+        "Symbol", "Country", "Ex-Date", "Filed", "Claimed", "Refunded", "Status", "Reference",
+    ]);
+    let mut by_country: BTreeMap<&str, (usize, Decimal)> = BTreeMap::new();
+    for reclaim in &reclaims {
+        let status = match reclaim.refund_date {
+            Some(date) => Text::new(format!("Refunded {}", date.format("%Y-%m-%d")), Tone::Positive),
+            None => Text::new(
+                format!("Pending ({} days)", (today - reclaim.filed_date).num_days()),
+                Tone::Warning,
+            ),
+        };
+        table.row(vec![
+            Text::new(reclaim.symbol.clone(), Tone::Highlight),
+            reclaim.country.clone().into(),
+            reclaim.ex_date.format("%Y-%m-%d").to_string().into(),
+            reclaim.filed_date.format("%Y-%m-%d").to_string().into(),
+            format_money(reclaim.claimed).into(),
+            reclaim.refunded.map(format_money).unwrap_or_else(|| "-".to_string()).into(),
+            status,
+            reclaim.reference.as_deref().unwrap_or("-").into(),
+        ]);
+
+        let entry = by_country.entry(reclaim.country.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += reclaim.outstanding();
+    }
+
+    let claimed: Decimal = reclaims.iter().map(|r| r.claimed).sum();
+    let refunded: Decimal = reclaims.iter().filter_map(|r| r.refunded).sum();
+    let outstanding: Decimal = reclaims.iter().map(|r| r.outstanding()).sum();
+    // Claims closed with a partial refund leave money that will not come back
+    let shortfall: Decimal = reclaims
+        .iter()
+        .filter_map(|r| r.refunded.map(|refunded| r.claimed - refunded))
+        .sum();
+
+    let mut totals = Section::untitled()
+        .table(table)
+        .field("Total Claimed", format_money(claimed))
+        .field("Total Refunded", Text::new(format_money(refunded), Tone::Positive))
+        .field("Outstanding", Text::new(format_money(outstanding), Tone::Warning));
+    if shortfall > Decimal::ZERO {
+        totals = totals.field("Not Recovered", Text::new(format_money(shortfall), Tone::Negative));
+    }
+
+    let mut report = Report::new(RECLAIMS_TITLE).section(totals);
+
+    if by_country.len() > 1 {
+        let mut countries = TableView::new(&["Country", "Reclaims", "Outstanding"]);
+        for (country, (count, outstanding)) in by_country {
+            countries.row(vec![
+                country.into(),
+                count.to_string().into(),
+                format_money(outstanding).into(),
+            ]);
+        }
+        report.push(Section::new("By Country").icon("🌍").table(countries));
+    }
+
+    report
+}
+
+/// Percentage of the year's total dividend income
+fn share_of(amount: Decimal, total: Decimal) -> String {
+    if total > Decimal::ZERO {
+        format_percent(amount / total * Decimal::from(100), 1)
+    } else {
+        "0.0%".to_string()
+    }
+}
+
+/// Build the tax summary report view
+pub fn tax_summary_report(summary: &TaxSummary) -> Report {
+    let total = summary.total_dividend_income;
+
+    let mut income = TableView::new(&["Category", "Amount", "Percentage"]);
+    income.row(vec![
+        "Total Dividend Income".into(),
+        format_money(total).into(),
+        "100.0%".into(),
+    ]);
+    income.row(vec![
+        "  Qualified Dividends".into(),
+        format_money(summary.qualified_dividends).into(),
+        share_of(summary.qualified_dividends, total).into(),
+    ]);
+    income.row(vec![
+        "  Non-Qualified Dividends".into(),
+        format_money(summary.non_qualified_dividends).into(),
+        share_of(summary.non_qualified_dividends, total).into(),
+    ]);
+
+    for (category, amount) in [
+        ("  Return of Capital", summary.return_of_capital),
+        ("  Tax-Free Dividends", summary.tax_free_dividends),
+        ("  Foreign Dividends", summary.foreign_dividends.total_foreign_income),
+    ] {
+        if amount > Decimal::ZERO {
+            income.row(vec![
+                category.into(),
+                format_money(amount).into(),
+                share_of(amount, total).into(),
+            ]);
+        }
+    }
+
+    let mut section = Section::new(format!("Tax Summary for {}", summary.tax_year))
+        .icon("📊")
+        .table(income);
+
+    // Fees and withholding don't reduce taxable income but explain the gap to broker deposits
+    if summary.total_fees > Decimal::ZERO || summary.total_withholding > Decimal::ZERO {
+        if summary.total_fees > Decimal::ZERO {
+            section = section.field("Fees Deducted", Text::new(format_money(summary.total_fees), Tone::Warning));
+        }
+        if summary.total_withholding > Decimal::ZERO {
+            section = section.field(
+                "Withholding Tax",
+                Text::new(format_money(summary.total_withholding), Tone::Warning),
+            );
+        }
+        section = section
+            .field("Gross Income", format_money(total))
+            .field(
+                "Net Received",
+                Text::new(
+                    format_money(total - summary.total_fees - summary.total_withholding),
+                    Tone::Positive,
+                ),
+            )
+            .note(
+                NoteKind::Info,
+                "Taxable income is the gross amount; fees and withholding are shown to reconcile with broker deposits.",
+            );
+    }
+
+    let mut report = Report::new("Tax Summary Report").section(section);
+
+    if summary.interest_income > Decimal::ZERO {
+        report.push(interest_section(summary));
+    }
+
+    if let Some(ref estimated_tax) = summary.estimated_tax {
+        let mut section = estimated_tax_section(estimated_tax);
+        if let Some(note) = retirement_note(summary) {
+            section = section.note(NoteKind::Info, note);
+        }
+        report.push(section);
+    }
+
+    // By-account breakdown only adds information with several accounts or a retirement one
+    if summary.by_account.len() > 1 || summary.retirement_income > Decimal::ZERO {
+        report.push(account_breakdown(&summary.by_account));
+    }
+
+    // By-symbol breakdown only adds information with multiple symbols
+    if summary.by_symbol.len() > 1 {
+        report.push(symbol_breakdown(&summary.by_symbol));
+    }
+
+    if !summary.foreign_dividends.by_country.is_empty() {
+        report.push(country_breakdown(&summary.foreign_dividends));
+    }
+
+    report
+}
+
+fn estimated_tax_section(estimated_tax: &EstimatedTax) -> Section {
+    let mut table = TableView::new(&["Income Type", "Tax Rate", "Estimated Tax"]);
+    table.row(vec![
+        "Qualified Dividends".into(),
+        format_percent(estimated_tax.capital_gains_rate * Decimal::from(100), 1).into(),
+        format_money(estimated_tax.qualified_tax).into(),
+    ]);
+    table.row(vec![
+        "Non-Qualified Dividends".into(),
+        format_percent(estimated_tax.ordinary_tax_bracket * Decimal::from(100), 1).into(),
+        format_money(estimated_tax.non_qualified_tax).into(),
+    ]);
+    if estimated_tax.interest_tax > Decimal::ZERO {
+        table.row(vec![
+            "Interest".into(),
+            format_percent(estimated_tax.ordinary_tax_bracket * Decimal::from(100), 1).into(),
+            format_money(estimated_tax.interest_tax).into(),
+        ]);
+    }
+    table.row(vec![
+        "Total Estimated Tax".into(),
+        "-".into(),
+        Text::new(format_money(estimated_tax.total_estimated_tax), Tone::Emphasis),
+    ]);
+
+    let assumptions = &estimated_tax.tax_assumptions;
+    Section::new("Estimated Tax Liability")
+        .icon("💰")
+        .table(table)
+        .note(
+            NoteKind::Info,
+            format!(
+                "Assumptions: {:?} filing, {:?} income bracket",
+                assumptions.filing_status, assumptions.income_bracket
+            ),
+        )
+        .note(
+            NoteKind::Warning,
+            format!("Tax rates are estimates based on {} tax brackets", assumptions.tax_year),
+        )
+}
+
+/// Interest is reported on 1099-INT, so it is listed apart from dividend income
+fn interest_section(summary: &TaxSummary) -> Section {
+    let mut table = TableView::new(&["Symbol", "Interest"]);
+    for (symbol, amount) in &summary.interest_by_symbol {
+        table.row(vec![symbol.clone().into(), format_money(*amount).into()]);
+    }
+
+    Section::new("Interest Income (1099-INT)")
+        .icon("🏦")
+        .field("Total Interest", Text::new(format_money(summary.interest_income), Tone::Positive))
+        .table(table)
+        .note(NoteKind::Info, "Interest is not part of dividend income and is taxed as ordinary income.")
+}
+
+fn account_breakdown(by_account: &[AccountTaxSummary]) -> Section {
+    let mut table = TableView::new(&["Account", "Type", "Total Income", "Qualified", "Non-Qualified", "Interest"]);
+    for account in by_account {
+        let kind = if account.kind.is_retirement() {
+            Text::new(account.kind.label(), Tone::Highlight)
+        } else {
+            account.kind.label().into()
+        };
+        table.row(vec![
+            account.account.clone().into(),
+            kind,
+            format_money(account.total_income).into(),
+            format_money(account.qualified_amount).into(),
+            format_money(account.non_qualified_amount).into(),
+            format_money(account.interest_income).into(),
+        ]);
+    }
+
+    let mut section = Section::new("Breakdown by Account").icon("🏦").table(table);
+    if by_account.iter().all(|a| !a.kind.is_retirement()) {
+        section = section.note(
+            NoteKind::Tip,
+            "List retirement accounts under traditional or roth in the [accounts] section of config.toml to leave them out of estimated tax.",
+        );
+    }
+    section
+}
+
+/// Why the estimate is lower than the income, when retirement accounts were left out
+fn retirement_note(summary: &TaxSummary) -> Option<String> {
+    let accounts: Vec<&str> = summary
+        .by_account
+        .iter()
+        .filter(|a| a.kind.is_retirement())
+        .map(|a| a.account.as_str())
+        .collect();
+    (summary.retirement_income > Decimal::ZERO).then(|| {
+        format!(
+            "Leaves out {} paid into retirement accounts ({})",
+            format_money(summary.retirement_income),
+            accounts.join(", ")
+        )
+    })
+}
+
+fn symbol_breakdown(by_symbol: &HashMap<String, SymbolTaxSummary>) -> Section {
+    // Sort by total income (highest first), then symbol for a stable order
+    let mut symbols: Vec<_> = by_symbol.iter().collect();
+    symbols.sort_by(|(a_sym, a), (b_sym, b)| {
+        b.total_income.cmp(&a.total_income).then_with(|| a_sym.cmp(b_sym))
+    });
+
+    let mut table = TableView::new(&[
+        "Symbol",
+        "Total Income",
+        "Qualified",
+        "Non-Qualified",
+        "Payments",
+    ]);
+    for (symbol, summary) in symbols {
+        table.row(vec![
+            symbol.clone().into(),
+            format_money(summary.total_income).into(),
+            format_money(summary.qualified_amount).into(),
+            format_money(summary.non_qualified_amount).into(),
+            summary.payment_count.to_string().into(),
+        ]);
+    }
+
+    Section::new("Breakdown by Stock Symbol").icon("📈").table(table)
+}
+
+/// Rate of tax withheld from an amount, as a percentage
+fn withholding_rate(withheld: Decimal, income: Decimal) -> String {
+    if income > Decimal::ZERO {
+        format_percent(withheld / income * Decimal::from(100), 1)
+    } else {
+        "-".to_string()
+    }
+}
+
+fn country_breakdown(foreign: &ForeignDividendSummary) -> Section {
+    let mut table = TableView::new(&["Country", "Income", "Withheld", "Rate", "Net"]);
+    for country in foreign.countries() {
+        table.row(vec![
+            country.country.clone().into(),
+            format_money(country.dividend_income).into(),
+            format_money(country.withholding_tax).into(),
+            withholding_rate(country.withholding_tax, country.dividend_income).into(),
+            format_money(country.net_income).into(),
+        ]);
+    }
+    Section::new("Foreign Income by Country").icon("🌍").table(table)
+}
+
+/// Handle the foreign income command
+pub fn foreign(config: &CliConfig, args: TaxForeignArgs) -> Result<()> {
+    let tracker = config.create_persistence_manager()?.load()?;
+    let tax_year = args.year.unwrap_or_else(|| Local::now().year());
+    let summary = TaxAnalyzer::generate_tax_summary(&tracker, tax_year, None)?;
+    config.render(&foreign_report(&summary.foreign_dividends, tax_year))
+}
+
+/// Build the foreign income view: totals by country, then by symbol
+pub fn foreign_report(foreign: &ForeignDividendSummary, tax_year: i32) -> Report {
+    if foreign.by_symbol.is_empty() {
+        return Report::warning(
+            FOREIGN_TITLE,
+            format!(
+                "No foreign dividends in {}. Set a dividend's country with 'tax classify SYMBOL --country CH'.",
+                tax_year
+            ),
+        );
+    }
+
+    let income: Decimal = foreign.by_symbol.iter().map(|s| s.dividend_income).sum();
+    let withheld: Decimal = foreign.by_symbol.iter().map(|s| s.withholding_tax).sum();
+    let totals = Section::new(format!("Foreign Dividends in {}", tax_year))
+        .icon("💱")
+        .field("Income", Text::new(format_money(income), Tone::Emphasis))
+        .field("Withholding Tax", Text::new(format_money(withheld), Tone::Warning))
+        .field("Effective Rate", withholding_rate(withheld, income))
+        .field("Net Received", Text::new(format_money(income - withheld), Tone::Positive));
+
+    let mut symbols = TableView::new(&["Symbol", "Country", "Payments", "Income", "Withheld", "Rate"]);
+    for symbol in &foreign.by_symbol {
+        symbols.row(vec![
+            symbol.symbol.clone().into(),
+            symbol.country.clone().into(),
+            symbol.payments.to_string().into(),
+            format_money(symbol.dividend_income).into(),
+            format_money(symbol.withholding_tax).into(),
+            withholding_rate(symbol.withholding_tax, symbol.dividend_income).into(),
+        ]);
+    }
+    let mut by_symbol = Section::new("By Symbol").icon("📈").table(symbols);
+    if foreign.by_country.contains_key(UNKNOWN_COUNTRY) {
+        by_symbol = by_symbol.note(
+            NoteKind::Tip,
+            "Set the country of foreign dividends without one with 'tax classify SYMBOL --country CH'.",
+        );
+    }
+
+    Report::new(FOREIGN_TITLE)
+        .section(totals)
+        .section(country_breakdown(foreign))
+        .section(by_symbol)
+}
+
+/// Build the 1099-DIV report view
+pub fn form_1099_div_report(report: &Form1099DIV) -> Report {
+    let mut totals = TableView::new(&["Box", "Description", "Amount"]);
+    totals.row(vec![
+        "1a".into(),
+        "Total Ordinary Dividends".into(),
+        format_money(report.summary.total_ordinary_dividends).into(),
+    ]);
+    totals.row(vec![
+        "1b".into(),
+        "Qualified Dividends".into(),
+        format_money(report.summary.total_qualified_dividends).into(),
+    ]);
+    totals.row(vec![
+        "3".into(),
+        "Non-dividend Distributions".into(),
+        format_money(report.summary.total_non_dividend_distributions).into(),
+    ]);
+
+    let mut view = Report::new("1099-DIV Style Tax Report").section(
+        Section::new(format!("1099-DIV Report for {}", report.tax_year))
+            .icon("📋")
+            .table(totals),
+    );
+
+    if !report.payers.is_empty() {
+        let mut payers = TableView::new(&["Payer", "Symbol", "Box 1a", "Box 1b", "Box 3"]);
+        for payer in &report.payers {
+            payers.row(vec![
+                payer.payer_name.clone().into(),
+                payer.symbols.join(", ").into(),
+                format_money(payer.total_ordinary_dividends).into(),
+                format_money(payer.qualified_dividends).into(),
+                format_money(payer.non_dividend_distributions).into(),
+            ]);
+        }
+        view.push(Section::new("Payer Details").table(payers));
+    }
+
+    let mut notes = Section::untitled();
+    if report.interest_income > Decimal::ZERO {
+        notes = notes.note(
+            NoteKind::Info,
+            format!(
+                "Interest income of {} is reported on 1099-INT and not included here",
+                format_money(report.interest_income)
+            ),
+        );
+    }
+    view.section(
+        notes
+            .note(NoteKind::Info, "This report summarizes your dividend income in 1099-DIV format")
+            .note(NoteKind::Tip, "Use these amounts when filing your tax return"),
+    )
+}
+
+/// Build the estimate-focused tax report view
+pub fn tax_estimate_report(summary: &TaxSummary) -> Report {
+    let mut section = Section::new(format!("Tax Estimate for {}", summary.tax_year)).icon("💰");
+
+    if let Some(ref estimated_tax) = summary.estimated_tax {
+        let assumptions = &estimated_tax.tax_assumptions;
+        section = section
+            .field(
+                "Qualified Dividend Income",
+                Text::new(format_money(summary.qualified_dividends), Tone::Positive),
+            )
+            .field(
+                "  Estimated Tax",
+                Text::new(
+                    format!(
+                        "{} ({} rate)",
+                        format_money(estimated_tax.qualified_tax),
+                        format_percent(estimated_tax.capital_gains_rate * Decimal::from(100), 1)
+                    ),
+                    Tone::Warning,
+                ),
+            )
+            .field(
+                "Non-Qualified Dividend Income",
+                Text::new(format_money(summary.non_qualified_dividends), Tone::Positive),
+            )
+            .field(
+                "  Estimated Tax",
+                Text::new(
+                    format!(
+                        "{} ({} rate)",
+                        format_money(estimated_tax.non_qualified_tax),
+                        format_percent(estimated_tax.ordinary_tax_bracket * Decimal::from(100), 1)
+                    ),
+                    Tone::Warning,
+                ),
+            );
+        if summary.interest_income > Decimal::ZERO {
+            section = section
+                .field(
+                    "Interest Income",
+                    Text::new(format_money(summary.interest_income), Tone::Positive),
+                )
+                .field(
+                    "  Estimated Tax",
+                    Text::new(
+                        format!(
+                            "{} ({} rate)",
+                            format_money(estimated_tax.interest_tax),
+                            format_percent(estimated_tax.ordinary_tax_bracket * Decimal::from(100), 1)
+                        ),
+                        Tone::Warning,
+                    ),
+                );
+        }
+        if let Some(note) = retirement_note(summary) {
+            section = section.note(NoteKind::Info, note);
+        }
+        section = section
+            .field(
+                "Total Estimated Tax",
+                Text::new(format_money(estimated_tax.total_estimated_tax), Tone::Negative),
+            )
+            .note(
+                NoteKind::Info,
+                format!(
+                    "Based on {:?} filing status, {:?} income bracket",
+                    assumptions.filing_status, assumptions.income_bracket
+                ),
+            )
+            .note(
+                NoteKind::Warning,
+                format!(
+                    "These are estimates based on {} tax rates. Consult a tax professional for accuracy.",
+                    assumptions.tax_year
+                ),
+            );
+    } else {
+        section = section.note(
+            NoteKind::Warning,
+            "No tax estimates available. Use --estimate flag with filing status and income bracket.",
+        );
+    }
+
+    Report::new("Tax Estimate Calculator").section(section)
+}
+
+/// Build the tax lot report view
+pub fn tax_lots_report(lots: &[&TaxLotSummary], symbol_filter: Option<&str>) -> Report {
+    if lots.is_empty() {
+        let message = match symbol_filter {
+            Some(symbol) => format!("No tax lot information found for {}.", symbol),
+            None => "No tax lot information found.".to_string(),
+        };
+        return Report::warning(LOTS_TITLE, message);
+    }
+
+    let title = match symbol_filter {
+        Some(symbol) => format!("Tax Lots for {}", symbol),
+        None => "Tax Lot Summary".to_string(),
+    };
+
+    let mut table = TableView::new(&[
+        "Tax Lot ID",
+        "Symbol",
+        "Dividend Income",
+        "Shares",
+        "Purchase Date",
+        "Cost Basis/Share",
+    ]);
+    for lot in lots {
+        table.row(vec![
+            lot.tax_lot_id.clone().into(),
+            lot.symbol.clone().into(),
+            format_money(lot.dividend_income).into(),
+            lot.shares.map(|s| s.to_string()).unwrap_or_else(|| "N/A".to_string()).into(),
+            lot.purchase_date
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+            lot.cost_basis_per_share
+                .map(format_money)
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+        ]);
+    }
+
+    let mut section = Section::new(title).icon("📊").table(table);
+    if lots.iter().any(|lot| lot.purchase_date.is_none()) {
+        section = section
+            .note(NoteKind::Info, "Tax lot tracking requires additional cost basis data")
+            .note(
+                NoteKind::Tip,
+                "Import your broker's lot export with 'holdings import-lots' to fill in purchase dates and cost basis",
+            );
+    }
+    Report::new(LOTS_TITLE).section(section)
+}
+
+/// Export tax lots to CSV
+fn export_tax_lots_csv(lots: &[&TaxLotSummary], file_path: &Path) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = paths::create_file(file_path)?;
+
+    // Write header
+    writeln!(file, "Tax Lot ID,Symbol,Dividend Income,Shares,Purchase Date,Cost Basis Per Share")?;
+
+    // Write data
+    for lot in lots {
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            lot.tax_lot_id,
+            lot.symbol,
+            lot.dividend_income,
+            lot.shares.map(|s| s.to_string()).unwrap_or_else(|| "".to_string()),
+            lot.purchase_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "".to_string()),
+            lot.cost_basis_per_share.map(|c| c.to_string()).unwrap_or_else(|| "".to_string())
+        )?;
+    }
+
+    Ok(())
+}
+
@@ -0,0 +1,317 @@
+//! Buy and sell ledger commands (`transactions buy`, `sell`, `list`, `entry-yield`)
+
+use anyhow::{bail, Result};
+use colored::*;
+use chrono::Datelike;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use super::{parse_amount, parse_date_or_today, CliConfig};
+use crate::cli::{TradeArgs, TransactionCommands};
+use crate::config::{Config, DisplaySettings};
+use crate::format::{format_money, format_percent, format_shares};
+use crate::models::{Dividend, DividendTracker, HoldingAdjustment};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Transactions";
+
+/// Handle transaction commands
+pub fn run(config: &CliConfig, command: TransactionCommands) -> Result<()> {
+    match command {
+        TransactionCommands::Buy(args) => trade(config, args, false),
+        TransactionCommands::Sell(args) => trade(config, args, true),
+        TransactionCommands::List { symbol } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let display = Config::load().unwrap_or_default().display;
+            let symbol = symbol.map(|s| tracker.canonical_symbol(&s));
+            config.render(&transactions_report(&tracker, symbol.as_deref(), &display))
+        }
+        TransactionCommands::EntryYield { symbol } => {
+            let tracker = config.create_persistence_manager()?.load()?;
+            let symbol = symbol.map(|s| tracker.canonical_symbol(&s));
+            config.render(&entry_yield_report(&tracker, symbol.as_deref()))
+        }
+    }
+}
+
+fn trade(config: &CliConfig, args: TradeArgs, sell: bool) -> Result<()> {
+    let TradeArgs {
+        symbol,
+        shares,
+        price,
+        date,
+        account,
+    } = args;
+
+    let shares = parse_amount(&shares, "shares")?;
+    if shares <= Decimal::ZERO {
+        bail!("Shares must be positive");
+    }
+    let price = parse_amount(&price, "price")?;
+    let date = parse_date_or_today(date.as_deref())?;
+    let delta = if sell { -shares } else { shares };
+
+    let persistence = config.create_persistence_manager()?;
+    let (holding, started) = persistence.with_tracker_mut(|tracker| {
+        let started = tracker.position_key(&tracker.canonical_symbol(&symbol), account.as_deref())?.is_none();
+        Ok((tracker.record_trade(&symbol, account.as_deref(), date, delta, price)?, started))
+    })?;
+
+    let display = Config::load().unwrap_or_default().display;
+    println!(
+        "{} {} {} {} at {} on {}",
+        "✓".green(),
+        if sell { "Sold" } else { "Bought" },
+        format_shares(shares, &display),
+        holding.label().cyan(),
+        format_money(price),
+        date.format("%Y-%m-%d").to_string().blue()
+    );
+    if started {
+        println!("  Started a holding for {}", holding.label());
+    }
+    if !sell {
+        let purchase_yield = holding
+            .adjustments
+            .iter()
+            .rev()
+            .find(|a| a.date == date && a.shares_delta == delta && a.price == Some(price))
+            .and_then(|a| a.purchase_yield);
+        match purchase_yield {
+            Some(purchase_yield) => println!(
+                "  Purchase yield: {} (forward dividend {} per share)",
+                format_percent(purchase_yield, 2).green(),
+                format_money(purchase_yield * price / Decimal::ONE_HUNDRED)
+            ),
+            None => println!(
+                "  Purchase yield unknown: {} had no recent dividend or forward dividend on {}",
+                holding.symbol,
+                date.format("%Y-%m-%d")
+            ),
+        }
+    }
+    if holding.is_closed() {
+        println!("  Every share is sold; {} is now closed", holding.label());
+    } else {
+        println!(
+            "  Shares held: {}{}",
+            format_shares(holding.shares, &display).yellow(),
+            holding
+                .avg_cost_basis
+                .map(|c| format!(" (average cost {})", format_money(c)))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Money put into buys, weighted by the purchase yield where it is known
+#[derive(Default)]
+struct EntryYield {
+    buys: usize,
+    invested: Decimal,
+    /// Money invested in buys whose purchase yield is known
+    with_yield: Decimal,
+    /// Sum of purchase yield times money invested over those buys
+    weighted: Decimal,
+    /// Buys without a known purchase yield
+    unknown: usize,
+}
+
+impl EntryYield {
+    fn add(&mut self, buy: &HoldingAdjustment, price: Decimal) {
+        let cost = buy.shares_delta * price;
+        self.buys += 1;
+        self.invested += cost;
+        match buy.purchase_yield {
+            Some(purchase_yield) => {
+                self.with_yield += cost;
+                self.weighted += purchase_yield * cost;
+            }
+            None => self.unknown += 1,
+        }
+    }
+
+    fn merge(&mut self, other: &EntryYield) {
+        self.buys += other.buys;
+        self.invested += other.invested;
+        self.with_yield += other.with_yield;
+        self.weighted += other.weighted;
+        self.unknown += other.unknown;
+    }
+
+    /// Purchase yield averaged over the money invested, if any buy has one
+    fn average(&self) -> Option<Decimal> {
+        (!self.with_yield.is_zero()).then(|| self.weighted / self.with_yield)
+    }
+}
+
+fn yield_cell(entry: &EntryYield) -> Text {
+    match entry.average() {
+        Some(average) => Text::new(format_percent(average, 2), Tone::Highlight),
+        None => "-".into(),
+    }
+}
+
+/// Average purchase yield per position, and for the portfolio year by year
+///
+/// Every buy counts, including shares sold since: the report is about the
+/// yield bought at, not the yield held today.
+fn entry_yield_report(tracker: &DividendTracker, symbol: Option<&str>) -> Report {
+    let title = "Entry Yield";
+    let mut by_symbol: BTreeMap<&str, EntryYield> = BTreeMap::new();
+    let mut by_year: BTreeMap<i32, EntryYield> = BTreeMap::new();
+    let mut total = EntryYield::default();
+    for holding in tracker.holdings.values().filter(|h| symbol.is_none_or(|s| h.symbol == s)) {
+        for buy in holding.adjustments.iter().filter(|a| a.shares_delta > Decimal::ZERO) {
+            let Some(price) = buy.price else { continue };
+            by_symbol.entry(&holding.symbol).or_default().add(buy, price);
+            by_year.entry(buy.date.year()).or_default().add(buy, price);
+            total.add(buy, price);
+        }
+    }
+    if total.buys == 0 {
+        let whose = symbol.map(|s| format!(" of {}", s)).unwrap_or_default();
+        return Report::warning(title, format!("No buys recorded{}. Record one with 'transactions buy'.", whose));
+    }
+
+    let mut positions = TableView::new(&["Symbol", "Buys", "Invested", "Avg Entry Yield"]);
+    for (symbol, entry) in &by_symbol {
+        positions.row(vec![
+            symbol.to_string().into(),
+            entry.buys.to_string().into(),
+            format_money(entry.invested).into(),
+            yield_cell(entry),
+        ]);
+    }
+
+    let mut years = TableView::new(&["Year", "Buys", "Invested", "Avg Entry Yield", "Portfolio to Date"]);
+    let mut to_date = EntryYield::default();
+    for (year, entry) in &by_year {
+        to_date.merge(entry);
+        years.row(vec![
+            year.to_string().into(),
+            entry.buys.to_string().into(),
+            format_money(entry.invested).into(),
+            yield_cell(entry),
+            yield_cell(&to_date),
+        ]);
+    }
+
+    let mut overall = Section::new("Portfolio")
+        .icon("📈")
+        .field("Buys", total.buys.to_string())
+        .field("Invested", format_money(total.invested))
+        .field("Avg Entry Yield", yield_cell(&total))
+        .note(NoteKind::Info, "Averages are weighted by the money invested in each buy.");
+    let unknown = total.unknown;
+    if unknown > 0 {
+        overall = overall.note(
+            NoteKind::Warning,
+            format!(
+                "{} buy{} without a known purchase yield {} left out of the averages; yields are kept for buys recorded with 'transactions buy' while the symbol had a recent dividend or forward dividend.",
+                unknown,
+                if unknown == 1 { "" } else { "s" },
+                if unknown == 1 { "is" } else { "are" }
+            ),
+        );
+    }
+
+    Report::new(title)
+        .section(Section::new("By Position").icon("🎯").table(positions))
+        .section(Section::new("By Year").icon("📅").table(years))
+        .section(overall)
+}
+
+/// Build the ledger of buys and sells, then the dividends that don't match them
+fn transactions_report(tracker: &DividendTracker, symbol: Option<&str>, display: &DisplaySettings) -> Report {
+    let mut holdings: Vec<_> = tracker
+        .holdings
+        .values()
+        .filter(|h| symbol.is_none_or(|s| h.symbol == s))
+        .filter(|h| h.adjustments.iter().any(|a| !a.shares_delta.is_zero()))
+        .collect();
+    if holdings.is_empty() {
+        let whose = symbol.map(|s| format!(" for {}", s)).unwrap_or_default();
+        return Report::warning(
+            TITLE,
+            format!("No transactions recorded{}. Record one with 'transactions buy'.", whose),
+        );
+    }
+    holdings.sort_by_key(|h| h.key());
+
+    let mut report = Report::new(TITLE);
+    for holding in &holdings {
+        let mut table = TableView::new(&["Date", "Trade", "Shares", "Price", "Amount", "Shares Held"]);
+        for adjustment in holding.adjustments.iter().filter(|a| !a.shares_delta.is_zero()) {
+            let (trade, tone) = if adjustment.shares_delta > Decimal::ZERO {
+                ("Buy", Tone::Positive)
+            } else {
+                ("Sell", Tone::Negative)
+            };
+            let shares = adjustment.shares_delta.abs();
+            table.row(vec![
+                adjustment.date.format("%Y-%m-%d").to_string().into(),
+                Text::new(trade, tone),
+                format_shares(shares, display).into(),
+                adjustment.price.map(format_money).unwrap_or_else(|| "-".to_string()).into(),
+                adjustment
+                    .price
+                    .map(|p| format_money(p * shares))
+                    .unwrap_or_else(|| "-".to_string())
+                    .into(),
+                Text::new(format_shares(adjustment.shares_after, display), Tone::Highlight),
+            ]);
+        }
+        report.push(Section::new(holding.label()).table(table));
+    }
+
+    let in_scope = |d: &Dividend| holdings.iter().any(|h| h.symbol == d.symbol && h.key().is_in(d.account_name()));
+    let checked = tracker
+        .dividends
+        .iter()
+        .filter(|d| in_scope(d))
+        .filter(|d| tracker.holding_for(d).is_some_and(|h| h.shares_entitled_on(d.ex_date).is_some()))
+        .count();
+    let mismatches: Vec<_> = tracker
+        .ownership_mismatches()
+        .into_iter()
+        .filter(|(d, _)| in_scope(d))
+        .collect();
+
+    let section = Section::new("Dividends vs. Ownership").icon("🔎");
+    if checked == 0 {
+        return report.section(section.note(
+            NoteKind::Info,
+            "No dividends go ex after the first recorded trade, so none could be checked.",
+        ));
+    }
+    if mismatches.is_empty() {
+        return report.section(section.note(
+            NoteKind::Info,
+            format!(
+                "All {} dividend{} checked match the shares held before the ex-date.",
+                checked,
+                if checked == 1 { "" } else { "s" }
+            ),
+        ));
+    }
+
+    let mut table = TableView::new(&["Symbol", "Ex-Date", "Recorded Shares", "Held Before Ex-Date"]);
+    for (dividend, held) in &mismatches {
+        table.row(vec![
+            dividend.symbol.clone().into(),
+            dividend.ex_date.format("%Y-%m-%d").to_string().into(),
+            Text::new(format_shares(dividend.shares_owned, display), Tone::Warning),
+            format_shares(*held, display).into(),
+        ]);
+    }
+    report.section(section.table(table).note(
+        NoteKind::Warning,
+        format!(
+            "{} of {} dividends checked don't match the shares held. Record any missing trade, or correct the dividend.",
+            mismatches.len(),
+            checked
+        ),
+    ))
+}
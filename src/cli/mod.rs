@@ -0,0 +1,102 @@
+//! The `dividend-tracker` command line
+//!
+//! Everything the binary needs on top of the library: the clap command tree
+//! ([`args`], re-exported here), one handler module per command group
+//! ([`commands`]) and the entry point that ties them together. None of it is
+//! covered by the library's stability promise.
+
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use std::process::ExitCode;
+
+mod args;
+pub mod commands;
+
+pub use args::*;
+
+use crate::api::ApiError;
+use crate::config::Config;
+use crate::securities::Securities;
+use commands::CliConfig;
+
+/// Run the command line and turn the outcome into the process exit code
+pub fn main() -> ExitCode {
+    let Err(error) = run() else {
+        return ExitCode::SUCCESS;
+    };
+    eprintln!("Error: {:?}", error);
+
+    // Provider failures get a hint and an exit code of their own so scripts
+    // can tell a bad key from a rate limit or a network problem. Errors that
+    // wrap one (batch fetch summaries) have already printed their hints.
+    let Some(api_error) = error.downcast_ref::<ApiError>() else {
+        return ExitCode::FAILURE;
+    };
+    if error.chain().count() == 1 {
+        eprintln!("{}", api_error.remediation().yellow());
+    }
+    ExitCode::from(api_error.kind().exit_code())
+}
+
+fn run() -> Result<()> {
+    // Rewrite deprecated command paths before clap sees them
+    let (args, legacy_route) = route_legacy_args(std::env::args().collect());
+    let cli = Cli::parse_from(args);
+
+    // Create global CLI configuration
+    let config = CliConfig {
+        data_dir: cli.data_dir.clone(),
+        verbose: cli.verbose,
+        quiet: cli.quiet,
+        output_format: cli.output_format,
+        table_style: cli.table_style,
+        max_column_width: cli.max_column_width.map(usize::from),
+        staged: None,
+    };
+
+    if let Some(route) = legacy_route {
+        if !config.quiet {
+            eprintln!(
+                "{} '{}' is deprecated, use '{}' instead",
+                "warning:".yellow().bold(),
+                route.legacy,
+                route.replacement.join(" ")
+            );
+        }
+    }
+
+    // Settings that are mistyped or unreadable would otherwise quietly fall back to defaults
+    if !config.quiet && !matches!(cli.command, Some(Commands::Configure(_))) {
+        warn_about_config();
+    }
+
+    // Show verbose information about configuration
+    if config.verbose {
+        config.print_verbose("Starting dividend-tracker with configuration:");
+        if let Ok(data_dir) = config.resolve_data_dir() {
+            config.print_verbose(&format!("Data directory: {}", data_dir.display()));
+        }
+    }
+
+    if cli.rpc {
+        if cli.command.is_some() {
+            anyhow::bail!("--rpc takes commands on stdin and cannot be combined with a subcommand");
+        }
+        return commands::rpc::run(&config);
+    }
+
+    commands::run(cli.command, &config)
+}
+
+/// Warn about a config.toml that doesn't parse or has settings that look wrong
+fn warn_about_config() {
+    let warn = |message: String| eprintln!("{} {}", "warning:".yellow().bold(), message);
+    match Config::load() {
+        Ok(settings) => settings.problems().into_iter().for_each(|p| warn(format!("config.toml: {}", p))),
+        Err(e) => warn(format!("config.toml can't be read: {:#}", e)),
+    }
+    if let Err(e) = Securities::load() {
+        warn(format!("{:#}; no symbol overrides are applied", e));
+    }
+}
@@ -1,21 +1,93 @@
 use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
+use crate::models::Dividend;
+use crate::paths;
+use crate::render::{TableOptions, TableStyle};
+
+/// Comment at the top of a saved config.toml
+const HEADER: &str = "\
+# Dividend Tracker configuration
+#
+# Text settings may refer to environment variables as ${NAME}; the reference
+# is kept when the file is saved again. Edit with `dividend-tracker configure edit`.
+";
+
+/// Order of the sections in a saved config.toml
+const SECTIONS: [&str; 8] = ["api", "cache", "display", "backup", "analysis", "classification", "accounts", "storage"];
+
+/// Comment written above each setting: section, key, comment and, for a
+/// setting that is unset by default, an example written commented out
+const SETTINGS: &[(&str, &str, &str, Option<&str>)] = &[
+    ("api", "alpha_vantage_key", "Alpha Vantage key for fetch, news and calendar --update", Some("\"${ALPHA_VANTAGE_API_KEY}\"")),
+    ("api", "rate_limit_delay_ms", "Wait between API calls in milliseconds (the free tier allows 5 calls a minute)", None),
+    ("api", "max_retries", "Retries after a timeout, connection failure, server error or rate limit", None),
+    ("api", "timeout_seconds", "Seconds to wait for an API response", None),
+    ("api", "retry_backoff_ms", "Wait before the first retry in milliseconds, doubled for each retry after", None),
+    ("api", "proxy", "Proxy for API requests; overrides HTTP_PROXY and HTTPS_PROXY", Some("\"http://proxy.example.com:8080\"")),
+    ("cache", "enabled", "Keep summary and projection results between runs", None),
+    ("cache", "ttl_hours", "Hours cached API responses stay fresh", None),
+    ("cache", "max_size_mb", "Size cap for cached API responses in megabytes", None),
+    ("display", "share_precision", "Decimal places shown for share quantities", None),
+    ("display", "share_rounding", "\"half-up\", \"half-even\" or \"truncate\"", None),
+    ("display", "table_style", "\"rounded\", \"ascii\", \"markdown\" or \"compact\"", None),
+    ("display", "max_column_width", "Cut table cells longer than this many characters", Some("24")),
+    ("backup", "compression", "\"gzip\" or \"none\"", None),
+    ("backup", "max_count", "Backups kept per data file", None),
+    ("backup", "max_age_days", "Remove backups older than this many days", Some("90")),
+    ("backup", "max_total_size_mb", "Cap on all backups together in megabytes, oldest removed first", Some("50")),
+    ("analysis", "month_drift_days", "Days either side of a month boundary a payment may drift into its usual month", None),
+    ("analysis", "default_pay_lag_days", "Ex-date to pay date for symbols without recorded payments", None),
+    ("analysis", "date_basis", "\"ex-date\" or \"pay-date\" (how the tax commands count years) for the years and months of summary", None),
+    ("classification", "auto", "Classify new dividends left unknown from the kind of security: REITs, municipal funds, foreign listings", None),
+    ("classification", "reits", "Symbols treated as REITs (non-qualified) besides the built-in list", Some("[\"WSR\", \"GOOD\"]")),
+    ("classification", "municipal_funds", "Symbols treated as municipal bond funds (tax-free) besides the built-in list", Some("[\"BKN\"]")),
+    ("accounts", "traditional", "Tax-deferred accounts (traditional IRA, 401(k)); their dividends are left out of estimated tax", Some("[\"IRA\", \"401k\"]")),
+    ("accounts", "roth", "Roth accounts; their dividends are left out of estimated tax", Some("[\"Roth IRA\"]")),
+    ("storage", "data_dir", "Data directory used when neither --data-dir nor DIVIDEND_TRACKER_DATA_DIR is set", Some("\"/path/to/data\"")),
+];
+
 /// Application configuration
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub api: ApiSettings,
     pub cache: CacheSettings,
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+    #[serde(default)]
+    pub analysis: AnalysisSettings,
+    #[serde(default)]
+    pub classification: ClassificationSettings,
+    #[serde(default)]
+    pub accounts: AccountSettings,
+    #[serde(default)]
+    pub storage: StorageSettings,
+    /// Settings as written in the file, before `${NAME}` references were
+    /// filled in, so saving keeps the references
+    #[serde(skip)]
+    source: Option<toml::Table>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ApiSettings {
     pub alpha_vantage_key: Option<String>,
     pub rate_limit_delay_ms: u64,
+    /// Times a request is retried after a timeout, connection failure,
+    /// server error or rate limit
     pub max_retries: u32,
     pub timeout_seconds: u64,
+    /// Wait before the first retry in milliseconds, doubled for each retry after
+    pub retry_backoff_ms: u64,
+    /// Proxy URL for API requests; overrides HTTP_PROXY and HTTPS_PROXY
+    pub proxy: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,20 +97,252 @@ pub struct CacheSettings {
     pub max_size_mb: u32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    /// Decimal places shown for share quantities (fractional DRIP shares)
+    pub share_precision: u32,
+    /// Rounding policy applied when shares are displayed or exported
+    pub share_rounding: RoundingPolicy,
+    /// Border style of terminal tables
+    #[serde(default)]
+    pub table_style: TableStyle,
+    /// Cut table cells longer than this many characters (unset shows them in full)
+    #[serde(default)]
+    pub max_column_width: Option<usize>,
+}
+
+impl DisplaySettings {
+    /// Table layout from these settings, with command-line overrides applied
+    pub fn table_options(&self, style: Option<TableStyle>, max_column_width: Option<usize>) -> TableOptions {
+        TableOptions {
+            style: style.unwrap_or(self.table_style),
+            max_column_width: max_column_width.or(self.max_column_width),
+        }
+    }
+}
+
+/// Rounding policy for fractional share quantities
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingPolicy {
+    /// Round half away from zero (0.00005 -> 0.0001)
+    HalfUp,
+    /// Banker's rounding (round half to even)
+    HalfEven,
+    /// Drop extra digits without rounding
+    Truncate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Compression applied to new backup files
+    pub compression: BackupCompression,
+    /// Backups kept per data file
+    pub max_count: usize,
+    /// Remove backups older than this many days (unset keeps them regardless of age)
+    pub max_age_days: Option<u32>,
+    /// Cap on the combined size of all backups in megabytes (unset is unlimited)
+    pub max_total_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisSettings {
+    /// Payments this many days either side of a month boundary count towards
+    /// the neighbouring month when the symbol usually pays in that month; 0,
+    /// the default, buckets payments strictly by date
+    pub month_drift_days: u32,
+    /// Days from ex-date to pay date assumed for a symbol with no recorded
+    /// payments when the data provider gives no pay date
+    pub default_pay_lag_days: u32,
+    /// Date that places a payment in a year and month for `summary`
+    pub date_basis: DateBasis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClassificationSettings {
+    /// Classify dividends added without a classification from the kind of
+    /// security; `tax classify` still changes them
+    pub auto: bool,
+    /// Symbols treated as REITs besides the built-in list
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reits: Vec<String>,
+    /// Symbols treated as municipal bond funds besides the built-in list
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub municipal_funds: Vec<String>,
+}
+
+/// Which date of a dividend the date filters look at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateBasis {
+    /// The ex-dividend date, used for listings and analytics
+    #[default]
+    ExDate,
+    /// The payment date, used for tax years
+    PayDate,
+}
+
+impl DateBasis {
+    pub fn date_of(self, dividend: &Dividend) -> NaiveDate {
+        match self {
+            DateBasis::ExDate => dividend.ex_date,
+            DateBasis::PayDate => dividend.pay_date,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateBasis::ExDate => "ex-date",
+            DateBasis::PayDate => "pay date",
+        }
+    }
+}
+
+/// Which accounts are retirement accounts; any other account is taxable
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountSettings {
+    /// Traditional IRAs, 401(k)s and other tax-deferred accounts
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub traditional: Vec<String>,
+    /// Roth IRAs and other accounts whose income is never taxed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roth: Vec<String>,
+}
+
+impl AccountSettings {
+    /// Tax treatment of an account, by name ignoring case
+    pub fn kind(&self, account: &str) -> AccountKind {
+        let listed = |names: &[String]| names.iter().any(|name| name.trim().eq_ignore_ascii_case(account.trim()));
+        if listed(&self.traditional) {
+            AccountKind::Traditional
+        } else if listed(&self.roth) {
+            AccountKind::Roth
+        } else {
+            AccountKind::Taxable
+        }
+    }
+}
+
+/// How an account's dividends are taxed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountKind {
+    /// Brokerage account; dividends are taxed the year they are paid
+    Taxable,
+    /// Tax-deferred: taxed as ordinary income when withdrawn
+    Traditional,
+    /// Never taxed
+    Roth,
+}
+
+impl AccountKind {
+    /// Whether dividends paid into the account are left out of the year's tax
+    pub fn is_retirement(self) -> bool {
+        self != AccountKind::Taxable
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AccountKind::Taxable => "Taxable",
+            AccountKind::Traditional => "Traditional IRA",
+            AccountKind::Roth => "Roth",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageSettings {
+    /// Data directory chosen during `init`, used when neither `--data-dir`
+    /// nor `DIVIDEND_TRACKER_DATA_DIR` is given
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Compression format for backup files
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupCompression {
+    /// Plain JSON copies (`.bak`)
+    None,
+    /// Gzip-compressed copies (`.bak.gz`)
+    Gzip,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings {
+            compression: BackupCompression::Gzip,
+            max_count: 10,
+            max_age_days: None,
+            max_total_size_mb: None,
+        }
+    }
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        ApiSettings {
+            alpha_vantage_key: None,
+            rate_limit_delay_ms: 12000, // 5 calls per minute
+            max_retries: 3,
+            timeout_seconds: 30,
+            retry_backoff_ms: 5000,
+            proxy: None,
+        }
+    }
+}
+
+impl Default for AnalysisSettings {
+    fn default() -> Self {
+        AnalysisSettings {
+            month_drift_days: 0,
+            default_pay_lag_days: 14,
+            date_basis: DateBasis::ExDate,
+        }
+    }
+}
+
+impl Default for ClassificationSettings {
+    fn default() -> Self {
+        ClassificationSettings {
+            auto: true,
+            reits: Vec::new(),
+            municipal_funds: Vec::new(),
+        }
+    }
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            share_precision: 4,
+            share_rounding: RoundingPolicy::HalfUp,
+            table_style: TableStyle::default(),
+            max_column_width: None,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
-            api: ApiSettings {
-                alpha_vantage_key: None,
-                rate_limit_delay_ms: 12000, // 5 calls per minute
-                max_retries: 3,
-                timeout_seconds: 30,
-            },
+            api: ApiSettings::default(),
             cache: CacheSettings {
                 enabled: true,
                 ttl_hours: 24,
                 max_size_mb: 100,
             },
+            display: DisplaySettings::default(),
+            backup: BackupSettings::default(),
+            analysis: AnalysisSettings::default(),
+            classification: ClassificationSettings::default(),
+            accounts: AccountSettings::default(),
+            storage: StorageSettings::default(),
+            source: None,
         }
     }
 }
@@ -46,11 +350,7 @@ impl Default for Config {
 impl Config {
     /// Get the configuration directory path
     pub fn config_dir() -> Result<PathBuf> {
-        let dir = dirs::config_dir()
-            .ok_or_else(|| anyhow!("Could not determine config directory"))?
-            .join("dividend-tracker");
-
-        Ok(dir)
+        paths::config_dir()
     }
 
     /// Get the configuration file path
@@ -64,32 +364,112 @@ impl Config {
 
         if config_file.exists() {
             let contents = fs::read_to_string(&config_file)?;
-            let config: Config = toml::from_str(&contents)?;
-            Ok(config)
+            Self::parse(&contents)
         } else {
             let mut config = Config::default();
 
-            // Check environment variable for API key
+            // Check environment variable for API key, saving only a reference to it
             if let Ok(api_key) = std::env::var("ALPHA_VANTAGE_API_KEY") {
                 config.api.alpha_vantage_key = Some(api_key);
+                config.source = Some(toml::from_str("api.alpha_vantage_key = \"${ALPHA_VANTAGE_API_KEY}\"")?);
             }
 
             Ok(config)
         }
     }
 
+    /// Parse the contents of a config file, filling in `${NAME}` references
+    /// from the environment
+    pub fn parse(contents: &str) -> Result<Self> {
+        // Parsing straight into settings first reports mistakes with line numbers
+        toml::from_str::<Config>(contents)?;
+
+        let source: toml::Table = toml::from_str(contents)?;
+        let mut value = toml::Value::Table(source.clone());
+        interpolate_value(&mut value)?;
+        let mut config: Config = value.try_into()?;
+        config.source = Some(source);
+        Ok(config)
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
         let config_dir = Self::config_dir()?;
         fs::create_dir_all(&config_dir)?;
 
         let config_file = Self::config_file()?;
-        let contents = toml::to_string_pretty(&self)?;
-        fs::write(config_file, contents)?;
+        fs::write(config_file, self.to_commented_toml()?)?;
 
         Ok(())
     }
 
+    /// The settings as config.toml, with a comment above each and references
+    /// to environment variables kept where the values still match
+    pub fn to_commented_toml(&self) -> Result<String> {
+        let mut value = toml::Value::try_from(self)?;
+        if let Some(source) = &self.source {
+            keep_references(&mut value, &toml::Value::Table(source.clone()));
+        }
+        let toml::Value::Table(sections) = value else {
+            return Err(anyhow!("Configuration did not serialize to a table"));
+        };
+
+        let mut names: Vec<&String> = sections.keys().collect();
+        names.sort_by_key(|name| SECTIONS.iter().position(|s| s == name).unwrap_or(SECTIONS.len()));
+
+        let mut out = HEADER.to_string();
+        for name in names {
+            let toml::Value::Table(settings) = &sections[name] else {
+                continue;
+            };
+            out.push_str(&format!("\n[{}]\n", name));
+            for (_, key, comment, example) in SETTINGS.iter().filter(|(section, ..)| section == name) {
+                out.push_str(&format!("# {}\n", comment));
+                match (settings.get(*key), example) {
+                    (Some(value), _) => out.push_str(&format!("{} = {}\n", key, value)),
+                    (None, Some(example)) => out.push_str(&format!("# {} = {}\n", key, example)),
+                    (None, None) => {}
+                }
+            }
+            for (key, value) in settings {
+                if !SETTINGS.iter().any(|(section, k, ..)| section == name && k == key) {
+                    out.push_str(&format!("{} = {}\n", key, value));
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Settings that parse but look wrong: keys that aren't settings (and so
+    /// are ignored) and values outside their sensible range
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = self.source.as_ref().map(unknown_keys).unwrap_or_default();
+
+        let ranges: [(&str, u64, RangeInclusive<u64>); 9] = [
+            ("api.rate_limit_delay_ms", self.api.rate_limit_delay_ms, 0..=600_000),
+            ("api.max_retries", self.api.max_retries.into(), 0..=10),
+            ("api.timeout_seconds", self.api.timeout_seconds, 1..=600),
+            ("api.retry_backoff_ms", self.api.retry_backoff_ms, 0..=60_000),
+            ("cache.ttl_hours", self.cache.ttl_hours.into(), 1..=8760),
+            ("display.share_precision", self.display.share_precision.into(), 0..=10),
+            ("backup.max_count", self.backup.max_count as u64, 1..=1000),
+            ("analysis.month_drift_days", self.analysis.month_drift_days.into(), 0..=15),
+            ("analysis.default_pay_lag_days", self.analysis.default_pay_lag_days.into(), 0..=120),
+        ];
+        for (key, value, range) in ranges {
+            if !range.contains(&value) {
+                problems.push(format!("{} is {}; use {} to {}", key, value, range.start(), range.end()));
+            }
+        }
+
+        if let Some(proxy) = &self.api.proxy {
+            if let Err(e) = reqwest::Url::parse(proxy) {
+                problems.push(format!("api.proxy \"{}\" is not a URL: {}", proxy, e));
+            }
+        }
+        problems
+    }
+
     /// Get the Alpha Vantage API key
     pub fn get_api_key(&self) -> Result<String> {
         // First check config file
@@ -110,6 +490,117 @@ impl Config {
     }
 }
 
+/// Sections and settings in a config file that aren't ones this version reads
+fn unknown_keys(source: &toml::Table) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (section, value) in source {
+        if !SECTIONS.contains(&section.as_str()) {
+            let hint = closest(section, SECTIONS.iter().copied())
+                .map(|s| format!(" (did you mean [{}]?)", s))
+                .unwrap_or_default();
+            problems.push(format!("[{}] is not a section and is ignored{}", section, hint));
+            continue;
+        }
+        let toml::Value::Table(settings) = value else {
+            problems.push(format!("{} should be a [{}] section", section, section));
+            continue;
+        };
+        let known = || SETTINGS.iter().filter(|(s, ..)| s == section).map(|(_, key, ..)| *key);
+        for key in settings.keys().filter(|key| !known().any(|k| k == key.as_str())) {
+            let hint = closest(key, known())
+                .map(|k| format!(" (did you mean {}?)", k))
+                .unwrap_or_default();
+            problems.push(format!("{}.{} is not a setting and is ignored{}", section, key, hint));
+        }
+    }
+    problems
+}
+
+/// The candidate closest to `word`, for suggesting what a typo meant
+///
+/// Longer words may be further off: up to a quarter of their letters, and at
+/// least two.
+fn closest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (word.chars().count() / 4).max(2);
+    candidates
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two words
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Replace each `${NAME}` in a setting with the environment variable's value
+fn interpolate(text: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow!("Unclosed '${{' in setting \"{}\"", text))?;
+        let name = &after[..end];
+        let value = std::env::var(name)
+            .map_err(|_| anyhow!("Setting \"{}\" refers to ${{{}}}, which is not set", text, name))?;
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn interpolate_value(value: &mut toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(text) => *text = interpolate(text)?,
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate_value(value)?;
+            }
+        }
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate_value(value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Put back references from `source` whose value is still what they resolve to
+fn keep_references(value: &mut toml::Value, source: &toml::Value) {
+    match (value, source) {
+        (toml::Value::String(text), toml::Value::String(reference))
+            if reference.contains("${") && interpolate(reference).is_ok_and(|resolved| resolved == *text) =>
+        {
+            *text = reference.clone();
+        }
+        (toml::Value::Table(table), toml::Value::Table(source)) => {
+            for (key, value) in table.iter_mut() {
+                if let Some(source) = source.get(key) {
+                    keep_references(value, source);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Initialize configuration for first-time setup
 pub fn init_config() -> Result<()> {
     let config = Config::default();
@@ -121,3 +612,101 @@ pub fn init_config() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_settings_missing_retention_uses_defaults() {
+        let settings: BackupSettings = toml::from_str("compression = \"none\"").unwrap();
+
+        assert_eq!(settings.compression, BackupCompression::None);
+        assert_eq!(settings.max_count, 10);
+        assert_eq!(settings.max_age_days, None);
+        assert_eq!(settings.max_total_size_mb, None);
+    }
+
+    #[test]
+    fn test_api_settings_missing_network_options_use_defaults() {
+        let settings: ApiSettings =
+            toml::from_str("alpha_vantage_key = \"demo\"\nrate_limit_delay_ms = 1000\nmax_retries = 1").unwrap();
+
+        assert_eq!(settings.rate_limit_delay_ms, 1000);
+        assert_eq!(settings.max_retries, 1);
+        assert_eq!(settings.timeout_seconds, 30);
+        assert_eq!(settings.retry_backoff_ms, 5000);
+        assert_eq!(settings.proxy, None);
+    }
+
+    #[test]
+    fn test_commented_defaults_parse_back_to_defaults() {
+        let text = Config::default().to_commented_toml().unwrap();
+        assert!(text.contains("# Seconds to wait for an API response\ntimeout_seconds = 30\n"));
+        assert!(text.contains("# proxy = \"http://proxy.example.com:8080\"\n"));
+        assert!(text.find("[api]").unwrap() < text.find("[storage]").unwrap());
+
+        let parsed = Config::parse(&text).unwrap();
+        assert_eq!(parsed.api.timeout_seconds, 30);
+        assert_eq!(parsed.api.proxy, None);
+        assert_eq!(parsed.backup.max_count, 10);
+        assert_eq!(parsed.display.table_options(None, None), TableOptions::default());
+    }
+
+    #[test]
+    fn test_environment_references_are_filled_in_and_kept_on_save() {
+        std::env::set_var("DIVIDEND_TRACKER_TEST_PROXY_HOST", "proxy.internal");
+        let text = "[api]\nproxy = \"http://${DIVIDEND_TRACKER_TEST_PROXY_HOST}:3128\"\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n";
+
+        let mut config = Config::parse(text).unwrap();
+        assert_eq!(config.api.proxy.as_deref(), Some("http://proxy.internal:3128"));
+        assert!(config.to_commented_toml().unwrap().contains("proxy = \"http://${DIVIDEND_TRACKER_TEST_PROXY_HOST}:3128\""));
+
+        // A changed value replaces the reference
+        config.api.proxy = Some("http://other:8080".to_string());
+        assert!(config.to_commented_toml().unwrap().contains("proxy = \"http://other:8080\""));
+
+        let missing = Config::parse(&text.replace("PROXY_HOST", "UNSET_VARIABLE")).unwrap_err();
+        assert!(missing.to_string().contains("${DIVIDEND_TRACKER_TEST_UNSET_VARIABLE}, which is not set"));
+        assert!(interpolate("${UNCLOSED").is_err());
+        assert_eq!(interpolate("no references $HOME").unwrap(), "no references $HOME");
+    }
+
+    #[test]
+    fn test_problems_catch_typos_and_out_of_range_values() {
+        let text = "[api]\ntimeout_second = 10\nmax_retries = 50\nproxy = \"not a url\"\n\n\
+                    [cache]\nenabled = true\nttl_hours = 0\nmax_size_mb = 100\n\n[dispaly]\nshare_precision = 2\n";
+        let problems = Config::parse(text).unwrap().problems();
+
+        assert_eq!(
+            problems,
+            vec![
+                "api.timeout_second is not a setting and is ignored (did you mean timeout_seconds?)",
+                "[dispaly] is not a section and is ignored (did you mean [display]?)",
+                "api.max_retries is 50; use 0 to 10",
+                "cache.ttl_hours is 0; use 1 to 8760",
+                "api.proxy \"not a url\" is not a URL: relative URL without a base",
+            ]
+        );
+        assert!(Config::default().problems().is_empty());
+        assert!(Config::parse(&Config::default().to_commented_toml().unwrap()).unwrap().problems().is_empty());
+    }
+
+    #[test]
+    fn test_table_options_prefer_command_line() {
+        let display: DisplaySettings = toml::from_str(
+            "share_precision = 4\nshare_rounding = \"half-up\"\ntable_style = \"ascii\"\nmax_column_width = 30",
+        )
+        .unwrap();
+        let options = display.table_options(None, None);
+        assert_eq!(options.style, TableStyle::Ascii);
+        assert_eq!(options.max_column_width, Some(30));
+
+        let options = display.table_options(Some(TableStyle::Markdown), Some(12));
+        assert_eq!(options.style, TableStyle::Markdown);
+        assert_eq!(options.max_column_width, Some(12));
+
+        let older: DisplaySettings = toml::from_str("share_precision = 2\nshare_rounding = \"truncate\"").unwrap();
+        assert_eq!(older.table_options(None, None), TableOptions::default());
+    }
+}
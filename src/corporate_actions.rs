@@ -0,0 +1,397 @@
+//! Corporate actions: splits, symbol changes and special dividends
+//!
+//! A feed is a CSV file (or URL) with one action per row, or a JSON array of
+//! actions as stored in the data. Applying an action updates the holding and
+//! the dividend history the way the corresponding manual edits would, and is
+//! recorded so that re-ingesting the same feed changes nothing.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::fs;
+use std::str::FromStr;
+
+use crate::models::{Dividend, DividendTracker, DividendType, Holding, HoldingAdjustment, DEFAULT_ACCOUNT};
+use crate::symbols::normalize_symbol;
+
+/// Decimal places kept for split-adjusted per-share amounts
+const PER_SHARE_DP: u32 = 6;
+
+/// One corporate action from a feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum CorporateAction {
+    /// `ratio` new shares for each old share (0.1 for a 1:10 reverse split)
+    Split {
+        symbol: String,
+        date: NaiveDate,
+        ratio: Decimal,
+    },
+    /// The security trades under `new_symbol` from `date`
+    SymbolChange {
+        symbol: String,
+        date: NaiveDate,
+        new_symbol: String,
+    },
+    /// A one-off cash dividend per share
+    SpecialDividend {
+        symbol: String,
+        ex_date: NaiveDate,
+        pay_date: NaiveDate,
+        amount: Decimal,
+    },
+}
+
+/// What applying an action did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Applied,
+    /// The action is already in the data
+    AlreadyApplied,
+    /// Nothing is recorded for the symbol
+    NotHeld,
+}
+
+impl CorporateAction {
+    pub fn symbol(&self) -> &str {
+        match self {
+            CorporateAction::Split { symbol, .. }
+            | CorporateAction::SymbolChange { symbol, .. }
+            | CorporateAction::SpecialDividend { symbol, .. } => symbol,
+        }
+    }
+
+    /// Effective date; the ex-date for special dividends
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            CorporateAction::Split { date, .. } | CorporateAction::SymbolChange { date, .. } => *date,
+            CorporateAction::SpecialDividend { ex_date, .. } => *ex_date,
+        }
+    }
+}
+
+impl Display for CorporateAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorporateAction::Split { symbol, date, ratio } => {
+                write!(f, "{} split {} on {}", symbol, format_ratio(*ratio), date)
+            }
+            CorporateAction::SymbolChange {
+                symbol,
+                date,
+                new_symbol,
+            } => write!(f, "{} renamed to {} on {}", symbol, new_symbol, date),
+            CorporateAction::SpecialDividend {
+                symbol,
+                ex_date,
+                amount,
+                ..
+            } => write!(f, "{} special dividend ${} ex {}", symbol, amount, ex_date),
+        }
+    }
+}
+
+/// `2` as `2:1`, `0.1` as `1:10`
+fn format_ratio(ratio: Decimal) -> String {
+    if ratio >= Decimal::ONE {
+        format!("{}:1", ratio.normalize())
+    } else {
+        format!("1:{}", (Decimal::ONE / ratio).round_dp(4).normalize())
+    }
+}
+
+/// Parse a split ratio written as `4:1`, `1:10`, `4-for-1` or a plain factor
+fn parse_ratio(text: &str) -> Result<Decimal> {
+    let text = text.trim().to_lowercase();
+    let parts: Vec<&str> = text.split([':', '/']).flat_map(|p| p.split("-for-")).collect();
+    let ratio = match parts.as_slice() {
+        [factor] => Decimal::from_str(factor.trim())?,
+        [new, old] => {
+            let old = Decimal::from_str(old.trim())?;
+            if old.is_zero() {
+                bail!("invalid split ratio '{}'", text);
+            }
+            Decimal::from_str(new.trim())? / old
+        }
+        _ => bail!("invalid split ratio '{}'", text),
+    };
+    if ratio <= Decimal::ZERO {
+        bail!("split ratio must be positive");
+    }
+    Ok(ratio)
+}
+
+/// Read a feed from a file path or an http(s) URL
+pub fn load_feed(source: &str) -> Result<Vec<CorporateAction>> {
+    let contents = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::blocking::get(source)
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .with_context(|| format!("Failed to download {}", source))?
+    } else {
+        fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))?
+    };
+    parse_feed(&contents).with_context(|| format!("Invalid corporate actions feed {}", source))
+}
+
+/// Parse a JSON array of actions, or CSV with the columns
+/// `action,symbol,date,ratio,new_symbol,amount,pay_date`
+///
+/// Only the columns an action needs have to be filled in; `date` is the
+/// ex-date of a special dividend.
+pub fn parse_feed(contents: &str) -> Result<Vec<CorporateAction>> {
+    if contents.trim_start().starts_with('[') {
+        let actions: Vec<CorporateAction> = serde_json::from_str(contents)?;
+        return Ok(actions.into_iter().map(normalized).collect());
+    }
+
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(contents.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_lowercase()).collect();
+    let mut actions = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 2;
+        let record = record?;
+        let field = |name: &str| -> Result<&str> {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .and_then(|i| record.get(i))
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| anyhow!("Line {}: missing {}", line, name))
+        };
+        let date = |name: &str| -> Result<NaiveDate> {
+            let value = field(name)?;
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| anyhow!("Line {}: invalid {} '{}' (expected YYYY-MM-DD)", line, name, value))
+        };
+
+        let symbol = normalize_symbol(field("symbol")?);
+        let action = match field("action")?.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "split" | "reverse_split" => CorporateAction::Split {
+                symbol,
+                date: date("date")?,
+                ratio: parse_ratio(field("ratio")?).with_context(|| format!("Line {}", line))?,
+            },
+            "symbol_change" | "rename" => CorporateAction::SymbolChange {
+                symbol,
+                date: date("date")?,
+                new_symbol: normalize_symbol(field("new_symbol")?),
+            },
+            "special_dividend" => CorporateAction::SpecialDividend {
+                symbol,
+                ex_date: date("date")?,
+                pay_date: date("pay_date")?,
+                amount: Decimal::from_str(field("amount")?)
+                    .ok()
+                    .filter(|a| *a > Decimal::ZERO)
+                    .ok_or_else(|| anyhow!("Line {}: amount must be a positive number", line))?,
+            },
+            other => bail!(
+                "Line {}: unknown action '{}' (expected split, symbol_change or special_dividend)",
+                line,
+                other
+            ),
+        };
+        actions.push(action);
+    }
+    Ok(actions)
+}
+
+fn normalized(action: CorporateAction) -> CorporateAction {
+    match action {
+        CorporateAction::Split { symbol, date, ratio } => CorporateAction::Split {
+            symbol: normalize_symbol(&symbol),
+            date,
+            ratio,
+        },
+        CorporateAction::SymbolChange {
+            symbol,
+            date,
+            new_symbol,
+        } => CorporateAction::SymbolChange {
+            symbol: normalize_symbol(&symbol),
+            date,
+            new_symbol: normalize_symbol(&new_symbol),
+        },
+        CorporateAction::SpecialDividend {
+            symbol,
+            ex_date,
+            pay_date,
+            amount,
+        } => CorporateAction::SpecialDividend {
+            symbol: normalize_symbol(&symbol),
+            ex_date,
+            pay_date,
+            amount,
+        },
+    }
+}
+
+/// Apply one action to the holding and dividend history of its symbol
+///
+/// Splits scale the shares and cost basis of the symbol's holdings and
+/// split-adjust earlier dividends (same totals, more shares at a lower amount
+/// per share). Symbol changes move every record to the new symbol and keep
+/// the old one as an alias. Special dividends are recorded on the shares
+/// currently held, once for each account holding the symbol.
+pub fn apply(tracker: &mut DividendTracker, action: &CorporateAction) -> Result<Outcome> {
+    if tracker.corporate_actions.contains(action) {
+        return Ok(Outcome::AlreadyApplied);
+    }
+    let symbol = tracker.canonical_symbol(action.symbol());
+    let held = tracker.holds(&symbol);
+    if !held && tracker.get_dividends_for_symbol(&symbol).is_empty() {
+        return Ok(Outcome::NotHeld);
+    }
+
+    match action {
+        CorporateAction::Split { date, ratio, .. } => {
+            for holding in tracker.holdings.values_mut().filter(|h| h.symbol == symbol) {
+                let shares_after = holding.shares * ratio;
+                let avg_cost_after = holding.avg_cost_basis.map(|cost| (cost / ratio).round_dp(4));
+                holding.adjustments.push(HoldingAdjustment {
+                    date: *date,
+                    shares_delta: shares_after - holding.shares,
+                    price: None,
+                    shares_after,
+                    avg_cost_after,
+                    purchase_yield: None,
+                });
+                holding.shares = shares_after;
+                holding.avg_cost_basis = avg_cost_after;
+                if let Some(forward) = holding.forward_dividend.as_mut() {
+                    forward.annual_amount = (forward.annual_amount / ratio).round_dp(PER_SHARE_DP);
+                }
+                for lot in holding.lots.iter_mut() {
+                    lot.shares *= ratio;
+                    lot.price = (lot.price / ratio).round_dp(4);
+                }
+            }
+            for dividend in tracker
+                .dividends
+                .iter_mut()
+                .filter(|d| d.symbol == symbol && d.ex_date < *date)
+            {
+                dividend.shares_owned *= ratio;
+                dividend.amount_per_share = (dividend.amount_per_share / ratio).round_dp(PER_SHARE_DP);
+            }
+        }
+        CorporateAction::SymbolChange { new_symbol, .. } => {
+            tracker.add_symbol_alias(&symbol, new_symbol)?;
+        }
+        CorporateAction::SpecialDividend {
+            ex_date,
+            pay_date,
+            amount,
+            ..
+        } => {
+            let holdings = tracker.holdings_of(&symbol);
+            if holdings.is_empty() {
+                return Ok(if tracker.has_duplicate(&symbol, *ex_date, DEFAULT_ACCOUNT) {
+                    Outcome::AlreadyApplied
+                } else {
+                    Outcome::NotHeld
+                });
+            }
+            let (recorded, positions): (Vec<&Holding>, Vec<&Holding>) = holdings
+                .into_iter()
+                .partition(|h| tracker.has_duplicate(&symbol, *ex_date, h.account_name()));
+            let positions: Vec<(Decimal, Option<String>)> = positions
+                .into_iter()
+                .filter(|h| h.shares > Decimal::ZERO)
+                .map(|h| (h.shares, h.account.clone()))
+                .collect();
+            if positions.is_empty() {
+                return Ok(if recorded.is_empty() { Outcome::NotHeld } else { Outcome::AlreadyApplied });
+            }
+            for (shares, account) in positions {
+                let mut dividend = Dividend::new(
+                    symbol.clone(),
+                    None,
+                    *ex_date,
+                    *pay_date,
+                    *amount,
+                    shares,
+                    DividendType::Special,
+                )?;
+                dividend.account = account;
+                tracker.add_dividend(dividend);
+            }
+        }
+    }
+
+    tracker.corporate_actions.push(action.clone());
+    Ok(Outcome::Applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_feed_reads_csv_and_json() {
+        let csv = "action,symbol,date,ratio,new_symbol,amount,pay_date\n\
+                   split,aapl,2020-08-31,4:1,,,\n\
+                   reverse split,GE,2021-08-02,1-for-8,,,\n\
+                   symbol_change,FB,2022-06-09,,META,,\n\
+                   special_dividend,COST,2023-12-28,,,15.00,2024-01-12\n";
+        let actions = parse_feed(csv).unwrap();
+        assert_eq!(
+            actions[0],
+            CorporateAction::Split {
+                symbol: "AAPL".into(),
+                date: date("2020-08-31"),
+                ratio: dec!(4)
+            }
+        );
+        assert_eq!(actions[1].to_string(), "GE split 1:8 on 2021-08-02");
+        assert_eq!(actions[2].to_string(), "FB renamed to META on 2022-06-09");
+        assert_eq!(actions[3].to_string(), "COST special dividend $15.00 ex 2023-12-28");
+
+        let json = serde_json::to_string(&actions).unwrap();
+        assert_eq!(parse_feed(&json).unwrap(), actions);
+
+        let error = parse_feed("action,symbol,date\nmerger,X,2024-01-01\n").unwrap_err();
+        assert!(error.to_string().starts_with("Line 2: unknown action 'merger'"));
+    }
+
+    #[test]
+    fn test_apply_split_adjusts_holding_and_history_once() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("AAPL".into(), dec!(10), Some(dec!(400)), None).unwrap());
+        tracker.add_dividend(
+            Dividend::new("AAPL".into(), None, date("2020-08-07"), date("2020-08-13"), dec!(0.82), dec!(10), DividendType::Regular)
+                .unwrap(),
+        );
+        let split = CorporateAction::Split {
+            symbol: "AAPL".into(),
+            date: date("2020-08-31"),
+            ratio: dec!(4),
+        };
+
+        assert_eq!(apply(&mut tracker, &split).unwrap(), Outcome::Applied);
+        let holding = tracker.holding("AAPL", DEFAULT_ACCOUNT).unwrap();
+        assert_eq!((holding.shares, holding.avg_cost_basis), (dec!(40), Some(dec!(100))));
+        assert_eq!(holding.adjustments[0].shares_delta, dec!(30));
+        let dividend = &tracker.dividends[0];
+        assert_eq!((dividend.shares_owned, dividend.amount_per_share), (dec!(40), dec!(0.205)));
+        assert_eq!(dividend.total_amount, dec!(8.20));
+
+        assert_eq!(apply(&mut tracker, &split).unwrap(), Outcome::AlreadyApplied);
+        assert_eq!(tracker.holding("AAPL", DEFAULT_ACCOUNT).unwrap().shares, dec!(40));
+
+        let other = CorporateAction::Split {
+            symbol: "TSLA".into(),
+            date: date("2022-08-25"),
+            ratio: dec!(3),
+        };
+        assert_eq!(apply(&mut tracker, &other).unwrap(), Outcome::NotHeld);
+    }
+}
@@ -0,0 +1,306 @@
+//! Comparing two datasets record by record
+//!
+//! Dividends are matched on symbol and ex-date, holdings on symbol. A symbol
+//! recorded twice for the same ex-date (added with `--force`) is paired up in
+//! the order the records were stored, so an extra duplicate shows as added
+//! rather than turning every pair into a change.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use crate::config::DisplaySettings;
+use crate::format::{format_money, format_shares};
+use crate::models::{Dividend, DividendTracker, Holding};
+use crate::render::{NoteKind, Report, Section, TableView, Text, Tone};
+
+const TITLE: &str = "Data Diff";
+
+/// A record present in both datasets with different contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changed<'a, T> {
+    pub before: &'a T,
+    pub after: &'a T,
+    pub changes: Vec<FieldChange>,
+}
+
+/// One field that differs between two versions of a record
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+/// Differences between a base dataset and the current one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatasetDiff<'a> {
+    pub added_dividends: Vec<&'a Dividend>,
+    pub removed_dividends: Vec<&'a Dividend>,
+    pub changed_dividends: Vec<Changed<'a, Dividend>>,
+    pub added_holdings: Vec<&'a Holding>,
+    pub removed_holdings: Vec<&'a Holding>,
+    pub changed_holdings: Vec<Changed<'a, Holding>>,
+}
+
+impl<'a> DatasetDiff<'a> {
+    /// Compare `after` against `base`
+    pub fn compare(base: &'a DividendTracker, after: &'a DividendTracker) -> Self {
+        let mut diff = DatasetDiff::default();
+
+        let mut before_by_key = group_dividends(&base.dividends);
+        for (key, after_records) in group_dividends(&after.dividends) {
+            let before_records = before_by_key.remove(&key).unwrap_or_default();
+            for i in 0..before_records.len().max(after_records.len()) {
+                match (before_records.get(i), after_records.get(i)) {
+                    (Some(before), Some(after)) => {
+                        let changes = dividend_changes(before, after);
+                        if !changes.is_empty() {
+                            diff.changed_dividends.push(Changed { before, after, changes });
+                        }
+                    }
+                    (None, Some(after)) => diff.added_dividends.push(after),
+                    (Some(before), None) => diff.removed_dividends.push(before),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        diff.removed_dividends.extend(before_by_key.into_values().flatten());
+        diff.removed_dividends.sort_by_key(|d| (d.ex_date, d.symbol.clone()));
+
+        let before_holdings: BTreeMap<_, _> = base.holdings.iter().collect();
+        let after_holdings: BTreeMap<_, _> = after.holdings.iter().collect();
+        for (key, after) in &after_holdings {
+            match before_holdings.get(key) {
+                Some(before) => {
+                    let changes = holding_changes(before, after);
+                    if !changes.is_empty() {
+                        diff.changed_holdings.push(Changed { before, after, changes });
+                    }
+                }
+                None => diff.added_holdings.push(after),
+            }
+        }
+        diff.removed_holdings = before_holdings
+            .iter()
+            .filter(|(key, _)| !after_holdings.contains_key(*key))
+            .map(|(_, holding)| *holding)
+            .collect();
+
+        diff
+    }
+
+    /// Whether the two datasets hold the same records
+    pub fn is_empty(&self) -> bool {
+        self.added_dividends.is_empty()
+            && self.removed_dividends.is_empty()
+            && self.changed_dividends.is_empty()
+            && self.added_holdings.is_empty()
+            && self.removed_holdings.is_empty()
+            && self.changed_holdings.is_empty()
+    }
+}
+
+/// Dividends keyed by symbol and ex-date, in date order
+fn group_dividends(dividends: &[Dividend]) -> BTreeMap<(chrono::NaiveDate, String), Vec<&Dividend>> {
+    let mut groups: BTreeMap<_, Vec<&Dividend>> = BTreeMap::new();
+    for dividend in dividends {
+        groups
+            .entry((dividend.ex_date, dividend.symbol.clone()))
+            .or_default()
+            .push(dividend);
+    }
+    groups
+}
+
+fn change<T: PartialEq + Display>(changes: &mut Vec<FieldChange>, field: &'static str, before: T, after: T) {
+    if before != after {
+        changes.push(FieldChange {
+            field,
+            before: before.to_string(),
+            after: after.to_string(),
+        });
+    }
+}
+
+fn optional<T: Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn dividend_changes(before: &Dividend, after: &Dividend) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    change(&mut changes, "pay date", before.pay_date, after.pay_date);
+    change(&mut changes, "amount", before.amount_per_share, after.amount_per_share);
+    change(&mut changes, "shares", before.shares_owned, after.shares_owned);
+    change(
+        &mut changes,
+        "total",
+        format_money(before.total_amount),
+        format_money(after.total_amount),
+    );
+    change(
+        &mut changes,
+        "type",
+        format!("{:?}", before.dividend_type),
+        format!("{:?}", after.dividend_type),
+    );
+    change(
+        &mut changes,
+        "tax class",
+        format!("{:?}", before.tax_classification),
+        format!("{:?}", after.tax_classification),
+    );
+    change(&mut changes, "withholding", optional(before.withholding_tax), optional(after.withholding_tax));
+    change(&mut changes, "country", optional(before.country.as_deref()), optional(after.country.as_deref()));
+    change(&mut changes, "fees", optional(before.fees), optional(after.fees));
+    changes
+}
+
+fn holding_changes(before: &Holding, after: &Holding) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    change(&mut changes, "shares", before.shares, after.shares);
+    change(
+        &mut changes,
+        "cost basis",
+        optional(before.avg_cost_basis),
+        optional(after.avg_cost_basis),
+    );
+    change(&mut changes, "closed", optional(before.closed_date), optional(after.closed_date));
+    change(
+        &mut changes,
+        "forward dividend",
+        optional(before.forward_dividend.as_ref().map(|f| f.annual_amount)),
+        optional(after.forward_dividend.as_ref().map(|f| f.annual_amount)),
+    );
+    changes
+}
+
+fn dividend_table(dividends: &[&Dividend], display: &DisplaySettings) -> TableView {
+    let mut table = TableView::new(&["Symbol", "Ex-Date", "Pay-Date", "$/Share", "Shares", "Total"]);
+    for dividend in dividends {
+        table.row(vec![
+            Text::new(dividend.symbol.clone(), Tone::Highlight),
+            dividend.ex_date.format("%Y-%m-%d").to_string().into(),
+            dividend.pay_date.format("%Y-%m-%d").to_string().into(),
+            format!("${:.4}", dividend.amount_per_share).into(),
+            format_shares(dividend.shares_owned, display).into(),
+            format_money(dividend.total_amount).into(),
+        ]);
+    }
+    table
+}
+
+fn holding_table(holdings: &[&Holding], display: &DisplaySettings) -> TableView {
+    let mut table = TableView::new(&["Symbol", "Shares", "Cost Basis"]);
+    for holding in holdings {
+        table.row(vec![
+            Text::new(holding.label(), Tone::Highlight),
+            format_shares(holding.shares, display).into(),
+            holding.avg_cost_basis.map(format_money).unwrap_or_else(|| "-".to_string()).into(),
+        ]);
+    }
+    table
+}
+
+fn changes_table<T>(changed: &[Changed<'_, T>], key: impl Fn(&T) -> String) -> TableView {
+    let mut table = TableView::new(&["Record", "Changes"]);
+    for entry in changed {
+        table.row(vec![
+            Text::new(key(entry.after), Tone::Highlight),
+            entry.changes.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("; ").into(),
+        ]);
+    }
+    table
+}
+
+/// Build the report for a diff between `base_label` and the current data
+pub fn diff_report(diff: &DatasetDiff, base_label: &str, display: &DisplaySettings) -> Report {
+    let count = |n: usize, tone: Tone| Text::new(n.to_string(), if n == 0 { Tone::Plain } else { tone });
+    let mut report = Report::new(TITLE).section(
+        Section::new(format!("Changes since {}", base_label))
+            .icon("🔍")
+            .field("Dividends Added", count(diff.added_dividends.len(), Tone::Positive))
+            .field("Dividends Removed", count(diff.removed_dividends.len(), Tone::Negative))
+            .field("Dividends Changed", count(diff.changed_dividends.len(), Tone::Warning))
+            .field("Holdings Added", count(diff.added_holdings.len(), Tone::Positive))
+            .field("Holdings Removed", count(diff.removed_holdings.len(), Tone::Negative))
+            .field("Holdings Changed", count(diff.changed_holdings.len(), Tone::Warning)),
+    );
+
+    if diff.is_empty() {
+        report.push(Section::untitled().note(NoteKind::Info, "The datasets contain the same records."));
+        return report;
+    }
+
+    if !diff.added_dividends.is_empty() {
+        report.push(Section::new("Added Dividends").table(dividend_table(&diff.added_dividends, display)));
+    }
+    if !diff.removed_dividends.is_empty() {
+        report.push(Section::new("Removed Dividends").table(dividend_table(&diff.removed_dividends, display)));
+    }
+    if !diff.changed_dividends.is_empty() {
+        report.push(Section::new("Changed Dividends").table(changes_table(&diff.changed_dividends, |d| {
+            format!("{} {}", d.symbol, d.ex_date.format("%Y-%m-%d"))
+        })));
+    }
+    if !diff.added_holdings.is_empty() {
+        report.push(Section::new("Added Holdings").table(holding_table(&diff.added_holdings, display)));
+    }
+    if !diff.removed_holdings.is_empty() {
+        report.push(Section::new("Removed Holdings").table(holding_table(&diff.removed_holdings, display)));
+    }
+    if !diff.changed_holdings.is_empty() {
+        report.push(
+            Section::new("Changed Holdings").table(changes_table(&diff.changed_holdings, Holding::label)),
+        );
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DividendType, HoldingKey, DEFAULT_ACCOUNT};
+    use rust_decimal::Decimal;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn dividend(symbol: &str, ex_date: &str, amount: Decimal) -> Dividend {
+        let date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+        Dividend::new(symbol.to_string(), None, date, date, amount, dec!(100), DividendType::Regular).unwrap()
+    }
+
+    #[test]
+    fn test_diff_dividends_and_holdings() {
+        let mut base = DividendTracker::new();
+        base.add_dividend(dividend("AAPL", "2024-02-09", dec!(0.24)));
+        base.add_dividend(dividend("KO", "2024-03-14", dec!(0.485)));
+        base.add_holding(Holding::new("AAPL".to_string(), dec!(100), None, None).unwrap());
+        base.add_holding(Holding::new("KO".to_string(), dec!(50), None, None).unwrap());
+
+        let mut after = base.clone();
+        after.dividends.retain(|d| d.symbol != "KO");
+        after.dividends[0].amount_per_share = dec!(0.25);
+        after.add_dividend(dividend("MSFT", "2024-02-14", dec!(0.75)));
+        after.add_dividend(dividend("MSFT", "2024-02-14", dec!(0.75)));
+        after.holdings.remove(&HoldingKey::new("KO", DEFAULT_ACCOUNT));
+        after.holdings.get_mut(&HoldingKey::new("AAPL", DEFAULT_ACCOUNT)).unwrap().shares = dec!(120);
+
+        let diff = DatasetDiff::compare(&base, &after);
+        assert_eq!(diff.added_dividends.len(), 2);
+        assert_eq!(diff.removed_dividends.len(), 1);
+        assert_eq!(diff.removed_dividends[0].symbol, "KO");
+        assert_eq!(diff.changed_dividends.len(), 1);
+        assert_eq!(diff.changed_dividends[0].changes[0].to_string(), "amount: 0.24 -> 0.25");
+        assert_eq!(diff.removed_holdings.len(), 1);
+        assert_eq!(diff.changed_holdings[0].changes[0].to_string(), "shares: 100 -> 120");
+
+        assert!(DatasetDiff::compare(&base, &base).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Append-only log of things that happened to the data
+//!
+//! Commands append one JSON line to `events.jsonl` in the data directory when
+//! dividends are added, alerts are generated or a fetch completes. `serve`
+//! tails the file and streams new lines to clients at `/events`. An event's
+//! id is the byte offset just past its line, so a reconnecting client can
+//! resume with `Last-Event-ID`.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Event log file, stored next to the dividend data
+const EVENTS_FILE: &str = "events.jsonl";
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    DividendAdded,
+    AlertsGenerated,
+    FetchCompleted,
+}
+
+impl EventKind {
+    /// Name used for the SSE `event:` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::DividendAdded => "dividend_added",
+            EventKind::AlertsGenerated => "alerts_generated",
+            EventKind::FetchCompleted => "fetch_completed",
+        }
+    }
+}
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub kind: EventKind,
+    pub at: DateTime<Local>,
+    pub data: Value,
+}
+
+/// The event log of one data directory
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    path: PathBuf,
+}
+
+impl EventLog {
+    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
+        EventLog {
+            path: data_dir.as_ref().join(EVENTS_FILE),
+        }
+    }
+
+    /// Append an event
+    pub fn publish(&self, kind: EventKind, data: Value) -> Result<()> {
+        let event = Event {
+            kind,
+            at: Local::now(),
+            data,
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&event)?)?;
+        Ok(())
+    }
+
+    /// Append an event without failing the command that caused it
+    ///
+    /// The event log is a convenience for listeners; losing a line must not
+    /// undo a change that was already saved.
+    pub fn notify(&self, kind: EventKind, data: Value) {
+        let _ = self.publish(kind, data);
+    }
+
+    /// Current end of the log, where a new listener starts
+    pub fn end(&self) -> u64 {
+        fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0)
+    }
+
+    /// Complete events after byte `offset`, each with the offset just past it
+    ///
+    /// A line still being written is left for the next read. An offset past
+    /// the end (the log was removed) starts again from the beginning.
+    pub fn read_from(&self, offset: u64) -> Result<Vec<(u64, Event)>> {
+        let mut file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let offset = if offset > file.metadata()?.len() { 0 } else { offset };
+        file.seek(SeekFrom::Start(offset))?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+
+        let mut events = Vec::new();
+        let mut position = offset;
+        for line in contents.split_inclusive('\n') {
+            if !line.ends_with('\n') {
+                break;
+            }
+            position += line.len() as u64;
+            if let Ok(event) = serde_json::from_str(line.trim_end()) {
+                events.push((position, event));
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_event_log_reads_from_offset() {
+        let dir = TempDir::new().unwrap();
+        let log = EventLog::new(dir.path());
+        assert!(log.read_from(0).unwrap().is_empty());
+
+        log.publish(EventKind::DividendAdded, json!({ "symbol": "KO" })).unwrap();
+        let start = log.end();
+        log.publish(EventKind::FetchCompleted, json!({ "symbols": ["KO"] })).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(EVENTS_FILE))
+            .unwrap()
+            .write_all(b"{\"partial")
+            .unwrap();
+
+        let events = log.read_from(start).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1.kind, EventKind::FetchCompleted);
+        assert_eq!(events[0].0, log.end() - "{\"partial".len() as u64);
+        assert_eq!(log.read_from(0).unwrap().len(), 2);
+        assert_eq!(log.read_from(u64::MAX).unwrap().len(), 2);
+    }
+}
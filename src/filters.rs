@@ -0,0 +1,402 @@
+//! Dividend filters shared by the reporting commands
+//!
+//! `div list`, `summary`, the tax commands, and `data export` all narrow the
+//! dividend history by symbol and date. [`DividendFilter`] holds those rules in
+//! one place so a symbol or year means the same thing in every command.
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::config::DateBasis;
+use crate::models::Dividend;
+
+/// A calendar quarter such as `Q3-2024`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quarter {
+    /// Quarter number, 1-4
+    pub number: u32,
+    pub year: i32,
+}
+
+impl Quarter {
+    /// Parse the `Q1-2024` format
+    pub fn parse(quarter: &str) -> Result<Self> {
+        let parts: Vec<&str> = quarter.trim().split('-').collect();
+        if parts.len() != 2 {
+            return Err(anyhow!("Invalid quarter format. Use Q1-2024, Q2-2024, etc."));
+        }
+
+        let number = match parts[0].to_uppercase().as_str() {
+            "Q1" => 1,
+            "Q2" => 2,
+            "Q3" => 3,
+            "Q4" => 4,
+            _ => return Err(anyhow!("Invalid quarter. Use Q1, Q2, Q3, or Q4")),
+        };
+
+        let year: i32 = parts[1]
+            .parse()
+            .map_err(|_| anyhow!("Invalid year in quarter format"))?;
+
+        Ok(Quarter { number, year })
+    }
+
+    /// Calendar months (1-12) in the quarter
+    pub fn months(&self) -> RangeInclusive<u32> {
+        let first = (self.number - 1) * 3 + 1;
+        first..=first + 2
+    }
+
+    /// Whether a year and month fall in the quarter
+    pub fn contains(&self, year: i32, month: u32) -> bool {
+        year == self.year && self.months().contains(&month)
+    }
+}
+
+impl fmt::Display for Quarter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Q{}-{}", self.number, self.year)
+    }
+}
+
+/// How a symbol filter compares against a dividend's symbol
+///
+/// Comparisons ignore case in every mode. Patterns containing `*` or `?`
+/// are globs and always match the whole symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolMatch {
+    /// The symbol must match exactly
+    #[default]
+    Exact,
+    /// The symbol must start with the filter
+    Prefix,
+    /// The symbol must contain the filter anywhere
+    Contains,
+}
+
+/// Builder for the symbol, date, and amount filters applied to dividends
+///
+/// ```ignore
+/// let filter = DividendFilter::new()
+///     .symbol("AAPL,BR*", SymbolMatch::Exact)
+///     .year(2024);
+/// let matching = filter.apply(&tracker.dividends);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DividendFilter {
+    symbols: Vec<String>,
+    symbol_match: SymbolMatch,
+    date_basis: DateBasis,
+    year: Option<i32>,
+    month: Option<u32>,
+    quarter: Option<Quarter>,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    amount_min: Option<Decimal>,
+    amount_max: Option<Decimal>,
+    total_min: Option<Decimal>,
+    total_max: Option<Decimal>,
+    shares_min: Option<Decimal>,
+    paid_after: Option<NaiveDate>,
+    account: Option<String>,
+}
+
+impl DividendFilter {
+    /// A filter that matches every dividend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep dividends whose symbol matches any of the comma-separated
+    /// patterns in `symbols` (e.g. `AAPL,MSFT` or `BR*`) under `mode`
+    pub fn symbol(mut self, symbols: &str, mode: SymbolMatch) -> Self {
+        self.symbols = symbols
+            .split(',')
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        self.symbol_match = mode;
+        self
+    }
+
+    /// Compare dates against the ex-date (default) or the pay date
+    pub fn date_basis(mut self, basis: DateBasis) -> Self {
+        self.date_basis = basis;
+        self
+    }
+
+    /// Keep dividends in a calendar year
+    pub fn year(mut self, year: i32) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    /// Keep dividends in a calendar month (1-12) of any year
+    pub fn month(mut self, month: u32) -> Self {
+        self.month = Some(month);
+        self
+    }
+
+    /// Keep dividends in a quarter
+    pub fn quarter(mut self, quarter: Quarter) -> Self {
+        self.quarter = Some(quarter);
+        self
+    }
+
+    /// Keep dividends on or after `start`
+    pub fn since(mut self, start: NaiveDate) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Keep dividends on or before `end`
+    pub fn until(mut self, end: NaiveDate) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Keep dividends paying at least `amount` per share
+    pub fn amount_min(mut self, amount: Decimal) -> Self {
+        self.amount_min = Some(amount);
+        self
+    }
+
+    /// Keep dividends paying at most `amount` per share
+    pub fn amount_max(mut self, amount: Decimal) -> Self {
+        self.amount_max = Some(amount);
+        self
+    }
+
+    /// Keep payments totalling at least `total`
+    pub fn total_min(mut self, total: Decimal) -> Self {
+        self.total_min = Some(total);
+        self
+    }
+
+    /// Keep payments totalling at most `total`
+    pub fn total_max(mut self, total: Decimal) -> Self {
+        self.total_max = Some(total);
+        self
+    }
+
+    /// Keep payments on at least `shares` shares
+    pub fn shares_min(mut self, shares: Decimal) -> Self {
+        self.shares_min = Some(shares);
+        self
+    }
+
+    /// Keep dividends paid after `date`, regardless of the date basis
+    pub fn paid_after(mut self, date: NaiveDate) -> Self {
+        self.paid_after = Some(date);
+        self
+    }
+
+    /// Keep dividends credited to an account, compared ignoring case
+    pub fn account(mut self, account: &str) -> Self {
+        self.account = Some(account.trim().to_string());
+        self
+    }
+
+    /// Whether a symbol passes the symbol filter
+    pub fn matches_symbol(&self, symbol: &str) -> bool {
+        if self.symbols.is_empty() {
+            return true;
+        }
+        let symbol = symbol.to_uppercase();
+
+        self.symbols.iter().any(|wanted| {
+            if wanted.contains(['*', '?']) {
+                return glob_match(wanted.as_bytes(), symbol.as_bytes());
+            }
+            match self.symbol_match {
+                SymbolMatch::Exact => symbol == *wanted,
+                SymbolMatch::Prefix => symbol.starts_with(wanted.as_str()),
+                SymbolMatch::Contains => symbol.contains(wanted.as_str()),
+            }
+        })
+    }
+
+    /// Whether a dividend passes every filter
+    pub fn matches(&self, dividend: &Dividend) -> bool {
+        let date = self.date_basis.date_of(dividend);
+        self.matches_in_period(dividend, (date.year(), date.month()))
+    }
+
+    /// Whether a dividend passes every filter, with the year, month, and
+    /// quarter filters checked against `period` instead of the raw date
+    ///
+    /// Analytics passes the drift-adjusted period from
+    /// [`PaymentPeriods`](crate::periods::PaymentPeriods) here.
+    pub fn matches_in_period(&self, dividend: &Dividend, period: (i32, u32)) -> bool {
+        let (year, month) = period;
+        let date = self.date_basis.date_of(dividend);
+
+        self.matches_symbol(&dividend.symbol)
+            && self.year.is_none_or(|y| y == year)
+            && self.month.is_none_or(|m| m == month)
+            && self.quarter.is_none_or(|q| q.contains(year, month))
+            && self.start.is_none_or(|start| date >= start)
+            && self.end.is_none_or(|end| date <= end)
+            && self.amount_min.is_none_or(|min| dividend.amount_per_share >= min)
+            && self.amount_max.is_none_or(|max| dividend.amount_per_share <= max)
+            && self.total_min.is_none_or(|min| dividend.total_amount >= min)
+            && self.total_max.is_none_or(|max| dividend.total_amount <= max)
+            && self.shares_min.is_none_or(|min| dividend.shares_owned >= min)
+            && self.paid_after.is_none_or(|after| dividend.pay_date > after)
+            && self.account.as_ref().is_none_or(|a| dividend.account_name().eq_ignore_ascii_case(a))
+    }
+
+    /// Dividends that pass every filter, in their original order
+    pub fn apply<'a>(&self, dividends: impl IntoIterator<Item = &'a Dividend>) -> Vec<&'a Dividend> {
+        dividends.into_iter().filter(|d| self.matches(d)).collect()
+    }
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?`
+/// is any single character
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_match(rest, &text[skip..])),
+        Some((&c, rest)) => match text.split_first() {
+            Some((&t, text_rest)) if c == b'?' || c == t => glob_match(rest, text_rest),
+            _ => false,
+        },
+    }
+}
+
+/// First day of the trailing twelve months ending on `today`
+pub fn ttm_start(today: NaiveDate) -> NaiveDate {
+    today - Duration::days(365)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DividendType;
+    use rust_decimal_macros::dec;
+
+    fn dividend(symbol: &str, ex_date: &str, pay_date: &str) -> Dividend {
+        Dividend::new(
+            symbol.to_string(),
+            None,
+            NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap(),
+            NaiveDate::parse_from_str(pay_date, "%Y-%m-%d").unwrap(),
+            dec!(0.5),
+            dec!(10),
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_quarter() {
+        let quarter = Quarter::parse("q2-2024").unwrap();
+        assert_eq!(quarter, Quarter { number: 2, year: 2024 });
+        assert_eq!(quarter.months(), 4..=6);
+        assert_eq!(quarter.to_string(), "Q2-2024");
+
+        assert!(Quarter::parse("Q5-2024").is_err());
+        assert!(Quarter::parse("Q1").is_err());
+        assert!(Quarter::parse("Q1-20x4").is_err());
+    }
+
+    #[test]
+    fn test_quarter_contains() {
+        let quarter = Quarter::parse("Q4-2023").unwrap();
+        assert!(quarter.contains(2023, 12));
+        assert!(!quarter.contains(2024, 1));
+        assert!(!quarter.contains(2023, 9));
+    }
+
+    #[test]
+    fn test_symbol_match_modes() {
+        let exact = DividendFilter::new().symbol("aapl", SymbolMatch::Exact);
+        assert!(exact.matches_symbol("AAPL"));
+        assert!(!exact.matches_symbol("AAPLX"));
+
+        let prefix = DividendFilter::new().symbol("BR", SymbolMatch::Prefix);
+        assert!(prefix.matches_symbol("BRK.B"));
+        assert!(!prefix.matches_symbol("ABR"));
+
+        let contains = DividendFilter::new().symbol("BR", SymbolMatch::Contains);
+        assert!(contains.matches_symbol("ABR"));
+        assert!(DividendFilter::new().matches_symbol("ANY"));
+    }
+
+    #[test]
+    fn test_symbol_lists_and_globs() {
+        let filter = DividendFilter::new().symbol("aapl, msft", SymbolMatch::Exact);
+        assert!(filter.matches_symbol("AAPL"));
+        assert!(filter.matches_symbol("MSFT"));
+        assert!(!filter.matches_symbol("AAP"));
+
+        let glob = DividendFilter::new().symbol("BR*,?OG", SymbolMatch::Exact);
+        assert!(glob.matches_symbol("BR"));
+        assert!(glob.matches_symbol("BRK.B"));
+        assert!(glob.matches_symbol("HOG"));
+        assert!(!glob.matches_symbol("ABR"));
+        assert!(!glob.matches_symbol("HOGS"));
+
+        assert!(DividendFilter::new().symbol(" , ", SymbolMatch::Exact).matches_symbol("KO"));
+    }
+
+    #[test]
+    fn test_amount_total_and_share_ranges() {
+        // $0.50 per share on 10 shares = $5.00
+        let div = dividend("KO", "2024-03-14", "2024-04-01");
+
+        assert!(DividendFilter::new().amount_max(dec!(0.5)).matches(&div));
+        assert!(!DividendFilter::new().amount_max(dec!(0.49)).matches(&div));
+        assert!(DividendFilter::new().total_min(dec!(5)).total_max(dec!(5)).matches(&div));
+        assert!(!DividendFilter::new().total_min(dec!(5.01)).matches(&div));
+        assert!(!DividendFilter::new().total_max(dec!(4.99)).matches(&div));
+        assert!(DividendFilter::new().shares_min(dec!(10)).matches(&div));
+        assert!(!DividendFilter::new().shares_min(dec!(10.5)).matches(&div));
+    }
+
+    #[test]
+    fn test_account_filter() {
+        let mut ira = dividend("KO", "2024-03-14", "2024-04-01");
+        ira.account = Some("IRA".to_string());
+        let taxable = dividend("KO", "2024-03-14", "2024-04-01");
+
+        assert!(DividendFilter::new().account("ira").matches(&ira));
+        assert!(!DividendFilter::new().account("IRA").matches(&taxable));
+        assert!(DividendFilter::new().account("default").matches(&taxable));
+    }
+
+    #[test]
+    fn test_date_basis() {
+        // Goes ex-dividend in December, pays in January
+        let div = dividend("KO", "2023-12-14", "2024-01-02");
+
+        assert!(DividendFilter::new().year(2023).matches(&div));
+        assert!(!DividendFilter::new().date_basis(DateBasis::PayDate).year(2023).matches(&div));
+        assert!(DividendFilter::new().date_basis(DateBasis::PayDate).year(2024).matches(&div));
+    }
+
+    #[test]
+    fn test_combined_filters() {
+        let dividends = vec![
+            dividend("KO", "2024-03-14", "2024-04-01"),
+            dividend("KO", "2024-06-14", "2024-07-01"),
+            dividend("PEP", "2024-03-07", "2024-03-29"),
+        ];
+        let filter = DividendFilter::new()
+            .symbol("ko", SymbolMatch::Exact)
+            .quarter(Quarter::parse("Q1-2024").unwrap())
+            .since(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+
+        let matching = filter.apply(&dividends);
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].pay_date, NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+
+        let in_period = DividendFilter::new().year(2025);
+        assert!(in_period.matches_in_period(&dividends[0], (2025, 1)));
+        assert!(!in_period.matches(&dividends[0]));
+    }
+}
@@ -0,0 +1,139 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::config::{DisplaySettings, RoundingPolicy};
+
+impl RoundingPolicy {
+    /// Map the configured policy onto rust_decimal's rounding strategy
+    fn strategy(&self) -> RoundingStrategy {
+        match self {
+            RoundingPolicy::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingPolicy::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingPolicy::Truncate => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Round a share quantity according to the display settings
+pub fn round_shares(shares: Decimal, settings: &DisplaySettings) -> Decimal {
+    shares
+        .round_dp_with_strategy(settings.share_precision, settings.share_rounding.strategy())
+        .normalize()
+}
+
+/// Format a share quantity for tables and CSV output (no trailing zeros)
+pub fn format_shares(shares: Decimal, settings: &DisplaySettings) -> String {
+    round_shares(shares, settings).to_string()
+}
+
+/// Round a money amount to cents, half away from zero
+///
+/// Totals are always summed from the exact values and rounded once at the end,
+/// so fractional-share payments never accumulate rounding error.
+pub fn round_money(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Format a money amount as `$1234.56`
+pub fn format_money(amount: Decimal) -> String {
+    format!("${:.2}", round_money(amount))
+}
+
+/// Round a percentage for display, half away from zero
+pub fn round_percent(percent: Decimal, dp: u32) -> Decimal {
+    percent.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Format a percentage (already scaled to 0-100) as `4.2%`
+pub fn format_percent(percent: Decimal, dp: u32) -> String {
+    format!("{:.*}%", dp as usize, round_percent(percent, dp))
+}
+
+/// Format a change as `+4.2%` / `-1.0%`; a change that rounds to zero is `0.0%`
+pub fn format_signed_percent(percent: Decimal, dp: u32) -> String {
+    let rounded = round_percent(percent, dp);
+    if rounded > Decimal::ZERO {
+        format!("+{:.*}%", dp as usize, rounded)
+    } else {
+        // Rounding a small negative change to zero leaves a negative zero
+        format!("{:.*}%", dp as usize, if rounded.is_zero() { Decimal::ZERO } else { rounded })
+    }
+}
+
+/// `part` as a percentage of `whole`, or `None` when `whole` is zero
+pub fn percent_of(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    (!whole.is_zero()).then(|| part / whole * Decimal::ONE_HUNDRED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn settings(precision: u32, rounding: RoundingPolicy) -> DisplaySettings {
+        DisplaySettings {
+            share_precision: precision,
+            share_rounding: rounding,
+            ..DisplaySettings::default()
+        }
+    }
+
+    #[test]
+    fn test_format_shares_whole_number() {
+        let s = DisplaySettings::default();
+        assert_eq!(format_shares(dec!(100), &s), "100");
+        assert_eq!(format_shares(dec!(100.0000), &s), "100");
+    }
+
+    #[test]
+    fn test_format_shares_fractional_drip() {
+        let s = DisplaySettings::default();
+        assert_eq!(format_shares(dec!(12.345678912), &s), "12.3457");
+        assert_eq!(format_shares(dec!(0.5), &s), "0.5");
+    }
+
+    #[test]
+    fn test_rounding_policies() {
+        assert_eq!(
+            round_shares(dec!(1.00125), &settings(4, RoundingPolicy::HalfUp)),
+            dec!(1.0013)
+        );
+        assert_eq!(
+            round_shares(dec!(1.00125), &settings(4, RoundingPolicy::HalfEven)),
+            dec!(1.0012)
+        );
+        assert_eq!(
+            round_shares(dec!(1.00129), &settings(4, RoundingPolicy::Truncate)),
+            dec!(1.0012)
+        );
+    }
+
+    #[test]
+    fn test_money_rounding_of_fractional_totals() {
+        // 0.24 * 12.345678 = 2.96296272
+        let total = dec!(0.24) * dec!(12.345678);
+        assert_eq!(round_money(total), dec!(2.96));
+        assert_eq!(format_money(total), "$2.96");
+
+        // Summing exact values before rounding avoids penny drift
+        let parts = [dec!(0.335), dec!(0.335), dec!(0.335)];
+        let sum: Decimal = parts.iter().sum();
+        assert_eq!(format_money(sum), "$1.01");
+    }
+
+    #[test]
+    fn test_percent_formatting_rounds_half_away_from_zero() {
+        assert_eq!(format_percent(dec!(4.25), 1), "4.3%");
+        assert_eq!(format_percent(dec!(4.35), 1), "4.4%");
+        assert_eq!(format_percent(dec!(3.14159), 2), "3.14%");
+        assert_eq!(format_percent(dec!(50), 1), "50.0%");
+        assert_eq!(format_percent(dec!(7), 0), "7%");
+
+        assert_eq!(format_signed_percent(dec!(4.25), 1), "+4.3%");
+        assert_eq!(format_signed_percent(dec!(-1), 1), "-1.0%");
+        assert_eq!(format_signed_percent(dec!(-0.04), 1), "0.0%");
+        assert_eq!(format_signed_percent(dec!(0), 1), "0.0%");
+
+        assert_eq!(percent_of(dec!(1), dec!(8)), Some(dec!(12.5)));
+        assert_eq!(percent_of(dec!(1), dec!(0)), None);
+    }
+}
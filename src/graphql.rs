@@ -0,0 +1,236 @@
+//! GraphQL endpoint of the JSON API, built with the `graphql` feature
+//!
+//! `serve` answers queries at `/graphql`, sent as a JSON body with `query`
+//! (and optionally `variables` and `operationName`) to `POST`, or as
+//! `?query=` to `GET`. They run over the same read models as the REST
+//! endpoints, and a holding can nest its latest dividends and its forward
+//! income, so a dashboard gets all it shows for a position in one request:
+//!
+//! ```graphql
+//! {
+//!   holdings {
+//!     symbol
+//!     shares
+//!     dividends(limit: 4) { exDate payDate totalAmount }
+//!     forwardIncome { annualIncome paymentsPerYear }
+//!   }
+//! }
+//! ```
+//!
+//! The schema only reads; dividends are added through `POST /dividends`.
+
+use anyhow::Result;
+use async_graphql::{ComplexObject, Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::models::{DividendTracker, HoldingKey, DEFAULT_ACCOUNT};
+use crate::run_rate::{ForwardIncome, PositionRunRate, RunRateSource};
+use crate::server::{by_symbol, newest_first, DividendView, HoldingView, DEFAULT_LIMIT, MAX_LIMIT};
+use crate::symbols::normalize_symbol;
+
+/// Latest dividends nested in a holding when the query gives no `limit`
+pub const DEFAULT_NESTED_DIVIDENDS: usize = 4;
+
+/// The data a query runs against
+struct QueryData {
+    tracker: DividendTracker,
+    today: NaiveDate,
+    /// Worked out the first time a query asks for forward income
+    forward: OnceLock<ForwardIncome>,
+}
+
+impl QueryData {
+    fn forward(&self) -> &ForwardIncome {
+        self.forward.get_or_init(|| ForwardIncome::calculate(&self.tracker, self.today))
+    }
+}
+
+/// A holding's forward income run-rate as the API returns it
+#[derive(Debug, Clone, SimpleObject)]
+pub struct ForwardIncomeView {
+    pub annual_income: Decimal,
+    pub per_share: Decimal,
+    pub payments_per_year: u32,
+    /// `last_payment` or `forward_dividend`
+    pub source: String,
+}
+
+impl From<&PositionRunRate> for ForwardIncomeView {
+    fn from(position: &PositionRunRate) -> Self {
+        ForwardIncomeView {
+            annual_income: position.annual_income,
+            per_share: position.per_share,
+            payments_per_year: position.frequency.payments_per_year(),
+            source: match position.source {
+                RunRateSource::LastPayment(_) => "last_payment",
+                RunRateSource::ForwardDividend => "forward_dividend",
+            }
+            .to_string(),
+        }
+    }
+}
+
+fn check_limit(limit: usize) -> async_graphql::Result<()> {
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(format!("'limit' must be between 1 and {}", MAX_LIMIT).into());
+    }
+    Ok(())
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Dividends newest first, optionally of one symbol
+    async fn dividends(
+        &self,
+        ctx: &Context<'_>,
+        symbol: Option<String>,
+        #[graphql(default_with = "DEFAULT_LIMIT")] limit: usize,
+        #[graphql(default)] offset: usize,
+    ) -> async_graphql::Result<Vec<DividendView>> {
+        check_limit(limit)?;
+        let data = ctx.data::<QueryData>()?;
+        let symbol = symbol.map(|s| normalize_symbol(&s));
+        Ok(newest_first(&data.tracker.dividends)
+            .filter(|d| symbol.as_ref().is_none_or(|s| &d.symbol == s))
+            .skip(offset)
+            .take(limit)
+            .map(DividendView::from)
+            .collect())
+    }
+
+    /// Holdings in symbol order, optionally just one
+    async fn holdings(&self, ctx: &Context<'_>, symbol: Option<String>) -> async_graphql::Result<Vec<HoldingView>> {
+        let data = ctx.data::<QueryData>()?;
+        let symbol = symbol.map(|s| normalize_symbol(&s));
+        Ok(by_symbol(&data.tracker)
+            .filter(|h| symbol.as_ref().is_none_or(|s| &h.symbol == s))
+            .map(HoldingView::from)
+            .collect())
+    }
+}
+
+impl HoldingView {
+    fn key(&self) -> HoldingKey {
+        HoldingKey::new(&self.symbol, self.account.as_deref().unwrap_or(DEFAULT_ACCOUNT))
+    }
+}
+
+#[ComplexObject]
+impl HoldingView {
+    /// The holding's latest dividends, newest first
+    async fn dividends(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default_with = "DEFAULT_NESTED_DIVIDENDS")] limit: usize,
+    ) -> async_graphql::Result<Vec<DividendView>> {
+        check_limit(limit)?;
+        let data = ctx.data::<QueryData>()?;
+        let key = self.key();
+        Ok(newest_first(&data.tracker.dividends)
+            .filter(|d| d.symbol == self.symbol && key.is_in(d.account_name()))
+            .take(limit)
+            .map(DividendView::from)
+            .collect())
+    }
+
+    /// Forward income at the current run-rate; null when there is nothing to go on
+    async fn forward_income(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<ForwardIncomeView>> {
+        let data = ctx.data::<QueryData>()?;
+        let key = self.key();
+        Ok(data
+            .forward()
+            .positions
+            .iter()
+            .find(|p| p.symbol == self.symbol && key.is_in(p.account.as_deref().unwrap_or(DEFAULT_ACCOUNT)))
+            .map(ForwardIncomeView::from))
+    }
+}
+
+/// Run a query against `tracker` as it stands on `today`, answering with the
+/// GraphQL response (`data` and any `errors`)
+pub fn execute(tracker: DividendTracker, today: NaiveDate, request: async_graphql::Request) -> Result<Value> {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(QueryData {
+            tracker,
+            today,
+            forward: OnceLock::new(),
+        })
+        .finish();
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    let response = runtime.block_on(schema.execute(request));
+    Ok(serde_json::to_value(response)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, DividendType, Holding};
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn tracker() -> DividendTracker {
+        let mut tracker = DividendTracker::new();
+        for (symbol, ex_date) in [
+            ("KO", "2023-11-30"),
+            ("KO", "2024-03-14"),
+            ("KO", "2024-06-14"),
+            ("O", "2024-06-28"),
+        ] {
+            let ex_date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+            tracker.add_dividend(
+                Dividend::new(symbol.to_string(), None, ex_date, ex_date, dec!(0.5), dec!(100), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(100), Some(dec!(55)), None).unwrap());
+        tracker
+    }
+
+    fn query(query: &str) -> Value {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        execute(tracker(), today, async_graphql::Request::new(query)).unwrap()
+    }
+
+    #[test]
+    fn test_holdings_nest_latest_dividends_and_forward_income() {
+        let response = query(
+            "{ holdings { symbol avgCostBasis dividends(limit: 2) { exDate totalAmount } \
+               forwardIncome { annualIncome paymentsPerYear source } } }",
+        );
+
+        assert_eq!(response.get("errors"), None);
+        let ko = &response["data"]["holdings"][0];
+        assert_eq!(ko["symbol"], "KO");
+        assert_eq!(ko["avgCostBasis"], "55");
+        assert_eq!(
+            ko["dividends"],
+            json!([
+                { "exDate": "2024-06-14", "totalAmount": "50.0" },
+                { "exDate": "2024-03-14", "totalAmount": "50.0" },
+            ])
+        );
+        assert_eq!(ko["forwardIncome"]["paymentsPerYear"], 4);
+        assert_eq!(ko["forwardIncome"]["source"], "last_payment");
+    }
+
+    #[test]
+    fn test_dividends_filter_and_page() {
+        let response = query(r#"{ dividends(symbol: "ko", limit: 1, offset: 1) { symbol exDate incomeKind } }"#);
+        assert_eq!(
+            response["data"]["dividends"],
+            json!([{ "symbol": "KO", "exDate": "2024-03-14", "incomeKind": "DIVIDEND" }])
+        );
+
+        let nested = query("{ holdings { dividends { exDate } } }");
+        assert_eq!(nested["data"]["holdings"][0]["dividends"].as_array().unwrap().len(), 3);
+
+        let errors = query("{ dividends(limit: 0) { symbol } }");
+        assert!(errors["errors"][0]["message"].as_str().unwrap().contains("between 1 and"));
+        assert!(query("{ lots { symbol } }")["errors"].is_array());
+    }
+}
@@ -1,103 +1,149 @@
 use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
 use colored::*;
 use csv::{Reader, Writer};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::str::FromStr;
-use tabled::{Table, Tabled};
 
-use crate::models::{DividendTracker, Holding};
+use crate::cli::{HoldingsSortField, SortSpec};
+use crate::config::Config;
+use crate::format::{format_money, format_percent, format_shares, format_signed_percent};
+use crate::models::{DividendTracker, ForwardDividend, Holding};
+use crate::paths;
 use crate::persistence::PersistenceManager;
+use crate::progress;
+use crate::render::{Report, Section, TableView, Text, Tone};
+use crate::run_rate::{self, ForwardIncome};
+use crate::symbols::did_you_mean;
 
 /// CSV record for holdings import/export
 #[derive(Debug, Serialize, Deserialize)]
 struct HoldingRecord {
+    /// May be left empty when the ISIN or CUSIP matches an existing holding
+    #[serde(default)]
     symbol: String,
     shares: String,
     cost_basis: Option<String>,
     current_yield: Option<String>,
-}
-
-/// Load existing holdings from the data file
-pub fn load_holdings() -> Result<DividendTracker> {
-    let persistence = PersistenceManager::new()?;
-    persistence.load()
-}
-
-/// Save holdings to the data file
-fn save_holdings(tracker: &DividendTracker) -> Result<()> {
-    let persistence = PersistenceManager::new()?;
-    persistence.save(tracker)
+    #[serde(default)]
+    isin: Option<String>,
+    #[serde(default)]
+    cusip: Option<String>,
+    #[serde(default)]
+    account: Option<String>,
 }
 
 /// Import holdings from a CSV file
-pub fn import_holdings(file_path: &str) -> Result<()> {
+///
+/// `account` is used for rows whose `account` column is missing or blank.
+pub fn import_holdings(persistence: &PersistenceManager, file_path: &str, account: Option<&str>) -> Result<()> {
     println!("{}", "Importing holdings from CSV...".green().bold());
 
     if !Path::new(file_path).exists() {
         return Err(anyhow!("File not found: {}", file_path));
     }
 
-    let mut tracker = load_holdings()?;
+    let rows = Reader::from_path(file_path)?.records().count();
     let mut reader = Reader::from_path(file_path)?;
-    let mut imported_count = 0;
-    let mut updated_count = 0;
-
-    for result in reader.deserialize() {
-        let record: HoldingRecord = result?;
-
-        let shares = Decimal::from_str(&record.shares).map_err(|_| {
-            anyhow!(
-                "Invalid shares value for {}: {}",
-                record.symbol,
-                record.shares
-            )
-        })?;
-
-        let cost_basis = if let Some(cb) = record.cost_basis {
-            if cb.trim().is_empty() || cb.trim() == "0" {
-                None
+    let pb = progress::bar(rows, "Importing holdings");
+
+    let result = persistence.with_tracker_mut(|tracker| {
+        let mut imported_count = 0;
+        let mut updated_count = 0;
+
+        for result in pb.wrap_iter(reader.deserialize()) {
+            let mut record: HoldingRecord = result?;
+
+            let isin = record.isin.as_deref().map(str::trim).filter(|id| !id.is_empty());
+            let cusip = record.cusip.as_deref().map(str::trim).filter(|id| !id.is_empty());
+
+            // Match by identifier first so a broker's ticker spelling (or a
+            // missing ticker) doesn't create a second holding
+            if let Some(existing) = isin.or(cusip).and_then(|id| tracker.find_holding_by_identifier(id)) {
+                let given = record.symbol.trim().to_uppercase();
+                if !given.is_empty() && given != existing.symbol {
+                    pb.suspend(|| {
+                        println!(
+                            "  {} {} to {} by {}",
+                            "Matched".blue(),
+                            given,
+                            existing.symbol.cyan(),
+                            isin.or(cusip).unwrap_or_default()
+                        )
+                    });
+                }
+                record.symbol = existing.symbol.clone();
+            } else if record.symbol.trim().is_empty() {
+                return Err(anyhow!(
+                    "No symbol given and no holding matches {}",
+                    isin.or(cusip).unwrap_or("the record")
+                ));
             } else {
-                Some(
-                    Decimal::from_str(&cb)
-                        .map_err(|_| anyhow!("Invalid cost basis for {}: {}", record.symbol, cb))?,
-                )
+                record.symbol = tracker.canonical_symbol(&record.symbol);
             }
-        } else {
-            None
-        };
 
-        let current_yield = if let Some(cy) = record.current_yield {
-            if cy.trim().is_empty() || cy.trim() == "0" {
+            let shares = Decimal::from_str(&record.shares).map_err(|_| {
+                anyhow!(
+                    "Invalid shares value for {}: {}",
+                    record.symbol,
+                    record.shares
+                )
+            })?;
+
+            let cost_basis = if let Some(cb) = record.cost_basis {
+                if cb.trim().is_empty() || cb.trim() == "0" {
+                    None
+                } else {
+                    Some(
+                        Decimal::from_str(&cb)
+                            .map_err(|_| anyhow!("Invalid cost basis for {}: {}", record.symbol, cb))?,
+                    )
+                }
+            } else {
                 None
+            };
+
+            let current_yield = if let Some(cy) = record.current_yield {
+                if cy.trim().is_empty() || cy.trim() == "0" {
+                    None
+                } else {
+                    Some(
+                        Decimal::from_str(&cy)
+                            .map_err(|_| anyhow!("Invalid yield for {}: {}", record.symbol, cy))?,
+                    )
+                }
             } else {
-                Some(
-                    Decimal::from_str(&cy)
-                        .map_err(|_| anyhow!("Invalid yield for {}: {}", record.symbol, cy))?,
-                )
-            }
-        } else {
-            None
-        };
+                None
+            };
 
-        let holding = Holding::new(record.symbol.clone(), shares, cost_basis, current_yield)?;
+            let mut holding = Holding::new(record.symbol.clone(), shares, cost_basis, current_yield)?;
+            holding.set_identifiers(isin, cusip)?;
+            let row_account = record.account.as_deref().filter(|a| !a.trim().is_empty());
+            if let Some(account) = row_account.or(account) {
+                holding.set_account(account);
+            }
 
-        let symbol_upper = record.symbol.trim().to_uppercase();
-        let is_update = tracker.holdings.contains_key(&symbol_upper);
+            place(tracker, &mut holding)?;
+            let label = holding.label();
+            let is_update = tracker.holdings.contains_key(&holding.key());
 
-        tracker.add_holding(holding);
+            tracker.add_holding(holding);
 
-        if is_update {
-            updated_count += 1;
-            println!("  {} {} shares", "Updated".yellow(), symbol_upper.cyan());
-        } else {
-            imported_count += 1;
-            println!("  {} {} shares", "Imported".green(), symbol_upper.cyan());
+            if is_update {
+                updated_count += 1;
+                pb.suspend(|| println!("  {} {} shares", "Updated".yellow(), label.cyan()));
+            } else {
+                imported_count += 1;
+                pb.suspend(|| println!("  {} {} shares", "Imported".green(), label.cyan()));
+            }
         }
-    }
 
-    save_holdings(&tracker)?;
+        Ok((imported_count, updated_count))
+    });
+    pb.finish_and_clear();
+    let (imported_count, updated_count) = result?;
 
     println!();
     println!("{}", "Import completed successfully!".green().bold());
@@ -114,29 +160,38 @@ pub fn import_holdings(file_path: &str) -> Result<()> {
 }
 
 /// Add or update a holding
-pub fn add_holding(
-    symbol: &str,
-    shares: Decimal,
-    cost_basis: Option<Decimal>,
-    current_yield: Option<Decimal>,
-) -> Result<()> {
-    let mut tracker = load_holdings()?;
-    let holding = Holding::new(symbol.to_string(), shares, cost_basis, current_yield)?;
-
-    let symbol_upper = symbol.trim().to_uppercase();
-    let is_update = tracker.holdings.contains_key(&symbol_upper);
+pub fn add_holding(persistence: &PersistenceManager, mut holding: Holding) -> Result<()> {
+    let (shares, cost_basis, current_yield) = (holding.shares, holding.avg_cost_basis, holding.current_yield);
+    let (holding_isin, holding_cusip) = (holding.isin.clone(), holding.cusip.clone());
+
+    let (is_update, label, holding_account) = persistence.with_tracker_mut(|tracker| {
+        holding.symbol = tracker.canonical_symbol(&holding.symbol);
+        let symbol_upper = holding.symbol.clone();
+
+        // The same security must not be tracked under two tickers
+        for id in holding.isin.iter().chain(&holding.cusip) {
+            if let Some(other) = tracker.find_holding_by_identifier(id) {
+                if other.symbol != symbol_upper {
+                    return Err(anyhow!("{} already identifies the {} holding", id, other.symbol));
+                }
+            }
+        }
 
-    tracker.add_holding(holding);
-    save_holdings(&tracker)?;
+        place(tracker, &mut holding)?;
+        let is_update = tracker.holdings.contains_key(&holding.key());
+        let (label, account) = (holding.label(), holding.account.clone());
+        tracker.add_holding(holding);
+        Ok((is_update, label, account))
+    })?;
 
     if is_update {
         println!(
             "{} Updated holding for {}",
             "✓".green(),
-            symbol_upper.cyan()
+            label.cyan()
         );
     } else {
-        println!("{} Added holding for {}", "✓".green(), symbol_upper.cyan());
+        println!("{} Added holding for {}", "✓".green(), label.cyan());
     }
 
     println!("  Shares: {}", shares.to_string().yellow());
@@ -146,162 +201,361 @@ pub fn add_holding(
     if let Some(cy) = current_yield {
         println!("  Current Yield: {}%", cy.to_string().yellow());
     }
+    if let Some(isin) = &holding_isin {
+        println!("  ISIN: {}", isin.yellow());
+    }
+    if let Some(cusip) = &holding_cusip {
+        println!("  CUSIP: {}", cusip.yellow());
+    }
+    if let Some(account) = &holding_account {
+        println!("  Account: {}", account.yellow());
+    }
 
     Ok(())
 }
 
-/// Remove a holding
-pub fn remove_holding(symbol: &str) -> Result<()> {
-    let mut tracker = load_holdings()?;
-    let symbol_upper = symbol.trim().to_uppercase();
+/// Put a holding in the account of the position it updates: the symbol's
+/// only position when no account was given, so updating it doesn't start a
+/// second one, and the account as first written when one was
+fn place(tracker: &DividendTracker, holding: &mut Holding) -> Result<()> {
+    if let Some(key) = tracker.position_key(&holding.symbol, holding.account.as_deref())? {
+        holding.account = tracker.holdings[&key].account.clone();
+    }
+    Ok(())
+}
 
-    if tracker.holdings.remove(&symbol_upper).is_some() {
-        save_holdings(&tracker)?;
-        println!(
-            "{} Removed holding for {}",
-            "✓".green(),
-            symbol_upper.cyan()
-        );
-    } else {
+/// Set or clear the forward dividend override of an existing holding
+pub fn set_forward_dividend(
+    persistence: &PersistenceManager,
+    symbol: &str,
+    account: Option<&str>,
+    forward: Option<ForwardDividend>,
+) -> Result<()> {
+    let symbol_upper = persistence.with_tracker_mut(|tracker| {
+        let symbol_upper = tracker.canonical_symbol(symbol);
+        let Some(key) = tracker.position_key(&symbol_upper, account)? else {
+            return Err(anyhow!("{} Add it with 'holdings add' first.", no_holding(tracker, &symbol_upper, account)));
+        };
+        let holding = tracker.holdings.get_mut(&key).expect("holding exists");
+        holding.forward_dividend = forward.clone();
+        Ok(holding.label())
+    })?;
+
+    match forward {
+        Some(forward) => {
+            println!(
+                "{} Set forward dividend for {}",
+                "✓".green(),
+                symbol_upper.cyan()
+            );
+            println!(
+                "  Annual: ${} per share, {:?} (${} per payment)",
+                forward.annual_amount.to_string().yellow(),
+                forward.frequency,
+                forward.per_payment().round_dp(4).to_string().yellow()
+            );
+        }
+        None => {
+            println!(
+                "{} Cleared forward dividend for {}",
+                "✓".green(),
+                symbol_upper.cyan()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Buy or sell shares of an existing holding and record the adjustment
+pub fn update_holding(
+    persistence: &PersistenceManager,
+    symbol: &str,
+    account: Option<&str>,
+    shares_delta: Decimal,
+    price: Option<Decimal>,
+    date: NaiveDate,
+) -> Result<()> {
+    let (before, after) = persistence.with_tracker_mut(|tracker| {
+        let symbol_upper = tracker.canonical_symbol(symbol);
+        let Some(key) = tracker.position_key(&symbol_upper, account)? else {
+            return Err(anyhow!("{} Add it with 'holdings add' first.", no_holding(tracker, &symbol_upper, account)));
+        };
+        let holding = tracker.holdings.get_mut(&key).expect("holding exists");
+        let before = holding.clone();
+        holding.adjust(shares_delta, price, date)?;
+        Ok((before, holding.clone()))
+    })?;
+
+    println!("{} Updated holding for {}", "✓".green(), after.label().cyan());
+    println!(
+        "  Shares: {} -> {}",
+        before.shares,
+        after.shares.to_string().yellow()
+    );
+    if before.avg_cost_basis.is_some() || after.avg_cost_basis.is_some() {
+        let format_cost = |cost: Option<Decimal>| cost.map(|c| format!("${}", c)).unwrap_or_else(|| "N/A".to_string());
         println!(
-            "{} No holding found for {}",
-            "⚠".yellow(),
-            symbol_upper.cyan()
+            "  Cost Basis: {} -> {}",
+            format_cost(before.avg_cost_basis),
+            format_cost(after.avg_cost_basis).yellow()
         );
     }
 
     Ok(())
 }
 
-/// Table display structure for holdings
-#[derive(Tabled)]
-struct HoldingDisplay {
-    #[tabled(rename = "Symbol")]
-    symbol: String,
-    #[tabled(rename = "Shares")]
-    shares: String,
-    #[tabled(rename = "Cost Basis")]
-    cost_basis: String,
-    #[tabled(rename = "Current Yield")]
-    current_yield: String,
-    #[tabled(rename = "Total Value")]
-    total_value: String,
+/// Build the adjustment history report for one holding
+pub fn history_report(tracker: &DividendTracker, symbol: &str, account: Option<&str>) -> Result<Report> {
+    let symbol_upper = tracker.canonical_symbol(symbol);
+    let key = tracker
+        .position_key(&symbol_upper, account)?
+        .ok_or_else(|| anyhow!(no_holding(tracker, &symbol_upper, account)))?;
+    let holding = &tracker.holdings[&key];
+    let title = format!("Holding History: {}", holding.label());
+
+    if holding.adjustments.is_empty() {
+        return Ok(Report::warning(
+            title,
+            "No adjustments recorded. Use 'holdings update' to buy or sell shares.",
+        ));
+    }
+
+    let display = Config::load().unwrap_or_default().display;
+    let mut table = TableView::new(&["Date", "Change", "Price", "Shares After", "Avg Cost After"]);
+    for adjustment in &holding.adjustments {
+        let change = if adjustment.shares_delta.is_zero() {
+            "cost basis".to_string()
+        } else if adjustment.shares_delta > Decimal::ZERO {
+            format!("+{}", format_shares(adjustment.shares_delta, &display))
+        } else {
+            format_shares(adjustment.shares_delta, &display)
+        };
+        table.row(vec![
+            adjustment.date.format("%Y-%m-%d").to_string().into(),
+            change.into(),
+            adjustment
+                .price
+                .map(|p| format!("${}", p))
+                .unwrap_or_else(|| "-".to_string())
+                .into(),
+            format_shares(adjustment.shares_after, &display).into(),
+            adjustment
+                .avg_cost_after
+                .map(|c| format!("${:.2}", c))
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+        ]);
+    }
+
+    Ok(Report::new(title).section(Section::untitled().table(table)))
 }
 
-/// List all holdings
-pub fn list_holdings(sort_by: Option<&str>, desc: bool) -> Result<()> {
-    let tracker = load_holdings()?;
+/// Close out a holding but keep it, with its cost basis and history, as archived
+pub fn archive_holding(persistence: &PersistenceManager, symbol: &str, account: Option<&str>, date: NaiveDate) -> Result<()> {
+    let (symbol_upper, sold) = persistence.with_tracker_mut(|tracker| {
+        let symbol_upper = tracker.canonical_symbol(symbol);
+        let key = tracker
+            .position_key(&symbol_upper, account)?
+            .ok_or_else(|| anyhow!(no_holding(tracker, &symbol_upper, account)))?;
+        let holding = tracker.holdings.get_mut(&key).expect("holding exists");
+        let shares = holding.shares;
+        holding.archive(date)?;
+        Ok((holding.label(), shares))
+    })?;
 
-    if tracker.holdings.is_empty() {
-        println!(
-            "{}",
-            "No holdings found. Use 'holdings add' to add some!".yellow()
-        );
-        return Ok(());
-    }
+    println!(
+        "{} Archived holding for {} ({} shares closed on {})",
+        "✓".green(),
+        symbol_upper.cyan(),
+        sold,
+        date.format("%Y-%m-%d")
+    );
+    println!("  Its dividend history stays in reports; projections leave it out.");
 
-    println!("{}", "Portfolio Holdings".green().bold());
-    println!();
+    Ok(())
+}
 
-    let mut holdings: Vec<_> = tracker.holdings.values().collect();
-
-    // Sort holdings based on the specified field
-    match sort_by {
-        Some("symbol") => holdings.sort_by(|a, b| a.symbol.cmp(&b.symbol)),
-        Some("shares") => holdings.sort_by(|a, b| a.shares.cmp(&b.shares)),
-        Some("yield") => holdings.sort_by(|a, b| match (a.current_yield, b.current_yield) {
-            (Some(a_yield), Some(b_yield)) => a_yield.cmp(&b_yield),
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            (None, Some(_)) => std::cmp::Ordering::Less,
-            (None, None) => std::cmp::Ordering::Equal,
-        }),
-        Some("value") => holdings.sort_by(|a, b| {
-            let a_value = a.avg_cost_basis.map(|cb| cb * a.shares);
-            let b_value = b.avg_cost_basis.map(|cb| cb * b.shares);
-            match (a_value, b_value) {
-                (Some(a_val), Some(b_val)) => a_val.cmp(&b_val),
-                (Some(_), None) => std::cmp::Ordering::Greater,
-                (None, Some(_)) => std::cmp::Ordering::Less,
-                (None, None) => std::cmp::Ordering::Equal,
+/// Remove a holding
+pub fn remove_holding(persistence: &PersistenceManager, symbol: &str, account: Option<&str>) -> Result<()> {
+    let (symbol_upper, missing) = persistence.with_tracker_mut(|tracker| {
+        let symbol_upper = tracker.canonical_symbol(symbol);
+        match tracker.position_key(&symbol_upper, account)? {
+            Some(key) => {
+                let holding = tracker.holdings.remove(&key).expect("holding exists");
+                Ok((holding.label(), None))
             }
-        }),
-        _ => holdings.sort_by(|a, b| a.symbol.cmp(&b.symbol)), // Default sort by symbol
+            None => Ok((symbol_upper.clone(), Some(no_holding(tracker, &symbol_upper, account)))),
+        }
+    })?;
+
+    match missing {
+        None => println!(
+            "{} Removed holding for {}",
+            "✓".green(),
+            symbol_upper.cyan()
+        ),
+        Some(message) => println!("{} {}", "⚠".yellow(), message),
     }
 
-    if desc {
-        holdings.reverse();
+    Ok(())
+}
+
+/// "No holding found" message, suggesting a held symbol that looks like a typo of it
+fn no_holding(tracker: &DividendTracker, symbol: &str, account: Option<&str>) -> String {
+    let message = match account {
+        Some(account) => format!("No holding found for {} in {}.", symbol, account),
+        None => format!("No holding found for {}.", symbol),
+    };
+    match did_you_mean(symbol, tracker.holdings.keys().map(|key| key.symbol.as_str())) {
+        Some(hint) => format!("{} {}", message, hint),
+        None => message,
     }
+}
 
-    let display_holdings: Vec<HoldingDisplay> = holdings
+/// Build the holdings list report view
+pub fn holdings_report(
+    tracker: &DividendTracker,
+    sort_by: &SortSpec<HoldingsSortField>,
+    desc: bool,
+    include_archived: bool,
+) -> Report {
+    const TITLE: &str = "Portfolio Holdings";
+
+    let mut holdings: Vec<_> = tracker
+        .holdings
         .iter()
-        .map(|h| HoldingDisplay {
-            symbol: h.symbol.clone(),
-            shares: h.shares.to_string(),
-            cost_basis: h
-                .avg_cost_basis
-                .map(|cb| format!("${:.2}", cb))
-                .unwrap_or_else(|| "N/A".to_string()),
-            current_yield: h
-                .current_yield
-                .map(|cy| format!("{:.2}%", cy))
-                .unwrap_or_else(|| "N/A".to_string()),
-            total_value: h
-                .avg_cost_basis
-                .map(|cb| format!("${:.2}", cb * h.shares))
-                .unwrap_or_else(|| "N/A".to_string()),
-        })
+        .filter(|(_, h)| include_archived || !h.is_closed())
         .collect();
+    // Positions that tie on every sort key stay in symbol and account order
+    holdings.sort_by(|a, b| a.0.cmp(b.0));
+    let mut holdings: Vec<&Holding> = holdings.into_iter().map(|(_, h)| h).collect();
 
-    let table = Table::new(display_holdings);
-    println!("{}", table);
+    if holdings.is_empty() {
+        return Report::warning(TITLE, "No holdings found. Use 'holdings add' to add some!");
+    }
 
-    Ok(())
+    // Sort holdings by each key in turn; holdings missing a value sort first
+    let sort = if desc { sort_by.reversed() } else { sort_by.clone() };
+    holdings.sort_by(|a, b| {
+        sort.compare(a, b, |field, a, b| match field {
+            HoldingsSortField::Symbol => a.key().cmp(&b.key()),
+            HoldingsSortField::Shares => a.shares.cmp(&b.shares),
+            HoldingsSortField::Yield => a.current_yield.cmp(&b.current_yield),
+            HoldingsSortField::Value => {
+                let a_value = a.avg_cost_basis.map(|cb| cb * a.shares);
+                let b_value = b.avg_cost_basis.map(|cb| cb * b.shares);
+                a_value.cmp(&b_value)
+            }
+        })
+    });
+
+    // Name and sector columns only appear when securities.toml pins some
+    let securities = &tracker.securities;
+    let show_names = holdings.iter().any(|h| securities.name(&h.symbol).is_some());
+    let show_sectors = holdings.iter().any(|h| securities.sector(&h.symbol).is_some());
+    let show_accounts = holdings.iter().any(|h| h.account.is_some());
+    let mut headers = vec!["Symbol"];
+    if show_names {
+        headers.push("Name");
+    }
+    if show_sectors {
+        headers.push("Sector");
+    }
+    headers.extend(["Shares", "Cost Basis", "Current Yield", "Total Value"]);
+    if show_accounts {
+        headers.push("Account");
+    }
+
+    let display = Config::load().unwrap_or_default().display;
+    let mut table = TableView::new(&headers);
+    for h in holdings {
+        let shares = match h.closed_date {
+            Some(closed) => Text::new(format!("closed {}", closed.format("%Y-%m-%d")), Tone::Warning),
+            None => format_shares(h.shares, &display).into(),
+        };
+        let mut row = vec![h.symbol.clone().into()];
+        if show_names {
+            row.push(securities.name(&h.symbol).unwrap_or("-").into());
+        }
+        if show_sectors {
+            row.push(securities.sector(&h.symbol).unwrap_or("-").into());
+        }
+        row.extend([
+            shares,
+            h.avg_cost_basis
+                .map(|cb| format!("${:.2}", cb))
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+            h.current_yield
+                .map(|cy| format_percent(cy, 2))
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+            h.avg_cost_basis
+                .map(|cb| format!("${:.2}", cb * h.shares))
+                .unwrap_or_else(|| "N/A".to_string())
+                .into(),
+        ]);
+        if show_accounts {
+            row.push(h.account_name().into());
+        }
+        table.row(row);
+    }
+
+    Report::new(TITLE).section(Section::untitled().table(table))
 }
 
 /// Export holdings to CSV
-pub fn export_holdings(output_path: &str) -> Result<()> {
-    let tracker = load_holdings()?;
+pub fn export_holdings(persistence: &PersistenceManager, output_path: &Path) -> Result<()> {
+    let tracker = persistence.load()?;
 
-    if tracker.holdings.is_empty() {
+    // Archived positions have no shares to import back
+    let holdings: Vec<&Holding> = tracker.open_holdings().map(|(_, h)| h).collect();
+    if holdings.is_empty() {
         println!("{}", "No holdings to export.".yellow());
         return Ok(());
     }
 
-    let mut writer = Writer::from_path(output_path)?;
+    let mut writer = Writer::from_writer(paths::create_file(output_path)?);
 
     // Write header
-    writer.write_record(&["symbol", "shares", "cost_basis", "current_yield"])?;
+    writer.write_record(["symbol", "shares", "cost_basis", "current_yield", "isin", "cusip", "account"])?;
 
-    for holding in tracker.holdings.values() {
+    let pb = progress::bar(holdings.len(), "Exporting holdings");
+    for holding in pb.wrap_iter(holdings.iter()) {
         let record = HoldingRecord {
             symbol: holding.symbol.clone(),
             shares: holding.shares.to_string(),
             cost_basis: holding.avg_cost_basis.map(|cb| cb.to_string()),
             current_yield: holding.current_yield.map(|cy| cy.to_string()),
+            isin: holding.isin.clone(),
+            cusip: holding.cusip.clone(),
+            account: holding.account.clone(),
         };
         writer.serialize(&record)?;
     }
+    pb.finish_and_clear();
 
     writer.flush()?;
 
     println!(
         "{} Holdings exported to {}",
         "✓".green(),
-        output_path.cyan()
+        output_path.display().to_string().cyan()
     );
     println!(
         "  Exported {} holdings",
-        tracker.holdings.len().to_string().yellow()
+        holdings.len().to_string().yellow()
     );
 
     Ok(())
 }
 
 /// Show portfolio summary
-pub fn show_summary(include_yield: bool) -> Result<()> {
-    let tracker = load_holdings()?;
+pub fn show_summary(persistence: &PersistenceManager, include_yield: bool) -> Result<()> {
+    let tracker = persistence.load()?;
 
-    if tracker.holdings.is_empty() {
+    if tracker.open_holdings().next().is_none() {
         println!(
             "{}",
             "No holdings found. Use 'holdings add' to add some!".yellow()
@@ -312,14 +566,14 @@ pub fn show_summary(include_yield: bool) -> Result<()> {
     println!("{}", "Portfolio Holdings Summary".green().bold());
     println!();
 
-    let total_positions = tracker.holdings.len();
+    let total_positions = tracker.open_holdings().count();
     let mut total_shares = Decimal::ZERO;
     let mut total_value = Decimal::ZERO;
     let mut positions_with_cost_basis = 0;
     let mut positions_with_yield = 0;
     let mut weighted_yield = Decimal::ZERO;
 
-    for holding in tracker.holdings.values() {
+    for (_, holding) in tracker.open_holdings() {
         total_shares += holding.shares;
 
         if let Some(cost_basis) = holding.avg_cost_basis {
@@ -369,9 +623,9 @@ pub fn show_summary(include_yield: bool) -> Result<()> {
     if include_yield && positions_with_yield > 0 && total_value > Decimal::ZERO {
         let avg_yield = weighted_yield / total_value;
         println!(
-            "📊 {} {:.2}%",
+            "📊 {} {}",
             "Weighted Average Yield:".bright_blue(),
-            avg_yield.to_string().green()
+            format_percent(avg_yield, 2).green()
         );
         println!(
             "🎯 {} {} of {}",
@@ -387,8 +641,48 @@ pub fn show_summary(include_yield: bool) -> Result<()> {
         );
     }
 
+
+    // Forward income: latest regular dividend x frequency x shares
+    let today = Local::now().naive_local().date();
+    let forward = ForwardIncome::calculate(&tracker, today);
+    println!(
+        "💵 {} {} ({}/month)",
+        "Forward Annual Income:".bright_blue(),
+        format_money(forward.total()).green(),
+        format_money(forward.monthly())
+    );
+    if !forward.not_paying.is_empty() {
+        println!(
+            "   {} No recent dividend or forward rate for {}",
+            "ℹ".blue(),
+            forward.not_paying.join(", ")
+        );
+    }
+
     println!();
 
+    // Month-end run-rate over the past year, oldest first
+    let history: Vec<_> = run_rate::monthly_history(&tracker, 12, today)
+        .into_iter()
+        .skip_while(|(_, income)| income.is_zero())
+        .collect();
+    if history.len() > 1 {
+        println!("{}", "📈 Forward Income by Month:".bright_blue());
+        let mut previous: Option<Decimal> = None;
+        for (date, income) in &history {
+            let change = match previous {
+                Some(previous) if !previous.is_zero() && *income != previous => {
+                    let pct = (*income - previous) / previous * Decimal::from(100);
+                    format!(" ({})", format_signed_percent(pct, 1))
+                }
+                _ => String::new(),
+            };
+            println!("  {}  {}{}", date.format("%Y-%m-%d"), format_money(*income), change);
+            previous = Some(*income);
+        }
+        println!();
+    }
+
     // Show top 5 holdings by value
     if positions_with_cost_basis > 0 {
         let mut holdings_by_value: Vec<_> = tracker
@@ -408,41 +702,14 @@ pub fn show_summary(include_yield: bool) -> Result<()> {
             let value = holding.avg_cost_basis.unwrap() * holding.shares;
             let percentage = (value / total_value) * rust_decimal::Decimal::from(100);
             println!(
-                "  {}. {} - ${:.2} ({:.1}%)",
+                "  {}. {} - {} ({})",
                 (i + 1).to_string().cyan(),
-                holding.symbol.green(),
-                value.to_string().yellow(),
-                percentage.to_string().blue()
+                holding.label().green(),
+                format_money(value).yellow(),
+                format_percent(percentage, 1).blue()
             );
         }
     }
 
     Ok(())
 }
-
-/// Validate if a dividend matches any holdings (used by dividend commands)
-pub fn validate_dividend_against_holdings(symbol: &str, shares: Decimal) -> Result<bool> {
-    let tracker = load_holdings()?;
-    let symbol_upper = symbol.trim().to_uppercase();
-
-    if let Some(holding) = tracker.holdings.get(&symbol_upper) {
-        if shares > holding.shares {
-            println!(
-                "{} Warning: Dividend for {} shares of {} exceeds your holding of {} shares",
-                "⚠".yellow(),
-                shares.to_string().red(),
-                symbol_upper.cyan(),
-                holding.shares.to_string().green()
-            );
-            return Ok(false);
-        }
-        Ok(true)
-    } else {
-        println!(
-            "{} Warning: No holding found for {}. Consider adding it with 'holdings add'",
-            "⚠".yellow(),
-            symbol_upper.cyan()
-        );
-        Ok(false)
-    }
-}
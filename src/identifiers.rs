@@ -0,0 +1,139 @@
+//! ISIN and CUSIP security identifiers
+//!
+//! Brokers outside the US key securities by ISIN rather than ticker, and US
+//! statements often carry the CUSIP. Both end in a check digit, so a typo is
+//! caught when the identifier is recorded rather than when a later import
+//! fails to match. Country codes are the two letters an ISIN starts with.
+
+use anyhow::{bail, Result};
+
+/// Value of an identifier character: digits as themselves, letters from 10
+fn char_value(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 10),
+        _ => None,
+    }
+}
+
+/// Validate an ISIN (e.g. US0378331005) and return it in upper case
+pub fn parse_isin(value: &str) -> Result<String> {
+    let isin = value.trim().to_uppercase();
+    let chars: Vec<char> = isin.chars().collect();
+
+    if chars.len() != 12
+        || !chars[..2].iter().all(|c| c.is_ascii_uppercase())
+        || !chars[2..11].iter().all(|c| c.is_ascii_alphanumeric())
+        || !chars[11].is_ascii_digit()
+    {
+        bail!(
+            "Invalid ISIN: {}. Use 2 country letters, 9 characters and a check digit like US0378331005",
+            value.trim()
+        );
+    }
+
+    // Letters expand to two digits, then the whole string must pass Luhn
+    let digits: Vec<u32> = chars
+        .iter()
+        .filter_map(|&c| char_value(c))
+        .flat_map(|v| if v >= 10 { vec![v / 10, v % 10] } else { vec![v] })
+        .collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+
+    if !sum.is_multiple_of(10) {
+        bail!("Invalid ISIN: {} (check digit does not match)", isin);
+    }
+
+    Ok(isin)
+}
+
+/// Validate a CUSIP (e.g. 037833100) and return it in upper case
+pub fn parse_cusip(value: &str) -> Result<String> {
+    let cusip = value.trim().to_uppercase();
+    let chars: Vec<char> = cusip.chars().collect();
+
+    if chars.len() != 9 || !chars[8].is_ascii_digit() {
+        bail!("Invalid CUSIP: {}. Use 8 characters and a check digit like 037833100", value.trim());
+    }
+
+    let mut sum = 0;
+    for (i, &c) in chars[..8].iter().enumerate() {
+        let mut v = match c {
+            '*' => 36,
+            '@' => 37,
+            '#' => 38,
+            _ => match char_value(c) {
+                Some(v) => v,
+                None => bail!("Invalid CUSIP: {} (unexpected character '{}')", cusip, c),
+            },
+        };
+        if i % 2 == 1 {
+            v *= 2;
+        }
+        sum += v / 10 + v % 10;
+    }
+
+    if (10 - sum % 10) % 10 != chars[8].to_digit(10).unwrap_or(10) {
+        bail!("Invalid CUSIP: {} (check digit does not match)", cusip);
+    }
+
+    Ok(cusip)
+}
+
+/// Validate a two-letter country code, e.g. `ch` becomes `CH`
+pub fn parse_country(value: &str) -> Result<String> {
+    let country = value.trim().to_uppercase();
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        bail!("Invalid country: {}. Use a two-letter code like CH or CA", value.trim());
+    }
+    Ok(country)
+}
+
+/// Country of the issuer an ISIN names; `XS` and other international
+/// prefixes name none
+pub fn isin_country(isin: &str) -> Option<&str> {
+    isin.get(..2).filter(|prefix| !prefix.starts_with('X') && *prefix != "EU")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_isin() {
+        assert_eq!(parse_isin(" us0378331005 ").unwrap(), "US0378331005");
+        assert_eq!(parse_isin("CH0038863350").unwrap(), "CH0038863350");
+        assert!(parse_isin("US0378331006").is_err());
+        assert!(parse_isin("AAPL").is_err());
+        assert!(parse_isin("120378331005").is_err());
+    }
+
+    #[test]
+    fn test_parse_country() {
+        assert_eq!(parse_country(" ch ").unwrap(), "CH");
+        assert!(parse_country("CHE").is_err());
+        assert!(parse_country("C1").is_err());
+        assert_eq!(isin_country("CH0038863350"), Some("CH"));
+        assert_eq!(isin_country("XS1234567890"), None);
+    }
+
+    #[test]
+    fn test_parse_cusip() {
+        assert_eq!(parse_cusip("037833100").unwrap(), "037833100");
+        assert_eq!(parse_cusip("594918104").unwrap(), "594918104");
+        assert!(parse_cusip("037833101").is_err());
+        assert!(parse_cusip("03783310").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Consumer price index for inflation-adjusted income
+//!
+//! Income is deflated to constant dollars by scaling each year's total by
+//! `CPI(base year) / CPI(year)`. The built-in table holds US CPI-U annual
+//! averages (1982-84 = 100); a CSV of `year,cpi` rows replaces it for other
+//! countries or newer figures. Years after the last index carry it forward,
+//! which treats them as having no inflation yet.
+
+use anyhow::{anyhow, bail, Context, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// US CPI-U, all items, annual average (BLS series CUUR0000SA0)
+const US_CPI_U: [(i32, Decimal); 25] = [
+    (2000, dec!(172.2)),
+    (2001, dec!(177.1)),
+    (2002, dec!(179.9)),
+    (2003, dec!(184.0)),
+    (2004, dec!(188.9)),
+    (2005, dec!(195.3)),
+    (2006, dec!(201.6)),
+    (2007, dec!(207.342)),
+    (2008, dec!(215.303)),
+    (2009, dec!(214.537)),
+    (2010, dec!(218.056)),
+    (2011, dec!(224.939)),
+    (2012, dec!(229.594)),
+    (2013, dec!(232.957)),
+    (2014, dec!(236.736)),
+    (2015, dec!(237.017)),
+    (2016, dec!(240.007)),
+    (2017, dec!(245.120)),
+    (2018, dec!(251.107)),
+    (2019, dec!(255.657)),
+    (2020, dec!(258.811)),
+    (2021, dec!(270.970)),
+    (2022, dec!(292.655)),
+    (2023, dec!(304.702)),
+    (2024, dec!(313.689)),
+];
+
+/// Annual price index by year
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpiTable {
+    /// Where the figures came from, for the report footnote
+    pub source: String,
+    pub index: BTreeMap<i32, Decimal>,
+}
+
+impl CpiTable {
+    /// The built-in US CPI-U table
+    pub fn builtin() -> Self {
+        CpiTable {
+            source: format!(
+                "US CPI-U annual averages {}-{} (built in)",
+                US_CPI_U[0].0,
+                US_CPI_U[US_CPI_U.len() - 1].0
+            ),
+            index: US_CPI_U.into_iter().collect(),
+        }
+    }
+
+    /// Read a CSV of `year,cpi` rows; a header row is skipped
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .with_context(|| format!("Failed to open CPI file {}", path.display()))?;
+
+        let mut index = BTreeMap::new();
+        for (line, record) in reader.records().enumerate() {
+            let record = record?;
+            let (Some(year), Some(cpi)) = (record.get(0), record.get(1)) else {
+                bail!("Line {} of {}: expected 'year,cpi'", line + 1, path.display());
+            };
+            let Ok(year) = year.parse::<i32>() else {
+                if line == 0 {
+                    continue;
+                }
+                bail!("Line {} of {}: invalid year '{}'", line + 1, path.display(), year);
+            };
+            let cpi = Decimal::from_str(cpi)
+                .ok()
+                .filter(|cpi| cpi.is_sign_positive() && !cpi.is_zero())
+                .ok_or_else(|| anyhow!("Line {} of {}: invalid CPI '{}'", line + 1, path.display(), cpi))?;
+            index.insert(year, cpi);
+        }
+
+        if index.is_empty() {
+            bail!("No CPI values found in {}", path.display());
+        }
+
+        Ok(CpiTable {
+            source: format!("CPI from {}", path.display()),
+            index,
+        })
+    }
+
+    /// Last year with an index value
+    pub fn latest_year(&self) -> Option<i32> {
+        self.index.keys().next_back().copied()
+    }
+
+    /// Index for `year`, carrying the latest value forward past the end of the table
+    pub fn index_for(&self, year: i32) -> Option<Decimal> {
+        match self.index.get(&year) {
+            Some(cpi) => Some(*cpi),
+            None if self.latest_year().is_some_and(|latest| year > latest) => {
+                self.index.values().next_back().copied()
+            }
+            None => None,
+        }
+    }
+
+    /// `amount` earned in `year` expressed in `base_year` dollars
+    pub fn to_constant_dollars(&self, amount: Decimal, year: i32, base_year: i32) -> Option<Decimal> {
+        let from = self.index_for(year)?;
+        let to = self.index_for(base_year)?;
+        Some(amount * to / from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpi_table_deflates_and_carries_forward() {
+        let table = CpiTable::builtin();
+        assert_eq!(table.latest_year(), Some(2024));
+        assert_eq!(table.index_for(2026), Some(dec!(313.689)));
+        assert_eq!(table.index_for(1999), None);
+
+        let real = table.to_constant_dollars(dec!(100), 2020, 2024).unwrap();
+        assert_eq!(real.round_dp(2), dec!(121.20));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cpi.csv");
+        std::fs::write(&path, "year,cpi\n2022,100\n2023, 110\n").unwrap();
+        let table = CpiTable::load(&path).unwrap();
+        assert_eq!(table.to_constant_dollars(dec!(50), 2022, 2023), Some(dec!(55)));
+
+        std::fs::write(&path, "year,cpi\n2022,abc\n").unwrap();
+        assert!(CpiTable::load(&path).is_err());
+    }
+}
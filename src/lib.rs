@@ -0,0 +1,65 @@
+//! Dividend tracking as a library
+//!
+//! The `dividend-tracker` binary is a thin layer over this crate, and the same
+//! engine can be used from other Rust programs:
+//!
+//! - [`models`]: dividends, holdings and the [`DividendTracker`](models::DividendTracker) that holds them
+//! - [`persistence`]: loading and saving a data directory, with backups
+//! - [`analytics`]: yearly, monthly and per-symbol income statistics
+//! - [`projections`]: income projections and saved plans
+//! - [`tax`]: tax summaries, reports and estimates
+//!
+//! ```no_run
+//! use dividend_tracker::persistence::PersistenceManager;
+//! use dividend_tracker::tax::TaxAnalyzer;
+//!
+//! let tracker = PersistenceManager::new()?.load()?;
+//! let summary = TaxAnalyzer::generate_tax_summary(&tracker, 2024, None)?;
+//! println!("Qualified dividends in 2024: {}", summary.qualified_dividends);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! These five modules, and the modules whose types appear in their
+//! signatures, are the public API. [`cli`] is the command line itself and may
+//! change with any release.
+
+pub mod analytics;
+pub mod models;
+pub mod persistence;
+pub mod projections;
+pub mod tax;
+
+// Types the public API is built from
+pub mod analytics_cache;
+pub mod config;
+pub mod corporate_actions;
+pub mod diff;
+pub mod events;
+pub mod inflation;
+pub mod render;
+pub mod securities;
+
+pub mod cli;
+
+mod api;
+mod calendar_view;
+mod classify;
+mod filters;
+mod format;
+#[cfg(feature = "graphql")]
+mod graphql;
+mod holdings;
+mod identifiers;
+mod lots;
+mod notifications;
+mod paths;
+mod periods;
+mod progress;
+mod reconcile;
+mod run_rate;
+mod server;
+#[cfg(test)]
+mod stress;
+mod symbols;
+mod tokens;
+mod yield_on_cost;
@@ -0,0 +1,281 @@
+//! Broker lot-level exports (`holdings import-lots`)
+//!
+//! A lot export lists every purchase still held: symbol, purchase date,
+//! shares and price per share, optionally with the broker's lot ID. Importing
+//! one rebuilds each listed holding from its lots, recording every purchase as
+//! a holding adjustment, and keeps the lots so dividends can be attributed to
+//! them at tax time.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fs;
+use std::str::FromStr;
+
+use crate::models::{DividendTracker, Holding, TaxLot};
+use crate::symbols::normalize_symbol;
+
+/// Header spellings accepted for each column, after lowercasing and
+/// replacing spaces and punctuation with underscores
+const SYMBOL_COLUMNS: &[&str] = &["symbol", "ticker", "security"];
+const DATE_COLUMNS: &[&str] = &["purchase_date", "date_acquired", "acquired", "open_date", "trade_date", "date"];
+const SHARES_COLUMNS: &[&str] = &["shares", "quantity", "qty"];
+const PRICE_COLUMNS: &[&str] = &["price", "price_per_share", "cost_per_share", "unit_cost", "cost_basis_per_share"];
+const TOTAL_COST_COLUMNS: &[&str] = &["cost_basis", "total_cost", "cost"];
+const LOT_ID_COLUMNS: &[&str] = &["lot_id", "lot", "lot_number"];
+
+/// Date formats found in broker exports
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%m/%d/%Y", "%Y/%m/%d"];
+
+/// One lot read from an export, before it is matched to a holding
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedLot {
+    pub symbol: String,
+    pub lot: TaxLot,
+}
+
+/// What importing lots did to one holding
+#[derive(Debug, Clone, PartialEq)]
+pub struct LotImport {
+    pub symbol: String,
+    pub lots: usize,
+    pub shares: Decimal,
+    pub avg_cost_basis: Option<Decimal>,
+    /// Whether the holding already existed and was rebuilt
+    pub replaced: bool,
+}
+
+/// Read a lot export from a file
+pub fn load_lots(path: &str) -> Result<Vec<ImportedLot>> {
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    parse_lots(&contents).with_context(|| format!("Invalid lot export {}", path))
+}
+
+/// Parse a lot export CSV
+///
+/// Needs a symbol, purchase date, shares and either a price per share or a
+/// total cost column; a lot ID column is optional.
+pub fn parse_lots(contents: &str) -> Result<Vec<ImportedLot>> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(contents.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(normalize_header).collect();
+    let column = |names: &[&str]| names.iter().find_map(|name| headers.iter().position(|h| h == name));
+
+    let symbol_col = column(SYMBOL_COLUMNS).ok_or_else(|| anyhow!("No symbol column"))?;
+    let date_col = column(DATE_COLUMNS).ok_or_else(|| anyhow!("No purchase date column"))?;
+    let shares_col = column(SHARES_COLUMNS).ok_or_else(|| anyhow!("No shares or quantity column"))?;
+    let price_col = column(PRICE_COLUMNS);
+    let total_col = column(TOTAL_COST_COLUMNS);
+    if price_col.is_none() && total_col.is_none() {
+        bail!("No price per share or total cost column");
+    }
+    let lot_id_col = column(LOT_ID_COLUMNS);
+
+    let mut lots = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 2;
+        let record = record?;
+        let field = |col: Option<usize>| col.and_then(|i| record.get(i)).filter(|value| !value.is_empty());
+        let number = |col: Option<usize>, name: &str| -> Result<Option<Decimal>> {
+            field(col)
+                .map(|value| {
+                    Decimal::from_str(&value.replace(['$', ','], ""))
+                        .ok()
+                        .filter(|n| *n > Decimal::ZERO)
+                        .ok_or_else(|| anyhow!("Line {}: {} must be a positive number, got '{}'", line, name, value))
+                })
+                .transpose()
+        };
+
+        let symbol = normalize_symbol(field(Some(symbol_col)).ok_or_else(|| anyhow!("Line {}: missing symbol", line))?);
+        let date = field(Some(date_col)).ok_or_else(|| anyhow!("Line {}: missing purchase date", line))?;
+        let purchase_date = parse_date(date)
+            .ok_or_else(|| anyhow!("Line {}: invalid purchase date '{}' (expected YYYY-MM-DD or MM/DD/YYYY)", line, date))?;
+        let shares = number(Some(shares_col), "shares")?.ok_or_else(|| anyhow!("Line {}: missing shares", line))?;
+        let price = match (number(price_col, "price")?, number(total_col, "total cost")?) {
+            (Some(price), _) => price,
+            (None, Some(total)) => (total / shares).round_dp(4),
+            (None, None) => bail!("Line {}: missing price", line),
+        };
+        let id = field(lot_id_col).map(str::to_string).unwrap_or_default();
+
+        lots.push(ImportedLot {
+            symbol,
+            lot: TaxLot {
+                id,
+                purchase_date,
+                shares,
+                price,
+            },
+        });
+    }
+    Ok(lots)
+}
+
+fn normalize_header(header: &str) -> String {
+    header
+        .trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// Rebuild each holding in the export from its lots
+///
+/// The lots replace the holding's shares, average cost and adjustment history,
+/// since the export is the broker's record of what is still held. Other
+/// details (forward dividend, identifiers) are kept. The holdings are the
+/// ones in `account`, or each symbol's only holding when no account is named.
+/// Lots without a broker ID get one from the symbol and purchase date.
+pub fn apply_lots(tracker: &mut DividendTracker, lots: Vec<ImportedLot>, account: Option<&str>) -> Result<Vec<LotImport>> {
+    let mut by_symbol: BTreeMap<String, Vec<TaxLot>> = BTreeMap::new();
+    for imported in lots {
+        let symbol = tracker.canonical_symbol(&imported.symbol);
+        by_symbol.entry(symbol).or_default().push(imported.lot);
+    }
+
+    let mut imports = Vec::new();
+    for (symbol, mut lots) in by_symbol {
+        lots.sort_by_key(|lot| lot.purchase_date);
+        assign_lot_ids(&symbol, &mut lots)?;
+
+        let total_shares: Decimal = lots.iter().map(|lot| lot.shares).sum();
+        let existing = tracker.position_key(&symbol, account)?.map(|key| tracker.holdings[&key].clone());
+        let replaced = existing.is_some();
+        let mut holding = match existing {
+            Some(holding) => holding,
+            None => {
+                let mut holding = Holding::new(symbol.clone(), total_shares, None, None)?;
+                if let Some(account) = account {
+                    holding.set_account(account);
+                }
+                holding
+            }
+        };
+        holding.shares = Decimal::ZERO;
+        holding.avg_cost_basis = None;
+        holding.closed_date = None;
+        holding.adjustments.clear();
+        for lot in &lots {
+            holding
+                .adjust(lot.shares, Some(lot.price), lot.purchase_date)
+                .with_context(|| format!("Lot {}", lot.id))?;
+        }
+        holding.lots = lots;
+
+        imports.push(LotImport {
+            symbol: symbol.clone(),
+            lots: holding.lots.len(),
+            shares: holding.shares,
+            avg_cost_basis: holding.avg_cost_basis,
+            replaced,
+        });
+        tracker.holdings.insert(holding.key(), holding);
+    }
+    Ok(imports)
+}
+
+/// Give lots without a broker ID one like `KO-20210315`, numbering lots bought
+/// the same day, and reject duplicate broker IDs
+fn assign_lot_ids(symbol: &str, lots: &mut [TaxLot]) -> Result<()> {
+    let mut seen: Vec<String> = Vec::new();
+    for lot in lots.iter_mut() {
+        if lot.id.is_empty() {
+            let base = format!("{}-{}", symbol, lot.purchase_date.format("%Y%m%d"));
+            let mut id = base.clone();
+            let mut n = 1;
+            while seen.contains(&id) {
+                n += 1;
+                id = format!("{}-{}", base, n);
+            }
+            lot.id = id;
+        } else if seen.contains(&lot.id) {
+            bail!("Lot ID {} appears more than once for {}", lot.id, symbol);
+        }
+        seen.push(lot.id.clone());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use crate::models::DEFAULT_ACCOUNT;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_lots_accepts_broker_headers() {
+        let lots = parse_lots(
+            "Symbol,Date Acquired,Quantity,Cost Per Share,Lot ID\n\
+             ko,03/15/2021,50,$52.10,A1\n\
+             KO,2022-06-01,25,\"1,000.00\",\n",
+        )
+        .unwrap();
+        assert_eq!(lots.len(), 2);
+        assert_eq!(lots[0].symbol, "KO");
+        assert_eq!(lots[0].lot.purchase_date, date("2021-03-15"));
+        assert_eq!(lots[0].lot.price, dec!(52.10));
+        assert_eq!(lots[0].lot.id, "A1");
+        assert_eq!(lots[1].lot.price, dec!(1000.00));
+        assert_eq!(lots[1].lot.id, "");
+
+        let lots = parse_lots("ticker,trade date,shares,total cost\nPEP,2023-01-10,4,650\n").unwrap();
+        assert_eq!(lots[0].lot.price, dec!(162.5));
+
+        let err = parse_lots("symbol,date,shares\nKO,2021-03-15,10\n").unwrap_err();
+        assert!(err.to_string().contains("No price per share"));
+        let err = parse_lots("symbol,date,shares,price\nKO,15.03.2021,10,50\n").unwrap_err();
+        assert!(err.to_string().contains("Line 2: invalid purchase date"));
+    }
+
+    #[test]
+    fn test_apply_lots_rebuilds_holding_from_purchases() {
+        let mut tracker = DividendTracker::new();
+        let mut holding = Holding::new("KO".to_string(), dec!(10), Some(dec!(40)), None).unwrap();
+        holding.isin = Some("US1912161007".to_string());
+        tracker.add_holding(holding);
+
+        let lots = parse_lots(
+            "symbol,purchase_date,shares,price\n\
+             KO,2022-06-01,50,60\n\
+             KO,2021-03-15,50,50\n\
+             KO,2022-06-01,20,61\n\
+             MSFT,2020-01-02,5,160\n",
+        )
+        .unwrap();
+        let imports = apply_lots(&mut tracker, lots, None).unwrap();
+        assert_eq!(imports.len(), 2);
+        assert!(imports[0].replaced);
+        assert!(!imports[1].replaced);
+
+        let ko = tracker.holding("KO", DEFAULT_ACCOUNT).unwrap();
+        assert_eq!(ko.shares, dec!(120));
+        assert_eq!(ko.avg_cost_basis, Some(dec!(56)));
+        assert_eq!(ko.isin.as_deref(), Some("US1912161007"));
+        assert_eq!(ko.adjustments.len(), 3);
+        assert_eq!(ko.shares_on(date("2022-01-01")), dec!(50));
+        let ids: Vec<&str> = ko.lots.iter().map(|lot| lot.id.as_str()).collect();
+        assert_eq!(ids, ["KO-20210315", "KO-20220601", "KO-20220601-2"]);
+        assert_eq!(tracker.holding("MSFT", DEFAULT_ACCOUNT).unwrap().avg_cost_basis, Some(dec!(160)));
+    }
+
+    #[test]
+    fn test_apply_lots_rejects_duplicate_broker_ids() {
+        let mut tracker = DividendTracker::new();
+        let lots = parse_lots("symbol,date,shares,price,lot\nKO,2021-03-15,5,50,A1\nKO,2021-04-15,5,51,A1\n").unwrap();
+        let err = apply_lots(&mut tracker, lots, None).unwrap_err();
+        assert!(err.to_string().contains("Lot ID A1 appears more than once"));
+    }
+}
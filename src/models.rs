@@ -1,13 +1,26 @@
 use anyhow::{bail, Result};
-use chrono::{Datelike, NaiveDate};
+use chrono::{DateTime, Datelike, Local, NaiveDate};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::classify;
+use crate::config::ClassificationSettings;
+use crate::corporate_actions::CorporateAction;
+use crate::filters::ttm_start;
+use crate::identifiers::{isin_country, parse_country, parse_cusip, parse_isin};
+use crate::run_rate;
+use crate::securities::Securities;
+use crate::symbols::{country_from_symbol, did_you_mean, normalize_symbol};
+
+/// The classification [`DividendTracker::add_new_dividend`] made for a dividend
+pub use crate::classify::Guess;
 
 // Module for core data structures used in dividend tracking
 
 /// Represents different types of dividend payments
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DividendType {
     /// Regular quarterly or annual dividend
     Regular,
@@ -22,7 +35,7 @@ pub enum DividendType {
 }
 
 /// Tax classification for dividend payments (for US tax purposes)
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TaxClassification {
     /// Qualified dividends eligible for capital gains tax rates
     Qualified,
@@ -38,8 +51,37 @@ pub enum TaxClassification {
     Unknown,
 }
 
+/// What kind of income a payment record is
+///
+/// Interest (bond funds, money-market and cash sweeps) is reported on 1099-INT
+/// rather than 1099-DIV, so tax reports keep it apart. Distributions, such as
+/// from bond ETFs, are reported like dividends but shown separately in summaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
+#[serde(rename_all = "lowercase")]
+pub enum IncomeKind {
+    #[default]
+    Dividend,
+    Interest,
+    Distribution,
+}
+
+impl IncomeKind {
+    pub fn is_dividend(&self) -> bool {
+        *self == IncomeKind::Dividend
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IncomeKind::Dividend => "Dividend",
+            IncomeKind::Interest => "Interest",
+            IncomeKind::Distribution => "Distribution",
+        }
+    }
+}
+
 /// Represents a dividend payment record
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Dividend {
     /// Stock symbol (e.g., AAPL, MSFT)
     pub symbol: String,
@@ -64,6 +106,65 @@ pub struct Dividend {
     pub tax_lot_id: Option<String>,
     /// Optional withholding tax amount for foreign dividends
     pub withholding_tax: Option<Decimal>,
+    /// Country that withheld tax on the payment (ISO code such as CH or CA)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Custody or other fees deducted from the payment (e.g. ADR pass-through fees)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<Decimal>,
+    /// International Securities Identification Number of the security
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isin: Option<String>,
+    /// CUSIP of the security (US and Canadian securities)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
+    /// Brokerage account the payment was credited to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Dividend, interest or distribution; records from before the distinction are dividends
+    #[serde(default, skip_serializing_if = "IncomeKind::is_dividend")]
+    pub income_kind: IncomeKind,
+}
+
+/// Account used for dividends recorded without one
+pub const DEFAULT_ACCOUNT: &str = "Default";
+
+/// Where a holding is kept: one symbol in one account
+///
+/// The same security can be held in several accounts, each a position of its
+/// own. Accounts are told apart ignoring case, like the `--account` filters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HoldingKey {
+    pub symbol: String,
+    /// Account name, lowercased
+    account: String,
+}
+
+impl HoldingKey {
+    pub fn new(symbol: &str, account: &str) -> Self {
+        HoldingKey {
+            symbol: symbol.to_string(),
+            account: account.trim().to_lowercase(),
+        }
+    }
+
+    /// Whether the key is in an account, compared ignoring case
+    pub fn is_in(&self, account: &str) -> bool {
+        self.account == account.trim().to_lowercase()
+    }
+}
+
+/// One record per payment, keeping the first of each symbol, ex-date and type
+///
+/// A payment credited to several accounts is recorded once in each of them.
+/// Income adds those records up, but per-share amounts and the spacing of
+/// payments have to count the payment once.
+pub fn one_per_payment<'a>(dividends: impl IntoIterator<Item = &'a Dividend>) -> Vec<&'a Dividend> {
+    let mut seen = HashSet::new();
+    dividends
+        .into_iter()
+        .filter(|d| seen.insert((d.symbol.as_str(), d.ex_date, &d.dividend_type)))
+        .collect()
 }
 
 /// Default tax classification for backward compatibility
@@ -82,15 +183,302 @@ pub struct Holding {
     pub avg_cost_basis: Option<Decimal>,
     /// Current dividend yield percentage (optional for display)
     pub current_yield: Option<Decimal>,
+    /// User-supplied forward dividend, used instead of payment history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forward_dividend: Option<ForwardDividend>,
+    /// Date the position was sold off and archived; closed holdings keep their
+    /// cost basis for history but are left out of projections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_date: Option<NaiveDate>,
+    /// Share and cost-basis changes made with `holdings update`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub adjustments: Vec<HoldingAdjustment>,
+    /// International Securities Identification Number of the security
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isin: Option<String>,
+    /// CUSIP of the security (US and Canadian securities)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
+    /// Open purchase lots imported with `holdings import-lots`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lots: Vec<TaxLot>,
+    /// Brokerage or retirement account the shares are held in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+/// Shares bought in one purchase, as reported by the broker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxLot {
+    /// Broker lot identifier, or one derived from the symbol and purchase date
+    pub id: String,
+    /// Date the lot was bought
+    pub purchase_date: NaiveDate,
+    /// Shares in the lot when imported; sales recorded since are taken from
+    /// the lots by [`Holding::lot_shares_on`]
+    pub shares: Decimal,
+    /// Price paid per share
+    pub price: Decimal,
 }
 
+/// One incremental change to a holding's shares or cost basis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoldingAdjustment {
+    /// Date of the trade or correction
+    pub date: NaiveDate,
+    /// Shares bought (positive) or sold (negative)
+    pub shares_delta: Decimal,
+    /// Price per share of a trade (paid for a purchase, received for a sale
+    /// recorded with `transactions sell`), or the corrected cost basis
+    pub price: Option<Decimal>,
+    /// Shares held after the adjustment
+    pub shares_after: Decimal,
+    /// Average cost basis after the adjustment
+    pub avg_cost_after: Option<Decimal>,
+    /// Forward dividend yield in percent at the price paid, for purchases
+    /// recorded with `transactions buy` while the forward dividend was known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purchase_yield: Option<Decimal>,
+}
+
+/// Manually set forward dividend rate for a holding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardDividend {
+    /// Expected dividend per share over the next 12 months
+    pub annual_amount: Decimal,
+    /// How often the annual amount is paid out
+    pub frequency: DividendFrequency,
+}
+
+/// A claim to recover foreign withholding tax charged above the treaty rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithholdingReclaim {
+    /// Symbol of the dividend the tax was withheld from
+    pub symbol: String,
+    /// Ex-date of that dividend
+    pub ex_date: NaiveDate,
+    /// Country the reclaim is filed with
+    pub country: String,
+    /// Amount claimed back
+    pub claimed: Decimal,
+    /// Date the claim was filed
+    pub filed_date: NaiveDate,
+    /// Reference number from the tax authority or custodian
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Amount actually refunded, once received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refunded: Option<Decimal>,
+    /// Date the refund arrived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refund_date: Option<NaiveDate>,
+}
+
+/// What happened to dividend cash taken out of the ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CashMovementKind {
+    /// Cash moved out of the account
+    Withdrawal,
+    /// Cash used to buy shares
+    Reinvestment,
+}
+
+/// Dividend cash withdrawn from or reinvested in an account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashMovement {
+    pub date: NaiveDate,
+    pub account: String,
+    pub kind: CashMovementKind,
+    pub amount: Decimal,
+    /// Symbol bought with a reinvestment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Ledger of uninvested dividend cash, kept once enabled
+///
+/// Credits aren't stored: every dividend paid on or after `since` counts as
+/// credited to its account, so corrections to a dividend flow through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashLedger {
+    /// First pay date credited to the ledger
+    pub since: NaiveDate,
+    /// Withdrawals and reinvestments, in the order recorded
+    #[serde(default)]
+    pub movements: Vec<CashMovement>,
+}
+
+/// Dividend cash credited to and taken from one account
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CashBalance {
+    pub account: String,
+    pub credited: Decimal,
+    pub withdrawn: Decimal,
+    pub reinvested: Decimal,
+}
+
+/// Forward income run-rate as it stood when the data last changed in a month
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRateSnapshot {
+    /// Date the snapshot was taken
+    pub date: NaiveDate,
+    /// Forward annual income on that date
+    pub annual_income: Decimal,
+    /// Number of paying positions
+    pub positions: usize,
+}
+
+/// A year's projected income saved with `project --save-plan`, so
+/// `project review` can compare the year's actual payments against it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectionPlan {
+    /// Date the plan was saved
+    pub saved_on: NaiveDate,
+    /// Projection method the plan was made with
+    pub method: String,
+    /// Growth scenario the plan was made with
+    pub growth_scenario: String,
+    /// Projected income for January through December
+    pub monthly: Vec<Decimal>,
+}
+
+impl ProjectionPlan {
+    /// Projected income for `month` (1-12)
+    pub fn month(&self, month: u32) -> Decimal {
+        self.monthly.get(month as usize - 1).copied().unwrap_or_default()
+    }
+
+    /// Projected income for the whole year
+    pub fn total(&self) -> Decimal {
+        self.monthly.iter().sum()
+    }
+}
+
+/// Living expenses dividend income is measured against, set with `expenses set`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpenseBudget {
+    /// Expenses per month; yearly budgets are stored divided by twelve
+    pub monthly: Decimal,
+    /// Date the budget was last set
+    pub set_on: NaiveDate,
+}
+
+/// A large expense or withdrawal planned for a date, added with `expenses plan`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedExpense {
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A research note about a symbol, added with `journal add`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub symbol: String,
+    /// When the note was written
+    pub at: DateTime<Local>,
+    pub text: String,
+}
+
+/// Which side of an income threshold raises an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdDirection {
+    /// Alert when income falls below the target, e.g. a quiet dividend cut
+    Below,
+    /// Alert when income rises above the target, e.g. a position grown too large
+    Above,
+}
+
+/// Trailing-12-month income target for a symbol, set with `alerts threshold`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeThreshold {
+    pub annual: Decimal,
+    pub direction: ThresholdDirection,
+}
+
+/// Premium collected from selling an option, e.g. a covered call, added with `premiums add`
+///
+/// Kept apart from dividends so dividend analytics, yields and tax forms
+/// never include it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionPremium {
+    pub symbol: String,
+    /// Date the option was sold
+    pub date: NaiveDate,
+    /// Net premium received, after commissions
+    pub amount: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contracts: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A dividend refused because one is already recorded for its symbol and ex-date
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateDividend {
+    pub existing: Dividend,
+}
+
+impl fmt::Display for DuplicateDividend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Duplicate dividend exists for {} on {}",
+            self.existing.symbol, self.existing.ex_date
+        )
+    }
+}
+
+impl std::error::Error for DuplicateDividend {}
+
 /// Main data structure for managing dividend and portfolio data
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DividendTracker {
     /// Collection of dividend payment records
     pub dividends: Vec<Dividend>,
-    /// Map of stock symbols to current holdings
-    pub holdings: HashMap<String, Holding>,
+    /// Current holdings, one per symbol and account
+    #[serde(with = "holding_map")]
+    pub holdings: HashMap<HoldingKey, Holding>,
+    /// Withholding tax reclaims, in filing order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reclaims: Vec<WithholdingReclaim>,
+    /// Alternative ticker spellings mapped to the symbol records are kept under
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub symbol_aliases: BTreeMap<String, String>,
+    /// Dividend cash ledger, when enabled with `cash enable`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cash: Option<CashLedger>,
+    /// Forward income run-rate saved once a month, keyed by `YYYY-MM`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub run_rate_history: BTreeMap<String, RunRateSnapshot>,
+    /// Living expenses for coverage, when set with `expenses set`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expenses: Option<ExpenseBudget>,
+    /// Research notes per symbol, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub journal: Vec<JournalEntry>,
+    /// Corporate actions applied from feeds, in the order they were applied
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corporate_actions: Vec<CorporateAction>,
+    /// Projections saved as plans with `project --save-plan`, keyed by `YYYY`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projection_plans: BTreeMap<String, ProjectionPlan>,
+    /// Option premiums collected, in the order recorded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub premiums: Vec<OptionPremium>,
+    /// Trailing-12-month income alert thresholds, keyed by symbol
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub income_thresholds: BTreeMap<String, IncomeThreshold>,
+    /// Large expenses planned with `expenses plan`, by date
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub planned_expenses: Vec<PlannedExpense>,
+    /// Facts pinned per symbol in securities.toml; never saved with the data
+    #[serde(skip)]
+    pub securities: Securities,
 }
 
 // Implementation blocks for constructor methods and validation
@@ -125,7 +513,7 @@ impl Dividend {
         let total_amount = amount_per_share * shares_owned;
 
         Ok(Dividend {
-            symbol: symbol.trim().to_uppercase(),
+            symbol: normalize_symbol(&symbol),
             company_name,
             ex_date,
             pay_date,
@@ -136,10 +524,96 @@ impl Dividend {
             tax_classification: TaxClassification::Unknown, // Default for new dividends
             tax_lot_id: None,
             withholding_tax: None,
+            country: None,
+            fees: None,
+            isin: None,
+            cusip: None,
+            account: None,
+            income_kind: IncomeKind::Dividend,
         })
     }
 
+    /// Validate and set the security's ISIN and CUSIP; `None` leaves a value as is
+    pub fn set_identifiers(&mut self, isin: Option<&str>, cusip: Option<&str>) -> Result<()> {
+        if let Some(isin) = isin {
+            self.isin = Some(parse_isin(isin)?);
+        }
+        if let Some(cusip) = cusip {
+            self.cusip = Some(parse_cusip(cusip)?);
+        }
+        Ok(())
+    }
+
+    /// Record the fees deducted from this payment
+    pub fn set_fees(&mut self, fees: Decimal) -> Result<()> {
+        if fees < Decimal::ZERO {
+            bail!("Fees cannot be negative");
+        }
+        if fees > self.total_amount {
+            bail!("Fees (${}) cannot exceed the dividend total (${})", fees, self.total_amount);
+        }
+        self.fees = Some(fees).filter(|f| !f.is_zero());
+        Ok(())
+    }
+
+    /// Record the tax withheld at source from this payment
+    pub fn set_withholding(&mut self, withholding: Decimal) -> Result<()> {
+        if withholding < Decimal::ZERO {
+            bail!("Withholding tax cannot be negative");
+        }
+        if withholding > self.net_amount() {
+            bail!(
+                "Withholding (${}) cannot exceed the dividend after fees (${})",
+                withholding,
+                self.net_amount()
+            );
+        }
+        self.withholding_tax = Some(withholding).filter(|w| !w.is_zero());
+        Ok(())
+    }
+
+    /// Set the country that withheld tax, e.g. `ch` becomes `CH`
+    pub fn set_country(&mut self, country: &str) -> Result<()> {
+        self.country = Some(parse_country(country)?);
+        Ok(())
+    }
+
+    /// Country the payment comes from: the one recorded, or else the one the
+    /// ISIN or the symbol's exchange suffix implies
+    pub fn source_country(&self) -> Option<&str> {
+        self.country
+            .as_deref()
+            .or_else(|| self.isin.as_deref().and_then(isin_country))
+            .or_else(|| country_from_symbol(&self.symbol))
+    }
+
+    /// Withholding tax taken from this payment, zero when none was recorded
+    pub fn withholding_amount(&self) -> Decimal {
+        self.withholding_tax.unwrap_or(Decimal::ZERO)
+    }
+
+    /// Fees deducted from this payment, zero when none were recorded
+    pub fn fee_amount(&self) -> Decimal {
+        self.fees.unwrap_or(Decimal::ZERO)
+    }
+
+    /// Cash actually received: the total less any fees
+    pub fn net_amount(&self) -> Decimal {
+        self.total_amount - self.fee_amount()
+    }
+
+    /// Cash credited to the account: the net amount less withholding tax
+    pub fn cash_credited(&self) -> Decimal {
+        self.net_amount() - self.withholding_amount()
+    }
+
+    /// Account the payment was credited to
+    pub fn account_name(&self) -> &str {
+        self.account.as_deref().unwrap_or(DEFAULT_ACCOUNT)
+    }
+
     /// Create a new dividend record with tax information
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_tax(
         symbol: String,
         company_name: Option<String>,
@@ -178,7 +652,7 @@ impl Dividend {
         let total_amount = amount_per_share * shares_owned;
 
         Ok(Dividend {
-            symbol: symbol.trim().to_uppercase(),
+            symbol: normalize_symbol(&symbol),
             company_name,
             ex_date,
             pay_date,
@@ -189,6 +663,12 @@ impl Dividend {
             tax_classification,
             tax_lot_id,
             withholding_tax,
+            country: None,
+            fees: None,
+            isin: None,
+            cusip: None,
+            account: None,
+            income_kind: IncomeKind::Dividend,
         })
     }
 }
@@ -223,12 +703,540 @@ impl Holding {
         }
 
         Ok(Holding {
-            symbol: symbol.trim().to_uppercase(),
+            symbol: normalize_symbol(&symbol),
             shares,
             avg_cost_basis,
             current_yield,
+            forward_dividend: None,
+            closed_date: None,
+            adjustments: Vec::new(),
+            isin: None,
+            cusip: None,
+            lots: Vec::new(),
+            account: None,
         })
     }
+
+    /// Account the shares are held in
+    pub fn account_name(&self) -> &str {
+        self.account.as_deref().unwrap_or(DEFAULT_ACCOUNT)
+    }
+
+    /// Key the holding is kept under in [`DividendTracker::holdings`]
+    pub fn key(&self) -> HoldingKey {
+        HoldingKey::new(&self.symbol, self.account_name())
+    }
+
+    /// The symbol, with its account when it has one
+    pub fn label(&self) -> String {
+        match &self.account {
+            Some(account) => format!("{} ({})", self.symbol, account),
+            None => self.symbol.clone(),
+        }
+    }
+
+    /// Set the account the shares are held in; a blank name leaves it unset
+    pub fn set_account(&mut self, account: &str) {
+        let account = account.trim();
+        self.account = (!account.is_empty()).then(|| account.to_string());
+    }
+
+    /// Validate and set the security's ISIN and CUSIP; `None` leaves a value as is
+    pub fn set_identifiers(&mut self, isin: Option<&str>, cusip: Option<&str>) -> Result<()> {
+        if let Some(isin) = isin {
+            self.isin = Some(parse_isin(isin)?);
+        }
+        if let Some(cusip) = cusip {
+            self.cusip = Some(parse_cusip(cusip)?);
+        }
+        Ok(())
+    }
+
+    /// Whether `id` is this holding's ISIN or CUSIP
+    pub fn has_identifier(&self, id: &str) -> bool {
+        let id = id.trim().to_uppercase();
+        self.isin.as_deref() == Some(id.as_str()) || self.cusip.as_deref() == Some(id.as_str())
+    }
+
+    /// Lots entitled to a dividend going ex on `ex_date`, with their shares:
+    /// those bought before it and not yet sold
+    pub fn lots_entitled_on(&self, ex_date: NaiveDate) -> Vec<(&TaxLot, Decimal)> {
+        self.lot_shares_on(ex_date)
+    }
+
+    /// Shares left in each purchase lot at the start of `date`, oldest lot first
+    ///
+    /// Sales recorded after the import, whether with `transactions sell`,
+    /// `holdings update` or by archiving, are taken from the oldest lots bought
+    /// by the day of the sale (first in, first out). Lots sold out are left out.
+    pub fn lot_shares_on(&self, date: NaiveDate) -> Vec<(&TaxLot, Decimal)> {
+        let mut lots: Vec<(&TaxLot, Decimal)> = self
+            .lots
+            .iter()
+            .filter(|lot| lot.purchase_date < date)
+            .map(|lot| (lot, lot.shares))
+            .collect();
+        lots.sort_by_key(|(lot, _)| lot.purchase_date);
+
+        for sale in self.adjustments.iter().filter(|a| a.shares_delta < Decimal::ZERO && a.date < date) {
+            let mut unsold = -sale.shares_delta;
+            for (_, shares) in lots.iter_mut().filter(|(lot, _)| lot.purchase_date <= sale.date) {
+                let sold = unsold.min(*shares);
+                *shares -= sold;
+                unsold -= sold;
+                if unsold.is_zero() {
+                    break;
+                }
+            }
+        }
+
+        lots.retain(|(_, shares)| *shares > Decimal::ZERO);
+        lots
+    }
+
+    /// Whether the position has been sold off and archived
+    pub fn is_closed(&self) -> bool {
+        self.closed_date.is_some()
+    }
+
+    /// Shares held on a date, worked back from the recorded adjustments
+    ///
+    /// Without adjustments the current share count is assumed to have been
+    /// held all along.
+    pub fn shares_on(&self, date: NaiveDate) -> Decimal {
+        if self.closed_date.is_some_and(|closed| closed <= date) {
+            return Decimal::ZERO;
+        }
+        match self.adjustments.iter().rev().find(|a| a.date <= date) {
+            Some(adjustment) => adjustment.shares_after,
+            None => match self.adjustments.first() {
+                Some(first) => first.shares_after - first.shares_delta,
+                None => self.shares,
+            },
+        }
+    }
+
+    /// Shares entitled to a dividend going ex on `ex_date`: those held at the
+    /// close the day before, from the recorded adjustments or purchase lots
+    ///
+    /// `None` when the history doesn't reach back that far, so the shares
+    /// held then are unknown.
+    pub fn shares_entitled_on(&self, ex_date: NaiveDate) -> Option<Decimal> {
+        let day_before = ex_date.pred_opt()?;
+        if let Some(first) = self.adjustments.first() {
+            return (first.date <= day_before).then(|| self.shares_on(day_before));
+        }
+        if !self.lots.is_empty() {
+            return Some(self.lots_entitled_on(ex_date).iter().map(|(_, shares)| *shares).sum());
+        }
+        None
+    }
+
+    /// Sell the remaining shares and mark the position closed, keeping its
+    /// cost basis and history
+    pub fn archive(&mut self, date: NaiveDate) -> Result<()> {
+        if let Some(closed) = self.closed_date {
+            bail!("{} was already archived on {}", self.symbol, closed);
+        }
+
+        self.adjustments.push(HoldingAdjustment {
+            date,
+            shares_delta: -self.shares,
+            price: None,
+            shares_after: Decimal::ZERO,
+            avg_cost_after: self.avg_cost_basis,
+            purchase_yield: None,
+        });
+        self.shares = Decimal::ZERO;
+        self.closed_date = Some(date);
+        Ok(())
+    }
+
+    /// Buy or sell shares and recompute the average cost basis
+    ///
+    /// Purchases blend `price` into the average cost; sales leave the average
+    /// unchanged. Without a share change, `price` replaces the cost basis.
+    pub fn adjust(&mut self, shares_delta: Decimal, price: Option<Decimal>, date: NaiveDate) -> Result<()> {
+        if let Some(price) = price {
+            if price <= Decimal::ZERO {
+                bail!("Cost basis must be positive");
+            }
+        }
+
+        let shares_after = self.shares + shares_delta;
+        let avg_cost_after = if shares_delta > Decimal::ZERO {
+            match (self.avg_cost_basis, price) {
+                (Some(avg), Some(price)) => Some((self.shares * avg + shares_delta * price) / shares_after),
+                (None, price) => price.filter(|_| self.shares.is_zero()),
+                (Some(_), None) => bail!(
+                    "{} has a cost basis; give the purchase price with --cost-basis to keep the average correct",
+                    self.symbol
+                ),
+            }
+        } else if shares_delta < Decimal::ZERO {
+            if price.is_some() {
+                bail!("--cost-basis only applies when buying shares; sales keep the average cost");
+            }
+            if shares_after <= Decimal::ZERO {
+                bail!(
+                    "Cannot sell {} shares of {}: only {} held. Use 'holdings remove' to close the position.",
+                    -shares_delta,
+                    self.symbol,
+                    self.shares
+                );
+            }
+            self.avg_cost_basis
+        } else {
+            match price {
+                Some(price) => Some(price),
+                None => bail!("Nothing to update: give --shares and/or --cost-basis"),
+            }
+        };
+
+        self.shares = shares_after;
+        self.avg_cost_basis = avg_cost_after.map(|c| c.round_dp(4));
+        // Buying back into an archived position reopens it
+        self.closed_date = None;
+        self.adjustments.push(HoldingAdjustment {
+            date,
+            shares_delta,
+            price,
+            shares_after,
+            avg_cost_after: self.avg_cost_basis,
+            purchase_yield: None,
+        });
+        Ok(())
+    }
+
+    /// Record a purchase (positive `shares_delta`) or sale at `price` on `date`
+    ///
+    /// Unlike `adjust`, the trade may be dated before ones already recorded:
+    /// it is slotted in by date and the adjustments after it are replayed, so
+    /// the shares held on every date stay right. Selling every share closes
+    /// the position. `purchase_yield` is kept with the trade as recorded.
+    pub fn record_trade(
+        &mut self,
+        date: NaiveDate,
+        shares_delta: Decimal,
+        price: Decimal,
+        purchase_yield: Option<Decimal>,
+    ) -> Result<()> {
+        if shares_delta.is_zero() {
+            bail!("A trade needs a number of shares");
+        }
+        if price <= Decimal::ZERO {
+            bail!("Trade price must be positive");
+        }
+        if let Some(closed) = self.closed_date.filter(|&closed| date <= closed) {
+            bail!("{} was closed on {}; record trades after that date", self.symbol, closed);
+        }
+
+        let at = self.adjustments.partition_point(|a| a.date <= date);
+        let (mut shares, mut avg_cost) = self.position_before(at);
+        let mut adjustments = self.adjustments.clone();
+        adjustments.insert(
+            at,
+            HoldingAdjustment {
+                date,
+                shares_delta,
+                price: Some(price),
+                shares_after: Decimal::ZERO,
+                avg_cost_after: None,
+                purchase_yield,
+            },
+        );
+        for adjustment in &mut adjustments[at..] {
+            let held = shares;
+            shares += adjustment.shares_delta;
+            if shares < Decimal::ZERO {
+                bail!(
+                    "Cannot sell {} shares of {} on {}: only {} held then",
+                    -adjustment.shares_delta,
+                    self.symbol,
+                    adjustment.date,
+                    held
+                );
+            }
+            avg_cost = if adjustment.shares_delta > Decimal::ZERO {
+                match (avg_cost, adjustment.price) {
+                    (Some(avg), Some(price)) => Some((held * avg + adjustment.shares_delta * price) / shares),
+                    (None, price) if held.is_zero() => price,
+                    _ => None,
+                }
+            } else if adjustment.shares_delta.is_zero() {
+                adjustment.price
+            } else {
+                avg_cost
+            }
+            .map(|c| c.round_dp(4));
+            adjustment.shares_after = shares;
+            adjustment.avg_cost_after = avg_cost;
+        }
+
+        self.adjustments = adjustments;
+        self.shares = shares;
+        self.avg_cost_basis = avg_cost;
+        self.closed_date = if shares.is_zero() {
+            self.adjustments.last().map(|a| a.date)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Shares and average cost held before the adjustment at `index`
+    fn position_before(&self, index: usize) -> (Decimal, Option<Decimal>) {
+        if let Some(previous) = index.checked_sub(1).and_then(|i| self.adjustments.get(i)) {
+            return (previous.shares_after, previous.avg_cost_after);
+        }
+        let Some(first) = self.adjustments.first() else {
+            // Nothing recorded: the current position was held all along
+            return (self.shares, self.avg_cost_basis);
+        };
+        let shares = first.shares_after - first.shares_delta;
+        let avg_cost = match (first.price, first.avg_cost_after) {
+            (_, avg) if first.shares_delta < Decimal::ZERO => avg,
+            (Some(price), Some(avg)) if first.shares_delta > Decimal::ZERO && shares > Decimal::ZERO => {
+                Some(((avg * first.shares_after - first.shares_delta * price) / shares).round_dp(4))
+            }
+            _ => None,
+        };
+        (shares, avg_cost)
+    }
+}
+
+impl ForwardDividend {
+    /// Create a forward dividend with validation
+    pub fn new(annual_amount: Decimal, frequency: DividendFrequency) -> Result<Self> {
+        if annual_amount <= Decimal::ZERO {
+            bail!("Forward dividend must be positive");
+        }
+
+        Ok(ForwardDividend {
+            annual_amount,
+            frequency,
+        })
+    }
+
+    /// Amount per share of each individual payment
+    pub fn per_payment(&self) -> Decimal {
+        self.annual_amount / Decimal::from(self.frequency.payments_per_year())
+    }
+
+    /// Payment months assumed when the holding has no history to learn them from
+    pub fn assumed_payment_months(&self) -> Vec<u32> {
+        let step = 12 / self.frequency.payments_per_year();
+        (1..=self.frequency.payments_per_year()).map(|n| n * step).collect()
+    }
+}
+
+impl WithholdingReclaim {
+    /// Create a reclaim with validation
+    pub fn new(
+        symbol: &str,
+        ex_date: NaiveDate,
+        country: &str,
+        claimed: Decimal,
+        filed_date: NaiveDate,
+    ) -> Result<Self> {
+        let country = country.trim().to_uppercase();
+        if country.is_empty() {
+            bail!("Country cannot be empty");
+        }
+        if claimed <= Decimal::ZERO {
+            bail!("Reclaim amount must be positive");
+        }
+        if filed_date < ex_date {
+            bail!("Reclaim cannot be filed before the ex-dividend date");
+        }
+
+        Ok(WithholdingReclaim {
+            symbol: normalize_symbol(symbol),
+            ex_date,
+            country,
+            claimed,
+            filed_date,
+            reference: None,
+            refunded: None,
+            refund_date: None,
+        })
+    }
+
+    /// Whether the refund is still outstanding
+    pub fn is_pending(&self) -> bool {
+        self.refund_date.is_none()
+    }
+
+    /// Amount still owed back: the full claim until a refund is recorded
+    pub fn outstanding(&self) -> Decimal {
+        if self.is_pending() {
+            self.claimed
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// Record the refund, closing the claim
+    ///
+    /// A refund below the claimed amount closes the claim as well; the
+    /// shortfall shows in the report as the difference between the two.
+    pub fn record_refund(&mut self, amount: Decimal, date: NaiveDate) -> Result<()> {
+        if !self.is_pending() {
+            bail!(
+                "Reclaim for {} ({}) was already refunded on {}",
+                self.symbol,
+                self.country,
+                self.refund_date.map(|d| d.to_string()).unwrap_or_default()
+            );
+        }
+        if amount < Decimal::ZERO {
+            bail!("Refund amount cannot be negative");
+        }
+        if amount > self.claimed {
+            bail!("Refund (${}) cannot exceed the amount claimed (${})", amount, self.claimed);
+        }
+        if date < self.filed_date {
+            bail!("Refund cannot arrive before the reclaim was filed ({})", self.filed_date);
+        }
+
+        self.refunded = Some(amount);
+        self.refund_date = Some(date);
+        Ok(())
+    }
+}
+
+impl CashMovement {
+    /// Create a withdrawal or reinvestment with validation
+    pub fn new(date: NaiveDate, account: Option<&str>, kind: CashMovementKind, amount: Decimal) -> Result<Self> {
+        if amount <= Decimal::ZERO {
+            bail!("Amount must be positive");
+        }
+        let account = account.map(str::trim).unwrap_or(DEFAULT_ACCOUNT);
+        if account.is_empty() {
+            bail!("Account cannot be empty");
+        }
+
+        Ok(CashMovement {
+            date,
+            account: account.to_string(),
+            kind,
+            amount,
+            symbol: None,
+            note: None,
+        })
+    }
+}
+
+impl IncomeThreshold {
+    /// Whether trailing income is on the alerting side of the target
+    pub fn is_crossed(&self, income: Decimal) -> bool {
+        match self.direction {
+            ThresholdDirection::Below => income < self.annual,
+            ThresholdDirection::Above => income > self.annual,
+        }
+    }
+}
+
+impl OptionPremium {
+    /// Create a premium record with validation
+    pub fn new(symbol: &str, date: NaiveDate, amount: Decimal) -> Result<Self> {
+        let symbol = normalize_symbol(symbol);
+        if symbol.is_empty() {
+            bail!("Symbol cannot be empty");
+        }
+        if amount <= Decimal::ZERO {
+            bail!("Premium must be positive");
+        }
+
+        Ok(OptionPremium {
+            symbol,
+            date,
+            amount,
+            contracts: None,
+            note: None,
+        })
+    }
+}
+
+impl ExpenseBudget {
+    /// Parse a budget such as `4500/month`, `54000/year` or `4500` (per month)
+    pub fn parse(value: &str, set_on: NaiveDate) -> Result<Self> {
+        let value = value.trim();
+        let (amount, per) = value.split_once('/').unwrap_or((value, "month"));
+        let months = match per.trim().to_lowercase().as_str() {
+            "month" | "mo" | "monthly" => 1,
+            "year" | "yr" | "yearly" | "annual" => 12,
+            other => bail!("Unknown period '{}'; use /month or /year", other),
+        };
+        let amount: Decimal = amount
+            .trim()
+            .trim_start_matches('$')
+            .replace(',', "")
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid expense amount: {}", amount.trim()))?;
+        if amount <= Decimal::ZERO {
+            bail!("Expenses must be positive");
+        }
+
+        Ok(ExpenseBudget {
+            monthly: amount / Decimal::from(months),
+            set_on,
+        })
+    }
+
+    /// Expenses per year
+    pub fn annual(&self) -> Decimal {
+        self.monthly * Decimal::from(12)
+    }
+}
+
+impl CashLedger {
+    /// Start a ledger crediting dividends paid from `since`
+    pub fn new(since: NaiveDate) -> Self {
+        CashLedger {
+            since,
+            movements: Vec::new(),
+        }
+    }
+
+    /// Dividends credited to the ledger by `as_of`
+    pub fn credits<'a>(&self, dividends: &'a [Dividend], as_of: NaiveDate) -> Vec<&'a Dividend> {
+        dividends
+            .iter()
+            .filter(|d| d.pay_date >= self.since && d.pay_date <= as_of)
+            .collect()
+    }
+
+    /// Balance of every account as of a date, sorted by account
+    pub fn balances(&self, dividends: &[Dividend], as_of: NaiveDate) -> Vec<CashBalance> {
+        fn entry<'m>(balances: &'m mut BTreeMap<String, CashBalance>, account: &str) -> &'m mut CashBalance {
+            balances.entry(account.to_string()).or_insert_with(|| CashBalance {
+                account: account.to_string(),
+                ..CashBalance::default()
+            })
+        }
+
+        let mut balances = BTreeMap::new();
+        for dividend in self.credits(dividends, as_of) {
+            entry(&mut balances, dividend.account_name()).credited += dividend.cash_credited();
+        }
+        for movement in self.movements.iter().filter(|m| m.date <= as_of) {
+            let balance = entry(&mut balances, &movement.account);
+            match movement.kind {
+                CashMovementKind::Withdrawal => balance.withdrawn += movement.amount,
+                CashMovementKind::Reinvestment => balance.reinvested += movement.amount,
+            }
+        }
+
+        balances.into_values().collect()
+    }
+}
+
+impl CashBalance {
+    /// Uninvested cash left in the account
+    pub fn balance(&self) -> Decimal {
+        self.credited - self.withdrawn - self.reinvested
+    }
 }
 
 impl DividendTracker {
@@ -237,6 +1245,18 @@ impl DividendTracker {
         DividendTracker {
             dividends: Vec::new(),
             holdings: HashMap::new(),
+            reclaims: Vec::new(),
+            symbol_aliases: BTreeMap::new(),
+            cash: None,
+            run_rate_history: BTreeMap::new(),
+            expenses: None,
+            journal: Vec::new(),
+            corporate_actions: Vec::new(),
+            projection_plans: BTreeMap::new(),
+            premiums: Vec::new(),
+            income_thresholds: BTreeMap::new(),
+            planned_expenses: Vec::new(),
+            securities: Securities::default(),
         }
     }
 
@@ -245,14 +1265,144 @@ impl DividendTracker {
         self.dividends.push(dividend);
     }
 
-    /// Add or update a holding
-    pub fn add_holding(&mut self, holding: Holding) {
-        self.holdings.insert(holding.symbol.clone(), holding);
+    /// Add a newly entered dividend the way `div add`, `--rpc` and the API all do
+    ///
+    /// A dividend already recorded for the symbol and ex-date is refused with
+    /// a [`DuplicateDividend`] error unless `force` is set. One still
+    /// unclassified is then classified from the kind of security; a copy of
+    /// the dividend as stored is returned with the [`Guess`] made.
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use dividend_tracker::config::ClassificationSettings;
+    /// use dividend_tracker::models::{Dividend, DividendTracker, DividendType, Guess, TaxClassification};
+    /// use rust_decimal::Decimal;
+    ///
+    /// let mut tracker = DividendTracker::new();
+    /// let ex_date = NaiveDate::from_ymd_opt(2024, 6, 27).unwrap();
+    /// let dividend = Dividend::new(
+    ///     "O".to_string(),
+    ///     Some("Realty Income REIT".to_string()),
+    ///     ex_date,
+    ///     ex_date,
+    ///     Decimal::new(2625, 4),
+    ///     Decimal::from(100),
+    ///     DividendType::Regular,
+    /// )?;
+    /// let (_, guess) = tracker.add_new_dividend(dividend, false, &ClassificationSettings::default())?;
+    /// let guess: Option<Guess> = guess;
+    /// assert_eq!(guess.map(|g| g.classification), Some(TaxClassification::NonQualified));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn add_new_dividend(
+        &mut self,
+        mut dividend: Dividend,
+        force: bool,
+        settings: &ClassificationSettings,
+    ) -> Result<(Dividend, Option<Guess>)> {
+        if !force {
+            if let Some(existing) = self.find_duplicate(&dividend.symbol, dividend.ex_date, dividend.account_name()) {
+                return Err(DuplicateDividend {
+                    existing: existing.clone(),
+                }
+                .into());
+            }
+        }
+        let guess = classify::classify_new(self, &mut dividend, settings);
+        self.dividends.push(dividend.clone());
+        Ok((dividend, guess))
+    }
+
+    /// Add or update the holding of a symbol in the holding's account
+    ///
+    /// Updating an existing holding keeps its forward dividend override,
+    /// adjustment history and identifiers unless the new record brings its own.
+    /// Positions of the symbol in other accounts are left alone.
+    pub fn add_holding(&mut self, mut holding: Holding) {
+        if let Some(existing) = self.holdings.get(&holding.key()) {
+            if holding.forward_dividend.is_none() {
+                holding.forward_dividend = existing.forward_dividend.clone();
+            }
+            if holding.adjustments.is_empty() {
+                holding.adjustments = existing.adjustments.clone();
+            }
+            if holding.isin.is_none() {
+                holding.isin = existing.isin.clone();
+            }
+            if holding.cusip.is_none() {
+                holding.cusip = existing.cusip.clone();
+            }
+            if holding.account.is_none() {
+                holding.account = existing.account.clone();
+            }
+        }
+        self.holdings.insert(holding.key(), holding);
+    }
+
+    /// Holdings that have not been archived
+    pub fn open_holdings(&self) -> impl Iterator<Item = (&HoldingKey, &Holding)> {
+        self.holdings.iter().filter(|(_, holding)| !holding.is_closed())
+    }
+
+    /// The holding of a symbol in an account
+    pub fn holding(&self, symbol: &str, account: &str) -> Option<&Holding> {
+        self.holdings.get(&HoldingKey::new(symbol, account))
+    }
+
+    /// The holding a dividend was paid on: its symbol in the dividend's account
+    pub fn holding_for(&self, dividend: &Dividend) -> Option<&Holding> {
+        self.holding(&dividend.symbol, dividend.account_name())
+    }
+
+    /// Holdings of a symbol across accounts, in account order
+    pub fn holdings_of(&self, symbol: &str) -> Vec<&Holding> {
+        let mut holdings: Vec<(&HoldingKey, &Holding)> =
+            self.holdings.iter().filter(|(key, _)| key.symbol == symbol).collect();
+        holdings.sort_by(|a, b| a.0.cmp(b.0));
+        holdings.into_iter().map(|(_, holding)| holding).collect()
+    }
+
+    /// Whether any account holds a symbol
+    pub fn holds(&self, symbol: &str) -> bool {
+        self.holdings.keys().any(|key| key.symbol == symbol)
+    }
+
+    /// Shares of a symbol held across every account; `None` when none holds it
+    pub fn shares_held(&self, symbol: &str) -> Option<Decimal> {
+        self.holdings
+            .values()
+            .filter(|h| h.symbol == symbol)
+            .map(|h| h.shares)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Key of the one holding of a symbol a command works on
+    ///
+    /// With an account that is the symbol's holding there; without one, the
+    /// only holding of the symbol. `None` when there is no such holding, and an
+    /// error when the symbol is held in several accounts and none was named.
+    pub fn position_key(&self, symbol: &str, account: Option<&str>) -> Result<Option<HoldingKey>> {
+        if let Some(account) = account {
+            let key = HoldingKey::new(symbol, account);
+            return Ok(self.holdings.contains_key(&key).then_some(key));
+        }
+        match self.holdings_of(symbol).as_slice() {
+            [] => Ok(None),
+            [holding] => Ok(Some(holding.key())),
+            several => {
+                let accounts: Vec<&str> = several.iter().map(|h| h.account_name()).collect();
+                bail!(
+                    "{} is held in several accounts ({}); pick one with --account",
+                    symbol,
+                    accounts.join(", ")
+                )
+            }
+        }
     }
 
     /// Get dividends for a specific symbol
     pub fn get_dividends_for_symbol(&self, symbol: &str) -> Vec<&Dividend> {
-        let symbol = symbol.trim().to_uppercase();
+        let symbol = normalize_symbol(symbol);
         self.dividends
             .iter()
             .filter(|div| div.symbol == symbol)
@@ -268,20 +1418,365 @@ impl DividendTracker {
             .sum()
     }
 
-    /// Check if a dividend with the same symbol and ex-date already exists
-    pub fn has_duplicate(&self, symbol: &str, ex_date: NaiveDate) -> bool {
-        let symbol = symbol.trim().to_uppercase();
+    /// Income paid by a symbol in the trailing twelve months ending on `today`
+    pub fn trailing_income(&self, symbol: &str, today: NaiveDate) -> Decimal {
+        let symbol = self.canonical_symbol(symbol);
+        let start = ttm_start(today);
         self.dividends
             .iter()
-            .any(|div| div.symbol == symbol && div.ex_date == ex_date)
+            .filter(|div| div.symbol == symbol && div.pay_date >= start && div.pay_date <= today)
+            .map(|div| div.net_amount())
+            .sum()
     }
 
-    /// Find existing dividend with same symbol and ex-date
-    pub fn find_duplicate(&self, symbol: &str, ex_date: NaiveDate) -> Option<&Dividend> {
-        let symbol = symbol.trim().to_uppercase();
+    /// Typical days from ex-date to pay date for a symbol: the median over
+    /// its recorded payments, or `default_days` when it has none
+    pub fn pay_lag_days(&self, symbol: &str, default_days: u32) -> i64 {
+        let symbol = self.canonical_symbol(symbol);
+        let mut lags: Vec<i64> = self
+            .dividends
+            .iter()
+            .filter(|div| div.symbol == symbol)
+            .map(|div| (div.pay_date - div.ex_date).num_days())
+            .collect();
+        if lags.is_empty() {
+            return i64::from(default_days);
+        }
+        lags.sort_unstable();
+        let mid = lags.len() / 2;
+        if lags.len().is_multiple_of(2) {
+            (lags[mid - 1] + lags[mid]) / 2
+        } else {
+            lags[mid]
+        }
+    }
+
+    /// Journal entries for a symbol, oldest first
+    pub fn journal_for(&self, symbol: &str) -> Vec<&JournalEntry> {
+        let symbol = self.canonical_symbol(symbol);
+        self.journal.iter().filter(|entry| entry.symbol == symbol).collect()
+    }
+
+    /// Check if a dividend with the same symbol and ex-date was already
+    /// credited to an account
+    pub fn has_duplicate(&self, symbol: &str, ex_date: NaiveDate, account: &str) -> bool {
+        self.find_duplicate(symbol, ex_date, account).is_some()
+    }
+
+    /// Find existing dividend with same symbol and ex-date in an account,
+    /// compared ignoring case
+    pub fn find_duplicate(&self, symbol: &str, ex_date: NaiveDate, account: &str) -> Option<&Dividend> {
+        let symbol = normalize_symbol(symbol);
+        let account = account.trim();
+        self.dividends.iter().find(|div| {
+            div.symbol == symbol && div.ex_date == ex_date && div.account_name().eq_ignore_ascii_case(account)
+        })
+    }
+
+    /// Company name for a dividend: the pinned one, else the one recorded with it
+    pub fn company_name<'a>(&'a self, dividend: &'a Dividend) -> Option<&'a str> {
+        self.securities
+            .name(&dividend.symbol)
+            .or(dividend.company_name.as_deref())
+    }
+
+    /// Tax classification of a dividend, with the symbol's pinned default
+    /// standing in for an unknown one
+    pub fn tax_classification(&self, dividend: &Dividend) -> TaxClassification {
+        match (&dividend.tax_classification, self.securities.tax_classification(&dividend.symbol)) {
+            (TaxClassification::Unknown, Some(pinned)) => pinned.clone(),
+            (recorded, _) => recorded.clone(),
+        }
+    }
+
+    /// The symbol records are kept under for a symbol as typed or imported
+    pub fn canonical_symbol(&self, symbol: &str) -> String {
+        let symbol = normalize_symbol(symbol);
+        self.symbol_aliases.get(&symbol).cloned().unwrap_or(symbol)
+    }
+
+    /// Symbols with a holding or dividend history
+    pub fn known_symbols(&self) -> BTreeSet<&str> {
+        self.holdings
+            .keys()
+            .map(|key| key.symbol.as_str())
+            .chain(self.dividends.iter().map(|d| d.symbol.as_str()))
+            .collect()
+    }
+
+    /// Suggestions for symbols in a comma-separated list that have no records
+    ///
+    /// Globs are skipped; `None` when every symbol is known or nothing is close.
+    pub fn symbol_hint(&self, symbols: &str) -> Option<String> {
+        let known = self.known_symbols();
+        let hints: Vec<String> = symbols
+            .split(',')
+            .filter(|s| !s.trim().is_empty() && !s.contains(['*', '?']))
+            .map(|s| self.canonical_symbol(s))
+            .filter(|s| !known.contains(s.as_str()))
+            .filter_map(|s| did_you_mean(&s, known.iter().copied()))
+            .collect();
+        (!hints.is_empty()).then(|| hints.join(" "))
+    }
+
+    /// Map `alias` to `symbol` and move records stored under the alias
+    ///
+    /// Returns the number of dividends moved and whether a holding moved.
+    pub fn add_symbol_alias(&mut self, alias: &str, symbol: &str) -> Result<(usize, bool)> {
+        let alias = normalize_symbol(alias);
+        let target = self.canonical_symbol(symbol);
+        if alias.is_empty() || target.is_empty() {
+            bail!("Symbol cannot be empty");
+        }
+        if alias == target {
+            bail!("{} cannot be an alias of itself", alias);
+        }
+
+        let clash = self.holdings_of(&alias).into_iter().find(|h| self.holding(&target, h.account_name()).is_some());
+        if let Some(holding) = clash {
+            bail!(
+                "Both {} and {} have holdings in the {} account. Remove one with 'holdings remove' before adding the alias.",
+                alias,
+                target,
+                holding.account_name()
+            );
+        }
+
+        let mut moved = 0;
+        for dividend in self.dividends.iter_mut().filter(|d| d.symbol == alias) {
+            dividend.symbol = target.clone();
+            moved += 1;
+        }
+        for reclaim in self.reclaims.iter_mut().filter(|r| r.symbol == alias) {
+            reclaim.symbol = target.clone();
+        }
+        for entry in self.journal.iter_mut().filter(|e| e.symbol == alias) {
+            entry.symbol = target.clone();
+        }
+        for premium in self.premiums.iter_mut().filter(|p| p.symbol == alias) {
+            premium.symbol = target.clone();
+        }
+        if let Some(threshold) = self.income_thresholds.remove(&alias) {
+            self.income_thresholds.entry(target.clone()).or_insert(threshold);
+        }
+        let moving: Vec<HoldingKey> = self.holdings.keys().filter(|key| key.symbol == alias).cloned().collect();
+        let holding_moved = !moving.is_empty();
+        for key in moving {
+            let mut holding = self.holdings.remove(&key).expect("holding exists");
+            holding.symbol = target.clone();
+            self.holdings.insert(holding.key(), holding);
+        }
+
+        // Aliases that pointed at the old spelling follow it
+        for existing in self.symbol_aliases.values_mut().filter(|s| **s == alias) {
+            *existing = target.clone();
+        }
+        self.symbol_aliases.insert(alias, target);
+
+        Ok((moved, holding_moved))
+    }
+
+    /// Remove an alias, returning the symbol it mapped to
+    pub fn remove_symbol_alias(&mut self, alias: &str) -> Result<String> {
+        let alias = normalize_symbol(alias);
+        self.symbol_aliases
+            .remove(&alias)
+            .ok_or_else(|| anyhow::anyhow!("No alias {} is defined", alias))
+    }
+
+    /// Start the cash ledger, crediting dividends paid from `since`
+    pub fn enable_cash_ledger(&mut self, since: NaiveDate) -> Result<()> {
+        if let Some(ledger) = &self.cash {
+            bail!("The cash ledger is already enabled (crediting dividends paid since {})", ledger.since);
+        }
+        self.cash = Some(CashLedger::new(since));
+        Ok(())
+    }
+
+    /// Record a buy or sell with `transactions`, returning the holding afterwards
+    ///
+    /// The trade is made in `account`, or in the symbol's only holding when no
+    /// account is named. Buying a symbol that isn't held there starts a holding
+    /// whose history begins with the purchase. A buy keeps its purchase yield:
+    /// the forward dividend as of the trade date over the price paid.
+    pub fn record_trade(
+        &mut self,
+        symbol: &str,
+        account: Option<&str>,
+        date: NaiveDate,
+        shares_delta: Decimal,
+        price: Decimal,
+    ) -> Result<Holding> {
+        let symbol = self.canonical_symbol(symbol);
+        let purchase_yield = if shares_delta > Decimal::ZERO && price > Decimal::ZERO {
+            run_rate::forward_dividend_per_share(self, &symbol, date).map(|annual| (annual / price * Decimal::ONE_HUNDRED).round_dp(4))
+        } else {
+            None
+        };
+        if let Some(key) = self.position_key(&symbol, account)? {
+            let holding = self.holdings.get_mut(&key).expect("holding exists");
+            holding.record_trade(date, shares_delta, price, purchase_yield)?;
+            return Ok(holding.clone());
+        }
+        if shares_delta < Decimal::ZERO {
+            match account {
+                Some(account) => bail!("No holding found for {} in {}; record a buy first", symbol, account),
+                None => bail!("No holding found for {}; record a buy first", symbol),
+            }
+        }
+        if price <= Decimal::ZERO {
+            bail!("Trade price must be positive");
+        }
+
+        let mut holding = Holding::new(symbol, shares_delta, Some(price), None)?;
+        if let Some(account) = account {
+            holding.set_account(account);
+        }
+        holding.adjustments.push(HoldingAdjustment {
+            date,
+            shares_delta,
+            price: Some(price),
+            shares_after: shares_delta,
+            avg_cost_after: Some(price),
+            purchase_yield,
+        });
+        self.holdings.insert(holding.key(), holding.clone());
+        Ok(holding)
+    }
+
+    /// Dividends whose recorded shares differ from those the trade history
+    /// says were held before the ex-date, with the shares held
+    pub fn ownership_mismatches(&self) -> Vec<(&Dividend, Decimal)> {
         self.dividends
             .iter()
-            .find(|div| div.symbol == symbol && div.ex_date == ex_date)
+            .filter_map(|dividend| {
+                let held = self.holding_for(dividend)?.shares_entitled_on(dividend.ex_date)?;
+                (held != dividend.shares_owned).then_some((dividend, held))
+            })
+            .collect()
+    }
+
+    /// Plan a large expense, keeping the plan in date order
+    pub fn plan_expense(&mut self, expense: PlannedExpense) -> Result<()> {
+        if expense.amount <= Decimal::ZERO {
+            bail!("A planned expense must be positive");
+        }
+        let at = self.planned_expenses.partition_point(|e| e.date <= expense.date);
+        self.planned_expenses.insert(at, expense);
+        Ok(())
+    }
+
+    /// Remove the expenses planned on `date`, returning them
+    pub fn remove_planned_expenses(&mut self, date: NaiveDate) -> Vec<PlannedExpense> {
+        let (removed, kept) = self.planned_expenses.drain(..).partition(|e| e.date == date);
+        self.planned_expenses = kept;
+        removed
+    }
+
+    /// Record a withdrawal or reinvestment, returning the account's balance afterwards
+    ///
+    /// The amount can't exceed the cash credited to the account by the
+    /// movement's date less everything already taken out of it.
+    pub fn record_cash_movement(&mut self, movement: CashMovement) -> Result<CashBalance> {
+        let Some(ledger) = self.cash.as_mut() else {
+            bail!("The cash ledger is not enabled. Start it with 'cash enable'.");
+        };
+        if movement.date < ledger.since {
+            bail!("The cash ledger starts on {}; {} is before that", ledger.since, movement.date);
+        }
+
+        let credited: Decimal = ledger
+            .credits(&self.dividends, movement.date)
+            .iter()
+            .filter(|d| d.account_name() == movement.account)
+            .map(|d| d.cash_credited())
+            .sum();
+        let taken: Decimal = ledger
+            .movements
+            .iter()
+            .filter(|m| m.account == movement.account)
+            .map(|m| m.amount)
+            .sum();
+        let available = credited - taken;
+        if movement.amount > available {
+            bail!(
+                "Only ${} of dividend cash is available in {} on {}",
+                available.round_dp(2),
+                movement.account,
+                movement.date
+            );
+        }
+
+        let account = movement.account.clone();
+        let as_of = movement.date;
+        ledger.movements.push(movement);
+        Ok(ledger
+            .balances(&self.dividends, as_of)
+            .into_iter()
+            .find(|b| b.account == account)
+            .unwrap_or_default())
+    }
+
+    /// Find the holding identified by an ISIN or CUSIP
+    pub fn find_holding_by_identifier(&self, id: &str) -> Option<&Holding> {
+        self.holdings.values().find(|h| h.has_identifier(id))
+    }
+
+    /// Record a reclaim against an existing dividend
+    ///
+    /// Each dividend can have one reclaim per country.
+    pub fn file_reclaim(&mut self, reclaim: WithholdingReclaim) -> Result<()> {
+        // Any account's payment will do; a reclaim doesn't name one
+        let symbol = normalize_symbol(&reclaim.symbol);
+        if !self.dividends.iter().any(|d| d.symbol == symbol && d.ex_date == reclaim.ex_date) {
+            bail!(
+                "No {} dividend with ex-date {} found. Record it with 'div add' first.",
+                reclaim.symbol,
+                reclaim.ex_date
+            );
+        }
+        if self.reclaims.iter().any(|r| {
+            r.symbol == reclaim.symbol && r.ex_date == reclaim.ex_date && r.country == reclaim.country
+        }) {
+            bail!(
+                "A reclaim for {} ({}) with ex-date {} is already on file",
+                reclaim.symbol,
+                reclaim.country,
+                reclaim.ex_date
+            );
+        }
+
+        self.reclaims.push(reclaim);
+        Ok(())
+    }
+
+    /// Find the reclaim filed for a dividend
+    ///
+    /// The country can be left out when only one reclaim exists for the dividend.
+    pub fn find_reclaim_mut(
+        &mut self,
+        symbol: &str,
+        ex_date: NaiveDate,
+        country: Option<&str>,
+    ) -> Result<&mut WithholdingReclaim> {
+        let symbol = normalize_symbol(symbol);
+        let country = country.map(|c| c.trim().to_uppercase());
+        let mut matches: Vec<&mut WithholdingReclaim> = self
+            .reclaims
+            .iter_mut()
+            .filter(|r| r.symbol == symbol && r.ex_date == ex_date)
+            .filter(|r| country.as_ref().is_none_or(|c| &r.country == c))
+            .collect();
+
+        match matches.len() {
+            0 => bail!("No reclaim on file for {} with ex-date {}", symbol, ex_date),
+            1 => Ok(matches.remove(0)),
+            _ => bail!(
+                "Several reclaims are on file for {} with ex-date {}. Pick one with --country.",
+                symbol,
+                ex_date
+            ),
+        }
     }
 }
 
@@ -302,6 +1797,10 @@ pub struct DividendCalendarEntry {
     pub ex_date: NaiveDate,
     /// Payment date (estimated or confirmed)
     pub pay_date: Option<NaiveDate>,
+    /// Whether the pay date was estimated from the symbol's usual ex-to-pay
+    /// lag because the provider didn't give one
+    #[serde(default)]
+    pub pay_date_estimated: bool,
     /// Dividend amount per share (estimated or confirmed)
     pub estimated_amount: Option<Decimal>,
     /// Whether this is an estimate based on historical data
@@ -322,6 +1821,19 @@ pub enum DividendFrequency {
     Irregular,
 }
 
+impl DividendFrequency {
+    /// Expected number of payments per year
+    pub fn payments_per_year(&self) -> u32 {
+        match self {
+            DividendFrequency::Monthly => 12,
+            DividendFrequency::Quarterly => 4,
+            DividendFrequency::SemiAnnual => 2,
+            DividendFrequency::Annual => 1,
+            DividendFrequency::Irregular => 1, // conservative assumption
+        }
+    }
+}
+
 /// Represents a dividend notification alert
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DividendAlert {
@@ -341,6 +1853,14 @@ pub struct DividendAlert {
     pub message: String,
 }
 
+/// The calendar and alerts, as carried in `dividends.json`, its backups and
+/// JSON exports so they move along with the rest of the data
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationData {
+    pub calendar: Vec<DividendCalendarEntry>,
+    pub alerts: Vec<DividendAlert>,
+}
+
 /// Types of dividend alerts
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AlertType {
@@ -356,6 +1876,12 @@ pub enum AlertType {
     DividendIncrease,
     /// Dividend cut
     DividendCut,
+    /// Pay date has passed but no matching dividend has been recorded
+    PaymentUnconfirmed,
+    /// Trailing-12-month income fell below the symbol's threshold
+    IncomeBelowTarget,
+    /// Trailing-12-month income rose above the symbol's threshold
+    IncomeAboveTarget,
 }
 
 impl DividendCalendarEntry {
@@ -372,10 +1898,11 @@ impl DividendCalendarEntry {
         let days_until_ex = (ex_date - today).num_days();
 
         DividendCalendarEntry {
-            symbol: symbol.trim().to_uppercase(),
+            symbol: normalize_symbol(&symbol),
             company_name,
             ex_date,
             pay_date,
+            pay_date_estimated: false,
             estimated_amount,
             is_estimated,
             frequency: None,
@@ -399,6 +1926,40 @@ impl DividendCalendarEntry {
     }
 }
 
+/// Holdings as saved: a map from a name to each holding
+///
+/// Holdings in the default account are named by their symbol, as they were
+/// before accounts; others by `SYMBOL@Account`. Loading goes by the symbol and
+/// account inside each holding, so the names only need to be unique.
+pub(crate) mod holding_map {
+    use super::{Holding, HoldingKey};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::{BTreeMap, HashMap};
+
+    pub fn serialize<S: Serializer>(holdings: &HashMap<HoldingKey, Holding>, serializer: S) -> Result<S::Ok, S::Error> {
+        let named: BTreeMap<String, &Holding> = holdings
+            .values()
+            .map(|holding| {
+                let name = match &holding.account {
+                    Some(account) => format!("{}@{}", holding.symbol, account),
+                    None => holding.symbol.clone(),
+                };
+                (name, holding)
+            })
+            .collect();
+        named.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<HoldingKey, Holding>, D::Error> {
+        Ok(from_named(HashMap::<String, Holding>::deserialize(deserializer)?))
+    }
+
+    /// Key holdings read from a saved map by their own symbol and account
+    pub fn from_named(named: HashMap<String, Holding>) -> HashMap<HoldingKey, Holding> {
+        named.into_values().map(|holding| (holding.key(), holding)).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -565,7 +2126,45 @@ mod tests {
 
         tracker.add_holding(holding);
         assert_eq!(tracker.holdings.len(), 1);
-        assert!(tracker.holdings.contains_key("AAPL"));
+        assert!(tracker.holds("AAPL"));
+    }
+
+    #[test]
+    fn test_duplicates_and_holdings_are_per_account() {
+        let mut tracker = DividendTracker::new();
+        let mut taxable = Holding::new("VZ".to_string(), dec!(50), None, None).unwrap();
+        taxable.set_account("Taxable");
+        tracker.add_holding(taxable);
+
+        let ex_date = NaiveDate::from_ymd_opt(2024, 4, 9).unwrap();
+        let mut dividend =
+            Dividend::new("VZ".to_string(), None, ex_date, ex_date, dec!(0.66), dec!(50), DividendType::Regular).unwrap();
+        dividend.account = Some("Taxable".to_string());
+        tracker.add_dividend(dividend);
+
+        assert!(tracker.has_duplicate("vz", ex_date, "taxable"));
+        assert!(!tracker.has_duplicate("VZ", ex_date, "IRA"));
+        assert!(!tracker.has_duplicate("VZ", ex_date, DEFAULT_ACCOUNT));
+
+        // A second account holds its own position beside the first
+        let mut ira = Holding::new("VZ".to_string(), dec!(20), None, None).unwrap();
+        ira.set_account("IRA");
+        tracker.add_holding(ira);
+        assert_eq!(tracker.holdings.len(), 2);
+        assert_eq!(tracker.holding("VZ", "taxable").unwrap().shares, dec!(50));
+        assert_eq!(tracker.holding("VZ", "IRA").unwrap().shares, dec!(20));
+        assert_eq!(tracker.shares_held("VZ"), Some(dec!(70)));
+        assert_eq!(tracker.holding_for(&tracker.dividends[0]).unwrap().shares, dec!(50));
+
+        // Commands on the symbol have to say which account they mean
+        assert!(tracker.position_key("VZ", None).is_err());
+        assert_eq!(tracker.position_key("VZ", Some("Ira")).unwrap(), Some(HoldingKey::new("VZ", "IRA")));
+        assert_eq!(tracker.position_key("VZ", Some("Roth")).unwrap(), None);
+
+        tracker.record_trade("VZ", Some("IRA"), ex_date, dec!(5), dec!(40)).unwrap();
+        assert_eq!(tracker.holding("VZ", "IRA").unwrap().shares, dec!(25));
+        assert_eq!(tracker.holding("VZ", "Taxable").unwrap().shares, dec!(50));
+        assert!(tracker.record_trade("VZ", None, ex_date, dec!(5), dec!(40)).is_err());
     }
 
     #[test]
@@ -671,4 +2270,279 @@ mod tests {
         assert!(deserialized.is_ok());
         assert_eq!(deserialized.unwrap(), dividend);
     }
+
+    #[test]
+    fn test_forward_dividend_payment_schedule() {
+        let forward = ForwardDividend::new(dec!(1.48), DividendFrequency::Quarterly).unwrap();
+        assert_eq!(forward.per_payment(), dec!(0.37));
+        assert_eq!(forward.assumed_payment_months(), vec![3, 6, 9, 12]);
+
+        assert!(ForwardDividend::new(dec!(0), DividendFrequency::Annual).is_err());
+    }
+
+    #[test]
+    fn test_updating_holding_keeps_forward_dividend() {
+        let mut tracker = DividendTracker::new();
+        let mut holding = Holding::new("NEW".to_string(), dec!(10), None, None).unwrap();
+        holding.forward_dividend = Some(ForwardDividend::new(dec!(2), DividendFrequency::Monthly).unwrap());
+        tracker.add_holding(holding);
+
+        tracker.add_holding(Holding::new("NEW".to_string(), dec!(25), None, None).unwrap());
+
+        let updated = tracker.holding("NEW", DEFAULT_ACCOUNT).unwrap();
+        assert_eq!(updated.shares, dec!(25));
+        assert_eq!(updated.forward_dividend.as_ref().unwrap().annual_amount, dec!(2));
+    }
+
+    #[test]
+    fn test_holding_adjust_recomputes_average_cost() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let mut holding = Holding::new("AAPL".to_string(), dec!(100), Some(dec!(150)), None).unwrap();
+
+        // 100 @ 150 + 25 @ 182.50 = 125 @ 156.50
+        holding.adjust(dec!(25), Some(dec!(182.50)), date).unwrap();
+        assert_eq!(holding.shares, dec!(125));
+        assert_eq!(holding.avg_cost_basis, Some(dec!(156.5)));
+
+        // Selling keeps the average
+        holding.adjust(dec!(-25), None, date).unwrap();
+        assert_eq!(holding.shares, dec!(100));
+        assert_eq!(holding.avg_cost_basis, Some(dec!(156.5)));
+
+        // A bare cost basis is a correction
+        holding.adjust(dec!(0), Some(dec!(155)), date).unwrap();
+        assert_eq!(holding.avg_cost_basis, Some(dec!(155)));
+
+        assert_eq!(holding.adjustments.len(), 3);
+        assert_eq!(holding.adjustments[0].shares_after, dec!(125));
+        assert_eq!(holding.adjustments[1].shares_delta, dec!(-25));
+    }
+
+    #[test]
+    fn test_holding_adjust_rejects_invalid_changes() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let mut holding = Holding::new("AAPL".to_string(), dec!(10), Some(dec!(150)), None).unwrap();
+
+        assert!(holding.adjust(dec!(-10), None, date).is_err());
+        assert!(holding.adjust(dec!(5), None, date).is_err());
+        assert!(holding.adjust(dec!(-5), Some(dec!(160)), date).is_err());
+        assert!(holding.adjust(dec!(0), None, date).is_err());
+        assert_eq!(holding.shares, dec!(10));
+        assert!(holding.adjustments.is_empty());
+    }
+
+    #[test]
+    fn test_shares_entitled_on_follows_recorded_trades() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let mut holding = Holding::new("KO".to_string(), dec!(100), None, None).unwrap();
+        assert_eq!(holding.shares_entitled_on(date("2024-03-14")), None);
+
+        holding.adjust(dec!(50), None, date("2024-03-14")).unwrap();
+        holding.adjust(dec!(-30), None, date("2024-06-01")).unwrap();
+        // Bought on the ex-date itself: too late for that dividend
+        assert_eq!(holding.shares_entitled_on(date("2024-03-14")), None);
+        assert_eq!(holding.shares_entitled_on(date("2024-03-15")), Some(dec!(150)));
+        assert_eq!(holding.shares_entitled_on(date("2024-09-12")), Some(dec!(120)));
+    }
+
+    #[test]
+    fn test_sales_are_taken_from_the_oldest_lots() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let lot = |id: &str, purchase_date: &str, shares| TaxLot {
+            id: id.to_string(),
+            purchase_date: date(purchase_date),
+            shares,
+            price: dec!(40),
+        };
+        let mut holding = Holding::new("KO".to_string(), dec!(150), None, None).unwrap();
+        holding.lots = vec![lot("B", "2021-03-01", dec!(50)), lot("A", "2020-03-01", dec!(100))];
+        holding.adjust(dec!(-120), None, date("2022-01-10")).unwrap();
+
+        let held = |on: &str| -> Vec<(String, Decimal)> {
+            holding.lot_shares_on(date(on)).into_iter().map(|(lot, shares)| (lot.id.clone(), shares)).collect()
+        };
+        // The sale still gets the dividend going ex on its own date
+        assert_eq!(held("2022-01-10"), vec![("A".to_string(), dec!(100)), ("B".to_string(), dec!(50))]);
+        assert_eq!(held("2022-01-11"), vec![("B".to_string(), dec!(30))]);
+        assert_eq!(holding.shares_entitled_on(date("2022-06-14")), Some(dec!(30)));
+    }
+
+    #[test]
+    fn test_record_trade_slots_backdated_trades_in() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let mut tracker = DividendTracker::new();
+        tracker.record_trade("ko", None, date("2022-06-01"), dec!(50), dec!(60)).unwrap();
+        tracker.record_trade("KO", None, date("2023-02-01"), dec!(-30), dec!(65)).unwrap();
+        let ko = tracker.record_trade("KO", None, date("2021-03-01"), dec!(100), dec!(50)).unwrap();
+
+        let history: Vec<_> = ko.adjustments.iter().map(|a| (a.date, a.shares_after, a.avg_cost_after)).collect();
+        assert_eq!(
+            history,
+            vec![
+                (date("2021-03-01"), dec!(100), Some(dec!(50))),
+                (date("2022-06-01"), dec!(150), Some(dec!(53.3333))),
+                (date("2023-02-01"), dec!(120), Some(dec!(53.3333))),
+            ]
+        );
+        assert_eq!(ko.shares, dec!(120));
+        assert_eq!(ko.shares_entitled_on(date("2022-06-01")), Some(dec!(100)));
+
+        // A sale can't take an earlier point in the history below zero
+        assert!(tracker.record_trade("KO", None, date("2021-06-01"), dec!(-101), dec!(55)).is_err());
+        assert!(tracker.record_trade("PEP", None, date("2021-06-01"), dec!(-1), dec!(55)).is_err());
+        assert_eq!(tracker.holding("KO", DEFAULT_ACCOUNT).unwrap().adjustments.len(), 3);
+
+        let ko = tracker.record_trade("KO", None, date("2024-01-05"), dec!(-120), dec!(70)).unwrap();
+        assert_eq!(ko.closed_date, Some(date("2024-01-05")));
+        assert!(tracker.record_trade("KO", None, date("2023-12-01"), dec!(10), dec!(60)).is_err());
+
+        let paid = |ex: &str, shares| {
+            Dividend::new("KO".to_string(), None, date(ex), date(ex), dec!(0.44), shares, DividendType::Regular).unwrap()
+        };
+        tracker.add_dividend(paid("2022-06-14", dec!(150)));
+        tracker.add_dividend(paid("2023-03-14", dec!(150)));
+        let mismatches: Vec<_> = tracker.ownership_mismatches().iter().map(|(d, held)| (d.ex_date, *held)).collect();
+        assert_eq!(mismatches, vec![(date("2023-03-14"), dec!(120))]);
+    }
+
+    #[test]
+    fn test_archived_holding_keeps_history_and_leaves_open_holdings() {
+        let date = NaiveDate::from_ymd_opt(2024, 9, 30).unwrap();
+        let mut tracker = DividendTracker::new();
+        let mut holding = Holding::new("T".to_string(), dec!(200), Some(dec!(18)), None).unwrap();
+        holding.archive(date).unwrap();
+        assert!(holding.archive(date).is_err());
+        tracker.add_holding(holding);
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(10), None, None).unwrap());
+
+        let archived = tracker.holding("T", DEFAULT_ACCOUNT).unwrap();
+        assert_eq!(archived.shares, dec!(0));
+        assert_eq!(archived.avg_cost_basis, Some(dec!(18)));
+        assert_eq!(archived.adjustments[0].shares_delta, dec!(-200));
+
+        let open: Vec<_> = tracker.open_holdings().map(|(key, _)| key.symbol.as_str()).collect();
+        assert_eq!(open, vec!["KO"]);
+
+        // Buying back in reopens the position at the new price
+        let mut reopened = archived.clone();
+        reopened.adjust(dec!(50), Some(dec!(20)), date).unwrap();
+        assert!(!reopened.is_closed());
+        assert_eq!(reopened.avg_cost_basis, Some(dec!(20)));
+    }
+
+    #[test]
+    fn test_dividend_fees_and_net_amount() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut dividend =
+            Dividend::new("TSM".to_string(), None, date, date, dec!(0.50), dec!(100), DividendType::Regular).unwrap();
+        assert_eq!(dividend.net_amount(), dec!(50));
+
+        dividend.set_fees(dec!(0.75)).unwrap();
+        assert_eq!(dividend.fee_amount(), dec!(0.75));
+        assert_eq!(dividend.net_amount(), dec!(49.25));
+
+        assert!(dividend.set_fees(dec!(-1)).is_err());
+        assert!(dividend.set_fees(dec!(51)).is_err());
+        dividend.set_fees(dec!(0)).unwrap();
+        assert_eq!(dividend.fees, None);
+    }
+
+    #[test]
+    fn test_dividend_withholding_and_country() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 30).unwrap();
+        let mut dividend =
+            Dividend::new("NESN".to_string(), None, date, date, dec!(3), dec!(100), DividendType::Regular).unwrap();
+        dividend.set_fees(dec!(2)).unwrap();
+
+        dividend.set_withholding(dec!(105)).unwrap();
+        assert_eq!(dividend.withholding_amount(), dec!(105));
+        assert_eq!(dividend.cash_credited(), dec!(193));
+        assert!(dividend.set_withholding(dec!(-1)).is_err());
+        assert!(dividend.set_withholding(dec!(299)).is_err(), "more than is left after fees");
+
+        assert_eq!(dividend.source_country(), None);
+        dividend.set_country(" ch ").unwrap();
+        assert_eq!(dividend.country.as_deref(), Some("CH"));
+        assert!(dividend.set_country("CHE").is_err());
+
+        let listed = Dividend::new("NESN.SW".to_string(), None, date, date, dec!(3), dec!(100), DividendType::Regular)
+            .unwrap();
+        assert_eq!(listed.source_country(), Some("CH"));
+    }
+
+    #[test]
+    fn test_withholding_reclaim_lifecycle() {
+        let ex_date = NaiveDate::from_ymd_opt(2024, 4, 26).unwrap();
+        let filed = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(
+            Dividend::new("NESN".to_string(), None, ex_date, ex_date, dec!(3), dec!(100), DividendType::Regular)
+                .unwrap(),
+        );
+
+        let reclaim = WithholdingReclaim::new("nesn", ex_date, "ch", dec!(60), filed).unwrap();
+        tracker.file_reclaim(reclaim.clone()).unwrap();
+        assert!(tracker.file_reclaim(reclaim).is_err(), "one reclaim per country");
+
+        let missing = WithholdingReclaim::new("ROG", ex_date, "CH", dec!(10), filed).unwrap();
+        assert!(tracker.file_reclaim(missing).is_err());
+
+        let reclaim = tracker.find_reclaim_mut("NESN", ex_date, None).unwrap();
+        assert_eq!(reclaim.outstanding(), dec!(60));
+        assert!(reclaim.record_refund(dec!(70), filed).is_err());
+        reclaim.record_refund(dec!(55), filed + chrono::Duration::days(200)).unwrap();
+        assert!(!reclaim.is_pending());
+        assert_eq!(reclaim.outstanding(), dec!(0));
+        assert!(reclaim.record_refund(dec!(5), filed).is_err());
+
+        assert!(WithholdingReclaim::new("NESN", ex_date, "CH", dec!(0), filed).is_err());
+        assert!(WithholdingReclaim::new("NESN", ex_date, "CH", dec!(1), ex_date.pred_opt().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_symbol_aliases_move_records() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 22).unwrap();
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(
+            Dividend::new("BP:LN".to_string(), None, date, date, dec!(0.06), dec!(100), DividendType::Regular)
+                .unwrap(),
+        );
+        assert_eq!(tracker.dividends[0].symbol, "BP.L");
+
+        tracker.add_dividend(
+            Dividend::new("BP".to_string(), None, date, date, dec!(0.06), dec!(100), DividendType::Regular).unwrap(),
+        );
+        tracker.add_holding(Holding::new("BP".to_string(), dec!(100), None, None).unwrap());
+
+        assert_eq!(tracker.add_symbol_alias("bp", "BP LN").unwrap(), (1, true));
+        assert!(tracker.dividends.iter().all(|d| d.symbol == "BP.L"));
+        assert!(tracker.holds("BP.L"));
+        assert_eq!(tracker.canonical_symbol("bp"), "BP.L");
+
+        assert!(tracker.add_symbol_alias("BP.L", "BP").is_err(), "alias of itself");
+        assert_eq!(tracker.remove_symbol_alias("BP").unwrap(), "BP.L");
+        assert!(tracker.remove_symbol_alias("BP").is_err());
+        assert_eq!(tracker.canonical_symbol("bp"), "BP");
+    }
+
+    #[test]
+    fn test_add_new_dividend_refuses_duplicates_before_classifying() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 28).unwrap();
+        let reit = || Dividend::new("O".to_string(), None, date, date, dec!(0.26), dec!(100), DividendType::Regular).unwrap();
+        let settings = ClassificationSettings::default();
+        let mut tracker = DividendTracker::new();
+
+        let (added, guess) = tracker.add_new_dividend(reit(), false, &settings).unwrap();
+        assert_eq!(added.tax_classification, TaxClassification::NonQualified);
+        assert_eq!(guess.unwrap().reason, "O is a REIT");
+
+        let refused = tracker.add_new_dividend(reit(), false, &settings).unwrap_err();
+        let duplicate = refused.downcast_ref::<DuplicateDividend>().unwrap();
+        assert_eq!(duplicate.existing.symbol, "O");
+        assert_eq!(refused.to_string(), "Duplicate dividend exists for O on 2024-06-28");
+        assert_eq!(tracker.dividends.len(), 1);
+
+        assert!(tracker.add_new_dividend(reit(), true, &settings).is_ok());
+        assert_eq!(tracker.dividends.len(), 2);
+    }
 }
@@ -1,75 +1,112 @@
 use anyhow::{anyhow, Result};
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use colored::*;
 use rust_decimal::Decimal;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 use crate::api::AlphaVantageClient;
-use crate::holdings;
+use crate::format::format_money;
 use crate::models::{
-    AlertType, DividendAlert, DividendCalendarEntry, DividendFrequency, DividendTracker, Holding,
+    AlertType, Dividend, DividendAlert, DividendCalendarEntry, DividendFrequency, DividendTracker, Holding,
+    NotificationData, PlannedExpense, ThresholdDirection,
 };
+use crate::paths;
 
-/// Data directory for storing notifications
-const DATA_DIR: &str = "data";
+/// Notification files, stored next to the dividend data
 const CALENDAR_FILE: &str = "dividend_calendar.json";
 const ALERTS_FILE: &str = "dividend_alerts.json";
 
+/// How far a recorded dividend's dates may be from an estimated calendar
+/// entry's and still count as that payment
+const CONFIRMATION_WINDOW_DAYS: i64 = 10;
+
+/// Payments landing this many days after a planned expense are listed as
+/// just missing it
+const JUST_AFTER_DAYS: i64 = 14;
+
+/// A calendar payment in dollars, for the shares held
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedPayment {
+    pub symbol: String,
+    pub pay_date: NaiveDate,
+    pub amount: Decimal,
+}
+
+/// Whether dividend cash expected by a planned expense's date covers it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedExpenseCoverage {
+    pub expense: PlannedExpense,
+    /// Cash left from the ledger and earlier payments after earlier planned expenses
+    pub carried_cash: Decimal,
+    /// Payments expected from the previous planned expense up to this one's date
+    pub expected_dividends: Decimal,
+    /// What the cash falls short of the expense by, zero when covered
+    pub shortfall: Decimal,
+    /// Payments expected within two weeks after the date, which arrive too late
+    pub paying_after: Vec<ExpectedPayment>,
+}
+
+impl PlannedExpenseCoverage {
+    /// Cash available on the expense's date
+    pub fn available(&self) -> Decimal {
+        self.carried_cash + self.expected_dividends
+    }
+}
+
 /// Notifications manager for dividend alerts and calendar
 pub struct NotificationManager {
     /// Dividend calendar entries
     pub calendar: Vec<DividendCalendarEntry>,
     /// Active alerts
     pub alerts: Vec<DividendAlert>,
+    /// Data directory holding the notification files and portfolio data
+    data_dir: PathBuf,
 }
 
 impl NotificationManager {
-    /// Create a new notification manager
-    pub fn new() -> Self {
+    /// Create an empty notification manager for a data directory
+    pub fn new<P: AsRef<Path>>(data_dir: P) -> Self {
         NotificationManager {
             calendar: Vec::new(),
             alerts: Vec::new(),
+            data_dir: data_dir.as_ref().to_path_buf(),
         }
     }
 
-    /// Load notifications from disk
-    pub fn load() -> Result<Self> {
-        let data_dir = Path::new(DATA_DIR);
-        if !data_dir.exists() {
-            fs::create_dir_all(data_dir)?;
-        }
-
-        let calendar_path = data_dir.join(CALENDAR_FILE);
-        let alerts_path = data_dir.join(ALERTS_FILE);
+    /// Load notifications from a data directory
+    pub fn load<P: AsRef<Path>>(data_dir: P) -> Result<Self> {
+        let mut manager = NotificationManager::new(data_dir);
+        let calendar_path = manager.data_dir.join(CALENDAR_FILE);
+        let alerts_path = manager.data_dir.join(ALERTS_FILE);
 
-        let calendar = if calendar_path.exists() {
+        if calendar_path.exists() {
             let contents = fs::read_to_string(&calendar_path)?;
-            serde_json::from_str(&contents)?
-        } else {
-            Vec::new()
-        };
+            manager.calendar = serde_json::from_str(&contents)?;
+
+            // The stored countdown is from when the entry was fetched
+            let today = Local::now().naive_local().date();
+            for entry in &mut manager.calendar {
+                entry.days_until_ex = (entry.ex_date - today).num_days();
+            }
+        }
 
-        let alerts = if alerts_path.exists() {
+        if alerts_path.exists() {
             let contents = fs::read_to_string(&alerts_path)?;
-            serde_json::from_str(&contents)?
-        } else {
-            Vec::new()
-        };
+            manager.alerts = serde_json::from_str(&contents)?;
+        }
 
-        Ok(NotificationManager { calendar, alerts })
+        Ok(manager)
     }
 
     /// Save notifications to disk
     pub fn save(&self) -> Result<()> {
-        let data_dir = Path::new(DATA_DIR);
-        if !data_dir.exists() {
-            fs::create_dir_all(data_dir)?;
-        }
+        fs::create_dir_all(&self.data_dir)?;
 
-        let calendar_path = data_dir.join(CALENDAR_FILE);
-        let alerts_path = data_dir.join(ALERTS_FILE);
+        let calendar_path = self.data_dir.join(CALENDAR_FILE);
+        let alerts_path = self.data_dir.join(ALERTS_FILE);
 
         fs::write(calendar_path, serde_json::to_string_pretty(&self.calendar)?)?;
         fs::write(alerts_path, serde_json::to_string_pretty(&self.alerts)?)?;
@@ -77,16 +114,36 @@ impl NotificationManager {
         Ok(())
     }
 
+    /// Copy of the calendar and alerts
+    pub fn data(&self) -> NotificationData {
+        NotificationData {
+            calendar: self.calendar.clone(),
+            alerts: self.alerts.clone(),
+        }
+    }
+
+    /// Replace the calendar and alerts, e.g. with those from a backup
+    pub fn replace(&mut self, data: NotificationData) {
+        self.calendar = data.calendar;
+        self.alerts = data.alerts;
+    }
+
     /// Fetch upcoming dividends for portfolio holdings
-    pub fn fetch_upcoming_dividends(&mut self, client: &AlphaVantageClient) -> Result<()> {
+    ///
+    /// Pay dates are estimated from each symbol's usual ex-to-pay lag, or
+    /// `default_pay_lag_days` for symbols without recorded payments.
+    pub fn fetch_upcoming_dividends(
+        &mut self,
+        tracker: &DividendTracker,
+        client: &AlphaVantageClient,
+        default_pay_lag_days: u32,
+    ) -> Result<()> {
         println!(
             "{}",
             "Fetching upcoming dividend calendar...".green().bold()
         );
 
-        // Load current holdings
-        let tracker = holdings::load_holdings()?;
-        if tracker.holdings.is_empty() {
+        if tracker.open_holdings().next().is_none() {
             return Err(anyhow!("No holdings found. Please add holdings first."));
         }
 
@@ -94,27 +151,45 @@ impl NotificationManager {
         let today = Local::now().naive_local().date();
         let end_date = today + Duration::days(90);
 
-        // Clear old calendar entries
-        self.calendar.clear();
-
-        let total_symbols = tracker.holdings.len();
+        // Clear old calendar entries, keeping past payments still waiting to be recorded
+        self.calendar
+            .retain(|entry| awaiting_confirmation(entry, &tracker.dividends, today));
+
+        // One fetch per symbol, however many accounts hold it; a holding
+        // with a forward dividend speaks for the symbol
+        let mut symbols: BTreeMap<&str, &Holding> = BTreeMap::new();
+        for (key, holding) in tracker.open_holdings() {
+            let chosen = symbols.entry(key.symbol.as_str()).or_insert(holding);
+            if chosen.forward_dividend.is_none() {
+                *chosen = holding;
+            }
+        }
+        let total_symbols = symbols.len();
         let mut fetched_count = 0;
 
         // Fetch calendar for each holding
-        for (symbol, holding) in &tracker.holdings {
+        for (symbol, holding) in symbols {
             println!("Fetching calendar for {}...", symbol.cyan());
+            let pay_lag = tracker.pay_lag_days(symbol, default_pay_lag_days);
 
             // Fetch historical dividends to estimate upcoming ones
             match client.fetch_dividends(symbol, Some(today - Duration::days(365)), Some(today)) {
                 Ok(historical) => {
                     if !historical.is_empty() {
                         // Estimate next dividend based on historical pattern
+                        let pinned = tracker.securities.frequency(symbol);
                         if let Some(estimated_entry) =
-                            estimate_next_dividend(symbol, &historical, today, end_date, holding)
+                            estimate_next_dividend(symbol, &historical, today, end_date, holding, pinned, pay_lag)
                         {
                             self.calendar.push(estimated_entry);
                             fetched_count += 1;
                         }
+                    } else if let Some(estimated_entry) =
+                        estimate_from_forward_dividend(symbol, holding, today, end_date, pay_lag)
+                    {
+                        println!("  {} No history, using forward dividend", "ℹ".blue());
+                        self.calendar.push(estimated_entry);
+                        fetched_count += 1;
                     } else {
                         println!("  {} No historical dividend data available", "⚠".yellow());
                     }
@@ -129,7 +204,7 @@ impl NotificationManager {
         }
 
         // Sort calendar by ex-date
-        self.calendar.sort_by(|a, b| a.ex_date.cmp(&b.ex_date));
+        self.calendar.sort_by_key(|entry| entry.ex_date);
 
         println!();
         println!(
@@ -145,21 +220,46 @@ impl NotificationManager {
         Ok(())
     }
 
-    /// Generate alerts for upcoming ex-dates
-    pub fn generate_alerts(&mut self) -> Result<()> {
-        // Load current holdings
-        let tracker = holdings::load_holdings()?;
+    /// Add a declared dividend to the calendar, replacing the estimate for
+    /// the same payment; `false` if it was already there
+    ///
+    /// A declared entry whose pay date was estimated is replaced once the
+    /// pay date is announced.
+    pub fn add_announced(&mut self, entry: DividendCalendarEntry) -> bool {
+        let same_payment = |existing: &DividendCalendarEntry| {
+            existing.symbol == entry.symbol
+                && (existing.ex_date - entry.ex_date).num_days().abs() <= CONFIRMATION_WINDOW_DAYS
+        };
+        let replaceable = |existing: &DividendCalendarEntry| {
+            existing.is_estimated || (existing.pay_date_estimated && !entry.pay_date_estimated)
+        };
+        if self.calendar.iter().any(|e| !replaceable(e) && same_payment(e)) {
+            return false;
+        }
+
+        self.calendar.retain(|e| !(replaceable(e) && same_payment(e)));
+        self.calendar.push(entry);
+        self.calendar.sort_by_key(|e| e.ex_date);
+        true
+    }
 
+    /// Generate alerts for upcoming ex-dates and crossed income thresholds
+    pub fn generate_alerts(&mut self, tracker: &DividendTracker) -> Result<()> {
         // Clear old alerts
         self.alerts.clear();
 
-        let _today = Local::now().naive_local().date();
+        let today = Local::now().naive_local().date();
 
         for entry in &self.calendar {
-            if let Some(alert_type) = entry.get_alert_type() {
+            let alert_type = if awaiting_confirmation(entry, &tracker.dividends, today) {
+                Some(AlertType::PaymentUnconfirmed)
+            } else {
+                entry.get_alert_type()
+            };
+
+            if let Some(alert_type) = alert_type {
                 // Get holding information
-                let holding = tracker.holdings.get(&entry.symbol);
-                let shares = holding.map(|h| h.shares);
+                let shares = tracker.shares_held(&entry.symbol);
                 let estimated_income = match (entry.estimated_amount, shares) {
                     (Some(amount), Some(shares)) => Some(amount * shares),
                     _ => None,
@@ -180,6 +280,7 @@ impl NotificationManager {
                 self.alerts.push(alert);
             }
         }
+        self.alerts.extend(threshold_alerts(tracker, today));
 
         self.save()?;
         Ok(())
@@ -200,6 +301,9 @@ impl NotificationManager {
                 AlertType::ExDateTomorrow => "🚨",
                 AlertType::ExDateThisWeek => "⚠️",
                 AlertType::ExDateThisMonth => "ℹ️",
+                AlertType::PaymentUnconfirmed => "💵",
+                AlertType::IncomeBelowTarget => "📉",
+                AlertType::IncomeAboveTarget => "📈",
                 _ => "📌",
             };
 
@@ -227,15 +331,21 @@ impl NotificationManager {
     }
 
     /// Display dividend calendar
-    pub fn show_calendar(&self, days: Option<i64>) -> Result<()> {
+    pub fn show_calendar(&self, tracker: &DividendTracker, days: Option<i64>) -> Result<()> {
+        let filter_days = days.unwrap_or(90);
+        let today = Local::now().naive_local().date();
+        let coverage: Vec<PlannedExpenseCoverage> = self
+            .planned_expense_coverage(tracker, today)
+            .into_iter()
+            .filter(|c| (c.expense.date - today).num_days() <= filter_days)
+            .collect();
+
         if self.calendar.is_empty() {
             println!("{}", "No upcoming dividends in calendar.".yellow());
+            show_planned_expenses(&coverage, today);
             return Ok(());
         }
 
-        let filter_days = days.unwrap_or(90);
-        let _today = Local::now().naive_local().date();
-
         println!("{}", "📅 Dividend Calendar".green().bold());
         println!();
 
@@ -266,9 +376,11 @@ impl NotificationManager {
                 }
 
                 if let Some(pay_date) = entry.pay_date {
+                    let estimated_text = if entry.pay_date_estimated { " (estimated)" } else { "" };
                     println!(
-                        "  Pay date: {}",
-                        pay_date.format("%Y-%m-%d").to_string().dimmed()
+                        "  Pay date: {}{}",
+                        pay_date.format("%Y-%m-%d").to_string().dimmed(),
+                        estimated_text.dimmed()
                     );
                 }
 
@@ -287,12 +399,76 @@ impl NotificationManager {
                 filter_days
             );
         }
+        show_planned_expenses(&coverage, today);
 
         Ok(())
     }
 
+    /// Upcoming calendar payments in dollars for the shares held, by pay date
+    ///
+    /// Entries without a pay date or amount, or for symbols no longer held,
+    /// can't be counted on.
+    pub fn expected_payments(&self, tracker: &DividendTracker, today: NaiveDate) -> Vec<ExpectedPayment> {
+        let mut payments: Vec<ExpectedPayment> = self
+            .calendar
+            .iter()
+            .filter_map(|entry| {
+                let pay_date = entry.pay_date.filter(|&d| d >= today)?;
+                let shares = tracker.shares_held(&entry.symbol)?;
+                let amount = entry.estimated_amount? * shares;
+                (amount > Decimal::ZERO).then(|| ExpectedPayment {
+                    symbol: entry.symbol.clone(),
+                    pay_date,
+                    amount,
+                })
+            })
+            .collect();
+        payments.sort_by(|a, b| a.pay_date.cmp(&b.pay_date).then_with(|| a.symbol.cmp(&b.symbol)));
+        payments
+    }
+
+    /// Check each planned expense from `today` on against the dividend cash expected by its date
+    ///
+    /// Cash starts from the ledger balance when the cash ledger is on. Each
+    /// expense spends what's available first, so a later one only counts the
+    /// cash left over; a shortfall is assumed to be met from elsewhere.
+    pub fn planned_expense_coverage(&self, tracker: &DividendTracker, today: NaiveDate) -> Vec<PlannedExpenseCoverage> {
+        let payments = self.expected_payments(tracker, today);
+        let mut cash: Decimal = tracker
+            .cash
+            .as_ref()
+            .map(|ledger| ledger.balances(&tracker.dividends, today).iter().map(|b| b.balance()).sum())
+            .unwrap_or_default();
+        let mut next = 0;
+
+        let mut coverage = Vec::new();
+        for expense in tracker.planned_expenses.iter().filter(|e| e.date >= today) {
+            let mut expected_dividends = Decimal::ZERO;
+            while let Some(payment) = payments.get(next).filter(|p| p.pay_date <= expense.date) {
+                expected_dividends += payment.amount;
+                next += 1;
+            }
+            let paying_after = payments[next..]
+                .iter()
+                .take_while(|p| (p.pay_date - expense.date).num_days() <= JUST_AFTER_DAYS)
+                .cloned()
+                .collect();
+
+            let available = cash + expected_dividends;
+            coverage.push(PlannedExpenseCoverage {
+                expense: expense.clone(),
+                carried_cash: cash,
+                expected_dividends,
+                shortfall: (expense.amount - available).max(Decimal::ZERO),
+                paying_after,
+            });
+            cash = (available - expense.amount).max(Decimal::ZERO);
+        }
+        coverage
+    }
+
     /// Export calendar to ICS format
-    pub fn export_to_ics(&self, output_path: &str) -> Result<()> {
+    pub fn export_to_ics(&self, output_path: &Path) -> Result<()> {
         let mut ics_content = String::new();
 
         // ICS header
@@ -340,6 +516,9 @@ impl NotificationManager {
                 }
                 if let Some(pay_date) = entry.pay_date {
                     description.push_str(&format!("\\nPay Date: {}", pay_date.format("%Y-%m-%d")));
+                    if entry.pay_date_estimated {
+                        description.push_str(" (estimated)");
+                    }
                 }
                 ics_content.push_str(&format!("DESCRIPTION:{}\r\n", description));
 
@@ -360,12 +539,13 @@ impl NotificationManager {
         ics_content.push_str("END:VCALENDAR\r\n");
 
         // Write to file
+        paths::ensure_parent_dir(output_path)?;
         fs::write(output_path, ics_content)?;
 
         println!(
             "{} Calendar exported to {}",
             "✓".green(),
-            output_path.cyan()
+            output_path.display().to_string().cyan()
         );
 
         Ok(())
@@ -373,12 +553,67 @@ impl NotificationManager {
 }
 
 /// Estimate next dividend based on historical patterns
+/// Print planned expenses under the calendar, flagging any the expected cash falls short of
+fn show_planned_expenses(coverage: &[PlannedExpenseCoverage], today: NaiveDate) {
+    if coverage.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "💸 Planned Expenses".green().bold());
+    println!();
+
+    for check in coverage {
+        let expense = &check.expense;
+        let days_text = match (expense.date - today).num_days() {
+            0 => "TODAY".red().bold().to_string(),
+            1 => "Tomorrow".yellow().to_string(),
+            d => format!("In {} days", d).cyan().to_string(),
+        };
+        println!(
+            "{} - {}{} - {}",
+            expense.date.format("%Y-%m-%d").to_string().blue(),
+            format_money(expense.amount).bold(),
+            expense.note.as_ref().map(|n| format!(" ({})", n)).unwrap_or_default(),
+            days_text
+        );
+        println!(
+            "  Expected cash by then: {} ({} in dividends, {} on hand)",
+            format_money(check.available()),
+            format_money(check.expected_dividends),
+            format_money(check.carried_cash)
+        );
+
+        if check.shortfall > Decimal::ZERO {
+            println!(
+                "  {} Shortfall of {}: dividends expected by then don't cover it",
+                "⚠".yellow(),
+                format_money(check.shortfall).red().bold()
+            );
+        } else {
+            println!("  {} Covered", "✓".green());
+        }
+
+        if !check.paying_after.is_empty() {
+            let after: Vec<String> = check
+                .paying_after
+                .iter()
+                .map(|p| format!("{} {} on {}", p.symbol, format_money(p.amount), p.pay_date.format("%Y-%m-%d")))
+                .collect();
+            println!("  Paying just after: {}", after.join(", ").dimmed());
+        }
+        println!();
+    }
+}
+
 fn estimate_next_dividend(
     symbol: &str,
     historical: &[crate::api::DividendData],
     today: NaiveDate,
     end_date: NaiveDate,
-    _holding: &Holding,
+    holding: &Holding,
+    pinned: Option<&DividendFrequency>,
+    pay_lag: i64,
 ) -> Option<DividendCalendarEntry> {
     if historical.is_empty() {
         return None;
@@ -392,13 +627,21 @@ fn estimate_next_dividend(
         historical.iter().map(|d| d.amount).sum::<Decimal>() / Decimal::from(historical.len());
 
     // Detect frequency (simplified - assumes quarterly if 3-5 dividends per year)
-    let frequency = match historical.len() {
+    let detected_frequency = match historical.len() {
         1..=2 => DividendFrequency::SemiAnnual,
         3..=5 => DividendFrequency::Quarterly,
         11..=13 => DividendFrequency::Monthly,
         _ => DividendFrequency::Irregular,
     };
 
+    // A forward dividend override takes precedence over the detected pattern,
+    // and a pinned frequency over both
+    let (avg_amount, frequency) = match &holding.forward_dividend {
+        Some(forward) => (forward.per_payment(), forward.frequency.clone()),
+        None => (avg_amount, detected_frequency),
+    };
+    let frequency = pinned.cloned().unwrap_or(frequency);
+
     // Estimate next ex-date based on frequency
     let days_to_add = match frequency {
         DividendFrequency::Monthly => 30,
@@ -416,10 +659,11 @@ fn estimate_next_dividend(
             symbol.to_string(),
             None,
             estimated_ex_date,
-            Some(estimated_ex_date + Duration::days(7)), // Estimate pay date as 7 days after ex
+            Some(estimated_ex_date + Duration::days(pay_lag)),
             Some(avg_amount),
             true, // This is an estimate
         );
+        entry.pay_date_estimated = true;
         entry.frequency = Some(frequency);
         Some(entry)
     } else {
@@ -427,6 +671,93 @@ fn estimate_next_dividend(
     }
 }
 
+/// Estimate the next payment of a holding without history from its forward dividend
+///
+/// Payments are assumed mid-month in the months implied by the frequency.
+fn estimate_from_forward_dividend(
+    symbol: &str,
+    holding: &Holding,
+    today: NaiveDate,
+    end_date: NaiveDate,
+    pay_lag: i64,
+) -> Option<DividendCalendarEntry> {
+    let forward = holding.forward_dividend.as_ref()?;
+    let months = forward.assumed_payment_months();
+
+    let estimated_ex_date = (0..=12)
+        .filter_map(|offset| {
+            let month_index = today.month0() + offset;
+            NaiveDate::from_ymd_opt(today.year() + (month_index / 12) as i32, month_index % 12 + 1, 15)
+        })
+        .find(|date| *date > today && months.contains(&date.month()))?;
+
+    if estimated_ex_date > end_date {
+        return None;
+    }
+
+    let mut entry = DividendCalendarEntry::new(
+        symbol.to_string(),
+        None,
+        estimated_ex_date,
+        Some(estimated_ex_date + Duration::days(pay_lag)),
+        Some(forward.per_payment()),
+        true,
+    );
+    entry.pay_date_estimated = true;
+    entry.frequency = Some(forward.frequency.clone());
+    Some(entry)
+}
+
+/// Whether a calendar entry's pay date has passed with no matching dividend
+/// recorded, so the payment still needs confirming
+fn awaiting_confirmation(entry: &DividendCalendarEntry, dividends: &[Dividend], today: NaiveDate) -> bool {
+    let Some(pay_date) = entry.pay_date else {
+        return false;
+    };
+    if pay_date > today {
+        return false;
+    }
+
+    !dividends.iter().any(|dividend| {
+        dividend.symbol == entry.symbol
+            && ((dividend.ex_date - entry.ex_date).num_days().abs() <= CONFIRMATION_WINDOW_DAYS
+                || (dividend.pay_date - pay_date).num_days().abs() <= CONFIRMATION_WINDOW_DAYS)
+    })
+}
+
+/// Alerts for symbols whose trailing-12-month income is on the wrong side of
+/// their threshold, dated `today`
+fn threshold_alerts(tracker: &DividendTracker, today: NaiveDate) -> Vec<DividendAlert> {
+    let mut alerts = Vec::new();
+    for (symbol, threshold) in &tracker.income_thresholds {
+        let income = tracker.trailing_income(symbol, today);
+        if !threshold.is_crossed(income) {
+            continue;
+        }
+
+        let (alert_type, side) = match threshold.direction {
+            ThresholdDirection::Below => (AlertType::IncomeBelowTarget, "below"),
+            ThresholdDirection::Above => (AlertType::IncomeAboveTarget, "above"),
+        };
+        alerts.push(DividendAlert {
+            symbol: symbol.clone(),
+            alert_type,
+            ex_date: today,
+            estimated_amount: None,
+            shares_owned: tracker.shares_held(symbol),
+            estimated_income: None,
+            message: format!(
+                "{} paid {} over the last 12 months, {} your {} target",
+                symbol,
+                format_money(income),
+                side,
+                format_money(threshold.annual)
+            ),
+        });
+    }
+    alerts
+}
+
 /// Format alert message based on type
 fn format_alert_message(
     alert_type: &AlertType,
@@ -450,6 +781,11 @@ fn format_alert_message(
             entry.symbol,
             entry.ex_date.format("%Y-%m-%d")
         ),
+        AlertType::PaymentUnconfirmed => format!(
+            "{} was due to pay on {} but no payment is recorded. Confirm the cash arrived and record it with 'div add'",
+            entry.symbol,
+            entry.pay_date.unwrap_or(entry.ex_date).format("%Y-%m-%d")
+        ),
         _ => format!("{} dividend alert", entry.symbol),
     };
 
@@ -462,7 +798,142 @@ fn format_alert_message(
     }
 }
 
-/// Load holdings helper function
-pub fn load_holdings() -> Result<DividendTracker> {
-    holdings::load_holdings()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DividendType, IncomeThreshold};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_awaiting_confirmation() {
+        let entry = DividendCalendarEntry::new(
+            "KO".to_string(),
+            None,
+            date("2024-03-14"),
+            Some(date("2024-04-01")),
+            Some(dec!(0.485)),
+            true,
+        );
+
+        // Not due yet, then due with nothing recorded
+        assert!(!awaiting_confirmation(&entry, &[], date("2024-03-31")));
+        assert!(awaiting_confirmation(&entry, &[], date("2024-04-01")));
+
+        // A recorded payment a few days off the estimate confirms it
+        let recorded = Dividend::new(
+            "KO".to_string(),
+            None,
+            date("2024-03-15"),
+            date("2024-04-03"),
+            dec!(0.485),
+            dec!(100),
+            DividendType::Regular,
+        )
+        .unwrap();
+        assert!(!awaiting_confirmation(&entry, &[recorded], date("2024-04-05")));
+    }
+
+    #[test]
+    fn test_threshold_alerts_fire_on_the_crossed_side() {
+        let mut tracker = DividendTracker::new();
+        for (ex, pay) in [("2023-11-30", "2023-12-15"), ("2024-02-28", "2024-03-15"), ("2024-05-30", "2024-06-14")] {
+            tracker.add_dividend(
+                Dividend::new("KO".to_string(), None, date(ex), date(pay), dec!(0.485), dec!(100), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+        assert_eq!(tracker.trailing_income("KO", date("2024-07-01")), dec!(145.50));
+
+        let target = |annual, direction| IncomeThreshold { annual, direction };
+        tracker.income_thresholds.insert("KO".to_string(), target(dec!(190), ThresholdDirection::Below));
+        tracker.income_thresholds.insert("PEP".to_string(), target(dec!(100), ThresholdDirection::Above));
+
+        let alerts = threshold_alerts(&tracker, date("2024-07-01"));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::IncomeBelowTarget);
+        assert_eq!(alerts[0].message, "KO paid $145.50 over the last 12 months, below your $190.00 target");
+
+        tracker.income_thresholds.insert("KO".to_string(), target(dec!(140), ThresholdDirection::Above));
+        assert_eq!(threshold_alerts(&tracker, date("2024-07-01"))[0].alert_type, AlertType::IncomeAboveTarget);
+        assert!(threshold_alerts(&tracker, date("2025-07-01")).is_empty());
+    }
+
+    #[test]
+    fn test_add_announced_replaces_estimate() {
+        let mut manager = NotificationManager::new("unused");
+        let estimate = DividendCalendarEntry::new("KO".to_string(), None, date("2024-06-10"), None, Some(dec!(0.46)), true);
+        manager.calendar.push(estimate);
+
+        let declared = DividendCalendarEntry::new(
+            "KO".to_string(),
+            None,
+            date("2024-06-14"),
+            Some(date("2024-07-01")),
+            Some(dec!(0.485)),
+            false,
+        );
+        assert!(manager.add_announced(declared.clone()));
+        assert_eq!(manager.calendar, vec![declared.clone()]);
+        assert!(!manager.add_announced(declared));
+        assert_eq!(manager.calendar.len(), 1);
+    }
+
+    #[test]
+    fn test_planned_expense_coverage_carries_leftover_cash() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(100), None, None).unwrap());
+        tracker.add_holding(Holding::new("PEP".to_string(), dec!(20), None, None).unwrap());
+        let plan = |date: &str, amount| PlannedExpense {
+            date: self::date(date),
+            amount,
+            note: None,
+        };
+        tracker.plan_expense(plan("2024-04-10", dec!(20))).unwrap();
+        tracker.plan_expense(plan("2024-01-01", dec!(99))).unwrap();
+        tracker.plan_expense(plan("2024-04-05", dec!(10))).unwrap();
+
+        let mut manager = NotificationManager::new("unused");
+        for (symbol, pay, amount) in [("KO", "2024-04-01", dec!(0.485)), ("PEP", "2024-04-12", dec!(1.265))] {
+            manager.calendar.push(DividendCalendarEntry::new(
+                symbol.to_string(),
+                None,
+                date("2024-03-14"),
+                Some(date(pay)),
+                Some(amount),
+                true,
+            ));
+        }
+        // Not held, so not counted on
+        manager.calendar.push(DividendCalendarEntry::new(
+            "T".to_string(),
+            None,
+            date("2024-03-14"),
+            Some(date("2024-04-02")),
+            Some(dec!(0.28)),
+            true,
+        ));
+
+        let coverage = manager.planned_expense_coverage(&tracker, date("2024-03-20"));
+        let summary: Vec<_> = coverage
+            .iter()
+            .map(|c| (c.expense.date, c.carried_cash, c.expected_dividends, c.shortfall))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (date("2024-04-05"), dec!(0), dec!(48.50), dec!(0)),
+                (date("2024-04-10"), dec!(38.50), dec!(0), dec!(0)),
+            ]
+        );
+        assert_eq!(coverage[1].paying_after[0].amount, dec!(25.300));
+
+        tracker.plan_expense(plan("2024-04-10", dec!(30))).unwrap();
+        let coverage = manager.planned_expense_coverage(&tracker, date("2024-03-20"));
+        assert_eq!(coverage[2].shortfall, dec!(11.50));
+    }
+
 }
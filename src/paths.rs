@@ -0,0 +1,133 @@
+//! Platform-specific locations for configuration, cache and data files
+//!
+//! Configuration and cache follow the platform conventions (XDG on Linux,
+//! `~/Library` on macOS, `%APPDATA%`/`%LOCALAPPDATA%` on Windows). Data keeps
+//! the historical `~/.dividend-tracker` directory on Unix; on Windows it lives
+//! under `%APPDATA%\dividend-tracker\data` unless a legacy home directory
+//! already exists.
+
+use anyhow::{anyhow, Context, Result};
+use directories::BaseDirs;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Directory name used under every platform base directory
+const APP_NAME: &str = "dividend-tracker";
+
+/// Data directory name used in the home directory by earlier versions
+const LEGACY_DATA_DIR: &str = ".dividend-tracker";
+
+/// Environment variable overriding the default data directory
+pub const DATA_DIR_ENV: &str = "DIVIDEND_TRACKER_DATA_DIR";
+
+fn base_dirs() -> Result<BaseDirs> {
+    BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))
+}
+
+/// Directory holding `config.toml` and the API configuration
+pub fn config_dir() -> Result<PathBuf> {
+    Ok(base_dirs()?.config_dir().join(APP_NAME))
+}
+
+/// Directory for disposable cached data such as API responses
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(base_dirs()?.cache_dir().join(APP_NAME))
+}
+
+/// Resolve the data directory used by every command
+///
+/// Precedence is the `--data-dir` flag, then `DIVIDEND_TRACKER_DATA_DIR`, then
+/// the `storage.data_dir` setting written by `init`, then [`default_data_dir`].
+/// An empty environment variable counts as unset.
+pub fn resolve_data_dir(flag: Option<&Path>) -> Result<PathBuf> {
+    if let Some(dir) = flag {
+        return Ok(dir.to_path_buf());
+    }
+
+    match std::env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => match Config::load().ok().and_then(|config| config.storage.data_dir) {
+            Some(dir) => Ok(dir),
+            None => default_data_dir(),
+        },
+    }
+}
+
+/// Default data directory when neither `--data-dir` nor the environment set one
+pub fn default_data_dir() -> Result<PathBuf> {
+    let dirs = base_dirs()?;
+    let legacy = dirs.home_dir().join(LEGACY_DATA_DIR);
+
+    if cfg!(windows) && !legacy.exists() {
+        // Separate from config_dir(), which holds a config.json of its own
+        Ok(dirs.data_dir().join(APP_NAME).join("data"))
+    } else {
+        Ok(legacy)
+    }
+}
+
+/// Append a suffix to the file name of `base`, keeping its directory
+///
+/// `with_suffix("exports/run", "_holdings.csv")` gives `exports/run_holdings.csv`
+/// with the platform's separator, and a base ending in a separator is treated
+/// as a file name rather than producing a hidden `_holdings.csv` inside it.
+pub fn with_suffix(base: &Path, suffix: &str) -> PathBuf {
+    let mut name = base
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_else(|| OsString::from("dividend_export"));
+    name.push(suffix);
+    base.with_file_name(name)
+}
+
+/// Create the parent directories of an export file that does not exist yet
+pub fn ensure_parent_dir(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display())),
+        _ => Ok(()),
+    }
+}
+
+/// Create (or truncate) an export file, creating missing parent directories
+pub fn create_file(path: &Path) -> Result<File> {
+    ensure_parent_dir(path)?;
+    File::create(path).with_context(|| format!("Failed to create {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_data_dir_flag_takes_precedence() {
+        let flag = Path::new("custom data");
+        assert_eq!(resolve_data_dir(Some(flag)).unwrap(), flag);
+    }
+
+    #[test]
+    fn test_with_suffix_keeps_directory() {
+        let base = Path::new("exports").join("my run");
+        assert_eq!(
+            with_suffix(&base, "_holdings.csv"),
+            Path::new("exports").join("my run_holdings.csv")
+        );
+        assert_eq!(with_suffix(Path::new("backup"), ".json"), PathBuf::from("backup.json"));
+    }
+
+    #[test]
+    fn test_ensure_parent_dir_creates_nested_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tax reports").join("2024").join("summary.csv");
+
+        ensure_parent_dir(&path).unwrap();
+        assert!(temp_dir.path().join("tax reports").join("2024").is_dir());
+
+        // A bare file name has no directory to create
+        ensure_parent_dir(Path::new("summary.csv")).unwrap();
+    }
+}
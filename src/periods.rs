@@ -0,0 +1,178 @@
+//! Assigning payments to calendar months and years
+//!
+//! A stock that usually goes ex-dividend in early January sometimes slips to
+//! the last days of December. Bucketing by the raw ex-date then shows two
+//! payments in one year, none in the next, and a December payment the stock
+//! never really makes. [`PaymentPeriods`] learns which months each symbol
+//! usually pays in and moves a payment within the configured number of days
+//! of a month boundary into the neighbouring month when that month is the
+//! more usual one for the symbol.
+//!
+//! Payments are placed by ex-date unless built [`by`](PaymentPeriods::build_by)
+//! pay date, which is how the tax commands count years.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::HashMap;
+
+use crate::config::DateBasis;
+use crate::models::Dividend;
+
+/// Drift-tolerant month and year assignment for dividend payments
+#[derive(Debug, Clone, Default)]
+pub struct PaymentPeriods {
+    tolerance_days: u32,
+    /// Date of a payment that decides its month
+    date_basis: DateBasis,
+    /// Payments per calendar month (January first) for each symbol
+    month_counts: HashMap<String, [usize; 12]>,
+}
+
+impl PaymentPeriods {
+    /// Learn each symbol's usual payment months from its history
+    pub fn build<'a>(dividends: impl IntoIterator<Item = &'a Dividend>, tolerance_days: u32) -> Self {
+        Self::build_by(dividends, tolerance_days, DateBasis::ExDate)
+    }
+
+    /// Learn the usual payment months going by the ex-date or the pay date
+    pub fn build_by<'a>(
+        dividends: impl IntoIterator<Item = &'a Dividend>,
+        tolerance_days: u32,
+        date_basis: DateBasis,
+    ) -> Self {
+        let mut month_counts: HashMap<String, [usize; 12]> = HashMap::new();
+
+        for dividend in dividends {
+            let counts = month_counts.entry(dividend.symbol.clone()).or_insert([0; 12]);
+            counts[date_basis.date_of(dividend).month0() as usize] += 1;
+        }
+
+        PaymentPeriods {
+            tolerance_days,
+            date_basis,
+            month_counts,
+        }
+    }
+
+    /// Year and month a payment belongs to
+    pub fn period(&self, dividend: &Dividend) -> (i32, u32) {
+        let date = self.date_basis.date_of(dividend);
+        let natural = (date.year(), date.month());
+
+        let Some(counts) = self.month_counts.get(&dividend.symbol) else {
+            return natural;
+        };
+        if self.tolerance_days == 0 {
+            return natural;
+        }
+
+        let tolerance = i64::from(self.tolerance_days);
+        let own = counts[date.month0() as usize];
+        let month_start = date.with_day(1).unwrap_or(date);
+
+        // Early in the month: maybe a late payment from the previous month
+        if i64::from(date.day()) <= tolerance {
+            let previous = month_start - Duration::days(1);
+            if counts[previous.month0() as usize] > own {
+                return (previous.year(), previous.month());
+            }
+        }
+
+        // Late in the month: maybe an early payment for the next month
+        let next = next_month_start(month_start);
+        if (next - date).num_days() <= tolerance && counts[next.month0() as usize] > own {
+            return (next.year(), next.month());
+        }
+
+        natural
+    }
+
+    /// Year a payment belongs to
+    pub fn year(&self, dividend: &Dividend) -> i32 {
+        self.period(dividend).0
+    }
+
+    /// Calendar month (1-12) a payment belongs to
+    pub fn month(&self, dividend: &Dividend) -> u32 {
+        self.period(dividend).1
+    }
+}
+
+/// First day of the month after `month_start`
+fn next_month_start(month_start: NaiveDate) -> NaiveDate {
+    if month_start.month() == 12 {
+        NaiveDate::from_ymd_opt(month_start.year() + 1, 1, 1).unwrap_or(month_start)
+    } else {
+        NaiveDate::from_ymd_opt(month_start.year(), month_start.month() + 1, 1).unwrap_or(month_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DividendType;
+    use rust_decimal_macros::dec;
+
+    fn dividend(symbol: &str, ex_date: &str) -> Dividend {
+        let date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+        Dividend::new(
+            symbol.to_string(),
+            None,
+            date,
+            date,
+            dec!(1),
+            dec!(10),
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_early_payment_moves_into_usual_month() {
+        let dividends = vec![
+            dividend("JAN", "2022-01-05"),
+            dividend("JAN", "2022-12-29"),
+            dividend("JAN", "2024-01-04"),
+        ];
+        let periods = PaymentPeriods::build(&dividends, 7);
+
+        assert_eq!(periods.period(&dividends[1]), (2023, 1));
+        assert_eq!(periods.period(&dividends[0]), (2022, 1));
+    }
+
+    #[test]
+    fn test_pay_date_basis_places_payments_by_pay_date() {
+        let mut late = dividend("KO", "2023-12-14");
+        late.pay_date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let dividends = vec![late];
+
+        assert_eq!(PaymentPeriods::build(&dividends, 0).period(&dividends[0]), (2023, 12));
+        assert_eq!(
+            PaymentPeriods::build_by(&dividends, 0, DateBasis::PayDate).period(&dividends[0]),
+            (2024, 1)
+        );
+    }
+
+    #[test]
+    fn test_late_payment_moves_back_into_usual_month() {
+        let dividends = vec![
+            dividend("MAR", "2022-03-30"),
+            dividend("MAR", "2023-03-29"),
+            dividend("MAR", "2024-04-02"),
+        ];
+        let periods = PaymentPeriods::build(&dividends, 7);
+
+        assert_eq!(periods.period(&dividends[2]), (2024, 3));
+    }
+
+    #[test]
+    fn test_payments_outside_tolerance_keep_their_month() {
+        let dividends = vec![
+            dividend("JAN", "2022-01-05"),
+            dividend("JAN", "2022-12-20"),
+            dividend("JAN", "2024-01-04"),
+        ];
+
+        assert_eq!(PaymentPeriods::build(&dividends, 7).period(&dividends[1]), (2022, 12));
+        assert_eq!(PaymentPeriods::build(&dividends, 0).period(&dividends[1]), (2022, 12));
+    }
+}
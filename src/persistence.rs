@@ -1,16 +1,33 @@
 use anyhow::{Context, Result};
-use chrono::Local;
+use chrono::{Datelike, Local, NaiveDate};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::io::Write;
+use std::hash::{Hash, Hasher};
+use std::io::{ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
 use tempfile::NamedTempFile;
 
-use crate::models::{Dividend, DividendTracker, Holding};
+use crate::analytics_cache::AnalyticsCache;
+use crate::config::{BackupCompression, BackupSettings, Config};
+use crate::events::{EventKind, EventLog};
+use crate::format::{format_shares, round_money};
+use crate::models::{holding_map, Dividend, DividendTracker, Holding, HoldingKey, NotificationData};
+use crate::notifications::NotificationManager;
+use crate::paths;
+use crate::progress;
+use crate::run_rate;
+use crate::securities::{FrequencyOverride, Securities};
 
 /// Schema version for data migration
-const SCHEMA_VERSION: u32 = 1;
+///
+/// Version 2 added the calendar and alerts.
+pub const SCHEMA_VERSION: u32 = 2;
 
 /// Data structure for versioned persistence
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -20,10 +37,23 @@ struct PersistedData {
     /// The actual dividend tracker data
     #[serde(flatten)]
     data: DividendTracker,
+    /// Calendar and alerts as of the save, so backups carry them; the
+    /// notification files stay authoritative. Missing before version 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notifications: Option<NotificationData>,
     /// Metadata about the saved data
     metadata: DataMetadata,
 }
 
+/// One year of dividends moved out of `dividends.json` by `data archive`
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct ArchivedYear {
+    schema_version: u32,
+    /// Pay-date year of every dividend in the file
+    year: i32,
+    dividends: Vec<Dividend>,
+}
+
 /// Metadata about persisted data
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct DataMetadata {
@@ -35,32 +65,53 @@ struct DataMetadata {
     app_version: String,
 }
 
+/// What `dividends.json` records about itself
+#[derive(Debug, Clone)]
+pub struct DataFileInfo {
+    pub schema_version: u32,
+    pub last_saved: String,
+    pub app_version: String,
+}
+
+/// How long to wait for another process to release the data lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Age after which a lock file is assumed to be left over from a crash
+const STALE_LOCK_AGE: Duration = Duration::from_secs(300);
+
+/// Exclusive lock on the data directory, released when dropped
+struct DataLock {
+    path: PathBuf,
+}
+
+impl Drop for DataLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
 /// Manages data persistence for the dividend tracker
+#[derive(Clone)]
 pub struct PersistenceManager {
     /// Base directory for all data files
     data_dir: PathBuf,
     /// Directory for backup files
     backup_dir: PathBuf,
+    /// Backup compression and retention settings
+    backup_settings: BackupSettings,
+    /// Pinned per-symbol facts attached to every loaded tracker
+    securities: Securities,
+    /// In-memory tracker that loads and saves go to instead of the data file
+    staged: Option<Arc<Mutex<DividendTracker>>>,
 }
 
 impl PersistenceManager {
-    /// Create a new persistence manager with default paths
+    /// Create a persistence manager for the environment or default data directory
+    ///
+    /// Command handlers use `CliConfig::create_persistence_manager` instead so
+    /// that `--data-dir` is honored as well.
     pub fn new() -> Result<Self> {
-        // Check for environment variable first (mainly for testing)
-        let data_dir = if let Ok(custom_dir) = std::env::var("DIVIDEND_TRACKER_DATA_DIR") {
-            PathBuf::from(custom_dir)
-        } else {
-            let home_dir = dirs::home_dir()
-                .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
-            home_dir.join(".dividend-tracker")
-        };
-
-        let backup_dir = data_dir.join("backups");
-
-        Ok(PersistenceManager {
-            data_dir,
-            backup_dir,
-        })
+        Ok(Self::with_custom_path(paths::resolve_data_dir(None)?))
     }
 
     /// Create a persistence manager with custom paths (mainly for testing)
@@ -71,7 +122,52 @@ impl PersistenceManager {
         PersistenceManager {
             data_dir,
             backup_dir,
+            backup_settings: Config::load().unwrap_or_default().backup,
+            securities: Securities::default(),
+            staged: None,
+        }
+    }
+
+    /// Attach pinned per-symbol facts to the trackers this manager loads
+    pub fn with_securities(mut self, securities: Securities) -> Self {
+        self.securities = securities;
+        self
+    }
+
+    /// Pin frequencies given on the command line on top of securities.toml
+    pub fn with_frequency_overrides(mut self, overrides: &[FrequencyOverride]) -> Self {
+        for o in overrides {
+            self.securities.pin_frequency(&o.symbol, o.frequency.clone());
         }
+        self
+    }
+
+    pub fn securities(&self) -> &Securities {
+        &self.securities
+    }
+
+    /// Override the backup settings (mainly for testing)
+    pub fn with_backup_settings(mut self, backup_settings: BackupSettings) -> Self {
+        self.backup_settings = backup_settings;
+        self
+    }
+
+    /// A manager whose loads and saves go to `tracker` instead of the data file
+    ///
+    /// `run` stages a whole script this way and writes the result once. Staged
+    /// changes take no lock, keep no backups and publish no events; whoever
+    /// writes the staged tracker back does all of that.
+    pub fn staged(&self, tracker: Arc<Mutex<DividendTracker>>) -> Self {
+        PersistenceManager {
+            staged: Some(tracker),
+            ..self.clone()
+        }
+    }
+
+    fn staged_tracker(&self) -> Option<MutexGuard<'_, DividendTracker>> {
+        self.staged
+            .as_ref()
+            .map(|staged| staged.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
     }
 
     /// Ensure all required directories exist
@@ -90,14 +186,26 @@ impl PersistenceManager {
         self.data_dir.join("dividends.json")
     }
 
-    /// Get the path to the holdings JSON file
+    /// Directory holding the yearly dividend archives
+    fn archive_dir(&self) -> PathBuf {
+        self.data_dir.join("archive")
+    }
+
+    /// Get the path to the archive of dividends paid in `year`
+    fn archive_file(&self, year: i32) -> PathBuf {
+        self.archive_dir().join(format!("dividends_{}.json", year))
+    }
+
+    /// Get the path to the legacy holdings JSON file
+    ///
+    /// Only read for migration; holdings live in `dividends.json`.
     fn holdings_file(&self) -> PathBuf {
         self.data_dir.join("holdings.json")
     }
 
-    /// Get the path to the config JSON file
-    fn config_file(&self) -> PathBuf {
-        self.data_dir.join("config.json")
+    /// Get the path to the lock file guarding read-modify-write cycles
+    fn lock_file(&self) -> PathBuf {
+        self.data_dir.join(".lock")
     }
 
     /// Create a backup of a file before overwriting
@@ -111,54 +219,367 @@ impl PersistenceManager {
             .and_then(|n| n.to_str())
             .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?;
 
+        // Skip the backup if the newest one already holds identical data
+        let current = fs::read(file_path)
+            .with_context(|| format!("Failed to read file for backup: {:?}", file_path))?;
+        if let Some((newest, _)) = self.list_backups(file_name)?.first() {
+            if let Ok(previous) = read_backup(newest) {
+                if content_hash(&previous) == content_hash(&current) {
+                    return Ok(());
+                }
+            }
+        }
+
         let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
-        let backup_name = format!("{}_{}.bak", file_name.trim_end_matches(".json"), timestamp);
+        let compression = self.backup_settings.compression;
+        let extension = match compression {
+            BackupCompression::None => "bak",
+            BackupCompression::Gzip => "bak.gz",
+        };
+        let backup_name = format!(
+            "{}_{}.{}",
+            file_name.trim_end_matches(".json"),
+            timestamp,
+            extension
+        );
         let backup_path = self.backup_dir.join(backup_name);
 
-        fs::copy(file_path, backup_path)
+        let content = match compression {
+            BackupCompression::None => current,
+            BackupCompression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&current)?;
+                encoder.finish()?
+            }
+        };
+
+        fs::write(&backup_path, content)
             .with_context(|| format!("Failed to backup file: {:?}", file_path))?;
 
-        // Clean up old backups (keep only the last 10)
-        self.cleanup_old_backups(file_name)?;
+        // Apply the configured retention policy
+        self.cleanup_old_backups()?;
 
         Ok(())
     }
 
-    /// Clean up old backup files, keeping only the most recent N
-    fn cleanup_old_backups(&self, base_filename: &str) -> Result<()> {
+    /// List backups of a data file with their modification times, newest first
+    fn list_backups(&self, base_filename: &str) -> Result<Vec<(PathBuf, SystemTime)>> {
         let prefix = base_filename.trim_end_matches(".json");
-        let mut backups: Vec<PathBuf> = Vec::new();
+        let mut backups: Vec<(PathBuf, SystemTime)> = Vec::new();
+
+        if !self.backup_dir.exists() {
+            return Ok(backups);
+        }
 
         for entry in fs::read_dir(&self.backup_dir)? {
             let entry = entry?;
             let path = entry.path();
             if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                if filename.starts_with(prefix) && filename.ends_with(".bak") {
-                    backups.push(path);
+                if filename.starts_with(prefix) && is_backup_file(filename) {
+                    let modified = entry
+                        .metadata()
+                        .and_then(|m| m.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    backups.push((path, modified));
                 }
             }
         }
 
-        // Sort by modification time (newest first)
-        backups.sort_by_key(|p| {
-            fs::metadata(p)
-                .and_then(|m| m.modified())
-                .ok()
-                .map(|t| std::cmp::Reverse(t))
-        });
+        backups.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        Ok(backups)
+    }
+
+    /// File names of the data files that get backed up
+    fn backed_up_files(&self) -> Vec<String> {
+        [self.dividends_file(), self.holdings_file()]
+            .iter()
+            .filter_map(|path| path.file_name().and_then(|n| n.to_str()).map(str::to_string))
+            .collect()
+    }
 
-        // Keep only the last 10 backups
-        const MAX_BACKUPS: usize = 10;
-        if backups.len() > MAX_BACKUPS {
-            for backup in &backups[MAX_BACKUPS..] {
-                fs::remove_file(backup)
+    /// Apply the retention policy from the backup settings, returning the
+    /// number of backups removed
+    ///
+    /// Count and age limits apply per data file; the size limit applies to all
+    /// backups together and removes the oldest first. The newest backup of
+    /// each data file is always kept.
+    fn cleanup_old_backups(&self) -> Result<usize> {
+        let settings = &self.backup_settings;
+        let max_age = settings
+            .max_age_days
+            .map(|days| Duration::from_secs(u64::from(days) * 86400));
+        let now = SystemTime::now();
+
+        let mut removed = 0;
+        let mut total_size = 0;
+        // Older backups that survived the count and age limits
+        let mut candidates: Vec<(PathBuf, SystemTime, u64)> = Vec::new();
+
+        for file_name in self.backed_up_files() {
+            let backups = self.list_backups(&file_name)?;
+            for (index, (backup, modified)) in backups.into_iter().enumerate() {
+                let size = fs::metadata(&backup).map(|m| m.len()).unwrap_or(0);
+                if index == 0 {
+                    total_size += size;
+                    continue;
+                }
+
+                let age = now.duration_since(modified).unwrap_or_default();
+                let expired = max_age.map(|max| age > max).unwrap_or(false);
+                if index >= settings.max_count || expired {
+                    fs::remove_file(&backup)
+                        .with_context(|| format!("Failed to remove old backup: {:?}", backup))?;
+                    removed += 1;
+                } else {
+                    total_size += size;
+                    candidates.push((backup, modified, size));
+                }
+            }
+        }
+
+        if let Some(max_mb) = settings.max_total_size_mb {
+            let limit = max_mb * 1024 * 1024;
+            candidates.sort_by_key(|(_, modified, _)| *modified);
+
+            for (backup, _, size) in candidates {
+                if total_size <= limit {
+                    break;
+                }
+                fs::remove_file(&backup)
                     .with_context(|| format!("Failed to remove old backup: {:?}", backup))?;
+                total_size -= size;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove backups of one data file beyond the newest `keep`, optionally
+    /// only those older than `older_than`
+    fn prune_file_backups(
+        &self,
+        base_filename: &str,
+        keep: usize,
+        older_than: Option<Duration>,
+    ) -> Result<usize> {
+        let backups = self.list_backups(base_filename)?;
+        let now = SystemTime::now();
+        let mut removed = 0;
+
+        for (backup, modified) in backups.iter().skip(keep) {
+            if let Some(min_age) = older_than {
+                let age = now.duration_since(*modified).unwrap_or_default();
+                if age < min_age {
+                    continue;
+                }
+            }
+
+            fs::remove_file(backup)
+                .with_context(|| format!("Failed to remove old backup: {:?}", backup))?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Prune backups of every data file, returning the number removed
+    pub fn prune_backups(&self, keep: usize, older_than: Option<Duration>) -> Result<usize> {
+        let mut removed = 0;
+        for file_name in self.backed_up_files() {
+            removed += self.prune_file_backups(&file_name, keep, older_than)?;
+        }
+        Ok(removed)
+    }
+
+    /// Acquire the data directory lock, waiting for other processes
+    fn acquire_lock(&self) -> Result<DataLock> {
+        self.ensure_directories()?;
+
+        let path = self.lock_file();
+        let started = Instant::now();
+
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(DataLock { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .map(|age| age > STALE_LOCK_AGE)
+                        .unwrap_or(false);
+
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        anyhow::bail!(
+                            "Timed out waiting for data lock {:?}. Remove it if no other dividend-tracker process is running.",
+                            path
+                        );
+                    }
+
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create lock file: {:?}", path));
+                }
+            }
+        }
+    }
+
+    /// Load, modify and save the tracker as a single locked transaction
+    ///
+    /// The closure's changes are written atomically only if it returns `Ok`
+    /// and actually changed something; on error nothing is written.
+    pub fn with_tracker_mut<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut DividendTracker) -> Result<T>,
+    {
+        if self.staged.is_some() {
+            let mut tracker = self.load()?;
+            let result = f(&mut tracker)?;
+            self.save(&tracker)?;
+            return Ok(result);
+        }
+
+        let _lock = self.acquire_lock()?;
+
+        let mut tracker = self.load()?;
+        let before = serde_json::to_value(&tracker)?;
+        let dividends_before = tracker.dividends.clone();
+
+        let result = f(&mut tracker)?;
+
+        if serde_json::to_value(&tracker)? != before {
+            run_rate::record_snapshot(&mut tracker, Local::now().naive_local().date());
+            self.save(&tracker)?;
+            self.publish_added_dividends(&dividends_before, &tracker.dividends);
+        }
+
+        Ok(result)
+    }
+
+    /// Whether anything has been saved in this data directory yet
+    pub fn data_exists(&self) -> bool {
+        self.staged.is_some() || self.dividends_file().exists()
+    }
+
+    /// When `dividends.json` was last written and its size, to tell whether a
+    /// loaded copy is still current; `None` before the first save
+    pub fn last_modified(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(self.dividends_file()).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Hash of the data files' contents, to key cached analytics by; `None`
+    /// before the first save or while changes are staged in memory
+    pub fn data_fingerprint(&self) -> Result<Option<u64>> {
+        if self.staged.is_some() || !self.dividends_file().exists() {
+            return Ok(None);
+        }
+        let mut hasher = DefaultHasher::new();
+        fs::read(self.dividends_file())?.hash(&mut hasher);
+        if let Ok(legacy) = fs::read(self.holdings_file()) {
+            legacy.hash(&mut hasher);
+        }
+        Ok(Some(hasher.finish()))
+    }
+
+    /// Cache of rendered reports for this data directory
+    pub fn analytics_cache(&self) -> AnalyticsCache {
+        AnalyticsCache::new(&self.data_dir)
+    }
+
+    /// Read the header of `dividends.json` without recovering or migrating
+    /// anything; `None` before the first save
+    pub fn inspect_data_file(&self) -> Result<Option<DataFileInfo>> {
+        let file_path = self.dividends_file();
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
+        let persisted: PersistedData = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {:?}", file_path))?;
+        Ok(Some(DataFileInfo {
+            schema_version: persisted.schema_version,
+            last_saved: persisted.metadata.last_saved,
+            app_version: persisted.metadata.app_version,
+        }))
+    }
+
+    /// Every backup, newest first per data file, with the reason it could
+    /// not be restored if it couldn't
+    pub fn verify_backups(&self) -> Result<Vec<(PathBuf, Option<String>)>> {
+        let mut results = Vec::new();
+        for file_name in self.backed_up_files() {
+            for (backup, _) in self.list_backups(&file_name)? {
+                let problem = match read_backup(&backup) {
+                    Ok(content) => {
+                        let valid = serde_json::from_slice::<PersistedData>(&content).is_ok()
+                            || serde_json::from_slice::<HashMap<String, Holding>>(&content).is_ok();
+                        (!valid).then(|| "does not contain valid dividend tracker data".to_string())
+                    }
+                    Err(e) => Some(format!("{:#}", e)),
+                };
+                results.push((backup, problem));
             }
         }
+        Ok(results)
+    }
 
+    /// Try creating a file in the data and backup directories
+    pub fn check_writable(&self) -> Result<()> {
+        for dir in [&self.data_dir, &self.backup_dir] {
+            if dir.exists() {
+                NamedTempFile::new_in(dir).with_context(|| format!("Cannot write to {:?}", dir))?;
+            }
+        }
         Ok(())
     }
 
+    /// How long ago the data lock was taken, if a lock file exists
+    pub fn lock_age(&self) -> Option<Duration> {
+        let modified = fs::metadata(self.lock_file()).and_then(|m| m.modified()).ok()?;
+        Some(modified.elapsed().unwrap_or_default())
+    }
+
+    /// The event log of this data directory
+    pub fn events(&self) -> EventLog {
+        EventLog::new(&self.data_dir)
+    }
+
+    /// Publish a `dividend_added` event for each dividend not there before
+    ///
+    /// Dividends are matched on symbol and ex-date, so edits to an existing
+    /// payment don't count as additions.
+    fn publish_added_dividends(&self, before: &[Dividend], after: &[Dividend]) {
+        let mut existing: HashMap<(&str, NaiveDate), usize> = HashMap::new();
+        for dividend in before {
+            *existing.entry((dividend.symbol.as_str(), dividend.ex_date)).or_default() += 1;
+        }
+
+        let events = self.events();
+        for dividend in after {
+            match existing.get_mut(&(dividend.symbol.as_str(), dividend.ex_date)) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => events.notify(EventKind::DividendAdded, serde_json::json!(dividend)),
+            }
+        }
+    }
+
     /// Perform an atomic write to a file
     fn atomic_write(&self, path: &Path, content: &[u8]) -> Result<()> {
         // Create a temporary file in the same directory as the target
@@ -189,6 +610,11 @@ impl PersistenceManager {
 
     /// Save the complete dividend tracker data
     pub fn save(&self, tracker: &DividendTracker) -> Result<()> {
+        if let Some(mut staged) = self.staged_tracker() {
+            *staged = tracker.clone();
+            return Ok(());
+        }
+
         self.ensure_directories()?;
 
         let file_path = self.dividends_file();
@@ -196,10 +622,44 @@ impl PersistenceManager {
         // Backup existing file
         self.backup_file(&file_path)?;
 
+        self.write_tracker(tracker)
+    }
+
+    /// Save the data and back up the file just written, so the newest backup
+    /// holds the current calendar and alerts as well
+    pub fn create_backup(&self, tracker: &DividendTracker) -> Result<()> {
+        if self.staged.is_some() {
+            return self.save(tracker);
+        }
+
+        self.ensure_directories()?;
+        self.write_tracker(tracker)?;
+        self.backup_file(&self.dividends_file())
+    }
+
+    /// The calendar and alerts stored next to the data
+    pub fn load_notifications(&self) -> Result<NotificationData> {
+        Ok(NotificationManager::load(&self.data_dir)?.data())
+    }
+
+    /// Write `dividends.json` with the current calendar and alerts, without a backup
+    fn write_tracker(&self, tracker: &DividendTracker) -> Result<()> {
+        let file_path = self.dividends_file();
+
+        // Unreadable notification files shouldn't keep the data from saving
+        let notifications = match self.load_notifications() {
+            Ok(notifications) => Some(notifications),
+            Err(e) => {
+                eprintln!("Warning: Leaving calendar and alerts out of {:?}: {}", file_path, e);
+                None
+            }
+        };
+
         // Prepare versioned data
         let persisted = PersistedData {
             schema_version: SCHEMA_VERSION,
             data: tracker.clone(),
+            notifications,
             metadata: DataMetadata {
                 last_saved: Local::now().to_rfc3339(),
                 save_count: self.get_save_count()? + 1,
@@ -214,11 +674,24 @@ impl PersistenceManager {
         // Atomic write
         self.atomic_write(&file_path, json.as_bytes())?;
 
+        // Reports cached for the old data can no longer be hit
+        self.analytics_cache().clear()?;
+
         Ok(())
     }
 
     /// Load the complete dividend tracker data
     pub fn load(&self) -> Result<DividendTracker> {
+        if let Some(staged) = self.staged_tracker() {
+            return Ok(staged.clone());
+        }
+        let mut tracker = self.migrate_legacy_holdings(self.load_tracker_file()?)?;
+        tracker.securities = self.securities.clone();
+        Ok(tracker)
+    }
+
+    /// Load `dividends.json`, recovering from backups if it is corrupted
+    fn load_tracker_file(&self) -> Result<DividendTracker> {
         let file_path = self.dividends_file();
 
         if !file_path.exists() {
@@ -233,15 +706,32 @@ impl PersistenceManager {
         let persisted: PersistedData = match serde_json::from_str(&content) {
             Ok(data) => data,
             Err(e) => {
-                // Handle corrupted JSON gracefully
-                eprintln!("Warning: Failed to parse JSON: {}", e);
-                eprintln!("Creating backup and starting fresh...");
+                // Handle corrupted JSON by falling back to the newest good backup
+                eprintln!("Warning: Failed to parse {:?}: {}", file_path, e);
 
-                // Backup the corrupted file
+                // Find a good backup before backing up the corrupted file,
+                // so the corrupted copy can never shadow it
+                let recovered = self.find_latest_good_backup()?;
+
+                // Keep the corrupted file for manual inspection
                 self.backup_file(&file_path)?;
 
-                // Return empty tracker
-                return Ok(DividendTracker::new());
+                match recovered {
+                    Some((backup_path, content, persisted)) => {
+                        self.atomic_write(&file_path, &content)?;
+                        eprintln!(
+                            "Recovered data from backup {:?} ({} dividends, {} holdings)",
+                            backup_path,
+                            persisted.data.dividends.len(),
+                            persisted.data.holdings.len()
+                        );
+                        persisted
+                    }
+                    None => {
+                        eprintln!("No usable backup found, starting fresh...");
+                        return Ok(DividendTracker::new());
+                    }
+                }
             }
         };
 
@@ -255,59 +745,85 @@ impl PersistenceManager {
         Ok(data)
     }
 
-    /// Save holdings separately
-    pub fn save_holdings(&self, holdings: &HashMap<String, Holding>) -> Result<()> {
-        self.ensure_directories()?;
-
-        let file_path = self.holdings_file();
-
-        // Backup existing file
-        self.backup_file(&file_path)?;
-
-        // Serialize to JSON with pretty printing
-        let json = serde_json::to_string_pretty(&holdings)
-            .with_context(|| "Failed to serialize holdings data")?;
+    /// Find the newest backup of the dividends file that parses cleanly
+    fn find_latest_good_backup(&self) -> Result<Option<(PathBuf, Vec<u8>, PersistedData)>> {
+        let file_name = self
+            .dividends_file()
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("dividends.json")
+            .to_string();
+
+        for (backup_path, _) in self.list_backups(&file_name)? {
+            let content = match read_backup(&backup_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Skipping unreadable backup {:?}: {}", backup_path, e);
+                    continue;
+                }
+            };
 
-        // Atomic write
-        self.atomic_write(&file_path, json.as_bytes())?;
+            match serde_json::from_slice::<PersistedData>(&content) {
+                Ok(persisted) => return Ok(Some((backup_path, content, persisted))),
+                Err(e) => eprintln!("Skipping corrupted backup {:?}: {}", backup_path, e),
+            }
+        }
 
-        Ok(())
+        Ok(None)
     }
 
-    /// Load holdings
-    pub fn load_holdings(&self) -> Result<HashMap<String, Holding>> {
-        let file_path = self.holdings_file();
-
-        if !file_path.exists() {
-            return Ok(HashMap::new());
+    /// Fold a legacy `holdings.json` into the tracker
+    ///
+    /// Holdings used to be written both to `holdings.json` and inside
+    /// `dividends.json`, and the two could drift apart. `dividends.json` is now
+    /// the single source of truth: holdings it is missing are taken from the
+    /// legacy file, which is then moved into the backups directory.
+    fn migrate_legacy_holdings(&self, mut tracker: DividendTracker) -> Result<DividendTracker> {
+        let legacy_path = self.holdings_file();
+
+        if !legacy_path.exists() {
+            return Ok(tracker);
         }
 
-        let content = fs::read_to_string(&file_path)
-            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
-
-        match serde_json::from_str(&content) {
-            Ok(holdings) => Ok(holdings),
+        let content = fs::read_to_string(&legacy_path)
+            .with_context(|| format!("Failed to read file: {:?}", legacy_path))?;
+
+        match serde_json::from_str::<HashMap<String, Holding>>(&content) {
+            Ok(legacy) => {
+                let mut added = 0;
+                for (key, holding) in holding_map::from_named(legacy) {
+                    if let Entry::Vacant(entry) = tracker.holdings.entry(key) {
+                        entry.insert(holding);
+                        added += 1;
+                    }
+                }
+                eprintln!(
+                    "Migrated legacy holdings.json into dividends.json ({} holdings added)",
+                    added
+                );
+            }
             Err(e) => {
-                eprintln!("Warning: Failed to parse holdings JSON: {}", e);
-                eprintln!("Creating backup and starting fresh...");
-
-                // Backup the corrupted file
-                self.backup_file(&file_path)?;
-
-                Ok(HashMap::new())
+                eprintln!("Warning: Ignoring unreadable legacy holdings.json: {}", e);
             }
         }
+
+        self.save(&tracker)?;
+
+        // Keep a copy of the legacy file, then retire it
+        self.backup_file(&legacy_path)?;
+        fs::remove_file(&legacy_path)
+            .with_context(|| format!("Failed to remove legacy file: {:?}", legacy_path))?;
+
+        Ok(tracker)
     }
 
     /// Save dividends only
     pub fn save_dividends(&self, dividends: &[Dividend]) -> Result<()> {
-        self.ensure_directories()?;
-
-        // Load existing data to preserve holdings
-        let mut tracker = self.load()?;
-        tracker.dividends = dividends.to_vec();
-
-        self.save(&tracker)
+        // Replace dividends while preserving holdings
+        self.with_tracker_mut(|tracker| {
+            tracker.dividends = dividends.to_vec();
+            Ok(())
+        })
     }
 
     /// Load dividends only
@@ -316,15 +832,122 @@ impl PersistenceManager {
         Ok(tracker.dividends)
     }
 
-    /// Export data to CSV format
-    pub fn export_to_csv(&self, output_path: &Path) -> Result<()> {
-        let tracker = self.load()?;
+    /// Move dividends paid before `before_year` out of `dividends.json` into
+    /// one archive file per pay-date year
+    ///
+    /// Going by pay date keeps every year left behind whole, whether it is
+    /// counted by ex-date or by pay date. Records already archived are
+    /// merged rather than duplicated. Returns how many dividends each year's
+    /// archive received.
+    pub fn archive_dividends(&self, before_year: i32) -> Result<BTreeMap<i32, usize>> {
+        self.with_tracker_mut(|tracker| {
+            let (old, kept): (Vec<Dividend>, Vec<Dividend>) = tracker
+                .dividends
+                .drain(..)
+                .partition(|d| d.pay_date.year() < before_year);
+            tracker.dividends = kept;
+
+            let mut by_year: BTreeMap<i32, Vec<Dividend>> = BTreeMap::new();
+            for dividend in old {
+                by_year.entry(dividend.pay_date.year()).or_default().push(dividend);
+            }
+            if by_year.is_empty() {
+                return Ok(BTreeMap::new());
+            }
+
+            let archive_dir = self.archive_dir();
+            fs::create_dir_all(&archive_dir)
+                .with_context(|| format!("Failed to create archive directory: {:?}", archive_dir))?;
+
+            // The archives are written before the trimmed data file, so a failed
+            // save leaves dividends in both places rather than in neither
+            let mut counts = BTreeMap::new();
+            for (year, dividends) in by_year {
+                let mut archived = self.read_archive(year)?;
+                counts.insert(year, merge_dividends(&mut archived, dividends));
+                archived.sort_by(|a, b| a.ex_date.cmp(&b.ex_date).then_with(|| a.symbol.cmp(&b.symbol)));
+
+                let file = ArchivedYear {
+                    schema_version: SCHEMA_VERSION,
+                    year,
+                    dividends: archived,
+                };
+                let json = serde_json::to_string_pretty(&file)
+                    .with_context(|| format!("Failed to serialize {} archive", year))?;
+                self.atomic_write(&self.archive_file(year), json.as_bytes())?;
+            }
+
+            Ok(counts)
+        })
+    }
+
+    /// Years with an archive file, oldest first
+    pub fn archived_years(&self) -> Result<Vec<i32>> {
+        let archive_dir = self.archive_dir();
+        if !archive_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut years: Vec<i32> = fs::read_dir(&archive_dir)
+            .with_context(|| format!("Failed to read archive directory: {:?}", archive_dir))?
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_str()?.to_string();
+                name.strip_prefix("dividends_")?.strip_suffix(".json")?.parse().ok()
+            })
+            .collect();
+        years.sort_unstable();
+        Ok(years)
+    }
+
+    /// The dividends archived for `year`, empty if there is no archive
+    fn read_archive(&self, year: i32) -> Result<Vec<Dividend>> {
+        let path = self.archive_file(year);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read archive: {:?}", path))?;
+        let archived: ArchivedYear = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse archive: {:?}", path))?;
+        Ok(archived.dividends)
+    }
+
+    /// Every archived dividend, oldest year first
+    pub fn load_archived_dividends(&self) -> Result<Vec<Dividend>> {
+        let mut dividends = Vec::new();
+        for year in self.archived_years()? {
+            dividends.extend(self.read_archive(year)?);
+        }
+        Ok(dividends)
+    }
 
-        let mut wtr = csv::Writer::from_path(output_path)
-            .with_context(|| format!("Failed to create CSV file: {:?}", output_path))?;
+    /// Load the tracker with the archived dividends folded back in
+    ///
+    /// Records present both in an archive and in `dividends.json` are
+    /// counted once.
+    pub fn load_with_archive(&self) -> Result<DividendTracker> {
+        let mut tracker = self.load()?;
+        merge_dividends(&mut tracker.dividends, self.load_archived_dividends()?);
+        Ok(tracker)
+    }
+
+    /// Export dividend records to CSV format
+    pub fn export_to_csv(&self, tracker: &DividendTracker, output_path: &Path) -> Result<()> {
+        self.export_dividends_to_csv(&tracker.dividends, output_path)
+    }
+
+    /// Export the given dividends to CSV in the order given
+    pub fn export_dividends_to_csv<'a>(
+        &self,
+        dividends: impl IntoIterator<Item = &'a Dividend>,
+        output_path: &Path,
+    ) -> Result<()> {
+        let mut wtr = csv::Writer::from_writer(paths::create_file(output_path)?);
 
         // Write header
-        wtr.write_record(&[
+        wtr.write_record([
             "Symbol",
             "Company Name",
             "Ex Date",
@@ -333,42 +956,53 @@ impl PersistenceManager {
             "Shares Owned",
             "Total Amount",
             "Dividend Type",
+            "ISIN",
+            "CUSIP",
         ])?;
 
+        let display = Config::load().unwrap_or_default().display;
+        let dividends: Vec<&Dividend> = dividends.into_iter().collect();
+        let pb = progress::bar(dividends.len(), "Exporting dividends");
+
         // Write dividend records
-        for dividend in &tracker.dividends {
-            wtr.write_record(&[
+        for dividend in pb.wrap_iter(dividends.into_iter()) {
+            wtr.write_record([
                 &dividend.symbol,
                 dividend.company_name.as_deref().unwrap_or(""),
                 &dividend.ex_date.to_string(),
                 &dividend.pay_date.to_string(),
                 &dividend.amount_per_share.to_string(),
-                &dividend.shares_owned.to_string(),
-                &dividend.total_amount.to_string(),
+                &format_shares(dividend.shares_owned, &display),
+                &round_money(dividend.total_amount).to_string(),
                 &format!("{:?}", dividend.dividend_type),
+                dividend.isin.as_deref().unwrap_or(""),
+                dividend.cusip.as_deref().unwrap_or(""),
             ])?;
         }
+        pb.finish_and_clear();
 
         wtr.flush()?;
         Ok(())
     }
 
     /// Export holdings to CSV format
-    pub fn export_holdings_to_csv(&self, output_path: &Path) -> Result<()> {
-        let tracker = self.load()?;
+    pub fn export_holdings_to_csv(&self, tracker: &DividendTracker, output_path: &Path) -> Result<()> {
         let holdings = &tracker.holdings;
 
-        let mut wtr = csv::Writer::from_path(output_path)
-            .with_context(|| format!("Failed to create CSV file: {:?}", output_path))?;
+        let mut wtr = csv::Writer::from_writer(paths::create_file(output_path)?);
 
         // Write header
-        wtr.write_record(&["Symbol", "Shares", "Avg Cost Basis", "Current Yield %"])?;
+        wtr.write_record(["Symbol", "Shares", "Avg Cost Basis", "Current Yield %", "ISIN", "CUSIP", "Account"])?;
+
+        let display = Config::load().unwrap_or_default().display;
+
+        let pb = progress::bar(holdings.len(), "Exporting holdings");
 
         // Write holding records
-        for (symbol, holding) in holdings {
-            wtr.write_record(&[
-                symbol,
-                &holding.shares.to_string(),
+        for holding in pb.wrap_iter(holdings.values()) {
+            wtr.write_record([
+                &holding.symbol,
+                &format_shares(holding.shares, &display),
                 &holding
                     .avg_cost_basis
                     .map(|cb| cb.to_string())
@@ -377,43 +1011,135 @@ impl PersistenceManager {
                     .current_yield
                     .map(|y| y.to_string())
                     .unwrap_or_else(|| "".to_string()),
+                holding.isin.as_deref().unwrap_or(""),
+                holding.cusip.as_deref().unwrap_or(""),
+                holding.account.as_deref().unwrap_or(""),
             ])?;
         }
+        pb.finish_and_clear();
 
         wtr.flush()?;
         Ok(())
     }
 
-    /// Export all data to human-readable JSON
-    pub fn export_to_json(&self, output_path: &Path) -> Result<()> {
-        let tracker = self.load()?;
-
+    /// Export all data to human-readable JSON, with the calendar and alerts
+    pub fn export_to_json(
+        &self,
+        tracker: &DividendTracker,
+        notifications: &NotificationData,
+        output_path: &Path,
+    ) -> Result<()> {
         #[derive(serde::Serialize)]
-        struct ExportData {
-            dividends: Vec<Dividend>,
-            holdings: HashMap<String, Holding>,
+        struct ExportData<'a> {
+            schema_version: u32,
+            dividends: &'a [Dividend],
+            #[serde(serialize_with = "serialize_holdings")]
+            holdings: &'a HashMap<HoldingKey, Holding>,
+            #[serde(flatten)]
+            notifications: &'a NotificationData,
             export_date: String,
             total_dividend_records: usize,
             total_holdings: usize,
         }
 
+        fn serialize_holdings<S: serde::Serializer>(
+            holdings: &&HashMap<HoldingKey, Holding>,
+            serializer: S,
+        ) -> std::result::Result<S::Ok, S::Error> {
+            holding_map::serialize(holdings, serializer)
+        }
+
         let export = ExportData {
+            schema_version: SCHEMA_VERSION,
             total_dividend_records: tracker.dividends.len(),
             total_holdings: tracker.holdings.len(),
-            dividends: tracker.dividends,
-            holdings: tracker.holdings,
+            dividends: &tracker.dividends,
+            holdings: &tracker.holdings,
+            notifications,
             export_date: Local::now().to_rfc3339(),
         };
 
         let json = serde_json::to_string_pretty(&export)
             .with_context(|| "Failed to serialize export data")?;
 
+        paths::ensure_parent_dir(output_path)?;
         fs::write(output_path, json)
             .with_context(|| format!("Failed to write JSON export: {:?}", output_path))?;
 
         Ok(())
     }
 
+    /// Restore data from a backup file (compressed or not)
+    ///
+    /// `backup` may be a path or the name of a file in the backup directory.
+    /// The current data is backed up before being replaced. The calendar and
+    /// alerts are restored too when the backup has them (schema version 2
+    /// on), and returned.
+    pub fn restore_backup(&self, backup: &str) -> Result<(DividendTracker, Option<NotificationData>)> {
+        let path = if Path::new(backup).exists() {
+            PathBuf::from(backup)
+        } else {
+            self.backup_dir.join(backup)
+        };
+
+        if !path.exists() {
+            anyhow::bail!("Backup not found: {}", backup);
+        }
+
+        let content = read_backup(&path)?;
+
+        self.with_tracker_mut(|tracker| {
+            let mut restored = None;
+            if let Ok(persisted) = serde_json::from_slice::<PersistedData>(&content) {
+                *tracker = persisted.data;
+                if let Some(notifications) = persisted.notifications {
+                    // Written before the data is saved, so it embeds them
+                    let mut manager = NotificationManager::new(&self.data_dir);
+                    manager.replace(notifications.clone());
+                    manager.save()?;
+                    restored = Some(notifications);
+                }
+            } else if let Ok(holdings) =
+                serde_json::from_slice::<HashMap<String, Holding>>(&content)
+            {
+                // Holdings-only backup: keep current dividends
+                tracker.holdings = holding_map::from_named(holdings);
+            } else {
+                anyhow::bail!("Backup {:?} does not contain valid dividend tracker data", path);
+            }
+
+            Ok((tracker.clone(), restored))
+        })
+    }
+
+    /// Read another dataset without touching the current data
+    ///
+    /// `source` may be another data directory, a backup file path or the name
+    /// of a file in the backup directory. Nothing is written anywhere: a
+    /// corrupt data file is an error rather than a reason to recover it from
+    /// that directory's backups.
+    pub fn load_snapshot(&self, source: &str) -> Result<DividendTracker> {
+        let mut path = if Path::new(source).exists() {
+            PathBuf::from(source)
+        } else {
+            self.backup_dir.join(source)
+        };
+
+        if path.is_dir() {
+            path = path.join("dividends.json");
+            if !path.exists() {
+                anyhow::bail!("No dividends.json found in {:?}", path.parent().unwrap_or(&path));
+            }
+        } else if !path.exists() {
+            anyhow::bail!("Data directory or backup not found: {}", source);
+        }
+
+        let content = read_backup(&path)?;
+        let persisted = serde_json::from_slice::<PersistedData>(&content)
+            .with_context(|| format!("{:?} does not contain dividend tracker data", path))?;
+        self.migrate_data(persisted)
+    }
+
     /// Get the current save count
     fn get_save_count(&self) -> Result<u32> {
         let file_path = self.dividends_file();
@@ -444,12 +1170,9 @@ impl PersistenceManager {
                 data.schema_version, SCHEMA_VERSION
             );
 
+            // Version 1 has no calendar and alerts; nothing to convert, the
+            // next save takes them from the notification files.
             // Future migrations would go here
-            // Example:
-            // if data.schema_version == 1 {
-            //     // Migrate from v1 to v2
-            //     data = migrate_v1_to_v2(data)?;
-            // }
 
             data.schema_version = SCHEMA_VERSION;
         }
@@ -458,14 +1181,11 @@ impl PersistenceManager {
     }
 
     /// Get statistics about the persisted data
-    pub fn get_stats(&self) -> Result<DataStats> {
+    pub fn get_stats(&self, tracker: &DividendTracker) -> Result<DataStats> {
         // Ensure directories exist before accessing them
         self.ensure_directories()?;
 
-        let tracker = self.load()?;
-
         let dividends_file = self.dividends_file();
-        let holdings_file = self.holdings_file();
 
         let dividends_size = if dividends_file.exists() {
             fs::metadata(&dividends_file)?.len()
@@ -473,40 +1193,121 @@ impl PersistenceManager {
             0
         };
 
-        let holdings_size = if holdings_file.exists() {
-            fs::metadata(&holdings_file)?.len()
-        } else {
-            0
-        };
+        let mut backup_count = 0;
+        let mut backup_size_bytes = 0;
+        let mut backup_uncompressed_bytes = 0;
+        for entry in fs::read_dir(&self.backup_dir)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_backup = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(is_backup_file)
+                .unwrap_or(false);
+            if !is_backup {
+                continue;
+            }
 
-        let backup_count = fs::read_dir(&self.backup_dir)?
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path()
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext == "bak")
-                    .unwrap_or(false)
-            })
-            .count();
+            backup_count += 1;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            backup_size_bytes += size;
+            backup_uncompressed_bytes += read_backup(&path)
+                .map(|content| content.len() as u64)
+                .unwrap_or(size);
+        }
+
+        let archived_years = self.archived_years()?;
+        let archived_dividend_count = self.load_archived_dividends()?.len();
 
         Ok(DataStats {
             dividend_count: tracker.dividends.len(),
             holding_count: tracker.holdings.len(),
-            total_size_bytes: dividends_size + holdings_size,
+            archived_dividend_count,
+            archived_years,
+            total_size_bytes: dividends_size,
             backup_count,
+            backup_size_bytes,
+            backup_uncompressed_bytes,
+            backup_settings: self.backup_settings.clone(),
             data_directory: self.data_dir.clone(),
         })
     }
 }
 
+/// Append the dividends `into` doesn't already hold, returning how many
+///
+/// Only identical records are the same: payments of a symbol sharing an
+/// ex-date, such as ones in different accounts, are all kept.
+fn merge_dividends(into: &mut Vec<Dividend>, dividends: impl IntoIterator<Item = Dividend>) -> usize {
+    let mut seen: HashSet<Dividend> = into.iter().cloned().collect();
+    let mut added = 0;
+    for dividend in dividends {
+        if seen.insert(dividend.clone()) {
+            into.push(dividend);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// Whether a file name is a backup (plain or compressed)
+fn is_backup_file(file_name: &str) -> bool {
+    file_name.ends_with(".bak") || file_name.ends_with(".bak.gz")
+}
+
+/// Read a backup file, transparently decompressing gzip backups
+fn read_backup(path: &Path) -> Result<Vec<u8>> {
+    let raw = fs::read(path).with_context(|| format!("Failed to read backup: {:?}", path))?;
+
+    if path.extension().and_then(|ext| ext.to_str()) == Some("gz") {
+        let mut decoder = GzDecoder::new(raw.as_slice());
+        let mut content = Vec::new();
+        decoder
+            .read_to_end(&mut content)
+            .with_context(|| format!("Failed to decompress backup: {:?}", path))?;
+        Ok(content)
+    } else {
+        Ok(raw)
+    }
+}
+
+/// Hash the data content of a file, ignoring save metadata
+///
+/// Saved files carry a `metadata` block (timestamp, save count) that changes on
+/// every save, so it is stripped before hashing to detect unchanged data.
+fn content_hash(bytes: &[u8]) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(mut value) => {
+            if let Some(object) = value.as_object_mut() {
+                object.remove("metadata");
+            }
+            value.to_string().hash(&mut hasher);
+        }
+        Err(_) => bytes.hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
 /// Statistics about persisted data
 #[derive(Debug)]
 pub struct DataStats {
     pub dividend_count: usize,
     pub holding_count: usize,
+    /// Dividends moved out to yearly archives by `data archive`
+    pub archived_dividend_count: usize,
+    /// Years with an archive file, oldest first
+    pub archived_years: Vec<i32>,
     pub total_size_bytes: u64,
     pub backup_count: usize,
+    /// Bytes used by backup files on disk
+    pub backup_size_bytes: u64,
+    /// Bytes the backups would take uncompressed
+    pub backup_uncompressed_bytes: u64,
+    /// Compression and retention policy applied to backups
+    pub backup_settings: BackupSettings,
     pub data_directory: PathBuf,
 }
 
@@ -519,6 +1320,7 @@ impl Default for PersistenceManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{DividendCalendarEntry, DEFAULT_ACCOUNT};
     use chrono::NaiveDate;
     use rust_decimal_macros::dec;
     use tempfile::TempDir;
@@ -582,7 +1384,30 @@ mod tests {
         assert_eq!(loaded.dividends.len(), 1);
         assert_eq!(loaded.dividends[0], dividend);
         assert_eq!(loaded.holdings.len(), 1);
-        assert_eq!(loaded.holdings["AAPL"], holding);
+        assert_eq!(loaded.holding("AAPL", DEFAULT_ACCOUNT), Some(&holding));
+    }
+
+    #[test]
+    fn test_holdings_of_one_symbol_in_several_accounts_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("VZ".to_string(), dec!(50), None, None).unwrap());
+        let mut ira = Holding::new("VZ".to_string(), dec!(20), None, None).unwrap();
+        ira.set_account("IRA");
+        tracker.add_holding(ira);
+        manager.save(&tracker).unwrap();
+
+        // The default account's holding keeps the plain symbol as its name
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(temp_dir.path().join("dividends.json")).unwrap()).unwrap();
+        let names: Vec<&String> = saved["holdings"].as_object().unwrap().keys().collect();
+        assert_eq!(names, vec!["VZ", "VZ@IRA"]);
+
+        let loaded = manager.load().unwrap();
+        assert_eq!(loaded.holding("VZ", DEFAULT_ACCOUNT).unwrap().shares, dec!(50));
+        assert_eq!(loaded.holding("VZ", "ira").unwrap().shares, dec!(20));
     }
 
     #[test]
@@ -605,7 +1430,317 @@ mod tests {
             .filter_map(|e| e.ok())
             .collect();
 
-        assert!(backups.len() > 0);
+        assert!(!backups.is_empty());
+    }
+
+    #[test]
+    fn test_exports_share_single_loaded_tracker() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(
+            Dividend::new(
+                "AAPL".to_string(),
+                None,
+                NaiveDate::from_ymd_opt(2024, 2, 15).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 22).unwrap(),
+                dec!(0.94),
+                dec!(100),
+                crate::models::DividendType::Regular,
+            )
+            .unwrap(),
+        );
+        tracker.add_holding(Holding::new("AAPL".to_string(), dec!(100), None, None).unwrap());
+
+        // Exports work from the in-memory tracker without touching the data file
+        let csv_path = temp_dir.path().join("dividends.csv");
+        let holdings_path = temp_dir.path().join("holdings.csv");
+        let json_path = temp_dir.path().join("export.json");
+        manager.export_to_csv(&tracker, &csv_path).unwrap();
+        manager.export_holdings_to_csv(&tracker, &holdings_path).unwrap();
+        manager
+            .export_to_json(&tracker, &NotificationData::default(), &json_path)
+            .unwrap();
+
+        assert!(!manager.dividends_file().exists());
+        assert!(fs::read_to_string(&csv_path).unwrap().contains("AAPL"));
+        assert!(fs::read_to_string(&holdings_path).unwrap().contains("AAPL"));
+        assert!(fs::read_to_string(&json_path)
+            .unwrap()
+            .contains("\"total_dividend_records\": 1"));
+    }
+
+    #[test]
+    fn test_unchanged_saves_do_not_duplicate_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let tracker = DividendTracker::new();
+        for _ in 0..4 {
+            manager.save(&tracker).unwrap();
+        }
+
+        // Only the first overwrite produces a backup; later ones hold the same data
+        assert_eq!(manager.list_backups("dividends.json").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_prune_backups_keeps_newest() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+        manager.ensure_directories().unwrap();
+
+        for i in 0..5 {
+            let name = format!("dividends_2024-01-0{}_00-00-00.bak", i + 1);
+            fs::write(manager.backup_dir.join(name), format!("{{\"n\": {}}}", i)).unwrap();
+        }
+
+        // Nothing is old enough to be removed
+        let removed = manager
+            .prune_backups(2, Some(Duration::from_secs(90 * 86400)))
+            .unwrap();
+        assert_eq!(removed, 0);
+
+        let removed = manager.prune_backups(2, None).unwrap();
+        assert_eq!(removed, 3);
+        assert_eq!(manager.list_backups("dividends.json").unwrap().len(), 2);
+    }
+
+    /// Write a backup file whose modification time is `age_days` in the past
+    fn write_aged_backup(manager: &PersistenceManager, name: &str, content: &[u8], age_days: u64) {
+        let path = manager.backup_dir.join(name);
+        fs::write(&path, content).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_days * 86400);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_backup_retention_count_and_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path()).with_backup_settings(
+            BackupSettings {
+                max_count: 3,
+                max_age_days: Some(30),
+                ..BackupSettings::default()
+            },
+        );
+        manager.ensure_directories().unwrap();
+
+        for (i, age_days) in [1, 2, 40, 50, 60].into_iter().enumerate() {
+            let name = format!("dividends_2024-01-0{}_00-00-00.bak", 5 - i);
+            write_aged_backup(&manager, &name, b"{}", age_days);
+        }
+
+        // Two beyond the count limit, one more past the age limit
+        assert_eq!(manager.cleanup_old_backups().unwrap(), 3);
+        assert_eq!(manager.list_backups("dividends.json").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_backup_retention_size_limit_keeps_newest() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path()).with_backup_settings(
+            BackupSettings {
+                max_total_size_mb: Some(1),
+                ..BackupSettings::default()
+            },
+        );
+        manager.ensure_directories().unwrap();
+
+        let content = vec![b'x'; 400 * 1024];
+        for (i, age_days) in [1, 2, 3, 4].into_iter().enumerate() {
+            let name = format!("dividends_2024-01-0{}_00-00-00.bak", 4 - i);
+            write_aged_backup(&manager, &name, &content, age_days);
+        }
+        // A single oversized backup of another file is never removed
+        let large = vec![b'x'; 2 * 1024 * 1024];
+        write_aged_backup(&manager, "holdings_2024-01-01_00-00-00.bak", &large, 5);
+
+        assert_eq!(manager.cleanup_old_backups().unwrap(), 3);
+        let remaining = manager.list_backups("dividends.json").unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(remaining[0].0.ends_with("dividends_2024-01-04_00-00-00.bak"));
+        assert_eq!(manager.list_backups("holdings.json").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_compressed_backup_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path()).with_backup_settings(
+            BackupSettings {
+                compression: BackupCompression::Gzip,
+                ..BackupSettings::default()
+            },
+        );
+
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("AAPL".to_string(), dec!(10), None, None).unwrap());
+        manager.save(&tracker).unwrap();
+
+        // Overwrite with different data so the first version is backed up
+        manager.save(&DividendTracker::new()).unwrap();
+
+        let backups = manager.list_backups("dividends.json").unwrap();
+        assert_eq!(backups.len(), 1);
+        let backup_path = &backups[0].0;
+        assert!(backup_path.to_string_lossy().ends_with(".bak.gz"));
+
+        let (restored, _) = manager
+            .restore_backup(backup_path.to_str().unwrap())
+            .unwrap();
+        assert_eq!(restored.holdings.len(), 1);
+        assert_eq!(manager.load().unwrap().holdings.len(), 1);
+
+        let stats = manager.get_stats(&restored).unwrap();
+        assert!(stats.backup_count >= 1);
+        assert!(stats.backup_uncompressed_bytes >= stats.backup_size_bytes);
+    }
+
+    #[test]
+    fn test_backups_carry_calendar_and_alerts() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+        let date = NaiveDate::from_ymd_opt(2024, 6, 13).unwrap();
+
+        let mut notifications = NotificationManager::new(temp_dir.path());
+        notifications.calendar.push(DividendCalendarEntry::new(
+            "KO".to_string(),
+            None,
+            date,
+            None,
+            Some(dec!(0.485)),
+            true,
+        ));
+        notifications.save().unwrap();
+        let tracker = DividendTracker::new();
+        manager.create_backup(&tracker).unwrap();
+
+        // Losing the calendar, then restoring the backup, brings it back
+        NotificationManager::new(temp_dir.path()).save().unwrap();
+        let backup = manager.list_backups("dividends.json").unwrap()[0].0.clone();
+        let (_, restored) = manager.restore_backup(backup.to_str().unwrap()).unwrap();
+
+        assert_eq!(restored.unwrap().calendar.len(), 1);
+        assert_eq!(manager.load_notifications().unwrap().calendar[0].symbol, "KO");
+    }
+
+    #[test]
+    fn test_archive_moves_old_years_out_of_default_loads() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let mut tracker = DividendTracker::new();
+        for (ex, pay) in [((2018, 6, 1), (2018, 6, 15)), ((2019, 12, 20), (2020, 1, 10)), ((2021, 3, 1), (2021, 3, 15))] {
+            tracker.add_dividend(
+                Dividend::new(
+                    "KO".to_string(),
+                    None,
+                    NaiveDate::from_ymd_opt(ex.0, ex.1, ex.2).unwrap(),
+                    NaiveDate::from_ymd_opt(pay.0, pay.1, pay.2).unwrap(),
+                    dec!(0.40),
+                    dec!(10),
+                    crate::models::DividendType::Regular,
+                )
+                .unwrap(),
+            );
+        }
+        manager.save(&tracker).unwrap();
+
+        // Paid in January 2020, so it stays although it went ex in 2019
+        let counts = manager.archive_dividends(2020).unwrap();
+        assert_eq!(counts, BTreeMap::from([(2018, 1)]));
+        assert_eq!(manager.load().unwrap().dividends.len(), 2);
+        assert_eq!(manager.archived_years().unwrap(), vec![2018]);
+
+        // Archiving again neither loses nor duplicates anything
+        assert!(manager.archive_dividends(2020).unwrap().is_empty());
+        assert_eq!(manager.archive_dividends(2021).unwrap(), BTreeMap::from([(2020, 1)]));
+        assert_eq!(manager.load().unwrap().dividends.len(), 1);
+        assert_eq!(manager.load_with_archive().unwrap().dividends.len(), 3);
+    }
+
+    #[test]
+    fn test_merge_dividends_keeps_only_new_records() {
+        let ex_date = NaiveDate::from_ymd_opt(2024, 4, 9).unwrap();
+        let dividend = Dividend::new(
+            "VZ".to_string(),
+            None,
+            ex_date,
+            ex_date,
+            dec!(0.66),
+            dec!(50),
+            crate::models::DividendType::Regular,
+        )
+        .unwrap();
+        let mut ira = dividend.clone();
+        ira.account = Some("IRA".to_string());
+        let mut rescaled = dividend.clone();
+        rescaled.amount_per_share = dec!(0.660);
+
+        let mut into = vec![dividend.clone()];
+        let added = merge_dividends(&mut into, [rescaled, ira.clone(), ira.clone()]);
+        assert_eq!(added, 1);
+        assert_eq!(into, vec![dividend, ira]);
+    }
+
+    #[test]
+    fn test_with_tracker_mut_commits_only_on_success() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        manager
+            .with_tracker_mut(|tracker| {
+                tracker.add_holding(Holding::new("AAPL".to_string(), dec!(10), None, None)?);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(manager.load().unwrap().holdings.len(), 1);
+
+        // A failing closure must not persist its partial changes
+        let result: Result<()> = manager.with_tracker_mut(|tracker| {
+            tracker.holdings.clear();
+            anyhow::bail!("validation failed")
+        });
+        assert!(result.is_err());
+        assert_eq!(manager.load().unwrap().holdings.len(), 1);
+
+        // The lock is released after each transaction
+        assert!(!manager.lock_file().exists());
+    }
+
+    #[test]
+    fn test_with_tracker_mut_serializes_concurrent_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_path_buf();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let manager = PersistenceManager::with_custom_path(&path);
+                    manager
+                        .with_tracker_mut(|tracker| {
+                            let symbol = format!("SYM{}", i);
+                            tracker.add_holding(Holding::new(symbol, dec!(1), None, None)?);
+                            Ok(())
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let manager = PersistenceManager::with_custom_path(&path);
+        assert_eq!(manager.load().unwrap().holdings.len(), 8);
     }
 
     #[test]
@@ -630,6 +1765,80 @@ mod tests {
             .filter_map(|e| e.ok())
             .collect();
 
-        assert!(backups.len() > 0);
+        assert!(!backups.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_holdings_file_is_migrated() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("AAPL".to_string(), dec!(10), None, None).unwrap());
+        manager.save(&tracker).unwrap();
+
+        // Legacy file disagrees on AAPL and has an extra MSFT holding
+        let mut legacy = HashMap::new();
+        legacy.insert(
+            "AAPL".to_string(),
+            Holding::new("AAPL".to_string(), dec!(99), None, None).unwrap(),
+        );
+        legacy.insert(
+            "MSFT".to_string(),
+            Holding::new("MSFT".to_string(), dec!(5), None, None).unwrap(),
+        );
+        fs::write(
+            manager.holdings_file(),
+            serde_json::to_string_pretty(&legacy).unwrap(),
+        )
+        .unwrap();
+
+        let loaded = manager.load().unwrap();
+        assert_eq!(loaded.holdings.len(), 2);
+        assert_eq!(loaded.holding("AAPL", DEFAULT_ACCOUNT).unwrap().shares, dec!(10));
+        assert_eq!(loaded.holding("MSFT", DEFAULT_ACCOUNT).unwrap().shares, dec!(5));
+        assert!(!manager.holdings_file().exists());
+
+        // The merged result is persisted in dividends.json
+        let reloaded = manager.load().unwrap();
+        assert_eq!(reloaded.holdings.len(), 2);
+    }
+
+    #[test]
+    fn test_corrupted_json_recovers_from_latest_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PersistenceManager::with_custom_path(temp_dir.path());
+
+        let mut tracker = DividendTracker::new();
+        tracker.add_holding(Holding::new("AAPL".to_string(), dec!(10), None, None).unwrap());
+        manager.save(&tracker).unwrap();
+
+        // Second save backs up the first version
+        tracker.add_holding(Holding::new("MSFT".to_string(), dec!(5), None, None).unwrap());
+        manager.save(&tracker).unwrap();
+
+        // A corrupted backup newer than the good one must be skipped
+        let corrupted_backup = temp_dir
+            .path()
+            .join("backups")
+            .join("dividends_9999-12-31_23-59-59.bak");
+        fs::write(&corrupted_backup, "not json").unwrap();
+        let future = SystemTime::now() + Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&corrupted_backup)
+            .unwrap()
+            .set_modified(future)
+            .unwrap();
+
+        fs::write(manager.dividends_file(), "{ this is not valid json }").unwrap();
+
+        let loaded = manager.load().unwrap();
+        assert_eq!(loaded.holdings.len(), 1);
+        assert!(loaded.holds("AAPL"));
+
+        // The restored file is written back and loads cleanly
+        let reloaded = manager.load().unwrap();
+        assert_eq!(reloaded.holdings.len(), 1);
     }
 }
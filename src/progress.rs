@@ -0,0 +1,19 @@
+//! Progress bars for long imports, exports and fetches
+//!
+//! Bars draw on stderr and only when it is a terminal, so piped or
+//! redirected output is unchanged.
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// A bar over `len` items, showing the rate and time remaining
+pub fn bar(len: usize, message: &str) -> ProgressBar {
+    let pb = ProgressBar::new(len as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} {msg} [{bar:40.cyan/blue}] {pos}/{len} ({per_sec}, ETA {eta})")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_message(message.to_string());
+    pb
+}
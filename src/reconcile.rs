@@ -0,0 +1,187 @@
+//! Reconciling recorded dividends with a data provider's history
+//!
+//! Each provider payment is paired with the recorded dividend of the same
+//! symbol whose ex-date is closest, within [`MATCH_WINDOW_DAYS`]. Only the
+//! ex-date and the amount per share are compared: the provider knows nothing
+//! of pay dates or the shares held.
+
+use crate::api::DividendData;
+use crate::diff::FieldChange;
+use crate::models::{Dividend, DividendTracker};
+
+/// How far apart a recorded and a provider ex-date may be and still be the
+/// same payment
+pub const MATCH_WINDOW_DAYS: i64 = 7;
+
+/// A recorded dividend that differs from the provider's payment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<'a> {
+    /// Position of the record in the tracker's dividends
+    pub index: usize,
+    pub provider: &'a DividendData,
+    pub changes: Vec<FieldChange>,
+}
+
+/// How a symbol's recorded dividends compare with the provider's history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reconciliation<'a> {
+    /// Payments recorded exactly as the provider has them
+    pub matched: usize,
+    pub mismatched: Vec<Mismatch<'a>>,
+    /// Provider payments with no recorded dividend
+    pub missing: Vec<&'a DividendData>,
+    /// Positions of records in the provider's date range it has no payment for
+    pub unconfirmed: Vec<usize>,
+}
+
+impl Reconciliation<'_> {
+    /// Correct the mismatched records to the provider's ex-date and amount,
+    /// returning how many changed
+    ///
+    /// An ex-date that would fall after the record's pay date is left alone.
+    pub fn apply(&self, tracker: &mut DividendTracker) -> usize {
+        for mismatch in &self.mismatched {
+            let dividend = &mut tracker.dividends[mismatch.index];
+            if mismatch.provider.ex_date <= dividend.pay_date {
+                dividend.ex_date = mismatch.provider.ex_date;
+            }
+            dividend.amount_per_share = mismatch.provider.amount;
+            dividend.total_amount = dividend.amount_per_share * dividend.shares_owned;
+        }
+        self.mismatched.len()
+    }
+}
+
+/// Compare a symbol's recorded dividends with the provider's payments
+///
+/// Interest and distribution records are left out; providers only report
+/// dividends.
+pub fn reconcile<'a>(tracker: &DividendTracker, symbol: &str, provider: &'a [DividendData]) -> Reconciliation<'a> {
+    let symbol = tracker.canonical_symbol(symbol);
+    let mut unpaired: Vec<(usize, &Dividend)> = tracker
+        .dividends
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.symbol == symbol && d.income_kind.is_dividend())
+        .collect();
+
+    let mut reconciliation = Reconciliation::default();
+    let mut payments: Vec<&DividendData> = provider.iter().collect();
+    payments.sort_by_key(|p| p.ex_date);
+
+    for payment in &payments {
+        let closest = unpaired
+            .iter()
+            .enumerate()
+            .map(|(pos, (_, d))| (pos, (d.ex_date - payment.ex_date).num_days().abs()))
+            .filter(|(_, distance)| *distance <= MATCH_WINDOW_DAYS)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(pos, _)| pos);
+        let Some(pos) = closest else {
+            reconciliation.missing.push(payment);
+            continue;
+        };
+
+        let (index, dividend) = unpaired.remove(pos);
+        let mut changes = Vec::new();
+        if dividend.ex_date != payment.ex_date {
+            changes.push(FieldChange {
+                field: "ex date",
+                before: dividend.ex_date.format("%Y-%m-%d").to_string(),
+                after: payment.ex_date.format("%Y-%m-%d").to_string(),
+            });
+        }
+        if dividend.amount_per_share != payment.amount {
+            changes.push(FieldChange {
+                field: "amount",
+                before: dividend.amount_per_share.normalize().to_string(),
+                after: payment.amount.normalize().to_string(),
+            });
+        }
+
+        if changes.is_empty() {
+            reconciliation.matched += 1;
+        } else {
+            reconciliation.mismatched.push(Mismatch {
+                index,
+                provider: payment,
+                changes,
+            });
+        }
+    }
+
+    // Records outside the provider's history can't be confirmed either way
+    if let (Some(first), Some(last)) = (payments.first(), payments.last()) {
+        reconciliation.unconfirmed = unpaired
+            .iter()
+            .filter(|(_, d)| (first.ex_date..=last.ex_date).contains(&d.ex_date))
+            .map(|(index, _)| *index)
+            .collect();
+    }
+
+    reconciliation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DividendType;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn recorded(ex_date: &str, pay_date: &str, amount: Decimal) -> Dividend {
+        Dividend::new(
+            "AAPL".to_string(),
+            None,
+            date(ex_date),
+            date(pay_date),
+            amount,
+            dec!(100),
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    fn provided(ex_date: &str, amount: Decimal) -> DividendData {
+        DividendData {
+            symbol: "AAPL".to_string(),
+            ex_date: date(ex_date),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_finds_typos_gaps_and_extras() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(recorded("2024-02-09", "2024-02-15", dec!(0.24)));
+        tracker.add_dividend(recorded("2024-05-10", "2024-05-16", dec!(0.24)));
+        tracker.add_dividend(recorded("2024-08-13", "2024-08-15", dec!(0.25)));
+        tracker.add_dividend(recorded("2024-09-20", "2024-09-27", dec!(0.25)));
+
+        let provider = vec![
+            provided("2024-02-09", dec!(0.24)),
+            provided("2024-05-10", dec!(0.25)),
+            provided("2024-08-12", dec!(0.25)),
+            provided("2024-11-08", dec!(0.25)),
+        ];
+        let reconciliation = reconcile(&tracker, "aapl", &provider);
+
+        assert_eq!(reconciliation.matched, 1);
+        assert_eq!(reconciliation.mismatched.len(), 2);
+        assert_eq!(reconciliation.mismatched[0].changes[0].to_string(), "amount: 0.24 -> 0.25");
+        assert_eq!(reconciliation.mismatched[1].changes[0].to_string(), "ex date: 2024-08-13 -> 2024-08-12");
+        assert_eq!(reconciliation.missing, vec![&provider[3]]);
+        assert_eq!(reconciliation.unconfirmed, vec![3]);
+
+        let mut corrected = tracker.clone();
+        assert_eq!(reconciliation.apply(&mut corrected), 2);
+        assert_eq!(corrected.dividends[1].total_amount, dec!(25.00));
+        assert_eq!(corrected.dividends[2].ex_date, date("2024-08-12"));
+        assert_eq!(reconcile(&corrected, "AAPL", &provider).mismatched.len(), 0);
+    }
+}
@@ -0,0 +1,45 @@
+//! CSV output
+//!
+//! Every row starts with its section title. Field groups are written as
+//! `Section,Field,Value` rows and each table repeats its own header row, so
+//! the blocks of a report can be told apart by their header line.
+
+use anyhow::Result;
+use csv::WriterBuilder;
+
+use super::{Block, Report};
+
+pub fn render(report: &Report) -> Result<String> {
+    let mut writer = WriterBuilder::new().flexible(true).from_writer(Vec::new());
+
+    for section in &report.sections {
+        for block in &section.blocks {
+            match block {
+                Block::Fields(fields) => {
+                    writer.write_record(["Section", "Field", "Value"])?;
+                    for field in fields {
+                        writer.write_record([
+                            section.title.as_str(),
+                            field.label.as_str(),
+                            field.value.text.as_str(),
+                        ])?;
+                    }
+                }
+                Block::Table(table) => {
+                    let header = std::iter::once("Section")
+                        .chain(table.headers.iter().map(String::as_str));
+                    writer.write_record(header)?;
+                    for row in &table.rows {
+                        let record = std::iter::once(section.title.as_str())
+                            .chain(row.iter().map(|cell| cell.text.as_str()));
+                        writer.write_record(record)?;
+                    }
+                }
+                // Notes are commentary for people, not data
+                Block::Note(_) => {}
+            }
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
@@ -0,0 +1,114 @@
+//! Standalone HTML output
+//!
+//! Tones become CSS classes so the embedded stylesheet (or a user's own) can
+//! colour values the same way the terminal renderer does.
+
+use std::fmt::Write;
+
+use super::{Block, NoteKind, Report, Text, Tone};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+.positive{color:#080}.negative{color:#c00}.warning{color:#b80}\
+.highlight{color:#06c}.emphasis{font-weight:bold}";
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    let title = escape(&report.title);
+
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html>");
+    let _ = writeln!(out, "<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>{}</title>", title);
+    let _ = writeln!(out, "<style>{}</style>", STYLE);
+    let _ = writeln!(out, "</head>");
+    let _ = writeln!(out, "<body>");
+    let _ = writeln!(out, "<h1>{}</h1>", title);
+
+    for section in &report.sections {
+        let _ = writeln!(out, "<section>");
+        if !section.title.is_empty() {
+            let _ = writeln!(out, "<h2>{}</h2>", escape(&section.title));
+        }
+
+        for block in &section.blocks {
+            match block {
+                Block::Fields(fields) => {
+                    let _ = writeln!(out, "<dl>");
+                    for field in fields {
+                        let _ = writeln!(out, "<dt>{}</dt>", escape(&field.label));
+                        let _ = writeln!(out, "{}", cell("dd", &field.value));
+                    }
+                    let _ = writeln!(out, "</dl>");
+                }
+                Block::Table(table) => {
+                    let _ = writeln!(out, "<table>");
+                    let _ = write!(out, "<thead><tr>");
+                    for header in &table.headers {
+                        let _ = write!(out, "<th>{}</th>", escape(header));
+                    }
+                    let _ = writeln!(out, "</tr></thead>");
+                    let _ = writeln!(out, "<tbody>");
+                    for row in &table.rows {
+                        let _ = write!(out, "<tr>");
+                        for value in row {
+                            let _ = write!(out, "{}", cell("td", value));
+                        }
+                        let _ = writeln!(out, "</tr>");
+                    }
+                    let _ = writeln!(out, "</tbody>");
+                    let _ = writeln!(out, "</table>");
+                }
+                Block::Note(note) => {
+                    let class = match note.kind {
+                        NoteKind::Info => "info",
+                        NoteKind::Tip => "tip",
+                        NoteKind::Warning => "warning",
+                    };
+                    let _ = writeln!(out, "<p class=\"note {}\">{}</p>", class, escape(&note.text));
+                }
+            }
+        }
+
+        let _ = writeln!(out, "</section>");
+    }
+
+    let _ = writeln!(out, "</body>");
+    let _ = writeln!(out, "</html>");
+    out
+}
+
+fn cell(tag: &str, text: &Text) -> String {
+    match class(text.tone) {
+        Some(class) => format!("<{0} class=\"{1}\">{2}</{0}>", tag, class, escape(&text.text)),
+        None => format!("<{0}>{1}</{0}>", tag, escape(&text.text)),
+    }
+}
+
+fn class(tone: Tone) -> Option<&'static str> {
+    match tone {
+        Tone::Plain => None,
+        Tone::Emphasis => Some("emphasis"),
+        Tone::Highlight => Some("highlight"),
+        Tone::Positive => Some("positive"),
+        Tone::Negative => Some("negative"),
+        Tone::Warning => Some("warning"),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,11 @@
+//! JSON output
+
+use anyhow::Result;
+
+use super::Report;
+
+pub fn render(report: &Report) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(report)?;
+    json.push('\n');
+    Ok(json)
+}
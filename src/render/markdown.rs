@@ -0,0 +1,76 @@
+//! GitHub-flavoured Markdown output
+//!
+//! The report title and section titles become headings, field groups become
+//! bullet lists and notes become block quotes, so a report can be pasted into
+//! a wiki or notes app as is. Only the emphasis tone survives, as bold text.
+
+use std::fmt::Write;
+
+use super::{Block, NoteKind, Report, Text, Tone};
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}", escape(&report.title));
+
+    for section in &report.sections {
+        if !section.title.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "## {}", escape(&section.title));
+        }
+
+        for block in &section.blocks {
+            let _ = writeln!(out);
+            match block {
+                Block::Fields(fields) => {
+                    for field in fields {
+                        let _ = writeln!(out, "- **{}:** {}", escape(&field.label), styled(&field.value));
+                    }
+                }
+                Block::Table(table) => {
+                    let headers: Vec<String> = table.headers.iter().map(|h| escape(h)).collect();
+                    let _ = writeln!(out, "| {} |", headers.join(" | "));
+                    let _ = writeln!(out, "|{}", " --- |".repeat(headers.len()));
+                    for row in &table.rows {
+                        let cells: Vec<String> = row.iter().map(styled).collect();
+                        let _ = writeln!(out, "| {} |", cells.join(" | "));
+                    }
+                }
+                Block::Note(note) => {
+                    let label = match note.kind {
+                        NoteKind::Info => "Note",
+                        NoteKind::Tip => "Tip",
+                        NoteKind::Warning => "Warning",
+                    };
+                    let _ = writeln!(out, "> **{}:** {}", label, escape(&note.text));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn styled(text: &Text) -> String {
+    let value = escape(&text.text);
+    match text.tone {
+        Tone::Emphasis if !value.is_empty() => format!("**{}**", value),
+        _ => value,
+    }
+}
+
+/// Escape characters Markdown would treat as markup; line breaks become
+/// spaces so a value can't end a table row early
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '|' | '<' | '>' | '[' | ']' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
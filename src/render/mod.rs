@@ -0,0 +1,401 @@
+//! Output rendering
+//!
+//! Commands turn analytics, tax and projection results into a [`Report`], a
+//! plain view model of titled sections holding fields, tables and notes. The
+//! renderers in this module only lay that model out as a terminal table, JSON,
+//! CSV, HTML or Markdown; they never compute anything, and view models never contain
+//! colour codes.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::format::{format_money, format_signed_percent};
+
+mod delimited;
+mod html;
+mod json;
+mod markdown;
+mod terminal;
+
+/// Output format for command reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Coloured tables for the terminal
+    #[default]
+    Table,
+    /// Pretty-printed JSON
+    Json,
+    /// CSV, one block of rows per field group or table
+    Csv,
+    /// Standalone HTML document
+    Html,
+    /// GitHub-flavoured Markdown headings, lists and tables
+    Markdown,
+}
+
+/// Border style of tables in terminal output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    /// Box drawing with rounded corners
+    #[default]
+    Rounded,
+    /// Plain ASCII borders
+    Ascii,
+    /// GitHub-flavoured Markdown, without colours
+    Markdown,
+    /// No borders, columns separated by spaces
+    Compact,
+}
+
+/// Layout of tables in terminal output; the other formats ignore it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableOptions {
+    pub style: TableStyle,
+    /// Longer cells are cut to this many characters, ending in `…`
+    pub max_column_width: Option<usize>,
+}
+
+/// Semantic styling hint for a value; each renderer maps it to its own styling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tone {
+    #[default]
+    Plain,
+    Emphasis,
+    Highlight,
+    Positive,
+    Negative,
+    Warning,
+}
+
+/// A piece of display text with its styling hint
+///
+/// Serializes as the bare string so JSON output stays free of styling noise.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Text {
+    pub text: String,
+    pub tone: Tone,
+}
+
+impl Text {
+    pub fn new(text: impl Into<String>, tone: Tone) -> Self {
+        Self {
+            text: text.into(),
+            tone,
+        }
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Self {
+        Self::new(text, Tone::Plain)
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Self::new(text, Tone::Plain)
+    }
+}
+
+impl Serialize for Text {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+/// A labelled value, e.g. `Total Payments: 12`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Field {
+    pub label: String,
+    pub value: Text,
+}
+
+/// Tabular data with a header row
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct TableView {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Text>>,
+}
+
+impl TableView {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, cells: Vec<Text>) {
+        debug_assert_eq!(cells.len(), self.headers.len());
+        self.rows.push(cells);
+    }
+}
+
+/// Kind of a free-text note attached to a section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteKind {
+    Info,
+    Tip,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Note {
+    pub kind: NoteKind,
+    pub text: String,
+}
+
+/// Content of a section, kept in display order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Block {
+    Fields(Vec<Field>),
+    Table(TableView),
+    Note(Note),
+}
+
+/// A titled group of blocks; an empty title renders without a heading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Section {
+    #[serde(skip)]
+    pub icon: Option<&'static str>,
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+impl Section {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            icon: None,
+            title: title.into(),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// A section without a heading, for notes and totals under the report title
+    pub fn untitled() -> Self {
+        Self::new("")
+    }
+
+    /// Decorate the heading with an icon in the terminal renderer
+    pub fn icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Add a labelled value, grouping it with any fields directly before it
+    pub fn field(mut self, label: impl Into<String>, value: impl Into<Text>) -> Self {
+        let field = Field {
+            label: label.into(),
+            value: value.into(),
+        };
+        match self.blocks.last_mut() {
+            Some(Block::Fields(fields)) => fields.push(field),
+            _ => self.blocks.push(Block::Fields(vec![field])),
+        }
+        self
+    }
+
+    pub fn table(mut self, table: TableView) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    pub fn note(mut self, kind: NoteKind, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Note(Note {
+            kind,
+            text: text.into(),
+        }));
+        self
+    }
+}
+
+/// A complete command result ready to be rendered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub title: String,
+    pub sections: Vec<Section>,
+}
+
+impl Report {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn section(mut self, section: Section) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    pub fn push(&mut self, section: Section) {
+        self.sections.push(section);
+    }
+
+    /// Shorthand for a report holding a single warning, e.g. "no data found"
+    pub fn warning(title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(title).section(Section::untitled().note(NoteKind::Warning, text))
+    }
+}
+
+/// Render a report in the requested format
+pub fn render(report: &Report, format: OutputFormat, tables: &TableOptions) -> Result<String> {
+    match format {
+        OutputFormat::Table => Ok(terminal::render(report, tables)),
+        OutputFormat::Json => json::render(report),
+        OutputFormat::Csv => delimited::render(report),
+        OutputFormat::Html => Ok(html::render(report)),
+        OutputFormat::Markdown => Ok(markdown::render(report)),
+    }
+}
+
+/// Format a surplus as `+$12.00` and a shortfall as `-$3.00`, toned by its sign
+pub fn signed_money(amount: Decimal) -> Text {
+    if amount >= Decimal::ZERO {
+        Text::new(format!("+{}", format_money(amount)), Tone::Positive)
+    } else {
+        Text::new(format!("-{}", format_money(-amount)), Tone::Negative)
+    }
+}
+
+/// Format a signed percentage as `+4.2%` / `-1.0%`, toned by its sign
+pub fn signed_percent(rate: Decimal) -> Text {
+    let tone = if rate >= Decimal::ZERO { Tone::Positive } else { Tone::Negative };
+    Text::new(format_signed_percent(rate, 1), tone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn sample_report() -> Report {
+        let mut table = TableView::new(&["Symbol", "Total"]);
+        table.row(vec!["AAPL".into(), Text::new("$24.00", Tone::Positive)]);
+        table.row(vec!["O, Inc".into(), "$1.50".into()]);
+
+        Report::new("Sample Report").section(
+            Section::new("Totals")
+                .icon("📊")
+                .field("Year", "2024")
+                .field("Total", Text::new("$25.50", Tone::Positive))
+                .table(table)
+                .note(NoteKind::Info, "Amounts are <estimates>"),
+        )
+    }
+
+    #[test]
+    fn test_consecutive_fields_share_a_block() {
+        let section = Section::new("S")
+            .field("A", "1")
+            .field("B", "2")
+            .note(NoteKind::Tip, "tip")
+            .field("C", "3");
+
+        assert_eq!(section.blocks.len(), 3);
+        match &section.blocks[0] {
+            Block::Fields(fields) => assert_eq!(fields.len(), 2),
+            other => panic!("expected fields, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_render_table_contains_all_values() {
+        colored::control::set_override(false);
+        let output = render(&sample_report(), OutputFormat::Table, &TableOptions::default()).unwrap();
+
+        assert!(output.contains("Sample Report"));
+        assert!(output.contains("📊 Totals"));
+        assert!(output.contains("  Year: 2024"));
+        assert!(output.contains("AAPL"));
+        assert!(output.contains("$24.00"));
+        assert!(output.contains("Amounts are <estimates>"));
+    }
+
+    #[test]
+    fn test_render_table_styles_and_truncation() {
+        colored::control::set_override(false);
+        let markdown = TableOptions {
+            style: TableStyle::Markdown,
+            max_column_width: None,
+        };
+        let output = render(&sample_report(), OutputFormat::Table, &markdown).unwrap();
+        assert!(output.contains("| Symbol | Total  |"));
+        assert!(output.contains("|--------|--------|"));
+        assert!(output.contains("| AAPL   | $24.00 |"));
+
+        let ascii = TableOptions {
+            style: TableStyle::Ascii,
+            max_column_width: Some(4),
+        };
+        let output = render(&sample_report(), OutputFormat::Table, &ascii).unwrap();
+        assert!(output.contains("+------+------+"));
+        assert!(output.contains("| Sym… | Tot… |"));
+        assert!(output.contains("| O, … | $1.… |"));
+        assert!(output.contains("| AAPL |"));
+    }
+
+    #[test]
+    fn test_render_json_drops_styling() {
+        let output = render(&sample_report(), OutputFormat::Json, &TableOptions::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["title"], "Sample Report");
+        let section = &value["sections"][0];
+        assert_eq!(section["title"], "Totals");
+        assert!(section.get("icon").is_none());
+        assert_eq!(section["blocks"][0]["fields"][1]["value"], "$25.50");
+        assert_eq!(section["blocks"][1]["table"]["rows"][0][1], "$24.00");
+        assert_eq!(section["blocks"][2]["note"]["kind"], "info");
+    }
+
+    #[test]
+    fn test_render_csv_quotes_and_prefixes_section() {
+        let output = render(&sample_report(), OutputFormat::Csv, &TableOptions::default()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "Section,Field,Value");
+        assert_eq!(lines[1], "Totals,Year,2024");
+        assert!(lines.contains(&"Section,Symbol,Total"));
+        assert!(lines.contains(&"Totals,\"O, Inc\",$1.50"));
+    }
+
+    #[test]
+    fn test_render_html_escapes_text() {
+        let output = render(&sample_report(), OutputFormat::Html, &TableOptions::default()).unwrap();
+
+        assert!(output.starts_with("<!DOCTYPE html>"));
+        assert!(output.contains("<h1>Sample Report</h1>"));
+        assert!(output.contains("<td class=\"positive\">$24.00</td>"));
+        assert!(output.contains("Amounts are &lt;estimates&gt;"));
+        assert!(!output.contains("📊"));
+    }
+
+    #[test]
+    fn test_render_markdown_headings_lists_and_tables() {
+        let output = render(&sample_report(), OutputFormat::Markdown, &TableOptions::default()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "# Sample Report");
+        assert!(lines.contains(&"## Totals"));
+        assert!(lines.contains(&"- **Year:** 2024"));
+        assert!(lines.contains(&"| Symbol | Total |"));
+        assert!(lines.contains(&"| --- | --- |"));
+        assert!(lines.contains(&"| AAPL | $24.00 |"));
+        assert!(lines.contains(&"> **Note:** Amounts are \\<estimates\\>"));
+        assert!(!output.contains("📊"));
+    }
+
+    #[test]
+    fn test_signed_percent() {
+        assert_eq!(signed_percent(dec!(4.26)), Text::new("+4.3%", Tone::Positive));
+        assert_eq!(signed_percent(dec!(-1)), Text::new("-1.0%", Tone::Negative));
+    }
+}
@@ -0,0 +1,122 @@
+//! Coloured terminal output
+
+use colored::*;
+use std::fmt::Write;
+use tabled::{builder::Builder, settings::Style};
+
+use super::{Block, Note, NoteKind, Report, Section, TableOptions, TableStyle, TableView, Text, Tone};
+
+pub fn render(report: &Report, tables: &TableOptions) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", report.title.green().bold());
+    let _ = writeln!(out);
+
+    for section in &report.sections {
+        render_section(&mut out, section, tables);
+    }
+
+    out
+}
+
+fn render_section(out: &mut String, section: &Section, tables: &TableOptions) {
+    if !section.title.is_empty() {
+        let heading = match section.icon {
+            Some(icon) => format!("{} {}", icon, section.title),
+            None => section.title.clone(),
+        };
+        let _ = writeln!(out, "{}", heading.blue().bold());
+        let _ = writeln!(out);
+    }
+
+    for block in &section.blocks {
+        match block {
+            Block::Fields(fields) => {
+                for field in fields {
+                    let _ = writeln!(out, "  {}: {}", field.label, styled(&field.value));
+                }
+            }
+            Block::Table(table) => {
+                let _ = writeln!(out, "{}", render_table(table, tables));
+            }
+            Block::Note(note) => {
+                let _ = writeln!(out, "{}", render_note(note));
+            }
+        }
+    }
+
+    let _ = writeln!(out);
+}
+
+fn render_table(table: &TableView, options: &TableOptions) -> String {
+    // Markdown is meant to be pasted elsewhere, so it stays free of colour codes
+    let markdown = options.style == TableStyle::Markdown;
+    let cell = |text: &str| {
+        let text = truncate(text, options.max_column_width);
+        if markdown {
+            text.replace('|', "\\|")
+        } else {
+            text
+        }
+    };
+
+    let mut builder = Builder::new();
+    builder.push_record(table.headers.iter().map(|h| {
+        let header = cell(h);
+        if markdown {
+            header
+        } else {
+            header.bold().to_string()
+        }
+    }));
+    for row in &table.rows {
+        builder.push_record(row.iter().map(|text| {
+            let value = cell(&text.text);
+            if markdown {
+                value
+            } else {
+                styled(&Text::new(value, text.tone))
+            }
+        }));
+    }
+
+    let mut table = builder.build();
+    match options.style {
+        TableStyle::Rounded => table.with(Style::rounded()),
+        TableStyle::Ascii => table.with(Style::ascii()),
+        TableStyle::Markdown => table.with(Style::markdown()),
+        TableStyle::Compact => table.with(Style::blank()),
+    };
+    table.to_string()
+}
+
+/// Cut `text` to `max` characters, marking the cut with an ellipsis
+fn truncate(text: &str, max: Option<usize>) -> String {
+    match max {
+        Some(max) if text.chars().count() > max => {
+            let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+            cut.push('…');
+            cut
+        }
+        _ => text.to_string(),
+    }
+}
+
+fn render_note(note: &Note) -> String {
+    match note.kind {
+        NoteKind::Info => format!("{} {}", "ℹ️".blue(), note.text),
+        NoteKind::Tip => format!("{} {}", "💡".yellow(), note.text),
+        NoteKind::Warning => note.text.yellow().to_string(),
+    }
+}
+
+fn styled(text: &Text) -> String {
+    let value = text.text.as_str();
+    match text.tone {
+        Tone::Plain => value.to_string(),
+        Tone::Emphasis => value.bold().to_string(),
+        Tone::Highlight => value.cyan().to_string(),
+        Tone::Positive => value.green().to_string(),
+        Tone::Negative => value.red().to_string(),
+        Tone::Warning => value.yellow().to_string(),
+    }
+}
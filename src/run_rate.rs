@@ -0,0 +1,380 @@
+//! Forward annual income (run-rate)
+//!
+//! The run-rate is what the portfolio would pay over the next year if every
+//! holding kept paying its most recent regular dividend: the latest per-share
+//! amount times the payment frequency times the shares held. Holdings without
+//! history fall back to a forward dividend set with `holdings
+//! set-forward-dividend`. Special dividends never count, and a symbol whose
+//! last payment is more than [`STALE_AFTER_DAYS`] old is treated as no longer
+//! paying.
+//!
+//! A snapshot is saved whenever the data changes, one per month, so the
+//! history survives holdings that are later removed.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::models::{
+    one_per_payment, Dividend, DividendFrequency, DividendTracker, DividendType, ForwardDividend, HoldingKey,
+    RunRateSnapshot,
+};
+
+/// A payer that has gone this long without a payment drops out of the run-rate
+pub const STALE_AFTER_DAYS: i64 = 400;
+
+/// Where a position's run-rate comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunRateSource {
+    /// The most recent regular payment
+    LastPayment(NaiveDate),
+    /// A forward dividend set on the holding
+    ForwardDividend,
+}
+
+/// Forward income from one position: a symbol in one account
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionRunRate {
+    pub symbol: String,
+    pub account: Option<String>,
+    pub shares: Decimal,
+    pub per_share: Decimal,
+    pub frequency: DividendFrequency,
+    pub annual_income: Decimal,
+    pub source: RunRateSource,
+}
+
+/// Forward income for the whole portfolio on a date
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardIncome {
+    pub as_of: NaiveDate,
+    /// Paying positions, largest income first
+    pub positions: Vec<PositionRunRate>,
+    /// Holdings with shares but no recent payment or forward dividend
+    pub not_paying: Vec<String>,
+}
+
+impl ForwardIncome {
+    /// Work out the run-rate as it stood on `as_of`
+    ///
+    /// Each account's position is priced on its own payments and shares.
+    /// Shares come from the holding's adjustments when it has one and from the
+    /// latest payment otherwise, so past dates use the shares held back then.
+    pub fn calculate(tracker: &DividendTracker, as_of: NaiveDate) -> Self {
+        let mut by_position: BTreeMap<HoldingKey, Vec<&Dividend>> = BTreeMap::new();
+        for dividend in tracker
+            .dividends
+            .iter()
+            .filter(|d| d.dividend_type == DividendType::Regular && d.ex_date <= as_of)
+        {
+            by_position
+                .entry(HoldingKey::new(&dividend.symbol, dividend.account_name()))
+                .or_default()
+                .push(dividend);
+        }
+        for key in tracker.holdings.keys() {
+            by_position.entry(key.clone()).or_default();
+        }
+
+        let mut positions = Vec::new();
+        let mut not_paying = Vec::new();
+        for (key, mut payments) in by_position {
+            payments.sort_by_key(|d| d.ex_date);
+            let holding = tracker.holdings.get(&key);
+            let latest = payments
+                .last()
+                .filter(|d| (as_of - d.ex_date).num_days() <= STALE_AFTER_DAYS);
+
+            let shares = match (holding, latest) {
+                (Some(holding), _) => holding.shares_on(as_of),
+                (None, Some(latest)) => latest.shares_owned,
+                (None, None) => Decimal::ZERO,
+            };
+            if shares.is_zero() {
+                continue;
+            }
+
+            let symbol = key.symbol.as_str();
+            let forward = holding.and_then(|h| h.forward_dividend.as_ref());
+            match payment_rate(tracker, symbol, &payments, forward, as_of) {
+                Some((per_share, frequency, source)) => positions.push(PositionRunRate {
+                    symbol: symbol.to_string(),
+                    account: holding.map_or_else(|| latest.and_then(|d| d.account.clone()), |h| h.account.clone()),
+                    shares,
+                    per_share,
+                    annual_income: per_share * Decimal::from(frequency.payments_per_year()) * shares,
+                    frequency,
+                    source,
+                }),
+                None if !not_paying.iter().any(|s| s == symbol) => not_paying.push(symbol.to_string()),
+                None => {}
+            }
+        }
+
+        positions.sort_by(|a, b| b.annual_income.cmp(&a.annual_income).then_with(|| a.symbol.cmp(&b.symbol)));
+        ForwardIncome {
+            as_of,
+            positions,
+            not_paying,
+        }
+    }
+
+    /// Forward annual income across all positions
+    pub fn total(&self) -> Decimal {
+        self.positions.iter().map(|p| p.annual_income).sum()
+    }
+
+    /// Forward income per month
+    pub fn monthly(&self) -> Decimal {
+        self.total() / Decimal::from(12)
+    }
+}
+
+/// Per-payment amount, frequency and source of a symbol's run-rate on `as_of`
+///
+/// `payments` are its regular dividends gone ex by then, oldest first, and
+/// `forward` the forward dividend set on its holding.
+fn payment_rate(
+    tracker: &DividendTracker,
+    symbol: &str,
+    payments: &[&Dividend],
+    forward: Option<&ForwardDividend>,
+    as_of: NaiveDate,
+) -> Option<(Decimal, DividendFrequency, RunRateSource)> {
+    let latest = payments
+        .last()
+        .filter(|d| (as_of - d.ex_date).num_days() <= STALE_AFTER_DAYS);
+
+    match (latest, forward) {
+        (Some(latest), _) => {
+            let frequency = tracker
+                .securities
+                .frequency(symbol)
+                .or(forward.map(|f| &f.frequency))
+                .cloned()
+                .unwrap_or_else(|| infer_frequency(payments));
+            Some((latest.amount_per_share, frequency, RunRateSource::LastPayment(latest.ex_date)))
+        }
+        (None, Some(forward)) => {
+            let per_payment = forward.annual_amount / Decimal::from(forward.frequency.payments_per_year());
+            Some((per_payment, forward.frequency.clone(), RunRateSource::ForwardDividend))
+        }
+        (None, None) => None,
+    }
+}
+
+/// Forward annual dividend per share of `symbol` as it stood on `as_of`,
+/// whether or not any shares were held then
+pub fn forward_dividend_per_share(tracker: &DividendTracker, symbol: &str, as_of: NaiveDate) -> Option<Decimal> {
+    let mut payments = one_per_payment(
+        tracker
+            .dividends
+            .iter()
+            .filter(|d| d.symbol == symbol && d.dividend_type == DividendType::Regular && d.ex_date <= as_of),
+    );
+    payments.sort_by_key(|d| d.ex_date);
+    let forward = tracker.holdings_of(symbol).into_iter().find_map(|h| h.forward_dividend.as_ref());
+
+    payment_rate(tracker, symbol, &payments, forward, as_of)
+        .map(|(per_share, frequency, _)| per_share * Decimal::from(frequency.payments_per_year()))
+}
+
+/// Frequency implied by the gap between the two most recent ex-dates
+///
+/// A single payment counts as annual, the conservative reading.
+pub fn infer_frequency(sorted_payments: &[&Dividend]) -> DividendFrequency {
+    match sorted_payments {
+        [.., previous, latest] => match (latest.ex_date - previous.ex_date).num_days() {
+            0..=45 => DividendFrequency::Monthly,
+            46..=135 => DividendFrequency::Quarterly,
+            136..=270 => DividendFrequency::SemiAnnual,
+            _ => DividendFrequency::Annual,
+        },
+        _ => DividendFrequency::Annual,
+    }
+}
+
+/// The run-rate at each of the last `months` month ends, ending with `as_of`
+///
+/// The final point is `as_of` itself so the series ends on today's figure.
+pub fn monthly_history(tracker: &DividendTracker, months: usize, as_of: NaiveDate) -> Vec<(NaiveDate, Decimal)> {
+    let mut dates = vec![as_of];
+    let mut month_start = as_of.with_day(1).unwrap_or(as_of);
+    while dates.len() < months.max(1) {
+        let month_end = month_start - Duration::days(1);
+        dates.push(month_end);
+        month_start = month_end.with_day(1).unwrap_or(month_end);
+    }
+    dates.reverse();
+
+    dates
+        .into_iter()
+        .map(|date| (date, ForwardIncome::calculate(tracker, date).total()))
+        .collect()
+}
+
+/// Save the run-rate for the month of `today`, replacing an earlier snapshot
+/// from the same month so each month keeps its latest figure
+pub fn record_snapshot(tracker: &mut DividendTracker, today: NaiveDate) {
+    let income = ForwardIncome::calculate(tracker, today);
+    tracker.run_rate_history.insert(
+        today.format("%Y-%m").to_string(),
+        RunRateSnapshot {
+            date: today,
+            annual_income: income.total(),
+            positions: income.positions.len(),
+        },
+    );
+}
+
+/// One month of run-rate history, saved or worked out from the records
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRatePoint {
+    pub date: NaiveDate,
+    pub annual_income: Decimal,
+    /// Whether the figure was saved at the time rather than reconstructed
+    pub saved: bool,
+}
+
+/// Run-rate for each of the last `months` months, ending with the month of `today`
+///
+/// Saved snapshots win; other months are reconstructed at month end, which
+/// misses holdings removed since. Months before the first income are dropped.
+pub fn history(tracker: &DividendTracker, months: usize, today: NaiveDate) -> Vec<RunRatePoint> {
+    monthly_history(tracker, months, today)
+        .into_iter()
+        .map(|(date, reconstructed)| {
+            match tracker.run_rate_history.get(&date.format("%Y-%m").to_string()) {
+                Some(snapshot) => RunRatePoint {
+                    date: snapshot.date,
+                    annual_income: snapshot.annual_income,
+                    saved: true,
+                },
+                None => RunRatePoint {
+                    date,
+                    annual_income: reconstructed,
+                    saved: false,
+                },
+            }
+        })
+        .skip_while(|point| point.annual_income.is_zero())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ForwardDividend, Holding};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dividend(symbol: &str, ex_date: &str, amount: Decimal, shares: Decimal) -> Dividend {
+        Dividend::new(
+            symbol.to_string(),
+            None,
+            date(ex_date),
+            date(ex_date),
+            amount,
+            shares,
+            DividendType::Regular,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_forward_dividend_per_share_as_of_a_date() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(dividend("KO", "2023-03-14", dec!(0.46), dec!(100)));
+        tracker.add_dividend(dividend("KO", "2023-06-14", dec!(0.46), dec!(100)));
+        tracker.add_dividend(dividend("KO", "2024-03-14", dec!(0.485), dec!(100)));
+
+        // Known without holding any shares, and from the payments up to the date
+        assert_eq!(forward_dividend_per_share(&tracker, "KO", date("2023-08-01")), Some(dec!(1.84)));
+        assert_eq!(forward_dividend_per_share(&tracker, "KO", date("2023-01-05")), None);
+        assert_eq!(forward_dividend_per_share(&tracker, "PEP", date("2023-08-01")), None);
+    }
+
+    #[test]
+    fn test_forward_income_from_latest_payment() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(dividend("KO", "2024-03-14", dec!(0.485), dec!(100)));
+        tracker.add_dividend(dividend("KO", "2024-06-14", dec!(0.485), dec!(100)));
+        tracker.add_dividend(dividend("O", "2024-05-31", dec!(0.2565), dec!(50)));
+        tracker.add_dividend(dividend("O", "2024-06-28", dec!(0.2625), dec!(50)));
+        tracker.add_dividend(dividend("OLD", "2022-01-10", dec!(1), dec!(10)));
+        let mut special = dividend("KO", "2024-06-20", dec!(5), dec!(100));
+        special.dividend_type = DividendType::Special;
+        tracker.add_dividend(special);
+
+        let mut ko = Holding::new("KO".to_string(), dec!(120), None, None).unwrap();
+        ko.adjust(dec!(20), None, date("2024-08-01")).unwrap();
+        tracker.add_holding(ko);
+        let mut new = Holding::new("NEW".to_string(), dec!(10), None, None).unwrap();
+        new.forward_dividend = Some(ForwardDividend::new(dec!(2), DividendFrequency::Quarterly).unwrap());
+        tracker.add_holding(new);
+        tracker.add_holding(Holding::new("GROW".to_string(), dec!(5), None, None).unwrap());
+
+        let income = ForwardIncome::calculate(&tracker, date("2024-07-01"));
+        // KO 0.485 x 4 x 120 (shares before the August purchase), O 0.2625 x 12 x 50, NEW 2 x 10
+        assert_eq!(income.positions[0].symbol, "KO");
+        assert_eq!(income.positions[0].shares, dec!(120));
+        assert_eq!(income.total(), dec!(232.8) + dec!(157.5) + dec!(20));
+        assert_eq!(income.not_paying, vec!["GROW"]);
+
+        let later = ForwardIncome::calculate(&tracker, date("2024-09-01"));
+        assert_eq!(later.positions[0].shares, dec!(140));
+
+        let history = monthly_history(&tracker, 3, date("2024-07-15"));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].0, date("2024-05-31"));
+        assert_eq!(history[2].0, date("2024-07-15"));
+    }
+
+    #[test]
+    fn test_forward_income_prices_each_account_on_its_own_shares() {
+        let mut tracker = DividendTracker::new();
+        for ex_date in ["2024-03-14", "2024-06-14"] {
+            tracker.add_dividend(dividend("KO", ex_date, dec!(0.485), dec!(100)));
+            let mut ira = dividend("KO", ex_date, dec!(0.485), dec!(20));
+            ira.account = Some("IRA".to_string());
+            tracker.add_dividend(ira);
+        }
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(100), None, None).unwrap());
+        let mut ira = Holding::new("KO".to_string(), dec!(20), None, None).unwrap();
+        ira.set_account("IRA");
+        tracker.add_holding(ira);
+
+        // Quarterly in both accounts, not monthly from the same ex-date twice
+        let income = ForwardIncome::calculate(&tracker, date("2024-07-01"));
+        assert_eq!(income.positions.len(), 2);
+        assert!(income.positions.iter().all(|p| p.frequency == DividendFrequency::Quarterly));
+        assert_eq!(income.positions[1].account.as_deref(), Some("IRA"));
+        assert_eq!(income.total(), dec!(194) + dec!(38.8));
+        assert_eq!(forward_dividend_per_share(&tracker, "KO", date("2024-07-01")), Some(dec!(1.94)));
+    }
+
+    #[test]
+    fn test_run_rate_snapshots_override_reconstruction() {
+        let mut tracker = DividendTracker::new();
+        tracker.add_dividend(dividend("KO", "2024-03-14", dec!(0.485), dec!(100)));
+        tracker.add_dividend(dividend("KO", "2024-06-14", dec!(0.485), dec!(100)));
+
+        record_snapshot(&mut tracker, date("2024-06-20"));
+        record_snapshot(&mut tracker, date("2024-06-25"));
+        assert_eq!(tracker.run_rate_history.len(), 1);
+        assert_eq!(tracker.run_rate_history["2024-06"].date, date("2024-06-25"));
+        assert_eq!(tracker.run_rate_history["2024-06"].annual_income, dec!(194));
+
+        // A holding sold since then would otherwise rewrite June's figure
+        tracker.dividends.clear();
+        let points = history(&tracker, 12, date("2024-07-10"));
+        assert_eq!(points.len(), 2, "leading months without income are dropped");
+        assert!(points[0].saved);
+        assert_eq!(points[0].annual_income, dec!(194));
+        assert!(!points[1].saved);
+        assert_eq!(points[1].annual_income, dec!(0));
+    }
+}
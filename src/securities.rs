@@ -0,0 +1,152 @@
+//! Per-symbol facts pinned in `securities.toml`
+//!
+//! The data provider knows nothing of company names or sectors, and a short
+//! or uneven payment history can make the schedule look wrong. Each
+//! `[SYMBOL]` table in `securities.toml` (next to `config.toml`) may pin:
+//!
+//! ```toml
+//! [KO]
+//! name = "The Coca-Cola Company"
+//! sector = "Consumer Staples"
+//! frequency = "quarterly"          # monthly, quarterly, semi-annual or annual
+//! tax_classification = "qualified" # used for records left unclassified
+//! ```
+//!
+//! Pinned values win over anything recorded or inferred, except that a
+//! dividend's own tax classification wins over the pinned default.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cli::{FrequencyArg, TaxClassificationArg};
+use crate::models::{DividendFrequency, TaxClassification};
+use crate::paths;
+use crate::symbols::normalize_symbol;
+
+/// What the user pinned for one symbol
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityOverride {
+    /// Company or fund name, shown wherever a name is displayed
+    pub name: Option<String>,
+    pub sector: Option<String>,
+    /// Payment schedule used instead of the one inferred from history
+    #[serde(deserialize_with = "frequency")]
+    pub frequency: Option<DividendFrequency>,
+    /// Classification for the symbol's dividends recorded as unknown
+    #[serde(deserialize_with = "tax_classification")]
+    pub tax_classification: Option<TaxClassification>,
+}
+
+fn frequency<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DividendFrequency>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    FrequencyArg::from_str(&text, true)
+        .map(|frequency| Some(frequency.into()))
+        .map_err(|_| D::Error::custom(format!("unknown frequency \"{}\"; use monthly, quarterly, semi-annual or annual", text)))
+}
+
+fn tax_classification<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<TaxClassification>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    TaxClassificationArg::from_str(&text, true)
+        .map(|classification| Some(classification.into()))
+        .map_err(|_| {
+            D::Error::custom(format!(
+                "unknown tax classification \"{}\"; use qualified, non-qualified, return-of-capital, tax-free or foreign",
+                text
+            ))
+        })
+}
+
+/// A payment frequency pinned for one run, e.g. `--frequency-override TSLA=annual`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyOverride {
+    pub symbol: String,
+    pub frequency: DividendFrequency,
+}
+
+/// Every symbol's pinned facts, keyed by normalized symbol
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Securities(BTreeMap<String, SecurityOverride>);
+
+impl Securities {
+    /// Path of `securities.toml`
+    pub fn file() -> Result<PathBuf> {
+        Ok(paths::config_dir()?.join("securities.toml"))
+    }
+
+    /// Load `securities.toml`; no file pins nothing
+    pub fn load() -> Result<Self> {
+        let file = Self::file()?;
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&file)?;
+        Self::parse(&contents).with_context(|| format!("{} is invalid", file.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let overrides: BTreeMap<String, SecurityOverride> = toml::from_str(contents)?;
+        Ok(Securities(
+            overrides
+                .into_iter()
+                .map(|(symbol, security)| (normalize_symbol(&symbol), security))
+                .collect(),
+        ))
+    }
+
+    /// Pin a symbol's payment frequency, replacing any from the file
+    pub fn pin_frequency(&mut self, symbol: &str, frequency: DividendFrequency) {
+        self.0.entry(normalize_symbol(symbol)).or_default().frequency = Some(frequency);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SecurityOverride> {
+        self.0.get(symbol)
+    }
+
+    pub fn name(&self, symbol: &str) -> Option<&str> {
+        self.get(symbol)?.name.as_deref()
+    }
+
+    pub fn sector(&self, symbol: &str) -> Option<&str> {
+        self.get(symbol)?.sector.as_deref()
+    }
+
+    pub fn frequency(&self, symbol: &str) -> Option<&DividendFrequency> {
+        self.get(symbol)?.frequency.as_ref()
+    }
+
+    pub fn tax_classification(&self, symbol: &str) -> Option<&TaxClassification> {
+        self.get(symbol)?.tax_classification.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_symbols_and_reads_cli_spellings() {
+        let securities = Securities::parse(
+            "[\"VOD LN\"]\nname = \"Vodafone Group\"\nfrequency = \"semi-annual\"\n\n\
+             [o]\nsector = \"Real Estate\"\ntax_classification = \"non-qualified\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(securities.name("VOD.L"), Some("Vodafone Group"));
+        assert_eq!(securities.frequency("VOD.L"), Some(&DividendFrequency::SemiAnnual));
+        assert_eq!(securities.sector("O"), Some("Real Estate"));
+        assert_eq!(securities.tax_classification("O"), Some(&TaxClassification::NonQualified));
+        assert_eq!(securities.name("O"), None);
+        assert!(securities.get("KO").is_none());
+
+        let typo = Securities::parse("[KO]\nfrequncy = \"quarterly\"\n").unwrap_err();
+        assert!(format!("{:#}", typo).contains("unknown field `frequncy`"));
+        let bad_value = Securities::parse("[KO]\nfrequency = \"weekly\"\n").unwrap_err();
+        assert!(format!("{:#}", bad_value).contains("use monthly, quarterly, semi-annual or annual"));
+    }
+}
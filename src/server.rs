@@ -0,0 +1,1061 @@
+//! Read-only HTTP API for dashboards
+//!
+//! `serve` answers `GET /dividends` and `GET /holdings` with JSON pages of
+//! read models. Every response carries an ETag over its body, so a client
+//! polling with `If-None-Match` gets an empty `304 Not Modified` until the
+//! data or its query changes. The data file is reloaded on each request,
+//! which keeps the API in step with edits made from the CLI.
+//!
+//! `GET /events` is a server-sent event stream of the data directory's
+//! [event log](crate::events): `dividend_added`, `alerts_generated` and
+//! `fetch_completed`, as the CLI records them.
+//!
+//! Query parameters:
+//! - `limit` (default [`DEFAULT_LIMIT`], at most [`MAX_LIMIT`]) and `offset`
+//! - `fields`, a comma-separated list of read-model fields to return
+//!
+//! Built with the `graphql` feature, the server also answers
+//! [GraphQL](crate::graphql) queries at `/graphql` over the same read models.
+//!
+//! `POST /dividends` adds a dividend from a JSON body with `symbol`,
+//! `ex_date`, `pay_date`, `amount_per_share`, `shares_owned` and optionally
+//! `withholding_tax`, `country`, `account`, `isin`, `cusip` and `force`, built
+//! the way `div add` builds it: the account and identifiers default to the
+//! holding's. Once [API tokens](crate::tokens) are configured every request needs
+//! one as `Authorization: Bearer <token>`, and only `write` tokens can add;
+//! without tokens the API can be read by anyone and not written at all.
+//!
+//! Started with `--share`, the server answers nothing but
+//! `GET /share/<token>`, a page of income totals and charts, and
+//! `GET /share/<token>/summary.json`, the same figures as JSON. Neither shows
+//! holdings, share counts or per-symbol amounts, so the link can be handed to
+//! anyone without giving away position sizes.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Local, Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::commands::dividends;
+use crate::cli::{AddArgs, IncomeKindArg};
+use crate::events::EventLog;
+use crate::format::format_money;
+use crate::models::{Dividend, DividendTracker, DuplicateDividend, Holding, IncomeKind};
+use crate::persistence::PersistenceManager;
+use crate::run_rate::ForwardIncome;
+use crate::tokens::{same_token, ApiTokens, TokenScope};
+
+/// Page size when the request doesn't give one
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Largest page a client can ask for
+pub const MAX_LIMIT: usize = 1000;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// A dividend as the API returns it
+///
+/// Every field is always present, `null` when unset, so clients can rely on
+/// the shape.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct DividendView {
+    pub symbol: String,
+    pub company_name: Option<String>,
+    pub ex_date: NaiveDate,
+    pub pay_date: NaiveDate,
+    pub amount_per_share: Decimal,
+    pub shares_owned: Decimal,
+    pub total_amount: Decimal,
+    pub net_amount: Decimal,
+    pub dividend_type: String,
+    pub tax_classification: String,
+    pub withholding_tax: Option<Decimal>,
+    pub country: Option<String>,
+    pub fees: Option<Decimal>,
+    pub account: Option<String>,
+    pub income_kind: IncomeKind,
+}
+
+/// Fields a client can select on `/dividends`
+pub const DIVIDEND_FIELDS: &[&str] = &[
+    "symbol",
+    "company_name",
+    "ex_date",
+    "pay_date",
+    "amount_per_share",
+    "shares_owned",
+    "total_amount",
+    "net_amount",
+    "dividend_type",
+    "tax_classification",
+    "withholding_tax",
+    "country",
+    "fees",
+    "account",
+    "income_kind",
+];
+
+impl From<&Dividend> for DividendView {
+    fn from(dividend: &Dividend) -> Self {
+        DividendView {
+            symbol: dividend.symbol.clone(),
+            company_name: dividend.company_name.clone(),
+            ex_date: dividend.ex_date,
+            pay_date: dividend.pay_date,
+            amount_per_share: dividend.amount_per_share,
+            shares_owned: dividend.shares_owned,
+            total_amount: dividend.total_amount,
+            net_amount: dividend.net_amount(),
+            dividend_type: format!("{:?}", dividend.dividend_type),
+            tax_classification: format!("{:?}", dividend.tax_classification),
+            withholding_tax: dividend.withholding_tax,
+            country: dividend.country.clone(),
+            fees: dividend.fees,
+            account: dividend.account.clone(),
+            income_kind: dividend.income_kind,
+        }
+    }
+}
+
+/// A holding as the API returns it
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject), graphql(complex))]
+pub struct HoldingView {
+    pub symbol: String,
+    pub shares: Decimal,
+    pub avg_cost_basis: Option<Decimal>,
+    pub current_yield: Option<Decimal>,
+    pub forward_annual_dividend: Option<Decimal>,
+    pub closed_date: Option<NaiveDate>,
+    pub account: Option<String>,
+}
+
+/// Fields a client can select on `/holdings`
+pub const HOLDING_FIELDS: &[&str] = &[
+    "symbol",
+    "shares",
+    "avg_cost_basis",
+    "current_yield",
+    "forward_annual_dividend",
+    "closed_date",
+    "account",
+];
+
+impl From<&Holding> for HoldingView {
+    fn from(holding: &Holding) -> Self {
+        HoldingView {
+            symbol: holding.symbol.clone(),
+            shares: holding.shares,
+            avg_cost_basis: holding.avg_cost_basis,
+            current_yield: holding.current_yield,
+            forward_annual_dividend: holding.forward_dividend.as_ref().map(|f| f.annual_amount),
+            closed_date: holding.closed_date,
+            account: holding.account.clone(),
+        }
+    }
+}
+
+/// Who the server answers
+#[derive(Debug, Clone, PartialEq)]
+pub enum Access {
+    /// The API, for holders of these tokens (or any reader when there are none)
+    Api(ApiTokens),
+    /// Holders of the share link: only the redacted summary under `/share/<token>`
+    Shared(String),
+}
+
+/// Income for one year of a shared summary
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearIncome {
+    pub year: i32,
+    pub income: Decimal,
+    pub payments: usize,
+}
+
+/// Income for one month of a shared summary
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthIncome {
+    /// `YYYY-MM`
+    pub month: String,
+    pub income: Decimal,
+}
+
+/// What a share link shows: income totals, never positions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShareSummary {
+    pub as_of: NaiveDate,
+    pub total_income: Decimal,
+    /// Income received each year, oldest first
+    pub years: Vec<YearIncome>,
+    /// Income received in each of the last 12 months, oldest first
+    pub months: Vec<MonthIncome>,
+    pub forward_annual_income: Decimal,
+    pub paying_positions: usize,
+}
+
+impl ShareSummary {
+    /// Summarize the payments received (by pay date) up to `today`
+    pub fn build(tracker: &DividendTracker, today: NaiveDate) -> Self {
+        let received: Vec<&Dividend> = tracker.dividends.iter().filter(|d| d.pay_date <= today).collect();
+
+        let mut years: BTreeMap<i32, YearIncome> = BTreeMap::new();
+        for dividend in &received {
+            let year = dividend.pay_date.year();
+            let entry = years.entry(year).or_insert(YearIncome {
+                year,
+                income: Decimal::ZERO,
+                payments: 0,
+            });
+            entry.income += dividend.total_amount;
+            entry.payments += 1;
+        }
+
+        let this_month = today.with_day(1).unwrap_or(today);
+        let months = (0..12)
+            .rev()
+            .map(|back| {
+                let start = this_month - Months::new(back);
+                let income = received
+                    .iter()
+                    .filter(|d| d.pay_date.year() == start.year() && d.pay_date.month() == start.month())
+                    .map(|d| d.total_amount)
+                    .sum();
+                MonthIncome {
+                    month: start.format("%Y-%m").to_string(),
+                    income,
+                }
+            })
+            .collect();
+
+        let forward = ForwardIncome::calculate(tracker, today);
+        ShareSummary {
+            as_of: today,
+            total_income: received.iter().map(|d| d.total_amount).sum(),
+            years: years.into_values().collect(),
+            months,
+            forward_annual_income: forward.total(),
+            paying_positions: forward.positions.len(),
+        }
+    }
+
+    /// A self-contained page with bar charts of the yearly and monthly income
+    pub fn to_html(&self) -> String {
+        fn bars(rows: Vec<(String, Decimal)>) -> String {
+            let largest = rows.iter().map(|(_, income)| *income).max().unwrap_or_default();
+            rows.into_iter()
+                .map(|(label, income)| {
+                let width = if largest.is_zero() { Decimal::ZERO } else { income * Decimal::from(100) / largest };
+                format!(
+                    "<div class=\"row\"><span class=\"label\">{}</span><span class=\"bar\" style=\"width:{:.1}%\"></span><span class=\"value\">{}</span></div>\n",
+                    label,
+                    width,
+                    format_money(income)
+                )
+            })
+            .collect()
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Dividend Income</title><style>\n\
+             body{{font-family:sans-serif;max-width:40em;margin:2em auto;color:#222}}\n\
+             .row{{display:flex;align-items:center;margin:.2em 0}}\n\
+             .label{{width:5em}}.value{{margin-left:.5em}}\n\
+             .bar{{display:inline-block;height:1em;background:#2a9d8f;min-width:1px}}\n\
+             </style></head><body>\n\
+             <h1>Dividend Income</h1>\n\
+             <p>Received to date: <strong>{}</strong><br>Forward annual income: <strong>{}</strong> from {} paying positions</p>\n\
+             <h2>Last 12 months</h2>\n{}<h2>By year</h2>\n{}<p><small>As of {}</small></p>\n</body></html>\n",
+            format_money(self.total_income),
+            format_money(self.forward_annual_income),
+            self.paying_positions,
+            bars(self.months.iter().map(|m| (m.month.clone(), m.income)).collect()),
+            bars(self.years.iter().map(|y| (y.year.to_string(), y.income)).collect()),
+            self.as_of.format("%Y-%m-%d")
+        )
+    }
+}
+
+/// A parsed HTTP request line and headers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Read the request line, headers and a body of `Content-Length` bytes
+    pub fn read(reader: &mut impl BufRead) -> Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            bail!("Malformed request line");
+        };
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let length: usize = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+        if length > MAX_BODY_BYTES {
+            bail!("Request body of {} bytes is over the {} byte limit", length, MAX_BODY_BYTES);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+
+        Ok(Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Decode `%XX` escapes and `+` in a query component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Status, headers and body of a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, value: &Value) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn html(status: u16, page: String) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/html; charset=utf-8".to_string())],
+            body: page.into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response::json(status, &json!({ "error": message.into() }))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            304 => "Not Modified",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// Write the response as HTTP/1.1
+    pub fn write_to(&self, out: &mut impl Write) -> Result<()> {
+        write!(out, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        for (name, value) in &self.headers {
+            write!(out, "{}: {}\r\n", name, value)?;
+        }
+        write!(out, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        out.write_all(&self.body)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Pagination and field selection taken from the query string
+#[derive(Debug, Clone, PartialEq)]
+struct PageQuery {
+    limit: usize,
+    offset: usize,
+    fields: Option<Vec<String>>,
+}
+
+impl PageQuery {
+    fn parse(request: &Request, allowed: &[&str]) -> Result<Self> {
+        let number = |name: &str, default: usize| -> Result<usize> {
+            match request.query.get(name) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| anyhow!("'{}' must be a non-negative integer, got '{}'", name, value)),
+                None => Ok(default),
+            }
+        };
+
+        let limit = number("limit", DEFAULT_LIMIT)?;
+        if limit == 0 || limit > MAX_LIMIT {
+            bail!("'limit' must be between 1 and {}", MAX_LIMIT);
+        }
+
+        let fields = match request.query.get("fields") {
+            Some(fields) => {
+                let fields: Vec<String> = fields
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if let Some(unknown) = fields.iter().find(|f| !allowed.contains(&f.as_str())) {
+                    bail!("Unknown field '{}'; choose from {}", unknown, allowed.join(", "));
+                }
+                Some(fields)
+            }
+            None => None,
+        };
+
+        Ok(PageQuery {
+            limit,
+            offset: number("offset", 0)?,
+            fields,
+        })
+    }
+
+    /// The page envelope for `items`, keeping only the selected fields
+    fn page<T: Serialize>(&self, items: &[T]) -> Result<Value> {
+        let total = items.len();
+        let mut page = Vec::new();
+        for item in items.iter().skip(self.offset).take(self.limit) {
+            let mut value = serde_json::to_value(item)?;
+            if let (Some(fields), Value::Object(object)) = (&self.fields, &mut value) {
+                let selected: Map<String, Value> = fields
+                    .iter()
+                    .filter_map(|f| object.remove(f).map(|v| (f.clone(), v)))
+                    .collect();
+                *object = selected;
+            }
+            page.push(value);
+        }
+
+        let next = self.offset + self.limit;
+        Ok(json!({
+            "items": page,
+            "total": total,
+            "offset": self.offset,
+            "limit": self.limit,
+            "next_offset": if next < total { Some(next) } else { None },
+        }))
+    }
+}
+
+/// 405 for anything but GET
+fn method_not_allowed(request: &Request) -> Option<Response> {
+    (request.method != "GET").then(|| {
+        let mut response = Response::error(405, "Only GET is supported");
+        response.headers.push(("Allow".to_string(), "GET".to_string()));
+        response
+    })
+}
+
+/// Dividends newest first, with a fixed order for ties so pages don't shift
+pub(crate) fn newest_first(dividends: &[Dividend]) -> impl Iterator<Item = &Dividend> {
+    let mut dividends: Vec<&Dividend> = dividends.iter().collect();
+    dividends.sort_by(|a, b| {
+        b.ex_date
+            .cmp(&a.ex_date)
+            .then_with(|| a.symbol.cmp(&b.symbol))
+            .then_with(|| a.pay_date.cmp(&b.pay_date))
+    });
+    dividends.into_iter()
+}
+
+/// Holdings in symbol order, then account order
+pub(crate) fn by_symbol(tracker: &DividendTracker) -> impl Iterator<Item = &Holding> {
+    let mut holdings: Vec<&Holding> = tracker.holdings.values().collect();
+    holdings.sort_by_key(|h| h.key());
+    holdings.into_iter()
+}
+
+/// Answer one request against the tracker
+pub fn respond(tracker: &DividendTracker, request: &Request) -> Response {
+    #[cfg(feature = "graphql")]
+    if request.path.trim_end_matches('/') == "/graphql" {
+        return graphql(tracker, request);
+    }
+    if let Some(response) = method_not_allowed(request) {
+        return response;
+    }
+
+    let page = match request.path.trim_end_matches('/') {
+        "/dividends" => PageQuery::parse(request, DIVIDEND_FIELDS).and_then(|query| {
+            let views: Vec<DividendView> = newest_first(&tracker.dividends).map(DividendView::from).collect();
+            query.page(&views)
+        }),
+        "/holdings" => PageQuery::parse(request, HOLDING_FIELDS).and_then(|query| {
+            let views: Vec<HoldingView> = by_symbol(tracker).map(HoldingView::from).collect();
+            query.page(&views)
+        }),
+        _ => return Response::error(404, format!("No endpoint at {}; try /dividends or /holdings", request.path)),
+    };
+
+    match page {
+        Ok(page) => with_etag(Response::json(200, &page), request),
+        Err(e) => Response::error(400, e.to_string()),
+    }
+}
+
+/// Answer a GraphQL query sent as a JSON body or, on GET, in the query string
+#[cfg(feature = "graphql")]
+fn graphql(tracker: &DividendTracker, request: &Request) -> Response {
+    let query = match request.method.as_str() {
+        "POST" => serde_json::from_slice::<async_graphql::Request>(&request.body)
+            .map_err(|e| anyhow!("Invalid GraphQL request: {}", e)),
+        "GET" => match request.query.get("query") {
+            Some(query) => {
+                let variables = match request.query.get("variables") {
+                    Some(variables) => serde_json::from_str(variables)
+                        .map(async_graphql::Variables::from_json)
+                        .map_err(|e| anyhow!("'variables' must be a JSON object: {}", e)),
+                    None => Ok(async_graphql::Variables::default()),
+                };
+                variables.map(|variables| async_graphql::Request::new(query.as_str()).variables(variables))
+            }
+            None => Err(anyhow!("Send the query as ?query= or in a POST body")),
+        },
+        _ => {
+            let mut response = Response::error(405, "Only GET and POST are supported");
+            response.headers.push(("Allow".to_string(), "GET, POST".to_string()));
+            return response;
+        }
+    };
+
+    let today = Local::now().naive_local().date();
+    match query.and_then(|query| crate::graphql::execute(tracker.clone(), today, query)) {
+        Ok(value) => with_etag(Response::json(200, &value), request),
+        Err(e) => Response::error(400, e.to_string()),
+    }
+}
+
+/// Answer one request from a share link holder
+///
+/// Anything but the summary for the right token is a plain 404, so the
+/// endpoints of the full API can't be discovered through a shared server.
+pub fn respond_shared(tracker: &DividendTracker, request: &Request, token: &str, today: NaiveDate) -> Response {
+    if let Some(response) = method_not_allowed(request) {
+        return response;
+    }
+
+    let path = request.path.trim_end_matches('/');
+    let (requested, json) = match path.strip_suffix("/summary.json") {
+        Some(rest) => (rest, true),
+        None => (path, false),
+    };
+    let authorized = requested.strip_prefix("/share/").is_some_and(|given| same_token(given, token));
+    if !authorized {
+        return Response::error(404, "Not found");
+    }
+
+    let summary = ShareSummary::build(tracker, today);
+    let response = if json {
+        match serde_json::to_value(&summary) {
+            Ok(value) => Response::json(200, &value),
+            Err(e) => return Response::error(500, e.to_string()),
+        }
+    } else {
+        Response::html(200, summary.to_html())
+    };
+    with_etag(response, request)
+}
+
+/// Check the request's bearer token allows `needed`, or the response refusing it
+pub fn authorize(tokens: &ApiTokens, request: &Request, needed: TokenScope) -> Result<(), Response> {
+    if tokens.is_empty() {
+        return match needed {
+            TokenScope::Read => Ok(()),
+            TokenScope::Write => Err(Response::error(
+                403,
+                "Writing needs a token with write scope; add one with 'dividend-tracker configure tokens add --scope write'",
+            )),
+        };
+    }
+
+    let given = request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    match given.and_then(|given| tokens.scope_of(given)) {
+        None => {
+            let mut response = Response::error(401, "Missing or unknown API token");
+            response.headers.push(("WWW-Authenticate".to_string(), "Bearer".to_string()));
+            Err(response)
+        }
+        Some(scope) if !scope.allows(needed) => {
+            Err(Response::error(403, format!("This token has {} scope and cannot write", scope.name())))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// A dividend posted to `/dividends`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewDividend {
+    symbol: String,
+    ex_date: NaiveDate,
+    pay_date: NaiveDate,
+    amount_per_share: Decimal,
+    shares_owned: Decimal,
+    /// Tax withheld at source, in total
+    #[serde(default)]
+    withholding_tax: Option<Decimal>,
+    /// Country that withheld the tax
+    #[serde(default)]
+    country: Option<String>,
+    /// Account paid into (defaults to the holding's)
+    #[serde(default)]
+    account: Option<String>,
+    /// ISIN of the security (defaults to the holding's)
+    #[serde(default)]
+    isin: Option<String>,
+    /// CUSIP of the security (defaults to the holding's)
+    #[serde(default)]
+    cusip: Option<String>,
+    /// Add even if the symbol already has a dividend on that ex-date
+    #[serde(default)]
+    force: bool,
+}
+
+impl NewDividend {
+    /// The same dividend as `div add` would be given it, so both build it alike
+    fn add_args(self) -> AddArgs {
+        AddArgs {
+            symbol: self.symbol,
+            ex_date: self.ex_date.to_string(),
+            pay_date: self.pay_date.to_string(),
+            amount: self.amount_per_share.to_string(),
+            shares: self.shares_owned.to_string(),
+            fee: None,
+            withholding: self.withholding_tax.map(|w| w.to_string()),
+            country: self.country,
+            isin: self.isin,
+            cusip: self.cusip,
+            account: self.account,
+            kind: IncomeKindArg::Dividend,
+            classification: None,
+            force: self.force,
+        }
+    }
+}
+
+/// Add the dividend in the request body, answering with it as stored
+fn add_dividend(persistence: &PersistenceManager, request: &Request) -> Response {
+    let args = match serde_json::from_slice::<NewDividend>(&request.body) {
+        Ok(new) => new.add_args(),
+        Err(e) => return Response::error(400, format!("Invalid dividend: {}", e)),
+    };
+    let added = persistence.with_tracker_mut(|tracker| {
+        let (added, _) = dividends::add_to_tracker(tracker, &args)?;
+        Ok(DividendView::from(&added))
+    });
+    match added {
+        Ok(view) => match serde_json::to_value(view) {
+            Ok(value) => Response::json(201, &value),
+            Err(e) => Response::error(500, e.to_string()),
+        },
+        Err(e) if e.downcast_ref::<DuplicateDividend>().is_some() => {
+            Response::error(409, format!("{}; send \"force\": true to add it anyway", e))
+        }
+        Err(e) => Response::error(400, e.to_string()),
+    }
+}
+
+/// Tag a response with an ETag of its body, answering 304 when the client's copy is current
+fn with_etag(mut response: Response, request: &Request) -> Response {
+    let mut hasher = DefaultHasher::new();
+    response.body.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let current = request.headers.get("if-none-match").is_some_and(|tags| {
+        tags.split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*")
+    });
+    if current {
+        response.status = 304;
+        response.body.clear();
+    }
+    response.headers.push(("ETag".to_string(), etag));
+    response.headers.push(("Cache-Control".to_string(), "no-cache".to_string()));
+    response
+}
+
+/// How often `/events` checks the log for new lines
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Idle time after which `/events` sends a comment to keep proxies from closing it
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+fn handle_connection(stream: TcpStream, persistence: &PersistenceManager, access: &Access) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request = Request::read(&mut reader)?;
+    let mut stream = stream;
+
+    let tokens = match access {
+        Access::Api(tokens) => tokens,
+        Access::Shared(token) => {
+            let response = match persistence.load() {
+                Ok(tracker) => respond_shared(&tracker, &request, token, Local::now().naive_local().date()),
+                Err(e) => Response::error(500, format!("Failed to load data: {}", e)),
+            };
+            return response.write_to(&mut stream);
+        }
+    };
+
+    // GraphQL queries are POSTed too, but only read
+    let path = request.path.trim_end_matches('/');
+    let writing = request.method == "POST" && path != "/graphql";
+    let needed = if writing { TokenScope::Write } else { TokenScope::Read };
+    if let Err(response) = authorize(tokens, &request, needed) {
+        return response.write_to(&mut stream);
+    }
+
+    if request.method == "GET" && path == "/events" {
+        return stream_events(&mut stream, &persistence.events(), &request);
+    }
+
+    let response = if writing && path == "/dividends" {
+        add_dividend(persistence, &request)
+    } else {
+        match persistence.load() {
+            Ok(tracker) => respond(&tracker, &request),
+            Err(e) => Response::error(500, format!("Failed to load data: {}", e)),
+        }
+    };
+    response.write_to(&mut stream)
+}
+
+/// Stream new events as server-sent events until the client goes away
+///
+/// A client starts at the end of the log, or just after the event named by
+/// its `Last-Event-ID` header when reconnecting.
+fn stream_events(out: &mut impl Write, log: &EventLog, request: &Request) -> Result<()> {
+    let mut offset = request
+        .headers
+        .get("last-event-id")
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_else(|| log.end());
+
+    write!(
+        out,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    out.flush()?;
+
+    let mut idle = Duration::ZERO;
+    loop {
+        let events = log.read_from(offset)?;
+        for (end, event) in &events {
+            write!(out, "id: {}\nevent: {}\ndata: {}\n\n", end, event.kind.name(), serde_json::to_string(event)?)?;
+            offset = *end;
+        }
+        if events.is_empty() && idle >= KEEP_ALIVE_INTERVAL {
+            write!(out, ": keep-alive\n\n")?;
+            idle = Duration::ZERO;
+        } else if !events.is_empty() {
+            idle = Duration::ZERO;
+        }
+        out.flush()?;
+
+        thread::sleep(EVENT_POLL_INTERVAL);
+        idle += EVENT_POLL_INTERVAL;
+    }
+}
+
+/// Serve each connection on its own thread until the process is stopped
+pub fn run(listener: TcpListener, persistence: &PersistenceManager, access: &Access) -> Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Connection failed: {}", e);
+                continue;
+            }
+        };
+        let persistence = persistence.clone();
+        let access = access.clone();
+        thread::spawn(move || match handle_connection(stream, &persistence, &access) {
+            Ok(()) => {}
+            // Event stream clients leave by closing the connection
+            Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| {
+                matches!(e.kind(), io::ErrorKind::BrokenPipe | io::ErrorKind::ConnectionReset)
+            }) => {}
+            Err(e) => eprintln!("Request failed: {}", e),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKind;
+    use crate::models::DividendType;
+    use rust_decimal_macros::dec;
+
+    fn tracker() -> DividendTracker {
+        let mut tracker = DividendTracker::new();
+        for (symbol, ex_date) in [("KO", "2024-03-14"), ("KO", "2024-06-14"), ("O", "2024-06-28")] {
+            let ex_date = NaiveDate::parse_from_str(ex_date, "%Y-%m-%d").unwrap();
+            tracker.add_dividend(
+                Dividend::new(symbol.to_string(), None, ex_date, ex_date, dec!(0.5), dec!(100), DividendType::Regular)
+                    .unwrap(),
+            );
+        }
+        tracker.add_holding(Holding::new("KO".to_string(), dec!(100), Some(dec!(55)), None).unwrap());
+        tracker
+    }
+
+    fn get(target: &str, headers: &str) -> Request {
+        let raw = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n", target, headers);
+        Request::read(&mut raw.as_bytes()).unwrap()
+    }
+
+    fn body(response: &Response) -> Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[test]
+    fn test_dividends_page_fields_and_etag() {
+        let tracker = tracker();
+
+        let response = respond(&tracker, &get("/dividends?limit=2&fields=symbol,ex_date", ""));
+        assert_eq!(response.status, 200);
+        let page = body(&response);
+        assert_eq!(page["total"], 3);
+        assert_eq!(page["next_offset"], 2);
+        assert_eq!(page["items"][0], json!({ "symbol": "O", "ex_date": "2024-06-28" }));
+
+        let rest = body(&respond(&tracker, &get("/dividends?limit=2&offset=2", "")));
+        assert_eq!(rest["items"].as_array().unwrap().len(), 1);
+        assert_eq!(rest["next_offset"], Value::Null);
+        assert_eq!(rest["items"][0]["net_amount"], "50.0");
+
+        let (_, etag) = response.headers.iter().find(|(name, _)| name == "ETag").unwrap();
+        let cached = respond(
+            &tracker,
+            &get("/dividends?limit=2&fields=symbol,ex_date", &format!("If-None-Match: {}\r\n", etag)),
+        );
+        assert_eq!(cached.status, 304);
+        assert!(cached.body.is_empty());
+
+        let holdings = respond(&tracker, &get("/holdings", &format!("If-None-Match: {}\r\n", etag)));
+        assert_eq!(holdings.status, 200);
+        assert_eq!(body(&holdings)["items"][0]["avg_cost_basis"], "55");
+
+        assert_eq!(respond(&tracker, &get("/dividends?fields=price", "")).status, 400);
+        assert_eq!(respond(&tracker, &get("/dividends?limit=0", "")).status, 400);
+        assert_eq!(respond(&tracker, &get("/reports", "")).status, 404);
+        assert_eq!(percent_decode("a%2Cb+c"), "a,b c");
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn test_graphql_answers_get_and_post() {
+        let tracker = tracker();
+
+        let fetched = body(&respond(&tracker, &get("/graphql?query=%7B+holdings+%7B+symbol+%7D+%7D", "")));
+        assert_eq!(fetched["data"], json!({ "holdings": [{ "symbol": "KO" }] }));
+
+        let query = r#"{"query":"query($n: Int!) { dividends(limit: $n) { symbol } }","variables":{"n":1}}"#;
+        let raw = format!("POST /graphql HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", query.len(), query);
+        let posted = respond(&tracker, &Request::read(&mut raw.as_bytes()).unwrap());
+        assert_eq!(posted.status, 200);
+        assert_eq!(body(&posted)["data"], json!({ "dividends": [{ "symbol": "O" }] }));
+
+        assert_eq!(respond(&tracker, &get("/graphql", "")).status, 400);
+    }
+
+    #[test]
+    fn test_share_link_shows_only_redacted_summary() {
+        let tracker = tracker();
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let token = "0123456789abcdef";
+
+        let page = respond_shared(&tracker, &get(&format!("/share/{}", token), ""), token, today);
+        assert_eq!(page.status, 200);
+        let html = String::from_utf8(page.body).unwrap();
+        assert!(html.contains("Received to date: <strong>$150.00</strong>"));
+        assert!(html.contains("2024-06"));
+
+        let summary = respond_shared(&tracker, &get(&format!("/share/{}/summary.json", token), ""), token, today);
+        let summary = body(&summary);
+        assert_eq!(summary["years"], json!([{ "year": 2024, "income": "150.0", "payments": 3 }]));
+        assert_eq!(summary["months"].as_array().unwrap().len(), 12);
+        assert_eq!(summary["months"][11], json!({ "month": "2024-07", "income": "0" }));
+        assert!(!summary.to_string().contains("shares"));
+
+        for target in ["/share/0123456789abcdee", "/share/", "/dividends", "/holdings"] {
+            assert_eq!(respond_shared(&tracker, &get(target, ""), token, today).status, 404);
+        }
+    }
+
+    #[test]
+    fn test_tokens_guard_reads_and_writes() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let open = ApiTokens::default();
+        assert!(authorize(&open, &get("/dividends", ""), TokenScope::Read).is_ok());
+        assert_eq!(authorize(&open, &get("/dividends", ""), TokenScope::Write).unwrap_err().status, 403);
+
+        let mut tokens = ApiTokens::default();
+        let read = tokens.add(None, TokenScope::Read, today).unwrap().token.clone();
+        let write = tokens.add(None, TokenScope::Write, today).unwrap().token.clone();
+        let bearer = |token: &str| format!("Authorization: Bearer {}\r\n", token);
+
+        let missing = authorize(&tokens, &get("/dividends", ""), TokenScope::Read).unwrap_err();
+        assert_eq!(missing.status, 401);
+        assert!(missing.headers.contains(&("WWW-Authenticate".to_string(), "Bearer".to_string())));
+        assert_eq!(authorize(&tokens, &get("/dividends", &bearer("dt_wrong")), TokenScope::Read).unwrap_err().status, 401);
+        assert!(authorize(&tokens, &get("/dividends", &bearer(&read)), TokenScope::Read).is_ok());
+        assert_eq!(authorize(&tokens, &get("/dividends", &bearer(&read)), TokenScope::Write).unwrap_err().status, 403);
+        assert!(authorize(&tokens, &get("/dividends", &bearer(&write)), TokenScope::Write).is_ok());
+    }
+
+    #[test]
+    fn test_post_dividend_adds_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let persistence = PersistenceManager::with_custom_path(dir.path());
+        let post = |body: &str| {
+            let raw = format!(
+                "POST /dividends HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            add_dividend(&persistence, &Request::read(&mut raw.as_bytes()).unwrap())
+        };
+        let ko = r#"{"symbol":"ko","ex_date":"2024-06-14","pay_date":"2024-07-01","amount_per_share":"0.485","shares_owned":"100"}"#;
+
+        let created = post(ko);
+        assert_eq!(created.status, 201);
+        assert_eq!(body(&created)["symbol"], "KO");
+        assert_eq!(persistence.load().unwrap().dividends.len(), 1);
+
+        assert_eq!(post(ko).status, 409);
+        assert_eq!(post(&ko.replace("}", r#","force":true}"#)).status, 201);
+        assert_eq!(post(r#"{"symbol":"KO"}"#).status, 400);
+        assert_eq!(persistence.load().unwrap().dividends.len(), 2);
+    }
+
+    #[test]
+    fn test_post_dividend_defaults_to_the_holding_account() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let persistence = PersistenceManager::with_custom_path(dir.path());
+        persistence
+            .with_tracker_mut(|tracker| {
+                let mut vz = Holding::new("VZ".to_string(), dec!(50), None, None)?;
+                vz.account = Some("IRA".to_string());
+                tracker.add_holding(vz);
+                Ok(())
+            })
+            .unwrap();
+        let post = |body: &str| {
+            let raw = format!("POST /dividends HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            add_dividend(&persistence, &Request::read(&mut raw.as_bytes()).unwrap())
+        };
+
+        let vz = r#"{"symbol":"vz","ex_date":"2024-07-10","pay_date":"2024-08-01","amount_per_share":"0.665","shares_owned":"50"}"#;
+        assert_eq!(body(&post(vz))["account"], "IRA");
+        let taxable = vz
+            .replace("2024-07-10", "2024-10-10")
+            .replace("2024-08-01", "2024-11-01")
+            .replace("}", r#","account":"Taxable"}"#);
+        assert_eq!(post(&taxable).status, 201);
+
+        let accounts: Vec<_> =
+            persistence.load().unwrap().dividends.iter().map(|d| d.account.clone().unwrap_or_default()).collect();
+        assert_eq!(accounts, vec!["IRA", "Taxable"]);
+    }
+
+    #[test]
+    fn test_events_stream_new_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let persistence = PersistenceManager::with_custom_path(dir.path());
+        persistence.events().publish(EventKind::FetchCompleted, json!({})).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = persistence.clone();
+        thread::spawn(move || run(listener, &server, &Access::Api(ApiTokens::default())));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(stream, "GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert!(line.starts_with("HTTP/1.1 200"));
+
+        persistence.events().publish(EventKind::DividendAdded, json!({ "symbol": "KO" })).unwrap();
+        let mut received = Vec::new();
+        while !received.iter().any(|l: &String| l.starts_with("data:")) {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            received.push(line);
+        }
+        // Events from before the client connected are not replayed
+        assert!(received.iter().any(|l| l == "event: dividend_added\n"));
+        assert!(!received.iter().any(|l| l.contains("fetch_completed")));
+    }
+}
@@ -0,0 +1,127 @@
+//! Large-dataset stress tests for the persistence and analytics layers
+//!
+//! Ignored by default; run them with an optimized build:
+//!
+//! ```text
+//! cargo test --release -- --ignored stress_
+//! ```
+//!
+//! The test generates [`DIVIDENDS`] dividends over [`SYMBOLS`] holdings and
+//! fails when a step exceeds its budget. The budgets are the scalability
+//! targets documented in the README; peak memory is only checked on Linux.
+
+use chrono::{Duration, NaiveDate};
+use rust_decimal_macros::dec;
+use std::time::{Duration as Elapsed, Instant};
+use tempfile::TempDir;
+
+use crate::analytics::{AnalyticsScope, DividendAnalytics};
+use crate::config::AnalysisSettings;
+use crate::filters::DividendFilter;
+use crate::models::{Dividend, DividendTracker, DividendType, Holding};
+use crate::persistence::PersistenceManager;
+
+/// Dividends in the generated dataset
+const DIVIDENDS: usize = 1_000_000;
+/// Distinct symbols, each held and paying quarterly
+const SYMBOLS: usize = 2_000;
+
+const SAVE_BUDGET: Elapsed = Elapsed::from_secs(20);
+const LOAD_BUDGET: Elapsed = Elapsed::from_secs(15);
+const LIST_BUDGET: Elapsed = Elapsed::from_secs(2);
+const SUMMARY_BUDGET: Elapsed = Elapsed::from_secs(5);
+/// Peak resident memory of the whole test process
+const MEMORY_BUDGET_MB: u64 = 3_072;
+
+/// A tracker with `count` quarterly dividends spread over `symbols` holdings
+fn generate_tracker(count: usize, symbols: usize) -> DividendTracker {
+    let mut tracker = DividendTracker::new();
+    let start = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+    for n in 0..symbols {
+        let holding = Holding::new(format!("S{:04}", n), dec!(100), Some(dec!(25)), None).unwrap();
+        tracker.add_holding(holding);
+    }
+    for i in 0..count {
+        let ex_date = start + Duration::days(((i / symbols) * 91 + i % 60) as i64);
+        tracker.add_dividend(
+            Dividend::new(
+                format!("S{:04}", i % symbols),
+                None,
+                ex_date,
+                ex_date + Duration::days(14),
+                dec!(0.25),
+                dec!(100),
+                DividendType::Regular,
+            )
+            .unwrap(),
+        );
+    }
+    tracker
+}
+
+/// Run `step`, failing if it takes longer than `budget`
+fn timed<T>(name: &str, budget: Elapsed, step: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = step();
+    let elapsed = started.elapsed();
+    println!("{}: {:?} (budget {:?})", name, elapsed, budget);
+    assert!(elapsed <= budget, "{} took {:?}, over its {:?} budget", name, elapsed, budget);
+    result
+}
+
+/// Peak resident memory of this process in MB, where the platform reports it
+fn peak_memory_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+#[test]
+#[ignore]
+fn stress_one_million_dividends() {
+    if cfg!(debug_assertions) {
+        println!("The stress budgets assume an optimized build; run with --release");
+        return;
+    }
+    let tracker = generate_tracker(DIVIDENDS, SYMBOLS);
+    let temp_dir = TempDir::new().unwrap();
+    let persistence = PersistenceManager::with_custom_path(temp_dir.path());
+
+    timed("save", SAVE_BUDGET, || persistence.save(&tracker).unwrap());
+    drop(tracker);
+    let tracker = timed("load", LOAD_BUDGET, || persistence.load().unwrap());
+    assert_eq!(tracker.dividends.len(), DIVIDENDS);
+
+    let listed = timed("list (one year, sorted)", LIST_BUDGET, || {
+        let mut listed = DividendFilter::new().year(2000).apply(&tracker.dividends);
+        listed.sort_by_key(|d| (d.pay_date, &d.symbol));
+        listed.len()
+    });
+    assert!(listed > 0);
+
+    let settings = AnalysisSettings::default();
+    timed("summary (one year)", SUMMARY_BUDGET, || {
+        DividendAnalytics::generate(&tracker, Some(2000), None, &settings, AnalyticsScope::default()).unwrap()
+    });
+    let all = AnalyticsScope {
+        top_payers: true,
+        frequency: true,
+        consistency: true,
+        yields: true,
+        growth: true,
+        symbol_growth: true,
+    };
+    let analytics = timed("summary (all analytics)", SUMMARY_BUDGET, || {
+        DividendAnalytics::generate(&tracker, None, None, &settings, all).unwrap()
+    });
+    assert_eq!(analytics.total_payments, DIVIDENDS);
+
+    // A second save also backs up the first one
+    timed("save (with backup)", SAVE_BUDGET, || persistence.save(&tracker).unwrap());
+
+    if let Some(peak) = peak_memory_mb() {
+        println!("peak memory: {} MB (budget {} MB)", peak, MEMORY_BUDGET_MB);
+        assert!(peak <= MEMORY_BUDGET_MB, "peak memory {} MB is over the {} MB budget", peak, MEMORY_BUDGET_MB);
+    }
+}
@@ -0,0 +1,215 @@
+//! Normalizing ticker spellings across broker conventions
+//!
+//! The same listing arrives as `VOD.L` (Yahoo style), `VOD:LN` or `VOD LN`
+//! (Bloomberg exchange codes) or `LON:VOD` (exchange prefix). Every symbol is
+//! rewritten to the dotted suffix form on the way in so one security is not
+//! tracked under several spellings. Mappings the conventions can't express,
+//! such as a renamed ticker, are kept as aliases on the tracker.
+
+/// Bloomberg exchange codes and the dotted suffix they map to
+const EXCHANGE_CODES: &[(&str, &str)] = &[
+    ("US", ""),
+    ("UN", ""),
+    ("UW", ""),
+    ("UQ", ""),
+    ("LN", "L"),
+    ("CN", "TO"),
+    ("CT", "TO"),
+    ("GR", "DE"),
+    ("GY", "DE"),
+    ("FP", "PA"),
+    ("NA", "AS"),
+    ("SW", "SW"),
+    ("SE", "SW"),
+    ("IM", "MI"),
+    ("SM", "MC"),
+    ("HK", "HK"),
+    ("AU", "AX"),
+    ("AT", "AX"),
+    ("JP", "T"),
+    ("JT", "T"),
+];
+
+/// Exchange prefixes (`LON:VOD`) and the dotted suffix they map to
+const EXCHANGE_PREFIXES: &[(&str, &str)] = &[
+    ("NYSE", ""),
+    ("NASDAQ", ""),
+    ("AMEX", ""),
+    ("LON", "L"),
+    ("LSE", "L"),
+    ("TSE", "TO"),
+    ("TSX", "TO"),
+    ("ETR", "DE"),
+    ("FRA", "F"),
+    ("EPA", "PA"),
+    ("AMS", "AS"),
+    ("SWX", "SW"),
+    ("BIT", "MI"),
+    ("BME", "MC"),
+    ("HKG", "HK"),
+    ("ASX", "AX"),
+    ("TYO", "T"),
+];
+
+/// Dotted exchange suffixes and the country of the exchange (ISO codes)
+const SUFFIX_COUNTRIES: &[(&str, &str)] = &[
+    ("L", "GB"),
+    ("TO", "CA"),
+    ("V", "CA"),
+    ("NE", "CA"),
+    ("DE", "DE"),
+    ("F", "DE"),
+    ("PA", "FR"),
+    ("AS", "NL"),
+    ("BR", "BE"),
+    ("SW", "CH"),
+    ("MI", "IT"),
+    ("MC", "ES"),
+    ("LS", "PT"),
+    ("IR", "IE"),
+    ("VI", "AT"),
+    ("ST", "SE"),
+    ("CO", "DK"),
+    ("OL", "NO"),
+    ("HE", "FI"),
+    ("HK", "HK"),
+    ("AX", "AU"),
+    ("NZ", "NZ"),
+    ("T", "JP"),
+    ("SI", "SG"),
+    ("KS", "KR"),
+    ("TW", "TW"),
+    ("SA", "BR"),
+    ("MX", "MX"),
+    ("JO", "ZA"),
+];
+
+fn with_suffix(ticker: &str, suffix: &str) -> String {
+    if suffix.is_empty() {
+        ticker.to_string()
+    } else {
+        format!("{}.{}", ticker, suffix)
+    }
+}
+
+fn lookup(table: &[(&str, &'static str)], code: &str) -> Option<&'static str> {
+    table.iter().find(|(c, _)| *c == code).map(|(_, suffix)| *suffix)
+}
+
+/// Rewrite a symbol in any supported convention to the dotted suffix form
+///
+/// Unknown exchange codes are left as written (upper-cased) rather than guessed.
+pub fn normalize_symbol(raw: &str) -> String {
+    let symbol = raw.trim().to_uppercase();
+
+    if let Some((left, right)) = symbol.split_once(':') {
+        if let Some(suffix) = lookup(EXCHANGE_PREFIXES, left) {
+            return with_suffix(right, suffix);
+        }
+        if let Some(suffix) = lookup(EXCHANGE_CODES, right) {
+            return with_suffix(left, suffix);
+        }
+    }
+
+    if let Some((ticker, code)) = symbol.split_once(' ') {
+        let code = code.trim();
+        if let Some(suffix) = lookup(EXCHANGE_CODES, code) {
+            return with_suffix(ticker, suffix);
+        }
+        // "VOD LN EQUITY" as copied from a terminal
+        if let Some(code) = code.strip_suffix(" EQUITY") {
+            if let Some(suffix) = lookup(EXCHANGE_CODES, code.trim()) {
+                return with_suffix(ticker, suffix);
+            }
+        }
+    }
+
+    symbol
+}
+
+/// Country a listing trades in, from its dotted suffix (`NESN.SW` is CH)
+///
+/// Symbols without a suffix, or with one that isn't an exchange such as
+/// `BRK.B`, give `None`.
+pub fn country_from_symbol(symbol: &str) -> Option<&'static str> {
+    let (_, suffix) = symbol.rsplit_once('.')?;
+    lookup(SUFFIX_COUNTRIES, &suffix.to_uppercase())
+}
+
+/// Number of single-character edits that turn `a` into `b` (Levenshtein distance)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// "Did you mean ...?" for a symbol that looks like a typo of a known one
+///
+/// Short tickers allow one edit, longer ones two; only the closest matches
+/// are offered.
+pub fn did_you_mean<'a>(symbol: &str, known: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let symbol = normalize_symbol(symbol);
+    let max_distance = if symbol.chars().count() <= 3 { 1 } else { 2 };
+
+    let mut candidates: Vec<(usize, &str)> = known
+        .into_iter()
+        .map(|k| (edit_distance(&symbol, k), k))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    // A known symbol needs no suggestion
+    let closest = candidates.iter().map(|(distance, _)| *distance).min().filter(|d| *d > 0)?;
+    candidates.retain(|(distance, _)| *distance == closest);
+    candidates.sort();
+    candidates.dedup();
+
+    let names: Vec<&str> = candidates.iter().take(3).map(|(_, k)| *k).collect();
+    Some(format!("Did you mean {}?", names.join(" or ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_did_you_mean_suggests_close_symbols() {
+        assert_eq!(edit_distance("MSTF", "MSFT"), 2);
+        assert_eq!(edit_distance("KO", "KO"), 0);
+        let known = ["MSFT", "KO", "PEP", "PEG", "O"];
+        assert_eq!(did_you_mean("msf", known).as_deref(), Some("Did you mean MSFT?"));
+        assert_eq!(did_you_mean("PEX", known).as_deref(), Some("Did you mean PEG or PEP?"));
+        assert_eq!(did_you_mean("XYZ", known), None);
+        assert_eq!(did_you_mean("KO", known), None);
+    }
+
+    #[test]
+    fn test_country_from_symbol_suffix() {
+        assert_eq!(country_from_symbol("NESN.SW"), Some("CH"));
+        assert_eq!(country_from_symbol("enb.to"), Some("CA"));
+        assert_eq!(country_from_symbol(&normalize_symbol("VOD LN")), Some("GB"));
+        assert_eq!(country_from_symbol("BRK.B"), None);
+        assert_eq!(country_from_symbol("KO"), None);
+    }
+
+    #[test]
+    fn test_normalize_symbol_conventions() {
+        assert_eq!(normalize_symbol(" bp.l "), "BP.L");
+        assert_eq!(normalize_symbol("VOD:LN"), "VOD.L");
+        assert_eq!(normalize_symbol("VOD LN"), "VOD.L");
+        assert_eq!(normalize_symbol("vod ln equity"), "VOD.L");
+        assert_eq!(normalize_symbol("LON:VOD"), "VOD.L");
+        assert_eq!(normalize_symbol("RY CN"), "RY.TO");
+        assert_eq!(normalize_symbol("TSX:RY"), "RY.TO");
+        assert_eq!(normalize_symbol("AAPL US"), "AAPL");
+        assert_eq!(normalize_symbol("NASDAQ:MSFT"), "MSFT");
+        assert_eq!(normalize_symbol("BRK.B"), "BRK.B");
+        assert_eq!(normalize_symbol("XYZ:QQ"), "XYZ:QQ");
+    }
+}
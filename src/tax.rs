@@ -1,11 +1,15 @@
 use anyhow::Result;
-use chrono::{Datelike, NaiveDate};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
-use crate::models::{Dividend, DividendTracker, TaxClassification};
+use crate::config::{AccountKind, AccountSettings, DateBasis};
+use crate::filters::DividendFilter;
+use crate::models::{Dividend, DividendTracker, Holding, IncomeKind, TaxClassification};
+use crate::paths;
 
 /// Tax summary for a specific tax year
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,8 +28,26 @@ pub struct TaxSummary {
     pub tax_free_dividends: Decimal,
     /// Foreign dividends with breakdown
     pub foreign_dividends: ForeignDividendSummary,
+    /// Fees deducted from payments (income is still reported gross)
+    #[serde(default)]
+    pub total_fees: Decimal,
+    /// Tax withheld at source from any payment, foreign-classified or not
+    #[serde(default)]
+    pub total_withholding: Decimal,
+    /// Interest income, reported on 1099-INT and left out of the dividend totals
+    #[serde(default)]
+    pub interest_income: Decimal,
+    /// Interest income by symbol
+    #[serde(default)]
+    pub interest_by_symbol: BTreeMap<String, Decimal>,
     /// Breakdown by stock symbol
     pub by_symbol: HashMap<String, SymbolTaxSummary>,
+    /// Breakdown by account, sorted by account name
+    #[serde(default)]
+    pub by_account: Vec<AccountTaxSummary>,
+    /// Dividends and interest paid into retirement accounts, left out of the estimated tax
+    #[serde(default)]
+    pub retirement_income: Decimal,
     /// Tax lot breakdown (if available)
     pub tax_lots: Vec<TaxLotSummary>,
     /// Estimated tax information
@@ -41,10 +63,28 @@ pub struct ForeignDividendSummary {
     pub total_withholding_tax: Decimal,
     /// Net foreign dividend income (after withholding)
     pub net_foreign_income: Decimal,
-    /// Breakdown by country (if available)
+    /// Breakdown by country of every dividend paid from abroad, whatever its
+    /// classification; the country comes from the dividend or its symbol's
+    /// exchange suffix, and is [`UNKNOWN_COUNTRY`] for foreign-classified
+    /// dividends with neither
     pub by_country: HashMap<String, CountryTaxSummary>,
+    /// The same dividends by symbol, largest income first
+    #[serde(default)]
+    pub by_symbol: Vec<ForeignSymbolSummary>,
 }
 
+impl ForeignDividendSummary {
+    /// Countries by income, largest first
+    pub fn countries(&self) -> Vec<&CountryTaxSummary> {
+        let mut countries: Vec<&CountryTaxSummary> = self.by_country.values().collect();
+        countries.sort_by(|a, b| b.dividend_income.cmp(&a.dividend_income).then_with(|| a.country.cmp(&b.country)));
+        countries
+    }
+}
+
+/// Country shown for foreign dividends whose country isn't known
+pub const UNKNOWN_COUNTRY: &str = "Unknown";
+
 /// Tax summary for a specific country
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CountryTaxSummary {
@@ -58,6 +98,16 @@ pub struct CountryTaxSummary {
     pub net_income: Decimal,
 }
 
+/// Foreign dividend income from one symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignSymbolSummary {
+    pub symbol: String,
+    pub country: String,
+    pub payments: usize,
+    pub dividend_income: Decimal,
+    pub withholding_tax: Decimal,
+}
+
 /// Tax summary for a specific stock symbol
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolTaxSummary {
@@ -81,6 +131,25 @@ pub struct SymbolTaxSummary {
     pub last_payment: Option<NaiveDate>,
 }
 
+/// Income paid into one account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountTaxSummary {
+    /// Account name
+    pub account: String,
+    /// Taxable, traditional IRA or Roth
+    pub kind: AccountKind,
+    /// Dividend and interest income paid into the account
+    pub total_income: Decimal,
+    /// Qualified dividend amount, unknown classifications included
+    pub qualified_amount: Decimal,
+    /// Non-qualified dividend amount
+    pub non_qualified_amount: Decimal,
+    /// Interest income
+    pub interest_income: Decimal,
+    /// Number of payments
+    pub payment_count: usize,
+}
+
 /// Tax lot summary for cost basis tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaxLotSummary {
@@ -90,7 +159,7 @@ pub struct TaxLotSummary {
     pub symbol: String,
     /// Total dividend income from this lot
     pub dividend_income: Decimal,
-    /// Number of shares in the lot
+    /// Shares left in the lot at the last ex-date of the year
     pub shares: Option<Decimal>,
     /// Purchase date of the lot
     pub purchase_date: Option<NaiveDate>,
@@ -105,6 +174,9 @@ pub struct EstimatedTax {
     pub qualified_tax: Decimal,
     /// Estimated tax on non-qualified dividends (ordinary rates)
     pub non_qualified_tax: Decimal,
+    /// Estimated tax on interest income (ordinary rates)
+    #[serde(default)]
+    pub interest_tax: Decimal,
     /// Total estimated tax
     pub total_estimated_tax: Decimal,
     /// Tax bracket used for ordinary income
@@ -153,6 +225,9 @@ pub struct Form1099DIV {
     pub payers: Vec<PayerInfo>,
     /// Summary totals
     pub summary: Form1099Summary,
+    /// Interest income for the year, reported on 1099-INT and left out of this report
+    #[serde(default)]
+    pub interest_income: Decimal,
 }
 
 /// Payer information for 1099-DIV
@@ -199,18 +274,56 @@ pub struct Form1099Summary {
 pub struct TaxAnalyzer;
 
 impl TaxAnalyzer {
-    /// Generate comprehensive tax summary for a given year
+    /// Share a dividend out over the holding's lots held at its ex-date, in
+    /// proportion to the shares left in them
+    ///
+    /// The last lot takes the rounding remainder so the parts add up to the
+    /// payment.
+    fn split_across_lots(dividend: &Dividend, holding: &Holding) -> Vec<(String, Decimal)> {
+        let lots = holding.lots_entitled_on(dividend.ex_date);
+        let total_shares: Decimal = lots.iter().map(|(_, shares)| *shares).sum();
+        if total_shares.is_zero() {
+            return Vec::new();
+        }
+
+        let mut remaining = dividend.total_amount;
+        let mut parts = Vec::with_capacity(lots.len());
+        for (index, (lot, shares)) in lots.iter().enumerate() {
+            let income = if index + 1 == lots.len() {
+                remaining
+            } else {
+                (dividend.total_amount * shares / total_shares).round_dp(2)
+            };
+            remaining -= income;
+            parts.push((lot.id.clone(), income));
+        }
+        parts
+    }
+
+    /// Generate comprehensive tax summary for a given year, treating every account as taxable
     pub fn generate_tax_summary(
         tracker: &DividendTracker,
         tax_year: i32,
         tax_assumptions: Option<TaxAssumptions>,
+    ) -> Result<TaxSummary> {
+        Self::generate_tax_summary_for_accounts(tracker, tax_year, tax_assumptions, &AccountSettings::default())
+    }
+
+    /// Generate a tax summary with `accounts` telling retirement accounts apart
+    ///
+    /// Income paid into a retirement account still counts in the totals, as
+    /// the broker reports it, but is left out of the estimated tax.
+    pub fn generate_tax_summary_for_accounts(
+        tracker: &DividendTracker,
+        tax_year: i32,
+        tax_assumptions: Option<TaxAssumptions>,
+        accounts: &AccountSettings,
     ) -> Result<TaxSummary> {
         // Filter dividends for the tax year (by pay date)
-        let tax_year_dividends: Vec<&Dividend> = tracker
-            .dividends
-            .iter()
-            .filter(|d| d.pay_date.year() == tax_year)
-            .collect();
+        let tax_year_dividends: Vec<&Dividend> = DividendFilter::new()
+            .date_basis(DateBasis::PayDate)
+            .year(tax_year)
+            .apply(&tracker.dividends);
 
         if tax_year_dividends.is_empty() {
             return Ok(TaxSummary {
@@ -225,8 +338,15 @@ impl TaxAnalyzer {
                     total_withholding_tax: dec!(0),
                     net_foreign_income: dec!(0),
                     by_country: HashMap::new(),
+                    by_symbol: Vec::new(),
                 },
+                total_fees: dec!(0),
+                total_withholding: dec!(0),
+                interest_income: dec!(0),
+                interest_by_symbol: BTreeMap::new(),
                 by_symbol: HashMap::new(),
+                by_account: Vec::new(),
+                retirement_income: dec!(0),
                 tax_lots: Vec::new(),
                 estimated_tax: None,
             });
@@ -239,13 +359,82 @@ impl TaxAnalyzer {
         let mut tax_free_total = dec!(0);
         let mut foreign_total = dec!(0);
         let mut total_withholding = dec!(0);
+        let mut total_fees = dec!(0);
+        let mut withheld_at_source = dec!(0);
+        let mut interest_income = dec!(0);
+        let mut interest_by_symbol: BTreeMap<String, Decimal> = BTreeMap::new();
 
         let mut by_symbol: HashMap<String, SymbolTaxSummary> = HashMap::new();
+        let mut by_account: BTreeMap<String, AccountTaxSummary> = BTreeMap::new();
+        let mut by_country: HashMap<String, CountryTaxSummary> = HashMap::new();
+        let mut foreign_by_symbol: HashMap<String, ForeignSymbolSummary> = HashMap::new();
         let mut tax_lots: Vec<TaxLotSummary> = Vec::new();
 
         for dividend in &tax_year_dividends {
+            total_fees += dividend.fee_amount();
+            withheld_at_source += dividend.withholding_amount();
+
+            let classification = tracker.tax_classification(dividend);
+            let account = by_account
+                .entry(dividend.account_name().to_string())
+                .or_insert_with(|| AccountTaxSummary {
+                    account: dividend.account_name().to_string(),
+                    kind: accounts.kind(dividend.account_name()),
+                    total_income: dec!(0),
+                    qualified_amount: dec!(0),
+                    non_qualified_amount: dec!(0),
+                    interest_income: dec!(0),
+                    payment_count: 0,
+                });
+            account.total_income += dividend.total_amount;
+            account.payment_count += 1;
+            match (&dividend.income_kind, &classification) {
+                (IncomeKind::Interest, _) => account.interest_income += dividend.total_amount,
+                (_, TaxClassification::Qualified | TaxClassification::Unknown) => {
+                    account.qualified_amount += dividend.total_amount
+                }
+                (_, TaxClassification::NonQualified) => account.non_qualified_amount += dividend.total_amount,
+                _ => {}
+            }
+
+            // Interest belongs on 1099-INT, so it stays out of every dividend bucket
+            if dividend.income_kind == IncomeKind::Interest {
+                interest_income += dividend.total_amount;
+                *interest_by_symbol.entry(dividend.symbol.clone()).or_default() += dividend.total_amount;
+                continue;
+            }
+
+            // Anything paid from abroad counts towards the country breakdown,
+            // qualified ADRs included
+            let country = dividend.source_country().filter(|c| *c != "US");
+            if country.is_some() || classification == TaxClassification::Foreign {
+                let country = country.unwrap_or(UNKNOWN_COUNTRY).to_string();
+                let withholding = dividend.withholding_amount();
+                let by_this_country = by_country.entry(country.clone()).or_insert_with(|| CountryTaxSummary {
+                    country: country.clone(),
+                    dividend_income: dec!(0),
+                    withholding_tax: dec!(0),
+                    net_income: dec!(0),
+                });
+                by_this_country.dividend_income += dividend.total_amount;
+                by_this_country.withholding_tax += withholding;
+                by_this_country.net_income += dividend.total_amount - withholding;
+
+                let by_this_symbol =
+                    foreign_by_symbol.entry(dividend.symbol.clone()).or_insert_with(|| ForeignSymbolSummary {
+                        symbol: dividend.symbol.clone(),
+                        country,
+                        payments: 0,
+                        dividend_income: dec!(0),
+                        withholding_tax: dec!(0),
+                    });
+                by_this_symbol.payments += 1;
+                by_this_symbol.dividend_income += dividend.total_amount;
+                by_this_symbol.withholding_tax += withholding;
+            }
+
             // Add to appropriate total based on tax classification
-            match dividend.tax_classification {
+            match classification {
                 TaxClassification::Qualified => qualified_total += dividend.total_amount,
                 TaxClassification::NonQualified => non_qualified_total += dividend.total_amount,
                 TaxClassification::ReturnOfCapital => return_of_capital_total += dividend.total_amount,
@@ -265,7 +454,7 @@ impl TaxAnalyzer {
             // Update symbol summary
             let symbol_summary = by_symbol.entry(dividend.symbol.clone()).or_insert(SymbolTaxSummary {
                 symbol: dividend.symbol.clone(),
-                company_name: dividend.company_name.clone(),
+                company_name: tracker.company_name(dividend).map(str::to_string),
                 total_income: dec!(0),
                 qualified_amount: dec!(0),
                 non_qualified_amount: dec!(0),
@@ -287,7 +476,7 @@ impl TaxAnalyzer {
             }
 
             // Update classification amounts
-            match dividend.tax_classification {
+            match tracker.tax_classification(dividend) {
                 TaxClassification::Qualified => symbol_summary.qualified_amount += dividend.total_amount,
                 TaxClassification::NonQualified => symbol_summary.non_qualified_amount += dividend.total_amount,
                 TaxClassification::ReturnOfCapital => symbol_summary.return_of_capital_amount += dividend.total_amount,
@@ -295,39 +484,82 @@ impl TaxAnalyzer {
                 _ => {} // Other classifications don't go into these buckets
             }
 
-            // Handle tax lots if available
-            if let Some(tax_lot_id) = &dividend.tax_lot_id {
+            // Handle tax lots if available: an explicit lot ID wins, otherwise
+            // the income is shared out over the imported lots held at the ex-date
+            let holding = tracker.holding_for(dividend);
+            let attributed = match &dividend.tax_lot_id {
+                Some(tax_lot_id) => vec![(tax_lot_id.clone(), dividend.total_amount)],
+                None => holding.map(|h| Self::split_across_lots(dividend, h)).unwrap_or_default(),
+            };
+            for (tax_lot_id, income) in attributed {
                 // Check if we already have a summary for this tax lot
-                if let Some(existing_lot) = tax_lots.iter_mut().find(|lot| lot.tax_lot_id == *tax_lot_id) {
-                    existing_lot.dividend_income += dividend.total_amount;
+                if let Some(existing_lot) = tax_lots.iter_mut().find(|lot| lot.tax_lot_id == tax_lot_id) {
+                    existing_lot.dividend_income += income;
                 } else {
+                    let lot = holding.and_then(|h| h.lots.iter().find(|lot| lot.id == tax_lot_id));
                     tax_lots.push(TaxLotSummary {
-                        tax_lot_id: tax_lot_id.clone(),
+                        tax_lot_id,
                         symbol: dividend.symbol.clone(),
-                        dividend_income: dividend.total_amount,
-                        shares: None, // Would need additional data
-                        purchase_date: None, // Would need additional data
-                        cost_basis_per_share: None, // Would need additional data
+                        dividend_income: income,
+                        shares: None,
+                        purchase_date: lot.map(|lot| lot.purchase_date),
+                        cost_basis_per_share: lot.map(|lot| lot.price),
                     });
                 }
             }
         }
 
+        // Lot shares are those still held at the position's last ex-date of
+        // the year, after any sales taken from the lot
+        for lot_summary in &mut tax_lots {
+            let Some(holding) = tracker
+                .holdings_of(&lot_summary.symbol)
+                .into_iter()
+                .find(|h| h.lots.iter().any(|lot| lot.id == lot_summary.tax_lot_id))
+            else {
+                continue;
+            };
+            let key = holding.key();
+            let Some(last_ex_date) = tax_year_dividends
+                .iter()
+                .filter(|d| d.symbol == lot_summary.symbol && key.is_in(d.account_name()))
+                .map(|d| d.ex_date)
+                .max()
+            else {
+                continue;
+            };
+            let held = holding
+                .lots_entitled_on(last_ex_date)
+                .into_iter()
+                .find(|(lot, _)| lot.id == lot_summary.tax_lot_id)
+                .map_or(Decimal::ZERO, |(_, shares)| shares);
+            lot_summary.shares = Some(held);
+        }
+
         let total_dividend_income = qualified_total + non_qualified_total + return_of_capital_total + tax_free_total + foreign_total;
 
         // Create foreign dividend summary
+        let mut foreign_by_symbol: Vec<ForeignSymbolSummary> = foreign_by_symbol.into_values().collect();
+        foreign_by_symbol.sort_by(|a, b| b.dividend_income.cmp(&a.dividend_income).then_with(|| a.symbol.cmp(&b.symbol)));
         let foreign_dividends = ForeignDividendSummary {
             total_foreign_income: foreign_total,
             total_withholding_tax: total_withholding,
             net_foreign_income: foreign_total - total_withholding,
-            by_country: HashMap::new(), // Would need country data in dividends
+            by_country,
+            by_symbol: foreign_by_symbol,
         };
 
+        // Retirement accounts aren't taxed on the year's income
+        let by_account: Vec<AccountTaxSummary> = by_account.into_values().collect();
+        let retirement: Vec<&AccountTaxSummary> = by_account.iter().filter(|a| a.kind.is_retirement()).collect();
+        let retirement_income = retirement.iter().map(|a| a.total_income).sum();
+
         // Calculate estimated tax if assumptions provided
         let estimated_tax = if let Some(assumptions) = tax_assumptions {
             Some(Self::calculate_estimated_tax(
-                qualified_total,
-                non_qualified_total,
+                qualified_total - retirement.iter().map(|a| a.qualified_amount).sum::<Decimal>(),
+                non_qualified_total - retirement.iter().map(|a| a.non_qualified_amount).sum::<Decimal>(),
+                interest_income - retirement.iter().map(|a| a.interest_income).sum::<Decimal>(),
                 &assumptions,
             )?)
         } else {
@@ -342,29 +574,38 @@ impl TaxAnalyzer {
             return_of_capital: return_of_capital_total,
             tax_free_dividends: tax_free_total,
             foreign_dividends,
+            total_fees,
+            total_withholding: withheld_at_source,
+            interest_income,
+            interest_by_symbol,
             by_symbol,
+            by_account,
+            retirement_income,
             tax_lots,
             estimated_tax,
         })
     }
 
-    /// Calculate estimated tax based on dividend income and tax assumptions
+    /// Calculate estimated tax based on dividend and interest income and tax assumptions
     pub fn calculate_estimated_tax(
         qualified_amount: Decimal,
         non_qualified_amount: Decimal,
+        interest_amount: Decimal,
         assumptions: &TaxAssumptions,
     ) -> Result<EstimatedTax> {
         // Get tax rates based on assumptions
         let (ordinary_rate, capital_gains_rate) = Self::get_tax_rates(assumptions)?;
 
-        // Calculate taxes
+        // Calculate taxes; interest is ordinary income like non-qualified dividends
         let qualified_tax = qualified_amount * capital_gains_rate;
         let non_qualified_tax = non_qualified_amount * ordinary_rate;
-        let total_estimated_tax = qualified_tax + non_qualified_tax;
+        let interest_tax = interest_amount * ordinary_rate;
+        let total_estimated_tax = qualified_tax + non_qualified_tax + interest_tax;
 
         Ok(EstimatedTax {
             qualified_tax,
             non_qualified_tax,
+            interest_tax,
             total_estimated_tax,
             ordinary_tax_bracket: ordinary_rate,
             capital_gains_rate,
@@ -414,6 +655,12 @@ impl TaxAnalyzer {
             let payer_name = symbol_summary.company_name.clone()
                 .unwrap_or_else(|| format!("{} Corporation", symbol));
 
+            let foreign = tax_summary
+                .foreign_dividends
+                .by_symbol
+                .iter()
+                .find(|foreign| &foreign.symbol == symbol);
+
             let payer = PayerInfo {
                 payer_name,
                 symbols: vec![symbol.clone()],
@@ -422,8 +669,8 @@ impl TaxAnalyzer {
                 capital_gain_distributions: dec!(0), // Would need separate tracking
                 non_dividend_distributions: symbol_summary.return_of_capital_amount,
                 federal_tax_withheld: dec!(0), // Would need separate tracking
-                foreign_tax_paid: dec!(0), // Would need foreign dividend details
-                foreign_country: None,
+                foreign_tax_paid: foreign.map(|f| f.withholding_tax).unwrap_or(dec!(0)),
+                foreign_country: foreign.map(|f| f.country.clone()),
             };
 
             payers.push(payer);
@@ -436,26 +683,26 @@ impl TaxAnalyzer {
             total_capital_gain_distributions: dec!(0),
             total_non_dividend_distributions: tax_summary.return_of_capital,
             total_federal_tax_withheld: dec!(0),
-            total_foreign_tax_paid: tax_summary.foreign_dividends.total_withholding_tax,
+            total_foreign_tax_paid: tax_summary.foreign_dividends.by_country.values().map(|c| c.withholding_tax).sum(),
         };
 
         Ok(Form1099DIV {
             tax_year,
             payers,
             summary,
+            interest_income: tax_summary.interest_income,
         })
     }
 
     /// Export tax summary to CSV format
-    pub fn export_tax_summary_csv(summary: &TaxSummary, file_path: &str) -> Result<()> {
-        use std::fs::File;
+    pub fn export_tax_summary_csv(summary: &TaxSummary, file_path: &Path) -> Result<()> {
         use std::io::Write;
 
-        let mut file = File::create(file_path)?;
+        let mut file = paths::create_file(file_path)?;
 
         // Write header
         writeln!(file, "Tax Year,{}", summary.tax_year)?;
-        writeln!(file, "")?;
+        writeln!(file)?;
 
         // Write summary section
         writeln!(file, "Summary")?;
@@ -466,7 +713,8 @@ impl TaxAnalyzer {
         writeln!(file, "Return of Capital,{}", summary.return_of_capital)?;
         writeln!(file, "Tax-Free Dividends,{}", summary.tax_free_dividends)?;
         writeln!(file, "Foreign Dividends,{}", summary.foreign_dividends.total_foreign_income)?;
-        writeln!(file, "")?;
+        writeln!(file, "Interest Income (1099-INT),{}", summary.interest_income)?;
+        writeln!(file)?;
 
         // Write by-symbol breakdown
         writeln!(file, "By Symbol")?;
@@ -486,12 +734,26 @@ impl TaxAnalyzer {
             )?;
         }
 
+        if !summary.foreign_dividends.by_country.is_empty() {
+            writeln!(file)?;
+            writeln!(file, "Foreign Income by Country")?;
+            writeln!(file, "Country,Income,Withholding Tax,Net Income")?;
+            for country in summary.foreign_dividends.countries() {
+                writeln!(
+                    file,
+                    "{},{},{},{}",
+                    country.country, country.dividend_income, country.withholding_tax, country.net_income
+                )?;
+            }
+        }
+
         if let Some(estimated_tax) = &summary.estimated_tax {
-            writeln!(file, "")?;
+            writeln!(file)?;
             writeln!(file, "Estimated Tax")?;
             writeln!(file, "Tax Type,Amount")?;
             writeln!(file, "Tax on Qualified Dividends,{}", estimated_tax.qualified_tax)?;
             writeln!(file, "Tax on Non-Qualified Dividends,{}", estimated_tax.non_qualified_tax)?;
+            writeln!(file, "Tax on Interest,{}", estimated_tax.interest_tax)?;
             writeln!(file, "Total Estimated Tax,{}", estimated_tax.total_estimated_tax)?;
         }
 
@@ -499,15 +761,14 @@ impl TaxAnalyzer {
     }
 
     /// Export 1099-DIV report to CSV format
-    pub fn export_1099_div_csv(report: &Form1099DIV, file_path: &str) -> Result<()> {
-        use std::fs::File;
+    pub fn export_1099_div_csv(report: &Form1099DIV, file_path: &Path) -> Result<()> {
         use std::io::Write;
 
-        let mut file = File::create(file_path)?;
+        let mut file = paths::create_file(file_path)?;
 
         // Write header
         writeln!(file, "1099-DIV Tax Report for {}", report.tax_year)?;
-        writeln!(file, "")?;
+        writeln!(file)?;
 
         // Write summary
         writeln!(file, "Summary Totals")?;
@@ -517,7 +778,7 @@ impl TaxAnalyzer {
         writeln!(file, "Box 3 - Non-dividend Distributions,{}", report.summary.total_non_dividend_distributions)?;
         writeln!(file, "Box 4 - Federal Income Tax Withheld,{}", report.summary.total_federal_tax_withheld)?;
         writeln!(file, "Box 6 - Foreign Tax Paid,{}", report.summary.total_foreign_tax_paid)?;
-        writeln!(file, "")?;
+        writeln!(file)?;
 
         // Write payer details
         writeln!(file, "Payer Details")?;
@@ -0,0 +1,157 @@
+//! API tokens for `serve`, kept in `tokens.toml`
+//!
+//! Each token has a scope: `read` tokens can use the GET endpoints, `write`
+//! tokens can also add records. Once any token exists, every request to the
+//! API has to carry one as `Authorization: Bearer <token>`. The file sits
+//! next to `config.toml` and is only readable by its owner.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::paths;
+
+/// What a token lets its holder do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    Read,
+    Write,
+}
+
+impl TokenScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Write => "write",
+        }
+    }
+
+    /// Whether a token of this scope may do what `needed` requires
+    pub fn allows(&self, needed: TokenScope) -> bool {
+        *self >= needed
+    }
+}
+
+/// One named token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub name: String,
+    pub scope: TokenScope,
+    pub token: String,
+    pub created: NaiveDate,
+}
+
+/// Every configured token
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiTokens {
+    #[serde(default, rename = "token")]
+    pub tokens: Vec<ApiToken>,
+}
+
+impl ApiTokens {
+    /// Path of `tokens.toml`
+    pub fn file() -> Result<PathBuf> {
+        Ok(paths::config_dir()?.join("tokens.toml"))
+    }
+
+    /// Load `tokens.toml`; no file means no tokens
+    pub fn load() -> Result<Self> {
+        let file = Self::file()?;
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&file)?;
+        toml::from_str(&contents).with_context(|| format!("{} is invalid", file.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = Self::file()?;
+        let mut out = paths::create_file(&file)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            out.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        std::io::Write::write_all(&mut out, toml::to_string(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Create a token; without a name it is called after its scope, e.g. `read-2`
+    pub fn add(&mut self, name: Option<String>, scope: TokenScope, today: NaiveDate) -> Result<&ApiToken> {
+        let name = match name {
+            Some(name) if name.trim().is_empty() => bail!("Token name cannot be empty"),
+            Some(name) => name.trim().to_string(),
+            None => (1..)
+                .map(|n| format!("{}-{}", scope.name(), n))
+                .find(|name| self.get(name).is_none())
+                .unwrap_or_default(),
+        };
+        if self.get(&name).is_some() {
+            bail!("A token named '{}' already exists", name);
+        }
+        self.tokens.push(ApiToken {
+            name,
+            scope,
+            token: format!("dt_{}", Uuid::new_v4().simple()),
+            created: today,
+        });
+        Ok(&self.tokens[self.tokens.len() - 1])
+    }
+
+    /// Remove a token by name; `false` if there is none
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.tokens.len();
+        self.tokens.retain(|t| t.name != name);
+        self.tokens.len() != before
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ApiToken> {
+        self.tokens.iter().find(|t| t.name == name)
+    }
+
+    /// Scope of the token a client presented, if it is one of ours
+    pub fn scope_of(&self, given: &str) -> Option<TokenScope> {
+        self.tokens.iter().find(|t| same_token(given, &t.token)).map(|t| t.scope)
+    }
+}
+
+/// Compare tokens in time independent of where they first differ
+pub fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_names_and_scopes() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let mut tokens = ApiTokens::default();
+        let read = tokens.add(None, TokenScope::Read, today).unwrap().token.clone();
+        tokens.add(Some("automation".to_string()), TokenScope::Write, today).unwrap();
+        tokens.add(None, TokenScope::Read, today).unwrap();
+
+        assert_eq!(tokens.get("read-2").unwrap().scope, TokenScope::Read);
+        assert!(tokens.add(Some("automation".to_string()), TokenScope::Read, today).is_err());
+        assert!(read.starts_with("dt_"));
+        assert_eq!(tokens.scope_of(&read), Some(TokenScope::Read));
+        assert_eq!(tokens.scope_of("dt_unknown"), None);
+        assert!(TokenScope::Write.allows(TokenScope::Read));
+        assert!(!TokenScope::Read.allows(TokenScope::Write));
+
+        let saved: ApiTokens = toml::from_str(&toml::to_string(&tokens).unwrap()).unwrap();
+        assert_eq!(saved, tokens);
+        assert!(tokens.remove("read-1"));
+        assert!(!tokens.remove("read-1"));
+        assert_eq!(tokens.scope_of(&read), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! Yield on cost over time, from purchase lots
+//!
+//! Yield on cost is a year's regular dividends per share divided by the price
+//! paid for the shares. The price is fixed once bought, so a growing dividend
+//! shows up as a rising yield on cost year after year: the compounding a
+//! dividend-growth strategy relies on.
+//!
+//! Each calendar year counts the shares held all year: lots bought before it
+//! began, less the shares sold from them by its end (oldest lots first). A
+//! purchase joins the history in its first full year of ownership and a sale
+//! leaves it from the year it was made. Only complete years
+//! up to the last one with recorded payments are included; the current one
+//! would read as a cut. Special dividends are left out, as they say nothing
+//! about the dividend's growth.
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::analytics::DividendAnalytics;
+use crate::models::{DividendTracker, DividendType, TaxLot};
+use crate::periods::PaymentPeriods;
+
+/// One position in one year
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldOnCostYear {
+    pub year: i32,
+    /// Regular dividends per share with an ex-date in the year
+    pub dividends_per_share: Decimal,
+    /// Shares from lots bought before the year began and held all year
+    pub shares: Decimal,
+    /// Price paid for those shares
+    pub cost: Decimal,
+    /// Dividends those shares earned: per-share dividends times shares
+    pub income: Decimal,
+    /// Income over cost, in percent
+    pub yield_on_cost: Decimal,
+    /// Yield on cost of the first lot alone, which no later purchase dilutes
+    pub first_lot_yield: Decimal,
+}
+
+/// Yield on cost of one position, year by year
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionYieldOnCost {
+    pub symbol: String,
+    pub account: Option<String>,
+    pub first_purchase: NaiveDate,
+    /// Complete years since the first purchase, oldest first
+    pub years: Vec<YieldOnCostYear>,
+    /// Compound annual growth of the dividend per share (%) over those years
+    pub per_share_growth: Option<Decimal>,
+}
+
+/// The whole portfolio's yield on cost in one year
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioYieldOnCost {
+    pub year: i32,
+    pub cost: Decimal,
+    pub income: Decimal,
+    /// Income over cost, in percent
+    pub yield_on_cost: Decimal,
+}
+
+/// Yield-on-cost history of every position with purchase lots
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldOnCostHistory {
+    /// Positions with at least one complete year, by symbol
+    pub positions: Vec<PositionYieldOnCost>,
+    /// All positions together, oldest year first
+    pub portfolio: Vec<PortfolioYieldOnCost>,
+    /// Open holdings without purchase lots, which can't be included
+    pub without_lots: Vec<String>,
+}
+
+impl YieldOnCostHistory {
+    /// Work out the history up to the last complete year before `today`
+    ///
+    /// `symbol` limits it to one position. `drift_days` is how far a payment
+    /// may slip across a year boundary and still count in its usual year.
+    pub fn calculate(tracker: &DividendTracker, symbol: Option<&str>, drift_days: u32, today: NaiveDate) -> Self {
+        let periods = PaymentPeriods::build(&tracker.dividends, drift_days);
+
+        let mut positions = Vec::new();
+        let mut without_lots = Vec::new();
+        let mut holdings: Vec<_> = tracker
+            .holdings
+            .values()
+            .filter(|h| !h.is_closed() && symbol.is_none_or(|s| h.symbol == s))
+            .collect();
+        holdings.sort_by_key(|h| h.key());
+
+        for holding in holdings {
+            let key = holding.key();
+            let mut lots: Vec<&TaxLot> = holding.lots.iter().collect();
+            lots.sort_by_key(|lot| lot.purchase_date);
+            let Some(first_lot) = lots.first().copied() else {
+                if holding.shares > Decimal::ZERO {
+                    without_lots.push(holding.symbol.clone());
+                }
+                continue;
+            };
+
+            let mut per_share: BTreeMap<i32, Decimal> = BTreeMap::new();
+            for dividend in tracker
+                .dividends
+                .iter()
+                .filter(|d| d.symbol == holding.symbol && key.is_in(d.account_name()))
+                .filter(|d| d.dividend_type == DividendType::Regular)
+            {
+                *per_share.entry(periods.year(dividend)).or_default() += dividend.amount_per_share;
+            }
+
+            // Stop at the last year with recorded payments rather than show
+            // years nobody has entered yet as cuts
+            let Some(last_year) = per_share.keys().copied().filter(|&y| y < today.year()).max() else {
+                continue;
+            };
+            let years: Vec<YieldOnCostYear> = (first_lot.purchase_date.year() + 1..=last_year)
+                .filter_map(|year| {
+                    let start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                    let end = NaiveDate::from_ymd_opt(year + 1, 1, 1)?;
+                    let held: Vec<(&TaxLot, Decimal)> = holding
+                        .lot_shares_on(end)
+                        .into_iter()
+                        .filter(|(lot, _)| lot.purchase_date < start)
+                        .collect();
+                    let shares: Decimal = held.iter().map(|(_, shares)| *shares).sum();
+                    let cost: Decimal = held.iter().map(|(lot, shares)| shares * lot.price).sum();
+                    if cost <= Decimal::ZERO {
+                        return None;
+                    }
+                    let dividends_per_share = per_share.get(&year).copied().unwrap_or_default();
+                    let income = dividends_per_share * shares;
+                    Some(YieldOnCostYear {
+                        year,
+                        dividends_per_share,
+                        shares,
+                        cost,
+                        income,
+                        yield_on_cost: income / cost * Decimal::from(100),
+                        first_lot_yield: dividends_per_share / first_lot.price * Decimal::from(100),
+                    })
+                })
+                .collect();
+            if years.is_empty() {
+                continue;
+            }
+
+            let series: Vec<(i32, Decimal)> = years.iter().map(|y| (y.year, y.dividends_per_share)).collect();
+            positions.push(PositionYieldOnCost {
+                symbol: holding.symbol.clone(),
+                account: holding.account.clone(),
+                first_purchase: first_lot.purchase_date,
+                per_share_growth: DividendAnalytics::compound_annual_growth(&series),
+                years,
+            });
+        }
+
+        let mut by_year: BTreeMap<i32, (Decimal, Decimal)> = BTreeMap::new();
+        for year in positions.iter().flat_map(|p| &p.years) {
+            let (cost, income) = by_year.entry(year.year).or_default();
+            *cost += year.cost;
+            *income += year.income;
+        }
+        let portfolio = by_year
+            .into_iter()
+            .map(|(year, (cost, income))| PortfolioYieldOnCost {
+                year,
+                cost,
+                income,
+                yield_on_cost: income / cost * Decimal::from(100),
+            })
+            .collect();
+
+        YieldOnCostHistory {
+            positions,
+            portfolio,
+            without_lots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dividend, Holding};
+    use rust_decimal_macros::dec;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn lot(purchase_date: &str, shares: Decimal, price: Decimal) -> TaxLot {
+        TaxLot {
+            id: format!("KO-{}", purchase_date),
+            purchase_date: date(purchase_date),
+            shares,
+            price,
+        }
+    }
+
+    #[test]
+    fn test_yield_on_cost_rises_with_the_dividend() {
+        let mut tracker = DividendTracker::new();
+        let mut ko = Holding::new("KO".to_string(), dec!(150), None, None).unwrap();
+        ko.lots = vec![lot("2020-06-01", dec!(100), dec!(40)), lot("2022-03-01", dec!(50), dec!(60))];
+        tracker.add_holding(ko);
+        tracker.add_holding(Holding::new("O".to_string(), dec!(10), None, None).unwrap());
+        for (ex_date, amount, kind) in [
+            ("2021-06-14", dec!(1.60), DividendType::Regular),
+            ("2022-06-14", dec!(1.80), DividendType::Regular),
+            ("2023-06-14", dec!(2.00), DividendType::Regular),
+            ("2023-09-14", dec!(5.00), DividendType::Special),
+            ("2024-06-14", dec!(2.10), DividendType::Regular),
+        ] {
+            let ex_date = date(ex_date);
+            tracker.add_dividend(
+                Dividend::new("KO".to_string(), None, ex_date, ex_date, amount, dec!(100), kind).unwrap(),
+            );
+        }
+
+        let history = YieldOnCostHistory::calculate(&tracker, None, 7, date("2024-10-01"));
+        assert_eq!(history.without_lots, vec!["O"]);
+        let ko = &history.positions[0];
+        let years: Vec<_> = ko.years.iter().map(|y| (y.year, y.shares, y.yield_on_cost)).collect();
+        assert_eq!(
+            years,
+            vec![
+                (2021, dec!(100), dec!(4.0)),
+                (2022, dec!(100), dec!(4.5)),
+                // The 2022 lot joins in its first full year, at a higher price
+                (2023, dec!(150), dec!(300) / dec!(7000) * dec!(100)),
+            ]
+        );
+        assert_eq!(ko.years[2].first_lot_yield, dec!(5.0));
+        assert_eq!(ko.per_share_growth, Some(dec!(11.8)));
+        assert_eq!(history.portfolio.len(), 3);
+        assert_eq!(history.portfolio[1].income, dec!(180));
+
+        let none = YieldOnCostHistory::calculate(&tracker, Some("O"), 7, date("2024-10-01"));
+        assert!(none.positions.is_empty());
+    }
+}
@@ -1,17 +1,131 @@
-use std::env;
-use std::path::PathBuf;
-
-/// Set up test environment with a temporary data directory
-pub fn setup_test_env() -> PathBuf {
-    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
-    let path = temp_dir.into_path();
-    env::set_var("DIVIDEND_TRACKER_DATA_DIR", &path);
-    path
+//! Shared helpers for the CLI integration tests
+//!
+//! Every test runs the cargo-built binary (located by assert_cmd, so it works
+//! on any target directory and on Windows) against its own temporary data
+//! directory. Fixture setup asserts that each command succeeds: a broken setup
+//! step fails the test instead of silently leaving it with less data.
+
+// Each test crate uses a different subset of these helpers
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+pub use predicates::str::contains;
+
+/// A command for the binary that touches no data (help, argument errors)
+pub fn bin() -> Command {
+    let mut cmd = Command::cargo_bin("dividend-tracker").expect("dividend-tracker binary should be built");
+    cmd.env("NO_COLOR", "1");
+    cmd
+}
+
+/// An isolated data directory for one test, removed when dropped
+pub struct TestEnv {
+    dir: TempDir,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().expect("failed to create temporary data directory"),
+        }
+    }
+
+    /// The data directory passed to the binary
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Path of a file inside the data directory
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    /// A command for the binary bound to this environment's data directory
+    pub fn cmd(&self) -> Command {
+        let mut cmd = bin();
+        cmd.env("DIVIDEND_TRACKER_DATA_DIR", self.path());
+        cmd
+    }
+
+    /// Run a command that must succeed and return its stdout
+    pub fn run_ok(&self, args: &[&str]) -> String {
+        let output = self.cmd().args(args).output().expect("failed to run dividend-tracker");
+        assert!(
+            output.status.success(),
+            "`dividend-tracker {}` failed:\n{}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+}
+
+/// Builder for the tracker data a test starts from
+///
+/// Records are added through the CLI itself so fixtures always match the
+/// current storage format.
+#[derive(Default)]
+pub struct Fixture {
+    commands: Vec<Vec<String>>,
 }
 
-/// Clean up test environment
-pub fn cleanup_test_env(path: PathBuf) {
-    if path.exists() {
-        std::fs::remove_dir_all(path).ok();
+impl Fixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A holding without cost basis
+    pub fn holding(self, symbol: &str, shares: &str) -> Self {
+        self.command(&["holdings", "add", symbol, "--shares", shares])
+    }
+
+    /// A holding with an average cost basis per share
+    pub fn holding_with_cost(self, symbol: &str, shares: &str, cost_basis: &str) -> Self {
+        self.command(&[
+            "holdings",
+            "add",
+            symbol,
+            "--shares",
+            shares,
+            "--cost-basis",
+            cost_basis,
+        ])
     }
-}
\ No newline at end of file
+
+    /// A dividend payment; duplicates are forced so fixtures can repeat ex-dates
+    pub fn dividend(self, symbol: &str, ex_date: &str, pay_date: &str, amount: &str, shares: &str) -> Self {
+        self.command(&[
+            "div",
+            "add",
+            symbol,
+            "--ex-date",
+            ex_date,
+            "--pay-date",
+            pay_date,
+            "--amount",
+            amount,
+            "--shares",
+            shares,
+            "--force",
+        ])
+    }
+
+    /// Any other setup command, e.g. a tax classification
+    pub fn command(mut self, args: &[&str]) -> Self {
+        self.commands.push(args.iter().map(|arg| arg.to_string()).collect());
+        self
+    }
+
+    /// Create a fresh environment and apply the fixture to it
+    pub fn build(self) -> TestEnv {
+        let env = TestEnv::new();
+        for args in &self.commands {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            env.run_ok(&args);
+        }
+        env
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_actions_apply_previews_then_applies_once() {
+    let env = Fixture::new()
+        .holding_with_cost("AAPL", "10", "400.00")
+        .holding("FB", "5")
+        .dividend("AAPL", "2020-08-07", "2020-08-13", "0.82", "10")
+        .build();
+    let feed = env.file("actions.csv");
+    fs::write(
+        &feed,
+        "action,symbol,date,ratio,new_symbol,amount,pay_date\n\
+         split,AAPL,2020-08-31,4:1,,,\n\
+         symbol_change,FB,2022-06-09,,META,,\n\
+         special_dividend,COST,2023-12-28,,,15.00,2024-01-12\n",
+    )
+    .unwrap();
+    let feed = feed.to_str().unwrap();
+
+    env.cmd()
+        .args(["actions", "apply", feed, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("COST special dividend $15.00 ex 2023-12-28 (not held)"))
+        .stdout(contains("- shares: 10"))
+        .stdout(contains("+ shares: 40"))
+        .stdout(contains("Dry run: 2 actions not applied."));
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("META").not());
+
+    env.cmd()
+        .args(["actions", "apply", feed, "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Applied 2 corporate actions"));
+    env.cmd()
+        .args(["show", "FB"])
+        .assert()
+        .success()
+        .stdout(contains("META"));
+
+    env.cmd()
+        .args(["actions", "apply", feed, "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("AAPL split 4:1 on 2020-08-31 (already applied)"))
+        .stdout(contains("No changes to apply."));
+    env.cmd()
+        .args(["actions", "list"])
+        .assert()
+        .success()
+        .stdout(contains("FB renamed to META on 2022-06-09"));
+}
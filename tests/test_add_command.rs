@@ -1,53 +1,71 @@
-use anyhow::Result;
+mod common;
+
 use chrono::{Duration, Local};
-use tempfile::tempdir;
+use common::*;
+use predicates::prelude::*;
+
+const AAPL_DIVIDEND: [&str; 9] = [
+    "AAPL",
+    "--ex-date",
+    "2024-01-15",
+    "--pay-date",
+    "2024-01-18",
+    "--amount",
+    "0.24",
+    "--shares",
+    "100",
+];
 
 #[test]
-fn test_add_dividend_basic() -> Result<()> {
-    // Setup test environment
-    let temp_dir = tempdir()?;
-    let _data_file = temp_dir.path().join("dividends.json");
-
-    // Run add command
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "add",
-            "AAPL",
-            "--ex-date",
-            "2024-01-15",
-            "--pay-date",
-            "2024-01-18",
-            "--amount",
-            "0.24",
-            "--shares",
-            "100",
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Adding dividend record"));
-    assert!(stdout.contains("Dividend Details"));
-    assert!(stdout.contains("Symbol: AAPL"));
-    assert!(stdout.contains("Ex-date: 2024-01-15"));
-    assert!(stdout.contains("Pay-date: 2024-01-18"));
-    assert!(stdout.contains("Amount per share: $0.2400"));
-    assert!(stdout.contains("Shares owned: 100"));
-    assert!(stdout.contains("Total dividend: $24"));
-    assert!(stdout.contains("Dividend record added successfully"));
-
-    Ok(())
+fn test_add_dividend_basic() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .assert()
+        .success()
+        .stdout(contains("Adding dividend record"))
+        .stdout(contains("Dividend Details"))
+        .stdout(contains("Symbol: AAPL"))
+        .stdout(contains("Ex-date: 2024-01-15"))
+        .stdout(contains("Pay-date: 2024-01-18"))
+        .stdout(contains("Amount per share: $0.2400"))
+        .stdout(contains("Shares owned: 100"))
+        .stdout(contains("Total dividend: $24"))
+        .stdout(contains("Dividend record added successfully"));
 }
 
 #[test]
-fn test_add_dividend_with_natural_language_dates() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_command_paths() {
+    let env = TestEnv::new();
+
+    // Current path: no deprecation warning
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .assert()
+        .success()
+        .stderr(contains("deprecated").not());
+
+    // Legacy path still works but warns
+    env.cmd()
+        .arg("add")
+        .args(AAPL_DIVIDEND)
+        .arg("--force")
+        .assert()
+        .success()
+        .stderr(contains("'add' is deprecated, use 'div add' instead"));
+}
 
-    // Test "tomorrow" date parsing
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+#[test]
+fn test_add_dividend_with_natural_language_dates() {
+    let env = TestEnv::new();
+    let tomorrow = (Local::now() + Duration::days(1)).naive_local().date();
+
+    env.cmd()
+        .args([
+            "div",
             "add",
             "MSFT",
             "--ex-date",
@@ -59,99 +77,38 @@ fn test_add_dividend_with_natural_language_dates() -> Result<()> {
             "--shares",
             "50",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Dividend record added successfully"));
-
-    // Verify the dates were parsed correctly by checking output
-    let tomorrow = (Local::now() + Duration::days(1)).naive_local().date();
-    assert!(stdout.contains(&tomorrow.format("%Y-%m-%d").to_string()));
-
-    Ok(())
+        .assert()
+        .success()
+        .stdout(contains("Dividend record added successfully"))
+        .stdout(contains(tomorrow.format("%Y-%m-%d").to_string()));
 }
 
 #[test]
-fn test_add_dividend_duplicate_detection() -> Result<()> {
-    let temp_dir = tempdir()?;
-
-    // Add first dividend
-    let output1 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "add",
-            "AAPL",
-            "--ex-date",
-            "2024-01-15",
-            "--pay-date",
-            "2024-01-18",
-            "--amount",
-            "0.24",
-            "--shares",
-            "100",
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output1.status.success());
-
-    // Try to add duplicate (same symbol and ex-date)
-    let output2 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "add",
-            "AAPL",
-            "--ex-date",
-            "2024-01-15",
-            "--pay-date",
-            "2024-01-18",
-            "--amount",
-            "0.24",
-            "--shares",
-            "100",
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output2.status.success(), "Duplicate should be rejected");
-
-    let stderr = String::from_utf8_lossy(&output2.stderr);
-    assert!(stderr.contains("Duplicate dividend exists"));
-
-    let stdout = String::from_utf8_lossy(&output2.stdout);
-    assert!(stdout.contains("Duplicate dividend found"));
-    assert!(stdout.contains("Use --force to override"));
-
-    Ok(())
+fn test_add_dividend_duplicate_detection() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .build();
+
+    // Same symbol and ex-date without --force
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .assert()
+        .failure()
+        .stderr(contains("Duplicate dividend exists"))
+        .stdout(contains("Duplicate dividend found"))
+        .stdout(contains("Use --force to override"));
 }
 
 #[test]
-fn test_add_dividend_force_flag() -> Result<()> {
-    let temp_dir = tempdir()?;
-
-    // Add first dividend
-    let output1 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "add",
-            "AAPL",
-            "--ex-date",
-            "2024-01-15",
-            "--pay-date",
-            "2024-01-18",
-            "--amount",
-            "0.24",
-            "--shares",
-            "100",
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output1.status.success());
-
-    // Add duplicate with --force flag
-    let output2 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+fn test_add_dividend_force_flag() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .build();
+
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
@@ -164,23 +121,18 @@ fn test_add_dividend_force_flag() -> Result<()> {
             "100",
             "--force",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output2.status.success(), "Force flag should allow duplicate");
-
-    let stdout = String::from_utf8_lossy(&output2.stdout);
-    assert!(stdout.contains("Dividend record added successfully"));
-
-    Ok(())
+        .assert()
+        .success()
+        .stdout(contains("Dividend record added successfully"));
 }
 
 #[test]
-fn test_add_dividend_invalid_amount() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_invalid_amount() {
+    let env = TestEnv::new();
 
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
@@ -192,23 +144,18 @@ fn test_add_dividend_invalid_amount() -> Result<()> {
             "--shares",
             "100",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid amount should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid amount format"));
-
-    Ok(())
+        .assert()
+        .failure()
+        .stderr(contains("Invalid amount format"));
 }
 
 #[test]
-fn test_add_dividend_invalid_shares() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_invalid_shares() {
+    let env = TestEnv::new();
 
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
@@ -220,23 +167,18 @@ fn test_add_dividend_invalid_shares() -> Result<()> {
             "--shares",
             "invalid",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid shares should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid shares format"));
-
-    Ok(())
+        .assert()
+        .failure()
+        .stderr(contains("Invalid shares format"));
 }
 
 #[test]
-fn test_add_dividend_invalid_date_format() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_invalid_date_format() {
+    let env = TestEnv::new();
 
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
@@ -248,69 +190,33 @@ fn test_add_dividend_invalid_date_format() -> Result<()> {
             "--shares",
             "100",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid date should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid date format"));
-
-    Ok(())
+        .assert()
+        .failure()
+        .stderr(contains("Invalid date format"));
 }
 
 #[test]
-fn test_add_dividend_validation_against_holdings() -> Result<()> {
-    let temp_dir = tempdir()?;
-
-    // First add a holding for AAPL
-    let output1 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "holdings",
-            "add",
-            "AAPL",
-            "--shares",
-            "50",
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output1.status.success());
-
-    // Now add a dividend with more shares than holdings
-    let output2 = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
-            "add",
-            "AAPL",
-            "--ex-date",
-            "2024-01-15",
-            "--pay-date",
-            "2024-01-18",
-            "--amount",
-            "0.24",
-            "--shares",
-            "100", // More than the 50 shares in holdings
-        ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    // Should still succeed but show a warning
-    assert!(output2.status.success());
-
-    let stdout = String::from_utf8_lossy(&output2.stdout);
-    assert!(stdout.contains("Validating against holdings"));
-    assert!(stdout.contains("Warning: Dividend shares (100) exceed current holdings (50)"));
-    assert!(stdout.contains("Dividend record added successfully"));
-
-    Ok(())
+fn test_add_dividend_validation_against_holdings() {
+    let env = Fixture::new().holding("AAPL", "50").build();
+
+    // More shares than the 50 in holdings: warns but still succeeds
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .assert()
+        .success()
+        .stdout(contains("Validating against holdings"))
+        .stdout(contains("Warning: Dividend shares (100) exceed current holdings (50)"))
+        .stdout(contains("Dividend record added successfully"));
 }
 
 #[test]
-fn test_add_dividend_with_decimal_shares() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_with_decimal_shares() {
+    let env = TestEnv::new();
 
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
@@ -320,46 +226,173 @@ fn test_add_dividend_with_decimal_shares() -> Result<()> {
             "--amount",
             "0.24",
             "--shares",
-            "100.5", // Fractional shares
+            "100.5",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Decimal shares should be accepted");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Shares owned: 100.5"));
-    assert!(stdout.contains("Total dividend: $24")); // 0.24 * 100.5 = 24.12 but displayed as $24
-    assert!(stdout.contains("Dividend record added successfully"));
-
-    Ok(())
+        .assert()
+        .success()
+        .stdout(contains("Shares owned: 100.5"))
+        .stdout(contains("Total dividend: $24")) // 0.24 * 100.5 = 24.12
+        .stdout(contains("Dividend record added successfully"));
 }
 
 #[test]
-fn test_add_dividend_date_validation() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_add_dividend_date_validation() {
+    let env = TestEnv::new();
 
-    // Try to add dividend with pay date before ex-date
-    let output = std::process::Command::new("./target/debug/dividend-tracker")
-        .args(&[
+    // Pay date before ex-date
+    env.cmd()
+        .args([
+            "div",
             "add",
             "AAPL",
             "--ex-date",
             "2024-01-18",
             "--pay-date",
-            "2024-01-15", // Before ex-date
+            "2024-01-15",
             "--amount",
             "0.24",
             "--shares",
             "100",
         ])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+        .assert()
+        .failure()
+        .stderr(contains("Pay date cannot be before ex-dividend date"));
+}
+
+#[test]
+fn test_add_dividend_with_fee() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .args(["--fee", "0.50"])
+        .assert()
+        .success()
+        .stdout(contains("Total dividend: $24"))
+        .stdout(contains("Fees: $0.50 (net $23.50)"));
+
+    env.cmd()
+        .args(["div", "add"])
+        .args(AAPL_DIVIDEND)
+        .args(["--fee", "30", "--force"])
+        .assert()
+        .failure()
+        .stderr(contains("Fees ($30) cannot exceed the dividend total ($24"));
+}
+
+#[test]
+fn test_add_dividend_with_withholding() {
+    let env = TestEnv::new();
+    let nestle = ["NESN", "--ex-date", "2024-04-26", "--pay-date", "2024-04-30", "--amount", "3.00", "--shares", "100"];
+
+    env.cmd()
+        .args(["div", "add"])
+        .args(nestle)
+        .args(["--withholding", "35%", "--country", "ch"])
+        .assert()
+        .success()
+        .stdout(contains("Withholding tax: $105.00 to CH (net $195.00)"));
+
+    env.cmd()
+        .args(["div", "add"])
+        .args(nestle)
+        .args(["--withholding", "301", "--force"])
+        .assert()
+        .failure()
+        .stderr(contains("Withholding ($301) cannot exceed the dividend after fees ($300"));
+    env.cmd()
+        .args(["div", "add"])
+        .args(nestle)
+        .args(["--country", "Switzerland", "--force"])
+        .assert()
+        .failure()
+        .stderr(contains("Use a two-letter code"));
+}
 
-    assert!(!output.status.success(), "Pay date before ex-date should fail");
+#[test]
+fn test_add_dividend_publishes_event() {
+    let env = TestEnv::new();
+    env.cmd().args(["div", "add"]).args(AAPL_DIVIDEND).assert().success();
+    env.run_ok(&["holdings", "add", "AAPL", "--shares", "100"]);
+
+    let events = std::fs::read_to_string(env.file("events.jsonl")).unwrap();
+    assert_eq!(events.lines().count(), 1, "only the new dividend is an event");
+    assert!(events.contains("\"kind\":\"dividend_added\""));
+    assert!(events.contains("\"symbol\":\"AAPL\""));
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Pay date cannot be before ex-dividend date"));
+#[test]
+fn test_div_import_reads_an_export_and_skips_duplicates() {
+    let source = Fixture::new()
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "50")
+        .build();
+    let csv = source.file("dividends.csv");
+    source.run_ok(&["div", "export", "--output", csv.to_str().unwrap()]);
+
+    let env = Fixture::new().dividend("KO", "2024-03-14", "2024-04-01", "0.485", "50").build();
+    env.cmd()
+        .args(["div", "import", csv.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("1 dividends imported"))
+        .stdout(contains("1 duplicates skipped"))
+        .stdout(contains("Skipped Duplicate dividend exists for KO on 2024-03-14"));
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("AAPL"))
+        .stdout(contains("2024-02-15"));
+
+    let bad = env.file("bad.csv");
+    std::fs::write(&bad, "symbol,ex_date,pay_date,amount,shares\nMSFT,2024-05-15,2024-06-13,0.75,20\nT,2024-04-09,2024-05-01,lots,10\n")
+        .unwrap();
+    env.cmd()
+        .args(["div", "import", bad.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("Row 3"))
+        .stderr(contains("Invalid amount format: lots"));
+    env.cmd().args(["div", "list"]).assert().success().stdout(contains("MSFT").not());
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[test]
+fn test_add_dividend_duplicate_detection_is_per_account() {
+    let env = Fixture::new()
+        .command(&["holdings", "add", "VZ", "--shares", "50", "--account", "Taxable"])
+        .dividend("VZ", "2024-04-09", "2024-05-01", "0.66", "50")
+        .build();
+    let vz_dividend = ["VZ", "--ex-date", "2024-04-09", "--pay-date", "2024-05-01", "--amount", "0.66", "--shares", "20"];
+
+    // Credited to the holding's account, so it is the same payment
+    env.cmd()
+        .args(["div", "add"])
+        .args(vz_dividend)
+        .assert()
+        .failure()
+        .stderr(contains("Duplicate dividend exists"));
+
+    // The same payment into another account is a separate record
+    env.cmd()
+        .args(["div", "add"])
+        .args(vz_dividend)
+        .args(["--account", "IRA"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend record added successfully"));
+    env.cmd()
+        .args(["div", "add"])
+        .args(vz_dividend)
+        .args(["--account", "ira"])
+        .assert()
+        .failure()
+        .stderr(contains("Duplicate dividend exists"));
+
+    env.cmd()
+        .args(["div", "list", "--account", "IRA"])
+        .assert()
+        .success()
+        .stdout(contains("$13.20"));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+/// Calendar entries whose pay dates have passed: KO was never recorded, PEP was
+fn write_past_calendar(env: &TestEnv) {
+    let calendar = r#"[
+        {
+            "symbol": "KO",
+            "company_name": null,
+            "ex_date": "2024-03-14",
+            "pay_date": "2024-04-01",
+            "estimated_amount": "0.485",
+            "is_estimated": true,
+            "frequency": "Quarterly",
+            "days_until_ex": 5
+        },
+        {
+            "symbol": "PEP",
+            "company_name": null,
+            "ex_date": "2024-03-07",
+            "pay_date": "2024-03-29",
+            "estimated_amount": "1.265",
+            "is_estimated": true,
+            "frequency": "Quarterly",
+            "days_until_ex": 5
+        }
+    ]"#;
+    std::fs::write(env.file("dividend_calendar.json"), calendar).unwrap();
+}
+
+#[test]
+fn test_alerts_flag_unrecorded_past_payments() {
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .holding("PEP", "20")
+        .dividend("PEP", "2024-03-08", "2024-03-29", "1.265", "20")
+        .build();
+    write_past_calendar(&env);
+
+    env.cmd()
+        .args(["alerts", "--generate"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "KO was due to pay on 2024-04-01 but no payment is recorded. Confirm the cash arrived and record it with 'div add'",
+        ))
+        .stdout(contains("Estimated income: $48.50"))
+        .stdout(contains("PEP").not());
+}
+
+#[test]
+fn test_income_threshold_alerts_on_quiet_cut() {
+    // Nothing paid in the last twelve months, so KO is below any target
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .holding("PEP", "20")
+        .dividend("KO", "2020-03-13", "2020-04-01", "0.41", "100")
+        .build();
+
+    env.cmd()
+        .args(["alerts", "threshold", "ko", "--annual", "500"])
+        .assert()
+        .success()
+        .stdout(contains("Alerting when KO's trailing 12-month income is below $500.00"))
+        .stdout(contains("Last 12 months: $0.00 (already crossed)"));
+    env.run_ok(&["alerts", "threshold", "PEP", "--annual", "1000", "--above"]);
+
+    env.cmd()
+        .args(["alerts", "threshold"])
+        .assert()
+        .success()
+        .stdout(contains("Income Thresholds"))
+        .stdout(contains("Crossed"))
+        .stdout(contains("above"));
+
+    env.cmd()
+        .args(["alerts", "--generate"])
+        .assert()
+        .success()
+        .stdout(contains("KO paid $0.00 over the last 12 months, below your $500.00 target"))
+        .stdout(contains("PEP paid").not());
+
+    env.run_ok(&["alerts", "threshold", "KO", "--remove"]);
+    env.cmd()
+        .args(["alerts", "threshold", "KO", "--remove"])
+        .assert()
+        .failure()
+        .stderr(contains("No income threshold is set for KO"));
+}
+
+#[test]
+fn test_interactive_calendar_needs_a_terminal() {
+    let env = Fixture::new().holding("KO", "100").build();
+    write_past_calendar(&env);
+
+    env.cmd()
+        .args(["calendar", "--interactive"])
+        .assert()
+        .failure()
+        .stderr(contains("calendar --interactive needs a terminal"));
+    env.cmd()
+        .args(["calendar", "--interactive", "--export", "out.ics"])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with"));
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_analyze_yoc_history_from_lots() {
+    let env = Fixture::new()
+        .holding("KO", "150")
+        .holding("O", "20")
+        .dividend("KO", "2021-06-14", "2021-07-01", "1.60", "100")
+        .dividend("KO", "2022-06-14", "2022-07-01", "1.80", "100")
+        .dividend("KO", "2023-06-14", "2023-07-01", "2.00", "150")
+        .build();
+    let lots = env.file("lots.csv");
+    std::fs::write(
+        &lots,
+        "symbol,purchase_date,shares,price\nKO,2020-06-01,100,40\nKO,2022-03-01,50,60\n",
+    )
+    .unwrap();
+    env.run_ok(&["holdings", "import-lots", lots.to_str().unwrap(), "--yes"]);
+
+    env.cmd()
+        .args(["analyze", "yoc-history"])
+        .assert()
+        .success()
+        .stdout(contains("KO (first bought 2020-06-01)"))
+        .stdout(contains("4.00%"))
+        .stdout(contains("4.50%"))
+        .stdout(contains("4.29%"))
+        .stdout(contains("First Lot Yield on Cost: 4.00% in 2021 to 5.00% in 2023"))
+        .stdout(contains("O has no purchase lots"));
+
+    env.cmd()
+        .args(["analyze", "yoc-history", "--symbol", "o"])
+        .assert()
+        .success()
+        .stdout(contains("No position has purchase lots"))
+        .stdout(contains("KO (first bought").not());
+}
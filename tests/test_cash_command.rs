@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+fn ledger_fixture() -> TestEnv {
+    Fixture::new()
+        .command(&["cash", "enable", "--since", "2024-01-01"])
+        .dividend("KO", "2023-11-30", "2023-12-15", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .command(&[
+            "div", "add", "JNJ", "--ex-date", "2024-05-21", "--pay-date", "2024-06-11", "--amount", "1.24",
+            "--shares", "50", "--account", "IRA",
+        ])
+        .build()
+}
+
+#[test]
+fn test_cash_ledger_tracks_dividends_and_movements() {
+    let env = ledger_fixture();
+
+    env.cmd()
+        .args(["cash", "reinvest", "40", "--symbol", "KO", "--date", "2024-04-02"])
+        .assert()
+        .success()
+        .stdout(contains("Cash remaining in Default: $8.50"));
+
+    env.cmd()
+        .args(["cash", "withdraw", "10", "--account", "IRA", "--date", "2024-06-01"])
+        .assert()
+        .failure()
+        .stderr(contains("Only $0 of dividend cash is available in IRA"));
+
+    env.run_ok(&["cash", "withdraw", "12", "--account", "IRA", "--date", "2024-07-01", "--note", "fees"]);
+
+    env.cmd()
+        .args(["cash"])
+        .assert()
+        .success()
+        .stdout(contains("KO dividend"))
+        .stdout(contains("Reinvested in KO"))
+        .stdout(contains("Withdrawal (fees)"))
+        .stdout(contains("2023-12-15").not());
+
+    env.cmd()
+        .args(["summary", "--cash"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Cash"))
+        .stdout(contains("$50.00"))
+        .stdout(contains("Uninvested Cash: $58.50"));
+}
+
+#[test]
+fn test_cash_ledger_must_be_enabled() {
+    let env = Fixture::new()
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .build();
+
+    env.cmd()
+        .args(["cash", "withdraw", "10"])
+        .assert()
+        .failure()
+        .stderr(contains("cash ledger is not enabled"));
+
+    env.cmd()
+        .args(["summary", "--cash"])
+        .assert()
+        .success()
+        .stdout(contains("Start it with 'cash enable'"));
+}
@@ -0,0 +1,154 @@
+// The editor is stood in for by a shell script
+#![cfg(unix)]
+
+mod common;
+use common::*;
+use predicates::prelude::*;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// A command whose config directory lives inside the test's directory
+fn configure(env: &TestEnv, editor: &Path) -> assert_cmd::Command {
+    let mut cmd = env.cmd();
+    cmd.env("HOME", env.path())
+        .env("XDG_CONFIG_HOME", env.file("config"))
+        .env_remove("VISUAL")
+        .env_remove("ALPHA_VANTAGE_API_KEY")
+        .env("EDITOR", editor);
+    cmd
+}
+
+/// An "editor" that replaces the file it is given with `contents`
+fn editor_writing(env: &TestEnv, name: &str, contents: &str) -> PathBuf {
+    let path = env.file(name);
+    std::fs::write(&path, format!("#!/bin/sh\ncat > \"$1\" <<'EOF'\n{}EOF\n", contents)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn test_configure_edit_keeps_only_valid_changes() {
+    let env = TestEnv::new();
+    let config_file = env.file("config").join("dividend-tracker").join("config.toml");
+
+    let valid = editor_writing(
+        &env,
+        "valid.sh",
+        "[api]\ntimeout_seconds = 5\nproxy = \"http://${PROXY_HOST}:3128\"\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n",
+    );
+    configure(&env, &valid)
+        .env("PROXY_HOST", "proxy.internal")
+        .args(["configure", "edit"])
+        .assert()
+        .success()
+        .stdout(contains("Saved"));
+    configure(&env, &valid)
+        .env("PROXY_HOST", "proxy.internal")
+        .args(["configure", "--show"])
+        .assert()
+        .success()
+        .stdout(contains("Request Timeout: 5s"))
+        .stdout(contains("Proxy: http://proxy.internal:3128"));
+
+    // An invalid edit is reported and, when not retried, leaves the file alone
+    let before = std::fs::read_to_string(&config_file).unwrap();
+    let invalid = editor_writing(&env, "invalid.sh", "[api\ntimeout_seconds = 5\n");
+    configure(&env, &invalid)
+        .args(["configure", "edit"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stderr(contains("Invalid configuration"))
+        .stdout(contains("Cancelled"))
+        .stdout(contains("Saved").not());
+    assert_eq!(std::fs::read_to_string(&config_file).unwrap(), before);
+
+    // Saving from another command keeps the reference rather than its value
+    configure(&env, &valid)
+        .env("PROXY_HOST", "proxy.internal")
+        .args(["configure", "--api-key", "demo"])
+        .assert()
+        .success();
+    let saved = std::fs::read_to_string(&config_file).unwrap();
+    assert!(saved.contains("proxy = \"http://${PROXY_HOST}:3128\""));
+    assert!(saved.contains("# Seconds to wait for an API response"));
+}
+
+#[test]
+fn test_configure_validate_reports_typos_and_ranges() {
+    let env = TestEnv::new();
+    let editor = env.file("unused-editor");
+    configure(&env, &editor)
+        .args(["configure", "validate"])
+        .assert()
+        .success()
+        .stdout(contains("defaults are in use"));
+
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\ntimeout_secs = 10\nmax_retries = 50\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n",
+    )
+    .unwrap();
+
+    configure(&env, &editor)
+        .args(["configure", "validate"])
+        .assert()
+        .failure()
+        .stdout(contains("api.timeout_secs is not a setting and is ignored (did you mean timeout_seconds?)"))
+        .stdout(contains("api.max_retries is 50; use 0 to 10"))
+        .stderr(contains("2 problems"));
+
+    // Other commands warn instead of quietly using the defaults
+    configure(&env, &editor)
+        .args(["list"])
+        .assert()
+        .success()
+        .stderr(contains("warning: config.toml: api.timeout_secs is not a setting"));
+    configure(&env, &editor)
+        .args(["--quiet", "list"])
+        .assert()
+        .success()
+        .stderr(contains("config.toml").not());
+}
+
+#[test]
+fn test_configure_tokens_add_list_remove() {
+    let env = TestEnv::new();
+    let editor = editor_writing(&env, "unused.sh", "");
+    let tokens_file = env.file("config").join("dividend-tracker").join("tokens.toml");
+
+    configure(&env, &editor)
+        .args(["configure", "tokens", "add", "--scope", "read"])
+        .assert()
+        .success()
+        .stdout(contains("read-1"))
+        .stdout(contains("dt_"));
+    configure(&env, &editor)
+        .args(["configure", "tokens", "add", "--scope", "read-write", "--name", "automation"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::metadata(&tokens_file).unwrap().permissions().mode() & 0o777, 0o600);
+
+    configure(&env, &editor)
+        .args(["configure", "tokens", "list"])
+        .assert()
+        .success()
+        .stdout(contains("read-1"))
+        .stdout(contains("automation"))
+        .stdout(contains("write"))
+        .stdout(contains("dt_").not());
+
+    configure(&env, &editor)
+        .args(["configure", "tokens", "remove", "read-1"])
+        .assert()
+        .success();
+    configure(&env, &editor)
+        .args(["configure", "tokens", "remove", "read-1"])
+        .assert()
+        .failure()
+        .stderr(contains("No token named 'read-1'"));
+    assert!(!std::fs::read_to_string(&tokens_file).unwrap().contains("read-1"));
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::path::{Path, PathBuf};
+
+fn base_fixture() -> Fixture {
+    Fixture::new()
+        .holding("AAPL", "100")
+        .holding("KO", "50")
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "50")
+}
+
+#[test]
+fn test_data_diff_against_other_directory() {
+    let laptop = base_fixture().build();
+    let desktop = base_fixture()
+        .dividend("MSFT", "2024-02-14", "2024-03-14", "0.75", "20")
+        .command(&["holdings", "add", "AAPL", "--shares", "120"])
+        .build();
+    let laptop_dir = laptop.path().display().to_string();
+
+    desktop
+        .cmd()
+        .args(["data", "diff", &laptop_dir])
+        .assert()
+        .success()
+        .stdout(contains("Dividends Added: 1"))
+        .stdout(contains("Dividends Removed: 0"))
+        .stdout(contains("Holdings Changed: 1"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("shares: 100 -> 120"));
+
+    laptop
+        .cmd()
+        .args(["data", "diff", &laptop_dir])
+        .assert()
+        .success()
+        .stdout(contains("The datasets contain the same records."));
+}
+
+#[test]
+fn test_data_diff_leaves_a_corrupt_directory_untouched() {
+    let env = base_fixture().build();
+    let other = base_fixture().command(&["data", "backup"]).build();
+    std::fs::write(other.file("dividends.json"), "{ not json").unwrap();
+    let listing = |dir: &Path| -> Vec<String> {
+        let mut names: Vec<String> = walk(dir).iter().map(|p| p.display().to_string()).collect();
+        names.sort();
+        names
+    };
+    let before = listing(other.path());
+
+    env.cmd()
+        .args(["data", "diff", &other.path().display().to_string()])
+        .assert()
+        .failure()
+        .stderr(contains("does not contain dividend tracker data"));
+
+    assert_eq!(listing(other.path()), before);
+    assert_eq!(std::fs::read_to_string(other.file("dividends.json")).unwrap(), "{ not json");
+}
+
+#[test]
+fn test_data_diff_against_backup() {
+    let env = base_fixture().build();
+    env.run_ok(&["data", "backup"]);
+    let backups: Vec<_> = std::fs::read_dir(env.file("backups"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("dividends"))
+        .collect();
+    let latest = backups.iter().max().expect("backup should exist").clone();
+
+    env.run_ok(&["holdings", "remove", "KO", "--yes"]);
+
+    env.cmd()
+        .args(["data", "diff", &latest])
+        .assert()
+        .success()
+        .stdout(contains("Holdings Removed: 1"))
+        .stdout(contains("Removed Holdings"))
+        .stdout(contains("Added Dividends").not());
+
+    env.cmd()
+        .args(["data", "diff", "no-such-backup.json"])
+        .assert()
+        .failure()
+        .stderr(contains("Data directory or backup not found"));
+}
+
+#[test]
+fn test_archive_keeps_old_years_out_of_everyday_commands() {
+    let env = base_fixture()
+        .dividend("KO", "2018-06-14", "2018-07-02", "0.39", "50")
+        .dividend("AAPL", "2019-11-07", "2019-11-14", "0.77", "100")
+        .build();
+
+    env.cmd()
+        .args(["data", "archive", "--before", "2020", "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Archived 2 dividends paid before 2020 into 2 yearly files"))
+        .stdout(contains("2018: 1 dividends"));
+    assert!(env.file("archive/dividends_2019.json").exists());
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("2019-11-07").not())
+        .stdout(contains("2024-02-09"));
+    env.cmd()
+        .args(["div", "list", "--include-archived", "--year", "2019"])
+        .assert()
+        .success()
+        .stdout(contains("2019-11-07"));
+    env.cmd()
+        .args(["tax", "summary", "--year", "2018", "--include-archived"])
+        .assert()
+        .success()
+        .stdout(contains("$19.50"));
+    env.cmd()
+        .args(["data", "stats"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Records: 2"))
+        .stdout(contains("Archived Dividends: 2 (2018-2019)"));
+
+    env.cmd()
+        .args(["data", "archive", "--before", "2020", "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("No dividends paid before 2020 to archive"));
+}
+
+#[test]
+fn test_calendar_and_alerts_travel_with_export_and_backup() {
+    let env = base_fixture().build();
+    let calendar = r#"[
+        {
+            "symbol": "KO",
+            "company_name": null,
+            "ex_date": "2024-06-13",
+            "pay_date": "2024-07-01",
+            "estimated_amount": "0.485",
+            "is_estimated": true,
+            "frequency": "Quarterly",
+            "days_until_ex": 5
+        }
+    ]"#;
+    std::fs::write(env.file("dividend_calendar.json"), calendar).unwrap();
+
+    let output = env.file("export");
+    env.run_ok(&["data", "export", "--format", "json", "--output", output.to_str().unwrap()]);
+    let exported = std::fs::read_to_string(env.file("export.json")).unwrap();
+    assert!(exported.contains("\"schema_version\": 2"));
+    assert!(exported.contains("\"calendar\""));
+    assert!(exported.contains("2024-06-13"));
+
+    env.run_ok(&["data", "backup"]);
+    let backups: Vec<_> = std::fs::read_dir(env.file("backups"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("dividends"))
+        .collect();
+    let latest = backups.iter().max().expect("backup should exist").clone();
+
+    // Moving machines: the calendar is gone until the backup is loaded
+    std::fs::remove_file(env.file("dividend_calendar.json")).unwrap();
+    env.cmd()
+        .args(["data", "load", &latest, "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Calendar: 1 entries, alerts: 0"));
+    assert!(std::fs::read_to_string(env.file("dividend_calendar.json"))
+        .unwrap()
+        .contains("2024-06-13"));
+}
+
+#[test]
+fn test_archive_keeps_same_day_dividends_in_different_accounts() {
+    let env = Fixture::new()
+        .dividend("KO", "2018-06-14", "2018-07-02", "0.39", "100")
+        .command(&[
+            "div", "add", "KO", "--ex-date", "2018-06-14", "--pay-date", "2018-07-02", "-a", "0.39", "-s", "40",
+            "--account", "IRA", "--force",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["data", "archive", "--before", "2020", "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Archived 2 dividends paid before 2020 into 1 yearly file"));
+    let archived = std::fs::read_to_string(env.file("archive/dividends_2018.json")).unwrap();
+    assert!(archived.contains("\"IRA\""));
+
+    env.cmd()
+        .args(["div", "list", "--include-archived", "--account", "IRA"])
+        .assert()
+        .success()
+        .stdout(contains("2018-06-14"));
+    env.cmd()
+        .args(["data", "stats"])
+        .assert()
+        .success()
+        .stdout(contains("Archived Dividends: 2 (2018)"));
+}
+
+/// Every file under `dir`
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walk(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
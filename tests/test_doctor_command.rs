@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_doctor_reports_healthy_data() {
+    let env = Fixture::new().holding("KO", "100").build();
+    env.run_ok(&["data", "backup"]);
+
+    env.cmd()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(contains("Schema version 2"))
+        .stdout(contains("all readable"))
+        .stdout(contains("failed").not());
+}
+
+#[test]
+fn test_doctor_flags_broken_backups_and_data_with_fixes() {
+    let env = Fixture::new().holding("KO", "100").build();
+    env.run_ok(&["data", "backup"]);
+    fs::write(env.file("backups").join("dividends_20240101_000000.json.bak"), "{truncated").unwrap();
+
+    env.cmd()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(contains("can't be restored: dividends_20240101_000000.json.bak"))
+        .stdout(contains("Delete the broken files"));
+
+    fs::write(env.file("dividends.json"), "{truncated").unwrap();
+    env.cmd()
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(contains("data load <backup>"))
+        .stderr(contains("checks failed"));
+}
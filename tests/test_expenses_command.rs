@@ -0,0 +1,106 @@
+mod common;
+
+use chrono::{Duration, Local, NaiveDate};
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_expenses_coverage_and_crossover() {
+    let env = Fixture::new()
+        .holding("KO", "1000")
+        .command(&["expenses", "set", "54000/year"])
+        .build();
+
+    env.cmd()
+        .args(["expenses"])
+        .assert()
+        .success()
+        .stdout(contains("Monthly Expenses: $4500.00"))
+        .stdout(contains("Projected Crossover: Needs dividend income first"));
+
+    env.run_ok(&["holdings", "set-forward-dividend", "KO", "27"]);
+
+    env.cmd()
+        .args(["expenses", "--growth-rate", "10%", "--inflation", "0%"])
+        .assert()
+        .success()
+        .stdout(contains("Forward Income: $2250.00/month"))
+        .stdout(contains("Covered by Forward Income: 50.0%"))
+        .stdout(contains("projected"))
+        .stdout(contains("Time to Go: 7 years 4 months"))
+        .stdout(contains("Reached").not());
+
+    env.cmd()
+        .args(["expenses", "--growth-rate", "2%"])
+        .assert()
+        .success()
+        .stdout(contains("Not reached at these rates"));
+
+    env.cmd()
+        .args(["expenses", "set", "100/week"])
+        .assert()
+        .failure()
+        .stderr(contains("use /month or /year"));
+
+    env.cmd()
+        .args(["expenses", "clear"])
+        .assert()
+        .success()
+        .stdout(contains("Removed expenses of $4500.00/month"));
+}
+
+#[test]
+fn test_planned_expenses_checked_against_calendar() {
+    let env = Fixture::new().holding("KO", "100").holding("PEP", "20").build();
+    let today = Local::now().naive_local().date();
+    let day = |days: i64| (today + Duration::days(days)).format("%Y-%m-%d").to_string();
+    let entry = |symbol: &str, ex: i64, pay: i64, amount: &str| {
+        format!(
+            r#"{{"symbol": "{}", "company_name": null, "ex_date": "{}", "pay_date": "{}", "estimated_amount": "{}", "is_estimated": true, "frequency": "Quarterly", "days_until_ex": 0}}"#,
+            symbol,
+            day(ex),
+            day(pay),
+            amount
+        )
+    };
+    let calendar = format!("[{}, {}]", entry("KO", 2, 10, "0.485"), entry("PEP", 5, 25, "1.265"));
+    std::fs::write(env.file("dividend_calendar.json"), calendar).unwrap();
+
+    env.cmd()
+        .args(["expenses", "plan", "60", "--date", &day(20), "--note", "Car repair"])
+        .assert()
+        .success()
+        .stdout(contains(format!("Planned $60.00 for Car repair on {}", day(20))));
+    env.run_ok(&["expenses", "plan", "10", "--date", &day(30)]);
+    env.run_ok(&["expenses", "plan", "500", "--date", &day(200)]);
+
+    env.cmd()
+        .args(["calendar"])
+        .assert()
+        .success()
+        .stdout(contains("Planned Expenses"))
+        .stdout(contains("Expected cash by then: $48.50 ($48.50 in dividends, $0.00 on hand)"))
+        .stdout(contains("Shortfall of $11.50"))
+        .stdout(contains(format!("Paying just after: PEP $25.30 on {}", day(25))))
+        .stdout(contains("Covered"))
+        // Beyond the calendar's 90 days
+        .stdout(contains("$500.00").not());
+
+    env.cmd()
+        .args(["expenses", "unplan", &day(20)])
+        .assert()
+        .success()
+        .stdout(contains("Removed $60.00 planned on"));
+    env.cmd()
+        .args(["calendar"])
+        .assert()
+        .success()
+        .stdout(contains("Shortfall").not());
+
+    let past = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap().format("%Y-%m-%d").to_string();
+    env.cmd()
+        .args(["expenses", "unplan", &past])
+        .assert()
+        .success()
+        .stdout(contains("No expenses were planned on 2020-01-01"));
+}
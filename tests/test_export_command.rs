@@ -0,0 +1,59 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+fn budget_fixture() -> TestEnv {
+    Fixture::new()
+        .holding("KO", "100")
+        .holding("O", "50")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("O", "2024-03-28", "2024-04-15", "0.257", "50")
+        .dividend("KO", "2024-06-14", "2024-07-01", "0.485", "100")
+        .build()
+}
+
+#[test]
+fn test_export_budget_writes_one_transaction_per_month() {
+    let env = budget_fixture();
+    let output = env.file("budget.csv");
+
+    env.cmd()
+        .args(["export", "budget", "--year", "2024", "--output"])
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(contains("Exported 2 months"));
+    let csv = std::fs::read_to_string(&output).unwrap();
+    assert_eq!(
+        csv,
+        "Date,Payee,Category,Notes,Amount\n\
+         2024-04-30,Dividend Income,Income,\"2 dividend payments from KO, O\",61.35\n\
+         2024-07-31,Dividend Income,Income,1 dividend payment from KO,48.50\n"
+    );
+}
+
+#[test]
+fn test_export_budget_ynab_format() {
+    let env = budget_fixture();
+    let output = env.file("ynab.csv");
+
+    env.cmd()
+        .args(["export", "budget", "--format", "ynab", "--year", "2024", "--payee", "Brokerage"])
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success();
+    let csv = std::fs::read_to_string(&output).unwrap();
+    assert!(csv.starts_with("Date,Payee,Memo,Outflow,Inflow\n"));
+    assert!(csv.contains("2024-07-31,Brokerage,1 dividend payment from KO,,48.50\n"));
+
+    env.cmd()
+        .args(["export", "budget", "--year", "2020", "--output"])
+        .arg(env.file("empty.csv"))
+        .assert()
+        .success()
+        .stdout(contains("No dividend income"))
+        .stdout(contains("Exported").not());
+    assert!(!env.file("empty.csv").exists());
+}
@@ -0,0 +1,363 @@
+mod common;
+
+use chrono::{Duration, Local};
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_holdings_update_recomputes_average_cost() {
+    let env = Fixture::new().holding_with_cost("AAPL", "100", "150").build();
+
+    env.cmd()
+        .args(["holdings", "update", "aapl", "--shares", "+25", "--cost-basis", "182.50", "--date", "2024-05-01"])
+        .assert()
+        .success()
+        .stdout(contains("Shares: 100 -> 125"))
+        .stdout(contains("Cost Basis: $150 -> $156.5"));
+
+    env.cmd()
+        .args(["holdings", "update", "AAPL", "--shares", "-10", "--date", "2024-06-01"])
+        .assert()
+        .success()
+        .stdout(contains("Shares: 125 -> 115"));
+
+    env.cmd()
+        .args(["holdings", "history", "AAPL"])
+        .assert()
+        .success()
+        .stdout(contains("Holding History: AAPL"))
+        .stdout(contains("2024-05-01"))
+        .stdout(contains("+25"))
+        .stdout(contains("$182.50"))
+        .stdout(contains("-10"))
+        .stdout(contains("$156.50"));
+}
+
+#[test]
+fn test_holdings_update_rejects_overselling() {
+    let env = Fixture::new().holding_with_cost("AAPL", "10", "150").build();
+
+    env.cmd()
+        .args(["holdings", "update", "AAPL", "--shares", "-25"])
+        .assert()
+        .failure()
+        .stderr(contains("Cannot sell 25 shares of AAPL: only 10 held"));
+
+    env.cmd()
+        .args(["holdings", "update", "MSFT", "--shares", "+5"])
+        .assert()
+        .failure()
+        .stderr(contains("No holding found for MSFT"));
+}
+
+#[test]
+fn test_holdings_add_keeps_adjustment_history() {
+    let env = Fixture::new().holding_with_cost("KO", "50", "60").build();
+
+    env.run_ok(&["holdings", "update", "KO", "--shares", "+10", "--cost-basis", "66"]);
+    env.run_ok(&["holdings", "add", "KO", "--shares", "70", "--cost-basis", "62"]);
+
+    env.cmd()
+        .args(["holdings", "history", "KO"])
+        .assert()
+        .success()
+        .stdout(contains("+10"));
+}
+
+#[test]
+fn test_holdings_remove_asks_for_confirmation() {
+    let env = Fixture::new().holding("KO", "100").build();
+
+    env.cmd()
+        .args(["holdings", "remove", "KO"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(contains("Delete holding KO (100 shares)? Use --archive"))
+        .stdout(contains("Cancelled, nothing was changed."));
+
+    env.cmd()
+        .args(["--quiet", "holdings", "remove", "KO"])
+        .assert()
+        .failure()
+        .stderr(contains("pass --yes"));
+
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("KO"));
+
+    env.cmd()
+        .args(["holdings", "remove", "KO"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(contains("Removed holding for KO"));
+
+    env.cmd()
+        .args(["holdings", "remove", "KO", "-y"])
+        .assert()
+        .success()
+        .stdout(contains("No holding found for KO"))
+        .stdout(contains("[y/N]").not());
+}
+
+#[test]
+fn test_holdings_remove_archive_keeps_closed_position() {
+    let env = Fixture::new()
+        .holding_with_cost("KO", "100", "55")
+        .holding_with_cost("VZ", "200", "40")
+        .dividend("KO", "yesterday", "today", "0.485", "100")
+        .dividend("VZ", "yesterday", "today", "0.665", "200")
+        .build();
+
+    env.cmd()
+        .args(["holdings", "remove", "VZ", "--archive", "--date", "2024-09-30"])
+        .assert()
+        .success()
+        .stdout(contains("Archived holding for VZ (200 shares closed on 2024-09-30)"));
+
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("KO"))
+        .stdout(contains("VZ").not());
+
+    env.cmd()
+        .args(["holdings", "list", "--include-archived"])
+        .assert()
+        .success()
+        .stdout(contains("closed 2024-09-30"))
+        .stdout(contains("$40.00"));
+
+    // Archived positions are neither projected nor reported as missing history
+    env.cmd()
+        .args(["project"])
+        .assert()
+        .success()
+        .stdout(contains("KO"))
+        .stdout(contains("VZ").not());
+
+    // Past income from the sold position still shows up
+    env.cmd()
+        .args(["div", "list", "--symbol", "VZ"])
+        .assert()
+        .success()
+        .stdout(contains("$133.00"));
+
+    env.cmd()
+        .args(["holdings", "history", "VZ"])
+        .assert()
+        .success()
+        .stdout(contains("-200"));
+}
+
+#[test]
+fn test_holdings_isin_identifiers() {
+    let env = Fixture::new()
+        .command(&["holdings", "add", "NESN", "--shares", "40", "--isin", "ch0038863350"])
+        .build();
+
+    env.cmd()
+        .args(["holdings", "add", "AAPL", "--shares", "10", "--isin", "US0378331006"])
+        .assert()
+        .failure()
+        .stderr(contains("check digit does not match"));
+
+    env.cmd()
+        .args(["holdings", "add", "NESN.SW", "--shares", "40", "--isin", "CH0038863350"])
+        .assert()
+        .failure()
+        .stderr(contains("CH0038863350 already identifies the NESN holding"));
+
+    // A dividend recorded by ISIN lands on the holding's ticker
+    env.cmd()
+        .args([
+            "div", "add", "CH0038863350", "--ex-date", "2024-04-22", "--pay-date", "2024-04-24",
+            "--amount", "3.00", "--shares", "40",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Matched CH0038863350 to holding NESN"))
+        .stdout(contains("ISIN: CH0038863350"));
+
+    // Imports match on ISIN even when the broker spells the ticker differently
+    let csv = env.file("broker.csv");
+    std::fs::write(&csv, "symbol,shares,cost_basis,current_yield,isin\nNESN.SW,55,,,CH0038863350\n,5,,,US0378331005\n")
+        .unwrap();
+    env.cmd()
+        .args(["holdings", "import", csv.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("No symbol given and no holding matches US0378331005"));
+
+    std::fs::write(&csv, "symbol,shares,cost_basis,current_yield,isin\nNESN.SW,55,,,CH0038863350\n").unwrap();
+    env.cmd()
+        .args(["holdings", "import", csv.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Matched NESN.SW to NESN by CH0038863350"))
+        .stdout(contains("Updated NESN shares"));
+
+    let exported = env.file("holdings.csv");
+    env.run_ok(&["holdings", "export", "--output", exported.to_str().unwrap()]);
+    let content = std::fs::read_to_string(&exported).unwrap();
+    assert!(content.contains("isin,cusip"));
+    assert!(content.contains("NESN,55,,,CH0038863350,"));
+}
+
+#[test]
+fn test_holdings_summary_shows_forward_income() {
+    let today = Local::now().naive_local().date();
+    let ex_date = |days_ago: i64| (today - Duration::days(days_ago)).format("%Y-%m-%d").to_string();
+    let (earlier, latest) = (ex_date(100), ex_date(9));
+
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .holding("GROW", "10")
+        .dividend("KO", &earlier, &earlier, "0.485", "100")
+        .dividend("KO", &latest, &latest, "0.51", "100")
+        .build();
+
+    env.cmd()
+        .args(["holdings", "summary"])
+        .assert()
+        .success()
+        .stdout(contains("Forward Annual Income: $204.00 ($17.00/month)"))
+        .stdout(contains("No recent dividend or forward rate for GROW"))
+        .stdout(contains("Forward Income by Month"));
+}
+
+#[test]
+fn test_holdings_import_lots_rebuilds_holding_and_attributes_dividends() {
+    let env = Fixture::new()
+        .holding_with_cost("KO", "10", "40")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "120")
+        .build();
+    let lots = env.file("lots.csv");
+    fs::write(
+        &lots,
+        "Symbol,Date Acquired,Quantity,Cost Per Share,Lot ID\n\
+         KO,06/01/2022,70,60.00,B2\n\
+         KO,03/15/2021,50,50.00,A1\n",
+    )
+    .unwrap();
+    let lots = lots.to_str().unwrap();
+
+    env.cmd()
+        .args(["holdings", "import-lots", lots, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("Rebuilt KO from 2 lots: 120 shares"))
+        .stdout(contains("+ shares: 120"))
+        .stdout(contains("Dry run: 2 lots not imported."));
+
+    env.cmd()
+        .args(["holdings", "import-lots", lots, "--yes"])
+        .assert()
+        .success()
+        .stdout(contains("Imported lots for 1 holdings"));
+    env.cmd()
+        .args(["holdings", "history", "KO"])
+        .assert()
+        .success()
+        .stdout(contains("2021-03-15"))
+        .stdout(contains("+70"))
+        .stdout(contains("$55.83"));
+
+    env.cmd()
+        .args(["tax", "lots", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("A1"))
+        .stdout(contains("$24.25"))
+        .stdout(contains("B2"))
+        .stdout(contains("$33.95"))
+        .stdout(contains("2022-06-01"))
+        .stdout(contains("requires additional cost basis data").not());
+}
+
+#[test]
+fn test_holdings_import_lots_reports_bad_rows() {
+    let env = TestEnv::new();
+    let lots = env.file("lots.csv");
+    fs::write(&lots, "symbol,purchase_date,shares,price\nKO,2021-03-15,-5,50\n").unwrap();
+
+    env.cmd()
+        .args(["holdings", "import-lots", lots.to_str().unwrap(), "--yes"])
+        .assert()
+        .failure()
+        .stderr(contains("Line 2: shares must be a positive number"));
+}
+
+#[test]
+fn test_holdings_update_sale_leaves_the_oldest_lots() {
+    let env = Fixture::new()
+        .holding("KO", "150")
+        .dividend("KO", "2022-06-14", "2022-07-01", "0.50", "50")
+        .build();
+    let lots = env.file("lots.csv");
+    fs::write(&lots, "symbol,purchase_date,shares,price\nKO,2020-03-01,100,40\nKO,2021-03-01,50,50\n").unwrap();
+    env.run_ok(&["holdings", "import-lots", lots.to_str().unwrap(), "--yes"]);
+    env.run_ok(&["holdings", "update", "KO", "--shares", "-100", "--date", "2022-01-10"]);
+
+    env.cmd()
+        .args(["analyze", "yoc-history", "--symbol", "KO"])
+        .assert()
+        .success()
+        .stdout(contains("│ 2022 │ $0.5000   │ 50     │ $2500.00 │"));
+    env.cmd()
+        .args(["tax", "lots", "--year", "2022"])
+        .assert()
+        .success()
+        .stdout(contains("KO-20210301"))
+        .stdout(contains("KO-20200301").not());
+}
+
+#[test]
+fn test_holdings_are_kept_per_account() {
+    let env = Fixture::new()
+        .command(&["holdings", "add", "VZ", "--shares", "50", "--account", "Taxable"])
+        .command(&["holdings", "add", "VZ", "--shares", "20", "--account", "IRA"])
+        .build();
+
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("│ VZ     │ 50     │"))
+        .stdout(contains("│ VZ     │ 20     │"));
+
+    // Importing into one account leaves the other alone
+    let csv = env.file("ira.csv");
+    fs::write(&csv, "symbol,shares\nVZ,25\n").unwrap();
+    env.run_ok(&["holdings", "import", csv.to_str().unwrap(), "--account", "ira"]);
+
+    // Without an account a command can't tell the positions apart
+    env.cmd()
+        .args(["holdings", "update", "VZ", "--shares", "+10"])
+        .assert()
+        .failure()
+        .stderr(contains("VZ is held in several accounts (IRA, Taxable); pick one with --account"));
+
+    env.run_ok(&["holdings", "update", "VZ", "--shares", "+10", "--account", "Taxable"]);
+    env.run_ok(&["transactions", "buy", "VZ", "--shares", "5", "--price", "40", "--account", "IRA"]);
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("│ VZ     │ 60     │"))
+        .stdout(contains("│ VZ     │ 30     │"));
+
+    env.run_ok(&["holdings", "remove", "VZ", "--account", "IRA", "--archive"]);
+    env.run_ok(&["holdings", "remove", "VZ", "--account", "Taxable", "--yes"]);
+    env.cmd()
+        .args(["holdings", "list", "--include-archived"])
+        .assert()
+        .success()
+        .stdout(contains("closed"))
+        .stdout(contains("Taxable").not());
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_init_imports_holdings_and_suggests_next_steps() {
+    let env = TestEnv::new();
+    let csv = env.file("broker.csv");
+    fs::write(&csv, "symbol,shares,cost_basis\nKO,100,52.40\nJNJ,40,155.10\n").unwrap();
+
+    env.cmd()
+        .args(["init", "--no-input", "--import-holdings", csv.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Data directory"))
+        .stdout(contains("Next steps"))
+        .stdout(contains("sample portfolio").not());
+
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("JNJ"));
+}
+
+#[test]
+fn test_init_demo_only_seeds_empty_data() {
+    let env = TestEnv::new();
+    env.cmd().assert().success().stdout(contains("dividend-tracker init"));
+
+    env.cmd()
+        .args(["init", "--no-input", "--demo"])
+        .assert()
+        .success()
+        .stdout(contains("Added a sample portfolio of 3 holdings and 20 dividends"));
+    env.cmd().assert().success().stdout(contains("dividend-tracker init").not());
+
+    env.cmd()
+        .args(["init", "--no-input", "--demo"])
+        .assert()
+        .failure()
+        .stderr(contains("already has data"));
+}
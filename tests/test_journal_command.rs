@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_journal_notes_show_with_symbol_details() {
+    let env = Fixture::new()
+        .holding_with_cost("AAPL", "10", "150.00")
+        .dividend("AAPL", "2024-05-10", "2024-05-16", "0.25", "10")
+        .build();
+
+    env.cmd()
+        .args(["journal", "add", "aapl", "Raised dividend 4%, payout ratio 15%"])
+        .assert()
+        .success()
+        .stdout(contains("Added a note to AAPL (1 in its journal)"))
+        .stdout(contains("no holding").not());
+    env.run_ok(&["journal", "add", "MSFT", "Watching for a buy under $400"]);
+
+    env.cmd()
+        .args(["show", "AAPL"])
+        .assert()
+        .success()
+        .stdout(contains("Total Received: $2.50"))
+        .stdout(contains("Raised dividend 4%, payout ratio 15%"))
+        .stdout(contains("Watching for a buy").not());
+
+    env.cmd()
+        .args(["journal", "list"])
+        .assert()
+        .success()
+        .stdout(contains("MSFT"))
+        .stdout(contains("AAPL"));
+
+    let output = env.file("notes.csv");
+    env.cmd()
+        .args(["journal", "export", "--symbol", "MSFT", "-o", output.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Exported 1 journal entries"));
+    let csv = fs::read_to_string(&output).unwrap();
+    assert!(csv.starts_with("Symbol,Timestamp,Note\nMSFT,"));
+    assert!(!csv.contains("AAPL"));
+}
+
+#[test]
+fn test_show_unknown_symbol_suggests_close_one() {
+    let env = Fixture::new().holding("AAPL", "10").build();
+
+    env.cmd()
+        .args(["show", "APPL"])
+        .assert()
+        .failure()
+        .stderr(contains("Nothing recorded for APPL. Did you mean AAPL?"));
+}
@@ -1,318 +1,582 @@
-use anyhow::Result;
-use tempfile::tempdir;
-use std::process::Command;
-
-fn get_binary_path() -> String {
-    "./target/debug/dividend-tracker".to_string()
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+/// Dividends with various dates and amounts, including one upcoming payment
+fn list_fixture() -> TestEnv {
+    Fixture::new()
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .dividend("AAPL", "2024-05-15", "2024-05-22", "0.25", "200")
+        .dividend("MSFT", "2024-03-20", "2024-03-25", "0.68", "50")
+        .dividend("GOOGL", "2024-06-10", "2024-06-15", "1.20", "25")
+        .dividend("TSLA", "tomorrow", "next friday", "0.45", "75")
+        .build()
 }
 
-fn setup_test_data(temp_dir: &std::path::Path) -> Result<()> {
-    // Add some test dividends with various dates and amounts
-    let commands = vec![
-        vec!["add", "AAPL", "--ex-date", "2024-01-15", "--pay-date", "2024-01-18", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-05-15", "--pay-date", "2024-05-22", "--amount", "0.25", "--shares", "200", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-03-20", "--pay-date", "2024-03-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "GOOGL", "--ex-date", "2024-06-10", "--pay-date", "2024-06-15", "--amount", "1.20", "--shares", "25", "--force"],
-        vec!["add", "TSLA", "--ex-date", "tomorrow", "--pay-date", "next friday", "--amount", "0.45", "--shares", "75", "--force"],
-    ];
-
-    for cmd_args in commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup test data: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    Ok(())
+#[test]
+fn test_list_all_dividends() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Payments"))
+        .stdout(contains("Symbol"))
+        .stdout(contains("Ex-Date"))
+        .stdout(contains("Pay-Date"))
+        .stdout(contains("$/Share"))
+        .stdout(contains("Shares"))
+        .stdout(contains("Total Dividends:"))
+        .stdout(contains("Number of Payments: 5"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("GOOGL"))
+        .stdout(contains("TSLA"));
 }
 
 #[test]
-fn test_list_all_dividends() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Listing dividend payments"));
-    assert!(stdout.contains("Symbol"));
-    assert!(stdout.contains("Ex-Date"));
-    assert!(stdout.contains("Pay-Date"));
-    assert!(stdout.contains("$/Share"));
-    assert!(stdout.contains("Shares"));
-    assert!(stdout.contains("Total"));
-    assert!(stdout.contains("Total Dividends:"));
-    assert!(stdout.contains("Number of Payments:"));
-    assert!(stdout.contains("AAPL"));
-    assert!(stdout.contains("MSFT"));
-    assert!(stdout.contains("GOOGL"));
-    assert!(stdout.contains("TSLA"));
-
-    Ok(())
+fn test_list_subtotals_by_year() {
+    let env = Fixture::new()
+        .dividend("KO", "2023-11-30", "2023-12-15", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("KO", "2024-06-14", "2024-07-01", "0.485", "100")
+        .build();
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("2023 Subtotal: $46.00 (1 payment)"))
+        .stdout(contains("2024 Subtotal: $97.00 (2 payments)"))
+        .stdout(contains("Total Dividends: $143.00"));
+
+    // A single year needs no subtotal
+    env.cmd()
+        .args(["div", "list", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Subtotal").not());
 }
 
 #[test]
-fn test_list_filter_by_symbol() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--symbol", "AAPL"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with symbol filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("AAPL"));
-    assert!(!stdout.contains("MSFT"));
-    assert!(!stdout.contains("GOOGL"));
-    assert!(!stdout.contains("TSLA"));
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Symbol: AAPL"));
-
-    Ok(())
+fn test_list_filter_by_symbol() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "AAPL"])
+        .assert()
+        .success()
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT").not())
+        .stdout(contains("GOOGL").not())
+        .stdout(contains("TSLA").not())
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Symbol: AAPL"));
 }
 
 #[test]
-fn test_list_filter_by_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--year", "2024"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with year filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Year: 2024"));
-    assert!(stdout.contains("2024-")); // Should contain 2024 dates
-
-    Ok(())
+fn test_list_symbol_match_modes() {
+    let env = list_fixture();
+
+    // Exact by default, so a partial symbol matches nothing
+    env.cmd()
+        .args(["div", "list", "--symbol", "goog"])
+        .assert()
+        .success()
+        .stdout(contains("No dividends match the specified filters."));
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "goog", "--symbol-match", "prefix"])
+        .assert()
+        .success()
+        .stdout(contains("GOOGL"))
+        .stdout(contains("Symbol: goog (prefix)"));
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "SF", "--symbol-match", "contains"])
+        .assert()
+        .success()
+        .stdout(contains("MSFT"))
+        .stdout(contains("Number of Payments: 1"));
 }
 
 #[test]
-fn test_list_filter_by_month() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--month", "5"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with month filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Month: 5"));
-
-    Ok(())
+fn test_list_multiple_symbols_and_globs() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "AAPL,msft"])
+        .assert()
+        .success()
+        .stdout(contains("Number of Payments: 3"))
+        .stdout(contains("GOOGL").not());
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "G*,T?LA"])
+        .assert()
+        .success()
+        .stdout(contains("GOOGL"))
+        .stdout(contains("TSLA"))
+        .stdout(contains("Number of Payments: 2"));
 }
 
 #[test]
-fn test_list_filter_by_amount_min() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--amount-min", "0.50"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with amount filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Min Amount: $0.50"));
-    // Should show MSFT (0.68) and GOOGL (1.20) but not AAPL (0.24, 0.25)
-    assert!(stdout.contains("MSFT"));
-    assert!(stdout.contains("GOOGL"));
-
-    Ok(())
+fn test_data_export_applies_symbol_and_year_filters() {
+    let env = Fixture::new()
+        .holding("AAPL", "100")
+        .holding("MSFT", "50")
+        .dividend("AAPL", "2023-11-10", "2023-11-16", "0.24", "100")
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .dividend("MSFT", "2024-02-14", "2024-03-14", "0.75", "50")
+        .build();
+    let base = env.file("filtered");
+
+    env.cmd()
+        .args(["data", "export", "--symbol", "aapl", "--year", "2024", "--output"])
+        .arg(&base)
+        .assert()
+        .success();
+
+    let dividends = std::fs::read_to_string(env.file("filtered_dividends.csv")).unwrap();
+    assert_eq!(dividends.lines().count(), 2, "header plus one dividend: {}", dividends);
+    assert!(dividends.contains("2024-02-09"));
+
+    let holdings = std::fs::read_to_string(env.file("filtered_holdings.csv")).unwrap();
+    assert!(holdings.contains("AAPL"));
+    assert!(!holdings.contains("MSFT"));
 }
 
 #[test]
-fn test_list_filter_upcoming() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--upcoming"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with upcoming filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Upcoming Only: Yes"));
-    // Should only show TSLA which has future dates
-    assert!(stdout.contains("TSLA"));
-
-    Ok(())
+fn test_list_filter_by_year() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Year: 2024"))
+        .stdout(contains("2024-"));
 }
 
 #[test]
-fn test_list_sort_by_symbol() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--sort-by", "symbol"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with symbol sort should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Sorted by: symbol (ascending)"));
-
-    Ok(())
+fn test_list_filter_by_month() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--month", "5"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Month: 5"));
 }
 
 #[test]
-fn test_list_sort_by_amount_reverse() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--sort-by", "amount", "--reverse"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with amount sort reverse should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Sorted by: amount (descending)"));
-
-    Ok(())
+fn test_list_filter_by_amount_min() {
+    let env = list_fixture();
+
+    // MSFT (0.68) and GOOGL (1.20) qualify, AAPL (0.24, 0.25) does not
+    env.cmd()
+        .args(["div", "list", "--amount-min", "0.50"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Min Amount: $0.50"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("GOOGL"))
+        .stdout(contains("AAPL").not());
 }
 
 #[test]
-fn test_list_combined_filters() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--symbol", "AAPL", "--year", "2024", "--sort-by", "amount", "--reverse"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "List command with combined filters should succeed");
+fn test_list_filter_by_total_and_amount_range() {
+    let env = list_fixture();
+
+    // Totals: AAPL $24 and $50, MSFT $34, GOOGL $30, TSLA $33.75
+    env.cmd()
+        .args(["div", "list", "--total-min", "30", "--total-max", "40", "--amount-max", "1.00"])
+        .assert()
+        .success()
+        .stdout(contains("Max Amount: $1.00"))
+        .stdout(contains("Min Total: $30"))
+        .stdout(contains("Max Total: $40"))
+        .stdout(contains("Number of Payments: 2"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("TSLA"));
+
+    env.cmd()
+        .args(["div", "list", "--shares-min", "100"])
+        .assert()
+        .success()
+        .stdout(contains("Min Shares: 100"))
+        .stdout(contains("Number of Payments: 2"))
+        .stdout(contains("MSFT").not());
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Symbol: AAPL"));
-    assert!(stdout.contains("Year: 2024"));
-    assert!(stdout.contains("Sorted by: amount (descending)"));
-    assert!(stdout.contains("AAPL"));
-    assert!(!stdout.contains("MSFT"));
+#[test]
+fn test_list_rejects_inverted_total_range() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--total-min", "500", "--total-max", "100"])
+        .assert()
+        .failure()
+        .stderr(contains("--total-min (500) cannot be greater than --total-max (100)"));
+}
 
-    Ok(())
+#[test]
+fn test_list_filter_upcoming() {
+    let env = list_fixture();
+
+    // Only TSLA has future dates
+    env.cmd()
+        .args(["div", "list", "--upcoming"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Upcoming Only: Yes"))
+        .stdout(contains("TSLA"))
+        .stdout(contains("MSFT").not());
 }
 
 #[test]
-fn test_list_no_results() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
+fn test_list_sort_by_symbol() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--sort-by", "symbol"])
+        .assert()
+        .success()
+        .stdout(contains("Sorted by: symbol (ascending)"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--symbol", "NVDA"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_list_sort_by_amount_reverse() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--sort-by", "amount", "--reverse"])
+        .assert()
+        .success()
+        .stdout(contains("Sorted by: amount (descending)"));
+}
 
-    assert!(output.status.success(), "List command should succeed even with no results");
+#[test]
+fn test_list_sort_by_multiple_keys() {
+    let env = list_fixture();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("No dividends match the specified filters"));
+    let stdout = env.run_ok(&["div", "list", "--sort-by", "symbol,total:desc"]);
+    assert!(stdout.contains("Sorted by: symbol (ascending), total (descending)"));
 
-    Ok(())
+    // AAPL's $50.00 payment comes before its $24.00 one, and both before GOOGL
+    let larger = stdout.find("$50.00").unwrap();
+    let smaller = stdout.find("$24.00").unwrap();
+    let googl = stdout.find("GOOGL").unwrap();
+    assert!(larger < smaller && smaller < googl, "{}", stdout);
 }
 
 #[test]
-fn test_list_empty_database() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_list_rejects_unknown_sort_key() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--sort-by", "symbol,price:desc"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid sort field"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["list"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_holdings_list_sort_by_multiple_keys() {
+    let env = Fixture::new()
+        .holding_with_cost("KO", "10", "60")
+        .holding_with_cost("PEP", "6", "100")
+        .holding_with_cost("ABBV", "4", "150")
+        .build();
+
+    // KO, PEP and ABBV are each worth $600; ties fall back to symbol descending
+    let stdout = env.run_ok(&["holdings", "list", "--sort-by", "value:desc,symbol:desc"]);
+    let positions: Vec<_> = ["PEP", "KO", "ABBV"].iter().map(|s| stdout.find(s).unwrap()).collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{}", stdout);
+}
 
-    assert!(output.status.success(), "List command should succeed with empty database");
+#[test]
+fn test_list_export_csv_matches_listing() {
+    let env = list_fixture();
+    let csv_path = env.file("aapl.csv");
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "AAPL,MSFT", "--sort-by", "total:desc", "--export-csv"])
+        .arg(&csv_path)
+        .assert()
+        .success()
+        .stdout(contains("Exported 3 dividends to"));
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    let symbols_and_totals: Vec<_> = csv
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let fields: Vec<_> = line.split(',').collect();
+            (fields[0], fields[6])
+        })
+        .collect();
+    assert_eq!(symbols_and_totals, vec![("AAPL", "50.00"), ("MSFT", "34.00"), ("AAPL", "24.00")]);
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("No dividend records found. Use 'add' command to add some!"));
+#[test]
+fn test_list_combined_filters() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "AAPL", "--year", "2024", "--sort-by", "amount", "--reverse"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Symbol: AAPL"))
+        .stdout(contains("Year: 2024"))
+        .stdout(contains("Sorted by: amount (descending)"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT").not());
+}
 
-    Ok(())
+#[test]
+fn test_list_no_results() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "NVDA"])
+        .assert()
+        .success()
+        .stdout(contains("No dividends match the specified filters"));
 }
 
 #[test]
-fn test_list_date_range_filter() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
+fn test_list_empty_database() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("No dividend records found. Use 'add' command to add some!"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--date-start", "2024-03-01", "--date-end", "2024-05-31"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_list_date_range_filter() {
+    let env = list_fixture();
+
+    // MSFT (March) and AAPL (May) are in range, GOOGL (June) is not
+    env.cmd()
+        .args(["div", "list", "--date-start", "2024-03-01", "--date-end", "2024-05-31"])
+        .assert()
+        .success()
+        .stdout(contains("Applied Filters"))
+        .stdout(contains("Date Start: 2024-03-01"))
+        .stdout(contains("Date End: 2024-05-31"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("GOOGL").not());
+}
 
-    assert!(output.status.success(), "List command with date range should succeed");
+#[test]
+fn test_list_invalid_amount_min() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--amount-min", "invalid"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid minimum amount format"));
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Applied Filters:"));
-    assert!(stdout.contains("Date Start: 2024-03-01"));
-    assert!(stdout.contains("Date End: 2024-05-31"));
-    // Should show MSFT (March) and AAPL (May) but not AAPL (January) or GOOGL (June)
-    assert!(stdout.contains("MSFT"));
+#[test]
+fn test_list_filter_by_quarter() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--quarter", "Q1-2024"])
+        .assert()
+        .success()
+        .stdout(contains("Quarter: Q1-2024"))
+        .stdout(contains("Number of Payments: 2"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("GOOGL").not());
+}
 
-    Ok(())
+#[test]
+fn test_list_invalid_quarter() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--quarter", "Q5-2024"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid quarter. Use Q1, Q2, Q3, or Q4"));
 }
 
 #[test]
-fn test_list_invalid_amount_min() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
+fn test_list_trailing_twelve_months() {
+    let env = Fixture::new()
+        .dividend("AAPL", "yesterday", "today", "0.25", "100")
+        .dividend("MSFT", "2020-03-20", "2020-03-25", "0.68", "50")
+        .dividend("TSLA", "tomorrow", "next friday", "0.45", "75")
+        .build();
+
+    env.cmd()
+        .args(["div", "list", "--ttm"])
+        .assert()
+        .success()
+        .stdout(contains("Trailing 12 Months: Yes"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT").not())
+        .stdout(contains("TSLA").not());
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--amount-min", "invalid"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_list_invalid_date() {
+    let env = list_fixture();
+
+    env.cmd()
+        .args(["div", "list", "--date-start", "invalid-date"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid date format"));
+}
 
-    assert!(!output.status.success(), "List command with invalid amount should fail");
+#[test]
+fn test_list_json_output() {
+    let env = list_fixture();
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid minimum amount format"));
+    let stdout = env.run_ok(&["div", "list", "--symbol", "MSFT", "--output-format", "json"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout).expect("list output should be JSON");
 
-    Ok(())
+    assert_eq!(report["title"], "Dividend Payments");
+    let rows = &report["sections"][0]["blocks"][0]["table"]["rows"];
+    assert_eq!(rows.as_array().map(Vec::len), Some(1));
+    assert_eq!(rows[0][0], "MSFT");
 }
 
 #[test]
-fn test_list_invalid_date() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
+fn test_list_markdown_table_with_truncated_columns() {
+    let env = list_fixture();
+
+    let stdout = env.run_ok(&["div", "list", "--symbol", "MSFT", "--table-style", "markdown", "--max-column-width", "4"]);
+    assert!(stdout.contains("| MSFT |"));
+    assert!(stdout.contains("| Sym… |"));
+    assert!(stdout.contains("|------|"));
+    assert!(!stdout.contains("╭"));
+
+    env.cmd()
+        .args(["div", "list", "--max-column-width", "1"])
+        .assert()
+        .failure();
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["list", "--date-start", "invalid-date"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_list_fractional_drip_shares() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args([
+            "div",
+            "add",
+            "O",
+            "--ex-date",
+            "2024-01-31",
+            "--pay-date",
+            "2024-02-15",
+            "--amount",
+            "0.24",
+            "--shares",
+            "12.345678912",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Shares owned: 12.3457"))
+        .stdout(contains("Total dividend: $2.96"));
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("12.3457"))
+        .stdout(contains("12.345678912").not())
+        .stdout(contains("$2.96"));
+}
 
-    assert!(!output.status.success(), "List command with invalid date should fail");
+#[test]
+fn test_list_net_of_fees() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .command(&[
+            "div", "add", "TSM", "--ex-date", "2024-03-14", "--pay-date", "2024-04-11",
+            "--amount", "0.50", "--shares", "100", "--fee", "1.25",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Fees"))
+        .stdout(contains("Total Fees: $1.25"))
+        .stdout(contains("Net Dividends: $72.75"));
+
+    // Without fees the extra columns stay hidden
+    let output = env.run_ok(&["div", "list", "--symbol", "AAPL"]);
+    assert!(!output.contains("Net Dividends"));
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid date format"));
+#[test]
+fn test_list_net_of_withholding() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .command(&[
+            "div", "add", "NESN", "--ex-date", "2024-04-26", "--pay-date", "2024-04-30",
+            "--amount", "3.00", "--shares", "100", "--withholding", "105", "--country", "CH",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Withheld"))
+        .stdout(contains("Fees").not())
+        .stdout(contains("Total Dividends: $324.00"))
+        .stdout(contains("Total Withheld: $105.00"))
+        .stdout(contains("Net Dividends: $219.00"));
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[test]
+fn test_unknown_symbol_suggests_close_match() {
+    let env = Fixture::new()
+        .holding("MSFT", "50")
+        .dividend("MSFT", "2024-02-14", "2024-03-14", "0.75", "50")
+        .build();
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "MSTF"])
+        .assert()
+        .success()
+        .stdout(contains("No dividends match the specified filters. Did you mean MSFT?"));
+
+    env.cmd()
+        .args(["summary", "--symbol", "msf"])
+        .assert()
+        .success()
+        .stdout(contains("Did you mean MSFT?"));
+
+    env.cmd()
+        .args(["holdings", "history", "MSFF"])
+        .assert()
+        .failure()
+        .stderr(contains("No holding found for MSFF. Did you mean MSFT?"));
+
+    env.cmd()
+        .args(["div", "list", "--symbol", "XOM"])
+        .assert()
+        .success()
+        .stdout(contains("Did you mean").not());
+}
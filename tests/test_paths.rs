@@ -0,0 +1,124 @@
+mod common;
+
+use common::*;
+
+const AAPL_DIVIDEND: [&str; 11] = [
+    "div",
+    "add",
+    "AAPL",
+    "--ex-date",
+    "2024-01-15",
+    "--pay-date",
+    "2024-01-18",
+    "--amount",
+    "0.24",
+    "--shares",
+    "100",
+];
+
+#[test]
+fn test_data_dir_flag_with_spaces() {
+    let env = TestEnv::new();
+    let data_dir = env.file("my dividend data");
+
+    bin().arg("--data-dir").arg(&data_dir).args(AAPL_DIVIDEND).assert().success();
+
+    assert!(data_dir.join("dividends.json").exists());
+
+    bin()
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("AAPL"));
+}
+
+#[test]
+fn test_export_creates_missing_directories() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .build();
+    let csv_path = env.file("exports").join("q1 2024").join("dividends.csv");
+
+    env.cmd()
+        .args(["div", "export", "--output"])
+        .arg(&csv_path)
+        .assert()
+        .success()
+        .stdout(contains("Dividends exported to"));
+
+    let csv_content = std::fs::read_to_string(&csv_path).expect("CSV file should be created");
+    assert!(csv_content.contains("AAPL"));
+}
+
+#[test]
+fn test_data_export_suffix_keeps_directory() {
+    let env = Fixture::new()
+        .holding("AAPL", "100")
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .build();
+    let base = env.file("backups out").join("portfolio");
+
+    env.cmd()
+        .args(["data", "export", "--format", "csv", "--data-type", "all", "--output"])
+        .arg(&base)
+        .assert()
+        .success();
+
+    assert!(env.file("backups out").join("portfolio_dividends.csv").exists());
+    assert!(env.file("backups out").join("portfolio_holdings.csv").exists());
+}
+
+/// Verbatim paths (`\\?\C:\...`) are what `canonicalize` returns on Windows
+#[cfg(windows)]
+#[test]
+fn test_data_dir_flag_with_verbatim_unc_path() {
+    let env = TestEnv::new();
+    let data_dir = std::fs::canonicalize(env.path()).unwrap().join("unc data");
+    assert!(data_dir.to_string_lossy().starts_with(r"\\?\"));
+
+    bin().arg("--data-dir").arg(&data_dir).args(AAPL_DIVIDEND).assert().success();
+
+    assert!(data_dir.join("dividends.json").exists());
+}
+
+#[test]
+fn test_data_dir_flag_overrides_environment() {
+    let env = TestEnv::new();
+    let flag_dir = env.file("flag dir");
+
+    // Holdings used to ignore --data-dir and always read the environment
+    env.cmd()
+        .arg("--data-dir")
+        .arg(&flag_dir)
+        .args(["holdings", "add", "AAPL", "--shares", "10"])
+        .assert()
+        .success();
+
+    assert!(flag_dir.join("dividends.json").exists());
+    assert!(!env.file("dividends.json").exists());
+
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("No holdings found"));
+
+    env.cmd()
+        .arg("--data-dir")
+        .arg(&flag_dir)
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("AAPL"));
+}
+
+#[test]
+fn test_notifications_stored_in_data_dir() {
+    let env = TestEnv::new();
+
+    env.cmd().args(["alerts", "--clear"]).assert().success();
+
+    assert!(env.file("dividend_alerts.json").exists());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_premiums_show_in_combined_income_but_not_dividend_summary() {
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .build();
+
+    env.cmd()
+        .args(["premiums", "add", "ko", "62.50", "--date", "2024-05-10", "--contracts", "1", "--note", "Jun 65 call"])
+        .assert()
+        .success()
+        .stdout(contains("Recorded $62.50 premium on KO (2024-05-10)"))
+        .stdout(contains("no holding").not());
+
+    env.cmd()
+        .args(["premiums", "list", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Option Premiums for 2024"))
+        .stdout(contains("Jun 65 call"))
+        .stdout(contains("Total Premiums: $62.50"));
+
+    env.cmd()
+        .args(["premiums", "income", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Combined Income for 2024"))
+        .stdout(contains("Dividend Income: $48.50"))
+        .stdout(contains("Option Premiums: $62.50"))
+        .stdout(contains("Total Income: $111.00"));
+
+    env.cmd()
+        .args(["summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Total Dividend Income: $48.50"));
+}
+
+#[test]
+fn test_premiums_add_rejects_non_positive_amount() {
+    let env = Fixture::new().holding("KO", "100").build();
+
+    env.cmd()
+        .args(["premiums", "add", "KO", "0"])
+        .assert()
+        .failure()
+        .stderr(contains("Premium must be positive"));
+}
@@ -1,446 +1,401 @@
-use anyhow::Result;
-use tempfile::tempdir;
-use std::process::Command;
-use std::path::Path;
-
-fn get_binary_path() -> String {
-    "./target/debug/dividend-tracker".to_string()
-}
-
-fn setup_comprehensive_test_data(temp_dir: &Path) -> Result<()> {
-    // Add holdings first
-    let holdings_commands = vec![
-        vec!["holdings", "add", "AAPL", "--shares", "100"],
-        vec!["holdings", "add", "MSFT", "--shares", "50"],
-        vec!["holdings", "add", "GOOGL", "--shares", "25"],
-        vec!["holdings", "add", "TSLA", "--shares", "75"],
-    ];
-
-    for cmd_args in holdings_commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup holdings: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    // Add comprehensive dividend data spanning multiple years
-    let dividend_commands = vec![
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+/// Holdings plus several years of dividend history with different patterns
+fn comprehensive_fixture() -> TestEnv {
+    Fixture::new()
+        .holding("AAPL", "100")
+        .holding("MSFT", "50")
+        .holding("GOOGL", "25")
+        .holding("TSLA", "75")
         // AAPL - Regular quarterly dividends with growth pattern
-        vec!["add", "AAPL", "--ex-date", "2022-02-15", "--pay-date", "2022-02-22", "--amount", "0.22", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2022-05-15", "--pay-date", "2022-05-22", "--amount", "0.23", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2022-08-15", "--pay-date", "2022-08-22", "--amount", "0.23", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2022-11-15", "--pay-date", "2022-11-22", "--amount", "0.23", "--shares", "100", "--force"],
-
-        vec!["add", "AAPL", "--ex-date", "2023-02-15", "--pay-date", "2023-02-22", "--amount", "0.23", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-05-15", "--pay-date", "2023-05-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-08-15", "--pay-date", "2023-08-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-11-15", "--pay-date", "2023-11-22", "--amount", "0.24", "--shares", "100", "--force"],
-
-        vec!["add", "AAPL", "--ex-date", "2024-02-15", "--pay-date", "2024-02-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-05-15", "--pay-date", "2024-05-22", "--amount", "0.25", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-08-15", "--pay-date", "2024-08-22", "--amount", "0.25", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-11-15", "--pay-date", "2024-11-22", "--amount", "0.25", "--shares", "100", "--force"],
-
+        .dividend("AAPL", "2022-02-15", "2022-02-22", "0.22", "100")
+        .dividend("AAPL", "2022-05-15", "2022-05-22", "0.23", "100")
+        .dividend("AAPL", "2022-08-15", "2022-08-22", "0.23", "100")
+        .dividend("AAPL", "2022-11-15", "2022-11-22", "0.23", "100")
+        .dividend("AAPL", "2023-02-15", "2023-02-22", "0.23", "100")
+        .dividend("AAPL", "2023-05-15", "2023-05-22", "0.24", "100")
+        .dividend("AAPL", "2023-08-15", "2023-08-22", "0.24", "100")
+        .dividend("AAPL", "2023-11-15", "2023-11-22", "0.24", "100")
+        .dividend("AAPL", "2024-02-15", "2024-02-22", "0.24", "100")
+        .dividend("AAPL", "2024-05-15", "2024-05-22", "0.25", "100")
+        .dividend("AAPL", "2024-08-15", "2024-08-22", "0.25", "100")
+        .dividend("AAPL", "2024-11-15", "2024-11-22", "0.25", "100")
         // MSFT - Different payment schedule and amounts
-        vec!["add", "MSFT", "--ex-date", "2023-03-20", "--pay-date", "2023-03-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-06-20", "--pay-date", "2023-06-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-09-20", "--pay-date", "2023-09-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-12-20", "--pay-date", "2023-12-25", "--amount", "0.68", "--shares", "50", "--force"],
-
-        vec!["add", "MSFT", "--ex-date", "2024-03-20", "--pay-date", "2024-03-25", "--amount", "0.75", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-06-20", "--pay-date", "2024-06-25", "--amount", "0.75", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-09-20", "--pay-date", "2024-09-25", "--amount", "0.75", "--shares", "50", "--force"],
-
+        .dividend("MSFT", "2023-03-20", "2023-03-25", "0.68", "50")
+        .dividend("MSFT", "2023-06-20", "2023-06-25", "0.68", "50")
+        .dividend("MSFT", "2023-09-20", "2023-09-25", "0.68", "50")
+        .dividend("MSFT", "2023-12-20", "2023-12-25", "0.68", "50")
+        .dividend("MSFT", "2024-03-20", "2024-03-25", "0.75", "50")
+        .dividend("MSFT", "2024-06-20", "2024-06-25", "0.75", "50")
+        .dividend("MSFT", "2024-09-20", "2024-09-25", "0.75", "50")
         // GOOGL - Irregular payments
-        vec!["add", "GOOGL", "--ex-date", "2023-06-10", "--pay-date", "2023-06-15", "--amount", "1.20", "--shares", "25", "--force"],
-        vec!["add", "GOOGL", "--ex-date", "2024-06-10", "--pay-date", "2024-06-15", "--amount", "1.20", "--shares", "25", "--force"],
-
+        .dividend("GOOGL", "2023-06-10", "2023-06-15", "1.20", "25")
+        .dividend("GOOGL", "2024-06-10", "2024-06-15", "1.20", "25")
         // TSLA - Only recent data to test edge cases
-        vec!["add", "TSLA", "--ex-date", "2024-12-15", "--pay-date", "2024-12-20", "--amount", "0.50", "--shares", "75", "--force"],
-    ];
-
-    for cmd_args in dividend_commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup dividend data: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    Ok(())
+        .dividend("TSLA", "2024-12-15", "2024-12-20", "0.50", "75")
+        .build()
 }
 
-fn setup_minimal_test_data(temp_dir: &Path) -> Result<()> {
-    // Add minimal holdings
-    let output = Command::new(&get_binary_path())
-        .args(&["holdings", "add", "AAPL", "--shares", "100"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-        .output()?;
-
-    if !output.status.success() {
-        eprintln!("Failed to setup minimal holdings: {:?}", String::from_utf8_lossy(&output.stderr));
-    }
-
-    // Add minimal dividend data
-    let output = Command::new(&get_binary_path())
-        .args(&["add", "AAPL", "--ex-date", "2024-01-15", "--pay-date", "2024-01-18", "--amount", "0.25", "--shares", "100", "--force"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-        .output()?;
-
-    if !output.status.success() {
-        eprintln!("Failed to setup minimal dividend data: {:?}", String::from_utf8_lossy(&output.stderr));
-    }
-
-    Ok(())
+fn minimal_fixture() -> TestEnv {
+    Fixture::new()
+        .holding("AAPL", "100")
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.25", "100")
+        .build()
 }
 
 #[test]
-fn test_project_command_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Project help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Project future dividend income"));
-    assert!(stdout.contains("--method"));
-    assert!(stdout.contains("--growth-rate"));
-    assert!(stdout.contains("--monthly"));
-    assert!(stdout.contains("--export-csv"));
-    assert!(stdout.contains("--export-json"));
-
-    Ok(())
+fn test_project_command_help() {
+    bin()
+        .args(["project", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Project future dividend income"))
+        .stdout(contains("--method"))
+        .stdout(contains("--growth-rate"))
+        .stdout(contains("--monthly"))
+        .stdout(contains("--export-csv"))
+        .stdout(contains("--export-json"));
 }
 
 #[test]
-fn test_project_average_2_years_method() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Project command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Dividend Income Projections"));
-    assert!(stdout.contains("Projection Method: AverageYears(2)"));
-    assert!(stdout.contains("Growth Scenario: Moderate (5%)"));
-    assert!(stdout.contains("Projected Annual Income:"));
-    assert!(stdout.contains("Individual Stock Projections"));
-    assert!(stdout.contains("AAPL"));
-    assert!(stdout.contains("Projection Details"));
-    assert!(stdout.contains("Confidence Score:"));
-
-    Ok(())
+fn test_project_average_2_years_method() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Income Projections"))
+        .stdout(contains("Projection Method: AverageYears(2)"))
+        .stdout(contains("Growth Scenario: Moderate (5%)"))
+        .stdout(contains("Projected Annual Income:"))
+        .stdout(contains("Individual Stock Projections"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("Projection Details"))
+        .stdout(contains("Confidence Score:"));
 }
 
 #[test]
-fn test_project_average_3_years_method() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-3-years"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Project command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("AverageYears(3)"));
-    assert!(stdout.contains("Projected Annual Income:"));
-
-    Ok(())
+fn test_project_average_3_years_method() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-3-years"])
+        .assert()
+        .success()
+        .stdout(contains("AverageYears(3)"))
+        .stdout(contains("Projected Annual Income:"));
 }
 
 #[test]
-fn test_project_current_yield_method() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "current-yield"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Project command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Projection Method: CurrentYield"));
-    assert!(stdout.contains("Projected Annual Income:"));
-
-    Ok(())
+fn test_project_current_yield_method() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "current-yield"])
+        .assert()
+        .success()
+        .stdout(contains("Projection Method: CurrentYield"))
+        .stdout(contains("Projected Annual Income:"));
 }
 
 #[test]
-fn test_project_last_12_months_method() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "last-12-months"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Project command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Projection Method: Last12Months"));
-    // Note: This might show $0.00 due to date filtering, which is expected
-
-    Ok(())
+fn test_project_last_12_months_method() {
+    let env = comprehensive_fixture();
+
+    // The total may be $0.00 once the fixture dates fall out of the window
+    env.cmd()
+        .args(["project", "--method", "last-12-months"])
+        .assert()
+        .success()
+        .stdout(contains("Projection Method: Last12Months"));
 }
 
 #[test]
-fn test_project_growth_scenarios() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    // Test conservative growth
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--growth-rate", "conservative"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Conservative growth should succeed");
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Conservative (2%)"));
-
-    // Test optimistic growth
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--growth-rate", "optimistic"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Optimistic growth should succeed");
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Optimistic (9%)"));
-
-    // Test custom growth rate
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--growth-rate", "7.5%"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Custom growth should succeed");
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Custom (") && stdout.contains("%)"));
-
-    Ok(())
+fn test_project_growth_scenarios() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--growth-rate", "conservative"])
+        .assert()
+        .success()
+        .stdout(contains("Conservative (2%)"));
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--growth-rate", "optimistic"])
+        .assert()
+        .success()
+        .stdout(contains("Optimistic (9%)"));
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--growth-rate", "7.5%"])
+        .assert()
+        .success()
+        .stdout(contains("Custom (").and(contains("%)")));
 }
 
 #[test]
-fn test_project_monthly_breakdown() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--monthly"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Monthly breakdown should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Monthly Projected Cash Flow"));
-    assert!(stdout.contains("January"));
-    assert!(stdout.contains("February"));
-    assert!(stdout.contains("March"));
-    assert!(stdout.contains("Top Contributors"));
-
-    Ok(())
+fn test_project_monthly_breakdown() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--monthly"])
+        .assert()
+        .success()
+        .stdout(contains("Monthly Projected Cash Flow"))
+        .stdout(contains("January"))
+        .stdout(contains("February"))
+        .stdout(contains("March"))
+        .stdout(contains("Top Contributors"));
 }
 
 #[test]
-fn test_project_csv_export() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let csv_path = temp_dir.path().join("test_projections.csv");
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--export-csv", csv_path.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "CSV export should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("exported to"));
-
-    // Verify CSV file was created and has expected content
-    assert!(csv_path.exists(), "CSV file should be created");
-    let csv_content = std::fs::read_to_string(&csv_path)?;
+fn test_project_csv_export() {
+    let env = comprehensive_fixture();
+    let csv_path = env.file("test_projections.csv");
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--export-csv"])
+        .arg(&csv_path)
+        .assert()
+        .success()
+        .stdout(contains("exported to"));
+
+    let csv_content = std::fs::read_to_string(&csv_path).expect("CSV file should be created");
     assert!(csv_content.contains("Type,Symbol,Month,Amount,Details"));
     assert!(csv_content.contains("Summary,Portfolio,Annual"));
     assert!(csv_content.contains("Stock,AAPL"));
     assert!(csv_content.contains("Monthly,Portfolio"));
     assert!(csv_content.contains("Metadata,Method"));
-
-    Ok(())
 }
 
 #[test]
-fn test_project_json_export() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let json_path = temp_dir.path().join("test_projections.json");
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--export-json", json_path.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "JSON export should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("exported to"));
-
-    // Verify JSON file was created and has expected content
-    assert!(json_path.exists(), "JSON file should be created");
-    let json_content = std::fs::read_to_string(&json_path)?;
-
-    // Parse as JSON to verify structure
-    let json_value: serde_json::Value = serde_json::from_str(&json_content)?;
+fn test_project_json_export() {
+    let env = comprehensive_fixture();
+    let json_path = env.file("test_projections.json");
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--export-json"])
+        .arg(&json_path)
+        .assert()
+        .success()
+        .stdout(contains("exported to"));
+
+    let json_content = std::fs::read_to_string(&json_path).expect("JSON file should be created");
+    let json_value: serde_json::Value = serde_json::from_str(&json_content).expect("export should be valid JSON");
     assert!(json_value["year"].is_number());
     assert!(json_value["total_projected_income"].is_string());
     assert!(json_value["stock_projections"].is_array());
     assert!(json_value["monthly_breakdown"].is_array());
     assert!(json_value["metadata"].is_object());
-
-    Ok(())
 }
 
 #[test]
-fn test_project_specific_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--year", "2027"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Specific year should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Target Year: 2027"));
-
-    Ok(())
+fn test_project_specific_year() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--year", "2027"])
+        .assert()
+        .success()
+        .stdout(contains("Target Year: 2027"));
 }
 
 #[test]
-fn test_project_invalid_method() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_minimal_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "invalid-method"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid method should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid projection method"));
-
-    Ok(())
+fn test_project_blend_current_year() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--blend", "--monthly"])
+        .assert()
+        .success()
+        .stdout(contains("Year-to-Date Blend"))
+        .stdout(contains("Full-Year Estimate:"))
+        .stdout(contains("YTD Progress:"))
+        .stdout(contains("Projected"));
 }
 
 #[test]
-fn test_project_invalid_growth_rate() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_minimal_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years", "--growth-rate", "invalid"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid growth rate should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid growth rate"));
-
-    Ok(())
+fn test_project_blend_rejects_other_years() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--blend", "--year", "2001"])
+        .assert()
+        .failure()
+        .stderr(contains("--blend only applies to the current year"));
 }
 
 #[test]
-fn test_project_no_holdings() -> Result<()> {
-    let temp_dir = tempdir()?;
-    // Don't setup any data
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    // Should handle gracefully - either succeed with empty results or provide helpful message
-    // The exact behavior depends on implementation details
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    // Should contain some indication of no data
-    assert!(stdout.contains("Projection") || stderr.contains("No") || stdout.contains("$0.00"));
-
-    Ok(())
+fn test_project_invalid_method() {
+    let env = minimal_fixture();
+
+    // Rejected by the argument parser, which lists the valid methods
+    env.cmd()
+        .args(["project", "--method", "invalid-method"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid value 'invalid-method'"))
+        .stderr(contains("average-2-years"));
 }
 
 #[test]
-fn test_project_no_dividend_history() -> Result<()> {
-    let temp_dir = tempdir()?;
-
-    // Add holdings but no dividend history
-    let output = Command::new(&get_binary_path())
-        .args(&["holdings", "add", "NEWSTOCK", "--shares", "100"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success());
-
-    let output = Command::new(&get_binary_path())
-        .args(&["project"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+fn test_project_invalid_growth_rate() {
+    let env = minimal_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years", "--growth-rate", "invalid"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid growth rate"));
+}
 
-    // Should handle gracefully
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Projection") || stdout.contains("$0.00"));
+#[test]
+fn test_project_no_holdings() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .arg("project")
+        .assert()
+        .success()
+        .stdout(contains("No holdings found"));
+}
 
-    Ok(())
+#[test]
+fn test_project_no_dividend_history() {
+    let env = Fixture::new().holding("NEWSTOCK", "100").build();
+
+    env.cmd()
+        .arg("project")
+        .assert()
+        .success()
+        .stdout(contains("No dividend history found"));
 }
 
 #[test]
-fn test_project_confidence_scoring() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_comprehensive_test_data(temp_dir.path())?;
+fn test_project_confidence_scoring() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "--method", "average-2-years"])
+        .assert()
+        .success()
+        .stdout(contains("Confidence Score:"))
+        .stdout(contains("confidence"))
+        .stdout(contains("%"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["project", "--method", "average-2-years"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_project_warns_when_large_holdings_are_excluded() {
+    let env = Fixture::new()
+        .holding("AAPL", "10")
+        .holding("NEWCO", "90")
+        .dividend("AAPL", "2024-02-15", "2024-02-22", "0.24", "10")
+        .build();
+
+    env.cmd()
+        .arg("project")
+        .assert()
+        .success()
+        .stdout(contains("Income is under-reported: 90.0% of your holdings (NEWCO)"))
+        .stdout(contains("Excluded Share of Holdings: 90.0%"));
+}
 
-    assert!(output.status.success(), "Project command should succeed");
+#[test]
+fn test_project_includes_forward_dividend_override() {
+    let env = Fixture::new()
+        .holding("AAPL", "10")
+        .holding("NEWCO", "100")
+        .dividend("AAPL", "2024-02-15", "2024-02-22", "0.24", "10")
+        .command(&["holdings", "set-forward-dividend", "NEWCO", "1.48", "--frequency", "quarterly"])
+        .build();
+
+    let stdout = env.run_ok(&["project", "--monthly"]);
+    assert!(stdout.contains("NEWCO"));
+    assert!(stdout.contains("$148.00"));
+    assert!(!stdout.contains("Stocks Excluded"));
+
+    env.cmd()
+        .args(["holdings", "set-forward-dividend", "NEWCO", "--clear"])
+        .assert()
+        .success()
+        .stdout(contains("Cleared forward dividend for NEWCO"));
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Confidence Score:"));
+#[test]
+fn test_set_forward_dividend_requires_existing_holding() {
+    let env = minimal_fixture();
+
+    env.cmd()
+        .args(["holdings", "set-forward-dividend", "MISSING", "1.00"])
+        .assert()
+        .failure()
+        .stderr(contains("No holding found for MISSING"));
+}
 
-    // Should have high confidence with comprehensive data
-    assert!(stdout.contains("confidence") || stdout.contains("Confidence"));
-    // Verify percentage is shown
-    assert!(stdout.contains("%"));
+#[test]
+fn test_project_review_against_saved_plan() {
+    let env = comprehensive_fixture();
+
+    env.cmd()
+        .args(["project", "review", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("No plan saved for 2024. Save one with 'project --year 2024 --save-plan'."));
+
+    env.cmd()
+        .args(["project", "--year", "2024", "--save-plan"])
+        .assert()
+        .success()
+        .stdout(contains("as the plan for 2024"))
+        .stdout(contains("project review --year 2024"));
+
+    let stdout = env.run_ok(&["project", "review", "--year", "2024"]);
+    assert!(stdout.contains("Plan vs Actual for 2024"));
+    assert!(stdout.contains("Months Complete: 12 of 12"));
+    assert!(stdout.contains("Actual to Date: $"));
+    assert!(stdout.contains("Behind Plan") || stdout.contains("Ahead of Plan"));
+    assert!(stdout.contains("$24.00"));
+
+    env.cmd()
+        .args(["project", "--year", "2024", "--save-plan"])
+        .assert()
+        .success()
+        .stdout(contains("Replaced the plan saved on"));
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[test]
+fn test_project_frequency_override_corrects_special_dividend() {
+    // Quarterly payer whose one special dividend breaks up the schedule
+    let mut fixture = Fixture::new().holding("SPEC", "10");
+    for date in ["2024-02-15", "2024-05-15", "2024-08-15", "2024-11-15", "2025-02-14", "2025-05-15", "2025-08-15"] {
+        fixture = fixture.dividend("SPEC", date, date, "0.50", "10");
+    }
+    let env = fixture.dividend("SPEC", "2025-06-02", "2025-06-10", "5.00", "10").build();
+
+    env.cmd()
+        .args(["project", "--year", "2027"])
+        .assert()
+        .success()
+        .stdout(contains("Irregular"));
+    env.cmd()
+        .args(["project", "--year", "2027", "--frequency-override", "spec=quarterly"])
+        .assert()
+        .success()
+        .stdout(contains("Quarterly"))
+        .stdout(contains("Irregular").not());
+
+    env.cmd()
+        .args(["project", "--frequency-override", "SPEC"])
+        .assert()
+        .failure()
+        .stderr(contains("Use SYMBOL=FREQUENCY"));
+    env.cmd()
+        .args(["calendar", "--frequency-override", "SPEC=annual"])
+        .assert()
+        .failure()
+        .stderr(contains("--update"));
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_report_annual_statement() {
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .command(&["cash", "enable", "--since", "2024-01-01"])
+        .dividend("KO", "2023-03-14", "2023-04-01", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("KO", "2024-06-14", "2024-07-01", "0.485", "100")
+        .command(&[
+            "div", "add", "NESN", "--ex-date", "2024-04-22", "--pay-date", "2024-04-26", "--amount", "3.00",
+            "--shares", "40", "--fee", "2",
+        ])
+        .command(&["cash", "reinvest", "50", "--symbol", "KO", "--date", "2024-07-02"])
+        .build();
+
+    env.cmd()
+        .args(["report", "annual", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Income Statement for 2024"))
+        .stdout(contains("Gross Dividends: $217.00"))
+        .stdout(contains("Fees: -$2.00"))
+        .stdout(contains("Net Received: $215.00"))
+        .stdout(contains("Reinvested: $50.00"))
+        .stdout(contains("Estimated Tax: -$32.55"))
+        .stdout(contains("Run-Rate at Year End"))
+        .stdout(contains("not over yet").not());
+
+    env.cmd()
+        .args(["report", "annual", "--year", "2019"])
+        .assert()
+        .success()
+        .stdout(contains("No dividends were paid in 2019."));
+}
+
+#[test]
+fn test_report_giving_schedule_and_export() {
+    let env = Fixture::new()
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("KO", "2024-06-14", "2024-07-01", "0.485", "100")
+        .build();
+    let csv_path = env.file("giving.csv");
+
+    env.cmd()
+        .args([
+            "report", "giving", "--pct", "10", "--year", "2024", "--by", "quarter", "--export-csv",
+            csv_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("10% of 2024 income"))
+        .stdout(contains("Q3-2024"))
+        .stdout(contains("Total Giving: $9.70"))
+        .stdout(contains("Giving schedule exported"));
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.starts_with("Period,Income,Percent,Giving"));
+    assert!(csv.contains("Q2-2024,48.50,10,4.85"));
+    assert!(csv.contains("Total,97.00,10,9.70"));
+
+    env.cmd()
+        .args(["report", "giving", "--pct", "150"])
+        .assert()
+        .failure()
+        .stderr(contains("between 0 and 100"));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_rpc_answers_requests_from_stdin() {
+    let env = Fixture::new().holding("KO", "100").build();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"add","params":{"symbol":"KO","ex_date":"2024-06-14","pay_date":"2024-07-01","amount":"0.485","shares":"100"}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"list","params":{"year":2024}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"summary"}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"list","params":{"bogus":true}}"#,
+    ]
+    .join("\n");
+
+    env.cmd()
+        .arg("--rpc")
+        .write_stdin(requests)
+        .assert()
+        .success()
+        .stdout(contains(r#""id":1,"jsonrpc":"2.0","result":{"#))
+        .stdout(contains(r#""count":1"#))
+        .stdout(contains(r#""title":"Portfolio Summary & Analytics""#))
+        .stdout(contains(r#""code":-32602,"message":"unexpected argument '--bogus' found""#))
+        .stdout(contains("Adding dividend record").not());
+
+    // The dividend added over RPC was saved
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("KO"));
+
+    env.cmd().args(["--rpc", "summary"]).assert().failure();
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_run_script_saves_once_and_rolls_back_on_failure() {
+    let env = TestEnv::new();
+    let script = env.file("seed.dtx");
+    fs::write(
+        &script,
+        "# Seed the portfolio\n\
+         dividend-tracker holdings add KO --shares 100\n\
+         div add KO --ex-date 2024-03-14 --pay-date 2024-04-01 --amount 0.485 --shares 100\n\
+         div add KO --ex-date 2024-06-14 --pay-date 2024-07-01 --amount 0.485 --shares 100 --account 'Roth IRA'\n\
+         summary\n",
+    )
+    .unwrap();
+
+    env.cmd()
+        .args(["run", script.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("[3] div add KO"))
+        .stdout(contains("Total Dividend Income: $97.00"))
+        .stdout(contains("Ran 4 commands and saved the changes"));
+
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("2024-06-14"));
+    let events = fs::read_to_string(env.file("events.jsonl")).unwrap();
+    assert_eq!(events.matches("dividend_added").count(), 2);
+
+    // The second line is a duplicate, so the first must not be saved either
+    fs::write(
+        &script,
+        "div add KO --ex-date 2024-09-13 --pay-date 2024-10-01 --amount 0.485 --shares 100\n\
+         div add KO --ex-date 2024-03-14 --pay-date 2024-04-01 --amount 0.485 --shares 100\n",
+    )
+    .unwrap();
+    env.cmd()
+        .args(["run", script.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("Line 2 failed"))
+        .stderr(contains("no changes were saved"));
+    env.cmd()
+        .args(["div", "list"])
+        .assert()
+        .success()
+        .stdout(contains("2024-09-13").not());
+
+    // Bad lines are rejected before anything runs
+    fs::write(&script, "div list\nserve --port 9000\n").unwrap();
+    env.cmd()
+        .args(["run", script.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("Line 2: serve runs until interrupted"));
+}
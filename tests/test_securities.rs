@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+/// A command reading `securities.toml` from inside the test's directory
+fn with_securities(env: &TestEnv) -> assert_cmd::Command {
+    let mut cmd = env.cmd();
+    cmd.env("HOME", env.path())
+        .env("XDG_CONFIG_HOME", env.file("config"))
+        .env_remove("ALPHA_VANTAGE_API_KEY");
+    cmd
+}
+
+fn write_securities(env: &TestEnv, contents: &str) {
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("securities.toml"), contents).unwrap();
+}
+
+#[test]
+fn test_pinned_names_sectors_and_tax_classification_are_used() {
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .holding("O", "50")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("O", "2024-03-28", "2024-04-15", "0.257", "50")
+        .build();
+
+    // Nothing pinned yet: no names and no name or sector columns
+    with_securities(&env)
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Sector").not());
+
+    write_securities(
+        &env,
+        "[ko]\nname = \"The Coca-Cola Company\"\nsector = \"Consumer Staples\"\n\n\
+         [O]\nsector = \"Real Estate\"\ntax_classification = \"non-qualified\"\n",
+    );
+
+    with_securities(&env)
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(contains("The Coca-Cola Company"));
+    with_securities(&env)
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("Sector"))
+        .stdout(contains("Consumer Staples"))
+        .stdout(contains("Real Estate"));
+    with_securities(&env)
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Non-Qualified Dividends │ $12.85"));
+}
+
+#[test]
+fn test_invalid_securities_file_is_reported() {
+    let env = Fixture::new().holding("KO", "100").build();
+    write_securities(&env, "[KO]\nfrequency = \"weekly\"\n");
+
+    with_securities(&env)
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stderr(contains("securities.toml is invalid"))
+        .stderr(contains("use monthly, quarterly, semi-annual or annual"));
+    with_securities(&env)
+        .args(["configure", "validate"])
+        .assert()
+        .failure()
+        .stderr(contains("securities.toml is invalid"));
+}
@@ -1,303 +1,537 @@
-use anyhow::Result;
-use tempfile::tempdir;
-use std::process::Command;
-
-fn get_binary_path() -> String {
-    "./target/debug/dividend-tracker".to_string()
+mod common;
+
+use chrono::{Duration, Local};
+use common::*;
+use predicates::prelude::*;
+
+/// Dividends with various dates and amounts for analytics testing
+fn summary_fixture() -> TestEnv {
+    Fixture::new()
+        .dividend("AAPL", "2024-01-15", "2024-01-18", "0.24", "100")
+        .dividend("AAPL", "2024-04-15", "2024-04-18", "0.25", "100")
+        .dividend("MSFT", "2024-03-20", "2024-03-25", "0.68", "50")
+        .dividend("MSFT", "2024-06-20", "2024-06-25", "0.70", "50")
+        .dividend("GOOGL", "2024-06-10", "2024-06-15", "1.20", "25")
+        .dividend("TSLA", "2023-12-15", "2023-12-20", "0.45", "75")
+        .build()
 }
 
-fn setup_test_data(temp_dir: &std::path::Path) -> Result<()> {
-    // Add test dividends with various dates and amounts for analytics testing
-    let commands = vec![
-        vec!["add", "AAPL", "--ex-date", "2024-01-15", "--pay-date", "2024-01-18", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-04-15", "--pay-date", "2024-04-18", "--amount", "0.25", "--shares", "100", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-03-20", "--pay-date", "2024-03-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-06-20", "--pay-date", "2024-06-25", "--amount", "0.70", "--shares", "50", "--force"],
-        vec!["add", "GOOGL", "--ex-date", "2024-06-10", "--pay-date", "2024-06-15", "--amount", "1.20", "--shares", "25", "--force"],
-        vec!["add", "TSLA", "--ex-date", "2023-12-15", "--pay-date", "2023-12-20", "--amount", "0.45", "--shares", "75", "--force"],
-    ];
-
-    for cmd_args in commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup test data: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    Ok(())
+#[test]
+fn test_summary_basic() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .arg("summary")
+        .assert()
+        .success()
+        .stdout(contains("Portfolio Summary & Analytics"))
+        .stdout(contains("Basic Summary"))
+        .stdout(contains("Total Dividend Income:"))
+        .stdout(contains("Total Payments: 6"))
+        .stdout(contains("Unique Stocks: 4"))
+        .stdout(contains("Average Payment:"));
 }
 
 #[test]
-fn test_summary_basic() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary command should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Portfolio Summary & Analytics"));
-    assert!(stdout.contains("Basic Summary"));
-    assert!(stdout.contains("Total Dividend Income:"));
-    assert!(stdout.contains("Total Payments:"));
-    assert!(stdout.contains("Unique Stocks:"));
-    assert!(stdout.contains("Average Payment:"));
-
-    Ok(())
+fn test_summary_symbol_filter() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--year", "2024", "--symbol", "aapl,msft"])
+        .assert()
+        .success()
+        .stdout(contains("Total Payments: 4"))
+        .stdout(contains("Unique Stocks: 2"));
+
+    env.cmd()
+        .args(["summary", "--symbol", "NVDA"])
+        .assert()
+        .success()
+        .stdout(contains("No dividend records found for NVDA."));
 }
 
 #[test]
-fn test_summary_monthly() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--monthly", "--year", "2024"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with monthly breakdown should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Monthly Breakdown"));
-    assert!(stdout.contains("Year: 2024"));
-    assert!(stdout.contains("Month"));
-    assert!(stdout.contains("January"));
-    assert!(stdout.contains("March"));
-    assert!(stdout.contains("April"));
-    assert!(stdout.contains("June"));
-
-    Ok(())
+fn test_summary_net_of_fees() {
+    let env = Fixture::new()
+        .dividend("AAPL", "2024-02-09", "2024-02-15", "0.24", "100")
+        .command(&[
+            "div", "add", "TSM", "--ex-date", "2024-03-14", "--pay-date", "2024-04-11",
+            "--amount", "0.50", "--shares", "100", "--fee", "1.25",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Total Dividend Income: $74"))
+        .stdout(contains("Fees Deducted: $1.25"))
+        .stdout(contains("Net Income: $72.75"));
 }
 
 #[test]
-fn test_summary_top_payers() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--top-payers", "3"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with top payers should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Top 3 Dividend Payers"));
-    assert!(stdout.contains("Rank"));
-    assert!(stdout.contains("Symbol"));
-    assert!(stdout.contains("Total"));
-    assert!(stdout.contains("Payments"));
-
-    Ok(())
+fn test_summary_merge_household() {
+    let env = summary_fixture();
+    let partner = Fixture::new()
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "200")
+        .build();
+    let partner_dir = format!("Partner={}", partner.path().display());
+
+    env.cmd()
+        .args(["summary", "--year", "2024", "--merge", &partner_dir])
+        .assert()
+        .success()
+        .stdout(contains("Total Payments: 6"))
+        .stdout(contains("Unique Stocks: 4"))
+        .stdout(contains("Household Breakdown"))
+        .stdout(contains("Partner"))
+        .stdout(contains("Combined"));
+
+    // Neither data directory is changed by the merge
+    let output = env.run_ok(&["summary", "--year", "2024"]);
+    assert!(output.contains("Total Payments: 5"));
+    assert!(!output.contains("Household Breakdown"));
+    let output = partner.run_ok(&["summary", "--year", "2024"]);
+    assert!(output.contains("Total Payments: 1"));
+
+    env.cmd()
+        .args(["summary", "--merge", "/nonexistent/dividend-data"])
+        .assert()
+        .failure()
+        .stderr(contains("Data directory not found"));
 }
 
 #[test]
-fn test_summary_all_analytics() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--all"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with all analytics should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Portfolio Summary & Analytics"));
-    assert!(stdout.contains("Basic Summary"));
-    assert!(stdout.contains("Growth Analysis"));
-    assert!(stdout.contains("Dividend Frequency Analysis"));
-    assert!(stdout.contains("Dividend Consistency Analysis"));
-    assert!(stdout.contains("Yield Analysis"));
-
-    Ok(())
+fn test_summary_monthly() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--monthly", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Monthly Breakdown"))
+        .stdout(contains("Year: 2024"))
+        .stdout(contains("Month"))
+        .stdout(contains("January"))
+        .stdout(contains("March"))
+        .stdout(contains("April"))
+        .stdout(contains("June"));
 }
 
 #[test]
-fn test_summary_growth_analysis() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--growth"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with growth analysis should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Growth Analysis"));
-    // Should show insufficient data message since we don't have enough years
-    assert!(stdout.contains("Insufficient data") || stdout.contains("Growth Analysis"));
-
-    Ok(())
+fn test_summary_top_payers() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--top-payers", "3"])
+        .assert()
+        .success()
+        .stdout(contains("Top 3 Dividend Payers"))
+        .stdout(contains("Rank"))
+        .stdout(contains("Symbol"))
+        .stdout(contains("Total"))
+        .stdout(contains("Payments"));
 }
 
 #[test]
-fn test_summary_frequency_analysis() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--frequency"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with frequency analysis should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Dividend Frequency Analysis"));
-    assert!(stdout.contains("AAPL") || stdout.contains("MSFT") || stdout.contains("GOOGL"));
-
-    Ok(())
+fn test_summary_all_analytics() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--all"])
+        .assert()
+        .success()
+        .stdout(contains("Portfolio Summary & Analytics"))
+        .stdout(contains("Basic Summary"))
+        .stdout(contains("Growth Analysis"))
+        .stdout(contains("Dividend Frequency Analysis"))
+        .stdout(contains("Dividend Consistency Analysis"))
+        .stdout(contains("Yield Analysis"));
 }
 
 #[test]
-fn test_summary_consistency_analysis() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--consistency"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with consistency analysis should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Dividend Consistency Analysis"));
-    assert!(stdout.contains("Portfolio Consistency Score"));
-
-    Ok(())
+fn test_summary_growth_analysis() {
+    let env = summary_fixture();
+
+    // 2023 and 2024 data is enough for a year-over-year comparison
+    env.cmd()
+        .args(["summary", "--growth"])
+        .assert()
+        .success()
+        .stdout(contains("Year-over-Year Growth Analysis"))
+        .stdout(contains("Total Growth Rate:"));
 }
 
 #[test]
-fn test_summary_yield_analysis() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--yield-analysis"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with yield analysis should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Yield Analysis"));
-    // Should show message about no holdings with cost basis
-    assert!(stdout.contains("No holdings with cost basis found") || stdout.contains("Yield Analysis"));
-
-    Ok(())
+fn test_summary_growth_by_symbol() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--growth", "--by-symbol"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend per Share Growth by Symbol"))
+        .stdout(contains("CAGR"))
+        .stdout(contains("$0.4900"));
 }
 
 #[test]
-fn test_summary_csv_export() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let csv_file = temp_dir.path().join("test_export.csv");
+fn test_summary_consistency_explain() {
+    let env = Fixture::new()
+        .dividend("KO", "2023-03-14", "2023-04-01", "0.46", "100")
+        .dividend("KO", "2023-06-14", "2023-07-01", "0.46", "100")
+        .dividend("KO", "2023-12-14", "2024-01-02", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .build();
+
+    env.cmd()
+        .args(["summary", "--consistency", "--explain", "KO"])
+        .assert()
+        .success()
+        .stdout(contains("Consistency Explanation: KO"))
+        .stdout(contains("Mean Interval:"))
+        .stdout(contains("Standard Deviation:"))
+        .stdout(contains("1 payment(s) likely missing"));
+
+    env.cmd()
+        .args(["summary", "--consistency", "--explain", "MSFT"])
+        .assert()
+        .failure()
+        .stderr(contains("at least 3 are needed"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--export-csv", csv_file.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_summary_frequency_analysis() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--frequency"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Frequency Analysis"))
+        .stdout(contains("AAPL").or(contains("MSFT")).or(contains("GOOGL")));
+}
 
-    assert!(output.status.success(), "Summary with CSV export should succeed");
+#[test]
+fn test_summary_consistency_analysis() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--consistency"])
+        .assert()
+        .success()
+        .stdout(contains("Dividend Consistency Analysis"))
+        .stdout(contains("Portfolio Consistency Score"));
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Analytics exported to"));
+#[test]
+fn test_summary_yield_analysis() {
+    let env = summary_fixture();
+
+    // No holdings with cost basis in the fixture
+    env.cmd()
+        .args(["summary", "--yield-analysis"])
+        .assert()
+        .success()
+        .stdout(contains("Yield Analysis"))
+        .stdout(contains("No holdings with cost basis found"));
+}
 
-    // Check that CSV file was created and has content
-    assert!(csv_file.exists(), "CSV file should be created");
-    let csv_content = std::fs::read_to_string(&csv_file)?;
+#[test]
+fn test_summary_csv_export() {
+    let env = summary_fixture();
+    let csv_file = env.file("test_export.csv");
+
+    env.cmd()
+        .args(["summary", "--export-csv"])
+        .arg(&csv_file)
+        .assert()
+        .success()
+        .stdout(contains("Analytics exported to"));
+
+    let csv_content = std::fs::read_to_string(&csv_file).expect("CSV file should be created");
     assert!(csv_content.contains("Total Dividends"));
     assert!(csv_content.contains("Total Payments"));
     assert!(csv_content.contains("Unique Symbols"));
-
-    Ok(())
 }
 
 #[test]
-fn test_summary_year_filter() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--year", "2024"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with year filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Year: 2024"));
-    // Should not include 2023 TSLA dividend
-    assert!(!stdout.contains("2023"));
-
-    Ok(())
+fn test_summary_year_filter() {
+    let env = summary_fixture();
+
+    // The 2023 TSLA dividend is excluded
+    env.cmd()
+        .args(["summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Year: 2024"))
+        .stdout(contains("2023").not());
 }
 
 #[test]
-fn test_summary_quarter_filter() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--quarter", "Q1-2024"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Summary with quarter filter should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Quarter: Q1-2024"));
+fn test_summary_quarter_filter() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--quarter", "Q1-2024"])
+        .assert()
+        .success()
+        .stdout(contains("Quarter: Q1-2024"));
+}
 
-    Ok(())
+#[test]
+fn test_summary_empty_database() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .arg("summary")
+        .assert()
+        .success()
+        .stdout(contains("Portfolio Summary & Analytics"))
+        .stdout(contains("No dividend records found. Use 'add' command to add some dividends first!"));
 }
 
 #[test]
-fn test_summary_empty_database() -> Result<()> {
-    let temp_dir = tempdir()?;
+fn test_summary_html_output() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--output-format", "html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<!DOCTYPE html>"))
+        .stdout(contains("<h2>Basic Summary</h2>"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["summary"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_summary_monthly_goal() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--year", "2024", "--monthly", "--goal", "30"])
+        .assert()
+        .success()
+        .stdout(contains("Monthly Goal: $30.00"))
+        .stdout(contains("Months at Goal: 2 of 12"))
+        .stdout(contains("Cumulative Deficit: -$212.00"))
+        .stdout(contains("Vs Goal"))
+        .stdout(contains("+$35.00"));
+
+    env.cmd().args(["summary", "--goal", "30"]).assert().failure();
+}
 
-    assert!(output.status.success(), "Summary should succeed with empty database");
+#[test]
+fn test_summary_markdown_output() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--output-format", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# Portfolio Summary & Analytics"))
+        .stdout(contains("## Basic Summary"))
+        .stdout(contains("- **Total Dividend Income:** $181.75"));
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Portfolio Summary & Analytics"));
-    assert!(stdout.contains("No dividend records found. Use 'add' command to add some dividends first!"));
+#[test]
+fn test_summary_invalid_quarter() {
+    let env = summary_fixture();
+
+    env.cmd()
+        .args(["summary", "--quarter", "invalid-quarter"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid quarter. Use Q1, Q2, Q3, or Q4"));
+}
 
-    Ok(())
+#[test]
+fn test_summary_run_rate_history() {
+    let today = Local::now().naive_local().date();
+    let ex_date = |days_ago: i64| (today - Duration::days(days_ago)).format("%Y-%m-%d").to_string();
+    let (first, second, third) = (ex_date(190), ex_date(100), ex_date(9));
+
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .dividend("KO", &first, &first, "0.46", "100")
+        .dividend("KO", &second, &second, "0.485", "100")
+        .dividend("KO", &third, &third, "0.51", "100")
+        .build();
+
+    env.cmd()
+        .args(["summary", "--run-rate-history"])
+        .assert()
+        .success()
+        .stdout(contains("Forward Income Run-Rate"))
+        .stdout(contains("Current Run-Rate: $204.00"))
+        .stdout(contains("█"))
+        .stdout(contains("Reconstructed from current records"));
 }
 
 #[test]
-fn test_summary_invalid_quarter() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_test_data(temp_dir.path())?;
+fn test_summary_real_income() {
+    let env = Fixture::new()
+        .dividend("KO", "2022-03-14", "2022-04-01", "1.00", "100")
+        .dividend("KO", "2023-03-14", "2023-04-01", "1.21", "100")
+        .build();
+    let cpi_path = env.file("cpi.csv");
+    std::fs::write(&cpi_path, "year,cpi\n2022,100\n2023,110\n").unwrap();
+
+    env.cmd()
+        .args(["summary", "--real", "--cpi-file", cpi_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("Inflation-Adjusted Income"))
+        .stdout(contains("Real (2023 $)"))
+        .stdout(contains("$110.00"))
+        .stdout(contains("Real Growth since 2022: +10.0%"))
+        .stdout(contains("Inflation since 2022: +10.0%"));
+
+    env.cmd()
+        .args(["summary", "--real"])
+        .assert()
+        .success()
+        .stdout(contains("US CPI-U annual averages"))
+        .stdout(contains("no inflation is counted").not());
+
+    env.cmd()
+        .args(["summary", "--cpi-file", cpi_path.to_str().unwrap()])
+        .assert()
+        .failure();
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["summary", "--quarter", "invalid-quarter"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_summary_reuses_cached_report_until_data_changes() {
+    let env = summary_fixture();
+    let cache_dir = env.file("analytics_cache");
+
+    let first = env.run_ok(&["summary", "--year", "2024"]);
+    assert!(first.contains("$148.00"));
+    assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+    env.cmd()
+        .args(["--verbose", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Using cached report"))
+        .stdout(contains("$148.00"));
+    env.cmd()
+        .args(["--verbose", "summary", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("Using cached report").not());
+
+    env.run_ok(&[
+        "div", "add", "MSFT", "--ex-date", "2024-09-20", "--pay-date", "2024-09-25", "--amount", "0.70", "--shares", "50",
+    ]);
+    assert!(!cache_dir.exists());
+    env.cmd()
+        .args(["summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("$183.00"));
+}
 
-    assert!(!output.status.success(), "Summary with invalid quarter should fail");
+#[test]
+fn test_summary_reconciles_with_tax_income() {
+    let env = Fixture::new()
+        .dividend("KO", "2023-12-14", "2024-01-02", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("MSFT", "2024-11-20", "2025-01-09", "0.83", "10")
+        .build();
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n",
+    )
+    .unwrap();
+    let summary = |args: &[&str]| {
+        let mut cmd = env.cmd();
+        cmd.env("XDG_CONFIG_HOME", env.file("config")).arg("summary").args(args);
+        cmd.assert().success()
+    };
+
+    summary(&["--reconcile-tax", "--year", "2024"])
+        .stdout(contains("Summary (by ex-date): $56.80"))
+        .stdout(contains("Tax Summary (by pay date): $94.50"))
+        .stdout(contains("Difference: -$37.70"))
+        .stdout(contains("│ KO     │ 2023-12-14 │ 2024-01-02 │ $46.00 │ Tax only     │ Ex-date in 2023, paid in 2024"))
+        .stdout(contains("│ MSFT   │ 2024-11-20 │ 2025-01-09 │ $8.30  │ Summary only │ Ex-date in 2024, paid in 2025"))
+        .stdout(contains("2 payments across the year boundary make up the difference"));
+    summary(&["--year", "2024"]).stdout(contains("Total Dividend Income: $56.80"));
+
+    // Counting by pay date, as configured, the totals agree
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n\n[analysis]\ndate_basis = \"pay-date\"\n",
+    )
+    .unwrap();
+    summary(&["--year", "2024"]).stdout(contains("Total Dividend Income: $94.50"));
+    summary(&["--reconcile-tax", "--year", "2024"])
+        .stdout(contains("Difference: +$0.00"))
+        .stdout(contains("Both count the same payments for the year"));
+    summary(&["--year", "2024", "--date-basis", "ex-date"]).stdout(contains("Total Dividend Income: $56.80"));
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("Invalid quarter. Use Q1, Q2, Q3, or Q4"));
+#[test]
+fn test_summary_reconciles_only_the_filtered_payments() {
+    let env = Fixture::new()
+        .command(&["holdings", "add", "VZ", "--shares", "50", "--account", "IRA"])
+        .dividend("KO", "2023-12-14", "2024-01-02", "0.46", "100")
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("VZ", "2024-04-09", "2024-05-01", "0.66", "50")
+        .dividend("VZ", "2024-12-27", "2025-01-06", "0.66", "50")
+        .build();
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n",
+    )
+    .unwrap();
+    let summary = |args: &[&str]| {
+        let mut cmd = env.cmd();
+        cmd.env("XDG_CONFIG_HOME", env.file("config")).arg("summary").args(args);
+        cmd.assert().success()
+    };
+
+    // Only the IRA's VZ payments are compared; KO's January payment is not a difference
+    summary(&["--reconcile-tax", "--year", "2024", "--account", "ira"])
+        .stdout(contains("Summary (by ex-date): $66.00"))
+        .stdout(contains("Tax Summary (by pay date): $33.00"))
+        .stdout(contains("Difference: +$33.00"))
+        .stdout(contains("2024-01-02").not());
+    summary(&["--reconcile-tax", "--year", "2024", "--symbol", "KO"])
+        .stdout(contains("Summary (by ex-date): $48.50"))
+        .stdout(contains("Tax Summary (by pay date): $94.50"))
+        .stdout(contains("VZ").not());
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[test]
+fn test_summary_consistency_counts_a_payment_to_two_accounts_once() {
+    let payments = [
+        ("2023-03-14", "2023-04-01"),
+        ("2023-06-14", "2023-07-01"),
+        ("2023-09-14", "2023-10-02"),
+        ("2023-12-14", "2024-01-02"),
+    ];
+    let mut single = Fixture::new();
+    let mut both = Fixture::new();
+    for (ex_date, pay_date) in payments {
+        single = single.dividend("KO", ex_date, pay_date, "0.46", "100");
+        both = both.dividend("KO", ex_date, pay_date, "0.46", "100").command(&[
+            "div", "add", "KO", "--ex-date", ex_date, "--pay-date", pay_date, "--amount", "0.46", "--shares", "20",
+            "--account", "IRA",
+        ]);
+    }
+    // Income doubles; the consistency section below it must not change
+    let consistency = |env: &TestEnv| {
+        let output = env.cmd().args(["summary", "--consistency"]).output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout[stdout.find("Dividend Consistency Analysis").unwrap()..].to_string()
+    };
+
+    let expected = consistency(&single.build());
+    assert!(expected.contains("│ KO     │ 99.5% │ Quarterly │"));
+    assert_eq!(consistency(&both.build()), expected);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_exchange_spellings_are_normalized() {
+    let env = Fixture::new()
+        .dividend("VOD:LN", "2024-06-06", "2024-08-02", "0.0455", "1000")
+        .dividend("RY CN", "2024-07-25", "2024-08-23", "1.42", "50")
+        .build();
+
+    env.cmd()
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(contains("VOD.L"))
+        .stdout(contains("RY.TO"))
+        .stdout(contains("VOD:LN").not());
+
+    env.cmd()
+        .args([
+            "div", "add", "LON:VOD", "--ex-date", "2024-06-06", "--pay-date", "2024-08-02", "--amount", "0.0455",
+            "--shares", "1000",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("VOD.L"));
+}
+
+#[test]
+fn test_symbol_aliases_add_list_remove() {
+    let env = Fixture::new()
+        .holding("BP.L", "500")
+        .dividend("BP", "2024-05-09", "2024-06-21", "0.06", "500")
+        .build();
+
+    env.cmd()
+        .args(["symbol", "aliases"])
+        .assert()
+        .success()
+        .stdout(contains("No aliases defined"));
+
+    env.cmd()
+        .args(["symbol", "aliases", "add", "bp", "BP:LN"])
+        .assert()
+        .success()
+        .stdout(contains("BP now maps to BP.L"))
+        .stdout(contains("Moved 1 dividend from BP"));
+
+    env.cmd()
+        .args(["symbol", "aliases"])
+        .assert()
+        .success()
+        .stdout(contains("Alias"))
+        .stdout(contains("BP.L"));
+
+    env.cmd()
+        .args(["holdings", "add", "BP", "--shares", "600"])
+        .assert()
+        .success();
+    env.cmd()
+        .args(["holdings", "list"])
+        .assert()
+        .success()
+        .stdout(contains("600"));
+
+    env.cmd()
+        .args(["symbol", "aliases", "remove", "BP"])
+        .assert()
+        .success()
+        .stdout(contains("Removed alias BP"));
+
+    env.cmd()
+        .args(["symbol", "aliases", "remove", "BP"])
+        .assert()
+        .failure()
+        .stderr(contains("No alias BP is defined"));
+}
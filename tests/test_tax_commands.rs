@@ -1,560 +1,731 @@
-use anyhow::Result;
-use tempfile::tempdir;
-use std::process::Command;
-use std::path::Path;
-
-fn get_binary_path() -> String {
-    "./target/debug/dividend-tracker".to_string()
-}
-
-fn setup_tax_test_data(temp_dir: &Path) -> Result<()> {
-    // Add holdings first
-    let holdings_commands = vec![
-        vec!["holdings", "add", "AAPL", "--shares", "100"],
-        vec!["holdings", "add", "MSFT", "--shares", "50"],
-        vec!["holdings", "add", "REIT", "--shares", "200"],
-        vec!["holdings", "add", "FOREIGN", "--shares", "75"],
-    ];
-
-    for cmd_args in holdings_commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup holdings: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    // Add comprehensive dividend data with different tax classifications for 2023 and 2024
-    let dividend_commands = vec![
-        // AAPL - Qualified dividends for 2023
-        vec!["add", "AAPL", "--ex-date", "2023-02-15", "--pay-date", "2023-02-22", "--amount", "0.23", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-05-15", "--pay-date", "2023-05-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-08-15", "--pay-date", "2023-08-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2023-11-15", "--pay-date", "2023-11-22", "--amount", "0.24", "--shares", "100", "--force"],
-
-        // AAPL - Qualified dividends for 2024
-        vec!["add", "AAPL", "--ex-date", "2024-02-15", "--pay-date", "2024-02-22", "--amount", "0.24", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-05-15", "--pay-date", "2024-05-22", "--amount", "0.25", "--shares", "100", "--force"],
-        vec!["add", "AAPL", "--ex-date", "2024-08-15", "--pay-date", "2024-08-22", "--amount", "0.25", "--shares", "100", "--force"],
-
-        // MSFT - Qualified dividends for 2023
-        vec!["add", "MSFT", "--ex-date", "2023-03-20", "--pay-date", "2023-03-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-06-20", "--pay-date", "2023-06-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-09-20", "--pay-date", "2023-09-25", "--amount", "0.68", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2023-12-20", "--pay-date", "2023-12-25", "--amount", "0.68", "--shares", "50", "--force"],
-
-        // MSFT - Qualified dividends for 2024
-        vec!["add", "MSFT", "--ex-date", "2024-03-20", "--pay-date", "2024-03-25", "--amount", "0.75", "--shares", "50", "--force"],
-        vec!["add", "MSFT", "--ex-date", "2024-06-20", "--pay-date", "2024-06-25", "--amount", "0.75", "--shares", "50", "--force"],
-
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+/// Holdings plus 2023 and 2024 dividends with different tax profiles
+fn tax_fixture() -> TestEnv {
+    Fixture::new()
+        .holding("AAPL", "100")
+        .holding("MSFT", "50")
+        .holding("REIT", "200")
+        .holding("FOREIGN", "75")
+        // AAPL - Qualified dividends
+        .dividend("AAPL", "2023-02-15", "2023-02-22", "0.23", "100")
+        .dividend("AAPL", "2023-05-15", "2023-05-22", "0.24", "100")
+        .dividend("AAPL", "2023-08-15", "2023-08-22", "0.24", "100")
+        .dividend("AAPL", "2023-11-15", "2023-11-22", "0.24", "100")
+        .dividend("AAPL", "2024-02-15", "2024-02-22", "0.24", "100")
+        .dividend("AAPL", "2024-05-15", "2024-05-22", "0.25", "100")
+        .dividend("AAPL", "2024-08-15", "2024-08-22", "0.25", "100")
+        // MSFT - Qualified dividends
+        .dividend("MSFT", "2023-03-20", "2023-03-25", "0.68", "50")
+        .dividend("MSFT", "2023-06-20", "2023-06-25", "0.68", "50")
+        .dividend("MSFT", "2023-09-20", "2023-09-25", "0.68", "50")
+        .dividend("MSFT", "2023-12-20", "2023-12-25", "0.68", "50")
+        .dividend("MSFT", "2024-03-20", "2024-03-25", "0.75", "50")
+        .dividend("MSFT", "2024-06-20", "2024-06-25", "0.75", "50")
         // REIT - Non-qualified dividends (REITs typically pay non-qualified)
-        vec!["add", "REIT", "--ex-date", "2023-01-31", "--pay-date", "2023-02-08", "--amount", "0.12", "--shares", "200", "--force"],
-        vec!["add", "REIT", "--ex-date", "2023-04-30", "--pay-date", "2023-05-08", "--amount", "0.12", "--shares", "200", "--force"],
-        vec!["add", "REIT", "--ex-date", "2023-07-31", "--pay-date", "2023-08-08", "--amount", "0.13", "--shares", "200", "--force"],
-        vec!["add", "REIT", "--ex-date", "2023-10-31", "--pay-date", "2023-11-08", "--amount", "0.13", "--shares", "200", "--force"],
-
-        vec!["add", "REIT", "--ex-date", "2024-01-31", "--pay-date", "2024-02-08", "--amount", "0.13", "--shares", "200", "--force"],
-        vec!["add", "REIT", "--ex-date", "2024-04-30", "--pay-date", "2024-05-08", "--amount", "0.14", "--shares", "200", "--force"],
-
+        .dividend("REIT", "2023-01-31", "2023-02-08", "0.12", "200")
+        .dividend("REIT", "2023-04-30", "2023-05-08", "0.12", "200")
+        .dividend("REIT", "2023-07-31", "2023-08-08", "0.13", "200")
+        .dividend("REIT", "2023-10-31", "2023-11-08", "0.13", "200")
+        .dividend("REIT", "2024-01-31", "2024-02-08", "0.13", "200")
+        .dividend("REIT", "2024-04-30", "2024-05-08", "0.14", "200")
         // FOREIGN - Foreign dividends with withholding
-        vec!["add", "FOREIGN", "--ex-date", "2023-06-15", "--pay-date", "2023-06-30", "--amount", "0.80", "--shares", "75", "--force"],
-        vec!["add", "FOREIGN", "--ex-date", "2023-12-15", "--pay-date", "2023-12-30", "--amount", "0.85", "--shares", "75", "--force"],
-
-        vec!["add", "FOREIGN", "--ex-date", "2024-06-15", "--pay-date", "2024-06-30", "--amount", "0.85", "--shares", "75", "--force"],
-    ];
-
-    for cmd_args in dividend_commands {
-        let output = Command::new(&get_binary_path())
-            .args(&cmd_args)
-            .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir)
-            .output()?;
-
-        if !output.status.success() {
-            eprintln!("Failed to setup dividend data: {:?}", String::from_utf8_lossy(&output.stderr));
-        }
-    }
-
-    Ok(())
+        .dividend("FOREIGN", "2023-06-15", "2023-06-30", "0.80", "75")
+        .dividend("FOREIGN", "2023-12-15", "2023-12-30", "0.85", "75")
+        .dividend("FOREIGN", "2024-06-15", "2024-06-30", "0.85", "75")
+        .build()
 }
 
 #[test]
-fn test_tax_command_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax reporting and analysis"));
-    assert!(stdout.contains("summary"));
-    assert!(stdout.contains("report"));
-    assert!(stdout.contains("estimate"));
-    assert!(stdout.contains("lots"));
-    assert!(stdout.contains("classify"));
-
-    Ok(())
+fn test_tax_command_help() {
+    bin()
+        .args(["tax", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Tax reporting and analysis"))
+        .stdout(contains("summary"))
+        .stdout(contains("report"))
+        .stdout(contains("estimate"))
+        .stdout(contains("lots"))
+        .stdout(contains("classify"));
 }
 
 #[test]
-fn test_tax_summary_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax summary help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Generate annual tax summary for a specific year"));
-    assert!(stdout.contains("--year"));
-    assert!(stdout.contains("--export-csv"));
-
-    Ok(())
+fn test_tax_summary_help() {
+    bin()
+        .args(["tax", "summary", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Generate annual tax summary for a specific year"))
+        .stdout(contains("--year"))
+        .stdout(contains("--export-csv"));
 }
 
 #[test]
-fn test_tax_summary_default_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax summary should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Summary Report"));
-    assert!(stdout.contains("Total Dividend Income"));
-    assert!(stdout.contains("Qualified Dividends"));
-    assert!(stdout.contains("Non-Qualified Dividends"));
-
-    Ok(())
+fn test_tax_summary_default_year() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "summary"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Summary Report"))
+        .stdout(contains("Total Dividend Income"))
+        .stdout(contains("Qualified Dividends"))
+        .stdout(contains("Non-Qualified Dividends"));
 }
 
 #[test]
-fn test_tax_summary_specific_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2023"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax summary for 2023 should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Summary for 2023"));
-    assert!(stdout.contains("Total Dividend Income"));
-    assert!(stdout.contains("AAPL"));
-    assert!(stdout.contains("MSFT"));
-    assert!(stdout.contains("REIT"));
-
-    Ok(())
+fn test_tax_summary_specific_year() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Summary for 2023"))
+        .stdout(contains("Total Dividend Income"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("REIT"));
 }
 
 #[test]
-fn test_tax_summary_csv_export() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let csv_path = temp_dir.path().join("tax_summary.csv");
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2023", "--export-csv", csv_path.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax summary CSV export should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("exported to"));
-
-    // Verify CSV file was created and has expected content
-    assert!(csv_path.exists(), "CSV file should be created");
-    let csv_content = std::fs::read_to_string(&csv_path)?;
+fn test_tax_summary_csv_export() {
+    let env = tax_fixture();
+    let csv_path = env.file("tax_summary.csv");
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2023", "--export-csv"])
+        .arg(&csv_path)
+        .assert()
+        .success()
+        .stdout(contains("exported to"));
+
+    let csv_content = std::fs::read_to_string(&csv_path).expect("CSV file should be created");
     assert!(csv_content.contains("Tax Year,2023"));
     assert!(csv_content.contains("Summary"));
     assert!(csv_content.contains("By Symbol"));
     assert!(csv_content.contains("AAPL"));
     assert!(csv_content.contains("MSFT"));
-
-    Ok(())
 }
 
-
 #[test]
-fn test_tax_report_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "report", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax report help should succeed");
+fn test_tax_summary_json_output() {
+    let env = tax_fixture();
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Generate 1099-DIV style report"));
-    assert!(stdout.contains("--year"));
+    let stdout = env.run_ok(&["tax", "summary", "--year", "2023", "--output-format", "json"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout).expect("summary output should be JSON");
 
-    Ok(())
+    assert_eq!(report["title"], "Tax Summary Report");
+    assert_eq!(report["sections"][0]["title"], "Tax Summary for 2023");
 }
 
 #[test]
-fn test_tax_report_all_symbols() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "report", "--year", "2023"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax report should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("1099-DIV Style Tax Report"));
-    assert!(stdout.contains("1099-DIV Report for 2023"));
-    assert!(stdout.contains("Payer Details"));
-    assert!(stdout.contains("AAPL"));
-    assert!(stdout.contains("MSFT"));
-    assert!(stdout.contains("Total Ordinary Dividends"));
-    assert!(stdout.contains("Qualified Dividends"));
-
-    Ok(())
+fn test_tax_summary_markdown_output() {
+    let env = tax_fixture();
+
+    let stdout = env.run_ok(&["tax", "summary", "--year", "2023", "--output-format", "markdown"]);
+    assert!(stdout.starts_with("# Tax Summary Report\n"));
+    assert!(stdout.contains("## Tax Summary for 2023"));
+    assert!(stdout.contains("| AAPL |"));
+    assert!(!stdout.contains('\u{1b}'));
 }
 
 #[test]
-fn test_tax_report_json_export() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
+fn test_interest_is_reported_apart_from_dividends() {
+    let env = Fixture::new()
+        .holding("AAPL", "100")
+        .dividend("AAPL", "2023-02-15", "2023-02-22", "0.25", "100")
+        .command(&[
+            "div", "add", "SGOV", "--ex-date", "2023-03-01", "--pay-date", "2023-03-03", "--amount", "0.40",
+            "--shares", "100", "--kind", "interest",
+        ])
+        .build();
+
+    let stdout = env.run_ok(&["tax", "summary", "--year", "2023"]);
+    assert!(stdout.contains("$25.00"));
+    assert!(stdout.contains("Interest Income (1099-INT)"));
+    assert!(stdout.contains("Total Interest: $40.00"));
+
+    env.cmd()
+        .args(["tax", "report", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("Interest income of $40.00 is reported on 1099-INT and not included here"))
+        .stdout(contains("SGOV").not());
+
+    env.cmd()
+        .args(["summary", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("Total Dividend Income: $65.00"))
+        .stdout(contains("  Dividends: $25.00"))
+        .stdout(contains("  Interest: $40.00"));
+}
 
-    let json_path = temp_dir.path().join("tax_report.json");
+#[test]
+fn test_tax_report_help() {
+    bin()
+        .args(["tax", "report", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Generate 1099-DIV style report"))
+        .stdout(contains("--year"));
+}
 
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "report", "--year", "2023", "--export-json", json_path.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_tax_report_all_symbols() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "report", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("1099-DIV Style Tax Report"))
+        .stdout(contains("1099-DIV Report for 2023"))
+        .stdout(contains("Payer Details"))
+        .stdout(contains("AAPL"))
+        .stdout(contains("MSFT"))
+        .stdout(contains("Total Ordinary Dividends"))
+        .stdout(contains("Qualified Dividends"));
+}
 
-    assert!(output.status.success(), "Tax report JSON export should succeed");
+#[test]
+fn test_tax_report_json_export() {
+    let env = tax_fixture();
+    let json_path = env.file("tax_report.json");
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("exported to"));
+    env.cmd()
+        .args(["tax", "report", "--year", "2023", "--export-json"])
+        .arg(&json_path)
+        .assert()
+        .success()
+        .stdout(contains("exported to"));
 
-    // Verify JSON file was created
     assert!(json_path.exists(), "JSON file should be created");
-
-    Ok(())
 }
 
 #[test]
-fn test_tax_estimate_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "estimate", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax estimate help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Calculate estimated taxes on dividend income"));
-    assert!(stdout.contains("--filing-status"));
-    assert!(stdout.contains("--income-bracket"));
-    assert!(stdout.contains("--year"));
-
-    Ok(())
+fn test_tax_estimate_help() {
+    bin()
+        .args(["tax", "estimate", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Calculate estimated taxes on dividend income"))
+        .stdout(contains("--filing-status"))
+        .stdout(contains("--income-bracket"))
+        .stdout(contains("--year"));
 }
 
 #[test]
-fn test_tax_estimate_single() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "estimate", "--year", "2023", "--filing-status", "single", "--income-bracket", "medium"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax estimate should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Estimate Calculator"));
-    assert!(stdout.contains("Tax Estimate for"));
-    assert!(stdout.contains("Single filing status"));
-    assert!(stdout.contains("Medium income bracket"));
-    assert!(stdout.contains("Qualified Dividend Income"));
-    assert!(stdout.contains("Total Estimated Tax"));
-
-    Ok(())
+fn test_tax_estimate_single() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "estimate", "--year", "2023", "--filing-status", "single", "--income-bracket", "medium"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Estimate Calculator"))
+        .stdout(contains("Tax Estimate for"))
+        .stdout(contains("Single filing status"))
+        .stdout(contains("Medium income bracket"))
+        .stdout(contains("Qualified Dividend Income"))
+        .stdout(contains("Total Estimated Tax"));
 }
 
 #[test]
-fn test_tax_estimate_married_joint() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "estimate", "--year", "2023", "--filing-status", "married-jointly", "--income-bracket", "high"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax estimate married filing jointly should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("MarriedFilingJointly filing status"));
-    assert!(stdout.contains("High income bracket"));
-
-    Ok(())
+fn test_tax_estimate_married_joint() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args([
+            "tax",
+            "estimate",
+            "--year",
+            "2023",
+            "--filing-status",
+            "married-jointly",
+            "--income-bracket",
+            "high",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("MarriedFilingJointly filing status"))
+        .stdout(contains("High income bracket"));
 }
 
 #[test]
-fn test_tax_lots_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "lots", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax lots help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Show tax lot breakdown"));
-    assert!(stdout.contains("--year"));
-    assert!(stdout.contains("--symbol"));
-
-    Ok(())
+fn test_tax_lots_help() {
+    bin()
+        .args(["tax", "lots", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Show tax lot breakdown"))
+        .stdout(contains("--year"))
+        .stdout(contains("--symbol"));
 }
 
 #[test]
-fn test_tax_lots_no_data() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "lots", "--year", "2023"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax lots should succeed even with no tax lot data");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Lot Analysis"));
-    // Should handle gracefully when no tax lot information is available
-
-    Ok(())
+fn test_tax_lots_no_data() {
+    let env = tax_fixture();
+
+    // No tax lot information is recorded by the fixture
+    env.cmd()
+        .args(["tax", "lots", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Lot Analysis"));
 }
 
 #[test]
-fn test_tax_classify_help() -> Result<()> {
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "classify", "--help"])
-        .output()?;
-
-    assert!(output.status.success(), "Tax classify help should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Update tax classification for dividends"));
-    assert!(stdout.contains("<SYMBOL>"));
-    assert!(stdout.contains("--classification"));
-    assert!(stdout.contains("--year"));
-
-    Ok(())
+fn test_tax_classify_help() {
+    bin()
+        .args(["tax", "classify", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Update tax classification for dividends"))
+        .stdout(contains("<SYMBOL>"))
+        .stdout(contains("--classification"))
+        .stdout(contains("--year"));
 }
 
 #[test]
-fn test_tax_classify_specific_dividend() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "classify", "REIT", "--year", "2023", "--classification", "non-qualified"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax classify should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Updated") && stdout.contains("dividend records"));
-    assert!(stdout.contains("REIT"));
-    assert!(stdout.contains("NonQualified"));
-
-    Ok(())
+fn test_tax_classify_specific_dividend() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "classify", "REIT", "--year", "2023", "--classification", "non-qualified"])
+        .assert()
+        .success()
+        .stdout(contains("Updated 4 dividend records"))
+        .stdout(contains("REIT"))
+        .stdout(contains("+ tax class: NonQualified"));
 }
 
 #[test]
-fn test_tax_classify_all_symbol() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "classify", "REIT", "--classification", "non-qualified"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax classify all should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Updated") && stdout.contains("dividend records"));
-    assert!(stdout.contains("REIT"));
-
-    Ok(())
+fn test_tax_classify_all_symbol() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "classify", "REIT", "--classification", "non-qualified"])
+        .assert()
+        .success()
+        .stdout(contains("Updated 6 dividend records"))
+        .stdout(contains("REIT"));
 }
 
 #[test]
-fn test_tax_invalid_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "invalid"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid year should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("invalid") || stderr.contains("Invalid"));
-
-    Ok(())
+fn test_tax_reclassify_interactive() {
+    let env = tax_fixture();
+
+    // Symbols are reviewed alphabetically: AAPL, FOREIGN, MSFT, REIT
+    env.cmd()
+        .args(["tax", "reclassify", "--year", "2024", "--interactive"])
+        .write_stdin("\nforeign\n\nnon-qualified\ny\n")
+        .assert()
+        .success()
+        .stdout(contains("Pending Changes"))
+        .stdout(contains("+ tax class: Foreign"))
+        .stdout(contains("Reclassified 3 dividend records"));
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Foreign Dividends"));
 }
 
 #[test]
-fn test_tax_invalid_filing_status() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "estimate", "--filing-status", "invalid", "--income-bracket", "medium"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid filing status should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("invalid") || stderr.contains("Invalid"));
-
-    Ok(())
+fn test_tax_reclassify_asks_again_after_a_bad_answer() {
+    let env = tax_fixture();
+
+    // A typo for FOREIGN keeps the AAPL answer and asks about FOREIGN again
+    env.cmd()
+        .args(["tax", "reclassify", "--year", "2024", "--interactive"])
+        .write_stdin("non-qualified\nforiegn\nforeign\nq\ny\n")
+        .assert()
+        .success()
+        .stdout(contains("Invalid classification. Use:"))
+        .stdout(contains("+ tax class: Foreign"))
+        .stdout(contains("Reclassified 4 dividend records"));
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Foreign Dividends"));
 }
 
 #[test]
-fn test_tax_invalid_classification() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "classify", "AAPL", "--classification", "invalid"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(!output.status.success(), "Invalid classification should fail");
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("invalid") || stderr.contains("Invalid"));
-
-    Ok(())
+fn test_tax_invalid_year() {
+    bin()
+        .args(["tax", "summary", "--year", "invalid"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid value 'invalid'"));
 }
 
 #[test]
-fn test_tax_no_data() -> Result<()> {
-    let temp_dir = tempdir()?;
-    // Don't setup any data
-
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    // Should handle gracefully - either succeed with empty results or provide helpful message
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    // Should contain some indication of no data or show empty summary
-    assert!(stdout.contains("Tax Summary") || stderr.contains("No") || stdout.contains("$0.00"));
-
-    Ok(())
+fn test_tax_invalid_filing_status() {
+    bin()
+        .args(["tax", "estimate", "--filing-status", "invalid", "--income-bracket", "medium"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid value 'invalid'"));
 }
 
 #[test]
-fn test_tax_year_with_no_dividends() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
-
-    // Test a year with no dividends (like 2020)
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2020"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-
-    assert!(output.status.success(), "Tax summary for year with no data should succeed");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Summary for 2020"));
-    assert!(stdout.contains("$0.00"));
-
-    Ok(())
+fn test_tax_invalid_classification() {
+    bin()
+        .args(["tax", "classify", "AAPL", "--classification", "invalid"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid value 'invalid'"));
 }
 
 #[test]
-fn test_tax_future_year() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
+fn test_tax_no_data() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["tax", "summary"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Summary"))
+        .stdout(contains("No dividend records found."));
+}
 
-    // Test a future year
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2030"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
+#[test]
+fn test_tax_year_with_no_dividends() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2020"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Summary for 2020"))
+        .stdout(contains("$0.00"));
+}
 
-    assert!(output.status.success(), "Tax summary for future year should succeed");
+#[test]
+fn test_tax_future_year() {
+    let env = tax_fixture();
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2030"])
+        .assert()
+        .success()
+        .stdout(contains("Tax Summary for 2030"))
+        .stdout(contains("$0.00"));
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Tax Summary for 2030"));
-    assert!(stdout.contains("$0.00"));
+#[test]
+fn test_tax_comprehensive_flow() {
+    let env = tax_fixture();
+    let csv_path = env.file("comprehensive_tax.csv");
+
+    env.run_ok(&["tax", "summary", "--year", "2023"]);
+    env.run_ok(&["tax", "classify", "REIT", "--classification", "non-qualified"]);
+    env.run_ok(&["tax", "report", "--year", "2023"]);
+    env.run_ok(&[
+        "tax",
+        "estimate",
+        "--year",
+        "2023",
+        "--filing-status",
+        "single",
+        "--income-bracket",
+        "medium",
+    ]);
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2023", "--export-csv"])
+        .arg(&csv_path)
+        .assert()
+        .success();
+    assert!(csv_path.exists(), "CSV file should be created");
+}
 
-    Ok(())
+#[test]
+fn test_tax_summary_reports_fees() {
+    let env = Fixture::new()
+        .command(&[
+            "div", "add", "TSM", "--ex-date", "2024-03-14", "--pay-date", "2024-04-11",
+            "--amount", "0.50", "--shares", "100", "--fee", "1.25",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Fees Deducted: $1.25"))
+        .stdout(contains("Net Received: $48.75"))
+        .stdout(contains("Taxable income is the gross amount"));
 }
 
 #[test]
-fn test_tax_comprehensive_flow() -> Result<()> {
-    let temp_dir = tempdir()?;
-    setup_tax_test_data(temp_dir.path())?;
+fn test_tax_summary_reports_withholding() {
+    let env = Fixture::new()
+        .command(&[
+            "div", "add", "NESN", "--ex-date", "2024-04-26", "--pay-date", "2024-04-30",
+            "--amount", "3.00", "--shares", "100", "--withholding", "35%", "--fee", "2",
+        ])
+        .build();
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Fees Deducted: $2.00"))
+        .stdout(contains("Withholding Tax: $105.00"))
+        .stdout(contains("Gross Income: $300.00"))
+        .stdout(contains("Net Received: $193.00"));
+}
 
-    // 1. Get tax summary
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2023"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-    assert!(output.status.success(), "Tax summary should succeed");
+#[test]
+fn test_tax_foreign_by_country_and_symbol() {
+    let env = Fixture::new()
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.485", "100")
+        .dividend("SAP", "2024-05-16", "2024-05-21", "2.20", "50")
+        .command(&[
+            "div", "add", "NESN.SW", "--ex-date", "2024-04-26", "--pay-date", "2024-04-30",
+            "--amount", "3.00", "--shares", "100", "--withholding", "35%",
+        ])
+        .build();
+
+    // NESN.SW is Swiss by its suffix; SAP needs its country set
+    env.cmd()
+        .args(["tax", "classify", "SAP", "--country", "de", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Updated 1 dividend records for SAP to country DE"));
+    env.cmd()
+        .args(["tax", "classify", "SAP", "--country", "Germany"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid country: Germany"));
+
+    env.cmd()
+        .args(["tax", "foreign", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Withholding Tax: $105.00"))
+        .stdout(contains("Net Received: $305.00"))
+        .stdout(contains("│ CH      │ $300.00 │ $105.00  │ 35.0%"))
+        .stdout(contains("│ SAP     │ DE"))
+        .stdout(contains("KO").not());
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("Foreign Income by Country"))
+        .stdout(contains("DE"));
+    env.cmd()
+        .args(["tax", "foreign", "--year", "2023"])
+        .assert()
+        .success()
+        .stdout(contains("No foreign dividends in 2023"));
+}
 
-    // 2. Classify REIT dividends as non-qualified
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "classify", "REIT", "--classification", "non-qualified"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-    assert!(output.status.success(), "Tax classify should succeed");
+/// A Swiss dividend with 35% withheld at source against a 15% treaty rate
+fn reclaim_fixture() -> TestEnv {
+    Fixture::new()
+        .dividend("NESN", "2024-04-26", "2024-04-30", "3.00", "100")
+        .dividend("SAP", "2024-05-16", "2024-05-21", "2.20", "50")
+        .build()
+}
 
-    // 3. Generate 1099-DIV report
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "report", "--year", "2023"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-    assert!(output.status.success(), "Tax report should succeed");
+#[test]
+fn test_tax_reclaims_file_and_refund() {
+    let env = reclaim_fixture();
+
+    env.cmd()
+        .args(["tax", "reclaims"])
+        .assert()
+        .success()
+        .stdout(contains("No withholding reclaims recorded"));
+
+    // (105 withheld) - (300 * 15%) = 60 to reclaim
+    env.cmd()
+        .args([
+            "tax", "reclaims", "file", "NESN", "--ex-date", "2024-04-26", "--country", "CH",
+            "--withheld", "105", "--treaty-rate", "15", "--date", "2024-06-01",
+            "--reference", "CH-2024-001",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Filed reclaim of $60.00 for NESN (CH)"));
+
+    env.cmd()
+        .args([
+            "tax", "reclaims", "file", "SAP", "--ex-date", "2024-05-16", "--country", "de",
+            "--amount", "12.50", "--date", "2024-06-10",
+        ])
+        .assert()
+        .success();
+
+    env.cmd()
+        .args(["tax", "reclaims"])
+        .assert()
+        .success()
+        .stdout(contains("CH-2024-001"))
+        .stdout(contains("Outstanding: $72.50"))
+        .stdout(contains("By Country"));
+
+    env.cmd()
+        .args(["tax", "reclaims", "refund", "NESN", "--ex-date", "2024-04-26", "--amount", "60", "--date", "2025-01-15"])
+        .assert()
+        .success()
+        .stdout(contains("Recorded refund of $60.00 for NESN (CH)"));
+
+    env.cmd()
+        .args(["tax", "reclaims", "--outstanding"])
+        .assert()
+        .success()
+        .stdout(contains("SAP"))
+        .stdout(contains("NESN").not())
+        .stdout(contains("Outstanding: $12.50"));
+
+    env.cmd()
+        .args(["tax", "reclaims", "--country", "CH"])
+        .assert()
+        .success()
+        .stdout(contains("Refunded 2025-01-15"))
+        .stdout(contains("Total Refunded: $60.00"));
+}
 
-    // 4. Calculate tax estimate
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "estimate", "--year", "2023", "--filing-status", "single", "--income-bracket", "medium"])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-    assert!(output.status.success(), "Tax estimate should succeed");
+#[test]
+fn test_tax_reclaims_validation() {
+    let env = reclaim_fixture();
+
+    // Withholding already at the treaty rate
+    env.cmd()
+        .args([
+            "tax", "reclaims", "file", "NESN", "--ex-date", "2024-04-26", "--country", "CH",
+            "--withheld", "45", "--treaty-rate", "15",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("already at or below the 15% treaty rate"));
+
+    // Unknown withholding
+    env.cmd()
+        .args([
+            "tax", "reclaims", "file", "SAP", "--ex-date", "2024-05-16", "--country", "DE",
+            "--treaty-rate", "15",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("Withholding for SAP is unknown"));
+
+    env.cmd()
+        .args([
+            "tax", "reclaims", "file", "ROG", "--ex-date", "2024-03-15", "--country", "CH",
+            "--amount", "10",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("No ROG dividend with ex-date 2024-03-15 found"));
+
+    env.cmd()
+        .args(["tax", "reclaims", "refund", "SAP", "--ex-date", "2024-05-16", "--amount", "5"])
+        .assert()
+        .failure()
+        .stderr(contains("No reclaim on file for SAP"));
+}
 
-    // 5. Export to CSV
-    let csv_path = temp_dir.path().join("comprehensive_tax.csv");
-    let output = Command::new(&get_binary_path())
-        .args(&["tax", "summary", "--year", "2023", "--export-csv", csv_path.to_str().unwrap()])
-        .env("DIVIDEND_TRACKER_DATA_DIR", temp_dir.path())
-        .output()?;
-    assert!(output.status.success(), "Tax CSV export should succeed");
-    assert!(csv_path.exists(), "CSV file should be created");
+#[test]
+fn test_new_dividends_classified_by_security_type() {
+    let env = Fixture::new().holding("O", "100").holding("WSR", "50").holding("KO", "10").build();
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n\n[classification]\nreits = [\"WSR\"]\n",
+    )
+    .unwrap();
+    let add = |args: &[&str]| {
+        let mut cmd = env.cmd();
+        cmd.env("XDG_CONFIG_HOME", env.file("config"))
+            .args(["div", "add", "--ex-date", "2024-03-01", "--pay-date", "2024-03-15", "-a", "0.25", "-s", "10"])
+            .args(args);
+        cmd.assert().success()
+    };
+
+    add(&["O"]).stdout(contains("Tax classification: NonQualified (O is a REIT; change it with 'tax classify')"));
+    add(&["WSR"]).stdout(contains("WSR is a REIT"));
+    add(&["NESN.SW"]).stdout(contains("Tax classification: Foreign (NESN.SW pays from CH"));
+    add(&["MUB"]).stdout(contains("Tax classification: TaxFree"));
+    add(&["KO"]).stdout(contains("Tax classification").not());
+    add(&["VTEB", "--classification", "qualified"]).stdout(contains("Tax classification").not());
+
+    env.cmd()
+        .args(["tax", "summary", "--year", "2024"])
+        .assert()
+        .success()
+        .stdout(contains("│   Non-Qualified Dividends │ $5.00"))
+        .stdout(contains("│   Tax-Free Dividends      │ $2.50"))
+        .stdout(contains("│   Foreign Dividends       │ $2.50"));
+
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n\n[classification]\nauto = false\n",
+    )
+    .unwrap();
+    let mut cmd = env.cmd();
+    cmd.env("XDG_CONFIG_HOME", env.file("config"))
+        .args(["div", "add", "O", "--ex-date", "2024-06-01", "--pay-date", "2024-06-15", "-a", "0.25", "-s", "10"])
+        .assert()
+        .success()
+        .stdout(contains("Tax classification").not());
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[test]
+fn test_accounts_break_down_tax_and_filter_listings() {
+    let env = Fixture::new()
+        .holding("KO", "100")
+        .command(&["holdings", "add", "VZ", "--shares", "50", "--account", "IRA"])
+        .dividend("KO", "2024-03-14", "2024-04-01", "0.50", "100")
+        // Paid into the holding's account
+        .dividend("VZ", "2024-04-09", "2024-05-01", "0.66", "50")
+        .build();
+    let config_dir = env.file("config").join("dividend-tracker");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[api]\n\n[cache]\nenabled = true\nttl_hours = 24\nmax_size_mb = 100\n\n[accounts]\ntraditional = [\"IRA\"]\n",
+    )
+    .unwrap();
+    let cmd = || {
+        let mut cmd = env.cmd();
+        cmd.env("XDG_CONFIG_HOME", env.file("config"));
+        cmd
+    };
+
+    cmd()
+        .args(["tax", "summary", "--year", "2024", "--estimate"])
+        .assert()
+        .success()
+        .stdout(contains("Breakdown by Account"))
+        .stdout(contains("Traditional IRA"))
+        .stdout(contains("Leaves out $33.00 paid into retirement accounts (IRA)"))
+        .stdout(contains("│ Total Estimated Tax     │ -        │ $7.50"));
+
+    cmd()
+        .args(["div", "list", "--account", "ira"])
+        .assert()
+        .success()
+        .stdout(contains("VZ"))
+        .stdout(contains("KO").not());
+
+    cmd()
+        .args(["summary", "--year", "2024", "--account", "Default"])
+        .assert()
+        .success()
+        .stdout(contains("Total Dividend Income: $50.00"));
+}
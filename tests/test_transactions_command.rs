@@ -0,0 +1,141 @@
+mod common;
+
+use common::*;
+use predicates::prelude::*;
+
+#[test]
+fn test_transactions_derive_shares_at_each_ex_date() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["transactions", "buy", "KO", "--shares", "50", "--price", "60", "--date", "2022-06-01"])
+        .assert()
+        .success()
+        .stdout(contains("Bought 50 KO at $60.00 on 2022-06-01"))
+        .stdout(contains("Started a holding for KO"));
+    // Backdated: the earlier purchase is slotted in before the first one
+    env.cmd()
+        .args(["transactions", "buy", "KO", "-s", "100", "-p", "50", "-d", "2021-03-01"])
+        .assert()
+        .success()
+        .stdout(contains("Shares held: 150 (average cost $53.33)"))
+        .stdout(contains("Started").not());
+    env.run_ok(&["transactions", "sell", "KO", "-s", "30", "-p", "65", "-d", "2023-02-01"]);
+
+    env.cmd()
+        .args(["transactions", "sell", "KO", "-s", "200", "-p", "65", "-d", "2023-03-01"])
+        .assert()
+        .failure()
+        .stderr(contains("Cannot sell 200 shares of KO on 2023-03-01: only 120 held then"));
+
+    // Recorded with the shares held before the first purchase
+    env.cmd()
+        .args(["div", "add", "KO", "--ex-date", "2022-06-14", "--pay-date", "2022-07-01", "-a", "0.44", "-s", "100"])
+        .assert()
+        .success()
+        .stdout(contains("differ from the 150 shares held before the ex-date"));
+    env.run_ok(&["div", "add", "KO", "--ex-date", "2023-03-14", "--pay-date", "2023-04-01", "-a", "0.46", "-s", "120"]);
+
+    env.cmd()
+        .args(["transactions", "list"])
+        .assert()
+        .success()
+        .stdout(contains("2021-03-01"))
+        .stdout(contains("Sell"))
+        .stdout(contains("$1950.00"))
+        .stdout(contains("Dividends vs. Ownership"))
+        .stdout(contains("2022-06-14"))
+        .stdout(contains("1 of 2 dividends checked don't match the shares held"));
+
+    env.cmd()
+        .args(["transactions", "list", "PEP"])
+        .assert()
+        .success()
+        .stdout(contains("No transactions recorded for PEP"));
+
+    env.cmd()
+        .args(["transactions", "sell", "KO", "-s", "120", "-p", "70", "-d", "2024-01-05"])
+        .assert()
+        .success()
+        .stdout(contains("Every share is sold; KO is now closed"));
+}
+
+#[test]
+fn test_buys_keep_their_purchase_yield() {
+    let env = TestEnv::new();
+
+    env.cmd()
+        .args(["transactions", "buy", "KO", "-s", "100", "-p", "50", "-d", "2023-01-05"])
+        .assert()
+        .success()
+        .stdout(contains("Purchase yield unknown: KO had no recent dividend or forward dividend on 2023-01-05"));
+    env.run_ok(&["div", "add", "KO", "--ex-date", "2023-03-14", "--pay-date", "2023-04-01", "-a", "0.46", "-s", "100"]);
+    env.run_ok(&["div", "add", "KO", "--ex-date", "2023-06-14", "--pay-date", "2023-07-01", "-a", "0.46", "-s", "100"]);
+
+    // $0.46 a quarter is $1.84 a year: 4% at $46, 3.2% at $57.50
+    env.cmd()
+        .args(["transactions", "buy", "KO", "-s", "50", "-p", "46", "-d", "2023-08-01"])
+        .assert()
+        .success()
+        .stdout(contains("Purchase yield: 4.00% (forward dividend $1.84 per share)"));
+    env.run_ok(&["transactions", "buy", "KO", "-s", "40", "-p", "57.50", "-d", "2024-02-01"]);
+    env.run_ok(&["transactions", "sell", "KO", "-s", "20", "-p", "60", "-d", "2024-03-01"]);
+
+    // ($2300 x 4% + $2300 x 3.2%) / $4600
+    env.cmd()
+        .args(["transactions", "entry-yield"])
+        .assert()
+        .success()
+        .stdout(contains("│ KO     │ 3    │ $9600.00 │ 3.60%"))
+        .stdout(contains("│ 2023 │ 2    │ $7300.00 │ 4.00%           │ 4.00%"))
+        .stdout(contains("│ 2024 │ 1    │ $2300.00 │ 3.20%           │ 3.60%"))
+        .stdout(contains("1 buy without a known purchase yield is left out"));
+
+    env.cmd()
+        .args(["transactions", "entry-yield", "PEP"])
+        .assert()
+        .success()
+        .stdout(contains("No buys recorded of PEP"));
+}
+
+#[test]
+fn test_sales_come_out_of_the_oldest_lots() {
+    let env = Fixture::new()
+        .holding("KO", "150")
+        .dividend("KO", "2021-06-14", "2021-07-01", "0.50", "150")
+        .dividend("KO", "2022-06-14", "2022-07-01", "0.50", "50")
+        .dividend("KO", "2023-06-14", "2023-07-01", "0.50", "50")
+        .build();
+    let lots = env.file("lots.csv");
+    std::fs::write(&lots, "symbol,purchase_date,shares,price\nKO,2020-03-01,100,40\nKO,2021-03-01,50,50\n").unwrap();
+    env.run_ok(&["holdings", "import-lots", lots.to_str().unwrap(), "--yes"]);
+    env.cmd()
+        .args(["transactions", "sell", "KO", "--shares", "100", "--price", "60", "--date", "2022-01-10"])
+        .assert()
+        .success()
+        .stdout(contains("Shares held: 50 (average cost $43.33)"));
+
+    // Only the 2021 lot is left from 2022 on
+    env.cmd()
+        .args(["analyze", "yoc-history"])
+        .assert()
+        .success()
+        .stdout(contains("│ 2021 │ $0.5000   │ 100    │ $4000.00 │"))
+        .stdout(contains("│ 2022 │ $0.5000   │ 50     │ $2500.00 │"))
+        .stdout(contains("│ 2023 │ $0.5000   │ 50     │ $2500.00 │"))
+        .stdout(contains("$6500.00").not());
+
+    env.cmd()
+        .args(["tax", "lots", "--year", "2021"])
+        .assert()
+        .success()
+        .stdout(contains("KO-20200301"))
+        .stdout(contains("$50.00"));
+    env.cmd()
+        .args(["tax", "lots", "--year", "2022"])
+        .assert()
+        .success()
+        .stdout(contains("KO-20210301"))
+        .stdout(contains("$25.00"))
+        .stdout(contains("KO-20200301").not());
+}